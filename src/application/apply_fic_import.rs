@@ -0,0 +1,48 @@
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps};
+use crate::domain::transfer::{ConflictChoice, FicImportPlan, FicImportSummary};
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+/// Parses the CLI `--on-conflict` value.
+pub fn parse_conflict_choice(input: &str) -> Result<ConflictChoice, FicflowError> {
+    match input.to_lowercase().as_str() {
+        "keep" => Ok(ConflictChoice::KeepLocal),
+        "theirs" => Ok(ConflictChoice::UseIncoming),
+        _ => Err(FicflowError::InvalidInput(format!(
+            "Invalid conflict choice: '{}'. Valid options are: 'keep', 'theirs'",
+            input
+        ))),
+    }
+}
+
+/// Saves the plan's new fics and the conflicts resolved in favour of the
+/// other library, all in one go. Trashed fics taken from the other
+/// library are restored first, so they keep their shelves. New fics only
+/// have the metadata the other library knew; the caller refreshes them
+/// from AO3.
+pub fn apply_fic_import(
+    fanfiction_ops: &dyn FanfictionOps,
+    trash_ops: &dyn TrashOps,
+    plan: &FicImportPlan,
+) -> Result<FicImportSummary, FicflowError> {
+    let mut to_save: Vec<Fanfiction> = plan.new.iter().map(|entry| entry.to_new_fic()).collect();
+    let mut summary = FicImportSummary {
+        added: plan.new.len(),
+        unchanged: plan.unchanged,
+        ..FicImportSummary::default()
+    };
+    for conflict in &plan.conflicts {
+        match conflict.choice {
+            ConflictChoice::KeepLocal => summary.kept += 1,
+            ConflictChoice::UseIncoming => {
+                if conflict.trashed {
+                    trash_ops.restore_fic(conflict.local.id)?;
+                }
+                to_save.push(conflict.incoming.apply_to(&conflict.local));
+                summary.updated += 1;
+            }
+        }
+    }
+    fanfiction_ops.save_fanfictions(&to_save)?;
+    Ok(summary)
+}
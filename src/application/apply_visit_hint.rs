@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps, ReadingStatus};
+use crate::domain::transfer::visit_hint;
+use crate::error::FicflowError;
+
+/// Guesses the status and last chapter read of a fic added from a
+/// browser link from when the link was last visited. A fic the reader
+/// has already started tracking is left as it is.
+pub fn apply_visit_hint(
+    fanfiction_ops: &dyn FanfictionOps,
+    fic_id: u64,
+    last_visited: DateTime<Utc>,
+) -> Result<Fanfiction, FicflowError> {
+    let mut fic = fanfiction_ops.get_fanfiction_by_id(fic_id)?;
+    if fic.reading_status != ReadingStatus::PlanToRead || fic.last_chapter_read.is_some() {
+        return Ok(fic);
+    }
+    let (status, last_chapter_read) = visit_hint(&fic, last_visited);
+    fic.reading_status = status;
+    fic.last_chapter_read = last_chapter_read;
+    fanfiction_ops.save_fanfiction(&fic)?;
+    Ok(fic)
+}
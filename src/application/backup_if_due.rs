@@ -0,0 +1,19 @@
+use chrono::{Duration, Utc};
+
+use crate::application::create_backup::create_backup;
+use crate::domain::backup::{BackupInfo, BackupOps, BackupReason};
+use crate::error::FicflowError;
+
+/// Takes the daily backup unless one of any kind was taken in the last
+/// day. Returns it when it was taken.
+pub fn backup_if_due(
+    backup_ops: &dyn BackupOps,
+    keep: usize,
+) -> Result<Option<BackupInfo>, FicflowError> {
+    let day_ago = Utc::now() - Duration::days(1);
+    let latest = backup_ops.list_backups()?.into_iter().next();
+    if latest.is_some_and(|b| b.created_at > day_ago) {
+        return Ok(None);
+    }
+    create_backup(backup_ops, BackupReason::Daily, keep).map(Some)
+}
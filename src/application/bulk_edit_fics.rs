@@ -0,0 +1,65 @@
+use crate::application::update_chapters::set_last_chapter_read;
+use crate::domain::fanfiction::{BulkEdit, Fanfiction, FanfictionOps, ReadingStatus};
+use crate::error::FicflowError;
+
+/// Applies `edit` to every fic in `fic_ids` and saves them in a single
+/// transaction. Ids that can't be loaded are returned as failures and
+/// skipped; if the save itself fails, nothing is written.
+pub fn bulk_edit_fics(
+    fanfiction_ops: &dyn FanfictionOps,
+    fic_ids: &[u64],
+    edit: &BulkEdit,
+) -> Result<(Vec<Fanfiction>, Vec<FicflowError>), FicflowError> {
+    let mut updated = Vec::with_capacity(fic_ids.len());
+    let mut failures = Vec::new();
+    for &fic_id in fic_ids {
+        match fanfiction_ops.get_fanfiction_by_id(fic_id) {
+            Ok(mut fic) => {
+                apply_edit(&mut fic, edit);
+                updated.push(fic);
+            }
+            Err(err) => failures.push(err),
+        }
+    }
+    fanfiction_ops.save_fanfictions(&updated)?;
+    Ok((updated, failures))
+}
+
+/// Same knock-on rules as the single-fic updates: clearing the read
+/// count un-reads a finished fic, and reaching the last chapter counts
+/// as a read only once the work is complete. Fics already on their last
+/// published chapter are left alone rather than read a second time.
+fn apply_edit(fic: &mut Fanfiction, edit: &BulkEdit) {
+    match edit {
+        BulkEdit::Status(status) => fic.reading_status = *status,
+        BulkEdit::UserRating(rating) => fic.user_rating = *rating,
+        BulkEdit::ReadCount(count) => {
+            fic.read_count = *count;
+            if *count == 0 && fic.reading_status == ReadingStatus::Read {
+                fic.reading_status = ReadingStatus::PlanToRead;
+            }
+        }
+        BulkEdit::MarkFullyRead => {
+            if fic.last_chapter_read != Some(fic.chapters_published) {
+                set_last_chapter_read(fic, fic.chapters_published);
+            }
+        }
+        BulkEdit::AppendNote(text) => {
+            let text = text.trim();
+            if text.is_empty() {
+                return;
+            }
+            fic.personal_note = Some(match fic.personal_note.take() {
+                Some(note) if !note.is_empty() => format!("{}\n\n{}", note, text),
+                _ => text.to_string(),
+            });
+        }
+        BulkEdit::AddPersonalTag(tag) => {
+            let tag = tag.trim();
+            if !tag.is_empty() && !fic.personal_tags.iter().any(|t| t == tag) {
+                fic.personal_tags.push(tag.to_string());
+            }
+        }
+        BulkEdit::RemovePersonalTag(tag) => fic.personal_tags.retain(|t| t != tag),
+    }
+}
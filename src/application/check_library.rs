@@ -0,0 +1,6 @@
+use crate::domain::health::{HealthOps, LibraryIssue};
+use crate::error::FicflowError;
+
+pub fn check_library(health_ops: &dyn HealthOps) -> Result<Vec<LibraryIssue>, FicflowError> {
+    health_ops.check_library()
+}
@@ -0,0 +1,9 @@
+use crate::domain::fanfiction::{FanfictionOps, TagCount, TagKind};
+use crate::error::FicflowError;
+
+pub fn count_tags(
+    fanfiction_ops: &dyn FanfictionOps,
+    kind: TagKind,
+) -> Result<Vec<TagCount>, FicflowError> {
+    fanfiction_ops.count_tags(kind)
+}
@@ -0,0 +1,14 @@
+use crate::application::prune_backups::prune_backups;
+use crate::domain::backup::{BackupInfo, BackupOps, BackupReason};
+use crate::error::FicflowError;
+
+/// Takes a backup, then drops the oldest of each reason beyond `keep`.
+pub fn create_backup(
+    backup_ops: &dyn BackupOps,
+    reason: BackupReason,
+    keep: usize,
+) -> Result<BackupInfo, FicflowError> {
+    let backup = backup_ops.create_backup(reason)?;
+    prune_backups(backup_ops, keep)?;
+    Ok(backup)
+}
@@ -0,0 +1,13 @@
+use crate::domain::fanfiction::SortPref;
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps, SearchScope};
+use crate::error::FicflowError;
+
+pub fn create_saved_search(
+    saved_search_ops: &dyn SavedSearchOps,
+    name: &str,
+    query: &str,
+    scope: SearchScope,
+    sort: SortPref,
+) -> Result<SavedSearch, FicflowError> {
+    saved_search_ops.create_saved_search(name, query, scope, sort)
+}
@@ -0,0 +1,9 @@
+use crate::domain::saved_search::SavedSearchOps;
+use crate::error::FicflowError;
+
+pub fn delete_saved_search(
+    saved_search_ops: &dyn SavedSearchOps,
+    saved_search_id: u64,
+) -> Result<(), FicflowError> {
+    saved_search_ops.delete_saved_search(saved_search_id)
+}
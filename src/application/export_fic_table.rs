@@ -0,0 +1,66 @@
+use std::io;
+
+use crate::application::query_fics::parse_names;
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps, FicQuery};
+use crate::domain::transfer::{TableField, TableOptions};
+use crate::error::FicflowError;
+
+pub const DEFAULT_LIST_SEPARATOR: &str = "; ";
+
+/// Parses the CLI's comma-separated `--columns` value, e.g.
+/// `title,author,words,url`.
+pub fn parse_table_fields(input: &str) -> Result<Vec<TableField>, FicflowError> {
+    let known: Vec<&str> = TableField::all().map(TableField::name).collect();
+    parse_names(input, TableField::from_name, &known)
+}
+
+/// `fics` as delimited text: a header row of field labels, then one
+/// row per fic in the order given. Cells a spreadsheet would read as a
+/// formula get a leading `'`, so opening the file can't run a formula
+/// hidden in a summary or note.
+pub fn render_fic_table(
+    fics: &[&Fanfiction],
+    options: &TableOptions,
+) -> Result<String, FicflowError> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_writer(Vec::new());
+    writer
+        .write_record(options.fields.iter().map(|field| field.label()))
+        .map_err(io::Error::from)?;
+    for fic in fics {
+        writer
+            .write_record(
+                options
+                    .fields
+                    .iter()
+                    .map(|field| defuse_formula(field.value(fic, &options.list_separator))),
+            )
+            .map_err(io::Error::from)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| FicflowError::Other(e.to_string()))
+}
+
+/// Prefixes `cell` with `'` when it starts with a character that makes
+/// spreadsheets evaluate it.
+fn defuse_formula(cell: String) -> String {
+    if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", cell)
+    } else {
+        cell
+    }
+}
+
+/// The fics `query` selects, in its order, as delimited text.
+pub fn export_fic_table(
+    fanfiction_ops: &dyn FanfictionOps,
+    query: &FicQuery,
+    options: &TableOptions,
+) -> Result<String, FicflowError> {
+    let page = fanfiction_ops.query_fanfictions(query)?;
+    let fics: Vec<&Fanfiction> = page.fics.iter().collect();
+    render_fic_table(&fics, options)
+}
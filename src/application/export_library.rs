@@ -0,0 +1,22 @@
+use crate::domain::transfer::{ExportFormat, TransferOps};
+use crate::error::FicflowError;
+
+/// Parses the CLI `--format` value.
+pub fn parse_export_format(input: &str) -> Result<ExportFormat, FicflowError> {
+    match input.to_lowercase().as_str() {
+        "json" => Ok(ExportFormat::Json),
+        "csv" => Ok(ExportFormat::Csv),
+        "tsv" => Ok(ExportFormat::Tsv),
+        _ => Err(FicflowError::InvalidInput(format!(
+            "Invalid export format: '{}'. Valid options are: 'json', 'csv', 'tsv'",
+            input
+        ))),
+    }
+}
+
+/// The whole library — fics, shelves, memberships and the trash — as a
+/// JSON document `import_library` can read back.
+pub fn export_library(transfer_ops: &dyn TransferOps) -> Result<String, FicflowError> {
+    let snapshot = transfer_ops.export_library()?;
+    Ok(serde_json::to_string_pretty(&snapshot)?)
+}
@@ -0,0 +1,83 @@
+use crate::domain::fanfiction::FanfictionOps;
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+/// How many matching titles an ambiguity error spells out.
+const LISTED_MATCHES: usize = 5;
+
+/// The library fic titled `title`, or the only one whose title starts
+/// with it, ignoring case. A whole title wins over longer titles it
+/// begins, so "Harbor" still picks out "Harbor" next to "Harbor Lights".
+pub fn find_fic_by_title(
+    fanfiction_ops: &dyn FanfictionOps,
+    title: &str,
+) -> Result<u64, FicflowError> {
+    let fics = fanfiction_ops.list_fanfictions()?;
+    pick_by_title(
+        fics.iter().map(|fic| (fic.id, fic.title.as_str())),
+        title,
+        "your library",
+    )
+}
+
+/// The same lookup over the fics in the trash.
+pub fn find_trashed_fic_by_title(
+    trash_ops: &dyn TrashOps,
+    title: &str,
+) -> Result<u64, FicflowError> {
+    let trashed = trash_ops.list_trashed_fics()?;
+    pick_by_title(
+        trashed.iter().map(|t| (t.fic.id, t.fic.title.as_str())),
+        title,
+        "the trash",
+    )
+}
+
+fn pick_by_title<'a>(
+    fics: impl Iterator<Item = (u64, &'a str)>,
+    title: &str,
+    place: &str,
+) -> Result<u64, FicflowError> {
+    let wanted = title.trim().to_lowercase();
+    if wanted.is_empty() {
+        return Err(FicflowError::InvalidInput(
+            "Give a fic ID, AO3 URL or title".to_string(),
+        ));
+    }
+    let matches: Vec<(u64, &str)> = fics
+        .filter(|(_, t)| t.to_lowercase().starts_with(&wanted))
+        .collect();
+    let exact: Vec<u64> = matches
+        .iter()
+        .filter(|(_, t)| t.to_lowercase() == wanted)
+        .map(|(id, _)| *id)
+        .collect();
+    if let [id] = exact[..] {
+        return Ok(id);
+    }
+    match matches[..] {
+        [] => Err(FicflowError::TitleNotFound(format!(
+            "No fic in {} has a title starting with '{}'",
+            place,
+            title.trim()
+        ))),
+        [(id, _)] => Ok(id),
+        _ => {
+            let mut listed: Vec<String> = matches
+                .iter()
+                .take(LISTED_MATCHES)
+                .map(|(id, t)| format!("{} ({})", t, id))
+                .collect();
+            if matches.len() > LISTED_MATCHES {
+                listed.push(format!("and {} more", matches.len() - LISTED_MATCHES));
+            }
+            Err(FicflowError::InvalidInput(format!(
+                "'{}' matches {} fics in {}: {}. Give more of the title, or the ID.",
+                title.trim(),
+                matches.len(),
+                place,
+                listed.join(", ")
+            )))
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::domain::fanfiction::{FanfictionOps, TagKind};
+use crate::error::FicflowError;
+
+pub fn find_fic_ids_by_tag(
+    fanfiction_ops: &dyn FanfictionOps,
+    kind: TagKind,
+    name: &str,
+) -> Result<Vec<u64>, FicflowError> {
+    fanfiction_ops.find_fic_ids_by_tag(kind, name)
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use crate::domain::shelf::{ShelfKind, ShelfOps};
+use crate::error::FicflowError;
+
+/// A top-level shelf id for each name, reusing a regular shelf of the
+/// same name (ignoring case) and creating the rest.
+pub fn find_or_create_shelves(
+    shelf_ops: &dyn ShelfOps,
+    names: &[String],
+) -> Result<HashMap<String, u64>, FicflowError> {
+    let shelves = shelf_ops.list_shelves()?;
+    let mut ids = HashMap::new();
+    for name in names {
+        let existing = shelves.iter().find(|s| {
+            s.parent_shelf_id.is_none()
+                && matches!(s.kind, ShelfKind::Normal)
+                && s.name.eq_ignore_ascii_case(name.trim())
+        });
+        let id = match existing {
+            Some(shelf) => shelf.id,
+            None => shelf_ops.create_shelf(name.trim(), None)?.id,
+        };
+        ids.insert(name.clone(), id);
+    }
+    Ok(ids)
+}
@@ -0,0 +1,78 @@
+use crate::domain::shelf::{Shelf, ShelfOps, shelf_path};
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+/// The shelf named `path`, or at `path` when it's written `Parent/Child`,
+/// ignoring case. A path only needs as many of the trailing names as it
+/// takes to single a shelf out: "Drafts" works until a second shelf is
+/// called that, then "Fandoms/Drafts" does.
+pub fn find_shelf_by_path(shelf_ops: &dyn ShelfOps, path: &str) -> Result<u64, FicflowError> {
+    let shelves = shelf_ops.list_shelves()?;
+    pick_by_path(&shelves, &shelves, path, "")
+}
+
+/// The same lookup over the shelves in the trash. Their paths run
+/// through the parents they had, whether those are live or trashed too.
+pub fn find_trashed_shelf_by_path(
+    shelf_ops: &dyn ShelfOps,
+    trash_ops: &dyn TrashOps,
+    path: &str,
+) -> Result<u64, FicflowError> {
+    let trashed: Vec<Shelf> = trash_ops
+        .list_trashed_shelves()?
+        .into_iter()
+        .map(|t| t.shelf)
+        .collect();
+    let mut every = shelf_ops.list_shelves()?;
+    every.extend(trashed.iter().cloned());
+    pick_by_path(&trashed, &every, path, " in the trash")
+}
+
+fn pick_by_path(
+    candidates: &[Shelf],
+    every: &[Shelf],
+    path: &str,
+    place: &str,
+) -> Result<u64, FicflowError> {
+    let whole = path.trim().to_lowercase();
+    let wanted: Vec<String> = whole
+        .split('/')
+        .map(|name| name.trim().to_string())
+        .collect();
+    if whole.is_empty() || wanted.iter().any(String::is_empty) {
+        return Err(FicflowError::InvalidInput(format!(
+            "Invalid shelf: '{}'. Give a shelf ID, name or Parent/Child path",
+            path
+        )));
+    }
+    let matches: Vec<(u64, String)> = candidates
+        .iter()
+        .map(|shelf| (shelf, shelf_path(every, shelf)))
+        .filter(|(shelf, full)| {
+            let names: Vec<String> = full.split('/').map(str::to_lowercase).collect();
+            shelf.name.to_lowercase() == whole || names.ends_with(&wanted)
+        })
+        .map(|(shelf, full)| (shelf.id, full))
+        .collect();
+    match &matches[..] {
+        [] => Err(FicflowError::ShelfNameNotFound(format!(
+            "No shelf{} is named or at '{}'",
+            place,
+            path.trim()
+        ))),
+        [(id, _)] => Ok(*id),
+        _ => {
+            let listed: Vec<String> = matches
+                .iter()
+                .map(|(id, full)| format!("{} ({})", full, id))
+                .collect();
+            Err(FicflowError::InvalidInput(format!(
+                "'{}' matches {} shelves{}: {}. Give more of its Parent/Child path, or the ID.",
+                path.trim(),
+                matches.len(),
+                place,
+                listed.join(", ")
+            )))
+        }
+    }
+}
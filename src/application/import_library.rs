@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+use crate::domain::transfer::{
+    ImportMode, ImportSource, ImportSummary, LIBRARY_SCHEMA_VERSION, LibrarySnapshot, TransferOps,
+};
+use crate::error::FicflowError;
+
+/// Parses the CLI `--from` value.
+pub fn parse_import_source(input: &str) -> Result<ImportSource, FicflowError> {
+    match input.to_lowercase().as_str() {
+        "json" => Ok(ImportSource::Library),
+        "calibre" => Ok(ImportSource::Calibre),
+        "bookmarks" => Ok(ImportSource::Bookmarks),
+        "firefox" => Ok(ImportSource::Firefox),
+        _ => Err(FicflowError::InvalidInput(format!(
+            "Invalid import source: '{}'. Valid options are: 'json', 'calibre', 'bookmarks', 'firefox'",
+            input
+        ))),
+    }
+}
+
+/// Parses the CLI `--mode` value.
+pub fn parse_import_mode(input: &str) -> Result<ImportMode, FicflowError> {
+    match input.to_lowercase().as_str() {
+        "merge" => Ok(ImportMode::Merge),
+        "replace" => Ok(ImportMode::Replace),
+        _ => Err(FicflowError::InvalidInput(format!(
+            "Invalid import mode: '{}'. Valid options are: 'merge', 'replace'",
+            input
+        ))),
+    }
+}
+
+/// Reads a document written by `export_library`. The version is checked
+/// before the rest is parsed, so a file from a newer build gets a clear
+/// message instead of a complaint about some field it added.
+pub fn parse_library_snapshot(json: &str) -> Result<LibrarySnapshot, FicflowError> {
+    #[derive(Deserialize)]
+    struct Header {
+        schema_version: u32,
+    }
+
+    let header: Header = serde_json::from_str(json)
+        .map_err(|e| FicflowError::InvalidInput(format!("not a ficflow library file: {}", e)))?;
+    if header.schema_version > LIBRARY_SCHEMA_VERSION {
+        return Err(FicflowError::InvalidInput(format!(
+            "library file version {} is newer than this build supports ({}); update ficflow to import it",
+            header.schema_version, LIBRARY_SCHEMA_VERSION
+        )));
+    }
+    let snapshot: LibrarySnapshot = serde_json::from_str(json)?;
+    snapshot.validate()?;
+    Ok(snapshot)
+}
+
+pub fn import_library(
+    transfer_ops: &dyn TransferOps,
+    snapshot: &LibrarySnapshot,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportSummary, FicflowError> {
+    snapshot.validate()?;
+    transfer_ops.import_library(snapshot, mode, dry_run)
+}
@@ -0,0 +1,7 @@
+use crate::domain::backup::{BackupInfo, BackupOps};
+use crate::error::FicflowError;
+
+/// Newest first.
+pub fn list_backups(backup_ops: &dyn BackupOps) -> Result<Vec<BackupInfo>, FicflowError> {
+    backup_ops.list_backups()
+}
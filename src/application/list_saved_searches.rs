@@ -0,0 +1,8 @@
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps};
+use crate::error::FicflowError;
+
+pub fn list_saved_searches(
+    saved_search_ops: &dyn SavedSearchOps,
+) -> Result<Vec<SavedSearch>, FicflowError> {
+    saved_search_ops.list_saved_searches()
+}
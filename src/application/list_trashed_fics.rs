@@ -0,0 +1,6 @@
+use crate::domain::trash::{TrashOps, TrashedFic};
+use crate::error::FicflowError;
+
+pub fn list_trashed_fics(trash_ops: &dyn TrashOps) -> Result<Vec<TrashedFic>, FicflowError> {
+    trash_ops.list_trashed_fics()
+}
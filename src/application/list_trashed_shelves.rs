@@ -0,0 +1,6 @@
+use crate::domain::trash::{TrashOps, TrashedShelf};
+use crate::error::FicflowError;
+
+pub fn list_trashed_shelves(trash_ops: &dyn TrashOps) -> Result<Vec<TrashedShelf>, FicflowError> {
+    trash_ops.list_trashed_shelves()
+}
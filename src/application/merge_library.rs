@@ -0,0 +1,25 @@
+use crate::domain::transfer::{LibrarySnapshot, MergePolicy, MergeReport, TransferOps};
+use crate::error::FicflowError;
+
+/// Parses the CLI `--policy` value.
+pub fn parse_merge_policy(input: &str) -> Result<MergePolicy, FicflowError> {
+    match input.to_lowercase().as_str() {
+        "newest" => Ok(MergePolicy::NewestWins),
+        "mine" => Ok(MergePolicy::KeepMine),
+        "theirs" => Ok(MergePolicy::KeepTheirs),
+        _ => Err(FicflowError::InvalidInput(format!(
+            "Invalid merge policy: '{}'. Valid options are: 'newest', 'mine', 'theirs'",
+            input
+        ))),
+    }
+}
+
+pub fn merge_library(
+    transfer_ops: &dyn TransferOps,
+    snapshot: &LibrarySnapshot,
+    policy: MergePolicy,
+    dry_run: bool,
+) -> Result<MergeReport, FicflowError> {
+    snapshot.validate()?;
+    transfer_ops.merge_library(snapshot, policy, dry_run)
+}
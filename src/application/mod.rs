@@ -21,24 +21,65 @@
 
 pub mod add_fic;
 pub mod add_to_shelf;
+pub mod apply_fic_import;
+pub mod apply_visit_hint;
+pub mod backup_if_due;
+pub mod bulk_edit_fics;
+pub mod check_library;
 pub mod check_updates;
 pub mod count_fics_in_shelf;
 pub mod count_fics_per_shelf;
+pub mod count_tags;
+pub mod create_backup;
+pub mod create_saved_search;
 pub mod create_shelf;
 pub mod delete_fic;
+pub mod delete_saved_search;
 pub mod delete_shelf;
+pub mod export_fic_table;
+pub mod export_library;
+pub mod find_fic_by_title;
+pub mod find_fics_by_tag;
+pub mod find_or_create_shelves;
+pub mod find_shelf_by_path;
 pub mod get_fic;
+pub mod import_library;
+pub mod list_backups;
 pub mod list_fics;
+pub mod list_saved_searches;
 pub mod list_shelf_fics;
 pub mod list_shelves;
 pub mod list_shelves_for_fic;
+pub mod list_trashed_fics;
+pub mod list_trashed_shelves;
+pub mod merge_library;
+pub mod move_saved_search;
 pub mod move_shelf;
+pub mod open_fic;
 pub mod pin_shelf;
+pub mod plan_fic_import;
+pub mod plan_link_import;
+pub mod prune_backups;
+pub mod purge_fic;
+pub mod purge_shelf;
+pub mod purge_trash;
+pub mod query_fic_ids;
+pub mod query_fics;
 pub mod remove_from_shelf;
+pub mod rename_saved_search;
 pub mod rename_shelf;
+pub mod repair_library;
+pub mod restore_fic;
+pub mod restore_shelf;
+pub mod run_saved_search;
+pub mod search_ao3;
+pub mod search_fics;
+pub mod set_user_fields;
 pub mod unpin_shelf;
+pub mod untracked_fic_ids;
 pub mod update_chapters;
 pub mod update_note;
+pub mod update_personal_tags;
 pub mod update_rating;
 pub mod update_read_count;
 pub mod update_status;
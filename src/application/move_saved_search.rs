@@ -0,0 +1,10 @@
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps};
+use crate::error::FicflowError;
+
+pub fn move_saved_search(
+    saved_search_ops: &dyn SavedSearchOps,
+    saved_search_id: u64,
+    new_position: u32,
+) -> Result<SavedSearch, FicflowError> {
+    saved_search_ops.move_saved_search(saved_search_id, new_position)
+}
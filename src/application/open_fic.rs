@@ -0,0 +1,29 @@
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps, LinkOpener, ReadingStatus};
+use crate::error::FicflowError;
+
+/// Opens the fic at the chapter to read next on the AO3 site at
+/// `base_url`, and returns it with the chapter opened. With
+/// `mark_in_progress`, a fic that was planned or paused becomes In
+/// Progress; finished and abandoned ones keep their status, since
+/// opening those is more often a look back than a restart.
+pub fn open_fic(
+    fanfiction_ops: &dyn FanfictionOps,
+    opener: &dyn LinkOpener,
+    fic_id: u64,
+    base_url: &str,
+    mark_in_progress: bool,
+) -> Result<(Fanfiction, u32), FicflowError> {
+    let mut fic = fanfiction_ops.get_fanfiction_by_id(fic_id)?;
+    let chapter = fic.next_chapter();
+    opener.open_link(&fic.chapter_url(base_url, chapter))?;
+    if mark_in_progress
+        && matches!(
+            fic.reading_status,
+            ReadingStatus::PlanToRead | ReadingStatus::Paused
+        )
+    {
+        fic.reading_status = ReadingStatus::InProgress;
+        fanfiction_ops.save_fanfiction(&fic)?;
+    }
+    Ok((fic, chapter))
+}
@@ -0,0 +1,51 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::fanfiction::FanfictionOps;
+use crate::domain::transfer::{ConflictChoice, ExternalLibrary, FicImportPlan, ImportConflict};
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+/// Reads `source` and sorts its fics into new ones, ones whose user
+/// fields disagree with the library's, and ones already matching. A fic
+/// in the trash is always a conflict, so importing doesn't bring it back
+/// unasked. Nothing is written; every conflict starts out as `KeepLocal`.
+pub fn plan_fic_import(
+    fanfiction_ops: &dyn FanfictionOps,
+    trash_ops: &dyn TrashOps,
+    source: &dyn ExternalLibrary,
+) -> Result<FicImportPlan, FicflowError> {
+    let mut plan = FicImportPlan::default();
+    let mut incoming = source.read_fics()?;
+    // The same work can be in the other library twice (e.g. two
+    // formats of one download); the first entry wins.
+    let mut seen = HashSet::new();
+    incoming.retain(|entry| seen.insert(entry.fic.id));
+    let mut trashed: HashMap<u64, _> = trash_ops
+        .list_trashed_fics()?
+        .into_iter()
+        .map(|t| (t.fic.id, t.fic))
+        .collect();
+
+    for entry in incoming {
+        match fanfiction_ops.get_fanfiction_by_id(entry.fic.id) {
+            Ok(local) if entry.differs_from(&local) => plan.conflicts.push(ImportConflict {
+                local,
+                incoming: entry,
+                choice: ConflictChoice::KeepLocal,
+                trashed: false,
+            }),
+            Ok(_) => plan.unchanged += 1,
+            Err(FicflowError::NotFound { .. }) => match trashed.remove(&entry.fic.id) {
+                Some(local) => plan.conflicts.push(ImportConflict {
+                    local,
+                    incoming: entry,
+                    choice: ConflictChoice::KeepLocal,
+                    trashed: true,
+                }),
+                None => plan.new.push(entry),
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(plan)
+}
@@ -0,0 +1,45 @@
+use crate::application::untracked_fic_ids::untracked_fic_ids;
+use crate::domain::fanfiction::FanfictionOps;
+use crate::domain::transfer::{BrowserLink, FoundWork, LinkImportPlan};
+use crate::error::FicflowError;
+
+/// Merges every link to the same AO3 work into one entry and leaves out
+/// the works the library already has. `links` pairs each link with the
+/// work id found in its URL. Nothing is written; every work starts out
+/// selected, with folders used as shelves.
+pub fn plan_link_import(
+    fanfiction_ops: &dyn FanfictionOps,
+    links: Vec<(u64, BrowserLink)>,
+) -> Result<LinkImportPlan, FicflowError> {
+    let mut works: Vec<FoundWork> = Vec::new();
+    for (fic_id, link) in links {
+        let title = link.title.filter(|t| !t.trim().is_empty());
+        match works.iter_mut().find(|w| w.fic_id == fic_id) {
+            // A work linked more than once (a bookmark plus its visits,
+            // or one link per chapter) keeps the first name and folder
+            // and the latest visit.
+            Some(work) => {
+                work.title = work.title.take().or(title);
+                work.folder = work.folder.take().or(link.folder);
+                work.last_visited = work.last_visited.max(link.last_visited);
+            }
+            None => works.push(FoundWork {
+                fic_id,
+                title,
+                folder: link.folder,
+                last_visited: link.last_visited,
+                selected: true,
+            }),
+        }
+    }
+
+    let ids: Vec<u64> = works.iter().map(|w| w.fic_id).collect();
+    let untracked = untracked_fic_ids(fanfiction_ops, &ids)?;
+    let found = works.len();
+    works.retain(|w| untracked.contains(&w.fic_id));
+    Ok(LinkImportPlan {
+        already_tracked: found - works.len(),
+        works,
+        use_folders: true,
+    })
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use crate::domain::backup::{BackupInfo, BackupOps, BackupReason};
+use crate::error::FicflowError;
+
+/// Deletes all but the newest `keep` backups of each reason, returning
+/// the ones deleted. Counting per reason means a run of exit backups
+/// can't push out the one taken before a migration or a wipe. At least
+/// one of each is always kept.
+pub fn prune_backups(
+    backup_ops: &dyn BackupOps,
+    keep: usize,
+) -> Result<Vec<BackupInfo>, FicflowError> {
+    let mut seen: HashMap<BackupReason, usize> = HashMap::new();
+    let stale: Vec<BackupInfo> = backup_ops
+        .list_backups()?
+        .into_iter()
+        .filter(|backup| {
+            let count = seen.entry(backup.reason).or_default();
+            *count += 1;
+            *count > keep.max(1)
+        })
+        .collect();
+    for backup in &stale {
+        backup_ops.delete_backup(&backup.path)?;
+    }
+    Ok(stale)
+}
@@ -0,0 +1,6 @@
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+pub fn purge_fic(trash_ops: &dyn TrashOps, fic_id: u64) -> Result<(), FicflowError> {
+    trash_ops.purge_fic(fic_id)
+}
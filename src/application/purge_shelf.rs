@@ -0,0 +1,6 @@
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+pub fn purge_shelf(trash_ops: &dyn TrashOps, shelf_id: u64) -> Result<(), FicflowError> {
+    trash_ops.purge_shelf(shelf_id)
+}
@@ -0,0 +1,14 @@
+use chrono::{Duration, Utc};
+
+use crate::domain::trash::{PurgeSummary, TrashOps};
+use crate::error::FicflowError;
+
+/// Empties the trash. With `older_than_days`, only items that have sat
+/// in it longer than that are purged.
+pub fn purge_trash(
+    trash_ops: &dyn TrashOps,
+    older_than_days: Option<u32>,
+) -> Result<PurgeSummary, FicflowError> {
+    let deleted_before = older_than_days.map(|days| Utc::now() - Duration::days(days.into()));
+    trash_ops.purge_trash(deleted_before)
+}
@@ -0,0 +1,9 @@
+use crate::domain::fanfiction::{FanfictionOps, FicQuery};
+use crate::error::FicflowError;
+
+pub fn query_fic_ids(
+    fanfiction_ops: &dyn FanfictionOps,
+    query: &FicQuery,
+) -> Result<Vec<u64>, FicflowError> {
+    fanfiction_ops.query_fanfiction_ids(query)
+}
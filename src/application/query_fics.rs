@@ -0,0 +1,75 @@
+use crate::domain::fanfiction::{
+    ColumnKey, FanfictionOps, FicPage, FicQuery, SortDirection, SortPref,
+};
+use crate::error::FicflowError;
+
+pub fn query_fics(
+    fanfiction_ops: &dyn FanfictionOps,
+    query: &FicQuery,
+) -> Result<FicPage, FicflowError> {
+    fanfiction_ops.query_fanfictions(query)
+}
+
+/// Parses a CLI sort key: a column name, optionally followed by `:asc`
+/// or `:desc` (e.g. `words:desc`). Ascending when no direction is given.
+pub fn parse_sort_pref(input: &str) -> Result<SortPref, FicflowError> {
+    let (name, direction) = match input.split_once(':') {
+        Some((name, direction)) => (name, Some(direction)),
+        None => (input, None),
+    };
+    let column = ColumnKey::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = ColumnKey::ALL.into_iter().map(ColumnKey::name).collect();
+        FicflowError::InvalidInput(format!(
+            "Invalid sort column: '{}'. Valid options are: {}",
+            name.trim(),
+            known.join(", ")
+        ))
+    })?;
+    let direction = match direction.map(|d| d.trim().to_lowercase()).as_deref() {
+        None | Some("asc") => SortDirection::Ascending,
+        Some("desc") => SortDirection::Descending,
+        Some(other) => {
+            return Err(FicflowError::InvalidInput(format!(
+                "Invalid sort direction: '{}'. Valid options are: 'asc', 'desc'",
+                other
+            )));
+        }
+    };
+    Ok(SortPref { column, direction })
+}
+
+/// Parses the CLI's comma-separated `--columns` value for the library
+/// table, e.g. `title,author,words`.
+pub fn parse_column_keys(input: &str) -> Result<Vec<ColumnKey>, FicflowError> {
+    let known: Vec<&str> = ColumnKey::ALL.into_iter().map(ColumnKey::name).collect();
+    parse_names(input, ColumnKey::from_name, &known)
+}
+
+/// Splits a comma-separated list of names and looks each one up with
+/// `from_name`, naming the `known` ones when one isn't found. Shared by
+/// the library table's columns and the export's fields.
+pub fn parse_names<T>(
+    input: &str,
+    from_name: impl Fn(&str) -> Option<T>,
+    known: &[&str],
+) -> Result<Vec<T>, FicflowError> {
+    let names = input
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            from_name(name).ok_or_else(|| {
+                FicflowError::InvalidInput(format!(
+                    "Unknown column: '{}'. Valid columns are: {}",
+                    name.trim(),
+                    known.join(", ")
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if names.is_empty() {
+        return Err(FicflowError::InvalidInput(
+            "at least one column is required".into(),
+        ));
+    }
+    Ok(names)
+}
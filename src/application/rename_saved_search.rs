@@ -0,0 +1,10 @@
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps};
+use crate::error::FicflowError;
+
+pub fn rename_saved_search(
+    saved_search_ops: &dyn SavedSearchOps,
+    saved_search_id: u64,
+    new_name: &str,
+) -> Result<SavedSearch, FicflowError> {
+    saved_search_ops.rename_saved_search(saved_search_id, new_name)
+}
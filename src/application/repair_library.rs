@@ -0,0 +1,30 @@
+use crate::domain::health::{HealthOps, RepairReport};
+use crate::error::FicflowError;
+
+/// Enough for a repair to uncover one more: moving a shelf out of a loop
+/// can leave its branch nested too deep.
+const MAX_REPAIR_PASSES: usize = 3;
+
+/// Makes every safe repair, re-checking after each pass, and reports
+/// what's still wrong at the end.
+pub fn repair_library(health_ops: &dyn HealthOps) -> Result<RepairReport, FicflowError> {
+    let mut report = RepairReport::default();
+    let mut issues = health_ops.check_library()?;
+    for _ in 0..MAX_REPAIR_PASSES {
+        let fixable: Vec<_> = issues
+            .iter()
+            .filter(|i| i.repair().is_some())
+            .cloned()
+            .collect();
+        if fixable.is_empty() {
+            break;
+        }
+        for issue in fixable {
+            health_ops.repair_issue(&issue)?;
+            report.repaired.push(issue);
+        }
+        issues = health_ops.check_library()?;
+    }
+    report.remaining = issues;
+    Ok(report)
+}
@@ -0,0 +1,7 @@
+use crate::domain::fanfiction::Fanfiction;
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+pub fn restore_fic(trash_ops: &dyn TrashOps, fic_id: u64) -> Result<Fanfiction, FicflowError> {
+    trash_ops.restore_fic(fic_id)
+}
@@ -0,0 +1,7 @@
+use crate::domain::shelf::Shelf;
+use crate::domain::trash::TrashOps;
+use crate::error::FicflowError;
+
+pub fn restore_shelf(trash_ops: &dyn TrashOps, shelf_id: u64) -> Result<Shelf, FicflowError> {
+    trash_ops.restore_shelf(shelf_id)
+}
@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps};
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps, SearchScope};
+use crate::domain::shelf::{ShelfKind, ShelfOps};
+use crate::error::FicflowError;
+
+/// Re-runs a saved search: resolves its scope to a set of fics, keeps the
+/// ones matching its query, and orders them by its saved sort.
+pub fn run_saved_search(
+    fanfiction_ops: &dyn FanfictionOps,
+    shelf_ops: &dyn ShelfOps,
+    saved_search_ops: &dyn SavedSearchOps,
+    saved_search_id: u64,
+) -> Result<(SavedSearch, Vec<Fanfiction>), FicflowError> {
+    let search = saved_search_ops.get_saved_search_by_id(saved_search_id)?;
+    let in_scope = match search.scope {
+        SearchScope::AllFics => fanfiction_ops.list_fanfictions()?,
+        SearchScope::ByStatus(status) => fanfiction_ops
+            .list_fanfictions()?
+            .into_iter()
+            .filter(|f| f.reading_status == status)
+            .collect(),
+        SearchScope::Shelf(shelf_id) => match shelf_ops.get_shelf_by_id(shelf_id)?.kind {
+            ShelfKind::Auto(criteria) => fanfiction_ops
+                .list_fanfictions()?
+                .into_iter()
+                .filter(|f| criteria.matches(f))
+                .collect(),
+            ShelfKind::Normal => shelf_ops.list_fics_in_shelf(shelf_id)?,
+        },
+    };
+    let mut fics: Vec<Fanfiction> = if search.query.trim().is_empty() {
+        in_scope
+    } else {
+        let hits: HashSet<u64> = fanfiction_ops
+            .search_fanfictions(&search.query)?
+            .into_iter()
+            .map(|hit| hit.fic.id)
+            .collect();
+        in_scope
+            .into_iter()
+            .filter(|f| hits.contains(&f.id))
+            .collect()
+    };
+    fics.sort_by(|a, b| search.sort.compare_total(a, b));
+    Ok((search, fics))
+}
@@ -0,0 +1,34 @@
+use crate::application::untracked_fic_ids::untracked_fic_ids;
+use crate::domain::discover::{Ao3Search, BlurbPage, WorkSearcher};
+use crate::domain::fanfiction::FanfictionOps;
+use crate::error::FicflowError;
+
+/// Fetches one page of `search` from AO3 and marks the works the library
+/// already has, or leaves them out with `hide_tracked`. Hiding is per
+/// page, so a page can come back short or empty while later ones still
+/// have new works.
+pub fn search_ao3(
+    searcher: &dyn WorkSearcher,
+    fanfiction_ops: &dyn FanfictionOps,
+    search: &Ao3Search,
+    page: u32,
+    hide_tracked: bool,
+) -> Result<BlurbPage, FicflowError> {
+    if search.terms().trim().is_empty() {
+        return Err(FicflowError::InvalidInput(
+            "Give a tag or some words to search AO3 for".to_string(),
+        ));
+    }
+    let mut found = searcher.search_works(search, page.max(1))?;
+    let ids: Vec<u64> = found.blurbs.iter().map(|b| b.id).collect();
+    let untracked = untracked_fic_ids(fanfiction_ops, &ids)?;
+    for blurb in &mut found.blurbs {
+        blurb.tracked = !untracked.contains(&blurb.id);
+    }
+    if hide_tracked {
+        let listed = found.blurbs.len();
+        found.blurbs.retain(|b| !b.tracked);
+        found.hidden = listed - found.blurbs.len();
+    }
+    Ok(found)
+}
@@ -0,0 +1,9 @@
+use crate::domain::fanfiction::{FanfictionOps, SearchHit};
+use crate::error::FicflowError;
+
+pub fn search_fics(
+    fanfiction_ops: &dyn FanfictionOps,
+    query: &str,
+) -> Result<Vec<SearchHit>, FicflowError> {
+    fanfiction_ops.search_fanfictions(query)
+}
@@ -0,0 +1,21 @@
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps, UserFields};
+use crate::error::FicflowError;
+
+/// Overwrites every user field at once, with none of the knock-on rules
+/// the single-field updates apply (e.g. finishing the last chapter
+/// marking the fic as read).
+pub fn set_user_fields(
+    fanfiction_ops: &dyn FanfictionOps,
+    fic_id: u64,
+    fields: &UserFields,
+) -> Result<Fanfiction, FicflowError> {
+    let mut fanfiction = fanfiction_ops.get_fanfiction_by_id(fic_id)?;
+    fanfiction.last_chapter_read = fields.last_chapter_read;
+    fanfiction.reading_status = fields.reading_status;
+    fanfiction.read_count = fields.read_count;
+    fanfiction.user_rating = fields.user_rating;
+    fanfiction.personal_note = fields.personal_note.clone();
+    fanfiction.personal_tags = fields.personal_tags.clone();
+    fanfiction_ops.save_fanfiction(&fanfiction)?;
+    Ok(fanfiction)
+}
@@ -0,0 +1,19 @@
+use crate::domain::fanfiction::FanfictionOps;
+use crate::error::FicflowError;
+
+/// The ids in `fic_ids` that aren't in the library, in the same order.
+/// Trashed fics count as untracked, since adding them again revives them.
+pub fn untracked_fic_ids(
+    fanfiction_ops: &dyn FanfictionOps,
+    fic_ids: &[u64],
+) -> Result<Vec<u64>, FicflowError> {
+    let mut untracked = Vec::new();
+    for &fic_id in fic_ids {
+        match fanfiction_ops.get_fanfiction_by_id(fic_id) {
+            Ok(_) => {}
+            Err(FicflowError::NotFound { .. }) => untracked.push(fic_id),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(untracked)
+}
@@ -8,6 +8,17 @@ pub fn update_last_chapter_read(
 ) -> Result<Fanfiction, FicflowError> {
     let mut fic = fanfiction_ops.get_fanfiction_by_id(fic_id)?;
 
+    set_last_chapter_read(&mut fic, new_chapter_count);
+
+    fanfiction_ops.save_fanfiction(&fic)?;
+
+    Ok(fic)
+}
+
+/// Moves `fic` to `new_chapter_count` without saving it. Reaching the
+/// last chapter of a finished work counts as a read; anything short of
+/// that, or any chapter of a work in progress, only starts reading it.
+pub fn set_last_chapter_read(fic: &mut Fanfiction, new_chapter_count: u32) {
     let adjusted_chapter_count = if let Some(total_chapters) = fic.chapters_total {
         if new_chapter_count > total_chapters {
             log::warn!(
@@ -41,8 +52,4 @@ pub fn update_last_chapter_read(
             _ => {}
         }
     }
-
-    fanfiction_ops.save_fanfiction(&fic)?;
-
-    Ok(fic)
 }
@@ -0,0 +1,21 @@
+use crate::domain::fanfiction::{Fanfiction, FanfictionOps};
+use crate::error::FicflowError;
+
+/// Replaces the fic's personal tags. Blank entries and repeats are
+/// dropped; order is otherwise kept.
+pub fn update_personal_tags(
+    fanfiction_ops: &dyn FanfictionOps,
+    fic_id: u64,
+    tags: &[String],
+) -> Result<Fanfiction, FicflowError> {
+    let mut fanfiction = fanfiction_ops.get_fanfiction_by_id(fic_id)?;
+    let mut cleaned: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !cleaned.iter().any(|t| t == tag) {
+            cleaned.push(tag.to_string());
+        }
+    }
+    fanfiction.personal_tags = cleaned;
+    fanfiction_ops.save_fanfiction(&fanfiction)?;
+    Ok(fanfiction)
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How many backups are kept when the config doesn't say.
+pub const DEFAULT_BACKUP_KEEP: usize = 10;
+
+/// What prompted a backup. Recorded in its file name, so a list of
+/// backups can say which one was taken just before, say, a wipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum BackupReason {
+    /// Asked for by hand.
+    Manual,
+    /// The first time the library is opened on a given day.
+    Daily,
+    /// The GUI closing.
+    Exit,
+    /// An upgrade of an existing library to a newer schema.
+    Migration,
+    Wipe,
+    /// Replacing the library with an older backup.
+    Restore,
+    /// `ficflow doctor --fix` or the GUI's repair.
+    Repair,
+}
+
+impl BackupReason {
+    pub const ALL: [BackupReason; 7] = [
+        BackupReason::Manual,
+        BackupReason::Daily,
+        BackupReason::Exit,
+        BackupReason::Migration,
+        BackupReason::Wipe,
+        BackupReason::Restore,
+        BackupReason::Repair,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackupReason::Manual => "manual",
+            BackupReason::Daily => "daily",
+            BackupReason::Exit => "exit",
+            BackupReason::Migration => "migration",
+            BackupReason::Wipe => "wipe",
+            BackupReason::Restore => "restore",
+            BackupReason::Repair => "repair",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    pub reason: BackupReason,
+}
@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod repository;
+
+pub use entity::{BackupInfo, BackupReason, DEFAULT_BACKUP_KEEP};
+pub use repository::BackupOps;
@@ -0,0 +1,14 @@
+use std::path::Path;
+
+use super::entity::{BackupInfo, BackupReason};
+use crate::error::FicflowError;
+
+/// Whole-library snapshots kept beside the library file.
+pub trait BackupOps {
+    /// Takes a consistent snapshot of the live library, safe while
+    /// another connection is writing to it.
+    fn create_backup(&self, reason: BackupReason) -> Result<BackupInfo, FicflowError>;
+    /// Newest first.
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, FicflowError>;
+    fn delete_backup(&self, path: &Path) -> Result<(), FicflowError>;
+}
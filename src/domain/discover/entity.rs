@@ -0,0 +1,75 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::domain::fanfiction::FanfictionFetcher;
+use crate::error::FicflowError;
+
+/// What to look for on AO3: every work filed under a tag, or the works a
+/// free-text search turns up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Ao3Search {
+    Tag(String),
+    Text(String),
+}
+
+impl Ao3Search {
+    pub fn terms(&self) -> &str {
+        match self {
+            Ao3Search::Tag(terms) | Ao3Search::Text(terms) => terms,
+        }
+    }
+}
+
+impl fmt::Display for Ao3Search {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ao3Search::Tag(tag) => write!(f, "works tagged '{}'", tag),
+            Ao3Search::Text(text) => write!(f, "works matching '{}'", text),
+        }
+    }
+}
+
+/// The little a listing page shows about a work, enough to decide
+/// whether to add it. Adding fetches the rest.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WorkBlurb {
+    pub id: u64,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub fandoms: Vec<String>,
+    pub words: u32,
+    pub chapters_published: u32,
+    pub chapters_total: Option<u32>,
+    pub kudos: u32,
+    /// Already in the library. Listings don't say; the search sets it.
+    pub tracked: bool,
+}
+
+/// One page of a listing, numbered from 1.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BlurbPage {
+    pub blurbs: Vec<WorkBlurb>,
+    pub page: u32,
+    pub page_count: u32,
+    /// Works on this page left out because the library has them.
+    pub hidden: usize,
+}
+
+impl BlurbPage {
+    pub fn has_next(&self) -> bool {
+        self.page < self.page_count
+    }
+}
+
+/// Where listings come from, so tests can stand in for AO3.
+pub trait WorkSearcher {
+    fn search_works(&self, search: &Ao3Search, page: u32) -> Result<BlurbPage, FicflowError>;
+}
+
+/// Both ways of reading AO3, so the CLI's composition root can hand the
+/// executor one fetcher for works and listings alike. Callers still take
+/// the narrower trait they need.
+pub trait Archive: FanfictionFetcher + WorkSearcher {}
+
+impl<T: FanfictionFetcher + WorkSearcher> Archive for T {}
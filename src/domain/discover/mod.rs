@@ -0,0 +1,3 @@
+pub mod entity;
+
+pub use entity::{Ao3Search, Archive, BlurbPage, WorkBlurb, WorkSearcher};
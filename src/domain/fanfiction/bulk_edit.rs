@@ -0,0 +1,16 @@
+use super::rating::UserRating;
+use super::status::ReadingStatus;
+
+/// One change applied to every fic in a multi-selection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BulkEdit {
+    Status(ReadingStatus),
+    UserRating(Option<UserRating>),
+    ReadCount(u32),
+    /// Last chapter read set to the last published chapter.
+    MarkFullyRead,
+    /// Added as a new paragraph after any existing note.
+    AppendNote(String),
+    AddPersonalTag(String),
+    RemovePersonalTag(String),
+}
@@ -9,6 +9,11 @@ pub trait FanfictionFetcher {
     fn fetch_fanfiction(&self, fic_id: u64) -> Result<Fanfiction, FicflowError>;
 }
 
+/// Hands a link to whatever shows it to the reader, usually a browser.
+pub trait LinkOpener {
+    fn open_link(&self, url: &str) -> Result<(), FicflowError>;
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Fanfiction {
     pub id: u64, // AO3 ID
@@ -32,10 +37,103 @@ pub struct Fanfiction {
     pub words: u32,
     pub date_published: DateTime<Utc>,
     pub date_updated: DateTime<Utc>,
-    pub last_chapter_read: Option<u32>,   // Custom field
-    pub reading_status: ReadingStatus,    // Custom field
-    pub read_count: u32,                  // Custom field
-    pub user_rating: Option<UserRating>,  // Custom field
-    pub personal_note: Option<String>,    // Custom field
+    pub last_chapter_read: Option<u32>,  // Custom field
+    pub reading_status: ReadingStatus,   // Custom field
+    pub read_count: u32,                 // Custom field
+    pub user_rating: Option<UserRating>, // Custom field
+    pub personal_note: Option<String>,   // Custom field
+    #[serde(default)]
+    pub personal_tags: Vec<String>, // Custom field (the reader's own labels)
     pub last_checked_date: DateTime<Utc>, // Custom field (last update check)
 }
+
+/// The custom fields a reader edits by hand, as one unit so a change to
+/// any of them can be captured and put back wholesale.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UserFields {
+    pub last_chapter_read: Option<u32>,
+    pub reading_status: ReadingStatus,
+    pub read_count: u32,
+    pub user_rating: Option<UserRating>,
+    pub personal_note: Option<String>,
+    pub personal_tags: Vec<String>,
+}
+
+impl Fanfiction {
+    /// A fic known only by its id and title, e.g. one found in another
+    /// app's library. Everything else is left blank until AO3 is
+    /// fetched; `last_checked_date` is the epoch so it reads as never
+    /// checked.
+    pub fn placeholder(id: u64, title: impl Into<String>) -> Self {
+        Self {
+            id,
+            title: title.into(),
+            authors: Vec::new(),
+            categories: None,
+            chapters_total: None,
+            chapters_published: 0,
+            characters: None,
+            complete: false,
+            fandoms: Vec::new(),
+            hits: 0,
+            kudos: 0,
+            language: String::new(),
+            rating: Rating::NotRated,
+            relationships: None,
+            restricted: false,
+            summary: String::new(),
+            tags: None,
+            warnings: Vec::new(),
+            words: 0,
+            date_published: DateTime::UNIX_EPOCH,
+            date_updated: DateTime::UNIX_EPOCH,
+            last_chapter_read: None,
+            reading_status: ReadingStatus::PlanToRead,
+            read_count: 0,
+            user_rating: None,
+            personal_note: None,
+            personal_tags: Vec::new(),
+            last_checked_date: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    /// The work's page on AO3.
+    pub fn url(&self) -> String {
+        format!("https://archiveofourown.org/works/{}", self.id)
+    }
+
+    /// Where to pick the fic back up: the chapter after the last one
+    /// read, the first for a fic not started, and never past the last
+    /// chapter published.
+    pub fn next_chapter(&self) -> u32 {
+        self.last_chapter_read
+            .map_or(1, |read| read.saturating_add(1))
+            .min(self.chapters_published.max(1))
+    }
+
+    /// `chapter` of the work on the AO3 site at `base_url`. AO3 only
+    /// addresses chapters by their own ids, which aren't stored, so later
+    /// chapters go through the full-work page's `#chapter-N` anchors.
+    pub fn chapter_url(&self, base_url: &str, chapter: u32) -> String {
+        let base = base_url.trim_end_matches('/');
+        if chapter <= 1 {
+            format!("{}/works/{}", base, self.id)
+        } else {
+            format!(
+                "{}/works/{}?view_full_work=true#chapter-{}",
+                base, self.id, chapter
+            )
+        }
+    }
+
+    pub fn user_fields(&self) -> UserFields {
+        UserFields {
+            last_chapter_read: self.last_chapter_read,
+            reading_status: self.reading_status,
+            read_count: self.read_count,
+            user_rating: self.user_rating,
+            personal_note: self.personal_note.clone(),
+            personal_tags: self.personal_tags.clone(),
+        }
+    }
+}
@@ -1,10 +1,20 @@
+pub mod bulk_edit;
 pub mod entity;
+pub mod query;
 pub mod rating;
 pub mod repository;
+pub mod search;
+pub mod sort;
 pub mod status;
+pub mod tag;
 
-pub use entity::Fanfiction;
-pub use entity::FanfictionFetcher;
+pub use bulk_edit::BulkEdit;
+pub use entity::{Fanfiction, UserFields};
+pub use entity::{FanfictionFetcher, LinkOpener};
+pub use query::{FicFilter, FicPage, FicQuery};
 pub use rating::{ArchiveWarnings, Categories, Rating, UserRating};
 pub use repository::FanfictionOps;
+pub use search::{SearchHit, Snippet};
+pub use sort::{ColumnKey, SortDirection, SortPref};
 pub use status::ReadingStatus;
+pub use tag::{TagCount, TagKind};
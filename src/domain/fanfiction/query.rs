@@ -0,0 +1,41 @@
+use super::entity::Fanfiction;
+use super::sort::SortPref;
+use super::status::ReadingStatus;
+use super::tag::TagKind;
+
+/// Which fics a `FicQuery` keeps. Unset fields don't filter; set ones
+/// must all match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FicFilter {
+    pub status: Option<ReadingStatus>,
+    /// Fics filed on this shelf or any shelf nested under it. Only
+    /// normal shelves have stored members; auto-shelves are criteria
+    /// and get matched in memory (`AutoShelfCriteria::matches`).
+    pub shelf_id: Option<u64>,
+    pub complete: Option<bool>,
+    /// Fics carrying every one of these tags, names compared without
+    /// regard to ASCII case, e.g. `(TagKind::Fandom, "Good Omens")`.
+    pub tags: Vec<(TagKind, String)>,
+    pub min_words: Option<u32>,
+    /// Full-text terms, with the same semantics as
+    /// `FanfictionOps::search_fanfictions`. Blank means no text filter.
+    pub text: Option<String>,
+}
+
+/// A filtered, sorted, optionally paged read of the library. Sort keys
+/// apply in order; ties fall back to title, then id, so pages are
+/// stable.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FicQuery {
+    pub filter: FicFilter,
+    pub sort: Vec<SortPref>,
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+/// One page of a `FicQuery`, plus how many fics match across all pages.
+#[derive(Debug, Clone)]
+pub struct FicPage {
+    pub fics: Vec<Fanfiction>,
+    pub total: usize,
+}
@@ -1,10 +1,29 @@
 use super::entity::Fanfiction;
+use super::query::{FicPage, FicQuery};
+use super::search::SearchHit;
+use super::tag::{TagCount, TagKind};
 use crate::error::FicflowError;
 
 pub trait FanfictionOps {
     fn save_fanfiction(&self, fic: &Fanfiction) -> Result<(), FicflowError>;
+    /// Saves every fic in one transaction: all of them or none.
+    fn save_fanfictions(&self, fics: &[Fanfiction]) -> Result<(), FicflowError>;
     fn delete_fanfiction(&self, fic_id: u64) -> Result<(), FicflowError>;
     fn list_fanfictions(&self) -> Result<Vec<Fanfiction>, FicflowError>;
     fn get_fanfiction_by_id(&self, fic_id: u64) -> Result<Fanfiction, FicflowError>;
+    fn query_fanfictions(&self, query: &FicQuery) -> Result<FicPage, FicflowError>;
+    /// `query_fanfictions` without loading the rows — for callers that
+    /// already hold the fics and only need the filtered order.
+    fn query_fanfiction_ids(&self, query: &FicQuery) -> Result<Vec<u64>, FicflowError>;
+    /// Full-text search over the live library, best match first. A query
+    /// with no searchable terms returns no hits.
+    fn search_fanfictions(&self, query: &str) -> Result<Vec<SearchHit>, FicflowError>;
+    /// Every distinct `kind` tag in the live library with its fic count,
+    /// ordered by name. Names that differ only in ASCII case are one tag,
+    /// under the spelling stored first.
+    fn count_tags(&self, kind: TagKind) -> Result<Vec<TagCount>, FicflowError>;
+    /// Ids of live fics carrying the `kind` tag `name`, by title. Names
+    /// compare ignoring ASCII case, same as auto-shelf clauses.
+    fn find_fic_ids_by_tag(&self, kind: TagKind, name: &str) -> Result<Vec<u64>, FicflowError>;
     fn wipe_database(&self) -> Result<(), FicflowError>;
 }
@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use super::entity::Fanfiction;
+
+/// One full-text search result. Repositories return hits best-first.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub fic: Fanfiction,
+    pub snippet: Snippet,
+}
+
+/// A short excerpt of the field that matched best, with the byte ranges
+/// of the matched terms inside `text`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Snippet {
+    pub text: String,
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl Snippet {
+    /// Splits `text` into `(segment, highlighted)` runs, in order.
+    pub fn segments(&self) -> Vec<(&str, bool)> {
+        let mut out = Vec::with_capacity(self.highlights.len() * 2 + 1);
+        let mut cursor = 0;
+        for range in &self.highlights {
+            if range.start > cursor {
+                out.push((&self.text[cursor..range.start], false));
+            }
+            out.push((&self.text[range.clone()], true));
+            cursor = range.end;
+        }
+        if cursor < self.text.len() {
+            out.push((&self.text[cursor..], false));
+        }
+        out
+    }
+}
@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::Fanfiction;
+use super::rating::{ArchiveWarnings, Rating};
+use super::status::ReadingStatus;
+
+/// A sortable/displayable fic attribute. Named after the library table's
+/// columns, which is where the set originated; the CLI and saved searches
+/// reuse the same keys so every front end agrees on names and ordering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColumnKey {
+    Title,
+    Author,
+    Fandom,
+    Pairing,
+    AO3Rating,
+    Warnings,
+    Status,
+    Complete,
+    LastChapter,
+    Words,
+    Kudos,
+    Hits,
+    Rating,
+    Reads,
+    Language,
+    DatePublished,
+    Updated,
+}
+
+impl ColumnKey {
+    pub const ALL: [ColumnKey; 17] = [
+        ColumnKey::Title,
+        ColumnKey::Author,
+        ColumnKey::Fandom,
+        ColumnKey::Pairing,
+        ColumnKey::AO3Rating,
+        ColumnKey::Warnings,
+        ColumnKey::Status,
+        ColumnKey::Complete,
+        ColumnKey::LastChapter,
+        ColumnKey::Words,
+        ColumnKey::Kudos,
+        ColumnKey::Hits,
+        ColumnKey::Rating,
+        ColumnKey::Reads,
+        ColumnKey::Language,
+        ColumnKey::DatePublished,
+        ColumnKey::Updated,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColumnKey::Title => "Title",
+            ColumnKey::Author => "Author",
+            ColumnKey::Fandom => "Fandom",
+            ColumnKey::Pairing => "Pairing",
+            ColumnKey::AO3Rating => "AO3 Rating",
+            ColumnKey::Warnings => "Warnings",
+            ColumnKey::Status => "Status",
+            ColumnKey::Complete => "Complete",
+            ColumnKey::LastChapter => "Last Ch.",
+            ColumnKey::Words => "Words",
+            ColumnKey::Kudos => "Kudos",
+            ColumnKey::Hits => "Hits",
+            ColumnKey::Rating => "Rating",
+            ColumnKey::Reads => "Reads",
+            ColumnKey::Language => "Language",
+            ColumnKey::DatePublished => "Published",
+            ColumnKey::Updated => "Updated",
+        }
+    }
+
+    /// The name the CLI takes for this column, e.g. `last-chapter`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnKey::Title => "title",
+            ColumnKey::Author => "author",
+            ColumnKey::Fandom => "fandom",
+            ColumnKey::Pairing => "pairing",
+            ColumnKey::AO3Rating => "ao3-rating",
+            ColumnKey::Warnings => "warnings",
+            ColumnKey::Status => "status",
+            ColumnKey::Complete => "complete",
+            ColumnKey::LastChapter => "last-chapter",
+            ColumnKey::Words => "words",
+            ColumnKey::Kudos => "kudos",
+            ColumnKey::Hits => "hits",
+            ColumnKey::Rating => "rating",
+            ColumnKey::Reads => "reads",
+            ColumnKey::Language => "language",
+            ColumnKey::DatePublished => "published",
+            ColumnKey::Updated => "updated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ColumnKey> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|column| column.name() == name)
+    }
+
+    /// Orders two fics by this key, ascending. Text folds ASCII case only,
+    /// like SQLite's `lower()`, so this agrees with the repository's
+    /// `ORDER BY` for every title, accented or not.
+    pub fn compare(self, a: &Fanfiction, b: &Fanfiction) -> Ordering {
+        match self {
+            ColumnKey::Title => a
+                .title
+                .to_ascii_lowercase()
+                .cmp(&b.title.to_ascii_lowercase()),
+            ColumnKey::Author => first_lower(&a.authors).cmp(&first_lower(&b.authors)),
+            ColumnKey::Fandom => first_lower(&a.fandoms).cmp(&first_lower(&b.fandoms)),
+            ColumnKey::Pairing => a
+                .relationships
+                .as_deref()
+                .map(first_lower)
+                .cmp(&b.relationships.as_deref().map(first_lower)),
+            ColumnKey::AO3Rating => ao3_rating_order(&a.rating).cmp(&ao3_rating_order(&b.rating)),
+            ColumnKey::Warnings => a
+                .warnings
+                .first()
+                .map(warning_order)
+                .cmp(&b.warnings.first().map(warning_order)),
+            ColumnKey::Status => {
+                status_order(&a.reading_status).cmp(&status_order(&b.reading_status))
+            }
+            ColumnKey::Complete => a.complete.cmp(&b.complete),
+            ColumnKey::LastChapter => a.last_chapter_read.cmp(&b.last_chapter_read),
+            ColumnKey::Words => a.words.cmp(&b.words),
+            ColumnKey::Kudos => a.kudos.cmp(&b.kudos),
+            ColumnKey::Hits => a.hits.cmp(&b.hits),
+            ColumnKey::Rating => a
+                .user_rating
+                .map(|r| r as u8)
+                .cmp(&b.user_rating.map(|r| r as u8)),
+            ColumnKey::Reads => a.read_count.cmp(&b.read_count),
+            ColumnKey::Language => a
+                .language
+                .to_ascii_lowercase()
+                .cmp(&b.language.to_ascii_lowercase()),
+            ColumnKey::DatePublished => a.date_published.cmp(&b.date_published),
+            ColumnKey::Updated => a.date_updated.cmp(&b.date_updated),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortPref {
+    pub column: ColumnKey,
+    pub direction: SortDirection,
+}
+
+impl Default for SortPref {
+    fn default() -> Self {
+        Self {
+            column: ColumnKey::Updated,
+            direction: SortDirection::Descending,
+        }
+    }
+}
+
+impl SortPref {
+    pub fn compare(&self, a: &Fanfiction, b: &Fanfiction) -> Ordering {
+        let ord = self.column.compare(a, b);
+        match self.direction {
+            SortDirection::Ascending => ord,
+            SortDirection::Descending => ord.reverse(),
+        }
+    }
+
+    /// `compare`, with ties broken by exact title and then id, the same
+    /// fallbacks a `FicQuery` sorts by.
+    pub fn compare_total(&self, a: &Fanfiction, b: &Fanfiction) -> Ordering {
+        self.compare(a, b)
+            .then_with(|| a.title.cmp(&b.title))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+fn first_lower(v: &[String]) -> Option<String> {
+    v.first().map(|s| s.to_ascii_lowercase())
+}
+
+fn ao3_rating_order(r: &Rating) -> u8 {
+    match r {
+        Rating::NotRated => 0,
+        Rating::General => 1,
+        Rating::TeenAndUp => 2,
+        Rating::Mature => 3,
+        Rating::Explicit => 4,
+    }
+}
+
+/// Alphabetical by the label the library table shows for each warning,
+/// with "no warnings" (rendered as a dash) sorting last.
+fn warning_order(w: &ArchiveWarnings) -> u8 {
+    match w {
+        ArchiveWarnings::ChooseNotToUse => 0,
+        ArchiveWarnings::GraphicDepictionsOfViolence => 1,
+        ArchiveWarnings::MajorCharacterDeath => 2,
+        ArchiveWarnings::RapeNonCon => 3,
+        ArchiveWarnings::Underage => 4,
+        ArchiveWarnings::NoArchiveWarningsApply => 5,
+    }
+}
+
+fn status_order(s: &ReadingStatus) -> u8 {
+    match s {
+        ReadingStatus::InProgress => 0,
+        ReadingStatus::Read => 1,
+        ReadingStatus::PlanToRead => 2,
+        ReadingStatus::Paused => 3,
+        ReadingStatus::Abandoned => 4,
+    }
+}
@@ -0,0 +1,30 @@
+use strum_macros::Display;
+
+/// The free-text list fields of a `Fanfiction`. Each value in one of them
+/// is a tag of that kind; the same text under two kinds is two tags.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+pub enum TagKind {
+    Author,
+    Fandom,
+    Relationship,
+    Character,
+    /// AO3's additional ("freeform") tags — `Fanfiction::tags`.
+    Tag,
+}
+
+impl TagKind {
+    pub const ALL: [TagKind; 5] = [
+        TagKind::Author,
+        TagKind::Fandom,
+        TagKind::Relationship,
+        TagKind::Character,
+        TagKind::Tag,
+    ];
+}
+
+/// A tag and how many live fics carry it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    pub name: String,
+    pub count: usize,
+}
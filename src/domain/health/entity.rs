@@ -0,0 +1,106 @@
+use serde::Serialize;
+
+use crate::domain::shelf::MAX_SHELF_DEPTH;
+
+/// One problem found by a library check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum LibraryIssue {
+    /// A line of `PRAGMA integrity_check` output. The file itself is
+    /// damaged; only a backup can fix it.
+    Corruption(String),
+    /// A row pointing at a row that no longer exists, in a table with
+    /// no more specific check below.
+    BrokenReference {
+        table: String,
+        rowid: i64,
+        parent: String,
+    },
+    /// A shelf membership whose fic or shelf row is gone.
+    DanglingMembership { fic_id: u64, shelf_id: u64 },
+    /// A fic list column that doesn't decode.
+    BadFicJson { fic_id: u64, column: &'static str },
+    BadSavedSearchJson {
+        saved_search_id: u64,
+        column: &'static str,
+    },
+    /// An auto-shelf whose criteria are missing, don't decode, or have
+    /// a clause with nothing to match.
+    BadAutoCriteria { shelf_id: u64, name: String },
+    /// A shelf under a parent that no longer exists.
+    MissingParent { shelf_id: u64, name: String },
+    /// A shelf that is its own ancestor. One shelf per loop is reported.
+    ShelfCycle { shelf_id: u64, name: String },
+    /// The topmost shelf of a branch nested past `MAX_SHELF_DEPTH`.
+    ShelfTooDeep {
+        shelf_id: u64,
+        name: String,
+        depth: u32,
+    },
+}
+
+impl LibraryIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            LibraryIssue::Corruption(message) => format!("Damaged database: {}", message),
+            LibraryIssue::BrokenReference {
+                table,
+                rowid,
+                parent,
+            } => format!(
+                "Row {} of {} points at a missing {} row",
+                rowid, table, parent
+            ),
+            LibraryIssue::DanglingMembership { fic_id, shelf_id } => format!(
+                "Fic {} is on shelf {}, but one of them no longer exists",
+                fic_id, shelf_id
+            ),
+            LibraryIssue::BadFicJson { fic_id, column } => {
+                format!("Fic {} has an unreadable {} list", fic_id, column)
+            }
+            LibraryIssue::BadSavedSearchJson {
+                saved_search_id,
+                column,
+            } => format!(
+                "Saved search {} has an unreadable {}",
+                saved_search_id, column
+            ),
+            LibraryIssue::BadAutoCriteria { name, .. } => {
+                format!("Auto-shelf \"{}\" has invalid criteria", name)
+            }
+            LibraryIssue::MissingParent { name, .. } => format!(
+                "Shelf \"{}\" is nested under a shelf that no longer exists",
+                name
+            ),
+            LibraryIssue::ShelfCycle { name, .. } => {
+                format!("Shelf \"{}\" is nested inside itself", name)
+            }
+            LibraryIssue::ShelfTooDeep { name, depth, .. } => format!(
+                "Shelf \"{}\" is nested {} levels deep, past the limit of {}",
+                name, depth, MAX_SHELF_DEPTH
+            ),
+        }
+    }
+
+    /// What `--fix` does about it, or `None` when there's no safe
+    /// repair.
+    pub fn repair(&self) -> Option<&'static str> {
+        match self {
+            LibraryIssue::Corruption(_) => None,
+            LibraryIssue::BrokenReference { .. } => Some("delete the row"),
+            LibraryIssue::DanglingMembership { .. } => Some("remove the membership"),
+            LibraryIssue::BadFicJson { .. } => Some("clear the list"),
+            LibraryIssue::BadSavedSearchJson { .. } => Some("reset it to the default"),
+            LibraryIssue::BadAutoCriteria { .. } => Some("drop the invalid clauses"),
+            LibraryIssue::MissingParent { .. }
+            | LibraryIssue::ShelfCycle { .. }
+            | LibraryIssue::ShelfTooDeep { .. } => Some("move it to the top level"),
+        }
+    }
+}
+
+/// What a repair run fixed, and what it couldn't.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepairReport {
+    pub repaired: Vec<LibraryIssue>,
+    pub remaining: Vec<LibraryIssue>,
+}
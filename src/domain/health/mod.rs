@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod repository;
+
+pub use entity::{LibraryIssue, RepairReport};
+pub use repository::HealthOps;
@@ -0,0 +1,13 @@
+use super::entity::LibraryIssue;
+use crate::error::FicflowError;
+
+/// Checks the library file for damage the normal reads would trip over,
+/// and makes the safe repairs.
+pub trait HealthOps {
+    /// Every problem found, most serious first. Empty for a healthy
+    /// library.
+    fn check_library(&self) -> Result<Vec<LibraryIssue>, FicflowError>;
+    /// Makes the repair `issue.repair()` describes. An issue without one
+    /// is an `InvalidInput` error.
+    fn repair_issue(&self, issue: &LibraryIssue) -> Result<(), FicflowError>;
+}
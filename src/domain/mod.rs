@@ -1,3 +1,9 @@
+pub mod backup;
+pub mod discover;
 pub mod fanfiction;
+pub mod health;
 pub mod repository;
+pub mod saved_search;
 pub mod shelf;
+pub mod transfer;
+pub mod trash;
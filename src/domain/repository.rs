@@ -1,11 +1,24 @@
 use super::fanfiction::FanfictionOps;
+use super::health::HealthOps;
+use super::saved_search::SavedSearchOps;
 use super::shelf::ShelfOps;
+use super::transfer::TransferOps;
+use super::trash::TrashOps;
 
 // Aggregate trait so the composition root (factory, interface, executor) can
-// depend on a single "does fic and shelf ops" reference instead of passing
-// the same object twice as two separate trait objects. Application functions
-// still take the specific trait they need; trait upcasting coerces
-// `&dyn Repository` to `&dyn FanfictionOps` / `&dyn ShelfOps` at call sites.
-pub trait Repository: FanfictionOps + ShelfOps {}
+// depend on a single "does fic, shelf, saved-search, trash, transfer and
+// health ops" reference instead of passing the same object several times as
+// separate trait objects.
+// Application functions still take the specific trait they need; trait
+// upcasting coerces `&dyn Repository` to `&dyn FanfictionOps` /
+// `&dyn ShelfOps` / `&dyn SavedSearchOps` / `&dyn TrashOps` / `&dyn TransferOps`
+// / `&dyn HealthOps` at call sites.
+pub trait Repository:
+    FanfictionOps + ShelfOps + SavedSearchOps + TrashOps + TransferOps + HealthOps
+{
+}
 
-impl<T: FanfictionOps + ShelfOps> Repository for T {}
+impl<T: FanfictionOps + ShelfOps + SavedSearchOps + TrashOps + TransferOps + HealthOps> Repository
+    for T
+{
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::fanfiction::{ReadingStatus, SortPref};
+
+/// The library view a saved search was taken from; the query is applied on
+/// top of it when the search is re-run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SearchScope {
+    AllFics,
+    ByStatus(ReadingStatus),
+    Shelf(u64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: u64,
+    pub name: String,
+    pub query: String,
+    pub scope: SearchScope,
+    pub sort: SortPref,
+    pub position: u32,
+    pub created_at: DateTime<Utc>,
+}
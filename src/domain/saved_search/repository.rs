@@ -0,0 +1,31 @@
+use super::entity::{SavedSearch, SearchScope};
+use crate::domain::fanfiction::SortPref;
+use crate::error::FicflowError;
+
+pub trait SavedSearchOps {
+    /// Appends a new saved search after the existing ones. Blank names are
+    /// rejected, same as shelves.
+    fn create_saved_search(
+        &self,
+        name: &str,
+        query: &str,
+        scope: SearchScope,
+        sort: SortPref,
+    ) -> Result<SavedSearch, FicflowError>;
+    fn delete_saved_search(&self, saved_search_id: u64) -> Result<(), FicflowError>;
+    fn rename_saved_search(
+        &self,
+        saved_search_id: u64,
+        new_name: &str,
+    ) -> Result<SavedSearch, FicflowError>;
+    /// Moves a saved search to `new_position` (0-based, clamped to the end
+    /// of the list) and renumbers the others so positions stay contiguous.
+    fn move_saved_search(
+        &self,
+        saved_search_id: u64,
+        new_position: u32,
+    ) -> Result<SavedSearch, FicflowError>;
+    /// All saved searches in sidebar order.
+    fn list_saved_searches(&self) -> Result<Vec<SavedSearch>, FicflowError>;
+    fn get_saved_search_by_id(&self, saved_search_id: u64) -> Result<SavedSearch, FicflowError>;
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::domain::fanfiction::ReadingStatus;
+use crate::domain::fanfiction::{Fanfiction, ReadingStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ShelfKind {
@@ -30,3 +30,50 @@ pub enum Clause {
     Author(String),
     Status(ReadingStatus),
 }
+
+impl AutoShelfCriteria {
+    /// Whether `fic` satisfies these criteria. An empty clause list matches
+    /// no fics, under either AND or OR, so an auto-shelf never accidentally
+    /// includes the whole library before its first clause is added.
+    pub fn matches(&self, fic: &Fanfiction) -> bool {
+        if self.clauses.is_empty() {
+            return false;
+        }
+        match self.logic {
+            ClauseLogic::And => self.clauses.iter().all(|c| c.matches(fic)),
+            ClauseLogic::Or => self.clauses.iter().any(|c| c.matches(fic)),
+        }
+    }
+}
+
+impl Clause {
+    pub fn matches(&self, fic: &Fanfiction) -> bool {
+        match self {
+            Clause::Tag(v) => contains_ci(fic.tags.as_deref(), v),
+            Clause::Fandom(v) => contains_ci(Some(&fic.fandoms), v),
+            Clause::Relationship(v) => contains_ci(fic.relationships.as_deref(), v),
+            Clause::Character(v) => contains_ci(fic.characters.as_deref(), v),
+            Clause::Author(v) => contains_ci(Some(&fic.authors), v),
+            Clause::Status(status) => fic.reading_status == *status,
+        }
+    }
+
+    /// A text clause with nothing to match, which no fic can satisfy.
+    pub fn is_blank(&self) -> bool {
+        match self {
+            Clause::Tag(v)
+            | Clause::Fandom(v)
+            | Clause::Relationship(v)
+            | Clause::Character(v)
+            | Clause::Author(v) => v.trim().is_empty(),
+            Clause::Status(_) => false,
+        }
+    }
+}
+
+fn contains_ci(values: Option<&[String]>, needle: &str) -> bool {
+    values
+        .unwrap_or(&[])
+        .iter()
+        .any(|v| v.eq_ignore_ascii_case(needle))
+}
@@ -14,3 +14,19 @@ pub struct Shelf {
     pub created_at: DateTime<Utc>,
     pub kind: ShelfKind,
 }
+
+/// The names from the top-level shelf down to `shelf`, joined with `/`,
+/// as in "Fandoms/Star Wars". Parents missing from `shelves` end the walk.
+pub fn shelf_path(shelves: &[Shelf], shelf: &Shelf) -> String {
+    let mut names = vec![shelf.name.as_str()];
+    let mut parent_id = shelf.parent_shelf_id;
+    while let Some(parent) = parent_id.and_then(|id| shelves.iter().find(|s| s.id == id)) {
+        names.push(&parent.name);
+        parent_id = parent.parent_shelf_id;
+        if names.len() > shelves.len() {
+            break;
+        }
+    }
+    names.reverse();
+    names.join("/")
+}
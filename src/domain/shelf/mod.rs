@@ -3,5 +3,5 @@ pub mod entity;
 pub mod repository;
 
 pub use auto_criteria::{AutoShelfCriteria, Clause, ClauseLogic, ShelfKind};
-pub use entity::{MAX_SHELF_DEPTH, Shelf};
+pub use entity::{MAX_SHELF_DEPTH, Shelf, shelf_path};
 pub use repository::ShelfOps;
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::fanfiction::Fanfiction;
+use crate::domain::shelf::Shelf;
+use crate::error::FicflowError;
+
+/// Bumped whenever the document layout changes in a way older readers
+/// can't follow. Importers refuse documents from a newer version.
+pub const LIBRARY_SCHEMA_VERSION: u32 = 1;
+
+/// The whole library as one document: every fic and shelf, trashed ones
+/// included, plus the shelf memberships between them. Ids are the ones
+/// the exporting library used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibrarySnapshot {
+    pub schema_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub fics: Vec<ExportedFic>,
+    pub shelves: Vec<ExportedShelf>,
+    pub memberships: Vec<ExportedMembership>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedFic {
+    pub fic: Fanfiction,
+    /// Set when the fic was in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// When the reader's own fields last changed; unknown for fics
+    /// last edited before this was tracked.
+    #[serde(default)]
+    pub user_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedShelf {
+    pub shelf: Shelf,
+    /// Set when the shelf was in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMembership {
+    pub fic_id: u64,
+    pub shelf_id: u64,
+    pub added_at: DateTime<Utc>,
+}
+
+impl LibrarySnapshot {
+    /// The shelves ordered so every parent comes before its children,
+    /// which is the order they can be inserted in. Fails on a parent
+    /// that isn't in the document or on a cycle.
+    pub fn shelves_parents_first(&self) -> Result<Vec<&ExportedShelf>, FicflowError> {
+        let known: HashSet<u64> = self.shelves.iter().map(|s| s.shelf.id).collect();
+        if let Some(orphan) = self.shelves.iter().find(|s| {
+            s.shelf
+                .parent_shelf_id
+                .is_some_and(|parent| !known.contains(&parent))
+        }) {
+            return Err(FicflowError::InvalidInput(format!(
+                "shelf {} refers to parent shelf {}, which is not in the file",
+                orphan.shelf.id,
+                orphan.shelf.parent_shelf_id.unwrap_or_default()
+            )));
+        }
+
+        let mut placed: HashSet<u64> = HashSet::new();
+        let mut ordered = Vec::with_capacity(self.shelves.len());
+        while ordered.len() < self.shelves.len() {
+            let ready: Vec<&ExportedShelf> = self
+                .shelves
+                .iter()
+                .filter(|s| !placed.contains(&s.shelf.id))
+                .filter(|s| s.shelf.parent_shelf_id.is_none_or(|p| placed.contains(&p)))
+                .collect();
+            if ready.is_empty() {
+                return Err(FicflowError::InvalidInput(
+                    "the shelves in the file form a cycle".into(),
+                ));
+            }
+            placed.extend(ready.iter().map(|s| s.shelf.id));
+            ordered.extend(ready);
+        }
+        Ok(ordered)
+    }
+
+    /// Checks what an import relies on: a version this build can read,
+    /// unique ids, and memberships that point at fics and shelves in the
+    /// document.
+    pub fn validate(&self) -> Result<(), FicflowError> {
+        if self.schema_version == 0 || self.schema_version > LIBRARY_SCHEMA_VERSION {
+            return Err(FicflowError::InvalidInput(format!(
+                "unsupported library file version {} (this build reads up to {})",
+                self.schema_version, LIBRARY_SCHEMA_VERSION
+            )));
+        }
+
+        let mut fic_ids = HashSet::new();
+        if let Some(dup) = self.fics.iter().find(|f| !fic_ids.insert(f.fic.id)) {
+            return Err(FicflowError::InvalidInput(format!(
+                "fanfiction {} appears more than once in the file",
+                dup.fic.id
+            )));
+        }
+        let mut shelves: HashMap<u64, &Shelf> = HashMap::new();
+        for exported in &self.shelves {
+            if shelves.insert(exported.shelf.id, &exported.shelf).is_some() {
+                return Err(FicflowError::InvalidInput(format!(
+                    "shelf {} appears more than once in the file",
+                    exported.shelf.id
+                )));
+            }
+        }
+        self.shelves_parents_first()?;
+
+        for membership in &self.memberships {
+            if !fic_ids.contains(&membership.fic_id) {
+                return Err(FicflowError::InvalidInput(format!(
+                    "shelf membership refers to fanfiction {}, which is not in the file",
+                    membership.fic_id
+                )));
+            }
+            if !shelves.contains_key(&membership.shelf_id) {
+                return Err(FicflowError::InvalidInput(format!(
+                    "shelf membership refers to shelf {}, which is not in the file",
+                    membership.shelf_id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The whole library, for `import_library`.
+    Json,
+    /// The fics alone, one row each, for spreadsheets.
+    Csv,
+    Tsv,
+}
+
+impl ExportFormat {
+    /// The field delimiter of the table formats; `None` for JSON.
+    pub fn delimiter(self) -> Option<u8> {
+        match self {
+            ExportFormat::Json => None,
+            ExportFormat::Csv => Some(b','),
+            ExportFormat::Tsv => Some(b'\t'),
+        }
+    }
+}
+
+/// Where `ficflow import` reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// A file written by `export_library`.
+    Library,
+    /// A Calibre library, as FanFicFare fills it.
+    Calibre,
+    /// A bookmarks file in the HTML format every browser exports.
+    Bookmarks,
+    /// A copy of Firefox's `places.sqlite`: its bookmarks and history.
+    Firefox,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Adds what the library doesn't have yet and leaves everything it
+    /// does have alone. Shelves are matched by name under the same
+    /// parent.
+    Merge,
+    /// Drops every fic and shelf first, so the library ends up exactly
+    /// as the file describes it. Saved searches stay; one scoped to a
+    /// shelf follows it to the file's shelf with the same path, or falls
+    /// back to the whole library when the file has none.
+    Replace,
+}
+
+/// What an import did, or would do on a dry run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub fics_added: usize,
+    /// Already in the library; the local copy was kept.
+    pub fics_skipped: usize,
+    pub shelves_added: usize,
+    /// Matched to an existing shelf of the same name and parent.
+    pub shelves_matched: usize,
+    pub memberships_added: usize,
+    /// Replace mode only: what was dropped to make room.
+    pub fics_removed: usize,
+    pub shelves_removed: usize,
+}
@@ -0,0 +1,102 @@
+use serde::Serialize;
+
+use crate::domain::fanfiction::{Fanfiction, ReadingStatus, UserRating};
+use crate::error::FicflowError;
+
+/// Another app's library that fics can be brought over from, such as a
+/// Calibre library kept up to date with FanFicFare.
+pub trait ExternalLibrary {
+    /// Every entry that could be matched to an AO3 work. Entries that
+    /// couldn't are left out.
+    fn read_fics(&self) -> Result<Vec<ExternalFic>, FicflowError>;
+}
+
+/// A work found in another library. `fic` carries whatever metadata that
+/// library had on top of `Fanfiction::placeholder`; AO3 fills in the rest
+/// on the first refresh.
+#[derive(Clone, Debug, Serialize)]
+pub struct ExternalFic {
+    pub fic: Fanfiction,
+    /// The user fields the other library recorded; `None` where it had
+    /// no value.
+    pub reading_status: Option<ReadingStatus>,
+    pub user_rating: Option<UserRating>,
+}
+
+impl ExternalFic {
+    /// The fic to save when it isn't in the library yet.
+    pub fn to_new_fic(&self) -> Fanfiction {
+        self.apply_to(&self.fic)
+    }
+
+    /// True when applying this entry would change `local`'s user fields.
+    pub fn differs_from(&self, local: &Fanfiction) -> bool {
+        self.reading_status
+            .is_some_and(|status| status != local.reading_status)
+            || self
+                .user_rating
+                .is_some_and(|rating| Some(rating) != local.user_rating)
+    }
+
+    /// `local` with the user fields this entry recorded written over it.
+    pub fn apply_to(&self, local: &Fanfiction) -> Fanfiction {
+        let mut fic = local.clone();
+        if let Some(status) = self.reading_status {
+            fic.reading_status = status;
+        }
+        if let Some(rating) = self.user_rating {
+            fic.user_rating = Some(rating);
+        }
+        fic
+    }
+}
+
+/// What to do with a fic that's already in the library when the other
+/// library disagrees about its user fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub enum ConflictChoice {
+    #[default]
+    KeepLocal,
+    UseIncoming,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportConflict {
+    pub local: Fanfiction,
+    pub incoming: ExternalFic,
+    pub choice: ConflictChoice,
+    /// `local` is in the trash. Keeping it leaves it there; taking the
+    /// other library's values restores it, shelves and all.
+    pub trashed: bool,
+}
+
+/// The preview of an import from an external library, before anything is
+/// written. Each conflict carries the choice that will be applied, so a
+/// front end can let the user flip them one by one.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FicImportPlan {
+    /// Not in the library or its trash.
+    pub new: Vec<ExternalFic>,
+    pub conflicts: Vec<ImportConflict>,
+    /// Already in the library with the same user fields.
+    pub unchanged: usize,
+}
+
+impl FicImportPlan {
+    pub fn set_all_choices(&mut self, choice: ConflictChoice) {
+        for conflict in &mut self.conflicts {
+            conflict.choice = choice;
+        }
+    }
+}
+
+/// What applying a `FicImportPlan` did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FicImportSummary {
+    pub added: usize,
+    /// Conflicts resolved in favour of the other library.
+    pub updated: usize,
+    /// Conflicts resolved in favour of the library's own values.
+    pub kept: usize,
+    pub unchanged: usize,
+}
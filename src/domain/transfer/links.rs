@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::fanfiction::{Fanfiction, ReadingStatus};
+use crate::error::FicflowError;
+
+/// Somewhere a browser kept links: a bookmarks export or its history.
+pub trait LinkSource {
+    /// Every link, AO3 or not; picking out the works is up to the caller.
+    fn read_links(&self) -> Result<Vec<BrowserLink>, FicflowError>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrowserLink {
+    pub url: String,
+    /// The bookmark's name or the page title the browser recorded.
+    pub title: Option<String>,
+    /// The bookmark folder the link sat in, innermost only. Browsers'
+    /// own root folders ("Bookmarks Toolbar" and the like) don't count.
+    pub folder: Option<String>,
+    pub last_visited: Option<DateTime<Utc>>,
+}
+
+/// One AO3 work found among the links, with everything the links said
+/// about it merged.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FoundWork {
+    pub fic_id: u64,
+    pub title: Option<String>,
+    /// Suggested shelf.
+    pub folder: Option<String>,
+    pub last_visited: Option<DateTime<Utc>>,
+    /// Ticked on the review screen; only these get added.
+    pub selected: bool,
+}
+
+/// The review screen's contents: works not in the library yet, before
+/// any of them are queued.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LinkImportPlan {
+    pub works: Vec<FoundWork>,
+    /// Works found that the library already has.
+    pub already_tracked: usize,
+    /// Put each added fic on a shelf named after its bookmark folder.
+    pub use_folders: bool,
+}
+
+impl LinkImportPlan {
+    pub fn selected(&self) -> impl Iterator<Item = &FoundWork> {
+        self.works.iter().filter(|w| w.selected)
+    }
+
+    pub fn select_all(&mut self, selected: bool) {
+        for work in &mut self.works {
+            work.selected = selected;
+        }
+    }
+
+    /// The shelves the selected works would go on, once each, in the
+    /// order they first come up. Empty when folders aren't used.
+    pub fn shelf_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        if !self.use_folders {
+            return names;
+        }
+        for folder in self.selected().filter_map(|w| w.folder.as_ref()) {
+            if !names.contains(folder) {
+                names.push(folder.clone());
+            }
+        }
+        names
+    }
+}
+
+/// What a last visit says about how far the reader got, going only by
+/// the fic's own dates. A visit only shows the reader started, so the
+/// hint never goes past `InProgress`. A work in progress visited on or
+/// after its latest update has likely been caught up on; a finished one
+/// could have been left at any chapter, and an older visit means the
+/// fic has moved on since. Returns the status and last chapter read.
+pub fn visit_hint(fic: &Fanfiction, last_visited: DateTime<Utc>) -> (ReadingStatus, Option<u32>) {
+    if last_visited >= fic.date_updated && !fic.complete {
+        (ReadingStatus::InProgress, Some(fic.chapters_published))
+    } else {
+        (ReadingStatus::InProgress, None)
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::entity::ExportedFic;
+use crate::domain::fanfiction::{Fanfiction, UserFields};
+
+/// Whose reading progress, rating, note and tags win when a fic is in
+/// both libraries with different ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Whichever side was edited last; this library on a tie or when
+    /// neither side knows.
+    #[default]
+    NewestWins,
+    KeepMine,
+    KeepTheirs,
+}
+
+impl MergePolicy {
+    pub const ALL: [MergePolicy; 3] = [
+        MergePolicy::NewestWins,
+        MergePolicy::KeepMine,
+        MergePolicy::KeepTheirs,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergePolicy::NewestWins => "Newest wins",
+            MergePolicy::KeepMine => "Keep mine",
+            MergePolicy::KeepTheirs => "Keep theirs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MergeSide {
+    /// The library being merged into.
+    Mine,
+    /// The file being merged in.
+    Theirs,
+}
+
+/// A fic both libraries have, with user fields that disagree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeConflict {
+    pub fic_id: u64,
+    pub title: String,
+    pub mine: UserFields,
+    pub theirs: UserFields,
+    pub kept: MergeSide,
+}
+
+/// What a merge did, or would do on a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MergeReport {
+    /// Only in the other library.
+    pub fics_added: usize,
+    /// In both, and this library's copy changed.
+    pub fics_updated: usize,
+    pub fics_unchanged: usize,
+    pub shelves_added: usize,
+    /// Matched to a shelf of the same name under the same parent.
+    pub shelves_matched: usize,
+    pub memberships_added: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// One fic as it should end up after merging, when it's in both.
+#[derive(Debug, Clone)]
+pub struct MergedFic {
+    pub fic: Fanfiction,
+    pub user_updated_at: Option<DateTime<Utc>>,
+    /// False when the result is this library's copy as it was.
+    pub changed: bool,
+    pub conflict: Option<MergeConflict>,
+}
+
+/// Merges a fic both libraries have. AO3 metadata comes from whichever
+/// copy was checked against AO3 last; user fields follow `policy` when
+/// they disagree. The trash state always stays as this library has it.
+pub fn merge_fic(mine: &ExportedFic, theirs: &ExportedFic, policy: MergePolicy) -> MergedFic {
+    let theirs_newer_metadata = theirs.fic.last_checked_date > mine.fic.last_checked_date;
+    let mine_fields = mine.fic.user_fields();
+    let theirs_fields = theirs.fic.user_fields();
+
+    let conflict = (mine_fields != theirs_fields).then(|| {
+        let kept = match policy {
+            MergePolicy::KeepMine => MergeSide::Mine,
+            MergePolicy::KeepTheirs => MergeSide::Theirs,
+            // `None` sorts first, so an unknown edit time never wins.
+            MergePolicy::NewestWins if theirs.user_updated_at > mine.user_updated_at => {
+                MergeSide::Theirs
+            }
+            MergePolicy::NewestWins => MergeSide::Mine,
+        };
+        MergeConflict {
+            fic_id: mine.fic.id,
+            title: mine.fic.title.clone(),
+            mine: mine_fields,
+            theirs: theirs_fields,
+            kept,
+        }
+    });
+    let take_theirs_fields = conflict
+        .as_ref()
+        .is_some_and(|c| c.kept == MergeSide::Theirs);
+
+    let mut fic = if theirs_newer_metadata {
+        theirs.fic.clone()
+    } else {
+        mine.fic.clone()
+    };
+    let (fields_from, user_updated_at) = if take_theirs_fields {
+        (&theirs.fic, theirs.user_updated_at)
+    } else {
+        (&mine.fic, mine.user_updated_at)
+    };
+    fic.last_chapter_read = fields_from.last_chapter_read;
+    fic.reading_status = fields_from.reading_status;
+    fic.read_count = fields_from.read_count;
+    fic.user_rating = fields_from.user_rating;
+    fic.personal_note = fields_from.personal_note.clone();
+    fic.personal_tags = fields_from.personal_tags.clone();
+
+    MergedFic {
+        fic,
+        user_updated_at,
+        changed: theirs_newer_metadata || take_theirs_fields,
+        conflict,
+    }
+}
@@ -0,0 +1,18 @@
+pub mod entity;
+pub mod external;
+pub mod links;
+pub mod merge;
+pub mod repository;
+pub mod table;
+
+pub use entity::{
+    ExportFormat, ExportedFic, ExportedMembership, ExportedShelf, ImportMode, ImportSource,
+    ImportSummary, LIBRARY_SCHEMA_VERSION, LibrarySnapshot,
+};
+pub use external::{
+    ConflictChoice, ExternalFic, ExternalLibrary, FicImportPlan, FicImportSummary, ImportConflict,
+};
+pub use links::{BrowserLink, FoundWork, LinkImportPlan, LinkSource, visit_hint};
+pub use merge::{MergeConflict, MergePolicy, MergeReport, MergeSide, MergedFic, merge_fic};
+pub use repository::TransferOps;
+pub use table::{TableField, TableOptions};
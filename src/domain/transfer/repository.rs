@@ -0,0 +1,28 @@
+use super::entity::{ImportMode, ImportSummary, LibrarySnapshot};
+use super::merge::{MergePolicy, MergeReport};
+use crate::error::FicflowError;
+
+/// Moving a whole library in and out of storage in one go.
+pub trait TransferOps {
+    /// Everything, trashed fics and shelves included.
+    fn export_library(&self) -> Result<LibrarySnapshot, FicflowError>;
+    /// Applies `snapshot` in a single transaction. With `dry_run` the
+    /// transaction is rolled back, so the summary describes what would
+    /// have happened. Expects a snapshot that passed `validate`.
+    fn import_library(
+        &self,
+        snapshot: &LibrarySnapshot,
+        mode: ImportMode,
+        dry_run: bool,
+    ) -> Result<ImportSummary, FicflowError>;
+
+    /// Folds another library into this one: fics are matched by AO3
+    /// id (see `merge_fic`), shelves by name path. Same transaction and
+    /// `dry_run` rules as `import_library`.
+    fn merge_library(
+        &self,
+        snapshot: &LibrarySnapshot,
+        policy: MergePolicy,
+        dry_run: bool,
+    ) -> Result<MergeReport, FicflowError>;
+}
@@ -0,0 +1,145 @@
+use crate::domain::fanfiction::{ColumnKey, Fanfiction};
+
+/// One column of a CSV/TSV export: a library-table column, or one of the
+/// fic fields the table doesn't show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TableField {
+    Column(ColumnKey),
+    Id,
+    Url,
+    Summary,
+    Tags,
+    Characters,
+    Categories,
+    ChaptersPublished,
+    ChaptersTotal,
+    PersonalNote,
+    PersonalTags,
+    LastChecked,
+}
+
+impl TableField {
+    /// The fields beyond the library table's columns, in the order the
+    /// export dialog lists them.
+    pub const EXTRAS: [TableField; 11] = [
+        TableField::Id,
+        TableField::Url,
+        TableField::Summary,
+        TableField::Tags,
+        TableField::Characters,
+        TableField::Categories,
+        TableField::ChaptersPublished,
+        TableField::ChaptersTotal,
+        TableField::PersonalNote,
+        TableField::PersonalTags,
+        TableField::LastChecked,
+    ];
+
+    /// Every field, table columns first.
+    pub fn all() -> impl Iterator<Item = TableField> {
+        ColumnKey::ALL
+            .into_iter()
+            .map(TableField::Column)
+            .chain(Self::EXTRAS)
+    }
+
+    /// The name the CLI's `--columns` takes.
+    pub fn name(self) -> &'static str {
+        match self {
+            TableField::Column(column) => column.name(),
+            TableField::Id => "id",
+            TableField::Url => "url",
+            TableField::Summary => "summary",
+            TableField::Tags => "tags",
+            TableField::Characters => "characters",
+            TableField::Categories => "categories",
+            TableField::ChaptersPublished => "chapters",
+            TableField::ChaptersTotal => "chapters-total",
+            TableField::PersonalNote => "note",
+            TableField::PersonalTags => "personal-tags",
+            TableField::LastChecked => "last-checked",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TableField> {
+        let name = name.trim().to_lowercase();
+        Self::all().find(|field| field.name() == name)
+    }
+
+    /// The header row's text. Table columns keep the label the library
+    /// table gives them.
+    pub fn label(self) -> &'static str {
+        match self {
+            TableField::Column(column) => column.label(),
+            TableField::Id => "ID",
+            TableField::Url => "URL",
+            TableField::Summary => "Summary",
+            TableField::Tags => "Tags",
+            TableField::Characters => "Characters",
+            TableField::Categories => "Categories",
+            TableField::ChaptersPublished => "Chapters",
+            TableField::ChaptersTotal => "Total Chapters",
+            TableField::PersonalNote => "Note",
+            TableField::PersonalTags => "Your Tags",
+            TableField::LastChecked => "Last Checked",
+        }
+    }
+
+    /// The cell for `fic`. Multi-valued fields are joined with
+    /// `list_separator`; unset ones are empty. Values are plain (no
+    /// thousands separators or display names) so spreadsheets can
+    /// compute on them.
+    pub fn value(self, fic: &Fanfiction, list_separator: &str) -> String {
+        let join = |values: &[String]| values.join(list_separator);
+        let join_display = |values: Vec<String>| values.join(list_separator);
+        let or_empty = |value: Option<u32>| value.map(|v| v.to_string()).unwrap_or_default();
+        match self {
+            TableField::Column(column) => match column {
+                ColumnKey::Title => fic.title.clone(),
+                ColumnKey::Author => join(&fic.authors),
+                ColumnKey::Fandom => join(&fic.fandoms),
+                ColumnKey::Pairing => fic.relationships.as_deref().map(join).unwrap_or_default(),
+                ColumnKey::AO3Rating => fic.rating.to_string(),
+                ColumnKey::Warnings => {
+                    join_display(fic.warnings.iter().map(|w| w.to_string()).collect())
+                }
+                ColumnKey::Status => fic.reading_status.to_string(),
+                ColumnKey::Complete => if fic.complete { "yes" } else { "no" }.to_string(),
+                ColumnKey::LastChapter => or_empty(fic.last_chapter_read),
+                ColumnKey::Words => fic.words.to_string(),
+                ColumnKey::Kudos => fic.kudos.to_string(),
+                ColumnKey::Hits => fic.hits.to_string(),
+                ColumnKey::Rating => or_empty(fic.user_rating.map(|r| r as u32)),
+                ColumnKey::Reads => fic.read_count.to_string(),
+                ColumnKey::Language => fic.language.clone(),
+                ColumnKey::DatePublished => fic.date_published.format("%Y-%m-%d").to_string(),
+                ColumnKey::Updated => fic.date_updated.format("%Y-%m-%d").to_string(),
+            },
+            TableField::Id => fic.id.to_string(),
+            TableField::Url => fic.url(),
+            TableField::Summary => fic.summary.clone(),
+            TableField::Tags => fic.tags.as_deref().map(join).unwrap_or_default(),
+            TableField::Characters => fic.characters.as_deref().map(join).unwrap_or_default(),
+            TableField::Categories => fic
+                .categories
+                .as_ref()
+                .map(|c| join_display(c.iter().map(|c| c.to_string()).collect()))
+                .unwrap_or_default(),
+            TableField::ChaptersPublished => fic.chapters_published.to_string(),
+            TableField::ChaptersTotal => or_empty(fic.chapters_total),
+            TableField::PersonalNote => fic.personal_note.clone().unwrap_or_default(),
+            TableField::PersonalTags => join(&fic.personal_tags),
+            TableField::LastChecked => fic.last_checked_date.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// How a CSV/TSV export is laid out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableOptions {
+    pub fields: Vec<TableField>,
+    /// `b','` for CSV, `b'\t'` for TSV.
+    pub delimiter: u8,
+    /// Goes between the values of a multi-valued field (authors, tags, …).
+    pub list_separator: String,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::fanfiction::Fanfiction;
+use crate::domain::shelf::Shelf;
+
+/// A soft-deleted fic, as it sat in the library when it was deleted.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedFic {
+    pub fic: Fanfiction,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// A soft-deleted shelf. Its fic memberships are kept until it's purged.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedShelf {
+    pub shelf: Shelf,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// How many rows a bulk purge removed for good.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PurgeSummary {
+    pub fics: usize,
+    pub shelves: usize,
+}
+
+impl PurgeSummary {
+    pub fn is_empty(&self) -> bool {
+        self.fics == 0 && self.shelves == 0
+    }
+}
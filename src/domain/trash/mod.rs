@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod repository;
+
+pub use entity::{PurgeSummary, TrashedFic, TrashedShelf};
+pub use repository::TrashOps;
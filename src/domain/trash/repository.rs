@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+
+use super::entity::{PurgeSummary, TrashedFic, TrashedShelf};
+use crate::domain::fanfiction::Fanfiction;
+use crate::domain::shelf::Shelf;
+use crate::error::FicflowError;
+
+/// The trash holds whatever `delete_fanfiction`, `wipe_database` and
+/// `delete_shelf` soft-deleted. Restoring undoes the soft delete; purging
+/// removes the row for good, along with its shelf memberships.
+pub trait TrashOps {
+    /// Most recently deleted first.
+    fn list_trashed_fics(&self) -> Result<Vec<TrashedFic>, FicflowError>;
+    /// Most recently deleted first.
+    fn list_trashed_shelves(&self) -> Result<Vec<TrashedShelf>, FicflowError>;
+    /// Puts a trashed fic back on the shelves it was on.
+    fn restore_fic(&self, fic_id: u64) -> Result<Fanfiction, FicflowError>;
+    /// Puts a trashed shelf back with its fics. It returns under its old
+    /// parent if that shelf is still live and has room, at the top level
+    /// otherwise. Shelves that were promoted out of it on delete stay
+    /// where they are.
+    fn restore_shelf(&self, shelf_id: u64) -> Result<Shelf, FicflowError>;
+    fn purge_fic(&self, fic_id: u64) -> Result<(), FicflowError>;
+    fn purge_shelf(&self, shelf_id: u64) -> Result<(), FicflowError>;
+    /// Purges everything deleted before `deleted_before`, or the whole
+    /// trash when `None`.
+    fn purge_trash(
+        &self,
+        deleted_before: Option<DateTime<Utc>>,
+    ) -> Result<PurgeSummary, FicflowError>;
+}
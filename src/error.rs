@@ -14,12 +14,34 @@ pub enum FicflowError {
     #[error("shelf with ID {shelf_id} not found")]
     ShelfNotFound { shelf_id: u64 },
 
+    /// No fic's title matched what was given in place of an ID.
+    #[error("{0}")]
+    TitleNotFound(String),
+
+    /// Likewise for a shelf name or path.
+    #[error("{0}")]
+    ShelfNameNotFound(String),
+
+    #[error("saved search with ID {saved_search_id} not found")]
+    SavedSearchNotFound { saved_search_id: u64 },
+
+    #[error("fanfiction with ID {fic_id} is not in the trash")]
+    NotInTrash { fic_id: u64 },
+
+    #[error("shelf with ID {shelf_id} is not in the trash")]
+    ShelfNotInTrash { shelf_id: u64 },
+
     #[error("shelf nesting cannot exceed {max} levels")]
     ShelfDepthExceeded { max: u8 },
 
     #[error("cannot move a shelf into itself or one of its descendants")]
     ShelfCycle,
 
+    /// A Ficflow window has the library open, and a write behind its
+    /// back would leave it showing the library as it was.
+    #[error("a Ficflow window has this library open; make the change there, or close it first")]
+    LibraryInUse,
+
     #[error("failed to parse {field}: {reason}")]
     Parse { field: String, reason: String },
 
@@ -41,3 +63,64 @@ pub enum FicflowError {
     #[error("{0}")]
     Other(String),
 }
+
+impl FicflowError {
+    /// A stable, machine-readable name for the kind of error. Scripts
+    /// match on this rather than the message. A fic or shelf that isn't
+    /// found by title or name has the same code as one not found by ID.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FicflowError::Network(_) => "network",
+            FicflowError::NotFound { .. } => "not_found",
+            FicflowError::AlreadyExists { .. } => "already_exists",
+            FicflowError::ShelfNotFound { .. } => "shelf_not_found",
+            FicflowError::TitleNotFound(_) => "not_found",
+            FicflowError::ShelfNameNotFound(_) => "shelf_not_found",
+            FicflowError::SavedSearchNotFound { .. } => "saved_search_not_found",
+            FicflowError::NotInTrash { .. } => "not_in_trash",
+            FicflowError::ShelfNotInTrash { .. } => "shelf_not_in_trash",
+            FicflowError::ShelfDepthExceeded { .. } => "shelf_depth_exceeded",
+            FicflowError::ShelfCycle => "shelf_cycle",
+            FicflowError::LibraryInUse => "library_in_use",
+            FicflowError::Parse { .. } => "parse",
+            FicflowError::Database(_) => "database",
+            FicflowError::Migration(_) => "migration",
+            FicflowError::Serialization(_) => "serialization",
+            FicflowError::Io(_) => "io",
+            FicflowError::InvalidInput(_) => "invalid_input",
+            FicflowError::Other(_) => "other",
+        }
+    }
+
+    /// The process exit status the CLI ends with on this error:
+    ///
+    /// * 1: anything else
+    /// * 2: invalid input, like a usage error
+    /// * 3: the fic, shelf or saved search isn't there (or isn't in the trash)
+    /// * 4: the change conflicts with the library as it is, or a window
+    ///   has it open
+    /// * 5: AO3 couldn't be reached or its page couldn't be read
+    /// * 6: the library file or another file couldn't be read or written
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            FicflowError::InvalidInput(_) => 2,
+            FicflowError::NotFound { .. }
+            | FicflowError::ShelfNotFound { .. }
+            | FicflowError::TitleNotFound(_)
+            | FicflowError::ShelfNameNotFound(_)
+            | FicflowError::SavedSearchNotFound { .. }
+            | FicflowError::NotInTrash { .. }
+            | FicflowError::ShelfNotInTrash { .. } => 3,
+            FicflowError::AlreadyExists { .. }
+            | FicflowError::ShelfDepthExceeded { .. }
+            | FicflowError::ShelfCycle
+            | FicflowError::LibraryInUse => 4,
+            FicflowError::Network(_) | FicflowError::Parse { .. } => 5,
+            FicflowError::Database(_)
+            | FicflowError::Migration(_)
+            | FicflowError::Serialization(_)
+            | FicflowError::Io(_) => 6,
+            FicflowError::Other(_) => 1,
+        }
+    }
+}
@@ -66,9 +66,13 @@ impl Ao3Client {
     }
 
     pub fn fetch_work(&self, fic_id: u64, base_url: &str) -> Result<String, FicflowError> {
+        self.fetch_page(&format!("{}/works/{}", base_url, fic_id))
+    }
+
+    /// Any AO3 page, such as a tag's works listing.
+    pub fn fetch_page(&self, url: &str) -> Result<String, FicflowError> {
         self.throttle();
-        let url = format!("{}/works/{}", base_url, fic_id);
-        let response = self.client.get(&url).send()?.error_for_status()?.text()?;
+        let response = self.client.get(url).send()?.error_for_status()?.text()?;
         Ok(response)
     }
 }
@@ -2,9 +2,10 @@ use std::thread;
 use std::time::Duration;
 
 use chrono::Utc;
-use reqwest::StatusCode;
+use reqwest::{StatusCode, Url};
 use scraper::Html;
 
+use crate::domain::discover::{Ao3Search, BlurbPage, WorkSearcher};
 use crate::domain::fanfiction::{Fanfiction, FanfictionFetcher, ReadingStatus};
 use crate::error::FicflowError;
 use crate::infrastructure::external::ao3::ao3_client::Ao3Client;
@@ -123,6 +124,7 @@ impl Ao3Fetcher {
             read_count: 0,
             user_rating: None,
             personal_note: None,
+            personal_tags: Vec::new(),
             last_checked_date: Utc::now(),
         })
     }
@@ -138,8 +140,57 @@ fn retryable(err: &FicflowError) -> bool {
     }
 }
 
-impl FanfictionFetcher for Ao3Fetcher {
-    fn fetch_fanfiction(&self, fic_id: u64) -> Result<Fanfiction, FicflowError> {
+/// `/tags/<name>/works` for a tag. AO3 spells the characters that would
+/// break its tag URLs as `*s*`-style escapes before percent-encoding.
+fn tag_works_url(base_url: &str, tag: &str) -> Result<Url, FicflowError> {
+    let name = tag
+        .trim()
+        .replace('/', "*s*")
+        .replace('&', "*a*")
+        .replace('.', "*d*")
+        .replace('?', "*q*")
+        .replace('#', "*h*");
+    let mut url = parse_base(base_url)?;
+    url.path_segments_mut()
+        .map_err(|_| invalid_base(base_url))?
+        .pop_if_empty()
+        .extend(["tags", name.as_str(), "works"]);
+    Ok(url)
+}
+
+fn listing_url(base_url: &str, search: &Ao3Search, page: u32) -> Result<Url, FicflowError> {
+    let mut url = match search {
+        Ao3Search::Tag(tag) => tag_works_url(base_url, tag)?,
+        Ao3Search::Text(text) => {
+            let mut url = parse_base(base_url)?;
+            url.path_segments_mut()
+                .map_err(|_| invalid_base(base_url))?
+                .pop_if_empty()
+                .extend(["works", "search"]);
+            url.query_pairs_mut()
+                .append_pair("work_search[query]", text.trim());
+            url
+        }
+    };
+    url.query_pairs_mut().append_pair("page", &page.to_string());
+    Ok(url)
+}
+
+fn parse_base(base_url: &str) -> Result<Url, FicflowError> {
+    Url::parse(base_url).map_err(|_| invalid_base(base_url))
+}
+
+fn invalid_base(base_url: &str) -> FicflowError {
+    FicflowError::InvalidInput(format!("Invalid AO3 address: '{}'", base_url))
+}
+
+impl Ao3Fetcher {
+    /// Runs `attempt` against each URL in turn, cycling through them with
+    /// a growing wait between cycles, until one succeeds or fails for good.
+    fn with_fallback<T>(
+        &self,
+        attempt: impl Fn(&str) -> Result<T, FicflowError>,
+    ) -> Result<T, FicflowError> {
         let mut last_err: Option<FicflowError> = None;
 
         for cycle in 1..=self.max_cycles {
@@ -155,8 +206,8 @@ impl FanfictionFetcher for Ao3Fetcher {
             }
 
             for url in &self.urls {
-                match self.scrape(fic_id, url) {
-                    Ok(fic) => return Ok(fic),
+                match attempt(url) {
+                    Ok(found) => return Ok(found),
                     Err(e) => {
                         if !retryable(&e) {
                             return Err(e);
@@ -176,4 +227,47 @@ impl FanfictionFetcher for Ao3Fetcher {
 
         Err(last_err.unwrap_or_else(|| FicflowError::Other("Fetch failed after retries".into())))
     }
+
+    fn scrape_listing(
+        &self,
+        search: &Ao3Search,
+        page: u32,
+        base_url: &str,
+    ) -> Result<BlurbPage, FicflowError> {
+        let url = listing_url(base_url, search, page)?;
+        let response = self
+            .client
+            .fetch_page(url.as_str())
+            .map_err(|e| match (e, search) {
+                // AO3 only knows tags by their exact (canonical or synonym) name.
+                (FicflowError::Network(e), Ao3Search::Tag(tag))
+                    if e.status() == Some(StatusCode::NOT_FOUND) =>
+                {
+                    FicflowError::InvalidInput(format!("AO3 has no tag named '{}'", tag.trim()))
+                }
+                (e, _) => e,
+            })?;
+        let document = Html::parse_document(&response);
+
+        let blurbs = self.parser.extract_blurbs(&document)?;
+        let page_count = self.parser.extract_page_count(&document).max(page);
+        Ok(BlurbPage {
+            blurbs,
+            page,
+            page_count,
+            hidden: 0,
+        })
+    }
+}
+
+impl FanfictionFetcher for Ao3Fetcher {
+    fn fetch_fanfiction(&self, fic_id: u64) -> Result<Fanfiction, FicflowError> {
+        self.with_fallback(|url| self.scrape(fic_id, url))
+    }
+}
+
+impl WorkSearcher for Ao3Fetcher {
+    fn search_works(&self, search: &Ao3Search, page: u32) -> Result<BlurbPage, FicflowError> {
+        self.with_fallback(|url| self.scrape_listing(search, page, url))
+    }
 }
@@ -1,7 +1,8 @@
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
+use crate::domain::discover::WorkBlurb;
 use crate::domain::fanfiction::{ArchiveWarnings, Categories, Rating};
 use crate::error::FicflowError;
 
@@ -312,6 +313,92 @@ impl Ao3Parser {
 
         Ok(restricted)
     }
+
+    /// The work blurbs on a tag or search listing page. Blurbs without a
+    /// work id, such as mystery works hidden in a collection, are skipped.
+    pub fn extract_blurbs(&self, document: &Html) -> Result<Vec<WorkBlurb>, FicflowError> {
+        let blurb_selector = parse_selector("li.work.blurb");
+        let blurbs: Vec<WorkBlurb> = document
+            .select(&blurb_selector)
+            .filter_map(|blurb| self.parse_blurb(blurb))
+            .collect();
+
+        // A listing with nothing on it still has its "0 Works in ..."
+        // heading; a page without one isn't a listing at all.
+        if blurbs.is_empty()
+            && document
+                .select(&parse_selector("h2.heading"))
+                .next()
+                .is_none()
+        {
+            return Err(missing("work listing"));
+        }
+
+        Ok(blurbs)
+    }
+
+    fn parse_blurb(&self, blurb: ElementRef<'_>) -> Option<WorkBlurb> {
+        let id = blurb
+            .value()
+            .attr("id")?
+            .strip_prefix("work_")?
+            .parse::<u64>()
+            .ok()?;
+
+        let title = blurb
+            .select(&parse_selector("h4.heading a"))
+            .find(|a| a.value().attr("rel") != Some("author"))
+            .map(|a| a.text().collect::<String>().trim().to_string())?;
+
+        let mut authors: Vec<String> = blurb
+            .select(&parse_selector("h4.heading a[rel=\"author\"]"))
+            .map(|a| a.text().collect::<String>().trim().to_string())
+            .collect();
+        if authors.is_empty() {
+            authors.push("Anonymous".to_string());
+        }
+
+        let fandoms = blurb
+            .select(&parse_selector("h5.fandoms a.tag"))
+            .map(|a| a.text().collect::<String>().trim().to_string())
+            .collect();
+
+        let stat = |class: &str| {
+            blurb
+                .select(&parse_selector(&format!("dd.{}", class)))
+                .next()
+                .map(|element| element.text().collect::<String>().trim().replace(",", ""))
+        };
+        let number = |class: &str| stat(class).and_then(|text| text.parse::<u32>().ok());
+
+        let chapters = stat("chapters").unwrap_or_default();
+        let mut chapters_iter = chapters.split('/').map(|s| s.parse::<u32>().ok());
+        let chapters_published = chapters_iter.next().flatten().unwrap_or(0);
+        let chapters_total = chapters_iter.next().flatten().filter(|&total| total > 0);
+
+        Some(WorkBlurb {
+            id,
+            title,
+            authors,
+            fandoms,
+            words: number("words").unwrap_or(0),
+            chapters_published,
+            chapters_total,
+            kudos: number("kudos").unwrap_or(0),
+            tracked: false,
+        })
+    }
+
+    /// How many pages the listing runs to, read off its pagination links.
+    /// A listing short enough to fit one page has none.
+    pub fn extract_page_count(&self, document: &Html) -> u32 {
+        let selector = parse_selector("ol.pagination li");
+        document
+            .select(&selector)
+            .filter_map(|li| li.text().collect::<String>().trim().parse::<u32>().ok())
+            .max()
+            .unwrap_or(1)
+    }
 }
 
 fn map_category(category_text: &str) -> Option<Categories> {
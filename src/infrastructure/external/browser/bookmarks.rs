@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::DateTime;
+use scraper::{ElementRef, Html, Selector};
+
+use super::tidy_title;
+use crate::domain::transfer::{BrowserLink, LinkSource};
+use crate::error::FicflowError;
+
+/// A bookmarks file in the Netscape HTML format that Firefox, Chrome,
+/// Safari and Edge all export.
+pub struct BookmarksFile {
+    html: String,
+}
+
+impl BookmarksFile {
+    pub fn open(path: &Path) -> Result<Self, FicflowError> {
+        let html = fs::read_to_string(path)?;
+        if !html.contains("NETSCAPE-Bookmark-file") && !html.to_lowercase().contains("<dl") {
+            return Err(FicflowError::InvalidInput(format!(
+                "{} is not a bookmarks export",
+                path.display()
+            )));
+        }
+        Ok(Self { html })
+    }
+}
+
+impl LinkSource for BookmarksFile {
+    fn read_links(&self) -> Result<Vec<BrowserLink>, FicflowError> {
+        let document = Html::parse_document(&self.html);
+        let anchors = Selector::parse("a[href]").unwrap();
+        let links = document
+            .select(&anchors)
+            .map(|a| BrowserLink {
+                url: a.value().attr("href").unwrap_or_default().to_string(),
+                title: tidy_title(&a.text().collect::<String>()),
+                folder: folder_of(a),
+                // Only some browsers write LAST_VISIT, in Unix seconds.
+                last_visited: a
+                    .value()
+                    .attr("last_visit")
+                    .and_then(|secs| secs.trim().parse().ok())
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+            })
+            .collect();
+        Ok(links)
+    }
+}
+
+/// The name of the folder holding a bookmark. Each folder is an `<H3>`
+/// followed by a `<DL>` of its entries; an HTML parser puts that list
+/// inside the heading's `<DT>`, or next to it when the `<DT>` was closed.
+fn folder_of(anchor: ElementRef) -> Option<String> {
+    let list = anchor
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().name() == "dl")?;
+    let from_parent = list
+        .parent()
+        .and_then(ElementRef::wrap)
+        .filter(|e| e.value().name() == "dt")
+        .and_then(heading_in);
+    let heading = from_parent.or_else(|| {
+        list.prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .next()
+            .and_then(|e| {
+                if e.value().name() == "h3" {
+                    Some(e)
+                } else {
+                    heading_in(e)
+                }
+            })
+    })?;
+    // The toolbar and "Other Bookmarks" are the browser's, not the
+    // reader's.
+    let attrs = heading.value();
+    if attrs.attr("personal_toolbar_folder").is_some()
+        || attrs.attr("unfiled_bookmarks_folder").is_some()
+    {
+        return None;
+    }
+    let name = heading.text().collect::<String>().trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+fn heading_in(element: ElementRef) -> Option<ElementRef> {
+    element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().name() == "h3")
+}
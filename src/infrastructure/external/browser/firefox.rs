@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use chrono::DateTime;
+use rusqlite::{Connection, OpenFlags};
+
+use super::tidy_title;
+use crate::domain::transfer::{BrowserLink, LinkSource};
+use crate::error::FicflowError;
+
+/// Firefox's own folders, which every profile has and which say nothing
+/// about the links in them.
+const ROOT_FOLDERS: [&str; 5] = [
+    "root________",
+    "menu________",
+    "toolbar_____",
+    "unfiled_____",
+    "mobile______",
+];
+
+/// A copy of a Firefox profile's `places.sqlite`, which holds both its
+/// bookmarks and its history. Firefox keeps the live file locked, hence
+/// a copy.
+pub struct FirefoxPlaces {
+    conn: Connection,
+}
+
+impl FirefoxPlaces {
+    pub fn open(path: &Path) -> Result<Self, FicflowError> {
+        if !path.is_file() {
+            return Err(FicflowError::InvalidInput(format!(
+                "no Firefox places database at {}",
+                path.display()
+            )));
+        }
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let is_places: bool = conn.query_row(
+            "SELECT COUNT(*) = 2 FROM sqlite_master
+             WHERE type = 'table' AND name IN ('moz_places', 'moz_bookmarks')",
+            [],
+            |r| r.get(0),
+        )?;
+        if !is_places {
+            return Err(FicflowError::InvalidInput(format!(
+                "{} is not a Firefox places database",
+                path.display()
+            )));
+        }
+        Ok(Self { conn })
+    }
+}
+
+impl LinkSource for FirefoxPlaces {
+    /// Bookmarks first, so a work's bookmark name and folder win over
+    /// its page title in the history. Only URLs that could be a work
+    /// are read; the history of a long-used profile is large.
+    fn read_links(&self) -> Result<Vec<BrowserLink>, FicflowError> {
+        // Tagging a bookmark files a second copy of it under a folder
+        // named after the tag, inside the tags root; those are skipped.
+        let mut bookmarks = self.conn.prepare(
+            "SELECT p.url, b.title, f.title, f.guid, p.last_visit_date
+             FROM moz_bookmarks b
+             JOIN moz_places p ON p.id = b.fk
+             LEFT JOIN moz_bookmarks f ON f.id = b.parent
+             LEFT JOIN moz_bookmarks g ON g.id = f.parent
+             WHERE b.type = 1 AND p.url LIKE '%/works/%'
+               AND (g.guid IS NULL OR g.guid != 'tags________')
+             ORDER BY b.id",
+        )?;
+        let mut links = bookmarks
+            .query_map([], |r| {
+                let folder: Option<String> = r.get(2)?;
+                let guid: Option<String> = r.get(3)?;
+                let is_root = guid.is_some_and(|g| ROOT_FOLDERS.contains(&g.as_str()));
+                Ok(BrowserLink {
+                    url: r.get(0)?,
+                    title: r
+                        .get::<_, Option<String>>(1)?
+                        .as_deref()
+                        .and_then(tidy_title),
+                    folder: folder.filter(|f| !is_root && !f.trim().is_empty()),
+                    last_visited: visit_time(r.get(4)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut history = self.conn.prepare(
+            "SELECT url, title, last_visit_date FROM moz_places
+             WHERE url LIKE '%/works/%' AND last_visit_date IS NOT NULL
+             ORDER BY last_visit_date DESC",
+        )?;
+        let visited = history.query_map([], |r| {
+            Ok(BrowserLink {
+                url: r.get(0)?,
+                title: r
+                    .get::<_, Option<String>>(1)?
+                    .as_deref()
+                    .and_then(tidy_title),
+                folder: None,
+                last_visited: visit_time(r.get(2)?),
+            })
+        })?;
+        for link in visited {
+            links.push(link?);
+        }
+        Ok(links)
+    }
+}
+
+/// Firefox records visits in microseconds since the Unix epoch.
+fn visit_time(micros: Option<i64>) -> Option<DateTime<chrono::Utc>> {
+    micros.and_then(DateTime::from_timestamp_micros)
+}
@@ -0,0 +1,16 @@
+pub mod bookmarks;
+pub mod firefox;
+
+pub use bookmarks::BookmarksFile;
+pub use firefox::FirefoxPlaces;
+
+/// Drops the suffix AO3 puts on every page title, which browsers keep as
+/// the bookmark name and the history entry's title.
+fn tidy_title(title: &str) -> Option<String> {
+    let title = title.trim();
+    let title = title
+        .strip_suffix("[Archive of Our Own]")
+        .unwrap_or(title)
+        .trim_end();
+    (!title.is_empty()).then(|| title.to_string())
+}
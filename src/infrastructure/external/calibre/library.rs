@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use regex::Regex;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags, params};
+use scraper::Html;
+
+use crate::domain::fanfiction::{Fanfiction, ReadingStatus, UserRating};
+use crate::domain::transfer::{ExternalFic, ExternalLibrary};
+use crate::error::FicflowError;
+
+/// The custom columns that hold the reader's own data. Calibre has no
+/// built-in "read" flag, so it's whatever column the user made for it.
+#[derive(Clone, Debug, Default)]
+pub struct CalibreColumns {
+    /// Lookup name of the read column, with or without the `#`. `None`
+    /// uses `#read` when the library has one.
+    pub read: Option<String>,
+    /// Lookup name of a rating column. `None` uses Calibre's built-in
+    /// rating.
+    pub rating: Option<String>,
+    /// Lookup name of a column holding the story URL, for libraries that
+    /// keep it outside the identifiers.
+    pub url: Option<String>,
+}
+
+/// A Calibre library, opened read-only so Calibre can stay open.
+pub struct CalibreLibrary {
+    conn: Connection,
+    columns: CalibreColumns,
+}
+
+/// One custom column's values by book id, plus Calibre's type name for
+/// it (`bool`, `rating`, `text`, …), which decides how they're read.
+struct ColumnValues {
+    datatype: String,
+    values: HashMap<i64, Value>,
+}
+
+impl CalibreLibrary {
+    /// `path` is `metadata.db` itself or the library folder holding it.
+    pub fn open(path: &Path, columns: CalibreColumns) -> Result<Self, FicflowError> {
+        let db_path: PathBuf = if path.is_dir() {
+            path.join("metadata.db")
+        } else {
+            path.to_path_buf()
+        };
+        if !db_path.is_file() {
+            return Err(FicflowError::InvalidInput(format!(
+                "no Calibre library at {}",
+                db_path.display()
+            )));
+        }
+        let conn = Connection::open_with_flags(
+            &db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let is_calibre: bool = conn.query_row(
+            "SELECT COUNT(*) = 2 FROM sqlite_master
+             WHERE type = 'table' AND name IN ('books', 'identifiers')",
+            [],
+            |r| r.get(0),
+        )?;
+        if !is_calibre {
+            return Err(FicflowError::InvalidInput(format!(
+                "{} is not a Calibre library",
+                db_path.display()
+            )));
+        }
+        Ok(Self { conn, columns })
+    }
+
+    /// Every value of a custom column. Calibre keeps normalized columns
+    /// (text, series, enumeration, rating) in a value table plus a link
+    /// table, and the rest directly against the book.
+    fn custom_column(&self, lookup_name: &str) -> Result<Option<ColumnValues>, FicflowError> {
+        let label = lookup_name.trim().trim_start_matches('#');
+        let column = self.conn.query_row(
+            "SELECT id, datatype, normalized FROM custom_columns WHERE label = ?1",
+            params![label],
+            |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, bool>(2)?,
+                ))
+            },
+        );
+        let (id, datatype, normalized) = match column {
+            Ok(column) => column,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let sql = if normalized {
+            format!(
+                "SELECT l.book, v.value FROM books_custom_column_{id}_link l
+                 JOIN custom_column_{id} v ON v.id = l.value ORDER BY l.id"
+            )
+        } else {
+            format!("SELECT book, value FROM custom_column_{id}")
+        };
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut values = HashMap::new();
+        for row in stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, Value>(1)?)))? {
+            let (book, value) = row?;
+            // A multi-valued column keeps its first value.
+            values.entry(book).or_insert(value);
+        }
+        Ok(Some(ColumnValues { datatype, values }))
+    }
+
+    /// Looks up a column the user named, which has to exist.
+    fn named_column(&self, lookup_name: &str) -> Result<ColumnValues, FicflowError> {
+        self.custom_column(lookup_name)?.ok_or_else(|| {
+            FicflowError::InvalidInput(format!(
+                "the Calibre library has no custom column '#{}'",
+                lookup_name.trim().trim_start_matches('#')
+            ))
+        })
+    }
+
+    /// Values of a two-column `(book, text)` query, grouped by book in
+    /// the order returned.
+    fn grouped_strings(&self, sql: &str) -> Result<HashMap<i64, Vec<String>>, FicflowError> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let mut grouped: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))? {
+            let (book, value) = row?;
+            grouped.entry(book).or_default().push(value);
+        }
+        Ok(grouped)
+    }
+
+    fn builtin_ratings(&self) -> Result<ColumnValues, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT l.book, r.rating FROM books_ratings_link l JOIN ratings r ON r.id = l.rating",
+        )?;
+        let values = stmt
+            .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, Value>(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(ColumnValues {
+            datatype: "rating".into(),
+            values,
+        })
+    }
+}
+
+impl ExternalLibrary for CalibreLibrary {
+    fn read_fics(&self) -> Result<Vec<ExternalFic>, FicflowError> {
+        let read = match &self.columns.read {
+            Some(name) => Some(self.named_column(name)?),
+            None => self.custom_column("read")?,
+        };
+        let rating = match &self.columns.rating {
+            Some(name) => self.named_column(name)?,
+            None => self.builtin_ratings()?,
+        };
+        let url = match &self.columns.url {
+            Some(name) => Some(self.named_column(name)?),
+            None => None,
+        };
+        let identifiers =
+            self.grouped_strings("SELECT book, type || ':' || val FROM identifiers ORDER BY id")?;
+        let authors = self.grouped_strings(
+            "SELECT l.book, a.name FROM books_authors_link l
+             JOIN authors a ON a.id = l.author ORDER BY l.id",
+        )?;
+        let tags = self.grouped_strings(
+            "SELECT l.book, t.name FROM books_tags_link l JOIN tags t ON t.id = l.tag ORDER BY l.id",
+        )?;
+        let comments = self.grouped_strings("SELECT book, text FROM comments")?;
+        let work_url = Regex::new(r"archiveofourown\.org/works/(\d+)").unwrap();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, title, pubdate FROM books ORDER BY id")?;
+        let books = stmt
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut fics = Vec::new();
+        for (book, title, pubdate) in books {
+            let links = identifiers.get(&book).into_iter().flatten().cloned();
+            let column_url = url
+                .as_ref()
+                .and_then(|c| c.values.get(&book))
+                .and_then(value_text);
+            let Some(fic_id) = links
+                .chain(column_url)
+                .find_map(|link| ao3_work_id(&link, &work_url))
+            else {
+                continue;
+            };
+
+            let mut fic = Fanfiction::placeholder(fic_id, title);
+            fic.authors = authors.get(&book).cloned().unwrap_or_default();
+            fic.tags = tags.get(&book).cloned();
+            if let Some(text) = comments.get(&book).and_then(|c| c.first()) {
+                fic.summary = html_to_text(text);
+            }
+            if let Some(published) = pubdate.as_deref().and_then(parse_calibre_date) {
+                fic.date_published = published;
+                fic.date_updated = published;
+            }
+
+            let reading_status = read.as_ref().and_then(|c| {
+                c.values
+                    .get(&book)
+                    .and_then(|v| read_status(&c.datatype, v))
+            });
+            let user_rating = rating
+                .values
+                .get(&book)
+                .and_then(|v| stars(&rating.datatype, v));
+            fics.push(ExternalFic {
+                fic,
+                reading_status,
+                user_rating,
+            });
+        }
+        Ok(fics)
+    }
+}
+
+/// The AO3 work id in an identifier (`type:value`, e.g.
+/// `url:https://archiveofourown.org/works/123` as FanFicFare writes it,
+/// or `ao3:123`) or a bare URL.
+fn ao3_work_id(link: &str, work_url: &Regex) -> Option<u64> {
+    if let Some(id) = link.strip_prefix("ao3:") {
+        return id.trim().parse().ok();
+    }
+    work_url
+        .captures(link)
+        .and_then(|c| c.get(1))
+        .and_then(|id| id.as_str().parse().ok())
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Text(text) => Some(text.clone()),
+        Value::Integer(n) => Some(n.to_string()),
+        Value::Real(n) => Some(n.to_string()),
+        Value::Null | Value::Blob(_) => None,
+    }
+}
+
+/// A read column is usually a yes/no, which only says anything when it's
+/// yes; text and enumeration columns are matched against the usual
+/// shelf names.
+fn read_status(datatype: &str, value: &Value) -> Option<ReadingStatus> {
+    match (datatype, value) {
+        ("bool" | "int", Value::Integer(n)) => (*n != 0).then_some(ReadingStatus::Read),
+        (_, Value::Text(text)) => match text.trim().to_lowercase().as_str() {
+            "read" | "yes" | "true" | "finished" | "done" => Some(ReadingStatus::Read),
+            "reading" | "in progress" | "currently reading" => Some(ReadingStatus::InProgress),
+            "to read" | "plan to read" | "unread" | "tbr" => Some(ReadingStatus::PlanToRead),
+            "paused" | "on hold" => Some(ReadingStatus::Paused),
+            "abandoned" | "dropped" | "dnf" => Some(ReadingStatus::Abandoned),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Calibre ratings go from 0 to 10 in half stars; a plain number column
+/// is taken as stars already. Zero means unrated.
+fn stars(datatype: &str, value: &Value) -> Option<UserRating> {
+    let raw = match value {
+        Value::Integer(n) => *n as f64,
+        Value::Real(n) => *n,
+        _ => return None,
+    };
+    let stars = if datatype == "rating" {
+        (raw / 2.0).ceil()
+    } else {
+        raw.round()
+    };
+    match stars as i64 {
+        i64::MIN..=0 => None,
+        1 => Some(UserRating::One),
+        2 => Some(UserRating::Two),
+        3 => Some(UserRating::Three),
+        4 => Some(UserRating::Four),
+        _ => Some(UserRating::Five),
+    }
+}
+
+/// Calibre writes `2021-03-04 05:06:07.123456+00:00`, and a year-101 date
+/// for "unknown".
+fn parse_calibre_date(text: &str) -> Option<DateTime<Utc>> {
+    let parsed = DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%:z")
+        .or_else(|_| DateTime::parse_from_rfc3339(text))
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").map(|d| d.and_utc())
+        })
+        .ok()?;
+    (parsed.year() >= 1000).then_some(parsed)
+}
+
+/// FanFicFare stores the summary as HTML in Calibre's comments.
+fn html_to_text(html: &str) -> String {
+    let spaced = html.replace("</p>", "</p>\n").replace("<br>", "\n");
+    let fragment = Html::parse_fragment(&spaced);
+    let text: String = fragment.root_element().text().collect();
+    text.trim().to_string()
+}
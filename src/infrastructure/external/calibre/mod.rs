@@ -0,0 +1,3 @@
+pub mod library;
+
+pub use library::{CalibreColumns, CalibreLibrary};
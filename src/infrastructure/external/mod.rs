@@ -1,3 +1,9 @@
 pub mod ao3;
+pub mod browser;
+pub mod calibre;
+pub mod opener;
 
 pub use ao3::fetcher::Ao3Fetcher;
+pub use browser::{BookmarksFile, FirefoxPlaces};
+pub use calibre::{CalibreColumns, CalibreLibrary};
+pub use opener::CommandOpener;
@@ -0,0 +1,57 @@
+use std::process::Command;
+use std::thread;
+
+use crate::domain::fanfiction::LinkOpener;
+use crate::error::FicflowError;
+
+/// Opens links by running a command with the URL as its last argument:
+/// the configured one (split on whitespace, e.g. `firefox --new-tab`), or
+/// the platform's own opener when none is set.
+pub struct CommandOpener {
+    command: Option<String>,
+}
+
+impl CommandOpener {
+    pub fn new(command: Option<String>) -> Self {
+        Self {
+            command: command.filter(|c| !c.trim().is_empty()),
+        }
+    }
+
+    fn command_line(&self) -> Vec<String> {
+        match &self.command {
+            Some(command) => command.split_whitespace().map(str::to_string).collect(),
+            None => system_opener(),
+        }
+    }
+}
+
+fn system_opener() -> Vec<String> {
+    let parts: &[&str] = if cfg!(target_os = "macos") {
+        &["open"]
+    } else if cfg!(windows) {
+        // `start`'s first quoted argument is the window title.
+        &["cmd", "/C", "start", ""]
+    } else {
+        &["xdg-open"]
+    };
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+impl LinkOpener for CommandOpener {
+    /// Doesn't wait for the command, which may be a browser that runs
+    /// until it's closed; a thread reaps it when it exits.
+    fn open_link(&self, url: &str) -> Result<(), FicflowError> {
+        let command_line = self.command_line();
+        let (program, args) = command_line
+            .split_first()
+            .expect("an opener command is never empty");
+        let mut child = Command::new(program)
+            .args(args)
+            .arg(url)
+            .spawn()
+            .map_err(|e| FicflowError::Other(format!("Couldn't run '{}': {}", program, e)))?;
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
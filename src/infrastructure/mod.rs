@@ -2,5 +2,11 @@ pub mod external;
 pub mod persistence;
 
 pub use external::ao3::Ao3Fetcher;
-pub use persistence::database::{open_configured_db, relocate_library, restore_backup};
+pub use external::browser::{BookmarksFile, FirefoxPlaces};
+pub use external::calibre::{CalibreColumns, CalibreLibrary};
+pub use external::opener::CommandOpener;
+pub use persistence::database::{
+    SqliteBackups, backups_dir, open_configured_db, open_read_only_db, read_library_file,
+    relocate_library, restore_backup,
+};
 pub use persistence::repository::sqlite_repository::SqliteRepository;
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{Connection, MAIN_DB};
+
+use crate::domain::backup::{BackupInfo, BackupOps, BackupReason};
+use crate::error::FicflowError;
+
+const DIR_NAME: &str = "backups";
+const FILE_PREFIX: &str = "ficflow-";
+// Milliseconds keep two backups taken in the same second apart, e.g.
+// the daily one and a pre-wipe one at the start of a CLI run.
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// Backups of the library at `db_path`, written with SQLite's online
+/// backup API into a `backups/` folder next to it.
+pub struct SqliteBackups<'a> {
+    conn: &'a Connection,
+    db_path: PathBuf,
+}
+
+impl<'a> SqliteBackups<'a> {
+    pub fn new(conn: &'a Connection, db_path: impl Into<PathBuf>) -> Self {
+        Self {
+            conn,
+            db_path: db_path.into(),
+        }
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        backups_dir(&self.db_path)
+    }
+}
+
+/// Where the backups of the library at `db_path` go.
+pub fn backups_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(DIR_NAME)
+}
+
+impl BackupOps for SqliteBackups<'_> {
+    fn create_backup(&self, reason: BackupReason) -> Result<BackupInfo, FicflowError> {
+        let dir = self.dir();
+        fs::create_dir_all(&dir)?;
+        let created_at = Utc::now();
+        let path = dir.join(format!(
+            "{}{}-{}.db",
+            FILE_PREFIX,
+            created_at.format(STAMP_FORMAT),
+            reason.as_str()
+        ));
+        self.conn.backup(MAIN_DB, &path, None)?;
+        let size_bytes = fs::metadata(&path)?.len();
+        Ok(BackupInfo {
+            path,
+            // Read back the way `list_backups` will see it.
+            created_at: parse_stamp(&created_at.format(STAMP_FORMAT).to_string())
+                .unwrap_or(created_at),
+            size_bytes,
+            reason,
+        })
+    }
+
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, FicflowError> {
+        let dir = self.dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            // Anything else in the folder isn't ours to list or prune.
+            let Some((created_at, reason)) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(parse_file_name)
+            else {
+                continue;
+            };
+            backups.push(BackupInfo {
+                size_bytes: entry.metadata()?.len(),
+                path,
+                created_at,
+                reason,
+            });
+        }
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        Ok(backups)
+    }
+
+    fn delete_backup(&self, path: &Path) -> Result<(), FicflowError> {
+        let ours = path.parent() == Some(self.dir().as_path())
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(parse_file_name)
+                .is_some();
+        if !ours {
+            return Err(FicflowError::InvalidInput(format!(
+                "not a ficflow backup: {}",
+                path.display()
+            )));
+        }
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
+
+/// `ficflow-20261018-153012-123-daily.db` → its time and reason.
+fn parse_file_name(name: &str) -> Option<(DateTime<Utc>, BackupReason)> {
+    let rest = name.strip_prefix(FILE_PREFIX)?.strip_suffix(".db")?;
+    // The stamp is fixed-width: 8 + 1 + 6 + 1 + 3.
+    let (stamp, reason) = rest.split_at_checked(19)?;
+    let reason = BackupReason::parse(reason.strip_prefix('-')?)?;
+    Some((parse_stamp(stamp)?, reason))
+}
+
+fn parse_stamp(stamp: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
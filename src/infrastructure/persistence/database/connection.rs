@@ -1,6 +1,13 @@
+use crate::domain::backup::{BackupOps, BackupReason};
+use crate::domain::transfer::{LibrarySnapshot, TransferOps};
 use crate::error::FicflowError;
-use crate::infrastructure::persistence::database::migration::run_migrations;
-use rusqlite::Connection;
+use crate::infrastructure::persistence::database::backup::SqliteBackups;
+use crate::infrastructure::persistence::database::migration::{
+    is_up_to_date, needs_upgrade, run_migrations,
+};
+use crate::infrastructure::persistence::repository::sqlite_repository::SqliteRepository;
+use chrono::Utc;
+use rusqlite::{Connection, OpenFlags};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -15,12 +22,16 @@ const SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
 // thread's reads and the task-worker thread's writes proceed concurrently —
 // without it, the worker can busy-wait for several seconds while the GUI keeps
 // grabbing SHARED locks during render(), which manifests as tasks stuck on the
-// `Running` state.
+// `Running` state. A library from an older build is backed up before it's
+// upgraded, since an upgrade can't be undone.
 pub fn open_configured_db(path: &Path) -> Result<Connection, FicflowError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
     let mut conn = Connection::open(path)?;
+    if needs_upgrade(&conn)? {
+        SqliteBackups::new(&conn, path).create_backup(BackupReason::Migration)?;
+    }
     run_migrations(&mut conn)?;
     conn.execute_batch(
         "PRAGMA journal_mode = WAL;\
@@ -29,6 +40,17 @@ pub fn open_configured_db(path: &Path) -> Result<Connection, FicflowError> {
     Ok(conn)
 }
 
+/// Opens the library at `path` for reading alone: nothing is created,
+/// migrated or backed up. `None` when its schema isn't the current one,
+/// since only `open_configured_db` may upgrade it.
+pub fn open_read_only_db(path: &Path) -> Result<Option<Connection>, FicflowError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    if !is_up_to_date(&conn)? {
+        return Ok(None);
+    }
+    Ok(Some(conn))
+}
+
 /// Moves the library file (and its `-wal`/`-shm` sidecars) from `from` to `to`.
 /// On Linux a plain rename keeps a live connection's open descriptors valid
 /// through the next restart; if the destination is on another filesystem the
@@ -64,6 +86,63 @@ pub fn restore_backup(backup: &Path, current: &Path) -> Result<(), FicflowError>
     Ok(())
 }
 
+/// Reads another library file whole, for merging into this one. The
+/// file may be from an older build, so it's migrated — but on a copy in
+/// the temp directory, leaving the original (and any app that has it
+/// open) alone.
+pub fn read_library_file(path: &Path) -> Result<LibrarySnapshot, FicflowError> {
+    // Checked on the original, read-only: migrating any other SQLite
+    // file would succeed and read back as an empty library.
+    let probe = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let is_library = probe
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'fanfiction'",
+            [],
+            |r| r.get::<_, bool>(0),
+        )
+        .unwrap_or(false);
+    drop(probe);
+    if !is_library {
+        return Err(FicflowError::InvalidInput(format!(
+            "not a ficflow library: {}",
+            path.display()
+        )));
+    }
+
+    let copy = std::env::temp_dir().join(format!(
+        "ficflow-merge-{}-{}.db",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    // Migrated directly rather than through `open_configured_db`, whose
+    // pre-upgrade backup would leave a copy of the other library behind
+    // in the temp directory.
+    let snapshot = copy_with_sidecars(path, &copy).and_then(|()| {
+        let mut conn = Connection::open(&copy)?;
+        run_migrations(&mut conn)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        SqliteRepository::new(&conn).export_library()
+    });
+    let _ = fs::remove_file(&copy);
+    for suffix in SIDECAR_SUFFIXES {
+        let _ = fs::remove_file(sidecar(&copy, suffix));
+    }
+    snapshot
+}
+
+// A `-wal` left by an app that still has the file open holds committed
+// changes, so it comes along with the copy.
+fn copy_with_sidecars(from: &Path, to: &Path) -> Result<(), FicflowError> {
+    fs::copy(from, to)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let from_sidecar = sidecar(from, suffix);
+        if from_sidecar.exists() {
+            fs::copy(&from_sidecar, sidecar(to, suffix))?;
+        }
+    }
+    Ok(())
+}
+
 fn move_file(from: &Path, to: &Path) -> Result<(), FicflowError> {
     match fs::rename(from, to) {
         Ok(()) => Ok(()),
@@ -3,7 +3,25 @@ use rusqlite::Connection;
 use rusqlite_migration::{M, Migrations};
 
 pub fn run_migrations(conn: &mut Connection) -> Result<(), FicflowError> {
-    let migrations = Migrations::new(vec![
+    migrations().to_latest(conn)?;
+    Ok(())
+}
+
+/// True for a library made by an older build, which opening it will
+/// upgrade. A brand-new file has nothing to upgrade.
+pub fn needs_upgrade(conn: &Connection) -> Result<bool, FicflowError> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    Ok(version > 0 && migrations().pending_migrations(conn)? > 0)
+}
+
+/// True when the library's schema is the one this build writes, so it
+/// can be read without migrating anything.
+pub fn is_up_to_date(conn: &Connection) -> Result<bool, FicflowError> {
+    Ok(migrations().pending_migrations(conn)? == 0)
+}
+
+fn migrations() -> Migrations<'static> {
+    Migrations::new(vec![
         M::up(
             r#"
             CREATE TABLE IF NOT EXISTS fanfiction (
@@ -76,9 +94,300 @@ pub fn run_migrations(conn: &mut Connection) -> Result<(), FicflowError> {
             ALTER TABLE shelf ADD COLUMN auto_criteria TEXT;
         "#,
         ),
-    ]);
+        M::up(
+            r#"
+            CREATE TABLE IF NOT EXISTS saved_search (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                name       TEXT NOT NULL,
+                query      TEXT NOT NULL,
+                scope      TEXT NOT NULL,
+                sort       TEXT NOT NULL,
+                position   INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                deleted_at TEXT
+            );
+        "#,
+        ),
+        M::up(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS fanfiction_fts USING fts5(
+                title, authors, fandoms, summary, tags, characters, relationships, personal_note,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
 
-    migrations.to_latest(conn)?;
+            INSERT INTO fanfiction_fts (rowid, title, authors, fandoms, summary, tags, characters, relationships, personal_note)
+                SELECT
+                    id,
+                    title,
+                    (SELECT group_concat(value, ', ') FROM json_each(authors)),
+                    (SELECT group_concat(value, ', ') FROM json_each(fandoms)),
+                    summary,
+                    (SELECT group_concat(value, ', ') FROM json_each(tags)),
+                    (SELECT group_concat(value, ', ') FROM json_each(characters)),
+                    (SELECT group_concat(value, ', ') FROM json_each(relationships)),
+                    personal_note
+                FROM fanfiction;
 
-    Ok(())
+            -- Delete-then-insert so a stale index row for the same id
+            -- never survives. Saves are upserts (`ON CONFLICT DO UPDATE`),
+            -- so a resave fires `fanfiction_fts_au` below; this trigger
+            -- covers new rows, and rows a REPLACE may still remove without
+            -- firing delete triggers.
+            CREATE TRIGGER IF NOT EXISTS fanfiction_fts_ai AFTER INSERT ON fanfiction BEGIN
+                DELETE FROM fanfiction_fts WHERE rowid = new.id;
+                INSERT INTO fanfiction_fts (rowid, title, authors, fandoms, summary, tags, characters, relationships, personal_note) VALUES (
+                    new.id,
+                    new.title,
+                    (SELECT group_concat(value, ', ') FROM json_each(new.authors)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.fandoms)),
+                    new.summary,
+                    (SELECT group_concat(value, ', ') FROM json_each(new.tags)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.characters)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.relationships)),
+                    new.personal_note
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS fanfiction_fts_au
+            AFTER UPDATE OF title, authors, fandoms, summary, tags, characters, relationships, personal_note
+            ON fanfiction BEGIN
+                DELETE FROM fanfiction_fts WHERE rowid = old.id;
+                INSERT INTO fanfiction_fts (rowid, title, authors, fandoms, summary, tags, characters, relationships, personal_note) VALUES (
+                    new.id,
+                    new.title,
+                    (SELECT group_concat(value, ', ') FROM json_each(new.authors)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.fandoms)),
+                    new.summary,
+                    (SELECT group_concat(value, ', ') FROM json_each(new.tags)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.characters)),
+                    (SELECT group_concat(value, ', ') FROM json_each(new.relationships)),
+                    new.personal_note
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS fanfiction_fts_ad AFTER DELETE ON fanfiction BEGIN
+                DELETE FROM fanfiction_fts WHERE rowid = old.id;
+            END;
+        "#,
+        ),
+        M::up(
+            r#"
+            -- Partial indexes over live rows: every library read filters on
+            -- `deleted_at IS NULL`. Expressions match the sort keys in
+            -- `repository::fic_query` character for character.
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_title
+                ON fanfiction(lower(title)) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_author
+                ON fanfiction(lower(json_extract(authors, '$[0]'))) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_status
+                ON fanfiction(reading_status) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_updated
+                ON fanfiction(date_updated) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_published
+                ON fanfiction(date_published) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_words
+                ON fanfiction(words) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_kudos
+                ON fanfiction(kudos) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fanfiction_hits
+                ON fanfiction(hits) WHERE deleted_at IS NULL;
+            CREATE INDEX IF NOT EXISTS idx_fic_shelf_shelf ON fic_shelf(shelf_id);
+        "#,
+        ),
+        M::up(
+            r#"
+            -- `fanfiction` keeps its JSON list columns as each row's
+            -- ordered copy (what `row_to_fanfiction` reads); `tag` and
+            -- `fic_tag` index those values for per-tag lookups and counts.
+            -- Triggers keep them in step, like `fanfiction_fts`. Names are
+            -- stored as written; lookups compare them NOCASE. The trigger
+            -- bodies avoid `INSERT OR IGNORE`: inside a trigger the outer
+            -- statement's conflict policy wins, and `save_fanfiction`'s
+            -- upsert would turn the IGNORE back into an ABORT.
+            CREATE TABLE IF NOT EXISTS tag (
+                id   INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                UNIQUE (kind, name)
+            );
+            CREATE INDEX IF NOT EXISTS idx_tag_kind_name_nocase ON tag(kind, name COLLATE NOCASE);
+
+            CREATE TABLE IF NOT EXISTS fic_tag (
+                fic_id   INTEGER NOT NULL REFERENCES fanfiction(id) ON DELETE CASCADE,
+                tag_id   INTEGER NOT NULL REFERENCES tag(id)        ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (fic_id, tag_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_fic_tag_tag ON fic_tag(tag_id);
+
+            CREATE TEMP TABLE fic_tag_backfill AS
+                SELECT 'Author' AS kind, f.id AS fic_id, j.key AS position, j.value AS name FROM fanfiction f, json_each(f.authors) j WHERE j.type = 'text'
+                UNION ALL SELECT 'Fandom', f.id, j.key, j.value FROM fanfiction f, json_each(f.fandoms) j WHERE j.type = 'text'
+                UNION ALL SELECT 'Relationship', f.id, j.key, j.value FROM fanfiction f, json_each(f.relationships) j WHERE j.type = 'text'
+                UNION ALL SELECT 'Character', f.id, j.key, j.value FROM fanfiction f, json_each(f.characters) j WHERE j.type = 'text'
+                UNION ALL SELECT 'Tag', f.id, j.key, j.value FROM fanfiction f, json_each(f.tags) j WHERE j.type = 'text';
+
+            INSERT OR IGNORE INTO tag (kind, name)
+                SELECT kind, name FROM fic_tag_backfill;
+            INSERT OR IGNORE INTO fic_tag (fic_id, tag_id, position)
+                SELECT b.fic_id, tag.id, b.position FROM fic_tag_backfill b
+                JOIN tag ON tag.kind = b.kind AND tag.name = b.name;
+            DROP TABLE fic_tag_backfill;
+
+            CREATE TRIGGER IF NOT EXISTS fanfiction_tags_ai AFTER INSERT ON fanfiction BEGIN
+                DELETE FROM fic_tag WHERE fic_id = new.id;
+                INSERT INTO tag (kind, name)
+                    SELECT DISTINCT kind, name FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) v
+                    WHERE NOT EXISTS (SELECT 1 FROM tag WHERE tag.kind = v.kind AND tag.name = v.name);
+                INSERT INTO fic_tag (fic_id, tag_id, position)
+                    SELECT new.id, tag.id, MIN(j.position) FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) j
+                    JOIN tag ON tag.kind = j.kind AND tag.name = j.name
+                    GROUP BY tag.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS fanfiction_tags_au
+            AFTER UPDATE OF authors, fandoms, relationships, characters, tags
+            ON fanfiction BEGIN
+                DELETE FROM fic_tag WHERE fic_id = new.id;
+                INSERT INTO tag (kind, name)
+                    SELECT DISTINCT kind, name FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) v
+                    WHERE NOT EXISTS (SELECT 1 FROM tag WHERE tag.kind = v.kind AND tag.name = v.name);
+                INSERT INTO fic_tag (fic_id, tag_id, position)
+                    SELECT new.id, tag.id, MIN(j.position) FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) j
+                    JOIN tag ON tag.kind = j.kind AND tag.name = j.name
+                    GROUP BY tag.id;
+            END;
+        "#,
+        ),
+        M::up(
+            r#"
+            ALTER TABLE fanfiction ADD COLUMN personal_tags TEXT NOT NULL DEFAULT '[]';
+        "#,
+        ),
+        // When the reader's own fields last changed, so merging two
+        // libraries can tell whose edit is newer. NULL on older rows.
+        M::up(
+            r#"
+            ALTER TABLE fanfiction ADD COLUMN user_updated_at TEXT;
+        "#,
+        ),
+        // Tag names are one tag whatever their case, as lookups already
+        // treated them. The column's NOCASE collation carries into every
+        // comparison the triggers make, and spellings that differ only
+        // in case are merged into the first one stored. NOCASE folds
+        // ASCII letters only, so `Émilie` and `émilie` stay two tags, as
+        // they were always two to lookups and auto-shelves. `tag` has to be
+        // rebuilt to change its key, so the triggers and `fic_tag` are
+        // set aside meanwhile. Safe to run on a library it already ran on.
+        M::up(
+            r#"
+            DROP TRIGGER IF EXISTS fanfiction_tags_ai;
+            DROP TRIGGER IF EXISTS fanfiction_tags_au;
+
+            CREATE TABLE tag_nocase (
+                id   INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL COLLATE NOCASE,
+                UNIQUE (kind, name)
+            );
+            -- With a lone MIN(), SQLite takes `name` from that same row.
+            INSERT INTO tag_nocase (id, kind, name)
+                SELECT MIN(id), kind, name FROM tag GROUP BY kind, name COLLATE NOCASE;
+            CREATE TEMP TABLE fic_tag_merged AS
+                SELECT ft.fic_id, kept.id AS tag_id, MIN(ft.position) AS position
+                FROM fic_tag ft
+                JOIN tag t ON t.id = ft.tag_id
+                JOIN tag_nocase kept ON kept.kind = t.kind AND kept.name = t.name
+                GROUP BY ft.fic_id, kept.id;
+
+            DROP TABLE fic_tag;
+            DROP TABLE tag;
+            ALTER TABLE tag_nocase RENAME TO tag;
+            CREATE TABLE fic_tag (
+                fic_id   INTEGER NOT NULL REFERENCES fanfiction(id) ON DELETE CASCADE,
+                tag_id   INTEGER NOT NULL REFERENCES tag(id)        ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (fic_id, tag_id)
+            );
+            CREATE INDEX idx_fic_tag_tag ON fic_tag(tag_id);
+            INSERT INTO fic_tag (fic_id, tag_id, position)
+                SELECT fic_id, tag_id, position FROM fic_tag_merged;
+            DROP TABLE fic_tag_merged;
+
+            CREATE TRIGGER fanfiction_tags_ai AFTER INSERT ON fanfiction BEGIN
+                DELETE FROM fic_tag WHERE fic_id = new.id;
+                INSERT INTO tag (kind, name)
+                    SELECT kind, name FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) v
+                    WHERE NOT EXISTS (SELECT 1 FROM tag WHERE tag.kind = v.kind AND tag.name = v.name)
+                    GROUP BY kind, name COLLATE NOCASE;
+                INSERT INTO fic_tag (fic_id, tag_id, position)
+                    SELECT new.id, tag.id, MIN(j.position) FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) j
+                    JOIN tag ON tag.kind = j.kind AND tag.name = j.name
+                    GROUP BY tag.id;
+            END;
+
+            CREATE TRIGGER fanfiction_tags_au
+            AFTER UPDATE OF authors, fandoms, relationships, characters, tags
+            ON fanfiction BEGIN
+                DELETE FROM fic_tag WHERE fic_id = new.id;
+                INSERT INTO tag (kind, name)
+                    SELECT kind, name FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) v
+                    WHERE NOT EXISTS (SELECT 1 FROM tag WHERE tag.kind = v.kind AND tag.name = v.name)
+                    GROUP BY kind, name COLLATE NOCASE;
+                INSERT INTO fic_tag (fic_id, tag_id, position)
+                    SELECT new.id, tag.id, MIN(j.position) FROM (
+                        SELECT 'Author' AS kind, key AS position, value AS name FROM json_each(new.authors) WHERE type = 'text'
+                        UNION ALL SELECT 'Fandom', key, value FROM json_each(new.fandoms) WHERE type = 'text'
+                        UNION ALL SELECT 'Relationship', key, value FROM json_each(new.relationships) WHERE type = 'text'
+                        UNION ALL SELECT 'Character', key, value FROM json_each(new.characters) WHERE type = 'text'
+                        UNION ALL SELECT 'Tag', key, value FROM json_each(new.tags) WHERE type = 'text'
+                    ) j
+                    JOIN tag ON tag.kind = j.kind AND tag.name = j.name
+                    GROUP BY tag.id;
+            END;
+        "#,
+        ),
+    ])
 }
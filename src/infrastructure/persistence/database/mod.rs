@@ -1,4 +1,8 @@
+pub mod backup;
 pub mod connection;
 pub mod migration;
 
-pub use connection::{open_configured_db, relocate_library, restore_backup};
+pub use backup::{SqliteBackups, backups_dir};
+pub use connection::{
+    open_configured_db, open_read_only_db, read_library_file, relocate_library, restore_backup,
+};
@@ -1,5 +1,8 @@
 pub mod database;
 pub mod repository;
 
-pub use database::connection::{open_configured_db, relocate_library, restore_backup};
+pub use database::backup::{SqliteBackups, backups_dir};
+pub use database::connection::{
+    open_configured_db, open_read_only_db, read_library_file, relocate_library, restore_backup,
+};
 pub use repository::sqlite_repository::SqliteRepository;
@@ -0,0 +1,159 @@
+//! Compiles a `FicQuery` into the `WHERE` / `ORDER BY` / `LIMIT` tail of
+//! a `SELECT ... FROM fanfiction f`. The sort expressions are the SQL
+//! twins of `ColumnKey::compare`; the ones worth an index have one in
+//! the migrations (`idx_fanfiction_*`), declared over the exact same
+//! expressions so SQLite can match them.
+
+use rusqlite::types::Value;
+
+use crate::domain::fanfiction::{ColumnKey, FicQuery, SortDirection};
+
+pub struct CompiledQuery {
+    /// Conditions only, without the `WHERE` keyword.
+    pub where_sql: String,
+    pub order_sql: String,
+    /// Bind values for `where_sql`, in placeholder order.
+    pub params: Vec<Value>,
+}
+
+impl CompiledQuery {
+    /// `params` followed by the page bounds, for a statement ending in
+    /// `LIMIT ? OFFSET ?`. SQLite reads a negative limit as "no limit".
+    pub fn paged_params(&self, query: &FicQuery) -> Vec<Value> {
+        let mut params = self.params.clone();
+        params.push(Value::Integer(query.limit.map_or(-1, i64::from)));
+        params.push(Value::Integer(i64::from(query.offset)));
+        params
+    }
+}
+
+pub fn compile(query: &FicQuery) -> CompiledQuery {
+    let filter = &query.filter;
+    let mut conditions = vec!["f.deleted_at IS NULL".to_string()];
+    let mut params = Vec::new();
+
+    if let Some(status) = filter.status {
+        conditions.push("f.reading_status = ?".into());
+        params.push(Value::Text(status.to_string()));
+    }
+    if let Some(complete) = filter.complete {
+        conditions.push("f.complete = ?".into());
+        params.push(Value::Integer(i64::from(complete)));
+    }
+    for (kind, name) in &filter.tags {
+        conditions.push(
+            "f.id IN (SELECT ft.fic_id FROM fic_tag ft JOIN tag t ON t.id = ft.tag_id \
+                      WHERE t.kind = ? AND t.name = ? COLLATE NOCASE)"
+                .into(),
+        );
+        params.push(Value::Text(kind.to_string()));
+        params.push(Value::Text(name.clone()));
+    }
+    if let Some(min_words) = filter.min_words {
+        conditions.push("f.words >= ?".into());
+        params.push(Value::Integer(i64::from(min_words)));
+    }
+    if let Some(shelf_id) = filter.shelf_id {
+        conditions.push(
+            "f.id IN ( \
+                 WITH RECURSIVE subtree(id) AS ( \
+                     SELECT id FROM shelf WHERE id = ? AND deleted_at IS NULL \
+                     UNION \
+                     SELECT s.id FROM shelf s \
+                     JOIN subtree ON s.parent_shelf_id = subtree.id \
+                     WHERE s.deleted_at IS NULL \
+                 ) \
+                 SELECT fs.fic_id FROM fic_shelf fs JOIN subtree ON subtree.id = fs.shelf_id \
+             )"
+            .into(),
+        );
+        params.push(Value::Integer(shelf_id as i64));
+    }
+    if let Some(text) = filter.text.as_deref().filter(|t| !t.trim().is_empty()) {
+        match to_fts_query(text) {
+            Some(fts_query) => {
+                conditions.push(
+                    "f.id IN (SELECT rowid FROM fanfiction_fts WHERE fanfiction_fts MATCH ?)"
+                        .into(),
+                );
+                params.push(Value::Text(fts_query));
+            }
+            // Nothing searchable (e.g. only punctuation): matches
+            // nothing, same as `search_fanfictions`.
+            None => conditions.push("0".into()),
+        }
+    }
+
+    let mut order: Vec<String> = query
+        .sort
+        .iter()
+        .map(|pref| {
+            let direction = match pref.direction {
+                SortDirection::Ascending => "ASC",
+                SortDirection::Descending => "DESC",
+            };
+            format!("{} {}", sort_expr(pref.column), direction)
+        })
+        .collect();
+    order.push("f.title".into());
+    order.push("f.id".into());
+
+    CompiledQuery {
+        where_sql: conditions.join(" AND "),
+        order_sql: order.join(", "),
+        params,
+    }
+}
+
+/// `lower()` only folds ASCII; `ColumnKey::compare` folds the same way
+/// so both orders agree on non-ASCII letters too.
+fn sort_expr(column: ColumnKey) -> &'static str {
+    match column {
+        ColumnKey::Title => "lower(f.title)",
+        ColumnKey::Author => "lower(json_extract(f.authors, '$[0]'))",
+        ColumnKey::Fandom => "lower(json_extract(f.fandoms, '$[0]'))",
+        ColumnKey::Pairing => "lower(json_extract(f.relationships, '$[0]'))",
+        ColumnKey::AO3Rating => {
+            "CASE f.rating WHEN 'NotRated' THEN 0 WHEN 'General' THEN 1 \
+             WHEN 'TeenAndUp' THEN 2 WHEN 'Mature' THEN 3 ELSE 4 END"
+        }
+        ColumnKey::Warnings => {
+            "CASE json_extract(f.warnings, '$[0]') WHEN 'ChooseNotToUse' THEN 0 \
+             WHEN 'GraphicDepictionsOfViolence' THEN 1 WHEN 'MajorCharacterDeath' THEN 2 \
+             WHEN 'RapeNonCon' THEN 3 WHEN 'Underage' THEN 4 \
+             WHEN 'NoArchiveWarningsApply' THEN 5 END"
+        }
+        ColumnKey::Status => {
+            "CASE f.reading_status WHEN 'InProgress' THEN 0 WHEN 'Read' THEN 1 \
+             WHEN 'PlanToRead' THEN 2 WHEN 'Paused' THEN 3 ELSE 4 END"
+        }
+        ColumnKey::Complete => "f.complete",
+        ColumnKey::LastChapter => "f.last_chapter_read",
+        ColumnKey::Words => "f.words",
+        ColumnKey::Kudos => "f.kudos",
+        ColumnKey::Hits => "f.hits",
+        ColumnKey::Rating => "f.user_rating",
+        ColumnKey::Reads => "f.read_count",
+        ColumnKey::Language => "lower(f.language)",
+        ColumnKey::DatePublished => "f.date_published",
+        ColumnKey::Updated => "f.date_updated",
+    }
+}
+
+/// Turns free text into an FTS5 query: every whitespace-separated word
+/// becomes a quoted prefix term, and all terms must match. Quoting keeps
+/// user punctuation from being read as FTS5 syntax. `None` when nothing
+/// searchable is left.
+pub fn to_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
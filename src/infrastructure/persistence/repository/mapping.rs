@@ -1,5 +1,10 @@
-use crate::domain::fanfiction::{Fanfiction, Rating, ReadingStatus, UserRating};
+use crate::domain::fanfiction::{
+    Fanfiction, Rating, ReadingStatus, SearchHit, Snippet, UserRating,
+};
+use crate::domain::saved_search::{SavedSearch, SearchScope};
 use crate::domain::shelf::{AutoShelfCriteria, Shelf, ShelfKind};
+use crate::domain::transfer::{ExportedFic, ExportedMembership, ExportedShelf};
+use crate::domain::trash::{TrashedFic, TrashedShelf};
 use chrono::{DateTime, Utc};
 use rusqlite::Row;
 
@@ -46,6 +51,69 @@ pub fn row_to_shelf(row: &Row) -> Result<Shelf, rusqlite::Error> {
     })
 }
 
+pub fn row_to_saved_search(row: &Row) -> Result<SavedSearch, rusqlite::Error> {
+    let id: u64 = row.get(0)?;
+    let name: String = row.get(1)?;
+    let query: String = row.get(2)?;
+    let scope_json: String = row.get(3)?;
+    let scope: SearchScope = serde_json::from_str(&scope_json).map_err(|_| {
+        rusqlite::Error::InvalidColumnType(3, "scope".into(), rusqlite::types::Type::Text)
+    })?;
+    let sort_json: String = row.get(4)?;
+    let sort = serde_json::from_str(&sort_json).map_err(|_| {
+        rusqlite::Error::InvalidColumnType(4, "sort".into(), rusqlite::types::Type::Text)
+    })?;
+    let position: u32 = row.get(5)?;
+    let created_at_str: String = row.get(6)?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+        .map_err(|_| {
+            rusqlite::Error::InvalidColumnType(6, "created_at".into(), rusqlite::types::Type::Text)
+        })?
+        .with_timezone(&Utc);
+    Ok(SavedSearch {
+        id,
+        name,
+        query,
+        scope,
+        sort,
+        position,
+        created_at,
+    })
+}
+
+/// Markers `snippet()` wraps matched terms in; stripped again by
+/// `row_to_search_hit`. Control characters so they can't collide with
+/// text stored in a fic.
+pub const SNIPPET_OPEN: char = '\u{1}';
+pub const SNIPPET_CLOSE: char = '\u{2}';
+
+/// A `fanfiction` row followed by a `snippet` column.
+pub fn row_to_search_hit(row: &Row) -> Result<SearchHit, rusqlite::Error> {
+    let fic = row_to_fanfiction(row)?;
+    let marked: String = row.get("snippet")?;
+    Ok(SearchHit {
+        fic,
+        snippet: parse_snippet(&marked),
+    })
+}
+
+fn parse_snippet(marked: &str) -> Snippet {
+    let mut snippet = Snippet::default();
+    let mut open_at = None;
+    for ch in marked.chars() {
+        match ch {
+            SNIPPET_OPEN => open_at = Some(snippet.text.len()),
+            SNIPPET_CLOSE => {
+                if let Some(start) = open_at.take() {
+                    snippet.highlights.push(start..snippet.text.len());
+                }
+            }
+            _ => snippet.text.push(ch),
+        }
+    }
+    snippet
+}
+
 pub fn row_to_fanfiction(row: &Row) -> Result<Fanfiction, rusqlite::Error> {
     let id: u64 = row.get(0)?;
     let title: String = row.get(1)?;
@@ -83,6 +151,10 @@ pub fn row_to_fanfiction(row: &Row) -> Result<Fanfiction, rusqlite::Error> {
     let warnings_json: String = row.get(17)?;
     let warnings = parse_json_array(&warnings_json, id, "warnings")?;
 
+    // Column 28: added after `deleted_at` (27).
+    let personal_tags_json: String = row.get(28)?;
+    let personal_tags = parse_json_array(&personal_tags_json, id, "personal_tags")?;
+
     let rating_str: String = row.get(12)?;
     let rating = parse_rating(&rating_str);
 
@@ -153,10 +225,90 @@ pub fn row_to_fanfiction(row: &Row) -> Result<Fanfiction, rusqlite::Error> {
         read_count,
         user_rating,
         personal_note,
+        personal_tags,
         last_checked_date,
     })
 }
 
+/// Expects `SELECT *` from `fanfiction`, where `deleted_at` is column 27.
+pub fn row_to_trashed_fic(row: &Row) -> Result<TrashedFic, rusqlite::Error> {
+    let fic = row_to_fanfiction(row)?;
+    let deleted_at = parse_deleted_at(row, 27)?;
+    Ok(TrashedFic { fic, deleted_at })
+}
+
+/// Expects the `row_to_shelf` columns followed by `deleted_at`.
+pub fn row_to_trashed_shelf(row: &Row) -> Result<TrashedShelf, rusqlite::Error> {
+    let shelf = row_to_shelf(row)?;
+    let deleted_at = parse_deleted_at(row, 7)?;
+    Ok(TrashedShelf { shelf, deleted_at })
+}
+
+/// Like `row_to_trashed_fic`, for rows that may or may not be deleted.
+pub fn row_to_exported_fic(row: &Row) -> Result<ExportedFic, rusqlite::Error> {
+    let fic = row_to_fanfiction(row)?;
+    let deleted_at = match row.get::<_, Option<String>>(27)? {
+        Some(_) => Some(parse_deleted_at(row, 27)?),
+        None => None,
+    };
+    // Column 29: added after `personal_tags` (28).
+    let user_updated_at = match row.get::<_, Option<String>>(29)? {
+        Some(text) => DateTime::parse_from_rfc3339(&text)
+            .map(|dt| Some(dt.with_timezone(&Utc)))
+            .map_err(|_| {
+                rusqlite::Error::InvalidColumnType(
+                    29,
+                    "user_updated_at".into(),
+                    rusqlite::types::Type::Text,
+                )
+            })?,
+        None => None,
+    };
+    Ok(ExportedFic {
+        fic,
+        deleted_at,
+        user_updated_at,
+    })
+}
+
+/// Like `row_to_trashed_shelf`, for rows that may or may not be deleted.
+pub fn row_to_exported_shelf(row: &Row) -> Result<ExportedShelf, rusqlite::Error> {
+    let shelf = row_to_shelf(row)?;
+    let deleted_at = match row.get::<_, Option<String>>(7)? {
+        Some(_) => Some(parse_deleted_at(row, 7)?),
+        None => None,
+    };
+    Ok(ExportedShelf { shelf, deleted_at })
+}
+
+/// Expects `fic_id, shelf_id, added_at` from `fic_shelf`.
+pub fn row_to_exported_membership(row: &Row) -> Result<ExportedMembership, rusqlite::Error> {
+    let added_at_str: String = row.get(2)?;
+    let added_at = DateTime::parse_from_rfc3339(&added_at_str)
+        .map_err(|_| {
+            rusqlite::Error::InvalidColumnType(2, "added_at".into(), rusqlite::types::Type::Text)
+        })?
+        .with_timezone(&Utc);
+    Ok(ExportedMembership {
+        fic_id: row.get(0)?,
+        shelf_id: row.get(1)?,
+        added_at,
+    })
+}
+
+fn parse_deleted_at(row: &Row, idx: usize) -> Result<DateTime<Utc>, rusqlite::Error> {
+    let deleted_at_str: String = row.get(idx)?;
+    DateTime::parse_from_rfc3339(&deleted_at_str)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| {
+            rusqlite::Error::InvalidColumnType(
+                idx,
+                "deleted_at".into(),
+                rusqlite::types::Type::Text,
+            )
+        })
+}
+
 fn parse_json_array<T: serde::de::DeserializeOwned>(
     json: &str,
     id: u64,
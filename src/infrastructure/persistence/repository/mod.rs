@@ -1,3 +1,4 @@
+pub mod fic_query;
 pub mod mapping;
 pub mod sqlite_repository;
 
@@ -1,9 +1,26 @@
-use crate::domain::fanfiction::{Fanfiction, FanfictionOps};
+use std::collections::HashMap;
+
+use crate::domain::fanfiction::{
+    ArchiveWarnings, Categories, Fanfiction, FanfictionOps, FicPage, FicQuery, SearchHit, SortPref,
+    TagCount, TagKind,
+};
+use crate::domain::health::{HealthOps, LibraryIssue};
+use crate::domain::saved_search::{SavedSearch, SavedSearchOps, SearchScope};
 use crate::domain::shelf::{AutoShelfCriteria, MAX_SHELF_DEPTH, Shelf, ShelfKind, ShelfOps};
+use crate::domain::transfer::{
+    ExportedFic, ExportedShelf, ImportMode, ImportSummary, LIBRARY_SCHEMA_VERSION, LibrarySnapshot,
+    MergePolicy, MergeReport, TransferOps, merge_fic,
+};
+use crate::domain::trash::{PurgeSummary, TrashOps, TrashedFic, TrashedShelf};
 use crate::error::FicflowError;
-use crate::infrastructure::persistence::repository::mapping::{row_to_fanfiction, row_to_shelf};
-use chrono::Utc;
-use rusqlite::{Connection, params};
+use crate::infrastructure::persistence::repository::fic_query::{self, to_fts_query};
+use crate::infrastructure::persistence::repository::mapping::{
+    SNIPPET_CLOSE, SNIPPET_OPEN, row_to_exported_fic, row_to_exported_membership,
+    row_to_exported_shelf, row_to_fanfiction, row_to_saved_search, row_to_search_hit, row_to_shelf,
+    row_to_trashed_fic, row_to_trashed_shelf,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, params, params_from_iter};
 
 pub struct SqliteRepository<'a> {
     conn: &'a Connection,
@@ -13,6 +30,19 @@ impl<'a> SqliteRepository<'a> {
     pub fn new(conn: &'a Connection) -> Self {
         Self { conn }
     }
+
+    /// Runs `op` in one transaction, rolled back if it fails. `op` must
+    /// not call the ops that open their own (`save_fanfictions`, imports
+    /// and merges).
+    pub fn in_transaction<T>(
+        &self,
+        op: impl FnOnce(&Self) -> Result<T, FicflowError>,
+    ) -> Result<T, FicflowError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let value = op(self)?;
+        tx.commit()?;
+        Ok(value)
+    }
 }
 
 impl<'a> FanfictionOps for SqliteRepository<'a> {
@@ -24,6 +54,7 @@ impl<'a> FanfictionOps for SqliteRepository<'a> {
         let relationships = serde_json::to_string(&fic.relationships)?;
         let tags = serde_json::to_string(&fic.tags)?;
         let warnings = serde_json::to_string(&fic.warnings)?;
+        let personal_tags = serde_json::to_string(&fic.personal_tags)?;
 
         let date_published_str = fic.date_published.to_rfc3339();
         let date_updated_str = fic.date_updated.to_rfc3339();
@@ -44,14 +75,41 @@ impl<'a> FanfictionOps for SqliteRepository<'a> {
                 .execute("DELETE FROM fic_shelf WHERE fic_id = ?1", params![fic.id])?;
         }
 
+        // An upsert rather than `INSERT OR REPLACE`: REPLACE deletes the
+        // old row first, which cascades to `fic_shelf` and `fic_tag`.
         self.conn.execute(
-            "INSERT OR REPLACE INTO fanfiction (
+            "INSERT INTO fanfiction (
                 id, title, authors, categories, chapters_total, chapters_published, characters,
                 complete, fandoms, hits, kudos, language, rating, relationships, restricted,
                 summary, tags, warnings, words, date_published, date_updated, last_chapter_read,
-                reading_status, read_count, user_rating, personal_note, last_checked_date, deleted_at
+                reading_status, read_count, user_rating, personal_note, last_checked_date, deleted_at,
+                personal_tags, user_updated_at
             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
-                ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, NULL)",
+                ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, NULL, ?28, ?29)
+            ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title, authors = excluded.authors,
+                categories = excluded.categories, chapters_total = excluded.chapters_total,
+                chapters_published = excluded.chapters_published,
+                characters = excluded.characters, complete = excluded.complete,
+                fandoms = excluded.fandoms, hits = excluded.hits, kudos = excluded.kudos,
+                language = excluded.language, rating = excluded.rating,
+                relationships = excluded.relationships, restricted = excluded.restricted,
+                summary = excluded.summary, tags = excluded.tags, warnings = excluded.warnings,
+                words = excluded.words, date_published = excluded.date_published,
+                date_updated = excluded.date_updated,
+                last_chapter_read = excluded.last_chapter_read,
+                reading_status = excluded.reading_status, read_count = excluded.read_count,
+                user_rating = excluded.user_rating, personal_note = excluded.personal_note,
+                last_checked_date = excluded.last_checked_date, deleted_at = NULL,
+                personal_tags = excluded.personal_tags,
+                user_updated_at = CASE WHEN
+                    last_chapter_read IS NOT excluded.last_chapter_read
+                    OR reading_status IS NOT excluded.reading_status
+                    OR read_count IS NOT excluded.read_count
+                    OR user_rating IS NOT excluded.user_rating
+                    OR personal_note IS NOT excluded.personal_note
+                    OR personal_tags IS NOT excluded.personal_tags
+                THEN excluded.user_updated_at ELSE user_updated_at END",
             params![
                 fic.id,
                 fic.title,
@@ -79,13 +137,24 @@ impl<'a> FanfictionOps for SqliteRepository<'a> {
                 fic.read_count,
                 fic.user_rating.map(|r| r as u32),
                 fic.personal_note,
-                last_checked_date_str
+                last_checked_date_str,
+                personal_tags,
+                Utc::now().to_rfc3339()
             ],
         )?;
 
         Ok(())
     }
 
+    fn save_fanfictions(&self, fics: &[Fanfiction]) -> Result<(), FicflowError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for fic in fics {
+            self.save_fanfiction(fic)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     fn delete_fanfiction(&self, fic_id: u64) -> Result<(), FicflowError> {
         let now = Utc::now().to_rfc3339();
         self.conn.execute(
@@ -115,6 +184,99 @@ impl<'a> FanfictionOps for SqliteRepository<'a> {
             })
     }
 
+    fn query_fanfictions(&self, query: &FicQuery) -> Result<FicPage, FicflowError> {
+        self.ensure_queryable_shelf(query)?;
+        let compiled = fic_query::compile(query);
+        let total: i64 = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM fanfiction f WHERE {}",
+                compiled.where_sql
+            ),
+            params_from_iter(&compiled.params),
+            |row| row.get(0),
+        )?;
+        // Sort and page ids only, then load the page's rows: sorting whole
+        // rows (summaries and all) is what makes deep pages slow.
+        let ids = self.query_fanfiction_ids(query)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT f.* FROM json_each(?1) page \
+             JOIN fanfiction f ON f.id = page.value \
+             ORDER BY page.key",
+        )?;
+        let rows = stmt.query_map(params![serde_json::to_string(&ids)?], row_to_fanfiction)?;
+        let fics = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(FicPage {
+            fics,
+            total: total as usize,
+        })
+    }
+
+    fn query_fanfiction_ids(&self, query: &FicQuery) -> Result<Vec<u64>, FicflowError> {
+        self.ensure_queryable_shelf(query)?;
+        let compiled = fic_query::compile(query);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT f.id FROM fanfiction f WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            compiled.where_sql, compiled.order_sql
+        ))?;
+        let rows = stmt.query_map(params_from_iter(compiled.paged_params(query)), |row| {
+            row.get(0)
+        })?;
+        let ids = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    fn search_fanfictions(&self, query: &str) -> Result<Vec<SearchHit>, FicflowError> {
+        let Some(fts_query) = to_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        // bm25 weights follow the `fanfiction_fts` column order: a title hit
+        // outranks an author hit, which outranks a tag hit, and so on.
+        let sql = format!(
+            "SELECT f.*, snippet(fanfiction_fts, -1, '{open}', '{close}', '\u{2026}', 16) AS snippet \
+             FROM fanfiction_fts JOIN fanfiction f ON f.id = fanfiction_fts.rowid \
+             WHERE fanfiction_fts MATCH ?1 AND f.deleted_at IS NULL \
+             ORDER BY bm25(fanfiction_fts, 10.0, 5.0, 2.0, 1.0, 3.0, 3.0, 3.0, 2.0), f.title",
+            open = SNIPPET_OPEN,
+            close = SNIPPET_CLOSE,
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![fts_query], row_to_search_hit)?;
+        let hits = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(hits)
+    }
+
+    fn count_tags(&self, kind: TagKind) -> Result<Vec<TagCount>, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.name, COUNT(*) FROM tag t \
+             JOIN fic_tag ft ON ft.tag_id = t.id \
+             JOIN fanfiction f ON f.id = ft.fic_id AND f.deleted_at IS NULL \
+             WHERE t.kind = ?1 \
+             GROUP BY t.id \
+             ORDER BY t.name",
+        )?;
+        let rows = stmt.query_map(params![kind.to_string()], |row| {
+            Ok(TagCount {
+                name: row.get(0)?,
+                count: row.get::<_, i64>(1)? as usize,
+            })
+        })?;
+        let counts = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
+    fn find_fic_ids_by_tag(&self, kind: TagKind, name: &str) -> Result<Vec<u64>, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT f.id, f.title FROM tag t \
+             JOIN fic_tag ft ON ft.tag_id = t.id \
+             JOIN fanfiction f ON f.id = ft.fic_id AND f.deleted_at IS NULL \
+             WHERE t.kind = ?1 AND t.name = ?2 COLLATE NOCASE \
+             ORDER BY f.title, f.id",
+        )?;
+        let rows = stmt.query_map(params![kind.to_string(), name], |row| row.get(0))?;
+        let ids = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
     fn wipe_database(&self) -> Result<(), FicflowError> {
         let now = Utc::now().to_rfc3339();
         self.conn.execute(
@@ -138,6 +300,19 @@ impl<'a> SqliteRepository<'a> {
         Ok(())
     }
 
+    /// A shelf filter needs stored members, which only normal shelves have.
+    fn ensure_queryable_shelf(&self, query: &FicQuery) -> Result<(), FicflowError> {
+        let Some(shelf_id) = query.filter.shelf_id else {
+            return Ok(());
+        };
+        match self.get_shelf_by_id(shelf_id)?.kind {
+            ShelfKind::Normal => Ok(()),
+            ShelfKind::Auto(_) => Err(FicflowError::InvalidInput(
+                "auto-shelves have no stored members to filter by".into(),
+            )),
+        }
+    }
+
     fn ensure_shelf_exists(&self, shelf_id: u64) -> Result<(), FicflowError> {
         let count: u64 = self.conn.query_row(
             "SELECT COUNT(*) FROM shelf WHERE id = ?1 AND deleted_at IS NULL",
@@ -215,10 +390,7 @@ impl<'a> SqliteRepository<'a> {
             }
         }
 
-        let (kind_col, criteria_col) = match &kind {
-            ShelfKind::Normal => ("normal", None),
-            ShelfKind::Auto(criteria) => ("auto", Some(serde_json::to_string(criteria)?)),
-        };
+        let (kind_col, criteria_col) = shelf_kind_columns(&kind)?;
 
         let created_at = Utc::now();
         self.conn.execute(
@@ -496,3 +668,949 @@ impl<'a> ShelfOps for SqliteRepository<'a> {
             .map_err(FicflowError::Database)
     }
 }
+
+/// `kind` and `auto_criteria` as stored in the `shelf` table.
+fn shelf_kind_columns(kind: &ShelfKind) -> Result<(&'static str, Option<String>), FicflowError> {
+    Ok(match kind {
+        ShelfKind::Normal => ("normal", None),
+        ShelfKind::Auto(criteria) => ("auto", Some(serde_json::to_string(criteria)?)),
+    })
+}
+
+const SAVED_SEARCH_COLUMNS: &str = "id, name, query, scope, sort, position, created_at";
+
+impl<'a> SavedSearchOps for SqliteRepository<'a> {
+    fn create_saved_search(
+        &self,
+        name: &str,
+        query: &str,
+        scope: SearchScope,
+        sort: SortPref,
+    ) -> Result<SavedSearch, FicflowError> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(FicflowError::InvalidInput(
+                "saved search name must not be empty".into(),
+            ));
+        }
+        if let SearchScope::Shelf(shelf_id) = scope {
+            self.ensure_shelf_exists(shelf_id)?;
+        }
+
+        let position: u32 = self.conn.query_row(
+            "SELECT COUNT(*) FROM saved_search WHERE deleted_at IS NULL",
+            [],
+            |r| r.get(0),
+        )?;
+        let created_at = Utc::now();
+        self.conn.execute(
+            "INSERT INTO saved_search (name, query, scope, sort, position, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                trimmed,
+                query.trim(),
+                serde_json::to_string(&scope)?,
+                serde_json::to_string(&sort)?,
+                position,
+                created_at.to_rfc3339()
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid() as u64;
+        Ok(SavedSearch {
+            id,
+            name: trimmed.to_string(),
+            query: query.trim().to_string(),
+            scope,
+            sort,
+            position,
+            created_at,
+        })
+    }
+
+    fn delete_saved_search(&self, saved_search_id: u64) -> Result<(), FicflowError> {
+        let now = Utc::now().to_rfc3339();
+        let rows_affected = self.conn.execute(
+            "UPDATE saved_search SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+            params![saved_search_id, now],
+        )?;
+        if rows_affected == 0 {
+            return Err(FicflowError::SavedSearchNotFound { saved_search_id });
+        }
+        // Close the gap so positions stay 0..n.
+        let ids: Vec<u64> = self
+            .list_saved_searches()?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        self.renumber_saved_searches(&ids)
+    }
+
+    fn rename_saved_search(
+        &self,
+        saved_search_id: u64,
+        new_name: &str,
+    ) -> Result<SavedSearch, FicflowError> {
+        let trimmed = new_name.trim();
+        if trimmed.is_empty() {
+            return Err(FicflowError::InvalidInput(
+                "saved search name must not be empty".into(),
+            ));
+        }
+        let rows_affected = self.conn.execute(
+            "UPDATE saved_search SET name = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![trimmed, saved_search_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(FicflowError::SavedSearchNotFound { saved_search_id });
+        }
+        self.get_saved_search_by_id(saved_search_id)
+    }
+
+    fn move_saved_search(
+        &self,
+        saved_search_id: u64,
+        new_position: u32,
+    ) -> Result<SavedSearch, FicflowError> {
+        let mut ids: Vec<u64> = self
+            .list_saved_searches()?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        let current = ids
+            .iter()
+            .position(|id| *id == saved_search_id)
+            .ok_or(FicflowError::SavedSearchNotFound { saved_search_id })?;
+        ids.remove(current);
+        let target = (new_position as usize).min(ids.len());
+        ids.insert(target, saved_search_id);
+        self.renumber_saved_searches(&ids)?;
+        self.get_saved_search_by_id(saved_search_id)
+    }
+
+    fn list_saved_searches(&self) -> Result<Vec<SavedSearch>, FicflowError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SAVED_SEARCH_COLUMNS} FROM saved_search \
+             WHERE deleted_at IS NULL \
+             ORDER BY position, id"
+        ))?;
+        let rows = stmt.query_map([], row_to_saved_search)?;
+        let searches = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(searches)
+    }
+
+    fn get_saved_search_by_id(&self, saved_search_id: u64) -> Result<SavedSearch, FicflowError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SAVED_SEARCH_COLUMNS} FROM saved_search \
+             WHERE id = ?1 AND deleted_at IS NULL"
+        ))?;
+        stmt.query_row(params![saved_search_id], row_to_saved_search)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    FicflowError::SavedSearchNotFound { saved_search_id }
+                }
+                other => FicflowError::Database(other),
+            })
+    }
+}
+
+impl<'a> SqliteRepository<'a> {
+    /// Rewrites `position` so the given ids sit at 0, 1, 2, … in order.
+    fn renumber_saved_searches(&self, ordered_ids: &[u64]) -> Result<(), FicflowError> {
+        let mut stmt = self
+            .conn
+            .prepare("UPDATE saved_search SET position = ?2 WHERE id = ?1")?;
+        for (position, id) in ordered_ids.iter().enumerate() {
+            stmt.execute(params![id, position as u32])?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> TrashOps for SqliteRepository<'a> {
+    fn list_trashed_fics(&self) -> Result<Vec<TrashedFic>, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM fanfiction WHERE deleted_at IS NOT NULL \
+             ORDER BY deleted_at DESC, title",
+        )?;
+        let rows = stmt.query_map([], row_to_trashed_fic)?;
+        let fics = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(fics)
+    }
+
+    fn list_trashed_shelves(&self) -> Result<Vec<TrashedShelf>, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, parent_shelf_id, pinned, created_at, kind, auto_criteria, deleted_at \
+             FROM shelf WHERE deleted_at IS NOT NULL \
+             ORDER BY deleted_at DESC, name COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], row_to_trashed_shelf)?;
+        let shelves = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(shelves)
+    }
+
+    fn restore_fic(&self, fic_id: u64) -> Result<Fanfiction, FicflowError> {
+        let rows_affected = self.conn.execute(
+            "UPDATE fanfiction SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![fic_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(FicflowError::NotInTrash { fic_id });
+        }
+        self.get_fanfiction_by_id(fic_id)
+    }
+
+    fn restore_shelf(&self, shelf_id: u64) -> Result<Shelf, FicflowError> {
+        let parent: Option<u64> = self
+            .conn
+            .query_row(
+                "SELECT parent_shelf_id FROM shelf WHERE id = ?1 AND deleted_at IS NOT NULL",
+                params![shelf_id],
+                |r| r.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => FicflowError::ShelfNotInTrash { shelf_id },
+                other => FicflowError::Database(other),
+            })?;
+        // Live children were promoted away on delete, so the shelf comes
+        // back as a leaf and only needs room for itself.
+        let parent = match parent {
+            Some(parent) if self.can_hold_restored_shelf(parent)? => Some(parent),
+            _ => None,
+        };
+        self.conn.execute(
+            "UPDATE shelf SET deleted_at = NULL, parent_shelf_id = ?2 WHERE id = ?1",
+            params![shelf_id, parent],
+        )?;
+        self.get_shelf_by_id(shelf_id)
+    }
+
+    fn purge_fic(&self, fic_id: u64) -> Result<(), FicflowError> {
+        if !self.delete_trashed_fic_row(fic_id)? {
+            return Err(FicflowError::NotInTrash { fic_id });
+        }
+        self.prune_orphan_tags()
+    }
+
+    fn purge_shelf(&self, shelf_id: u64) -> Result<(), FicflowError> {
+        if !self.delete_trashed_shelf_row(shelf_id)? {
+            return Err(FicflowError::ShelfNotInTrash { shelf_id });
+        }
+        Ok(())
+    }
+
+    fn purge_trash(
+        &self,
+        deleted_before: Option<DateTime<Utc>>,
+    ) -> Result<PurgeSummary, FicflowError> {
+        let expired =
+            |deleted_at: DateTime<Utc>| deleted_before.is_none_or(|cutoff| deleted_at < cutoff);
+        let mut summary = PurgeSummary::default();
+        for trashed in self.list_trashed_fics()? {
+            if expired(trashed.deleted_at) && self.delete_trashed_fic_row(trashed.fic.id)? {
+                summary.fics += 1;
+            }
+        }
+        for trashed in self.list_trashed_shelves()? {
+            if expired(trashed.deleted_at) && self.delete_trashed_shelf_row(trashed.shelf.id)? {
+                summary.shelves += 1;
+            }
+        }
+        if summary.fics > 0 {
+            self.prune_orphan_tags()?;
+        }
+        Ok(summary)
+    }
+}
+
+impl<'a> SqliteRepository<'a> {
+    /// A live normal shelf with a free level below it.
+    fn can_hold_restored_shelf(&self, parent: u64) -> Result<bool, FicflowError> {
+        match self.get_shelf_by_id(parent) {
+            Ok(shelf) => Ok(matches!(shelf.kind, ShelfKind::Normal)
+                && self.shelf_depth(parent)? < MAX_SHELF_DEPTH as u32),
+            Err(FicflowError::ShelfNotFound { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Shelf memberships and tag links go with the row via `ON DELETE
+    /// CASCADE`; the FTS delete trigger drops its search entry.
+    fn delete_trashed_fic_row(&self, fic_id: u64) -> Result<bool, FicflowError> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM fanfiction WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![fic_id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Children trashed before their parent still point at it; they take
+    /// over its parent, the same promotion `delete_shelf` gives live ones.
+    fn delete_trashed_shelf_row(&self, shelf_id: u64) -> Result<bool, FicflowError> {
+        let trashed: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM shelf WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![shelf_id],
+            |r| r.get(0),
+        )?;
+        if !trashed {
+            return Ok(false);
+        }
+        self.conn.execute(
+            "UPDATE shelf SET parent_shelf_id = \
+                 (SELECT parent_shelf_id FROM shelf WHERE id = ?1) \
+             WHERE parent_shelf_id = ?1",
+            params![shelf_id],
+        )?;
+        self.conn
+            .execute("DELETE FROM shelf WHERE id = ?1", params![shelf_id])?;
+        Ok(true)
+    }
+
+    fn prune_orphan_tags(&self) -> Result<(), FicflowError> {
+        self.conn.execute(
+            "DELETE FROM tag WHERE NOT EXISTS (SELECT 1 FROM fic_tag WHERE fic_tag.tag_id = tag.id)",
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+impl<'a> TransferOps for SqliteRepository<'a> {
+    fn export_library(&self) -> Result<LibrarySnapshot, FicflowError> {
+        let mut stmt = self.conn.prepare("SELECT * FROM fanfiction ORDER BY id")?;
+        let fics = stmt
+            .query_map([], row_to_exported_fic)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, parent_shelf_id, pinned, created_at, kind, auto_criteria, deleted_at \
+             FROM shelf ORDER BY id",
+        )?;
+        let shelves = stmt
+            .query_map([], row_to_exported_shelf)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT fic_id, shelf_id, added_at FROM fic_shelf ORDER BY shelf_id, fic_id",
+        )?;
+        let memberships = stmt
+            .query_map([], row_to_exported_membership)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LibrarySnapshot {
+            schema_version: LIBRARY_SCHEMA_VERSION,
+            exported_at: Utc::now(),
+            fics,
+            shelves,
+            memberships,
+        })
+    }
+
+    fn import_library(
+        &self,
+        snapshot: &LibrarySnapshot,
+        mode: ImportMode,
+        dry_run: bool,
+    ) -> Result<ImportSummary, FicflowError> {
+        // Dropped without a commit on a dry run, which rolls it back.
+        let tx = self.conn.unchecked_transaction()?;
+        let mut summary = ImportSummary::default();
+
+        // The shelves are about to go; note where each shelf-scoped
+        // search pointed so it can follow its shelf across.
+        let mut scoped_searches = Vec::new();
+        if mode == ImportMode::Replace {
+            scoped_searches = self.shelf_scoped_searches()?;
+            summary.fics_removed =
+                self.conn
+                    .query_row("SELECT COUNT(*) FROM fanfiction", [], |r| r.get(0))?;
+            summary.shelves_removed =
+                self.conn
+                    .query_row("SELECT COUNT(*) FROM shelf", [], |r| r.get(0))?;
+            self.conn.execute_batch(
+                "DELETE FROM fic_shelf; DELETE FROM fanfiction; DELETE FROM shelf;",
+            )?;
+        }
+
+        for exported in &snapshot.fics {
+            let exists: bool = self.conn.query_row(
+                "SELECT COUNT(*) > 0 FROM fanfiction WHERE id = ?1",
+                params![exported.fic.id],
+                |r| r.get(0),
+            )?;
+            if exists {
+                summary.fics_skipped += 1;
+                continue;
+            }
+            self.insert_exported_fic(exported)?;
+            summary.fics_added += 1;
+        }
+
+        (
+            summary.shelves_added,
+            summary.shelves_matched,
+            summary.memberships_added,
+        ) = self.import_shelves(snapshot, mode)?;
+
+        for (saved_search_id, path) in scoped_searches {
+            let shelf_id = match path {
+                Some(path) => self.shelf_at_path(&path)?,
+                None => None,
+            };
+            let scope = shelf_id.map_or(SearchScope::AllFics, SearchScope::Shelf);
+            self.conn.execute(
+                "UPDATE saved_search SET scope = ?2 WHERE id = ?1",
+                params![saved_search_id, serde_json::to_string(&scope)?],
+            )?;
+        }
+
+        self.prune_orphan_tags()?;
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(summary)
+    }
+
+    fn merge_library(
+        &self,
+        snapshot: &LibrarySnapshot,
+        policy: MergePolicy,
+        dry_run: bool,
+    ) -> Result<MergeReport, FicflowError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut report = MergeReport::default();
+
+        for theirs in &snapshot.fics {
+            let mine = match self.conn.query_row(
+                "SELECT * FROM fanfiction WHERE id = ?1",
+                params![theirs.fic.id],
+                row_to_exported_fic,
+            ) {
+                Ok(mine) => mine,
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    self.insert_exported_fic(theirs)?;
+                    report.fics_added += 1;
+                    continue;
+                }
+                Err(e) => return Err(FicflowError::Database(e)),
+            };
+            let merged = merge_fic(&mine, theirs, policy);
+            report.conflicts.extend(merged.conflict);
+            if !merged.changed {
+                report.fics_unchanged += 1;
+                continue;
+            }
+            // Saving revives a trashed fic and stamps a fresh edit time;
+            // put both back to what the merge settled on.
+            self.save_fanfiction(&merged.fic)?;
+            self.conn.execute(
+                "UPDATE fanfiction SET deleted_at = ?2, user_updated_at = ?3 WHERE id = ?1",
+                params![
+                    merged.fic.id,
+                    mine.deleted_at.map(|d| d.to_rfc3339()),
+                    merged.user_updated_at.map(|d| d.to_rfc3339())
+                ],
+            )?;
+            report.fics_updated += 1;
+        }
+
+        (
+            report.shelves_added,
+            report.shelves_matched,
+            report.memberships_added,
+        ) = self.import_shelves(snapshot, ImportMode::Merge)?;
+
+        self.prune_orphan_tags()?;
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+}
+
+/// A shelf's name and its parents', top shelf first.
+type ShelfPath = Vec<String>;
+
+impl<'a> SqliteRepository<'a> {
+    /// Every saved search scoped to a shelf, trashed ones included, with
+    /// that shelf's path. The path is `None` when the shelf is already
+    /// gone.
+    fn shelf_scoped_searches(&self) -> Result<Vec<(u64, Option<ShelfPath>)>, FicflowError> {
+        let mut stmt = self.conn.prepare("SELECT id, scope FROM saved_search")?;
+        let scopes = stmt
+            .query_map([], |r| Ok((r.get::<_, u64>(0)?, r.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut scoped = Vec::new();
+        for (saved_search_id, scope) in scopes {
+            if let Ok(SearchScope::Shelf(shelf_id)) = serde_json::from_str(&scope) {
+                scoped.push((saved_search_id, self.shelf_path(shelf_id)?));
+            }
+        }
+        Ok(scoped)
+    }
+
+    fn shelf_path(&self, shelf_id: u64) -> Result<Option<ShelfPath>, FicflowError> {
+        let mut path = Vec::new();
+        let mut next = Some(shelf_id);
+        while let Some(id) = next {
+            let found = self.conn.query_row(
+                "SELECT name, parent_shelf_id FROM shelf WHERE id = ?1",
+                params![id],
+                |r| Ok((r.get::<_, String>(0)?, r.get::<_, Option<u64>>(1)?)),
+            );
+            match found {
+                Ok((name, parent)) => {
+                    path.push(name);
+                    next = parent;
+                }
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(FicflowError::Database(e)),
+            }
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    /// The shelf reached by following `path` down from the top level,
+    /// matching names as `find_matching_shelf` does.
+    fn shelf_at_path(&self, path: &[String]) -> Result<Option<u64>, FicflowError> {
+        let mut parent: Option<u64> = None;
+        for name in path {
+            let found = self.conn.query_row(
+                "SELECT id FROM shelf \
+                 WHERE name = ?1 COLLATE NOCASE AND parent_shelf_id IS ?2 \
+                 ORDER BY deleted_at IS NOT NULL, id LIMIT 1",
+                params![name, parent],
+                |r| r.get(0),
+            );
+            match found {
+                Ok(id) => parent = Some(id),
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(FicflowError::Database(e)),
+            }
+        }
+        Ok(parent)
+    }
+
+    /// Inserts a fic from a library file with its trash state and edit
+    /// time as the file has them.
+    fn insert_exported_fic(&self, exported: &ExportedFic) -> Result<(), FicflowError> {
+        self.save_fanfiction(&exported.fic)?;
+        self.conn.execute(
+            "UPDATE fanfiction SET deleted_at = ?2, user_updated_at = ?3 WHERE id = ?1",
+            params![
+                exported.fic.id,
+                exported.deleted_at.map(|d| d.to_rfc3339()),
+                exported.user_updated_at.map(|d| d.to_rfc3339())
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Brings in the file's shelves and memberships, for fics already
+    /// in the library. Returns how many shelves were added and matched
+    /// and how many memberships were added.
+    fn import_shelves(
+        &self,
+        snapshot: &LibrarySnapshot,
+        mode: ImportMode,
+    ) -> Result<(usize, usize, usize), FicflowError> {
+        let (mut added, mut matched, mut memberships) = (0, 0, 0);
+        // File shelf id -> library shelf id.
+        let mut shelf_ids: HashMap<u64, u64> = HashMap::new();
+        for exported in snapshot.shelves_parents_first()? {
+            let parent = exported
+                .shelf
+                .parent_shelf_id
+                .and_then(|p| shelf_ids.get(&p).copied());
+            let found = match mode {
+                ImportMode::Merge => self.find_matching_shelf(exported, parent)?,
+                ImportMode::Replace => None,
+            };
+            let id = match found {
+                Some(id) => {
+                    matched += 1;
+                    id
+                }
+                None => {
+                    added += 1;
+                    self.insert_exported_shelf(exported, parent, mode == ImportMode::Replace)?
+                }
+            };
+            shelf_ids.insert(exported.shelf.id, id);
+        }
+
+        for membership in &snapshot.memberships {
+            let Some(&shelf_id) = shelf_ids.get(&membership.shelf_id) else {
+                continue;
+            };
+            memberships += self.conn.execute(
+                "INSERT OR IGNORE INTO fic_shelf (fic_id, shelf_id, added_at) \
+                 SELECT ?1, ?2, ?3 \
+                 WHERE EXISTS (SELECT 1 FROM fanfiction WHERE id = ?1) \
+                   AND EXISTS (SELECT 1 FROM shelf WHERE id = ?2 AND kind = 'normal')",
+                params![
+                    membership.fic_id,
+                    shelf_id,
+                    membership.added_at.to_rfc3339()
+                ],
+            )?;
+        }
+        Ok((added, matched, memberships))
+    }
+
+    /// A shelf with the same name, parent, kind and trash state — the
+    /// one a merge should reuse instead of adding a duplicate.
+    fn find_matching_shelf(
+        &self,
+        exported: &ExportedShelf,
+        parent: Option<u64>,
+    ) -> Result<Option<u64>, FicflowError> {
+        let (kind_col, _) = shelf_kind_columns(&exported.shelf.kind)?;
+        let found = self.conn.query_row(
+            "SELECT id FROM shelf \
+             WHERE name = ?1 COLLATE NOCASE AND parent_shelf_id IS ?2 AND kind = ?3 \
+               AND (deleted_at IS NULL) = ?4 \
+             ORDER BY id LIMIT 1",
+            params![
+                exported.shelf.name,
+                parent,
+                kind_col,
+                exported.deleted_at.is_none()
+            ],
+            |r| r.get(0),
+        );
+        match found {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(FicflowError::Database(e)),
+        }
+    }
+
+    /// Inserts the shelf as exported, under `parent`. `keep_id` reuses
+    /// the file's id, which only a replace can do safely.
+    fn insert_exported_shelf(
+        &self,
+        exported: &ExportedShelf,
+        parent: Option<u64>,
+        keep_id: bool,
+    ) -> Result<u64, FicflowError> {
+        let shelf = &exported.shelf;
+        let (kind_col, criteria_col) = shelf_kind_columns(&shelf.kind)?;
+        self.conn.execute(
+            "INSERT INTO shelf \
+                 (id, name, created_at, parent_shelf_id, pinned, kind, auto_criteria, deleted_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                keep_id.then_some(shelf.id),
+                shelf.name,
+                shelf.created_at.to_rfc3339(),
+                parent,
+                shelf.pinned,
+                kind_col,
+                criteria_col,
+                exported.deleted_at.map(|d| d.to_rfc3339())
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+}
+
+/// Whether a column's text decodes as the type it's read as.
+type JsonCheck = fn(&str) -> bool;
+
+/// The `fanfiction` columns `row_to_fanfiction` decodes as JSON, each
+/// with its decode check and the empty value a repair writes.
+const FIC_JSON_COLUMNS: [(&str, JsonCheck, &str); 8] = [
+    ("authors", decodes::<Vec<String>>, "[]"),
+    ("categories", decodes::<Option<Vec<Categories>>>, "null"),
+    ("characters", decodes::<Option<Vec<String>>>, "null"),
+    ("fandoms", decodes::<Vec<String>>, "[]"),
+    ("relationships", decodes::<Option<Vec<String>>>, "null"),
+    ("tags", decodes::<Option<Vec<String>>>, "null"),
+    ("warnings", decodes::<Vec<ArchiveWarnings>>, "[]"),
+    ("personal_tags", decodes::<Vec<String>>, "[]"),
+];
+
+fn decodes<T: serde::de::DeserializeOwned>(json: &str) -> bool {
+    serde_json::from_str::<T>(json).is_ok()
+}
+
+/// A non-text value reads as `None`, the same as NULL: neither decodes.
+fn text_column(row: &rusqlite::Row, idx: usize) -> Option<String> {
+    row.get::<_, Option<String>>(idx).ok().flatten()
+}
+
+impl<'a> HealthOps for SqliteRepository<'a> {
+    fn check_library(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let damage = stmt
+            .query_map([], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if damage != ["ok"] {
+            // The other checks read rows a damaged file may not give back.
+            return Ok(damage.into_iter().map(LibraryIssue::Corruption).collect());
+        }
+
+        let mut issues = self.broken_references()?;
+        issues.extend(self.bad_fic_json()?);
+        issues.extend(self.bad_saved_search_json()?);
+        issues.extend(self.bad_auto_criteria()?);
+        issues.extend(self.shelf_tree_issues()?);
+        Ok(issues)
+    }
+
+    fn repair_issue(&self, issue: &LibraryIssue) -> Result<(), FicflowError> {
+        match issue {
+            LibraryIssue::Corruption(_) => {
+                return Err(FicflowError::InvalidInput(
+                    "a damaged database can't be repaired in place; restore a backup".into(),
+                ));
+            }
+            LibraryIssue::BrokenReference { table, rowid, .. } => {
+                self.conn.execute(
+                    &format!(
+                        "DELETE FROM \"{}\" WHERE rowid = ?1",
+                        table.replace('"', "\"\"")
+                    ),
+                    params![rowid],
+                )?;
+            }
+            LibraryIssue::DanglingMembership { fic_id, shelf_id } => {
+                self.conn.execute(
+                    "DELETE FROM fic_shelf WHERE fic_id = ?1 AND shelf_id = ?2",
+                    params![fic_id, shelf_id],
+                )?;
+            }
+            LibraryIssue::BadFicJson { fic_id, column } => {
+                let (column, _, empty) = FIC_JSON_COLUMNS
+                    .iter()
+                    .find(|(name, ..)| name == column)
+                    .ok_or_else(|| {
+                        FicflowError::InvalidInput(format!("unknown fic column '{}'", column))
+                    })?;
+                self.conn.execute(
+                    &format!("UPDATE fanfiction SET {} = ?2 WHERE id = ?1", column),
+                    params![fic_id, empty],
+                )?;
+            }
+            LibraryIssue::BadSavedSearchJson {
+                saved_search_id,
+                column,
+            } => {
+                let (column, default) = match *column {
+                    "scope" => ("scope", serde_json::to_string(&SearchScope::AllFics)?),
+                    "sort" => ("sort", serde_json::to_string(&SortPref::default())?),
+                    other => {
+                        return Err(FicflowError::InvalidInput(format!(
+                            "unknown saved search column '{}'",
+                            other
+                        )));
+                    }
+                };
+                self.conn.execute(
+                    &format!("UPDATE saved_search SET {} = ?2 WHERE id = ?1", column),
+                    params![saved_search_id, default],
+                )?;
+            }
+            LibraryIssue::BadAutoCriteria { shelf_id, .. } => {
+                let json: Option<String> = self.conn.query_row(
+                    "SELECT auto_criteria FROM shelf WHERE id = ?1",
+                    params![shelf_id],
+                    |r| r.get(0),
+                )?;
+                let mut criteria: AutoShelfCriteria = json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+                criteria.clauses.retain(|c| !c.is_blank());
+                self.conn.execute(
+                    "UPDATE shelf SET auto_criteria = ?2 WHERE id = ?1",
+                    params![shelf_id, serde_json::to_string(&criteria)?],
+                )?;
+            }
+            LibraryIssue::MissingParent { shelf_id, .. }
+            | LibraryIssue::ShelfCycle { shelf_id, .. }
+            | LibraryIssue::ShelfTooDeep { shelf_id, .. } => {
+                self.conn.execute(
+                    "UPDATE shelf SET parent_shelf_id = NULL WHERE id = ?1",
+                    params![shelf_id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> SqliteRepository<'a> {
+    /// `foreign_key_check` findings, except for shelf memberships and
+    /// shelf parents, which get their own issues.
+    fn broken_references(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let mut stmt = self.conn.prepare("PRAGMA foreign_key_check")?;
+        let violations = stmt
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, Option<i64>>(1)?,
+                    r.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut issues: Vec<LibraryIssue> = violations
+            .into_iter()
+            .filter(|(table, ..)| table != "fic_shelf" && table != "shelf")
+            .filter_map(|(table, rowid, parent)| {
+                rowid.map(|rowid| LibraryIssue::BrokenReference {
+                    table,
+                    rowid,
+                    parent,
+                })
+            })
+            .collect();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT fic_id, shelf_id FROM fic_shelf \
+             WHERE fic_id NOT IN (SELECT id FROM fanfiction) \
+                OR shelf_id NOT IN (SELECT id FROM shelf) \
+             ORDER BY fic_id, shelf_id",
+        )?;
+        let dangling = stmt.query_map([], |r| {
+            Ok(LibraryIssue::DanglingMembership {
+                fic_id: r.get(0)?,
+                shelf_id: r.get(1)?,
+            })
+        })?;
+        for issue in dangling {
+            issues.push(issue?);
+        }
+        Ok(issues)
+    }
+
+    fn bad_fic_json(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let columns: Vec<&str> = FIC_JSON_COLUMNS.iter().map(|(name, ..)| *name).collect();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, {} FROM fanfiction ORDER BY id",
+            columns.join(", ")
+        ))?;
+        let mut rows = stmt.query([])?;
+        let mut issues = Vec::new();
+        while let Some(row) = rows.next()? {
+            let fic_id: u64 = row.get(0)?;
+            for (idx, (column, decodes, _)) in FIC_JSON_COLUMNS.iter().enumerate() {
+                if !text_column(row, idx + 1).is_some_and(|json| decodes(&json)) {
+                    issues.push(LibraryIssue::BadFicJson { fic_id, column });
+                }
+            }
+        }
+        Ok(issues)
+    }
+
+    fn bad_saved_search_json(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, scope, sort FROM saved_search ORDER BY id")?;
+        let mut rows = stmt.query([])?;
+        let mut issues = Vec::new();
+        while let Some(row) = rows.next()? {
+            let saved_search_id: u64 = row.get(0)?;
+            if !text_column(row, 1).is_some_and(|json| decodes::<SearchScope>(&json)) {
+                issues.push(LibraryIssue::BadSavedSearchJson {
+                    saved_search_id,
+                    column: "scope",
+                });
+            }
+            if !text_column(row, 2).is_some_and(|json| decodes::<SortPref>(&json)) {
+                issues.push(LibraryIssue::BadSavedSearchJson {
+                    saved_search_id,
+                    column: "sort",
+                });
+            }
+        }
+        Ok(issues)
+    }
+
+    fn bad_auto_criteria(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, auto_criteria FROM shelf WHERE kind = 'auto' ORDER BY id")?;
+        let mut rows = stmt.query([])?;
+        let mut issues = Vec::new();
+        while let Some(row) = rows.next()? {
+            let valid = text_column(row, 2)
+                .and_then(|json| serde_json::from_str::<AutoShelfCriteria>(&json).ok())
+                .is_some_and(|criteria| !criteria.clauses.iter().any(|c| c.is_blank()));
+            if !valid {
+                issues.push(LibraryIssue::BadAutoCriteria {
+                    shelf_id: row.get(0)?,
+                    name: row.get(1)?,
+                });
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Missing parents, loops and over-deep nesting, walking the parent
+    /// links of every shelf row the way `shelf_depth` does.
+    fn shelf_tree_issues(&self) -> Result<Vec<LibraryIssue>, FicflowError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, parent_shelf_id, deleted_at IS NULL FROM shelf ORDER BY id",
+        )?;
+        let shelves = stmt
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, u64>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, Option<u64>>(2)?,
+                    r.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let parent_of: HashMap<u64, Option<u64>> = shelves
+            .iter()
+            .map(|(id, _, parent, _)| (*id, *parent))
+            .collect();
+
+        // Depth of each shelf whose ancestry ends at a root or a missing
+        // row; shelves in or under a loop have none.
+        let mut depths: HashMap<u64, u32> = HashMap::new();
+        let mut issues = Vec::new();
+        for (id, name, parent, live) in &shelves {
+            if *live && parent.is_some_and(|p| !parent_of.contains_key(&p)) {
+                issues.push(LibraryIssue::MissingParent {
+                    shelf_id: *id,
+                    name: name.clone(),
+                });
+            }
+            let mut chain = vec![*id];
+            let mut next = *parent;
+            let mut looped = false;
+            while let Some(ancestor) = next.filter(|a| parent_of.contains_key(a)) {
+                if chain.contains(&ancestor) {
+                    looped = true;
+                    if ancestor == *id && chain.iter().min() == Some(id) {
+                        issues.push(LibraryIssue::ShelfCycle {
+                            shelf_id: *id,
+                            name: name.clone(),
+                        });
+                    }
+                    break;
+                }
+                chain.push(ancestor);
+                next = parent_of[&ancestor];
+            }
+            if !looped {
+                depths.insert(*id, chain.len() as u32);
+            }
+        }
+
+        let live: HashMap<u64, bool> = shelves.iter().map(|(id, _, _, l)| (*id, *l)).collect();
+        let too_deep = |id: &u64| depths.get(id).is_some_and(|d| *d > MAX_SHELF_DEPTH as u32);
+        for (id, name, parent, is_live) in &shelves {
+            let parent_reported =
+                parent.is_some_and(|p| live.get(&p) == Some(&true) && too_deep(&p));
+            if *is_live && too_deep(id) && !parent_reported {
+                issues.push(LibraryIssue::ShelfTooDeep {
+                    shelf_id: *id,
+                    name: name.clone(),
+                    depth: depths[id],
+                });
+            }
+        }
+        Ok(issues)
+    }
+}
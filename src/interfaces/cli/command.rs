@@ -1,83 +1,408 @@
+use super::completion::{fic_candidates, setting_candidates, shelf_candidates, trash_candidates};
+use super::output::OutputFormat;
+use crate::application::export_fic_table::DEFAULT_LIST_SEPARATOR;
+use crate::interfaces::gui::Setting;
+use crate::interfaces::native_host::manifest::Browser;
+use crate::interfaces::server::DEFAULT_BIND;
 use crate::interfaces::utils::url_parser;
 use clap::{Arg, Command, value_parser};
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum CliCommand {
-    Add { fic_id: u64 },
-    Delete { fic_id: u64 },
-    Get { fic_id: u64 },
-    List,
+    Add {
+        fic_id: u64,
+    },
+    /// `-` reads standard input.
+    AddMany {
+        source: PathBuf,
+        shelf: Option<String>,
+    },
+    Delete {
+        fic: String,
+    },
+    Get {
+        fic: String,
+    },
+    Open {
+        fic: String,
+        keep_status: bool,
+    },
+    List(ListArgs),
+    SearchLocal {
+        query: String,
+    },
+    /// Search AO3 itself, by tag with `tag`.
+    Search {
+        terms: String,
+        tag: bool,
+        page: u32,
+        hide_tracked: bool,
+    },
     Wipe,
-    UpdateChapter { fic_id: u64, chapter: u32 },
-    UpdateStatus { fic_id: u64, status: String },
-    UpdateReadCount { fic_id: u64, read_count: u32 },
-    UpdateRating { fic_id: u64, rating: String },
-    UpdateNote { fic_id: u64, note: Option<String> },
+    UpdateChapter {
+        fic: String,
+        chapter: u32,
+    },
+    UpdateStatus {
+        fic: String,
+        status: String,
+    },
+    UpdateReadCount {
+        fic: String,
+        read_count: u32,
+    },
+    UpdateRating {
+        fic: String,
+        rating: String,
+    },
+    UpdateNote {
+        fic: String,
+        note: Option<String>,
+    },
     Shelf(ShelfCommand),
+    SavedSearch(SavedSearchCommand),
+    Trash(TrashCommand),
+    Export(ExportArgs),
+    Import(ImportArgs),
+    Merge {
+        path: PathBuf,
+        policy: String,
+        dry_run: bool,
+    },
+    Backup(BackupCommand),
+    Doctor {
+        fix: bool,
+    },
+    Config(ConfigCommand),
+    Serve {
+        bind: String,
+    },
+    NativeHost(NativeHostCommand),
+    UrlHandler {
+        install: bool,
+    },
+    Tui,
+}
+
+impl CliCommand {
+    /// Whether the command prints its result as JSON under `--output
+    /// json|ndjson`. Only the ones that run until stopped or set up the
+    /// desktop don't.
+    pub fn has_machine_output(&self) -> bool {
+        !matches!(
+            self,
+            CliCommand::Serve { .. }
+                | CliCommand::NativeHost(_)
+                | CliCommand::UrlHandler { .. }
+                | CliCommand::Tui
+        )
+    }
+
+    /// Whether the daily backup and the trash's auto-purge run before
+    /// the command, as they do when a window opens. Commands that only
+    /// read, and hosts that run until stopped, leave the library as is.
+    pub fn runs_housekeeping(&self) -> bool {
+        self.changes_library() || matches!(self, CliCommand::Add { .. })
+    }
+
+    /// Whether the command may write to the library, and so is refused
+    /// while a window has it open. A wrong `true` refuses a command that
+    /// would have been harmless.
+    pub fn changes_library(&self) -> bool {
+        match self {
+            CliCommand::Get { .. }
+            | CliCommand::List(_)
+            | CliCommand::SearchLocal { .. }
+            | CliCommand::Search { .. }
+            | CliCommand::Export(_)
+            | CliCommand::SavedSearch(_)
+            | CliCommand::Backup(_)
+            | CliCommand::UrlHandler { .. } => false,
+            // Setting or resetting the library's path moves the library.
+            CliCommand::Config(ConfigCommand::Set { key, .. }) => is_library_path(key),
+            CliCommand::Config(ConfigCommand::Reset { key }) => {
+                key.as_deref().is_none_or(is_library_path)
+            }
+            CliCommand::Config(_) => false,
+            // Handed to the window when one is open.
+            CliCommand::Add { .. } => false,
+            // They run alongside the window and ask it to reload after
+            // each write.
+            CliCommand::Serve { .. } | CliCommand::NativeHost(_) => false,
+            CliCommand::Shelf(sub) => {
+                !matches!(sub, ShelfCommand::List | ShelfCommand::Show { .. })
+            }
+            CliCommand::Trash(sub) => !matches!(sub, TrashCommand::List),
+            CliCommand::Import(args) => !args.dry_run,
+            CliCommand::Merge { dry_run, .. } => !dry_run,
+            CliCommand::Doctor { fix } => *fix,
+            _ => true,
+        }
+    }
+}
+
+fn is_library_path(key: &str) -> bool {
+    Setting::from_key(key).is_ok_and(|setting| setting == Setting::LibraryPath)
+}
+
+#[derive(Debug, Default)]
+pub struct ListArgs {
+    pub status: Option<String>,
+    pub shelf: Option<String>,
+    pub fandom: Option<String>,
+    pub tag: Option<String>,
+    pub complete: Option<bool>,
+    pub min_words: Option<u32>,
+    /// The title, ascending, when `None`.
+    pub sort: Option<String>,
+    /// The default list columns when `None`.
+    pub columns: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug)]
+pub struct ExportArgs {
+    pub format: String,
+    /// Standard output when `None`.
+    pub output: Option<PathBuf>,
+    /// The rest only apply to the CSV/TSV formats.
+    pub columns: String,
+    pub separator: String,
+    pub status: Option<String>,
+    pub shelf: Option<String>,
+    pub search: Option<String>,
+    pub sort: String,
+}
+
+#[derive(Debug)]
+pub struct ImportArgs {
+    pub path: PathBuf,
+    pub source: String,
+    pub mode: String,
+    pub dry_run: bool,
+    /// The rest only apply to Calibre libraries.
+    pub on_conflict: String,
+    pub read_column: Option<String>,
+    pub rating_column: Option<String>,
+    pub url_column: Option<String>,
+    pub no_fetch: bool,
+    /// Bookmarks and Firefox only.
+    pub no_shelves: bool,
 }
 
 #[derive(Debug)]
 pub enum ShelfCommand {
-    Create { name: String, parent: Option<u64> },
-    Delete { shelf_id: u64 },
-    Rename { shelf_id: u64, new_name: String },
-    Move { shelf_id: u64, parent: Option<u64> },
-    Pin { shelf_id: u64 },
-    Unpin { shelf_id: u64 },
+    Create {
+        name: String,
+        parent: Option<String>,
+    },
+    Delete {
+        shelf: String,
+    },
+    Rename {
+        shelf: String,
+        new_name: String,
+    },
+    Move {
+        shelf: String,
+        parent: Option<String>,
+    },
+    Pin {
+        shelf: String,
+    },
+    Unpin {
+        shelf: String,
+    },
+    List,
+    Add {
+        fic: String,
+        shelf: String,
+    },
+    Remove {
+        fic: String,
+        shelf: String,
+    },
+    Show {
+        shelf: String,
+    },
+}
+
+#[derive(Debug)]
+pub enum SavedSearchCommand {
+    List,
+    Run { saved_search_id: u64 },
+}
+
+#[derive(Debug)]
+pub enum TrashCommand {
+    List,
+    RestoreFic { fic: String },
+    RestoreShelf { shelf: String },
+    PurgeFic { fic: String },
+    PurgeShelf { shelf: String },
+    Empty { older_than_days: Option<u32> },
+}
+
+#[derive(Debug)]
+pub enum BackupCommand {
+    Create,
+    List,
+    /// The configured retention when `None`.
+    Prune {
+        keep: Option<usize>,
+    },
+}
+
+#[derive(Debug)]
+pub enum ConfigCommand {
+    Get {
+        key: String,
+    },
+    Set {
+        key: String,
+        value: String,
+    },
     List,
-    Add { fic_id: u64, shelf_id: u64 },
-    Remove { fic_id: u64, shelf_id: u64 },
-    Show { shelf_id: u64 },
+    Path,
+    /// Every setting when `None`.
+    Reset {
+        key: Option<String>,
+    },
 }
 
-pub fn parse_cli_commands() -> CliCommand {
-    let matches = Command::new("FicFlow")
+#[derive(Debug)]
+pub enum NativeHostCommand {
+    /// Started by the browser, which talks to it over stdin/stdout.
+    Run,
+    Manifest {
+        browser: String,
+        extension_id: String,
+        install: bool,
+    },
+}
+
+/// What `--output` scripts can rely on and how to turn on completion,
+/// shown under `--help`.
+const AFTER_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Any other failure
+  2  Invalid input or usage
+  3  Fic, shelf or saved search not found, or not in the trash
+  4  Conflicts with the library (already added, shelf nesting), or a
+     Ficflow window has it open
+  5  AO3 unreachable or its page unreadable
+  6  Library or file couldn't be read or written
+
+With a Ficflow window open on the library, add hands the fic to that
+window and every other change is refused; make it in the window, or
+close the window first.
+
+With --output json or ndjson, errors are printed to standard error as
+{\"error\": {\"code\", \"message\", \"exit_code\"}}.
+
+Shell completion, with shelf names and fic titles from your library:
+  bash  source <(COMPLETE=bash ficflow)    (in ~/.bashrc)
+  zsh   source <(COMPLETE=zsh ficflow)     (in ~/.zshrc)
+  fish  COMPLETE=fish ficflow | source     (in config.fish)";
+
+const FIC_HELP: &str = "The fanfiction: its ID, AO3 URL, or the start of its title";
+const SHELF_HELP: &str = "The shelf: its ID, name, or Parent/Child path";
+
+pub fn parse_cli_commands() -> (OutputFormat, CliCommand) {
+    let matches = cli().get_matches();
+    let output = matches
+        .get_one::<String>("output")
+        .and_then(|name| OutputFormat::parse(name))
+        .expect("output has a default");
+    (output, command_from_matches(&matches))
+}
+
+/// Every subcommand and argument, also what shell completion walks.
+pub fn cli() -> Command {
+    Command::new("FicFlow")
+        .after_help(AFTER_HELP)
+        .arg(Arg::new("output").long("output").value_name("FORMAT").value_parser(OutputFormat::NAMES).default_value("table").help("How to print results: table for people, or json / ndjson for scripts (every command but serve, native-host, tui and url-handler)"))
         .subcommand(
             Command::new("add")
-                .about("Add a fanfiction to the database")
-                .arg(Arg::new("fic-id").required(true).index(1).help("The ID or URL of the fanfiction (e.g. 12345678, https://archiveofourown.org/works/12345678)")),
+                .about("Add a fanfiction to the database, or every one linked in a file")
+                .arg(Arg::new("fic-id").required_unless_present("from-file").conflicts_with("from-file").index(1).help("The ID or URL of the fanfiction (e.g. 12345678, https://archiveofourown.org/works/12345678)"))
+                .arg(Arg::new("from-file").long("from-file").value_name("PATH").value_parser(value_parser!(PathBuf)).help("Add every AO3 work linked in this text file, skipping ones already in the library; '-' reads standard input"))
+                .arg(Arg::new("shelf").long("shelf").add(shelf_candidates()).requires("from-file").help("Put the fics added from the file on this shelf")),
         )
         .subcommand(
             Command::new("delete")
                 .about("Delete a fanfiction from the database")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction")),
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP)),
         )
         .subcommand(
             Command::new("get")
                 .about("Get detailed information about a specific fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction")),
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP)),
+        )
+        .subcommand(
+            Command::new("open")
+                .about("Open a fanfiction in the browser at the chapter to read next")
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
+                .arg(Arg::new("keep-status").long("keep-status").action(clap::ArgAction::SetTrue).help("Don't mark a planned or paused fic In Progress")),
         )
         .subcommand(
             Command::new("chapter")
                 .about("Update the last chapter read for a fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction"))
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
                 .arg(Arg::new("chapter").required(true).index(2).value_parser(value_parser!(u32)).help("The chapter number you've read up to")),
         )
         .subcommand(
             Command::new("status")
                 .about("Update the reading status of a fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction"))
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
                 .arg(Arg::new("status").required(true).index(2).help("The new reading status (inprogress, read, plantoread, paused, abandoned)")),
         )
         .subcommand(
             Command::new("reads")
                 .about("Update the read count of a fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction"))
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
                 .arg(Arg::new("count").required(true).index(2).value_parser(value_parser!(u32)).help("The new read count")),
         )
         .subcommand(
             Command::new("rating")
                 .about("Update the user rating of a fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction"))
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
                 .arg(Arg::new("rating").required(true).index(2).help("The new rating (1-5, or 'one' through 'five', or 'none' to remove)")),
         )
         .subcommand(
             Command::new("note")
                 .about("Add or remove a personal note for a fanfiction")
-                .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("The ID of the fanfiction"))
+                .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
                 .arg(Arg::new("note").required(false).index(2).help("The personal note text (omit to remove note)")),
         )
-        .subcommand(Command::new("list").about("List all stored fanfictions"))
+        .subcommand(
+            Command::new("list")
+                .about("List stored fanfictions, optionally filtered, sorted and trimmed to chosen columns")
+                .arg(Arg::new("status").long("status").help("Only fics with this reading status"))
+                .arg(Arg::new("shelf").long("shelf").add(shelf_candidates()).help("Only fics on this shelf (or shelves nested under it)"))
+                .arg(Arg::new("fandom").long("fandom").help("Only fics in this fandom (exact name, any case)"))
+                .arg(Arg::new("tag").long("tag").help("Only fics with this additional tag (exact name, any case)"))
+                .arg(Arg::new("complete").long("complete").num_args(0..=1).require_equals(true).default_missing_value("true").value_parser(clap::builder::BoolishValueParser::new()).help("Only complete fics; --complete=no for works in progress"))
+                .arg(Arg::new("min-words").long("min-words").value_parser(value_parser!(u32)).help("Only fics with at least this many words"))
+                .arg(Arg::new("sort").long("sort").help("Sort column, optionally with :asc or :desc (e.g. words:desc); title by default"))
+                .arg(Arg::new("columns").long("columns").short('c').help("Comma-separated columns to show after the ID: title, author, fandom, pairing, ao3-rating, warnings, status, complete, last-chapter, words, kudos, hits, rating, reads, language, published, updated"))
+                .arg(Arg::new("limit").long("limit").value_parser(value_parser!(u32)).help("Show at most this many fics")),
+        )
+        .subcommand(
+            Command::new("search-local")
+                .about("Full-text search your library (titles, summaries, tags, characters, relationships, notes)")
+                .arg(Arg::new("text").required(true).num_args(1..).help("Words to search for; every word must match")),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("Search AO3 for works to add, marking the ones already in your library")
+                .arg(Arg::new("terms").required(true).num_args(1..).help("Words to search AO3 for, or a tag's name with --tag"))
+                .arg(Arg::new("tag").long("tag").action(clap::ArgAction::SetTrue).help("List the works filed under this tag, newest first, instead of searching"))
+                .arg(Arg::new("page").long("page").default_value("1").value_parser(value_parser!(u32).range(1..)).help("Which page of 20 results to show"))
+                .arg(Arg::new("hide-tracked").long("hide-tracked").action(clap::ArgAction::SetTrue).help("Leave out works already in your library")),
+        )
         .subcommand(Command::new("wipe").about("Wipe the database (removes all fanfictions)"))
         .subcommand(
             Command::new("shelf")
@@ -88,57 +413,202 @@ pub fn parse_cli_commands() -> CliCommand {
                     Command::new("create")
                         .about("Create a new shelf")
                         .arg(Arg::new("name").required(true).index(1).help("Shelf name"))
-                        .arg(Arg::new("parent").long("parent").short('p').value_parser(value_parser!(u64)).help("Parent shelf ID to nest the new shelf under")),
+                        .arg(Arg::new("parent").long("parent").short('p').add(shelf_candidates()).help("Parent shelf to nest the new shelf under: its ID, name or Parent/Child path")),
                 )
                 .subcommand(
                     Command::new("delete")
                         .about("Delete a shelf")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP)),
                 )
                 .subcommand(
                     Command::new("rename")
                         .about("Rename an existing shelf")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID"))
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP))
                         .arg(Arg::new("new-name").required(true).index(2).help("New shelf name")),
                 )
                 .subcommand(
                     Command::new("move")
                         .about("Move a shelf under another shelf (omit parent-id to move to top level)")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID"))
-                        .arg(Arg::new("parent-id").required(false).index(2).value_parser(value_parser!(u64)).help("New parent shelf ID (omit for top level)")),
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP))
+                        .arg(Arg::new("parent").required(false).index(2).add(shelf_candidates()).help("New parent shelf: its ID, name or Parent/Child path (omit for top level)")),
                 )
                 .subcommand(
                     Command::new("pin")
                         .about("Pin a shelf to the top of its siblings")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP)),
                 )
                 .subcommand(
                     Command::new("unpin")
                         .about("Unpin a shelf")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP)),
                 )
                 .subcommand(Command::new("list").about("List all shelves"))
                 .subcommand(
                     Command::new("add")
                         .about("Add a fanfiction to a shelf")
-                        .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("Fanfiction ID"))
-                        .arg(Arg::new("shelf-id").required(true).index(2).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
+                        .arg(Arg::new("shelf").required(true).index(2).add(shelf_candidates()).help(SHELF_HELP)),
                 )
                 .subcommand(
                     Command::new("remove")
                         .about("Remove a fanfiction from a shelf")
-                        .arg(Arg::new("fic-id").required(true).index(1).value_parser(value_parser!(u64)).help("Fanfiction ID"))
-                        .arg(Arg::new("shelf-id").required(true).index(2).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("fic").required(true).index(1).add(fic_candidates()).help(FIC_HELP))
+                        .arg(Arg::new("shelf").required(true).index(2).add(shelf_candidates()).help(SHELF_HELP)),
                 )
                 .subcommand(
                     Command::new("show")
                         .about("List the fanfictions in a shelf")
-                        .arg(Arg::new("shelf-id").required(true).index(1).value_parser(value_parser!(u64)).help("Shelf ID")),
+                        .arg(Arg::new("shelf").required(true).index(1).add(shelf_candidates()).help(SHELF_HELP)),
+                ),
+        )
+        .subcommand(
+            Command::new("saved-search")
+                .about("List and run saved searches (created from the GUI search bar)")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("list").about("List all saved searches"))
+                .subcommand(
+                    Command::new("run")
+                        .about("List the fanfictions matching a saved search, in its saved sort order")
+                        .arg(Arg::new("saved-search-id").required(true).index(1).value_parser(value_parser!(u64)).help("Saved search ID")),
+                ),
+        )
+        .subcommand(
+            Command::new("trash")
+                .about("List, restore or permanently delete deleted fanfictions and shelves")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("list").about("List everything in the trash"))
+                .subcommand(
+                    Command::new("restore")
+                        .about("Restore a fanfiction (with its shelves) or a shelf from the trash")
+                        .arg(Arg::new("id").required(true).index(1).add(trash_candidates()).help("The fanfiction's ID, AO3 URL or title, or with --shelf the shelf's ID, name or path"))
+                        .arg(Arg::new("shelf").long("shelf").action(clap::ArgAction::SetTrue).help("Restore a shelf instead of a fanfiction")),
+                )
+                .subcommand(
+                    Command::new("purge")
+                        .about("Permanently delete one item from the trash, or empty it (omit the ID)")
+                        .arg(Arg::new("id").required(false).index(1).add(trash_candidates()).help("The fanfiction's ID, AO3 URL or title, or with --shelf the shelf's ID, name or path (omit to empty the trash)"))
+                        .arg(Arg::new("shelf").long("shelf").action(clap::ArgAction::SetTrue).requires("id").help("Purge a shelf instead of a fanfiction"))
+                        .arg(Arg::new("older-than").long("older-than").value_name("DAYS").value_parser(value_parser!(u32)).conflicts_with("id").help("Only purge items deleted more than DAYS days ago")),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export the whole library (json), or a table of fics for spreadsheets (csv, tsv)")
+                .arg(Arg::new("format").long("format").short('f').default_value("json").help("Output format (json, csv, tsv)"))
+                .arg(Arg::new("output").long("output").short('o').value_parser(value_parser!(PathBuf)).help("File to write to (defaults to standard output)"))
+                .arg(Arg::new("columns").long("columns").short('c').default_value("title,author,status,last-chapter,rating,reads,updated").help("csv/tsv: comma-separated columns, library-table columns plus id, url, summary, tags, characters, categories, chapters, chapters-total, note, personal-tags, last-checked"))
+                .arg(Arg::new("separator").long("separator").default_value(DEFAULT_LIST_SEPARATOR).help("csv/tsv: goes between the values of multi-valued fields such as authors and tags"))
+                .arg(Arg::new("status").long("status").help("csv/tsv: only fics with this reading status"))
+                .arg(Arg::new("shelf").long("shelf").add(shelf_candidates()).help("csv/tsv: only fics on this shelf (or shelves nested under it)"))
+                .arg(Arg::new("search").long("search").help("csv/tsv: only fics matching this full-text search"))
+                .arg(Arg::new("sort").long("sort").default_value("title").help("csv/tsv: sort column, optionally with :asc or :desc (e.g. words:desc)")),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Import a library file written by `ficflow export`, or the AO3 works in a Calibre library, a bookmarks export or Firefox's history")
+                .arg(Arg::new("file").required(true).index(1).value_parser(value_parser!(PathBuf)).help("The exported library file, Calibre's metadata.db (or its library folder), a bookmarks .html, or a copy of Firefox's places.sqlite"))
+                .arg(Arg::new("from").long("from").default_value("json").help("What the file is: json (a ficflow export), calibre, bookmarks or firefox"))
+                .arg(Arg::new("mode").long("mode").short('m').default_value("merge").help("json: merge adds what's missing and keeps existing fics; replace drops the current library first"))
+                .arg(Arg::new("dry-run").long("dry-run").action(clap::ArgAction::SetTrue).help("Show what the import would do without changing anything"))
+                .arg(Arg::new("on-conflict").long("on-conflict").default_value("keep").help("calibre: for fics already in your library whose status or rating differ, keep yours or take theirs"))
+                .arg(Arg::new("read-column").long("read-column").value_name("COLUMN").help("calibre: custom column saying a book was read (defaults to #read when there is one)"))
+                .arg(Arg::new("rating-column").long("rating-column").value_name("COLUMN").help("calibre: custom column holding your rating (defaults to Calibre's own rating)"))
+                .arg(Arg::new("url-column").long("url-column").value_name("COLUMN").help("calibre: custom column holding the story URL, if it isn't in the identifiers"))
+                .arg(Arg::new("no-fetch").long("no-fetch").action(clap::ArgAction::SetTrue).help("calibre: don't fetch metadata from AO3 for the fics added"))
+                .arg(Arg::new("no-shelves").long("no-shelves").action(clap::ArgAction::SetTrue).help("bookmarks/firefox: don't put fics on shelves named after their bookmark folders")),
+        )
+        .subcommand(
+            Command::new("backup")
+                .about("Take, list or prune backups of the library, kept in a backups folder next to it")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("create").about("Back up the library now"))
+                .subcommand(Command::new("list").about("List the backups, newest first"))
+                .subcommand(
+                    Command::new("prune")
+                        .about("Delete all but the newest backups")
+                        .arg(Arg::new("keep").long("keep").value_name("COUNT").value_parser(value_parser!(usize)).help("How many of each kind (daily, exit, migration...) to keep (defaults to the configured retention)")),
+                ),
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge another ficflow library (.db) into this one, matching fics by AO3 ID and shelves by name")
+                .arg(Arg::new("file").required(true).index(1).value_parser(value_parser!(PathBuf)).help("The other library's .db file; it isn't changed"))
+                .arg(Arg::new("policy").long("policy").short('p').default_value("newest").help("When a fic's progress, rating, note or tags differ: newest (last edited wins), mine or theirs"))
+                .arg(Arg::new("dry-run").long("dry-run").action(clap::ArgAction::SetTrue).help("Show what the merge would do and its conflicts without changing anything")),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("Check the library file for damage, broken references, unreadable data and bad shelf nesting")
+                .arg(Arg::new("fix").long("fix").action(clap::ArgAction::SetTrue).help("Make the safe repairs, after backing the library up")),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Read or change the settings shared with the GUI's Settings page")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("get")
+                        .about("Print one setting's value")
+                        .arg(Arg::new("key").required(true).index(1).add(setting_candidates()).help("The setting, e.g. theme or text_zoom")),
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("Change a setting; setting library_path moves the library there")
+                        .arg(Arg::new("key").required(true).index(1).add(setting_candidates()).help("The setting, e.g. theme or text_zoom"))
+                        .arg(Arg::new("value").required(true).index(2).allow_hyphen_values(true).help("The new value; `ficflow config list` shows what each setting takes")),
+                )
+                .subcommand(Command::new("list").about("List every setting with its value and what it takes"))
+                .subcommand(Command::new("path").about("Print where the config file is"))
+                .subcommand(
+                    Command::new("reset")
+                        .about("Put a setting, or all of them, back to its default")
+                        .arg(Arg::new("key").index(1).add(setting_candidates()).help("The setting to reset; all of them when left out")),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Serve the library as a JSON API over HTTP, for browser extensions and other tools; requests need the token kept in the config folder")
+                .arg(Arg::new("bind").long("bind").value_name("HOST:PORT").default_value(DEFAULT_BIND).help("Address to listen on; use 0.0.0.0:PORT to reach it from other devices on the network")),
+        )
+        .subcommand(
+            Command::new("native-host")
+                .about("Talk to a browser extension over native messaging, so it can add works and sync reading progress; started by the browser")
+                .args_conflicts_with_subcommands(true)
+                .arg(Arg::new("browser-args").num_args(0..).trailing_var_arg(true).allow_hyphen_values(true).hide(true))
+                .subcommand(
+                    Command::new("manifest")
+                        .about("Print the host manifest a browser needs to start ficflow, or install it with --install")
+                        .arg(Arg::new("browser").long("browser").required(true).value_parser(Browser::NAMES).help("Which browser the manifest is for"))
+                        .arg(Arg::new("extension-id").long("extension-id").required(true).value_name("ID").help("The extension allowed to connect: name@example.org for Firefox, its 32-letter ID for the others"))
+                        .arg(Arg::new("install").long("install").action(clap::ArgAction::SetTrue).help("Write the manifest where the browser looks for it, with a launcher script in the config folder")),
                 ),
         )
-        .get_matches();
+        .subcommand(
+            Command::new("url-handler")
+                .about("Print the desktop entry that opens ficflow://add?url=... links in Ficflow, or install it with --install")
+                .arg(Arg::new("install").long("install").action(clap::ArgAction::SetTrue).help("Write the entry to the applications folder and make it the handler for ficflow:// links")),
+        )
+        .subcommand(Command::new("tui").about("Browse and edit the library in a keyboard-driven terminal interface"))
+}
+
+fn required(matches: &clap::ArgMatches, name: &str) -> String {
+    matches
+        .get_one::<String>(name)
+        .unwrap_or_else(|| panic!("{} is required", name))
+        .to_string()
+}
 
+fn command_from_matches(matches: &clap::ArgMatches) -> CliCommand {
     if let Some(matches) = matches.subcommand_matches("add") {
+        if let Some(source) = matches.get_one::<PathBuf>("from-file") {
+            return CliCommand::AddMany {
+                source: source.clone(),
+                shelf: matches.get_one::<String>("shelf").cloned(),
+            };
+        }
         let fic_id_input = matches
             .get_one::<String>("fic-id")
             .expect("fic-id or url is required");
@@ -148,68 +618,222 @@ pub fn parse_cli_commands() -> CliCommand {
             Ok(id) => CliCommand::Add { fic_id: id },
             Err(e) => {
                 eprintln!("Error: {}. Please provide a valid AO3 ID or URL.", e);
-                std::process::exit(1);
+                std::process::exit(2);
             }
         }
     } else if let Some(matches) = matches.subcommand_matches("delete") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
-        CliCommand::Delete { fic_id }
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
+        CliCommand::Delete { fic }
     } else if let Some(matches) = matches.subcommand_matches("get") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
-        CliCommand::Get { fic_id }
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
+        CliCommand::Get { fic }
+    } else if let Some(matches) = matches.subcommand_matches("open") {
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
+        CliCommand::Open {
+            fic,
+            keep_status: matches.get_flag("keep-status"),
+        }
     } else if let Some(matches) = matches.subcommand_matches("chapter") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
         let chapter = *matches
             .get_one::<u32>("chapter")
             .expect("chapter number is required");
-        CliCommand::UpdateChapter { fic_id, chapter }
+        CliCommand::UpdateChapter { fic, chapter }
     } else if let Some(matches) = matches.subcommand_matches("status") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
         let status = matches
             .get_one::<String>("status")
             .expect("status is required")
             .to_string();
-        CliCommand::UpdateStatus { fic_id, status }
+        CliCommand::UpdateStatus { fic, status }
     } else if let Some(matches) = matches.subcommand_matches("reads") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
         let read_count = *matches
             .get_one::<u32>("count")
             .expect("read count is required");
-        CliCommand::UpdateReadCount { fic_id, read_count }
+        CliCommand::UpdateReadCount { fic, read_count }
     } else if let Some(matches) = matches.subcommand_matches("rating") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
         let rating = matches
             .get_one::<String>("rating")
             .expect("rating is required")
             .to_string();
-        CliCommand::UpdateRating { fic_id, rating }
+        CliCommand::UpdateRating { fic, rating }
     } else if let Some(matches) = matches.subcommand_matches("note") {
-        let fic_id = *matches
-            .get_one::<u64>("fic-id")
-            .expect("fic-id is required");
+        let fic = matches
+            .get_one::<String>("fic")
+            .expect("fic is required")
+            .to_string();
         let note = matches.get_one::<String>("note").map(|s| s.to_string());
-        CliCommand::UpdateNote { fic_id, note }
-    } else if matches.subcommand_matches("list").is_some() {
-        CliCommand::List
+        CliCommand::UpdateNote { fic, note }
+    } else if let Some(matches) = matches.subcommand_matches("list") {
+        CliCommand::List(ListArgs {
+            status: matches.get_one::<String>("status").cloned(),
+            shelf: matches.get_one::<String>("shelf").cloned(),
+            fandom: matches.get_one::<String>("fandom").cloned(),
+            tag: matches.get_one::<String>("tag").cloned(),
+            complete: matches.get_one::<bool>("complete").copied(),
+            min_words: matches.get_one::<u32>("min-words").copied(),
+            sort: matches.get_one::<String>("sort").cloned(),
+            columns: matches.get_one::<String>("columns").cloned(),
+            limit: matches.get_one::<u32>("limit").copied(),
+        })
+    } else if let Some(matches) = matches.subcommand_matches("search-local") {
+        let query = matches
+            .get_many::<String>("text")
+            .expect("text is required")
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        CliCommand::SearchLocal { query }
+    } else if let Some(matches) = matches.subcommand_matches("search") {
+        let terms = matches
+            .get_many::<String>("terms")
+            .expect("terms is required")
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        CliCommand::Search {
+            terms,
+            tag: matches.get_flag("tag"),
+            page: *matches.get_one::<u32>("page").expect("page has a default"),
+            hide_tracked: matches.get_flag("hide-tracked"),
+        }
     } else if matches.subcommand_matches("wipe").is_some() {
         CliCommand::Wipe
     } else if let Some(shelf_matches) = matches.subcommand_matches("shelf") {
         CliCommand::Shelf(parse_shelf_subcommand(shelf_matches))
+    } else if let Some(saved_search_matches) = matches.subcommand_matches("saved-search") {
+        CliCommand::SavedSearch(parse_saved_search_subcommand(saved_search_matches))
+    } else if let Some(trash_matches) = matches.subcommand_matches("trash") {
+        CliCommand::Trash(parse_trash_subcommand(trash_matches))
+    } else if let Some(matches) = matches.subcommand_matches("export") {
+        let format = matches
+            .get_one::<String>("format")
+            .expect("format has a default")
+            .to_string();
+        let output = matches.get_one::<PathBuf>("output").cloned();
+        let columns = matches
+            .get_one::<String>("columns")
+            .expect("columns has a default")
+            .to_string();
+        let separator = matches
+            .get_one::<String>("separator")
+            .expect("separator has a default")
+            .to_string();
+        let status = matches.get_one::<String>("status").cloned();
+        let shelf = matches.get_one::<String>("shelf").cloned();
+        let search = matches.get_one::<String>("search").cloned();
+        let sort = matches
+            .get_one::<String>("sort")
+            .expect("sort has a default")
+            .to_string();
+        CliCommand::Export(ExportArgs {
+            format,
+            output,
+            columns,
+            separator,
+            status,
+            shelf,
+            search,
+            sort,
+        })
+    } else if let Some(matches) = matches.subcommand_matches("import") {
+        let path = matches
+            .get_one::<PathBuf>("file")
+            .expect("file is required")
+            .clone();
+        let mode = matches
+            .get_one::<String>("mode")
+            .expect("mode has a default")
+            .to_string();
+        let source = matches
+            .get_one::<String>("from")
+            .expect("from has a default")
+            .to_string();
+        let dry_run = matches.get_flag("dry-run");
+        let on_conflict = matches
+            .get_one::<String>("on-conflict")
+            .expect("on-conflict has a default")
+            .to_string();
+        CliCommand::Import(ImportArgs {
+            path,
+            source,
+            mode,
+            dry_run,
+            on_conflict,
+            read_column: matches.get_one::<String>("read-column").cloned(),
+            rating_column: matches.get_one::<String>("rating-column").cloned(),
+            url_column: matches.get_one::<String>("url-column").cloned(),
+            no_fetch: matches.get_flag("no-fetch"),
+            no_shelves: matches.get_flag("no-shelves"),
+        })
+    } else if let Some(backup_matches) = matches.subcommand_matches("backup") {
+        CliCommand::Backup(parse_backup_subcommand(backup_matches))
+    } else if let Some(matches) = matches.subcommand_matches("merge") {
+        CliCommand::Merge {
+            path: matches
+                .get_one::<PathBuf>("file")
+                .expect("file is required")
+                .clone(),
+            policy: matches
+                .get_one::<String>("policy")
+                .expect("policy has a default")
+                .to_string(),
+            dry_run: matches.get_flag("dry-run"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("doctor") {
+        CliCommand::Doctor {
+            fix: matches.get_flag("fix"),
+        }
+    } else if let Some(config_matches) = matches.subcommand_matches("config") {
+        CliCommand::Config(parse_config_subcommand(config_matches))
+    } else if let Some(matches) = matches.subcommand_matches("serve") {
+        CliCommand::Serve {
+            bind: matches
+                .get_one::<String>("bind")
+                .expect("bind has a default")
+                .to_string(),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("native-host") {
+        CliCommand::NativeHost(match matches.subcommand_matches("manifest") {
+            Some(m) => NativeHostCommand::Manifest {
+                browser: required(m, "browser"),
+                extension_id: required(m, "extension-id"),
+                install: m.get_flag("install"),
+            },
+            None => NativeHostCommand::Run,
+        })
+    } else if let Some(matches) = matches.subcommand_matches("url-handler") {
+        CliCommand::UrlHandler {
+            install: matches.get_flag("install"),
+        }
+    } else if matches.subcommand_matches("tui").is_some() {
+        CliCommand::Tui
     } else {
         // Default to list if no command provided
-        CliCommand::List
+        CliCommand::List(ListArgs::default())
     }
 }
 
@@ -219,42 +843,115 @@ fn parse_shelf_subcommand(matches: &clap::ArgMatches) -> ShelfCommand {
             .get_one::<String>("name")
             .expect("name is required")
             .to_string();
-        let parent = m.get_one::<u64>("parent").copied();
+        let parent = m.get_one::<String>("parent").cloned();
         ShelfCommand::Create { name, parent }
     } else if let Some(m) = matches.subcommand_matches("delete") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Delete { shelf_id }
+        let shelf = required(m, "shelf");
+        ShelfCommand::Delete { shelf }
     } else if let Some(m) = matches.subcommand_matches("rename") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
+        let shelf = required(m, "shelf");
         let new_name = m
             .get_one::<String>("new-name")
             .expect("new-name is required")
             .to_string();
-        ShelfCommand::Rename { shelf_id, new_name }
+        ShelfCommand::Rename { shelf, new_name }
     } else if let Some(m) = matches.subcommand_matches("move") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        let parent = m.get_one::<u64>("parent-id").copied();
-        ShelfCommand::Move { shelf_id, parent }
+        let shelf = required(m, "shelf");
+        let parent = m.get_one::<String>("parent").cloned();
+        ShelfCommand::Move { shelf, parent }
     } else if let Some(m) = matches.subcommand_matches("pin") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Pin { shelf_id }
+        let shelf = required(m, "shelf");
+        ShelfCommand::Pin { shelf }
     } else if let Some(m) = matches.subcommand_matches("unpin") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Unpin { shelf_id }
+        let shelf = required(m, "shelf");
+        ShelfCommand::Unpin { shelf }
     } else if matches.subcommand_matches("list").is_some() {
         ShelfCommand::List
     } else if let Some(m) = matches.subcommand_matches("add") {
-        let fic_id = *m.get_one::<u64>("fic-id").expect("fic-id is required");
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Add { fic_id, shelf_id }
+        let fic = required(m, "fic");
+        let shelf = required(m, "shelf");
+        ShelfCommand::Add { fic, shelf }
     } else if let Some(m) = matches.subcommand_matches("remove") {
-        let fic_id = *m.get_one::<u64>("fic-id").expect("fic-id is required");
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Remove { fic_id, shelf_id }
+        let fic = required(m, "fic");
+        let shelf = required(m, "shelf");
+        ShelfCommand::Remove { fic, shelf }
     } else if let Some(m) = matches.subcommand_matches("show") {
-        let shelf_id = *m.get_one::<u64>("shelf-id").expect("shelf-id is required");
-        ShelfCommand::Show { shelf_id }
+        let shelf = required(m, "shelf");
+        ShelfCommand::Show { shelf }
     } else {
         unreachable!("subcommand_required on shelf ensures one of the above matches")
     }
 }
+
+fn parse_saved_search_subcommand(matches: &clap::ArgMatches) -> SavedSearchCommand {
+    if matches.subcommand_matches("list").is_some() {
+        SavedSearchCommand::List
+    } else if let Some(m) = matches.subcommand_matches("run") {
+        let saved_search_id = *m
+            .get_one::<u64>("saved-search-id")
+            .expect("saved-search-id is required");
+        SavedSearchCommand::Run { saved_search_id }
+    } else {
+        unreachable!("subcommand_required on saved-search ensures one of the above matches")
+    }
+}
+
+fn parse_trash_subcommand(matches: &clap::ArgMatches) -> TrashCommand {
+    if matches.subcommand_matches("list").is_some() {
+        TrashCommand::List
+    } else if let Some(m) = matches.subcommand_matches("restore") {
+        let id = required(m, "id");
+        if m.get_flag("shelf") {
+            TrashCommand::RestoreShelf { shelf: id }
+        } else {
+            TrashCommand::RestoreFic { fic: id }
+        }
+    } else if let Some(m) = matches.subcommand_matches("purge") {
+        match m.get_one::<String>("id").cloned() {
+            Some(shelf) if m.get_flag("shelf") => TrashCommand::PurgeShelf { shelf },
+            Some(fic) => TrashCommand::PurgeFic { fic },
+            None => TrashCommand::Empty {
+                older_than_days: m.get_one::<u32>("older-than").copied(),
+            },
+        }
+    } else {
+        unreachable!("subcommand_required on trash ensures one of the above matches")
+    }
+}
+
+fn parse_config_subcommand(matches: &clap::ArgMatches) -> ConfigCommand {
+    if let Some(m) = matches.subcommand_matches("get") {
+        ConfigCommand::Get {
+            key: required(m, "key"),
+        }
+    } else if let Some(m) = matches.subcommand_matches("set") {
+        ConfigCommand::Set {
+            key: required(m, "key"),
+            value: required(m, "value"),
+        }
+    } else if matches.subcommand_matches("list").is_some() {
+        ConfigCommand::List
+    } else if matches.subcommand_matches("path").is_some() {
+        ConfigCommand::Path
+    } else if let Some(m) = matches.subcommand_matches("reset") {
+        ConfigCommand::Reset {
+            key: m.get_one::<String>("key").cloned(),
+        }
+    } else {
+        unreachable!("subcommand_required on config ensures one of the above matches")
+    }
+}
+
+fn parse_backup_subcommand(matches: &clap::ArgMatches) -> BackupCommand {
+    if matches.subcommand_matches("create").is_some() {
+        BackupCommand::Create
+    } else if matches.subcommand_matches("list").is_some() {
+        BackupCommand::List
+    } else if let Some(m) = matches.subcommand_matches("prune") {
+        BackupCommand::Prune {
+            keep: m.get_one::<usize>("keep").copied(),
+        }
+    } else {
+        unreachable!("subcommand_required on backup ensures one of the above matches")
+    }
+}
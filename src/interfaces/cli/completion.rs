@@ -0,0 +1,105 @@
+//! Live values for shell completion. The shell runs `ficflow` with
+//! `COMPLETE=<shell>` set on every Tab, and these read the configured
+//! library each time, so new fics and renamed shelves show up at once.
+//! Any failure just means no suggestions. Setting names are the one
+//! fixed list.
+
+use std::collections::HashMap;
+
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+
+use crate::application::{
+    list_fics::list_fics, list_shelves::list_shelves, list_trashed_fics::list_trashed_fics,
+    list_trashed_shelves::list_trashed_shelves,
+};
+use crate::domain::fanfiction::Fanfiction;
+use crate::domain::repository::Repository;
+use crate::domain::shelf::{Shelf, shelf_path};
+use crate::error::FicflowError;
+use crate::infrastructure::{SqliteRepository, open_read_only_db};
+use crate::interfaces::gui::{AppConfig, Setting};
+
+/// Titles of the fics in the library.
+pub fn fic_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| from_library(|repository| Ok(fic_titles(&list_fics(repository)?))))
+}
+
+/// Paths of the shelves, which are just their names at the top level.
+pub fn shelf_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        from_library(|repository| Ok(shelf_paths(&list_shelves(repository)?)))
+    })
+}
+
+/// Titles and shelf names in the trash, for `trash restore` and `purge`.
+pub fn trash_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        from_library(|repository| {
+            let fics: Vec<Fanfiction> = list_trashed_fics(repository)?
+                .into_iter()
+                .map(|t| t.fic)
+                .collect();
+            let shelves: Vec<Shelf> = list_trashed_shelves(repository)?
+                .into_iter()
+                .map(|t| t.shelf)
+                .collect();
+            let mut candidates = fic_titles(&fics);
+            candidates.extend(shelf_paths(&shelves));
+            Ok(candidates)
+        })
+    })
+}
+
+/// The settings `ficflow config` takes, with what each one expects.
+pub fn setting_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        Setting::ALL
+            .into_iter()
+            .map(|setting| {
+                CompletionCandidate::new(setting.key()).help(Some(setting.hint().into()))
+            })
+            .collect()
+    })
+}
+
+fn from_library(
+    read: impl FnOnce(&dyn Repository) -> Result<Vec<CompletionCandidate>, FicflowError>,
+) -> Vec<CompletionCandidate> {
+    let Ok(db_path) = AppConfig::load().resolved_db_path() else {
+        return Vec::new();
+    };
+    // A Tab press only reads: it never creates the library, and one an
+    // older build left is offered nothing rather than upgraded.
+    let Ok(Some(conn)) = open_read_only_db(&db_path) else {
+        return Vec::new();
+    };
+    read(&SqliteRepository::new(&conn)).unwrap_or_default()
+}
+
+/// A title two fics share wouldn't pick either, so those are offered by
+/// ID instead, with the title alongside.
+fn fic_titles(fics: &[Fanfiction]) -> Vec<CompletionCandidate> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for fic in fics {
+        *seen.entry(fic.title.to_lowercase()).or_default() += 1;
+    }
+    fics.iter()
+        .map(|fic| {
+            if seen[&fic.title.to_lowercase()] > 1 {
+                CompletionCandidate::new(fic.id.to_string()).help(Some(fic.title.clone().into()))
+            } else {
+                CompletionCandidate::new(&fic.title).help(Some(format!("ID {}", fic.id).into()))
+            }
+        })
+        .collect()
+}
+
+fn shelf_paths(shelves: &[Shelf]) -> Vec<CompletionCandidate> {
+    shelves
+        .iter()
+        .map(|shelf| {
+            CompletionCandidate::new(shelf_path(shelves, shelf))
+                .help(Some(format!("shelf {}", shelf.id).into()))
+        })
+        .collect()
+}
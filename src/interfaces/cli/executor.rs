@@ -1,25 +1,71 @@
 use std::env;
-use std::io::{self, Write};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use super::command::{CliCommand, ShelfCommand};
-use super::views::{details_view, list_view, shelf_list_view};
+use serde::Serialize;
+use serde_json::json;
+
+use super::command::{
+    BackupCommand, CliCommand, ConfigCommand, ExportArgs, ImportArgs, ListArgs, NativeHostCommand,
+    SavedSearchCommand, ShelfCommand, TrashCommand,
+};
+use super::output::{self, OutputFormat};
+use super::views::{
+    ao3_search_view, backup_list_view, config_view, details_view, fic_import_view,
+    health_report_view, import_summary_view, list_view, merge_view, saved_search_list_view,
+    search_result_view, shelf_list_view, trash_list_view,
+};
 use crate::{
     application::{
         add_fic::add_fanfiction,
         add_to_shelf::add_to_shelf,
+        apply_fic_import::{apply_fic_import, parse_conflict_choice},
+        apply_visit_hint::apply_visit_hint,
+        backup_if_due::backup_if_due,
+        check_library::check_library,
+        check_updates::check_fic_updates,
+        create_backup::create_backup,
         create_shelf::create_shelf,
         delete_fic::delete_fic,
         delete_shelf::delete_shelf,
+        export_fic_table::{export_fic_table, parse_table_fields},
+        export_library::{export_library, parse_export_format},
+        find_fic_by_title::{find_fic_by_title, find_trashed_fic_by_title},
+        find_or_create_shelves::find_or_create_shelves,
+        find_shelf_by_path::{find_shelf_by_path, find_trashed_shelf_by_path},
         get_fic::get_fanfiction,
-        list_fics::list_fics,
+        import_library::{
+            import_library, parse_import_mode, parse_import_source, parse_library_snapshot,
+        },
+        list_backups::list_backups,
+        list_saved_searches::list_saved_searches,
         list_shelf_fics::list_shelf_fics,
         list_shelves::list_shelves,
+        list_trashed_fics::list_trashed_fics,
+        list_trashed_shelves::list_trashed_shelves,
+        merge_library::{merge_library, parse_merge_policy},
         move_shelf::move_shelf,
+        open_fic::open_fic,
         pin_shelf::pin_shelf,
+        plan_fic_import::plan_fic_import,
+        plan_link_import::plan_link_import,
+        prune_backups::prune_backups,
+        purge_fic::purge_fic,
+        purge_shelf::purge_shelf,
+        purge_trash::purge_trash,
+        query_fics::{parse_column_keys, parse_sort_pref, query_fics},
         remove_from_shelf::remove_from_shelf,
         rename_shelf::rename_shelf,
+        repair_library::repair_library,
+        restore_fic::restore_fic,
+        restore_shelf::restore_shelf,
+        run_saved_search::run_saved_search,
+        search_ao3::search_ao3,
+        search_fics::search_fics,
         unpin_shelf::unpin_shelf,
+        untracked_fic_ids::untracked_fic_ids,
         update_chapters::update_last_chapter_read,
         update_note::update_personal_note,
         update_rating::{parse_user_rating, update_user_rating},
@@ -27,8 +73,30 @@ use crate::{
         update_status::{parse_reading_status, update_reading_status},
         wipe_db::wipe_database,
     },
-    domain::{fanfiction::FanfictionFetcher, repository::Repository},
+    domain::{
+        backup::{BackupOps, BackupReason},
+        discover::{Ao3Search, Archive},
+        fanfiction::{
+            ColumnKey, FicFilter, FicQuery, LinkOpener, SortDirection, SortPref, TagKind,
+        },
+        repository::Repository,
+        shelf::{Shelf, ShelfKind},
+        transfer::{ImportMode, ImportSource, LinkSource, TableOptions},
+    },
     error::FicflowError,
+    infrastructure::{
+        BookmarksFile, CalibreColumns, CalibreLibrary, FirefoxPlaces, read_library_file,
+        relocate_library,
+    },
+    interfaces::gui::{AppConfig, Setting, config_path, default_db_path},
+    interfaces::instance::{self, Message, link},
+    interfaces::native_host::{
+        manifest::{self, Browser},
+        run_native_host,
+    },
+    interfaces::server::run_server,
+    interfaces::tui::run_tui,
+    interfaces::utils::url_parser::{extract_ao3_id, extract_ao3_ids},
 };
 
 pub trait CommandExecutor {
@@ -36,177 +104,637 @@ pub trait CommandExecutor {
 }
 
 pub struct CliCommandExecutor<'a> {
-    fetcher: &'a dyn FanfictionFetcher,
+    /// Fetches works and searches listings.
+    fetcher: &'a dyn Archive,
     repository: &'a dyn Repository,
+    backups: &'a dyn BackupOps,
+    /// Backup retention and the reading settings.
+    config: &'a AppConfig,
+    opener: &'a dyn LinkOpener,
+    output: OutputFormat,
+    /// The library file, for front ends that open their own connections.
+    db_path: &'a Path,
 }
 
 impl<'a> CliCommandExecutor<'a> {
-    pub fn new(fetcher: &'a dyn FanfictionFetcher, repository: &'a dyn Repository) -> Self {
+    pub fn new(
+        fetcher: &'a dyn Archive,
+        repository: &'a dyn Repository,
+        backups: &'a dyn BackupOps,
+        config: &'a AppConfig,
+        opener: &'a dyn LinkOpener,
+        output: OutputFormat,
+        db_path: &'a Path,
+    ) -> Self {
         Self {
             fetcher,
             repository,
+            backups,
+            config,
+            opener,
+            output,
+            db_path,
+        }
+    }
+
+    /// Prints `value` in the machine formats, or runs `table` to print
+    /// it for people.
+    fn emit<T: Serialize>(&self, value: &T, table: impl FnOnce()) -> ExitCode {
+        if self.output.is_table() {
+            table();
+            return ExitCode::SUCCESS;
+        }
+        match output::render_value(self.output, value) {
+            Ok(rendered) => {
+                println!("{}", rendered);
+                ExitCode::SUCCESS
+            }
+            Err(e) => self.fail("printing the result", &e),
+        }
+    }
+
+    /// Reports `err` and picks the exit code documented for its kind.
+    fn fail(&self, verb: &str, err: &FicflowError) -> ExitCode {
+        self.report(verb, err);
+        ExitCode::from(err.exit_code())
+    }
+
+    fn report(&self, verb: &str, err: &FicflowError) {
+        if self.output.is_table() {
+            report_error(verb, err);
+        } else {
+            eprintln!("{}", output::render_error(self.output, err));
+        }
+    }
+
+    /// Runs `run` with what the command line named, or reports why it
+    /// couldn't be found.
+    fn resolved<T>(
+        &self,
+        resolved: Result<T, FicflowError>,
+        run: impl FnOnce(T) -> ExitCode,
+    ) -> ExitCode {
+        match resolved {
+            Ok(value) => run(value),
+            Err(e) => self.fail("finding what to change", &e),
+        }
+    }
+
+    /// A fic given as its ID, an AO3 link or its title. A bare number is
+    /// an ID unless no fic has it, so a title like "1984" still works.
+    fn resolve_fic(&self, fic: &str) -> Result<u64, FicflowError> {
+        let fic = fic.trim();
+        match extract_ao3_id(fic) {
+            Ok(fic_id) if is_number(fic) && !self.is_live_fic(fic_id)? => {
+                name_or_id(find_fic_by_title(self.repository, fic), fic_id)
+            }
+            Ok(fic_id) => Ok(fic_id),
+            Err(_) => find_fic_by_title(self.repository, fic),
+        }
+    }
+
+    /// Whether a live fic has `fic_id`. Only its absence is an answer;
+    /// any other failure is passed up rather than read as "try the title".
+    fn is_live_fic(&self, fic_id: u64) -> Result<bool, FicflowError> {
+        match self.repository.get_fanfiction_by_id(fic_id) {
+            Ok(_) => Ok(true),
+            Err(FicflowError::NotFound { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn resolve_trashed_fic(&self, fic: &str) -> Result<u64, FicflowError> {
+        let fic = fic.trim();
+        match extract_ao3_id(fic) {
+            Ok(fic_id) if is_number(fic) && !self.is_trashed_fic(fic_id)? => {
+                name_or_id(find_trashed_fic_by_title(self.repository, fic), fic_id)
+            }
+            Ok(fic_id) => Ok(fic_id),
+            Err(_) => find_trashed_fic_by_title(self.repository, fic),
+        }
+    }
+
+    fn is_trashed_fic(&self, fic_id: u64) -> Result<bool, FicflowError> {
+        let trashed = self.repository.list_trashed_fics()?;
+        Ok(trashed.iter().any(|t| t.fic.id == fic_id))
+    }
+
+    /// A shelf given as its ID, its name, or a `Parent/Child` path. As
+    /// with fics, a number no shelf has as its ID is tried as a name.
+    fn resolve_shelf(&self, shelf: &str) -> Result<u64, FicflowError> {
+        match shelf.trim().parse() {
+            Ok(shelf_id) if !self.is_live_shelf(shelf_id)? => {
+                name_or_id(find_shelf_by_path(self.repository, shelf), shelf_id)
+            }
+            Ok(shelf_id) => Ok(shelf_id),
+            Err(_) => find_shelf_by_path(self.repository, shelf),
+        }
+    }
+
+    fn is_live_shelf(&self, shelf_id: u64) -> Result<bool, FicflowError> {
+        match self.repository.get_shelf_by_id(shelf_id) {
+            Ok(_) => Ok(true),
+            Err(FicflowError::ShelfNotFound { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn resolve_trashed_shelf(&self, shelf: &str) -> Result<u64, FicflowError> {
+        let by_path = || find_trashed_shelf_by_path(self.repository, self.repository, shelf);
+        match shelf.trim().parse() {
+            Ok(shelf_id) if !self.is_trashed_shelf(shelf_id)? => name_or_id(by_path(), shelf_id),
+            Ok(shelf_id) => Ok(shelf_id),
+            Err(_) => by_path(),
+        }
+    }
+
+    fn is_trashed_shelf(&self, shelf_id: u64) -> Result<bool, FicflowError> {
+        let trashed = self.repository.list_trashed_shelves()?;
+        Ok(trashed.iter().any(|t| t.shelf.id == shelf_id))
+    }
+
+    /// Progress and headings that would get in the way of a script
+    /// reading standard output.
+    fn note(&self, line: &str) {
+        if self.output.is_table() {
+            println!("{}", line);
+        }
+    }
+
+    /// The daily backup and the trash's auto-purge, as a window does
+    /// when it opens. Neither stops the command if it fails.
+    fn housekeeping(&self) {
+        if let Err(err) = backup_if_due(self.backups, self.config.backup_keep) {
+            log::warn!("Failed to take the daily backup: {}", err);
+        }
+        if let Some(days) = self.config.trash_auto_purge_days
+            && let Err(err) = purge_trash(self.repository, Some(days))
+        {
+            log::warn!("Failed to purge expired trash: {}", err);
         }
     }
 
     fn execute_add(&self, fic_id: u64) -> ExitCode {
-        println!("Adding fanfiction with ID: {}", fic_id);
+        // An open window adds it itself, so the fic shows up there at
+        // once. Adds are the only command handed over; other changes are
+        // refused while a window is open.
+        let forward = Message::Add {
+            fic: fic_id.to_string(),
+        };
+        match instance::send(&instance::socket_path(self.db_path), &forward) {
+            Ok(true) => {
+                return self.emit(&json!({ "fic_id": fic_id, "forwarded": true }), || {
+                    println!(
+                        "Sent {} to the open Ficflow window, which is adding it",
+                        fic_id
+                    )
+                });
+            }
+            Ok(false) => {}
+            Err(e) => log::warn!("Adding here instead of in the open window: {}", e),
+        }
+        self.note(&format!("Adding fanfiction with ID: {}", fic_id));
 
-        match add_fanfiction(self.fetcher, self.repository, fic_id) {
-            Ok(title) => {
-                println!("Successfully added: {}", title);
-                ExitCode::SUCCESS
+        let added = add_fanfiction(self.fetcher, self.repository, fic_id)
+            .and_then(|_| get_fanfiction(self.repository, fic_id));
+        match added {
+            Ok(fic) => self.emit(&fic, || println!("Successfully added: {}", fic.title)),
+            Err(e) => self.fail("adding fanfiction", &e),
+        }
+    }
+
+    /// Adds every AO3 work linked in `source` that isn't tracked yet,
+    /// one fetch at a time, and shelves each one as it lands.
+    fn execute_add_many(&self, source: &Path, shelf_id: Option<u64>) -> ExitCode {
+        let text = if source == Path::new("-") {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text)
+        } else {
+            fs::read_to_string(source)
+        };
+        let ids = match text {
+            Ok(text) => extract_ao3_ids(&text),
+            Err(e) => return self.fail("reading the list of fics", &e.into()),
+        };
+        if ids.is_empty() {
+            let message = format!("No AO3 work links or IDs found in {}", source.display());
+            return self.fail(
+                "reading the list of fics",
+                &FicflowError::InvalidInput(message),
+            );
+        }
+        if let Some(shelf_id) = shelf_id {
+            match self.find_shelf(shelf_id) {
+                Ok(shelf) if matches!(shelf.kind, ShelfKind::Auto(_)) => {
+                    let message = "Error: cannot add fics to an auto-shelf".to_string();
+                    return self.fail("finding the shelf", &FicflowError::InvalidInput(message));
+                }
+                Ok(_) => {}
+                Err(e) => return self.fail("finding the shelf", &e),
             }
-            Err(e) => {
-                report_error("adding fanfiction", &e);
-                ExitCode::FAILURE
+        }
+        let untracked = match untracked_fic_ids(self.repository, &ids) {
+            Ok(untracked) => untracked,
+            Err(e) => return self.fail("checking your library", &e),
+        };
+        self.note(&format!(
+            "Found {} AO3 work(s), {} already in your library.",
+            ids.len(),
+            ids.len() - untracked.len()
+        ));
+
+        let mut added = Vec::new();
+        let mut failed = 0;
+        for fic_id in untracked {
+            let outcome = add_fanfiction(self.fetcher, self.repository, fic_id).and_then(|_| {
+                if let Some(shelf_id) = shelf_id {
+                    add_to_shelf(self.repository, fic_id, shelf_id)?;
+                }
+                get_fanfiction(self.repository, fic_id)
+            });
+            match outcome {
+                Ok(fic) => {
+                    self.note(&format!("Added: {}", fic.title));
+                    added.push(fic);
+                }
+                Err(e) => {
+                    failed += 1;
+                    self.report(&format!("adding fanfiction {}", fic_id), &e);
+                }
             }
         }
+        // The table lines went out as each fic landed.
+        self.emit(&added, || {});
+        if failed > 0 {
+            eprintln!("{} fic(s) couldn't be added.", failed);
+            return ExitCode::FAILURE;
+        }
+        ExitCode::SUCCESS
     }
 
     fn execute_delete(&self, fic_id: u64) -> ExitCode {
-        println!("Deleting fanfiction with ID: {}", fic_id);
-        match delete_fic(self.repository, fic_id) {
-            Ok(()) => ExitCode::SUCCESS,
-            Err(e) => {
-                report_error("deleting fanfiction", &e);
-                ExitCode::FAILURE
-            }
+        self.note(&format!("Deleting fanfiction with ID: {}", fic_id));
+        let deleted = delete_fic(self.repository, fic_id).and_then(|()| {
+            let trashed = list_trashed_fics(self.repository)?;
+            Ok(trashed.into_iter().find(|t| t.fic.id == fic_id))
+        });
+        match deleted {
+            Ok(trashed) => self.emit(&trashed, || {}),
+            Err(e) => self.fail("deleting fanfiction", &e),
         }
     }
 
     fn execute_get(&self, fic_id: u64) -> ExitCode {
-        println!("Getting fanfiction with ID: {}", fic_id);
+        self.note(&format!("Getting fanfiction with ID: {}", fic_id));
         match get_fanfiction(self.repository, fic_id) {
-            Ok(fic) => {
+            Ok(fic) => self.emit(&fic, || {
                 let details = details_view::render_fanfiction_details(&fic);
                 println!("\n{}", details);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("getting fanfiction", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("getting fanfiction", &e),
         }
     }
 
-    fn execute_list(&self) -> ExitCode {
-        println!("Listing all fanfictions");
-        match list_fics(self.repository) {
-            Ok(fanfictions) => {
-                println!("{}", list_view::render_fanfiction_list(&fanfictions));
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("listing fanfictions", &e);
-                ExitCode::FAILURE
+    fn execute_open(&self, fic_id: u64, keep_status: bool) -> ExitCode {
+        let mark_in_progress = self.config.open_marks_in_progress && !keep_status;
+        match open_fic(
+            self.repository,
+            self.opener,
+            fic_id,
+            self.config.reading_base_url(),
+            mark_in_progress,
+        ) {
+            Ok((fic, chapter)) => {
+                let url = fic.chapter_url(self.config.reading_base_url(), chapter);
+                self.emit(
+                    &json!({ "fic": fic, "chapter": chapter, "url": url }),
+                    || {
+                        println!(
+                            "Opened chapter {} of \"{}\" (ID: {}).",
+                            chapter, fic.title, fic_id
+                        );
+                    },
+                )
             }
+            Err(e) => self.fail("opening fanfiction", &e),
+        }
+    }
+
+    fn execute_list(&self, args: &ListArgs) -> ExitCode {
+        self.note("Listing all fanfictions");
+        let listed = self.list_query(args).and_then(|(query, columns)| {
+            query_fics(self.repository, &query).map(|page| (page, columns))
+        });
+        match listed {
+            Ok((page, columns)) => self.emit(&page.fics, || {
+                println!(
+                    "{}",
+                    list_view::render_fanfiction_table(&page.fics, page.total, &columns)
+                );
+            }),
+            Err(e) => self.fail("listing fanfictions", &e),
+        }
+    }
+
+    fn list_query(&self, args: &ListArgs) -> Result<(FicQuery, Vec<ColumnKey>), FicflowError> {
+        let mut tags = Vec::new();
+        if let Some(fandom) = &args.fandom {
+            tags.push((TagKind::Fandom, fandom.clone()));
+        }
+        if let Some(tag) = &args.tag {
+            tags.push((TagKind::Tag, tag.clone()));
+        }
+        let sort = match &args.sort {
+            Some(sort) => parse_sort_pref(sort)?,
+            None => SortPref {
+                column: ColumnKey::Title,
+                direction: SortDirection::Ascending,
+            },
+        };
+        let columns = match &args.columns {
+            Some(columns) => parse_column_keys(columns)?,
+            None => list_view::DEFAULT_LIST_COLUMNS.to_vec(),
+        };
+        let query = FicQuery {
+            filter: FicFilter {
+                status: args
+                    .status
+                    .as_deref()
+                    .map(parse_reading_status)
+                    .transpose()?,
+                shelf_id: args
+                    .shelf
+                    .as_deref()
+                    .map(|s| self.resolve_shelf(s))
+                    .transpose()?,
+                complete: args.complete,
+                tags,
+                min_words: args.min_words,
+                ..FicFilter::default()
+            },
+            sort: vec![sort],
+            limit: args.limit,
+            ..FicQuery::default()
+        };
+        Ok((query, columns))
+    }
+
+    fn execute_search_local(&self, query: &str) -> ExitCode {
+        self.note(&format!("Searching your library for \"{}\"", query));
+        match search_fics(self.repository, query) {
+            Ok(hits) => self.emit(&hits, || {
+                println!("{}", search_result_view::render_search_results(&hits));
+            }),
+            Err(e) => self.fail("searching library", &e),
+        }
+    }
+
+    fn execute_search(&self, search: &Ao3Search, page: u32, hide_tracked: bool) -> ExitCode {
+        self.note(&format!("Searching AO3 for {}", search));
+        match search_ao3(self.fetcher, self.repository, search, page, hide_tracked) {
+            Ok(found) => self.emit(&found, || {
+                println!("{}", ao3_search_view::render_ao3_results(search, &found));
+            }),
+            Err(e) => self.fail("searching AO3", &e),
         }
     }
 
     fn execute_wipe(&self) -> ExitCode {
-        println!("Preparing to wipe database...");
+        self.note("Preparing to wipe database...");
 
-        if !confirm_wipe() {
-            println!("Operation cancelled.");
+        if !confirm(
+            "WARNING: This action will move ALL fanfictions to the trash. \
+             Emptying the trash afterwards CANNOT be reversed!",
+            self.output,
+        ) {
+            self.note("Operation cancelled.");
             return ExitCode::SUCCESS;
         }
 
+        let backup = match create_backup(self.backups, BackupReason::Wipe, self.config.backup_keep)
+        {
+            Ok(backup) => backup,
+            Err(e) => return self.fail("backing up before the wipe", &e),
+        };
+        self.note(&format!("Backed up to {}", backup.path.display()));
+
         match wipe_database(self.repository) {
-            Ok(()) => {
+            Ok(()) => self.emit(&json!({ "backup": backup.path }), || {
                 println!("Database wiped successfully.");
-                ExitCode::SUCCESS
+            }),
+            Err(e) => self.fail("wiping database", &e),
+        }
+    }
+
+    fn execute_backup_create(&self) -> ExitCode {
+        match create_backup(self.backups, BackupReason::Manual, self.config.backup_keep) {
+            Ok(backup) => self.emit(&backup, || {
+                println!("Backed up to {}", backup.path.display());
+            }),
+            Err(e) => self.fail("backing up", &e),
+        }
+    }
+
+    fn execute_backup_list(&self) -> ExitCode {
+        match list_backups(self.backups) {
+            Ok(backups) => self.emit(&backups, || {
+                println!("{}", backup_list_view::render_backup_list(&backups));
+            }),
+            Err(e) => self.fail("listing backups", &e),
+        }
+    }
+
+    fn execute_backup_prune(&self, keep: Option<usize>) -> ExitCode {
+        let keep = keep.unwrap_or(self.config.backup_keep);
+        match prune_backups(self.backups, keep) {
+            Ok(removed) => self.emit(&removed, || {
+                println!(
+                    "Deleted {} backups, keeping the newest {} of each kind.",
+                    removed.len(),
+                    keep.max(1)
+                );
+            }),
+            Err(e) => self.fail("pruning backups", &e),
+        }
+    }
+
+    fn execute_config(&self, command: ConfigCommand) -> ExitCode {
+        match command {
+            ConfigCommand::Get { key } => self.resolved(Setting::from_key(&key), |setting| {
+                let value = self.config.setting(setting);
+                self.emit(&json!({ "key": setting.key(), "value": value }), || {
+                    println!("{}", value.as_deref().unwrap_or(config_view::UNSET))
+                })
+            }),
+            ConfigCommand::Set { key, value } => self
+                .resolved(Setting::from_key(&key), |setting| {
+                    self.execute_config_set(setting, &value)
+                }),
+            ConfigCommand::List => {
+                let settings: Vec<(Setting, Option<String>)> = Setting::ALL
+                    .into_iter()
+                    .map(|setting| (setting, self.config.setting(setting)))
+                    .collect();
+                let listed: Vec<_> = settings
+                    .iter()
+                    .map(|(setting, value)| {
+                        json!({ "key": setting.key(), "value": value, "takes": setting.hint() })
+                    })
+                    .collect();
+                self.emit(&listed, || {
+                    println!("{}", config_view::render_settings(&settings))
+                })
             }
-            Err(e) => {
-                report_error("wiping database", &e);
-                ExitCode::FAILURE
+            ConfigCommand::Path => match config_path() {
+                Some(path) => {
+                    self.emit(&json!({ "path": path }), || println!("{}", path.display()))
+                }
+                None => self.fail(
+                    "finding the config file",
+                    &FicflowError::Other("no config directory available on this platform".into()),
+                ),
+            },
+            ConfigCommand::Reset { key } => {
+                let settings = match key {
+                    Some(key) => Setting::from_key(&key).map(|setting| vec![setting]),
+                    None => Ok(Setting::ALL.to_vec()),
+                };
+                self.resolved(settings, |settings| self.execute_config_reset(&settings))
+            }
+        }
+    }
+
+    fn execute_config_set(&self, setting: Setting, value: &str) -> ExitCode {
+        let mut config = self.config.clone();
+        let changed = config.set_setting(setting, value).and_then(|()| {
+            if setting == Setting::LibraryPath
+                && let Some(target) = &config.library_path
+            {
+                config.library_path = Some(self.move_library(target)?);
+            }
+            Ok(config.save()?)
+        });
+        if let Err(e) = changed {
+            return self.fail("changing the setting", &e);
+        }
+        let value = config.setting(setting);
+        self.emit(&json!({ "key": setting.key(), "value": value }), || {
+            if setting == Setting::LibraryPath {
+                println!("Library moved to {}.", value.as_deref().unwrap_or_default());
+            } else {
+                println!(
+                    "Set {} to {}.",
+                    setting.key(),
+                    value.as_deref().unwrap_or(config_view::UNSET)
+                );
+            }
+        })
+    }
+
+    fn execute_config_reset(&self, settings: &[Setting]) -> ExitCode {
+        let mut config = self.config.clone();
+        let reset = (|| {
+            if settings.contains(&Setting::LibraryPath) && config.library_path.is_some() {
+                self.move_library(&default_db_path()?)?;
+            }
+            for setting in settings {
+                config.reset_setting(*setting);
             }
+            Ok::<(), FicflowError>(config.save()?)
+        })();
+        if let Err(e) = reset {
+            return self.fail("resetting the settings", &e);
         }
+        let values: Vec<_> = settings
+            .iter()
+            .map(|setting| json!({ "key": setting.key(), "value": config.setting(*setting) }))
+            .collect();
+        self.emit(&values, || match settings {
+            [setting] => println!("Reset {} to its default.", setting.key()),
+            _ => println!("Reset every setting to its default."),
+        })
+    }
+
+    /// Moves the library the way Settings' "Change location" does: a
+    /// folder keeps the file's name, and a library already at the target
+    /// is adopted instead of overwritten. Returns where the library is now.
+    fn move_library(&self, target: &Path) -> Result<PathBuf, FicflowError> {
+        let target = match self.db_path.file_name() {
+            Some(name) if target.is_dir() => target.join(name),
+            _ => target.to_path_buf(),
+        };
+        if target != self.db_path && !target.exists() {
+            relocate_library(self.db_path, &target)?;
+        }
+        Ok(target)
     }
 
     fn execute_update_chapter(&self, fic_id: u64, chapter: u32) -> ExitCode {
-        println!(
+        self.note(&format!(
             "Updating last read chapter for fanfiction ID: {} to chapter {}",
             fic_id, chapter
-        );
+        ));
         match update_last_chapter_read(self.repository, fic_id, chapter) {
-            Ok(fic) => {
+            Ok(fic) => self.emit(&fic, || {
                 println!(
                     "Successfully updated \"{}\" (ID: {}) to chapter {}.",
                     fic.title, fic_id, chapter
                 );
                 println!("Reading Status: {}", fic.reading_status);
                 println!("Read Count: {}", fic.read_count);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("updating last read chapter", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("updating last read chapter", &e),
         }
     }
 
     fn execute_update_status(&self, fic_id: u64, status: &str) -> ExitCode {
-        println!(
+        self.note(&format!(
             "Updating reading status for fanfiction ID: {} to '{}'",
             fic_id, status
-        );
+        ));
         let parsed_status = match parse_reading_status(status) {
             Ok(s) => s,
-            Err(e) => {
-                report_error("updating reading status", &e);
-                return ExitCode::FAILURE;
-            }
+            Err(e) => return self.fail("updating reading status", &e),
         };
         match update_reading_status(self.repository, fic_id, parsed_status) {
-            Ok(fic) => {
+            Ok(fic) => self.emit(&fic, || {
                 println!(
                     "Successfully updated \"{}\" (ID: {}) to status: {}.",
                     fic.title, fic_id, fic.reading_status
                 );
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("updating reading status", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("updating reading status", &e),
         }
     }
 
     fn execute_update_read_count(&self, fic_id: u64, read_count: u32) -> ExitCode {
-        println!(
+        self.note(&format!(
             "Updating read count for fanfiction ID: {} to {}",
             fic_id, read_count
-        );
+        ));
         match update_read_count(self.repository, fic_id, read_count) {
-            Ok(fic) => {
+            Ok(fic) => self.emit(&fic, || {
                 println!(
                     "Successfully updated \"{}\" (ID: {}) to read count: {}.",
                     fic.title, fic_id, fic.read_count
                 );
                 println!("Reading Status: {}", fic.reading_status);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("updating read count", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("updating read count", &e),
         }
     }
 
     fn execute_update_rating(&self, fic_id: u64, rating: &str) -> ExitCode {
-        println!(
+        self.note(&format!(
             "Updating user rating for fanfiction ID: {} to '{}'",
             fic_id, rating
-        );
+        ));
         let parsed_rating = match parse_user_rating(rating) {
             Ok(r) => r,
-            Err(e) => {
-                report_error("updating user rating", &e);
-                return ExitCode::FAILURE;
-            }
+            Err(e) => return self.fail("updating user rating", &e),
         };
         match update_user_rating(self.repository, fic_id, parsed_rating) {
-            Ok(fic) => {
+            Ok(fic) => self.emit(&fic, || {
                 let rating_display = match &fic.user_rating {
                     Some(r) => format!("{}", r),
                     None => "None".to_string(),
@@ -215,154 +743,634 @@ impl<'a> CliCommandExecutor<'a> {
                     "Successfully updated \"{}\" (ID: {}) to rating: {}.",
                     fic.title, fic_id, rating_display
                 );
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("updating user rating", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("updating user rating", &e),
         }
     }
 
     fn execute_shelf_create(&self, name: &str, parent: Option<u64>) -> ExitCode {
         match create_shelf(self.repository, name, parent) {
-            Ok(shelf) => {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!(
-                    "Created shelf \"{}\" (id: {}). Use this id to add, remove, or show fics.",
+                    "Created shelf \"{}\" (id: {}). Use this id or its name to add, remove, or show fics.",
                     shelf.name, shelf.id
                 );
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("creating shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("creating shelf", &e),
         }
     }
 
     fn execute_shelf_move(&self, shelf_id: u64, parent: Option<u64>) -> ExitCode {
         match move_shelf(self.repository, shelf_id, parent) {
-            Ok(shelf) => {
-                match shelf.parent_shelf_id {
-                    Some(parent_id) => println!("Moved shelf {} under {}.", shelf.id, parent_id),
-                    None => println!("Moved shelf {} to top level.", shelf.id),
-                }
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("moving shelf", &e);
-                ExitCode::FAILURE
-            }
+            Ok(shelf) => self.emit(&shelf, || match shelf.parent_shelf_id {
+                Some(parent_id) => println!("Moved shelf {} under {}.", shelf.id, parent_id),
+                None => println!("Moved shelf {} to top level.", shelf.id),
+            }),
+            Err(e) => self.fail("moving shelf", &e),
         }
     }
 
     fn execute_shelf_pin(&self, shelf_id: u64) -> ExitCode {
         match pin_shelf(self.repository, shelf_id) {
-            Ok(shelf) => {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!("Pinned shelf {} (\"{}\").", shelf.id, shelf.name);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("pinning shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("pinning shelf", &e),
         }
     }
 
     fn execute_shelf_unpin(&self, shelf_id: u64) -> ExitCode {
         match unpin_shelf(self.repository, shelf_id) {
-            Ok(shelf) => {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!("Unpinned shelf {} (\"{}\").", shelf.id, shelf.name);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("unpinning shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("unpinning shelf", &e),
         }
     }
 
     fn execute_shelf_delete(&self, shelf_id: u64) -> ExitCode {
-        match delete_shelf(self.repository, shelf_id) {
-            Ok(()) => {
-                println!("Deleted shelf {}.", shelf_id);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("deleting shelf", &e);
-                ExitCode::FAILURE
-            }
+        let deleted = delete_shelf(self.repository, shelf_id).and_then(|()| {
+            let trashed = list_trashed_shelves(self.repository)?;
+            Ok(trashed.into_iter().find(|t| t.shelf.id == shelf_id))
+        });
+        match deleted {
+            Ok(trashed) => self.emit(&trashed, || println!("Deleted shelf {}.", shelf_id)),
+            Err(e) => self.fail("deleting shelf", &e),
         }
     }
 
     fn execute_shelf_rename(&self, shelf_id: u64, new_name: &str) -> ExitCode {
         match rename_shelf(self.repository, shelf_id, new_name) {
-            Ok(shelf) => {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!("Renamed shelf {} to \"{}\".", shelf.id, shelf.name);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("renaming shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("renaming shelf", &e),
         }
     }
 
     fn execute_shelf_list(&self) -> ExitCode {
         match list_shelves(self.repository) {
-            Ok(shelves) => {
+            Ok(shelves) => self.emit(&shelves, || {
                 println!("{}", shelf_list_view::render_shelf_list(&shelves));
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("listing shelves", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("listing shelves", &e),
         }
     }
 
     fn execute_shelf_add(&self, fic_id: u64, shelf_id: u64) -> ExitCode {
-        match add_to_shelf(self.repository, fic_id, shelf_id) {
-            Ok(()) => {
+        let added = add_to_shelf(self.repository, fic_id, shelf_id)
+            .and_then(|()| self.find_shelf(shelf_id));
+        match added {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!("Added fanfiction {} to shelf {}.", fic_id, shelf_id);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("adding fanfiction to shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("adding fanfiction to shelf", &e),
         }
     }
 
     fn execute_shelf_remove(&self, fic_id: u64, shelf_id: u64) -> ExitCode {
-        match remove_from_shelf(self.repository, fic_id, shelf_id) {
-            Ok(()) => {
+        let removed = remove_from_shelf(self.repository, fic_id, shelf_id)
+            .and_then(|()| self.find_shelf(shelf_id));
+        match removed {
+            Ok(shelf) => self.emit(&shelf, || {
                 println!("Removed fanfiction {} from shelf {}.", fic_id, shelf_id);
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("removing fanfiction from shelf", &e);
-                ExitCode::FAILURE
-            }
+            }),
+            Err(e) => self.fail("removing fanfiction from shelf", &e),
         }
     }
 
+    fn find_shelf(&self, shelf_id: u64) -> Result<Shelf, FicflowError> {
+        list_shelves(self.repository)?
+            .into_iter()
+            .find(|s| s.id == shelf_id)
+            .ok_or(FicflowError::ShelfNotFound { shelf_id })
+    }
+
     fn execute_shelf_show(&self, shelf_id: u64) -> ExitCode {
         match list_shelf_fics(self.repository, shelf_id) {
-            Ok(fics) => {
+            Ok(fics) => self.emit(&fics, || {
                 println!("{}", list_view::render_fanfiction_list(&fics));
-                ExitCode::SUCCESS
+            }),
+            Err(e) => self.fail("listing shelf contents", &e),
+        }
+    }
+
+    fn execute_saved_search_list(&self) -> ExitCode {
+        // Shelves are only needed to name the shelf a search is scoped to.
+        let listed = list_saved_searches(self.repository)
+            .and_then(|searches| list_shelves(self.repository).map(|shelves| (searches, shelves)));
+        match listed {
+            Ok((searches, shelves)) => self.emit(&searches, || {
+                println!(
+                    "{}",
+                    saved_search_list_view::render_saved_search_list(&searches, &shelves)
+                );
+            }),
+            Err(e) => self.fail("listing saved searches", &e),
+        }
+    }
+
+    fn execute_saved_search_run(&self, saved_search_id: u64) -> ExitCode {
+        match run_saved_search(
+            self.repository,
+            self.repository,
+            self.repository,
+            saved_search_id,
+        ) {
+            Ok((search, fics)) => self.emit(&fics, || {
+                println!("Running saved search \"{}\"", search.name);
+                println!("{}", list_view::render_fanfiction_list(&fics));
+            }),
+            Err(e) => self.fail("running saved search", &e),
+        }
+    }
+
+    fn execute_trash_list(&self) -> ExitCode {
+        let listed = list_trashed_fics(self.repository)
+            .and_then(|fics| list_trashed_shelves(self.repository).map(|shelves| (fics, shelves)));
+        match listed {
+            Ok((fics, shelves)) => self.emit(&json!({ "fics": fics, "shelves": shelves }), || {
+                println!("{}", trash_list_view::render_trash_list(&fics, &shelves));
+            }),
+            Err(e) => self.fail("listing trash", &e),
+        }
+    }
+
+    fn execute_trash_restore_fic(&self, fic_id: u64) -> ExitCode {
+        match restore_fic(self.repository, fic_id) {
+            Ok(fic) => self.emit(&fic, || {
+                println!("Restored \"{}\" (ID: {}).", fic.title, fic.id);
+            }),
+            Err(e) => self.fail("restoring fanfiction", &e),
+        }
+    }
+
+    fn execute_trash_restore_shelf(&self, shelf_id: u64) -> ExitCode {
+        match restore_shelf(self.repository, shelf_id) {
+            Ok(shelf) => self.emit(&shelf, || {
+                println!("Restored shelf {} (\"{}\").", shelf.id, shelf.name);
+            }),
+            Err(e) => self.fail("restoring shelf", &e),
+        }
+    }
+
+    fn execute_trash_purge_fic(&self, fic_id: u64) -> ExitCode {
+        // Looked up first: once purged there's nothing left to print.
+        let purged = list_trashed_fics(self.repository).and_then(|trashed| {
+            purge_fic(self.repository, fic_id)?;
+            Ok(trashed.into_iter().find(|t| t.fic.id == fic_id))
+        });
+        match purged {
+            Ok(trashed) => self.emit(&trashed, || {
+                println!("Permanently deleted fanfiction {}.", fic_id);
+            }),
+            Err(e) => self.fail("purging fanfiction", &e),
+        }
+    }
+
+    fn execute_trash_purge_shelf(&self, shelf_id: u64) -> ExitCode {
+        let purged = list_trashed_shelves(self.repository).and_then(|trashed| {
+            purge_shelf(self.repository, shelf_id)?;
+            Ok(trashed.into_iter().find(|t| t.shelf.id == shelf_id))
+        });
+        match purged {
+            Ok(trashed) => self.emit(&trashed, || {
+                println!("Permanently deleted shelf {}.", shelf_id);
+            }),
+            Err(e) => self.fail("purging shelf", &e),
+        }
+    }
+
+    fn execute_trash_empty(&self, older_than_days: Option<u32>) -> ExitCode {
+        let warning = match older_than_days {
+            Some(days) => format!(
+                "WARNING: This action will permanently delete everything deleted more than \
+                 {} days ago. This process CANNOT be reversed!",
+                days
+            ),
+            None => "WARNING: This action will permanently delete everything in the trash. \
+                     This process CANNOT be reversed!"
+                .to_string(),
+        };
+        if !confirm(&warning, self.output) {
+            self.note("Operation cancelled.");
+            return ExitCode::SUCCESS;
+        }
+
+        match purge_trash(self.repository, older_than_days) {
+            Ok(summary) => self.emit(&summary, || {
+                println!(
+                    "Permanently deleted {} fanfictions and {} shelves.",
+                    summary.fics, summary.shelves
+                );
+            }),
+            Err(e) => self.fail("emptying trash", &e),
+        }
+    }
+
+    fn execute_export(&self, args: &ExportArgs) -> ExitCode {
+        let exported = parse_export_format(&args.format).and_then(|format| {
+            match format.delimiter() {
+                // The table formats end every row with a newline already.
+                None => export_library(self.repository).map(|json| json + "\n"),
+                Some(delimiter) => self.export_table(args, delimiter),
             }
-            Err(e) => {
-                report_error("listing shelf contents", &e);
+        });
+        let document = match exported {
+            Ok(document) => document,
+            Err(e) => return self.fail("exporting library", &e),
+        };
+        // Standard output gets the document and nothing else, so it can
+        // be piped; the document is the output whatever the format.
+        let Some(path) = &args.output else {
+            return match io::stdout().write_all(document.as_bytes()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => self.fail("exporting library", &FicflowError::Io(e)),
+            };
+        };
+        match fs::write(path, document) {
+            Ok(()) => self.emit(&json!({ "path": path }), || {
+                println!("Exported library to {}.", path.display());
+            }),
+            Err(e) => self.fail("exporting library", &FicflowError::Io(e)),
+        }
+    }
+
+    fn export_table(&self, args: &ExportArgs, delimiter: u8) -> Result<String, FicflowError> {
+        let options = TableOptions {
+            fields: parse_table_fields(&args.columns)?,
+            delimiter,
+            list_separator: args.separator.clone(),
+        };
+        let query = FicQuery {
+            filter: FicFilter {
+                status: args
+                    .status
+                    .as_deref()
+                    .map(parse_reading_status)
+                    .transpose()?,
+                shelf_id: args
+                    .shelf
+                    .as_deref()
+                    .map(|s| self.resolve_shelf(s))
+                    .transpose()?,
+                text: args.search.clone(),
+                ..FicFilter::default()
+            },
+            sort: vec![parse_sort_pref(&args.sort)?],
+            ..FicQuery::default()
+        };
+        export_fic_table(self.repository, &query, &options)
+    }
+
+    fn execute_import(&self, args: &ImportArgs) -> ExitCode {
+        match parse_import_source(&args.source) {
+            Ok(ImportSource::Library) => self.execute_import_library(args),
+            Ok(ImportSource::Calibre) => self.execute_import_calibre(args),
+            Ok(ImportSource::Bookmarks) => match BookmarksFile::open(&args.path) {
+                Ok(file) => self.execute_import_links(args, &file, "bookmarks file"),
+                Err(e) => self.fail("reading bookmarks file", &e),
+            },
+            Ok(ImportSource::Firefox) => match FirefoxPlaces::open(&args.path) {
+                Ok(places) => self.execute_import_links(args, &places, "Firefox profile"),
+                Err(e) => self.fail("reading Firefox profile", &e),
+            },
+            Err(e) => self.fail("importing", &e),
+        }
+    }
+
+    fn execute_merge(&self, path: &Path, policy: &str, dry_run: bool) -> ExitCode {
+        let parsed =
+            parse_merge_policy(policy).and_then(|policy| Ok((policy, read_library_file(path)?)));
+        let (policy, snapshot) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return self.fail("reading library file", &e),
+        };
+        match merge_library(self.repository, &snapshot, policy, dry_run) {
+            Ok(report) => self.emit(&report, || {
+                println!("{}", merge_view::render_merge_report(&report, dry_run));
+            }),
+            Err(e) => self.fail("merging library", &e),
+        }
+    }
+
+    /// Fails while any problem is left, so scripts can tell a healthy
+    /// library apart.
+    fn execute_doctor(&self, fix: bool) -> ExitCode {
+        let issues = match check_library(self.repository) {
+            Ok(issues) => issues,
+            Err(e) => return self.fail("checking the library", &e),
+        };
+        if !fix || !issues.iter().any(|i| i.repair().is_some()) {
+            let printed = self.emit(&issues, || {
+                println!("{}", health_report_view::render_library_issues(&issues));
+            });
+            return if issues.is_empty() {
+                printed
+            } else {
                 ExitCode::FAILURE
+            };
+        }
+
+        match create_backup(self.backups, BackupReason::Repair, self.config.backup_keep) {
+            Ok(backup) => self.note(&format!("Backed up to {}", backup.path.display())),
+            Err(e) => return self.fail("backing up before the repair", &e),
+        }
+        match repair_library(self.repository) {
+            Ok(report) => {
+                let printed = self.emit(&report, || {
+                    println!("{}", health_report_view::render_repair_report(&report));
+                });
+                if report.remaining.is_empty() {
+                    printed
+                } else {
+                    ExitCode::FAILURE
+                }
             }
+            Err(e) => self.fail("repairing the library", &e),
+        }
+    }
+
+    fn execute_tui(&self) -> ExitCode {
+        match run_tui(self.repository, self.db_path) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => self.fail("running the terminal interface", &e),
+        }
+    }
+
+    fn execute_serve(&self, bind: &str) -> ExitCode {
+        match run_server(self.repository, bind, self.db_path) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => self.fail("serving the library", &e),
         }
     }
 
+    fn execute_native_host(&self) -> ExitCode {
+        let stdin = io::stdin().lock();
+        let stdout = io::stdout().lock();
+        match run_native_host(self.fetcher, self.repository, self.db_path, stdin, stdout) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => self.fail("talking to the browser", &e),
+        }
+    }
+
+    fn execute_native_host_manifest(
+        &self,
+        browser: Browser,
+        extension_id: &str,
+        install: bool,
+    ) -> ExitCode {
+        if !install {
+            let printed = manifest::launcher_path()
+                .ok_or_else(|| {
+                    FicflowError::Other("no config directory available on this platform".into())
+                })
+                .and_then(|launcher| manifest::manifest(browser, extension_id, &launcher))
+                .and_then(|manifest| Ok(serde_json::to_string_pretty(&manifest)?));
+            return match printed {
+                Ok(manifest) => {
+                    println!("{}", manifest);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => self.fail("writing the manifest", &e),
+            };
+        }
+        let installed = env::current_exe()
+            .map_err(FicflowError::from)
+            .and_then(|exe| manifest::install(browser, extension_id, &exe));
+        match installed {
+            Ok(installed) => {
+                println!(
+                    "Wrote the host manifest to {}",
+                    installed.manifest.display()
+                );
+                println!("and its launcher to {}", installed.launcher.display());
+                if let Some(registry) = installed.registry {
+                    println!("Register it with the browser by running:\n  {}", registry);
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => self.fail("installing the manifest", &e),
+        }
+    }
+
+    /// Prints the desktop entry for `ficflow://` links, or installs it and
+    /// makes it the scheme's handler with `install`.
+    fn execute_url_handler(&self, install: bool) -> ExitCode {
+        let exe = match env::current_exe() {
+            Ok(exe) => exe,
+            Err(e) => return self.fail("finding the ficflow binary", &e.into()),
+        };
+        if !install {
+            print!("{}", link::desktop_entry(&exe));
+            return ExitCode::SUCCESS;
+        }
+        match link::install(&exe) {
+            Ok(installed) => {
+                println!(
+                    "Wrote the desktop entry to {}",
+                    installed.desktop_file.display()
+                );
+                if installed.registered {
+                    println!("ficflow:// links now open in Ficflow");
+                } else {
+                    println!(
+                        "Couldn't run xdg-mime; make it the handler for x-scheme-handler/{} \
+                         in your desktop's default applications",
+                        link::SCHEME
+                    );
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => self.fail("installing the link handler", &e),
+        }
+    }
+
+    fn execute_import_library(&self, args: &ImportArgs) -> ExitCode {
+        let dry_run = args.dry_run;
+        let parsed = parse_import_mode(&args.mode).and_then(|mode| {
+            let json = fs::read_to_string(&args.path)?;
+            Ok((mode, parse_library_snapshot(&json)?))
+        });
+        let (mode, snapshot) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return self.fail("reading library file", &e),
+        };
+
+        if mode == ImportMode::Replace
+            && !dry_run
+            && !confirm(
+                "WARNING: This action will permanently delete every fanfiction and shelf \
+                 in your library, trash included, before importing. \
+                 This process CANNOT be reversed!",
+                self.output,
+            )
+        {
+            self.note("Operation cancelled.");
+            return ExitCode::SUCCESS;
+        }
+
+        match import_library(self.repository, &snapshot, mode, dry_run) {
+            Ok(summary) => self.emit(&summary, || {
+                println!(
+                    "{}",
+                    import_summary_view::render_import_summary(&summary, mode, dry_run)
+                );
+            }),
+            Err(e) => self.fail("importing library", &e),
+        }
+    }
+
+    /// Adds the AO3 works linked from a browser's bookmarks or history,
+    /// fetching each from AO3, shelving it under its bookmark folder and
+    /// guessing how far it was read from the last visit.
+    fn execute_import_links(
+        &self,
+        args: &ImportArgs,
+        source: &dyn LinkSource,
+        source_name: &str,
+    ) -> ExitCode {
+        let planned = source.read_links().and_then(|links| {
+            let works = links
+                .into_iter()
+                .filter_map(|link| extract_ao3_id(&link.url).ok().map(|id| (id, link)))
+                .collect();
+            plan_link_import(self.repository, works)
+        });
+        let mut plan = match planned {
+            Ok(plan) => plan,
+            Err(e) => return self.fail("reading browser links", &e),
+        };
+        plan.use_folders = !args.no_shelves;
+        if args.dry_run {
+            return self.emit(&plan, || {
+                println!(
+                    "{}",
+                    fic_import_view::render_link_import_plan(&plan, source_name)
+                );
+                println!("Dry run: nothing was changed.");
+            });
+        }
+        self.note(&fic_import_view::render_link_import_plan(
+            &plan,
+            source_name,
+        ));
+
+        let shelf_names = plan.shelf_names();
+        let shelves = match find_or_create_shelves(self.repository, &shelf_names) {
+            Ok(shelves) => shelves,
+            Err(e) => return self.fail("creating shelves", &e),
+        };
+        let mut added = Vec::new();
+        let mut failed = Vec::new();
+        for work in plan.selected() {
+            self.note(&format!("Fetching {}...", work.fic_id));
+            let shelf_id = work.folder.as_ref().and_then(|f| shelves.get(f)).copied();
+            let outcome =
+                add_fanfiction(self.fetcher, self.repository, work.fic_id).and_then(|_| {
+                    if let Some(shelf_id) = shelf_id {
+                        add_to_shelf(self.repository, work.fic_id, shelf_id)?;
+                    }
+                    if let Some(visited) = work.last_visited {
+                        apply_visit_hint(self.repository, work.fic_id, visited)?;
+                    }
+                    get_fanfiction(self.repository, work.fic_id)
+                });
+            match outcome {
+                Ok(fic) => added.push(fic),
+                Err(e) => {
+                    self.report("adding fanfiction", &e);
+                    failed.push(work.fic_id);
+                }
+            }
+        }
+        let printed = self.emit(
+            &json!({ "added": added, "shelves": shelves.len(), "failed": failed }),
+            || {
+                println!(
+                    "{}",
+                    fic_import_view::render_link_import_summary(
+                        added.len(),
+                        shelves.len(),
+                        &failed
+                    )
+                );
+            },
+        );
+        if failed.is_empty() {
+            printed
+        } else {
+            ExitCode::FAILURE
+        }
+    }
+
+    fn execute_import_calibre(&self, args: &ImportArgs) -> ExitCode {
+        let columns = CalibreColumns {
+            read: args.read_column.clone(),
+            rating: args.rating_column.clone(),
+            url: args.url_column.clone(),
+        };
+        let planned = parse_conflict_choice(&args.on_conflict).and_then(|choice| {
+            let library = CalibreLibrary::open(&args.path, columns)?;
+            let mut plan = plan_fic_import(self.repository, self.repository, &library)?;
+            plan.set_all_choices(choice);
+            Ok(plan)
+        });
+        let plan = match planned {
+            Ok(plan) => plan,
+            Err(e) => return self.fail("reading Calibre library", &e),
+        };
+        if args.dry_run {
+            return self.emit(&plan, || {
+                println!(
+                    "{}",
+                    fic_import_view::render_fic_import_plan(&plan, "Calibre library")
+                );
+                println!("Dry run: nothing was changed.");
+            });
+        }
+        self.note(&fic_import_view::render_fic_import_plan(
+            &plan,
+            "Calibre library",
+        ));
+
+        let summary = match apply_fic_import(self.repository, self.repository, &plan) {
+            Ok(summary) => summary,
+            Err(e) => return self.fail("importing from Calibre", &e),
+        };
+        // The new fics only have what Calibre knew; AO3 has the rest.
+        let mut fetched = 0;
+        let mut failed = Vec::new();
+        if !args.no_fetch {
+            for entry in &plan.new {
+                self.note(&format!("Fetching metadata for {}...", entry.fic.id));
+                match check_fic_updates(self.fetcher, self.repository, entry.fic.id) {
+                    Ok(_) => fetched += 1,
+                    Err(e) => {
+                        self.report("fetching metadata", &e);
+                        failed.push(entry.fic.id);
+                    }
+                }
+            }
+        }
+        let result = json!({ "summary": summary, "fetched": fetched, "failed": failed });
+        self.emit(&result, || {
+            println!(
+                "{}",
+                fic_import_view::render_fic_import_summary(
+                    &summary,
+                    "Calibre library",
+                    fetched,
+                    &failed
+                )
+            );
+        })
+    }
+
     fn execute_update_note(&self, fic_id: u64, note: Option<&str>) -> ExitCode {
         // If removing a note, show the current one first so the user sees what's being dropped.
         if note.is_none()
+            && self.output.is_table()
             && let Ok(fic) = get_fanfiction(self.repository, fic_id)
             && let Some(current_note) = &fic.personal_note
         {
@@ -374,32 +1382,39 @@ impl<'a> CliCommandExecutor<'a> {
         }
 
         match update_personal_note(self.repository, fic_id, note) {
-            Ok(fic) => {
-                match &fic.personal_note {
-                    Some(note_text) => {
-                        println!(
-                            "Successfully added note to \"{}\" (ID: {}).",
-                            fic.title, fic_id
-                        );
-                        println!("Note: {}", note_text);
-                    }
-                    None => {
-                        println!(
-                            "Successfully removed note from \"{}\" (ID: {}).",
-                            fic.title, fic_id
-                        );
-                    }
+            Ok(fic) => self.emit(&fic, || match &fic.personal_note {
+                Some(note_text) => {
+                    println!(
+                        "Successfully added note to \"{}\" (ID: {}).",
+                        fic.title, fic_id
+                    );
+                    println!("Note: {}", note_text);
                 }
-                ExitCode::SUCCESS
-            }
-            Err(e) => {
-                report_error("updating personal note", &e);
-                ExitCode::FAILURE
-            }
+                None => {
+                    println!(
+                        "Successfully removed note from \"{}\" (ID: {}).",
+                        fic.title, fic_id
+                    );
+                }
+            }),
+            Err(e) => self.fail("updating personal note", &e),
         }
     }
 }
 
+fn is_number(input: &str) -> bool {
+    !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// What a lookup by name found, or `id` when it found nothing, so the
+/// command goes on to report that ID as missing.
+fn name_or_id(found: Result<u64, FicflowError>, id: u64) -> Result<u64, FicflowError> {
+    match found {
+        Err(FicflowError::TitleNotFound(_) | FicflowError::ShelfNameNotFound(_)) => Ok(id),
+        other => other,
+    }
+}
+
 fn report_error(verb: &str, err: &FicflowError) {
     match err {
         FicflowError::NotFound { fic_id } => {
@@ -414,7 +1429,27 @@ fn report_error(verb: &str, err: &FicflowError) {
                 shelf_id
             );
         }
-        FicflowError::InvalidInput(msg) => {
+        FicflowError::SavedSearchNotFound { saved_search_id } => {
+            eprintln!(
+                "Saved search ID {} not found. Run `ficflow saved-search list` to see available saved searches.",
+                saved_search_id
+            );
+        }
+        FicflowError::NotInTrash { fic_id } => {
+            eprintln!(
+                "Fanfiction ID {} is not in the trash. Run `ficflow trash list` to see what is.",
+                fic_id
+            );
+        }
+        FicflowError::ShelfNotInTrash { shelf_id } => {
+            eprintln!(
+                "Shelf ID {} is not in the trash. Run `ficflow trash list` to see what is.",
+                shelf_id
+            );
+        }
+        FicflowError::InvalidInput(msg)
+        | FicflowError::TitleNotFound(msg)
+        | FicflowError::ShelfNameNotFound(msg) => {
             eprintln!("{}", msg);
         }
         other => {
@@ -423,16 +1458,22 @@ fn report_error(verb: &str, err: &FicflowError) {
     }
 }
 
-fn confirm_wipe() -> bool {
+/// The prompt goes to standard error when standard output is for
+/// scripts.
+fn confirm(warning: &str, output: OutputFormat) -> bool {
     if env::var("FICFLOW_NON_INTERACTIVE").is_ok() {
         return true;
     }
 
-    print!(
-        "WARNING: This action will delete ALL fanfictions from the database. \
-         This process CANNOT be reversed!\nAre you sure you want to continue? (y/N): "
-    );
-    if io::stdout().flush().is_err() {
+    let prompt = format!("{}\nAre you sure you want to continue? (y/N): ", warning);
+    let shown = if output.is_table() {
+        io::stdout()
+            .write_all(prompt.as_bytes())
+            .and_then(|()| io::stdout().flush())
+    } else {
+        io::stderr().write_all(prompt.as_bytes())
+    };
+    if shown.is_err() {
         return false;
     }
 
@@ -446,45 +1487,165 @@ fn confirm_wipe() -> bool {
 
 impl<'a> CommandExecutor for CliCommandExecutor<'a> {
     fn execute_command(&self, command: CliCommand) -> ExitCode {
+        if !self.output.is_table() && !command.has_machine_output() {
+            let message = "This command only prints tables; leave out --output or use \
+                           --output table."
+                .to_string();
+            return self.fail("running the command", &FicflowError::InvalidInput(message));
+        }
+        if command.runs_housekeeping() {
+            // The window did its housekeeping when it opened, and takes
+            // adds itself; anything else written behind its back would
+            // leave it showing the library as it was.
+            if !instance::is_open(&instance::socket_path(self.db_path)) {
+                self.housekeeping();
+            } else if command.changes_library() {
+                return self.fail("changing the library", &FicflowError::LibraryInUse);
+            }
+        }
         match command {
             CliCommand::Add { fic_id } => self.execute_add(fic_id),
-            CliCommand::Delete { fic_id } => self.execute_delete(fic_id),
-            CliCommand::Get { fic_id } => self.execute_get(fic_id),
-            CliCommand::UpdateChapter { fic_id, chapter } => {
-                self.execute_update_chapter(fic_id, chapter)
-            }
-            CliCommand::UpdateStatus { fic_id, status } => {
-                self.execute_update_status(fic_id, &status)
+            CliCommand::AddMany { source, shelf } => self.resolved(
+                shelf.as_deref().map(|s| self.resolve_shelf(s)).transpose(),
+                |shelf_id| self.execute_add_many(&source, shelf_id),
+            ),
+            CliCommand::Delete { fic } => {
+                self.resolved(self.resolve_fic(&fic), |id| self.execute_delete(id))
             }
-            CliCommand::UpdateReadCount { fic_id, read_count } => {
-                self.execute_update_read_count(fic_id, read_count)
+            CliCommand::Get { fic } => {
+                self.resolved(self.resolve_fic(&fic), |id| self.execute_get(id))
             }
-            CliCommand::UpdateRating { fic_id, rating } => {
-                self.execute_update_rating(fic_id, &rating)
-            }
-            CliCommand::UpdateNote { fic_id, note } => {
-                self.execute_update_note(fic_id, note.as_deref())
+            CliCommand::Open { fic, keep_status } => self.resolved(self.resolve_fic(&fic), |id| {
+                self.execute_open(id, keep_status)
+            }),
+            CliCommand::UpdateChapter { fic, chapter } => self
+                .resolved(self.resolve_fic(&fic), |id| {
+                    self.execute_update_chapter(id, chapter)
+                }),
+            CliCommand::UpdateStatus { fic, status } => self
+                .resolved(self.resolve_fic(&fic), |id| {
+                    self.execute_update_status(id, &status)
+                }),
+            CliCommand::UpdateReadCount { fic, read_count } => self
+                .resolved(self.resolve_fic(&fic), |id| {
+                    self.execute_update_read_count(id, read_count)
+                }),
+            CliCommand::UpdateRating { fic, rating } => self
+                .resolved(self.resolve_fic(&fic), |id| {
+                    self.execute_update_rating(id, &rating)
+                }),
+            CliCommand::UpdateNote { fic, note } => self.resolved(self.resolve_fic(&fic), |id| {
+                self.execute_update_note(id, note.as_deref())
+            }),
+            CliCommand::List(args) => self.execute_list(&args),
+            CliCommand::SearchLocal { query } => self.execute_search_local(&query),
+            CliCommand::Search {
+                terms,
+                tag,
+                page,
+                hide_tracked,
+            } => {
+                let search = if tag {
+                    Ao3Search::Tag(terms)
+                } else {
+                    Ao3Search::Text(terms)
+                };
+                self.execute_search(&search, page, hide_tracked)
             }
-            CliCommand::List => self.execute_list(),
             CliCommand::Wipe => self.execute_wipe(),
             CliCommand::Shelf(sub) => match sub {
-                ShelfCommand::Create { name, parent } => self.execute_shelf_create(&name, parent),
-                ShelfCommand::Delete { shelf_id } => self.execute_shelf_delete(shelf_id),
-                ShelfCommand::Rename { shelf_id, new_name } => {
-                    self.execute_shelf_rename(shelf_id, &new_name)
-                }
-                ShelfCommand::Move { shelf_id, parent } => {
-                    self.execute_shelf_move(shelf_id, parent)
+                ShelfCommand::Create { name, parent } => self.resolved(
+                    parent.as_deref().map(|p| self.resolve_shelf(p)).transpose(),
+                    |parent| self.execute_shelf_create(&name, parent),
+                ),
+                ShelfCommand::Delete { shelf } => self.resolved(self.resolve_shelf(&shelf), |id| {
+                    self.execute_shelf_delete(id)
+                }),
+                ShelfCommand::Rename { shelf, new_name } => self
+                    .resolved(self.resolve_shelf(&shelf), |id| {
+                        self.execute_shelf_rename(id, &new_name)
+                    }),
+                ShelfCommand::Move { shelf, parent } => self.resolved(
+                    self.resolve_shelf(&shelf).and_then(|id| {
+                        let parent = parent.as_deref().map(|p| self.resolve_shelf(p));
+                        Ok((id, parent.transpose()?))
+                    }),
+                    |(id, parent)| self.execute_shelf_move(id, parent),
+                ),
+                ShelfCommand::Pin { shelf } => {
+                    self.resolved(self.resolve_shelf(&shelf), |id| self.execute_shelf_pin(id))
                 }
-                ShelfCommand::Pin { shelf_id } => self.execute_shelf_pin(shelf_id),
-                ShelfCommand::Unpin { shelf_id } => self.execute_shelf_unpin(shelf_id),
+                ShelfCommand::Unpin { shelf } => self.resolved(self.resolve_shelf(&shelf), |id| {
+                    self.execute_shelf_unpin(id)
+                }),
                 ShelfCommand::List => self.execute_shelf_list(),
-                ShelfCommand::Add { fic_id, shelf_id } => self.execute_shelf_add(fic_id, shelf_id),
-                ShelfCommand::Remove { fic_id, shelf_id } => {
-                    self.execute_shelf_remove(fic_id, shelf_id)
+                ShelfCommand::Add { fic, shelf } => self.resolved(
+                    self.resolve_fic(&fic)
+                        .and_then(|fic_id| Ok((fic_id, self.resolve_shelf(&shelf)?))),
+                    |(fic_id, shelf_id)| self.execute_shelf_add(fic_id, shelf_id),
+                ),
+                ShelfCommand::Remove { fic, shelf } => self.resolved(
+                    self.resolve_fic(&fic)
+                        .and_then(|fic_id| Ok((fic_id, self.resolve_shelf(&shelf)?))),
+                    |(fic_id, shelf_id)| self.execute_shelf_remove(fic_id, shelf_id),
+                ),
+                ShelfCommand::Show { shelf } => {
+                    self.resolved(self.resolve_shelf(&shelf), |id| self.execute_shelf_show(id))
                 }
-                ShelfCommand::Show { shelf_id } => self.execute_shelf_show(shelf_id),
             },
+            CliCommand::SavedSearch(sub) => match sub {
+                SavedSearchCommand::List => self.execute_saved_search_list(),
+                SavedSearchCommand::Run { saved_search_id } => {
+                    self.execute_saved_search_run(saved_search_id)
+                }
+            },
+            CliCommand::Trash(sub) => match sub {
+                TrashCommand::List => self.execute_trash_list(),
+                TrashCommand::RestoreFic { fic } => self
+                    .resolved(self.resolve_trashed_fic(&fic), |id| {
+                        self.execute_trash_restore_fic(id)
+                    }),
+                TrashCommand::RestoreShelf { shelf } => self
+                    .resolved(self.resolve_trashed_shelf(&shelf), |id| {
+                        self.execute_trash_restore_shelf(id)
+                    }),
+                TrashCommand::PurgeFic { fic } => self
+                    .resolved(self.resolve_trashed_fic(&fic), |id| {
+                        self.execute_trash_purge_fic(id)
+                    }),
+                TrashCommand::PurgeShelf { shelf } => self
+                    .resolved(self.resolve_trashed_shelf(&shelf), |id| {
+                        self.execute_trash_purge_shelf(id)
+                    }),
+                TrashCommand::Empty { older_than_days } => {
+                    self.execute_trash_empty(older_than_days)
+                }
+            },
+            CliCommand::Export(args) => self.execute_export(&args),
+            CliCommand::Import(args) => self.execute_import(&args),
+            CliCommand::Merge {
+                path,
+                policy,
+                dry_run,
+            } => self.execute_merge(&path, &policy, dry_run),
+            CliCommand::Backup(sub) => match sub {
+                BackupCommand::Create => self.execute_backup_create(),
+                BackupCommand::List => self.execute_backup_list(),
+                BackupCommand::Prune { keep } => self.execute_backup_prune(keep),
+            },
+            CliCommand::Doctor { fix } => self.execute_doctor(fix),
+            CliCommand::Config(sub) => self.execute_config(sub),
+            CliCommand::Serve { bind } => self.execute_serve(&bind),
+            CliCommand::NativeHost(NativeHostCommand::Run) => self.execute_native_host(),
+            CliCommand::UrlHandler { install } => self.execute_url_handler(install),
+            CliCommand::NativeHost(NativeHostCommand::Manifest {
+                browser,
+                extension_id,
+                install,
+            }) => self.resolved(Browser::parse(&browser), |browser| {
+                self.execute_native_host_manifest(browser, &extension_id, install)
+            }),
+            CliCommand::Tui => self.execute_tui(),
         }
     }
 }
@@ -1,16 +1,32 @@
 pub mod command;
+pub mod completion;
 pub mod executor;
+pub mod output;
 pub mod views;
 
+use std::path::Path;
 use std::process::ExitCode;
 
-use crate::domain::fanfiction::FanfictionFetcher;
+use crate::domain::backup::BackupOps;
+use crate::domain::discover::Archive;
+use crate::domain::fanfiction::LinkOpener;
 use crate::domain::repository::Repository;
+use crate::interfaces::gui::AppConfig;
+use command::CliCommand;
 use executor::CommandExecutor;
+use output::OutputFormat;
 
-pub fn run_cli(fetcher: &dyn FanfictionFetcher, repository: &dyn Repository) -> ExitCode {
-    let command = command::parse_cli_commands();
-    let executor = executor::CliCommandExecutor::new(fetcher, repository);
-
+pub fn run_cli(
+    fetcher: &dyn Archive,
+    repository: &dyn Repository,
+    backups: &dyn BackupOps,
+    config: &AppConfig,
+    opener: &dyn LinkOpener,
+    db_path: &Path,
+    (output, command): (OutputFormat, CliCommand),
+) -> ExitCode {
+    let executor = executor::CliCommandExecutor::new(
+        fetcher, repository, backups, config, opener, output, db_path,
+    );
     executor.execute_command(command)
 }
@@ -0,0 +1,66 @@
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::error::FicflowError;
+
+/// How the CLI prints what a command did, picked with the top-level
+/// `--output` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Tables and sentences, for people.
+    #[default]
+    Table,
+    /// One pretty-printed JSON document.
+    Json,
+    /// One compact JSON value per line; a list prints a line per item.
+    Ndjson,
+}
+
+impl OutputFormat {
+    pub const NAMES: [&'static str; 3] = ["table", "json", "ndjson"];
+
+    pub fn parse(input: &str) -> Option<OutputFormat> {
+        match input {
+            "table" => Some(OutputFormat::Table),
+            "json" => Some(OutputFormat::Json),
+            "ndjson" => Some(OutputFormat::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn is_table(self) -> bool {
+        self == OutputFormat::Table
+    }
+}
+
+/// `value` as the machine formats print it, without a trailing newline.
+/// Table output is the views' job, so it gets the JSON document.
+pub fn render_value<T: Serialize>(format: OutputFormat, value: &T) -> Result<String, FicflowError> {
+    let value = serde_json::to_value(value)?;
+    match (format, value) {
+        (OutputFormat::Ndjson, Value::Array(items)) => Ok(items
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n")),
+        (OutputFormat::Ndjson, value) => Ok(serde_json::to_string(&value)?),
+        (_, value) => Ok(serde_json::to_string_pretty(&value)?),
+    }
+}
+
+/// The error object printed to standard error in the machine formats.
+/// Its shape is stable: `code` is `FicflowError::code` and `exit_code`
+/// is the status the process ends with.
+pub fn render_error(format: OutputFormat, err: &FicflowError) -> String {
+    let object = json!({
+        "error": {
+            "code": err.code(),
+            "message": err.to_string(),
+            "exit_code": err.exit_code(),
+        }
+    });
+    match format {
+        OutputFormat::Ndjson => object.to_string(),
+        _ => serde_json::to_string_pretty(&object).unwrap_or_else(|_| object.to_string()),
+    }
+}
@@ -0,0 +1,93 @@
+use crate::domain::discover::{Ao3Search, BlurbPage, WorkBlurb};
+use crate::interfaces::utils::formatter;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_ao3_results(search: &Ao3Search, found: &BlurbPage) -> String {
+    let mut output = format!(
+        "Page {} of {} of AO3 {}",
+        found.page, found.page_count, search
+    );
+    if found.hidden > 0 {
+        output.push_str(&format!(
+            ", leaving out {} already in your library",
+            found.hidden
+        ));
+    }
+
+    if found.blurbs.is_empty() {
+        output.push_str(":\n\nNothing new on this page.");
+    } else {
+        output.push_str(":\n\n");
+        output.push_str(&results_table(&found.blurbs));
+    }
+
+    if found.has_next() {
+        output.push_str(&format!(
+            "\nMore with --page {}. Add a work with `ficflow add <ID>`.",
+            found.page + 1
+        ));
+    } else {
+        output.push_str("\nAdd a work with `ficflow add <ID>`.");
+    }
+    output
+}
+
+fn results_table(blurbs: &[WorkBlurb]) -> String {
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(
+        [
+            "ID",
+            "Title",
+            "Author(s)",
+            "Fandoms",
+            "Words",
+            "Chapters",
+            "Kudos",
+            "In library",
+        ]
+        .into_iter()
+        .map(|label| TableCell::new_with_alignment(label, 1, Alignment::Center))
+        .collect::<Vec<_>>(),
+    ));
+
+    for blurb in blurbs {
+        let chapters = format!(
+            "{}/{}",
+            blurb.chapters_published,
+            blurb
+                .chapters_total
+                .map_or("?".to_string(), |total| total.to_string())
+        );
+        #[allow(deprecated)]
+        let row_cells = vec![
+            TableCell::new_with_alignment(blurb.id, 1, Alignment::Right),
+            TableCell::new(&blurb.title),
+            TableCell::new(blurb.authors.join(", ")),
+            TableCell::new(blurb.fandoms.join(", ")),
+            TableCell::new_with_alignment(
+                formatter::format_word_count(blurb.words),
+                1,
+                Alignment::Right,
+            ),
+            TableCell::new_with_alignment(chapters, 1, Alignment::Right),
+            TableCell::new_with_alignment(
+                formatter::format_word_count(blurb.kudos),
+                1,
+                Alignment::Right,
+            ),
+            TableCell::new_with_alignment(
+                if blurb.tracked { "yes" } else { "" },
+                1,
+                Alignment::Center,
+            ),
+        ];
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.render()
+}
@@ -0,0 +1,37 @@
+use crate::domain::backup::BackupInfo;
+use crate::interfaces::utils::formatter::format_file_size;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_backup_list(backups: &[BackupInfo]) -> String {
+    if backups.is_empty() {
+        return "No backups yet.".to_string();
+    }
+
+    let mut output = format!("Found {} backups:\n\n", backups.len());
+
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Taken", 1, Alignment::Center),
+        TableCell::new_with_alignment("Reason", 1, Alignment::Center),
+        TableCell::new_with_alignment("Size", 1, Alignment::Center),
+        TableCell::new_with_alignment("File", 1, Alignment::Center),
+    ]));
+
+    for backup in backups {
+        #[allow(deprecated)]
+        table.add_row(Row::new(vec![
+            TableCell::new(backup.created_at.format("%Y-%m-%d %H:%M").to_string()),
+            TableCell::new(backup.reason.as_str()),
+            TableCell::new_with_alignment(format_file_size(backup.size_bytes), 1, Alignment::Right),
+            TableCell::new(backup.path.display()),
+        ]));
+    }
+
+    output.push_str(&table.render());
+    output
+}
@@ -0,0 +1,30 @@
+use crate::interfaces::gui::Setting;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+/// How an unset setting's value is shown.
+pub const UNSET: &str = "(default)";
+
+pub fn render_settings(settings: &[(Setting, Option<String>)]) -> String {
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Setting", 1, Alignment::Center),
+        TableCell::new_with_alignment("Value", 1, Alignment::Center),
+        TableCell::new_with_alignment("Takes", 1, Alignment::Center),
+    ]));
+
+    for (setting, value) in settings {
+        #[allow(deprecated)]
+        table.add_row(Row::new(vec![
+            TableCell::new(setting.key()),
+            TableCell::new(value.as_deref().unwrap_or(UNSET)),
+            TableCell::new(setting.hint()),
+        ]));
+    }
+
+    table.render()
+}
@@ -100,6 +100,13 @@ pub fn render_fanfiction_details(fic: &Fanfiction) -> String {
         output.push_str(&format!("Your Rating:         {} / 5\n", *rating as u8));
     }
 
+    if !fic.personal_tags.is_empty() {
+        output.push_str(&format!(
+            "Your Tags:           {}\n",
+            fic.personal_tags.join(", ")
+        ));
+    }
+
     if let Some(note) = &fic.personal_note {
         output.push_str(&format!("\nPersonal Note:\n{}\n", note));
     }
@@ -0,0 +1,175 @@
+use crate::domain::fanfiction::{Fanfiction, ReadingStatus, UserRating};
+use crate::domain::transfer::{
+    ConflictChoice, FicImportPlan, FicImportSummary, FoundWork, ImportConflict, LinkImportPlan,
+};
+
+/// What an import from another app's library found, before anything is
+/// written: counts, then one line per conflict with both sides.
+pub fn render_fic_import_plan(plan: &FicImportPlan, source: &str) -> String {
+    let found = plan.new.len() + plan.conflicts.len() + plan.unchanged;
+    let mut lines = vec![format!("Found {} AO3 works in the {}.", found, source)];
+    lines.push(format!("  {} not in your library yet", plan.new.len()));
+    let trashed = plan.conflicts.iter().filter(|c| c.trashed).count();
+    lines.push(format!(
+        "  {} already in your library with a different status or rating{}",
+        plan.conflicts.len() - trashed,
+        if trashed > 0 {
+            format!(", or in your trash ({})", trashed)
+        } else {
+            String::new()
+        }
+    ));
+    for conflict in &plan.conflicts {
+        lines.push(render_conflict(conflict));
+    }
+    lines.push(format!(
+        "  {} already in your library as they are",
+        plan.unchanged
+    ));
+    lines.join("\n")
+}
+
+fn render_conflict(conflict: &ImportConflict) -> String {
+    let ImportConflict {
+        local,
+        incoming,
+        choice,
+        trashed,
+    } = conflict;
+    let mut differences = Vec::new();
+    if *trashed {
+        differences.push("in your trash".to_string());
+    }
+    if let Some(status) = incoming.reading_status
+        && status != local.reading_status
+    {
+        differences.push(format!(
+            "status {} (yours: {})",
+            status_label(status),
+            status_label(local.reading_status)
+        ));
+    }
+    if let Some(rating) = incoming.user_rating
+        && Some(rating) != local.user_rating
+    {
+        differences.push(format!(
+            "rating {} (yours: {})",
+            rating_label(Some(rating)),
+            rating_label(local.user_rating)
+        ));
+    }
+    let outcome = match (choice, trashed) {
+        (ConflictChoice::KeepLocal, false) => "keeping yours",
+        (ConflictChoice::UseIncoming, false) => "taking theirs",
+        (ConflictChoice::KeepLocal, true) => "leaving it there",
+        (ConflictChoice::UseIncoming, true) => "restoring it with theirs",
+    };
+    format!(
+        "    {:<10} {}: {} [{}]",
+        local.id,
+        title(local),
+        differences.join(", "),
+        outcome
+    )
+}
+
+pub fn render_fic_import_summary(
+    summary: &FicImportSummary,
+    source: &str,
+    fetched: usize,
+    fetch_failed: &[u64],
+) -> String {
+    let mut lines = vec!["Import complete.".to_string()];
+    lines.push(format!("  Added {} fanfictions", summary.added));
+    if fetched > 0 || !fetch_failed.is_empty() {
+        lines.push(format!("  Fetched metadata from AO3 for {}", fetched));
+    }
+    if !fetch_failed.is_empty() {
+        let ids: Vec<String> = fetch_failed.iter().map(u64::to_string).collect();
+        lines.push(format!(
+            "  Couldn't fetch {} ({}); they keep the details from the {} for now",
+            fetch_failed.len(),
+            ids.join(", "),
+            source
+        ));
+    }
+    lines.push(format!(
+        "  Took the other library's status or rating for {}",
+        summary.updated
+    ));
+    lines.push(format!("  Kept your own values for {}", summary.kept));
+    lines.join("\n")
+}
+
+/// What a scan of browser links found: one line per work that would be
+/// added, with the folder it would be shelved under and the last visit.
+pub fn render_link_import_plan(plan: &LinkImportPlan, source: &str) -> String {
+    let found = plan.works.len() + plan.already_tracked;
+    let mut lines = vec![format!("Found {} AO3 works in the {}.", found, source)];
+    lines.push(format!("  {} not in your library yet", plan.works.len()));
+    for work in &plan.works {
+        lines.push(render_found_work(work, plan.use_folders));
+    }
+    lines.push(format!(
+        "  {} already in your library",
+        plan.already_tracked
+    ));
+    lines.join("\n")
+}
+
+fn render_found_work(work: &FoundWork, use_folders: bool) -> String {
+    let mut line = format!(
+        "    {:<10} {}",
+        work.fic_id,
+        work.title.as_deref().unwrap_or("(no title)")
+    );
+    if use_folders && let Some(folder) = &work.folder {
+        line.push_str(&format!(" [shelf: {}]", folder));
+    }
+    if let Some(visited) = work.last_visited {
+        line.push_str(&format!(" [last visited {}]", visited.format("%Y-%m-%d")));
+    }
+    line
+}
+
+pub fn render_link_import_summary(added: usize, shelves: usize, failed: &[u64]) -> String {
+    let mut lines = vec!["Import complete.".to_string()];
+    lines.push(format!("  Added {} fanfictions", added));
+    if shelves > 0 {
+        lines.push(format!("  Filed them on {} shelves", shelves));
+    }
+    if !failed.is_empty() {
+        let ids: Vec<String> = failed.iter().map(u64::to_string).collect();
+        lines.push(format!(
+            "  Couldn't add {} ({})",
+            failed.len(),
+            ids.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+fn title(fic: &Fanfiction) -> &str {
+    if fic.title.is_empty() {
+        "(untitled)"
+    } else {
+        &fic.title
+    }
+}
+
+pub(super) fn status_label(status: ReadingStatus) -> &'static str {
+    match status {
+        ReadingStatus::InProgress => "in progress",
+        ReadingStatus::Read => "read",
+        ReadingStatus::PlanToRead => "plan to read",
+        ReadingStatus::Paused => "paused",
+        ReadingStatus::Abandoned => "abandoned",
+    }
+}
+
+pub(super) fn rating_label(rating: Option<UserRating>) -> String {
+    match rating {
+        Some(rating) => format!("{}/5", rating as u8),
+        None => "none".to_string(),
+    }
+}
@@ -0,0 +1,50 @@
+use crate::domain::health::{LibraryIssue, RepairReport};
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_library_issues(issues: &[LibraryIssue]) -> String {
+    if issues.is_empty() {
+        return "No problems found.".to_string();
+    }
+
+    let mut output = format!("Found {} problems:\n\n", issues.len());
+    output.push_str(&render_issue_table(issues));
+    if issues.iter().any(|i| i.repair().is_some()) {
+        output.push_str("\nRun `ficflow doctor --fix` to make the repairs.");
+    }
+    output
+}
+
+pub fn render_repair_report(report: &RepairReport) -> String {
+    let mut output = format!("Repaired {} problems.", report.repaired.len());
+    if !report.remaining.is_empty() {
+        output.push_str(&format!(
+            "\n{} problems remain:\n\n",
+            report.remaining.len()
+        ));
+        output.push_str(&render_issue_table(&report.remaining));
+    }
+    output
+}
+
+fn render_issue_table(issues: &[LibraryIssue]) -> String {
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Problem", 1, Alignment::Center),
+        TableCell::new_with_alignment("Repair", 1, Alignment::Center),
+    ]));
+
+    for issue in issues {
+        #[allow(deprecated)]
+        table.add_row(Row::new(vec![
+            TableCell::new(issue.describe()),
+            TableCell::new(issue.repair().unwrap_or("none; restore a backup")),
+        ]));
+    }
+
+    table.render()
+}
@@ -0,0 +1,48 @@
+use crate::domain::transfer::{ImportMode, ImportSummary};
+
+pub fn render_import_summary(summary: &ImportSummary, mode: ImportMode, dry_run: bool) -> String {
+    // "Would add" on a dry run, "added" otherwise.
+    let verb = |present: &str, past: &str| {
+        if dry_run {
+            format!("Would {}", present)
+        } else {
+            past.to_string()
+        }
+    };
+
+    let mut lines = vec![if dry_run {
+        "Dry run: nothing was changed.".to_string()
+    } else {
+        "Import complete.".to_string()
+    }];
+    if mode == ImportMode::Replace {
+        lines.push(format!(
+            "  {} {} fanfictions and {} shelves that were in the library",
+            verb("remove", "Removed"),
+            summary.fics_removed,
+            summary.shelves_removed
+        ));
+    }
+    lines.push(format!(
+        "  {} {} fanfictions, {} shelves and {} shelf memberships",
+        verb("add", "Added"),
+        summary.fics_added,
+        summary.shelves_added,
+        summary.memberships_added
+    ));
+    if summary.fics_skipped > 0 {
+        lines.push(format!(
+            "  {} {} fanfictions already in the library",
+            verb("keep", "Kept"),
+            summary.fics_skipped
+        ));
+    }
+    if summary.shelves_matched > 0 {
+        lines.push(format!(
+            "  {} {} existing shelves with the same name",
+            verb("reuse", "Reused"),
+            summary.shelves_matched
+        ));
+    }
+    lines.join("\n")
+}
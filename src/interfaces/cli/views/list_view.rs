@@ -1,18 +1,45 @@
-use crate::domain::fanfiction::{Fanfiction, ReadingStatus};
+use crate::domain::fanfiction::{ColumnKey, Fanfiction, ReadingStatus};
 use crate::interfaces::utils::formatter;
 use term_table::row::Row;
 use term_table::table_cell::{Alignment, TableCell};
 use term_table::{Table, TableStyle};
 
+/// What `list` shows without `--columns`, after the ID.
+pub const DEFAULT_LIST_COLUMNS: [ColumnKey; 5] = [
+    ColumnKey::Title,
+    ColumnKey::Author,
+    ColumnKey::Fandom,
+    ColumnKey::Words,
+    ColumnKey::Status,
+];
+
 pub fn render_fanfiction_list(fanfictions: &[Fanfiction]) -> String {
+    render_fanfiction_table(fanfictions, fanfictions.len(), &DEFAULT_LIST_COLUMNS)
+}
+
+/// `fanfictions` under an ID column and `columns`. `total` is how many
+/// matched before a `--limit`, so a cut-off list says so.
+pub fn render_fanfiction_table(
+    fanfictions: &[Fanfiction],
+    total: usize,
+    columns: &[ColumnKey],
+) -> String {
     if fanfictions.is_empty() {
         return "No fanfictions found in your library.".to_string();
     }
 
-    let mut output = format!(
-        "Found {} fanfictions in your library:\n\n",
-        fanfictions.len()
-    );
+    let mut output = if total > fanfictions.len() {
+        format!(
+            "Showing {} of {} fanfictions in your library:\n\n",
+            fanfictions.len(),
+            total
+        )
+    } else {
+        format!(
+            "Found {} fanfictions in your library:\n\n",
+            fanfictions.len()
+        )
+    };
 
     // Create a table for better display
     let mut table = Table::new();
@@ -21,48 +48,63 @@ pub fn render_fanfiction_list(fanfictions: &[Fanfiction]) -> String {
     // Add header row with centered headers
     // Use #[allow(deprecated)] to silence warnings about new_with_alignment
     #[allow(deprecated)]
-    table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("ID", 1, Alignment::Center),
-        TableCell::new_with_alignment("Title", 1, Alignment::Center),
-        TableCell::new_with_alignment("Author(s)", 1, Alignment::Center),
-        TableCell::new_with_alignment("Fandom(s)", 1, Alignment::Center),
-        TableCell::new_with_alignment("Words", 1, Alignment::Center),
-        TableCell::new_with_alignment("Status", 1, Alignment::Center),
-    ]));
+    let header = std::iter::once("ID")
+        .chain(columns.iter().map(|column| column.label()))
+        .map(|label| TableCell::new_with_alignment(label, 1, Alignment::Center))
+        .collect::<Vec<_>>();
+    table.add_row(Row::new(header));
 
     // Add fanfiction rows
     for fic in fanfictions {
-        let authors = fic.authors.join(", ");
-        let fandoms = fic.fandoms.join(", ");
+        let mut row_cells = vec![TableCell::new(fic.id)];
+        row_cells.extend(columns.iter().map(|column| cell(fic, *column)));
+        table.add_row(Row::new(row_cells));
+    }
 
-        // Format the status (with a symbol)
-        let status = match fic.reading_status {
-            ReadingStatus::PlanToRead => "📚 Plan to Read",
-            ReadingStatus::InProgress => "📖 In Progress",
-            ReadingStatus::Read => "✅ Read",
-            ReadingStatus::Paused => "⏸️ Paused",
-            ReadingStatus::Abandoned => "❌ Abandoned",
-        };
+    output.push_str(&table.render());
+    output
+}
 
-        // Create a row with cells
+fn cell(fic: &Fanfiction, column: ColumnKey) -> TableCell {
+    let number = |n: u32| {
         // Use #[allow(deprecated)] to silence warnings about new_with_alignment
         #[allow(deprecated)]
-        let row_cells = vec![
-            TableCell::new(fic.id),
-            TableCell::new(&fic.title),
-            TableCell::new(authors),
-            TableCell::new(fandoms),
-            TableCell::new_with_alignment(
-                formatter::format_word_count(fic.words),
-                1,
-                Alignment::Right,
-            ),
-            TableCell::new(status),
-        ];
-
-        table.add_row(Row::new(row_cells));
+        TableCell::new_with_alignment(formatter::format_word_count(n), 1, Alignment::Right)
+    };
+    let or_dash = |value: Option<String>| TableCell::new(value.unwrap_or_else(|| "-".into()));
+    match column {
+        ColumnKey::Title => TableCell::new(&fic.title),
+        ColumnKey::Author => TableCell::new(fic.authors.join(", ")),
+        ColumnKey::Fandom => TableCell::new(fic.fandoms.join(", ")),
+        ColumnKey::Pairing => or_dash(fic.relationships.as_ref().map(|r| r.join(", "))),
+        ColumnKey::AO3Rating => TableCell::new(&fic.rating),
+        ColumnKey::Warnings => TableCell::new(
+            fic.warnings
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        ColumnKey::Status => TableCell::new(status_with_symbol(fic.reading_status)),
+        ColumnKey::Complete => TableCell::new(if fic.complete { "Yes" } else { "No" }),
+        ColumnKey::LastChapter => or_dash(fic.last_chapter_read.map(|c| c.to_string())),
+        ColumnKey::Words => number(fic.words),
+        ColumnKey::Kudos => number(fic.kudos),
+        ColumnKey::Hits => number(fic.hits),
+        ColumnKey::Rating => or_dash(fic.user_rating.map(|r| format!("{}/5", r as u8))),
+        ColumnKey::Reads => number(fic.read_count),
+        ColumnKey::Language => TableCell::new(&fic.language),
+        ColumnKey::DatePublished => TableCell::new(fic.date_published.format("%Y-%m-%d")),
+        ColumnKey::Updated => TableCell::new(fic.date_updated.format("%Y-%m-%d")),
     }
+}
 
-    output.push_str(&table.render());
-    output
+fn status_with_symbol(status: ReadingStatus) -> &'static str {
+    match status {
+        ReadingStatus::PlanToRead => "📚 Plan to Read",
+        ReadingStatus::InProgress => "📖 In Progress",
+        ReadingStatus::Read => "✅ Read",
+        ReadingStatus::Paused => "⏸️ Paused",
+        ReadingStatus::Abandoned => "❌ Abandoned",
+    }
 }
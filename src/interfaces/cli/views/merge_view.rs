@@ -0,0 +1,99 @@
+use super::fic_import_view::{rating_label, status_label};
+use crate::domain::transfer::{MergeConflict, MergeReport, MergeSide};
+
+/// Counts, then the conflict log: one line per fic whose user fields
+/// differed, saying what differed and which side was kept.
+pub fn render_merge_report(report: &MergeReport, dry_run: bool) -> String {
+    let verb = |present: &str, past: &str| {
+        if dry_run {
+            format!("Would {}", present)
+        } else {
+            past.to_string()
+        }
+    };
+
+    let mut lines = vec![if dry_run {
+        "Dry run: nothing was changed.".to_string()
+    } else {
+        "Merge complete.".to_string()
+    }];
+    lines.push(format!(
+        "  {} {} fanfictions, {} shelves and {} shelf memberships",
+        verb("add", "Added"),
+        report.fics_added,
+        report.shelves_added,
+        report.memberships_added
+    ));
+    lines.push(format!(
+        "  {} {} fanfictions in both libraries, {} left as they were",
+        verb("update", "Updated"),
+        report.fics_updated,
+        report.fics_unchanged
+    ));
+    if report.shelves_matched > 0 {
+        lines.push(format!(
+            "  {} {} existing shelves with the same name",
+            verb("reuse", "Reused"),
+            report.shelves_matched
+        ));
+    }
+    if !report.conflicts.is_empty() {
+        lines.push(format!("Conflicts ({}):", report.conflicts.len()));
+        for conflict in &report.conflicts {
+            lines.push(render_conflict(conflict));
+        }
+    }
+    lines.join("\n")
+}
+
+fn render_conflict(conflict: &MergeConflict) -> String {
+    let MergeConflict {
+        mine, theirs, kept, ..
+    } = conflict;
+    let mut differences = Vec::new();
+    if mine.reading_status != theirs.reading_status {
+        differences.push(format!(
+            "status {} (theirs: {})",
+            status_label(mine.reading_status),
+            status_label(theirs.reading_status)
+        ));
+    }
+    if mine.last_chapter_read != theirs.last_chapter_read {
+        let chapter = |c: Option<u32>| c.map_or("none".to_string(), |c| c.to_string());
+        differences.push(format!(
+            "last chapter {} (theirs: {})",
+            chapter(mine.last_chapter_read),
+            chapter(theirs.last_chapter_read)
+        ));
+    }
+    if mine.read_count != theirs.read_count {
+        differences.push(format!(
+            "reads {} (theirs: {})",
+            mine.read_count, theirs.read_count
+        ));
+    }
+    if mine.user_rating != theirs.user_rating {
+        differences.push(format!(
+            "rating {} (theirs: {})",
+            rating_label(mine.user_rating),
+            rating_label(theirs.user_rating)
+        ));
+    }
+    if mine.personal_note != theirs.personal_note {
+        differences.push("note".to_string());
+    }
+    if mine.personal_tags != theirs.personal_tags {
+        differences.push("personal tags".to_string());
+    }
+    let outcome = match kept {
+        MergeSide::Mine => "kept yours",
+        MergeSide::Theirs => "took theirs",
+    };
+    format!(
+        "    {:<10} {}: {} [{}]",
+        conflict.fic_id,
+        conflict.title,
+        differences.join(", "),
+        outcome
+    )
+}
@@ -1,3 +1,13 @@
+pub mod ao3_search_view;
+pub mod backup_list_view;
+pub mod config_view;
 pub mod details_view;
+pub mod fic_import_view;
+pub mod health_report_view;
+pub mod import_summary_view;
 pub mod list_view;
+pub mod merge_view;
+pub mod saved_search_list_view;
+pub mod search_result_view;
 pub mod shelf_list_view;
+pub mod trash_list_view;
@@ -0,0 +1,64 @@
+use crate::domain::fanfiction::{ReadingStatus, SortDirection};
+use crate::domain::saved_search::{SavedSearch, SearchScope};
+use crate::domain::shelf::Shelf;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_saved_search_list(searches: &[SavedSearch], shelves: &[Shelf]) -> String {
+    if searches.is_empty() {
+        return "No saved searches found. Save one from the search bar in the GUI.".to_string();
+    }
+
+    let mut output = format!("Found {} saved searches:\n\n", searches.len());
+
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("ID", 1, Alignment::Center),
+        TableCell::new_with_alignment("Name", 1, Alignment::Center),
+        TableCell::new_with_alignment("Query", 1, Alignment::Center),
+        TableCell::new_with_alignment("In", 1, Alignment::Center),
+        TableCell::new_with_alignment("Sort", 1, Alignment::Center),
+    ]));
+
+    for search in searches {
+        let direction = match search.sort.direction {
+            SortDirection::Ascending => "asc",
+            SortDirection::Descending => "desc",
+        };
+        #[allow(deprecated)]
+        let row_cells = vec![
+            TableCell::new_with_alignment(search.id, 1, Alignment::Right),
+            TableCell::new(&search.name),
+            TableCell::new(&search.query),
+            TableCell::new(scope_label(search.scope, shelves)),
+            TableCell::new(format!("{} ({})", search.sort.column.label(), direction)),
+        ];
+        table.add_row(Row::new(row_cells));
+    }
+
+    output.push_str(&table.render());
+    output
+}
+
+fn scope_label(scope: SearchScope, shelves: &[Shelf]) -> String {
+    match scope {
+        SearchScope::AllFics => "All fanfictions".to_string(),
+        SearchScope::ByStatus(status) => match status {
+            ReadingStatus::InProgress => "In Progress",
+            ReadingStatus::Read => "Read",
+            ReadingStatus::PlanToRead => "Plan to Read",
+            ReadingStatus::Paused => "Paused",
+            ReadingStatus::Abandoned => "Abandoned",
+        }
+        .to_string(),
+        SearchScope::Shelf(id) => shelves
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| format!("Shelf \"{}\"", s.name))
+            .unwrap_or_else(|| format!("Shelf {} (deleted)", id)),
+    }
+}
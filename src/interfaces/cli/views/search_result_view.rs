@@ -0,0 +1,54 @@
+use crate::domain::fanfiction::{SearchHit, Snippet};
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_search_results(hits: &[SearchHit]) -> String {
+    if hits.is_empty() {
+        return "No fanfictions in your library match your search.".to_string();
+    }
+
+    let mut output = format!("Found {} matching fanfictions, best first:\n\n", hits.len());
+
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("ID", 1, Alignment::Center),
+        TableCell::new_with_alignment("Title", 1, Alignment::Center),
+        TableCell::new_with_alignment("Author(s)", 1, Alignment::Center),
+        TableCell::new_with_alignment("Match", 1, Alignment::Center),
+    ]));
+
+    for hit in hits {
+        #[allow(deprecated)]
+        let row_cells = vec![
+            TableCell::new_with_alignment(hit.fic.id, 1, Alignment::Right),
+            TableCell::new(&hit.fic.title),
+            TableCell::new(hit.fic.authors.join(", ")),
+            TableCell::new(mark_highlights(&hit.snippet)),
+        ];
+        table.add_row(Row::new(row_cells));
+    }
+
+    output.push_str(&table.render());
+    output
+}
+
+/// Brackets the matched terms — plain text, since the table is often
+/// piped or read in terminals without colour.
+fn mark_highlights(snippet: &Snippet) -> String {
+    snippet
+        .segments()
+        .into_iter()
+        .map(|(text, highlighted)| {
+            let text = text.replace('\n', " ");
+            if highlighted {
+                format!("[{}]", text)
+            } else {
+                text
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,49 @@
+use crate::domain::trash::{TrashedFic, TrashedShelf};
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::{Table, TableStyle};
+
+pub fn render_trash_list(fics: &[TrashedFic], shelves: &[TrashedShelf]) -> String {
+    if fics.is_empty() && shelves.is_empty() {
+        return "The trash is empty.".to_string();
+    }
+
+    let mut output = format!(
+        "Found {} fanfictions and {} shelves in the trash:\n\n",
+        fics.len(),
+        shelves.len()
+    );
+
+    let mut table = Table::new();
+    table.style = TableStyle::thin();
+
+    #[allow(deprecated)]
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Kind", 1, Alignment::Center),
+        TableCell::new_with_alignment("ID", 1, Alignment::Center),
+        TableCell::new_with_alignment("Name", 1, Alignment::Center),
+        TableCell::new_with_alignment("Deleted", 1, Alignment::Center),
+    ]));
+
+    for trashed in shelves {
+        #[allow(deprecated)]
+        table.add_row(Row::new(vec![
+            TableCell::new("shelf"),
+            TableCell::new_with_alignment(trashed.shelf.id, 1, Alignment::Right),
+            TableCell::new(&trashed.shelf.name),
+            TableCell::new(trashed.deleted_at.format("%Y-%m-%d %H:%M").to_string()),
+        ]));
+    }
+    for trashed in fics {
+        #[allow(deprecated)]
+        table.add_row(Row::new(vec![
+            TableCell::new("fic"),
+            TableCell::new_with_alignment(trashed.fic.id, 1, Alignment::Right),
+            TableCell::new(&trashed.fic.title),
+            TableCell::new(trashed.deleted_at.format("%Y-%m-%d %H:%M").to_string()),
+        ]));
+    }
+
+    output.push_str(&table.render());
+    output
+}
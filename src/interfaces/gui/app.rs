@@ -1,41 +1,80 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use egui_notify::Toasts;
 use rusqlite::Connection;
 
-use super::auto_shelf;
+use super::auto_shelf::{self, DistinctValues};
 use super::config::{self, AppConfig, ColumnKey, SortDirection, SortPref, ThemeChoice};
+use super::history::{Change, History};
 use crate::application::{
-    add_to_shelf::add_to_shelf, create_shelf::create_shelf, delete_fic, delete_shelf, move_shelf,
-    pin_shelf::pin_shelf, remove_from_shelf, rename_shelf::rename_shelf, unpin_shelf::unpin_shelf,
-    update_chapters, update_note, update_rating, update_read_count, update_status,
-    upsert_auto_shelf,
+    add_to_shelf::add_to_shelf, apply_fic_import::apply_fic_import, backup_if_due::backup_if_due,
+    bulk_edit_fics::bulk_edit_fics, check_library::check_library, create_backup::create_backup,
+    create_saved_search::create_saved_search, create_shelf::create_shelf, delete_fic,
+    delete_saved_search::delete_saved_search, delete_shelf, export_fic_table::render_fic_table,
+    find_or_create_shelves::find_or_create_shelves, get_fic::get_fanfiction,
+    list_backups::list_backups, list_fics::list_fics, list_shelves_for_fic::list_shelves_for_fic,
+    merge_library::merge_library, move_saved_search::move_saved_search, move_shelf,
+    open_fic::open_fic, pin_shelf::pin_shelf, plan_fic_import::plan_fic_import,
+    plan_link_import::plan_link_import, purge_fic::purge_fic, purge_shelf::purge_shelf,
+    purge_trash::purge_trash, remove_from_shelf, rename_saved_search::rename_saved_search,
+    rename_shelf::rename_shelf, repair_library::repair_library, restore_fic::restore_fic,
+    restore_shelf::restore_shelf, unpin_shelf::unpin_shelf, untracked_fic_ids::untracked_fic_ids,
+    update_chapters, update_note, update_personal_tags, update_rating, update_read_count,
+    update_status, upsert_auto_shelf,
 };
-use crate::domain::fanfiction::{Fanfiction, ReadingStatus, UserRating};
+use crate::domain::backup::{BackupInfo, BackupOps, BackupReason};
+use crate::domain::discover::{Ao3Search, BlurbPage};
+use crate::domain::fanfiction::{
+    BulkEdit, Fanfiction, ReadingStatus, Snippet, UserFields, UserRating,
+};
+use crate::domain::health::LibraryIssue;
+use crate::domain::saved_search::SavedSearch;
 use crate::domain::shelf::{AutoShelfCriteria, Shelf, ShelfKind};
+use crate::domain::transfer::{
+    ExportFormat, FicImportPlan, FicImportSummary, ImportSource, LinkImportPlan, LinkSource,
+    MergePolicy, MergeReport, TableField, TableOptions,
+};
+use crate::domain::trash::{TrashedFic, TrashedShelf};
 use crate::error::FicflowError;
-use crate::infrastructure::SqliteRepository;
 use crate::infrastructure::external::ao3::fetcher::ao3_urls_from_env;
 use crate::infrastructure::persistence::database::connection::{
     open_configured_db, relocate_library, restore_backup,
 };
+use crate::infrastructure::{
+    BookmarksFile, CalibreColumns, CalibreLibrary, CommandOpener, FirefoxPlaces, SqliteBackups,
+    SqliteRepository, read_library_file,
+};
+use crate::interfaces::instance::{Instance, Message};
+use crate::interfaces::utils::url_parser::{extract_ao3_id, extract_ao3_ids};
 
 use super::chrome::FrameChrome;
 use super::library_cache::LibraryCache;
 use super::selection::Selection;
 use super::selection_controller::SelectionController;
-use super::tasks::TaskExecutor;
+use super::tasks::{AddOptions, TaskExecutor};
 use super::theme;
 use super::view::View;
 use super::views::details_panel::DetailsState;
+use super::views::discover_view;
+use super::views::health_banner;
 use super::views::modals::add_fic_dialog::{self, AddFicState};
+use super::views::modals::export_modal::{self, ExportRequest, ExportState};
+use super::views::modals::fic_import_modal::{self, FicImportState};
+use super::views::modals::link_import_modal::{self, LinkImportState};
+use super::views::modals::merge_modal::{self, MergeState};
+use super::views::modals::saved_search_modals::{self, SaveState};
 use super::views::modals::shelf_modals::{self, AutoShelfState, CreateState, RenameState};
-use super::views::modals::{bulk_modals, column_picker, quit_modal, restore_modal};
+use super::views::modals::{bulk_modals, column_picker, quit_modal, restore_modal, trash_modal};
 use super::views::settings_view;
 use super::views::tasks_view;
+use super::views::trash_view;
+use super::views::undo_prompt;
 use super::views::{
-    LibraryCounts, LibraryViewState, SelectionBarState, SidebarState, TableOutcome, TaskFilter,
-    TasksViewState, details_panel, library_view, selection_bar, sidebar,
+    BulkDetailsState, DiscoverState, LibraryViewState, SelectionBarState, SidebarState,
+    TableOutcome, TaskFilter, TasksViewState, TrashViewState, bulk_details_panel, details_panel,
+    library_view, selection_bar, sidebar,
 };
 
 pub struct FicflowApp {
@@ -52,19 +91,41 @@ pub struct FicflowApp {
     initial_window_state_applied: bool,
     sort: SortPref,
     search_query: String,
+    /// While the search box has a query, rows are ordered by relevance
+    /// until a column header is clicked.
+    rank_by_relevance: bool,
     show_column_picker: bool,
     selection: SelectionController,
     current_view: View,
     active_modal: ActiveModal,
+    /// Clause-value suggestions for the auto-shelf modal, loaded when it
+    /// opens.
+    auto_shelf_values: DistinctValues,
     /// A Library button was clicked this frame; the native picker is opened
     /// from `ui()` next, where the window handle is available to parent it.
     pending_library_request: Option<settings_view::LibraryRequest>,
+    /// Newest first; reloaded whenever a backup is taken.
+    backups: Vec<BackupInfo>,
+    /// What the startup check found, shown in a banner until repaired
+    /// or dismissed.
+    library_issues: Vec<LibraryIssue>,
+    health_banner_dismissed: bool,
+    /// Same, for the save dialog of "Export visible rows…".
+    pending_export: Option<ExportRequest>,
     task_executor: TaskExecutor,
+    /// This window's hold on the library, through which other launches,
+    /// the CLI and `ficflow://` links reach it. `None` in tests and when
+    /// the socket couldn't be set up.
+    instance: Option<Instance>,
     quit_confirmed: bool,
     task_filter: TaskFilter,
+    discover: DiscoverState,
     /// Set by Ctrl+F; consumed by `draw_search_field` on next paint.
     focus_search_pending: bool,
     toasts: Toasts,
+    history: History,
+    /// The most recent edit, offered for undo until it times out.
+    undo_prompt: Option<UndoPrompt>,
     /// Details panel width — managed locally instead of via egui's
     /// `PanelState` to side-step egui#8055 (resizable panel stores an
     /// overflowed rect, then grows by that overflow every frame until
@@ -96,13 +157,25 @@ pub enum ActiveModal {
     None,
     CreateShelf(CreateState),
     RenameShelf(RenameState),
+    SaveSearch(SaveState),
+    RenameSavedSearch(saved_search_modals::RenameState),
     AutoShelf(AutoShelfState),
     DeleteShelf(u64),
     DeleteFics(Vec<u64>),
     RemoveOrDeleteFics { ids: Vec<u64>, shelf_id: u64 },
     AddFic(AddFicState),
+    ExportRows(ExportState),
+    ImportFics(FicImportState),
+    ImportLinks(LinkImportState),
+    MergeLibrary(MergeState),
     ConfirmQuit,
     ConfirmRestore(PathBuf),
+    ConfirmEmptyTrash,
+}
+
+struct UndoPrompt {
+    label: String,
+    shown_at: Instant,
 }
 
 /// Explicit wiring so embedders and integration tests can inject a
@@ -142,6 +215,22 @@ impl FicflowApp {
             None => app_config.resolved_db_path().map_err(InitError::Database)?,
         };
         let connection = open_configured_db(&db_path).map_err(InitError::Database)?;
+        // Before the purge below, which can't be undone.
+        let backup_ops = SqliteBackups::new(&connection, &db_path);
+        if let Err(err) = backup_if_due(&backup_ops, app_config.backup_keep) {
+            log::warn!("Failed to take the daily backup: {}", err);
+        }
+        let backups = list_backups(&backup_ops).unwrap_or_default();
+        if let Some(days) = app_config.trash_auto_purge_days
+            && let Err(err) = purge_trash(&SqliteRepository::new(&connection), Some(days))
+        {
+            log::warn!("Failed to purge expired trash: {}", err);
+        }
+        let library_issues =
+            check_library(&SqliteRepository::new(&connection)).unwrap_or_else(|err| {
+                log::warn!("Failed to check the library: {}", err);
+                Vec::new()
+            });
         let cache = LibraryCache::load(&connection);
         let chrome = FrameChrome::new().map_err(InitError::Chrome)?;
         app_config.text_zoom = config::set_zoom(ctx, app_config.text_zoom);
@@ -149,7 +238,7 @@ impl FicflowApp {
         let sort = app_config.default_sort;
         let current_view = app_config
             .last_view
-            .and_then(|pv| View::from_persisted(pv, &cache.shelves))
+            .and_then(|pv| View::from_persisted(pv, &cache.shelves, &cache.saved_searches))
             .unwrap_or_default();
         let task_executor =
             TaskExecutor::spawn(config.ao3_urls, config.max_retry_cycles, db_path.clone());
@@ -162,24 +251,32 @@ impl FicflowApp {
             initial_window_state_applied: false,
             sort,
             search_query: String::new(),
+            rank_by_relevance: false,
             show_column_picker: false,
             selection: SelectionController::new(),
             current_view,
             active_modal: ActiveModal::None,
+            auto_shelf_values: DistinctValues::default(),
             pending_library_request: None,
+            backups,
+            library_issues,
+            health_banner_dismissed: false,
+            pending_export: None,
             task_executor,
+            instance: None,
             quit_confirmed: false,
             task_filter: TaskFilter::default(),
+            discover: DiscoverState::default(),
             focus_search_pending: false,
             toasts: Toasts::default(),
+            history: History::default(),
+            undo_prompt: None,
             details_panel_width: 320.0,
         };
-        // Restoring straight into a shelf view skips the sidebar's
-        // prev/post diff (see `paint_sidebar`) that normally populates
-        // `shelf_members` on a view change, so do it explicitly here.
-        if matches!(app.current_view, View::Shelf(_)) {
-            app.refresh_shelf_members();
-        }
+        // Restoring straight into a shelf or saved-search view skips the
+        // sidebar's prev/post diff (see `paint_sidebar`) that normally
+        // populates `shelf_members` on a view change, so do it explicitly.
+        app.sync_view_state();
         Ok(app)
     }
 
@@ -187,14 +284,21 @@ impl FicflowApp {
     // Kept narrow on purpose: tests should drive behaviour via the same
     // public methods the GUI uses, not by mutating internal fields.
 
-    pub fn fics(&self) -> &[Fanfiction] {
-        &self.cache.fics
+    /// The whole live library, read fresh from the repository — the
+    /// window itself only ever holds a page of it.
+    pub fn fics(&self) -> Vec<Fanfiction> {
+        list_fics(&self.repo()).unwrap_or_default()
     }
 
     pub fn shelves(&self) -> &[Shelf] {
         &self.cache.shelves
     }
 
+    /// Sidebar count for the whole library, as of the last reload.
+    pub fn library_count(&self) -> usize {
+        self.cache.library_counts.all
+    }
+
     /// Sidebar count for a shelf; 0 for a missing or empty shelf. For
     /// auto-shelves this comes from the live-computed cache, not
     /// `fic_shelf` rows.
@@ -202,6 +306,47 @@ impl FicflowApp {
         self.cache.shelf_counts.get(&shelf_id).copied().unwrap_or(0)
     }
 
+    pub fn saved_searches(&self) -> &[SavedSearch] {
+        &self.cache.saved_searches
+    }
+
+    /// Sidebar count for a saved search: fics in its scope matching its
+    /// query. 0 for a missing saved search.
+    pub fn saved_search_count(&self, saved_search_id: u64) -> usize {
+        self.cache
+            .saved_search_counts
+            .get(&saved_search_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Most recently deleted first.
+    pub fn trashed_fics(&self) -> &[TrashedFic] {
+        &self.cache.trashed_fics
+    }
+
+    /// Most recently deleted first.
+    pub fn trashed_shelves(&self) -> &[TrashedShelf] {
+        &self.cache.trashed_shelves
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Entries on the undo stack, for tests of the size bound.
+    pub fn undo_depth(&self) -> usize {
+        self.history.undo_len()
+    }
+
+    pub fn undo_prompt(&self) -> Option<&str> {
+        self.undo_prompt.as_ref().map(|p| p.label.as_str())
+    }
+
     pub fn selection(&self) -> &Selection {
         self.selection.current()
     }
@@ -218,12 +363,18 @@ impl FicflowApp {
         &self.search_query
     }
 
+    /// Best-matching excerpt for a fic under the current search query.
+    pub fn search_snippet(&self, fic_id: u64) -> Option<&Snippet> {
+        self.cache.search.snippet(fic_id)
+    }
+
     pub fn sort(&self) -> SortPref {
         self.sort
     }
 
     pub fn set_sort(&mut self, column: ColumnKey, direction: SortDirection) {
         self.sort = SortPref { column, direction };
+        self.rank_by_relevance = false;
     }
 
     pub fn theme_choice(&self) -> ThemeChoice {
@@ -236,19 +387,21 @@ impl FicflowApp {
         self.save_config();
     }
 
+    /// The library table's rows, in display order. Computed fresh rather
+    /// than read from the per-frame memo, so it's current between frames.
     pub fn visible_ids(&self) -> Vec<u64> {
-        library_view::visible_ids(
-            &self.cache.fics,
-            &self.current_view,
-            &self.cache.shelf_members,
-            &self.search_query,
+        self.cache.compute_rows(
+            &self.connection,
+            &self.library_scope(),
             self.sort,
+            self.rank_by_relevance,
         )
     }
 
+    /// Any selection in a library view mounts the panel — the fic's
+    /// details for one, the bulk editor for several.
     pub fn details_panel_visible(&self) -> bool {
-        matches!(self.selection.current(), Selection::Single(_))
-            && self.current_view.shows_library()
+        !matches!(self.selection.current(), Selection::None) && self.current_view.shows_library()
     }
 
     pub fn has_running_tasks(&self) -> bool {
@@ -265,6 +418,10 @@ impl FicflowApp {
 
     /// `Some(shelf_id)` when the "remove from shelf vs delete" chooser is
     /// the open modal.
+    pub fn empty_trash_open(&self) -> bool {
+        matches!(self.active_modal, ActiveModal::ConfirmEmptyTrash)
+    }
+
     pub fn remove_or_delete_shelf(&self) -> Option<u64> {
         match self.active_modal {
             ActiveModal::RemoveOrDeleteFics { shelf_id, .. } => Some(shelf_id),
@@ -284,8 +441,113 @@ impl FicflowApp {
     // Closing that gap needs an event-injection harness incompatible
     // with the pinned egui 0.29.
 
+    /// Starts answering messages sent to `instance`, repainting as each
+    /// arrives so a window with nothing else going on still sees it.
+    pub fn listen(&mut self, ctx: &egui::Context, instance: Instance) {
+        let ctx = ctx.clone();
+        instance.wake_with(move || ctx.request_repaint());
+        self.instance = Some(instance);
+    }
+
     pub fn submit_add_fic(&self, input: impl Into<String>) {
-        self.task_executor.enqueue_add(input.into());
+        self.task_executor
+            .enqueue_add(input.into(), AddOptions::default());
+    }
+
+    /// Runs a Discover search for `page` of `search` in the background;
+    /// `discover_results` has the page once it's in.
+    pub fn search_ao3(&mut self, search: Ao3Search, page: u32, hide_tracked: bool) {
+        self.discover.terms = search.terms().to_string();
+        self.discover.by_tag = matches!(search, Ao3Search::Tag(_));
+        self.discover.hide_tracked = hide_tracked;
+        self.discover.searching = true;
+        self.discover.error = None;
+        self.task_executor
+            .enqueue_search(search, page, hide_tracked);
+    }
+
+    pub fn discover_results(&self) -> Option<&BlurbPage> {
+        self.discover.results.as_ref().map(|(_, page)| page)
+    }
+
+    pub fn discover_error(&self) -> Option<&str> {
+        self.discover.error.as_deref()
+    }
+
+    /// Queues adds for the Discover results in `ids`, skipping any the
+    /// library has or that are already on their way. Returns how many
+    /// were queued.
+    pub fn add_discovered(&mut self, ids: &[u64]) -> usize {
+        let tracked: HashSet<u64> = self
+            .discover_results()
+            .map(|page| {
+                page.blurbs
+                    .iter()
+                    .filter(|b| b.tracked)
+                    .map(|b| b.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut queued = 0;
+        for &fic_id in ids {
+            if tracked.contains(&fic_id) || !self.discover.queued.insert(fic_id) {
+                continue;
+            }
+            self.discover.checked.remove(&fic_id);
+            self.task_executor
+                .enqueue_add(fic_id.to_string(), AddOptions::default());
+            queued += 1;
+        }
+        if queued > 0 {
+            self.toasts
+                .success(format!("Adding {} fanfiction(s)", queued));
+        }
+        queued
+    }
+
+    /// Queues an add for every AO3 work in `text` that isn't in the
+    /// library yet, each going on `shelf_id` when one is given. Returns
+    /// how many were queued.
+    pub fn submit_bulk_add(
+        &mut self,
+        text: &str,
+        shelf_id: Option<u64>,
+    ) -> Result<usize, FicflowError> {
+        let ids = extract_ao3_ids(text);
+        if ids.is_empty() {
+            let err = FicflowError::InvalidInput("no AO3 work links or IDs found".into());
+            self.toasts.error(format!("Couldn't add: {}", err));
+            return Err(err);
+        }
+        let untracked = match untracked_fic_ids(&self.repo(), &ids) {
+            Ok(untracked) => untracked,
+            Err(err) => {
+                self.toasts.error(format!("Couldn't add: {}", err));
+                return Err(err);
+            }
+        };
+        for fic_id in &untracked {
+            let options = AddOptions {
+                shelf_id,
+                ..AddOptions::default()
+            };
+            self.task_executor.enqueue_add(fic_id.to_string(), options);
+        }
+        let skipped = ids.len() - untracked.len();
+        if untracked.is_empty() {
+            self.toasts
+                .info(format!("All {} are already in your library", skipped));
+        } else if skipped > 0 {
+            self.toasts.success(format!(
+                "Adding {} fanfiction(s), skipped {} already in your library",
+                untracked.len(),
+                skipped
+            ));
+        } else {
+            self.toasts
+                .success(format!("Adding {} fanfiction(s)", untracked.len()));
+        }
+        Ok(untracked.len())
     }
 
     pub fn confirm_quit(&mut self) {
@@ -310,12 +572,55 @@ impl FicflowApp {
 
     pub fn open_view(&mut self, view: View) {
         self.current_view = view;
-        if matches!(self.current_view, View::Shelf(_)) {
+        self.sync_view_state();
+        self.persist_current_view();
+    }
+
+    /// Brings the view-derived state in line with `current_view`: a saved
+    /// search loads its query and sort, and `shelf_members` is refilled
+    /// when the effective scope is a shelf.
+    fn sync_view_state(&mut self) {
+        if let View::SavedSearch(id) = self.current_view
+            && let Some(search) = self.cache.saved_searches.iter().find(|s| s.id == id)
+        {
+            self.search_query = search.query.clone();
+            self.sort = search.sort;
+            self.sync_search();
+            self.rank_by_relevance = false;
+        }
+        if matches!(self.library_scope(), View::Shelf(_)) {
             self.refresh_shelf_members();
         } else {
             self.cache.shelf_members.clear();
         }
-        self.persist_current_view();
+    }
+
+    /// Re-runs the search box query against the index when it changed.
+    /// A fresh query starts out ranked by relevance.
+    fn sync_search(&mut self) {
+        if self
+            .cache
+            .set_search_query(&self.connection, &self.search_query)
+        {
+            self.rank_by_relevance = true;
+        }
+    }
+
+    fn sync_library_rows(&mut self) {
+        let scope = self.library_scope();
+        self.cache
+            .sync_rows(&self.connection, &scope, self.sort, self.rank_by_relevance);
+    }
+
+    fn sync_selected_fics(&mut self) {
+        self.cache
+            .sync_selected_fics(&self.connection, self.selection.current());
+    }
+
+    /// The view whose filter the library table applies — `current_view`
+    /// itself, or a saved search's scope.
+    fn library_scope(&self) -> View {
+        self.current_view.scope(&self.cache.saved_searches)
     }
 
     /// Saves `current_view` into `AppConfig` if it's a restorable
@@ -330,7 +635,7 @@ impl FicflowApp {
 
     pub fn refresh_selected(&self) {
         if let Selection::Single(id) = *self.selection.current()
-            && let Some(fic) = self.cache.fics.iter().find(|f| f.id == id)
+            && let Some(fic) = self.cache.fic(&self.connection, id)
         {
             self.task_executor.enqueue_refresh(id, fic.title.clone());
         }
@@ -349,7 +654,9 @@ impl FicflowApp {
                 })
                 .collect()
         });
-        self.cache.remove_fics(&surviving);
+        self.cache.reload_fics(&self.connection);
+        self.cache.reload_trash(&self.connection);
+        self.record_deletions(&surviving);
         self.clear_selection();
     }
 
@@ -392,7 +699,7 @@ impl FicflowApp {
                 ));
                 self.cache.reload_shelves(&self.connection);
                 self.refresh_shelf_counts();
-                if shelf_id.is_some_and(|id| self.current_view == View::Shelf(id)) {
+                if shelf_id.is_some_and(|id| self.library_scope() == View::Shelf(id)) {
                     self.refresh_shelf_members();
                 }
                 Ok(())
@@ -415,6 +722,7 @@ impl FicflowApp {
                     self.cache.shelf_members.clear();
                 }
                 self.cache.reload_shelves(&self.connection);
+                self.cache.reload_trash(&self.connection);
                 self.refresh_shelf_counts();
                 Ok(())
             }
@@ -425,19 +733,105 @@ impl FicflowApp {
         }
     }
 
+    /// Brings a fic back from the trash, onto the shelves it was on.
+    pub fn restore_fic(&mut self, fic_id: u64) -> Result<(), FicflowError> {
+        match self.mutate(|repo| restore_fic(repo, fic_id)) {
+            Ok(fic) => {
+                self.toasts
+                    .success(format!("Restored \u{201C}{}\u{201D}", fic.title));
+                self.cache.reload_fics(&self.connection);
+                self.cache.reload_trash(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't restore fic: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    pub fn restore_shelf(&mut self, shelf_id: u64) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        match restore_shelf(&repo, shelf_id) {
+            Ok(shelf) => {
+                self.toasts
+                    .success(format!("Restored shelf \u{201C}{}\u{201D}", shelf.name));
+                self.cache.reload_shelves(&self.connection);
+                self.cache.reload_trash(&self.connection);
+                self.refresh_shelf_counts();
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't restore shelf: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    pub fn purge_fic(&mut self, fic_id: u64) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        let result = purge_fic(&repo, fic_id).map(|()| "Fanfiction deleted forever".to_string());
+        self.finish_purge(result)
+    }
+
+    pub fn purge_shelf(&mut self, shelf_id: u64) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        let result = purge_shelf(&repo, shelf_id).map(|()| "Shelf deleted forever".to_string());
+        self.finish_purge(result)
+    }
+
+    pub fn empty_trash(&mut self) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        let result = purge_trash(&repo, None).map(|summary| {
+            format!(
+                "Deleted {} fanfiction(s) and {} shelf(s) forever",
+                summary.fics, summary.shelves
+            )
+        });
+        self.finish_purge(result)
+    }
+
+    /// Purged rows were already out of the library, so only the trash
+    /// listing needs reloading.
+    fn finish_purge(&mut self, result: Result<String, FicflowError>) -> Result<(), FicflowError> {
+        match result {
+            Ok(message) => {
+                self.toasts.success(message);
+                self.cache.reload_trash(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't purge: {}", err));
+                Err(err)
+            }
+        }
+    }
+
     pub fn move_shelf(
         &mut self,
         shelf_id: u64,
         new_parent: Option<u64>,
     ) -> Result<(), FicflowError> {
+        let before = self.cached_shelf(shelf_id).and_then(|s| s.parent_shelf_id);
         let repo = self.repo();
         match move_shelf::move_shelf(&repo, shelf_id, new_parent) {
             Ok(shelf) => {
                 self.toasts
                     .success(format!("Moved shelf \u{201C}{}\u{201D}", shelf.name));
+                if before != shelf.parent_shelf_id {
+                    self.record(
+                        format!("Moved shelf \u{201C}{}\u{201D}", shelf.name),
+                        vec![Change::ShelfParent {
+                            shelf_id,
+                            before,
+                            after: shelf.parent_shelf_id,
+                        }],
+                    );
+                }
                 self.cache.reload_shelves(&self.connection);
                 self.refresh_shelf_counts();
-                if matches!(self.current_view, View::Shelf(_)) {
+                if matches!(self.library_scope(), View::Shelf(_)) {
                     self.refresh_shelf_members();
                 }
                 Ok(())
@@ -462,8 +856,15 @@ impl FicflowApp {
         match result {
             Ok(shelf) => {
                 let verb = if shelf.pinned { "Pinned" } else { "Unpinned" };
-                self.toasts
-                    .success(format!("{} shelf \u{201C}{}\u{201D}", verb, shelf.name));
+                let label = format!("{} shelf \u{201C}{}\u{201D}", verb, shelf.name);
+                self.toasts.success(label.clone());
+                self.record(
+                    label,
+                    vec![Change::ShelfPinned {
+                        shelf_id,
+                        pinned: shelf.pinned,
+                    }],
+                );
                 self.cache.reload_shelves(&self.connection);
                 Ok(())
             }
@@ -479,23 +880,406 @@ impl FicflowApp {
         shelf_id: u64,
         new_name: impl AsRef<str>,
     ) -> Result<(), FicflowError> {
+        let before = self.cached_shelf(shelf_id).map(|s| s.name.clone());
         let repo = self.repo();
         match rename_shelf(&repo, shelf_id, new_name.as_ref()) {
             Ok(shelf) => {
+                let label = format!("Renamed shelf to \u{201C}{}\u{201D}", shelf.name);
+                self.toasts.success(label.clone());
+                if let Some(before) = before.filter(|name| *name != shelf.name) {
+                    self.record(
+                        label,
+                        vec![Change::ShelfName {
+                            shelf_id,
+                            before,
+                            after: shelf.name,
+                        }],
+                    );
+                }
+                self.cache.reload_shelves(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't rename shelf: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Saves the current search text, scope and sort under `name`. The
+    /// scope is the library view the search was typed in; saving from a
+    /// saved search keeps that search's scope.
+    pub fn save_search(&mut self, name: impl AsRef<str>) -> Result<(), FicflowError> {
+        let Some(scope) = self.library_scope().to_search_scope() else {
+            return Err(FicflowError::InvalidInput(
+                "only library views can be saved as searches".into(),
+            ));
+        };
+        let repo = self.repo();
+        match create_saved_search(&repo, name.as_ref(), &self.search_query, scope, self.sort) {
+            Ok(search) => {
                 self.toasts
-                    .success(format!("Renamed shelf to \u{201C}{}\u{201D}", shelf.name));
+                    .success(format!("Saved search \u{201C}{}\u{201D}", search.name));
+                self.cache.reload_saved_searches(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't save search: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Writes the rows the library table shows — current view, search
+    /// and sort — to `path`, with the table's visible columns followed by
+    /// `request.extras`. Returns how many rows were written.
+    pub fn export_visible_rows(
+        &mut self,
+        path: &Path,
+        request: &ExportRequest,
+    ) -> Result<usize, FicflowError> {
+        let options = TableOptions {
+            fields: self
+                .config
+                .visible_columns
+                .iter()
+                .copied()
+                .map(TableField::Column)
+                .chain(request.extras.iter().copied())
+                .collect(),
+            delimiter: request.format.delimiter().unwrap_or(b','),
+            list_separator: request.separator.clone(),
+        };
+        let fics: Vec<Fanfiction> = self
+            .cache
+            .rows
+            .iter()
+            .filter_map(|id| self.cache.fic(&self.connection, *id))
+            .collect();
+        let fics: Vec<&Fanfiction> = fics.iter().collect();
+        let written = render_fic_table(&fics, &options)
+            .and_then(|table| std::fs::write(path, table).map_err(FicflowError::from));
+        match written {
+            Ok(()) => {
+                self.toasts
+                    .success(format!("Exported {} fanfiction(s)", fics.len()));
+                Ok(fics.len())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't export: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Reads the Calibre library at `path` (its `metadata.db` or the
+    /// folder holding it) and opens the import preview. Nothing is written
+    /// until the preview is confirmed.
+    pub fn preview_calibre_import(&mut self, path: &Path) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        let planned = CalibreLibrary::open(path, CalibreColumns::default())
+            .and_then(|library| plan_fic_import(&repo, &repo, &library));
+        match planned {
+            Ok(plan) if plan.new.is_empty() && plan.conflicts.is_empty() => {
+                self.toasts.info(format!(
+                    "Nothing to import: all {} AO3 works are already in your library",
+                    plan.unchanged
+                ));
+                Ok(())
+            }
+            Ok(plan) => {
+                self.active_modal = ActiveModal::ImportFics(FicImportState {
+                    plan,
+                    source: "Calibre".into(),
+                });
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't read the Calibre library: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Applies an import preview as confirmed, then queues an AO3 refresh
+    /// for every fic it added, since those only have what the other
+    /// library knew.
+    pub fn apply_fic_import(
+        &mut self,
+        plan: FicImportPlan,
+    ) -> Result<FicImportSummary, FicflowError> {
+        let repo = self.repo();
+        match apply_fic_import(&repo, &repo, &plan) {
+            Ok(summary) => {
+                for entry in &plan.new {
+                    self.task_executor
+                        .enqueue_refresh(entry.fic.id, entry.fic.title.clone());
+                }
+                self.toasts.success(format!(
+                    "Imported {} fanfiction(s), updated {}",
+                    summary.added, summary.updated
+                ));
+                self.cache.reload_fics(&self.connection);
+                if matches!(self.library_scope(), View::Shelf(_)) {
+                    self.refresh_shelf_members();
+                }
+                Ok(summary)
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't import: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Scans a bookmarks export or a copy of Firefox's `places.sqlite`
+    /// for AO3 works and opens the review screen. Nothing is queued
+    /// until it's confirmed.
+    pub fn preview_link_import(
+        &mut self,
+        source: ImportSource,
+        path: &Path,
+    ) -> Result<(), FicflowError> {
+        let (links, name) = match source {
+            ImportSource::Firefox => (
+                FirefoxPlaces::open(path).and_then(|places| places.read_links()),
+                "Firefox history",
+            ),
+            _ => (
+                BookmarksFile::open(path).and_then(|file| file.read_links()),
+                "bookmarks",
+            ),
+        };
+        let repo = self.repo();
+        let planned = links.and_then(|links| {
+            let works = links
+                .into_iter()
+                .filter_map(|link| extract_ao3_id(&link.url).ok().map(|id| (id, link)))
+                .collect();
+            plan_link_import(&repo, works)
+        });
+        match planned {
+            Ok(plan) if plan.works.is_empty() => {
+                self.toasts.info(format!(
+                    "No new AO3 works in the {} ({} already in your library)",
+                    name, plan.already_tracked
+                ));
+                Ok(())
+            }
+            Ok(plan) => {
+                self.active_modal = ActiveModal::ImportLinks(LinkImportState {
+                    plan,
+                    source: name.into(),
+                });
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't read the {}: {}", name, err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Queues an add for each work ticked on the review screen, creating
+    /// the folder shelves first. Returns how many were queued.
+    pub fn queue_link_import(&mut self, plan: LinkImportPlan) -> Result<usize, FicflowError> {
+        let shelves = match find_or_create_shelves(&self.repo(), &plan.shelf_names()) {
+            Ok(shelves) => shelves,
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't create the shelves: {}", err));
+                return Err(err);
+            }
+        };
+        if !shelves.is_empty() {
+            self.cache.reload_shelves(&self.connection);
+        }
+        let mut queued = 0;
+        for work in plan.selected() {
+            let options = AddOptions {
+                shelf_id: work.folder.as_ref().and_then(|f| shelves.get(f)).copied(),
+                last_visited: work.last_visited,
+            };
+            self.task_executor
+                .enqueue_add(work.fic_id.to_string(), options);
+            queued += 1;
+        }
+        self.toasts
+            .success(format!("Adding {} fanfiction(s)", queued));
+        Ok(queued)
+    }
+
+    /// The link review screen's plan while it's open, so tests can tick
+    /// works the way the checkboxes do.
+    pub fn pending_link_import_mut(&mut self) -> Option<&mut LinkImportPlan> {
+        match &mut self.active_modal {
+            ActiveModal::ImportLinks(state) => Some(&mut state.plan),
+            _ => None,
+        }
+    }
+
+    /// Reads another library file and opens the merge preview, a dry
+    /// run under the default policy. Nothing is written until it's
+    /// confirmed.
+    pub fn preview_library_merge(&mut self, path: &Path) -> Result<(), FicflowError> {
+        let policy = MergePolicy::default();
+        let previewed = read_library_file(path).and_then(|snapshot| {
+            let preview = merge_library(&self.repo(), &snapshot, policy, true)?;
+            Ok((snapshot, preview))
+        });
+        match previewed {
+            Ok((snapshot, preview)) => {
+                self.active_modal = ActiveModal::MergeLibrary(MergeState {
+                    path: path.to_path_buf(),
+                    snapshot,
+                    policy,
+                    preview,
+                });
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't read that library: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Redoes the merge preview's dry run after its policy changed.
+    pub fn refresh_merge_preview(&mut self) {
+        let repo = SqliteRepository::new(&self.connection);
+        let ActiveModal::MergeLibrary(state) = &mut self.active_modal else {
+            return;
+        };
+        match merge_library(&repo, &state.snapshot, state.policy, true) {
+            Ok(preview) => state.preview = preview,
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't preview the merge: {}", err));
+            }
+        }
+    }
+
+    /// Applies the open merge preview under its chosen policy and closes
+    /// it.
+    pub fn confirm_library_merge(&mut self) -> Result<MergeReport, FicflowError> {
+        let ActiveModal::MergeLibrary(state) =
+            std::mem::replace(&mut self.active_modal, ActiveModal::None)
+        else {
+            return Err(FicflowError::InvalidInput(
+                "no merge to confirm".to_string(),
+            ));
+        };
+        match merge_library(&self.repo(), &state.snapshot, state.policy, false) {
+            Ok(report) => {
+                self.toasts.success(format!(
+                    "Merged: {} fanfiction(s) added, {} updated, {} conflict(s)",
+                    report.fics_added,
+                    report.fics_updated,
+                    report.conflicts.len()
+                ));
+                self.cache.reload_fics(&self.connection);
                 self.cache.reload_shelves(&self.connection);
+                if matches!(self.library_scope(), View::Shelf(_)) {
+                    self.refresh_shelf_members();
+                }
+                self.refresh_selection_shelf_ids();
+                Ok(report)
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't merge: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// The merge preview while it's open, so tests can pick a policy the
+    /// way the radio buttons do.
+    pub fn pending_merge_mut(&mut self) -> Option<&mut MergeState> {
+        match &mut self.active_modal {
+            ActiveModal::MergeLibrary(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    /// The import preview's plan while it's open, so tests can resolve
+    /// conflicts the way the radio buttons do.
+    pub fn pending_fic_import_mut(&mut self) -> Option<&mut FicImportPlan> {
+        match &mut self.active_modal {
+            ActiveModal::ImportFics(state) => Some(&mut state.plan),
+            _ => None,
+        }
+    }
+
+    pub fn rename_saved_search(
+        &mut self,
+        saved_search_id: u64,
+        new_name: impl AsRef<str>,
+    ) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        match rename_saved_search(&repo, saved_search_id, new_name.as_ref()) {
+            Ok(search) => {
+                self.toasts.success(format!(
+                    "Renamed saved search to \u{201C}{}\u{201D}",
+                    search.name
+                ));
+                self.cache.reload_saved_searches(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't rename saved search: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Moves a saved search to `new_position` in the sidebar (0-based).
+    pub fn move_saved_search(
+        &mut self,
+        saved_search_id: u64,
+        new_position: u32,
+    ) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        match move_saved_search(&repo, saved_search_id, new_position) {
+            Ok(_) => {
+                self.cache.reload_saved_searches(&self.connection);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Couldn't move saved search: {}", err));
+                Err(err)
+            }
+        }
+    }
+
+    pub fn delete_saved_search(&mut self, saved_search_id: u64) -> Result<(), FicflowError> {
+        let repo = self.repo();
+        match delete_saved_search(&repo, saved_search_id) {
+            Ok(()) => {
+                self.toasts.success("Saved search deleted");
+                if self.current_view == View::SavedSearch(saved_search_id) {
+                    self.open_view(View::AllFics);
+                }
+                self.cache.reload_saved_searches(&self.connection);
                 Ok(())
             }
             Err(err) => {
-                self.toasts.error(format!("Couldn't rename shelf: {}", err));
+                self.toasts
+                    .error(format!("Couldn't delete saved search: {}", err));
                 Err(err)
             }
         }
     }
 
     pub fn add_fic_to_shelf(&mut self, fic_id: u64, shelf_id: u64) -> Result<(), FicflowError> {
-        self.mutate(|repo| add_to_shelf(repo, fic_id, shelf_id))
+        let (changes, mut errors) =
+            self.mutate(|repo| change_membership(repo, &[fic_id], shelf_id, true));
+        self.record("Added to shelf", changes);
+        errors.pop().map_or(Ok(()), Err)
     }
 
     pub fn remove_fic_from_shelf(
@@ -503,41 +1287,69 @@ impl FicflowApp {
         fic_id: u64,
         shelf_id: u64,
     ) -> Result<(), FicflowError> {
-        self.mutate(|repo| remove_from_shelf::remove_from_shelf(repo, fic_id, shelf_id))
+        let (changes, mut errors) =
+            self.mutate(|repo| change_membership(repo, &[fic_id], shelf_id, false));
+        self.record("Removed from shelf", changes);
+        errors.pop().map_or(Ok(()), Err)
     }
 
-    /// Status changes can't affect shelf membership, so this skips the
-    /// `mutate()` funnel that the other bulk ops use.
     pub fn bulk_set_status(&mut self, ids: &[u64], status: ReadingStatus) -> (usize, usize) {
-        let mut errors = 0usize;
-        let mut updated_fics: Vec<Fanfiction> = Vec::with_capacity(ids.len());
+        self.bulk_edit(ids, &BulkEdit::Status(status))
+    }
+
+    /// Applies one edit to every fic in `ids`, saved in a single
+    /// transaction and undone as one step. User fields can't affect
+    /// shelf membership, so this skips the `mutate()` funnel that the
+    /// shelf bulk ops use.
+    pub fn bulk_edit(&mut self, ids: &[u64], edit: &BulkEdit) -> (usize, usize) {
         let repo = self.repo();
-        for id in ids {
-            match update_status::update_reading_status(&repo, *id, status) {
-                Ok(updated) => updated_fics.push(updated),
-                Err(_) => errors += 1,
+        let before: HashMap<u64, UserFields> = ids
+            .iter()
+            .filter_map(|id| get_fanfiction(&repo, *id).ok())
+            .map(|f| (f.id, f.user_fields()))
+            .collect();
+        let (updated, failures) = match bulk_edit_fics(&repo, ids, edit) {
+            Ok(result) => result,
+            Err(err) => {
+                log::warn!("Bulk edit failed: {}", err);
+                return (0, ids.len());
             }
-        }
-        self.cache.replace_fics(updated_fics);
-        (ids.len() - errors, errors)
+        };
+        let changes: Vec<Change> = updated
+            .iter()
+            .filter_map(|fic| {
+                let before = before.get(&fic.id)?.clone();
+                fields_change(fic.id, before, fic)
+            })
+            .collect();
+        self.cache.reload_fics(&self.connection);
+        self.record(
+            format!(
+                "{}: {} fanfiction(s)",
+                bulk_edit_action(edit),
+                changes.len()
+            ),
+            changes,
+        );
+        (ids.len() - failures.len(), failures.len())
     }
 
     pub fn bulk_add_to_shelf(&mut self, ids: &[u64], shelf_id: u64) -> (usize, usize) {
-        let errors = self.mutate(|repo| {
-            ids.iter()
-                .filter(|id| add_to_shelf(repo, **id, shelf_id).is_err())
-                .count()
-        });
-        (ids.len() - errors, errors)
+        let (changes, errors) = self.mutate(|repo| change_membership(repo, ids, shelf_id, true));
+        self.record(
+            format!("Added {} fanfiction(s) to shelf", changes.len()),
+            changes,
+        );
+        (ids.len() - errors.len(), errors.len())
     }
 
     pub fn bulk_remove_from_shelf(&mut self, ids: &[u64], shelf_id: u64) -> (usize, usize) {
-        let errors = self.mutate(|repo| {
-            ids.iter()
-                .filter(|id| remove_from_shelf::remove_from_shelf(repo, **id, shelf_id).is_err())
-                .count()
-        });
-        (ids.len() - errors, errors)
+        let (changes, errors) = self.mutate(|repo| change_membership(repo, ids, shelf_id, false));
+        self.record(
+            format!("Removed {} fanfiction(s) from shelf", changes.len()),
+            changes,
+        );
+        (ids.len() - errors.len(), errors.len())
     }
 
     fn is_normal_shelf(&self, shelf_id: u64) -> bool {
@@ -549,27 +1361,41 @@ impl FicflowApp {
 
     pub fn set_search(&mut self, query: impl Into<String>) {
         self.search_query = query.into();
+        self.sync_search();
     }
 
     pub fn set_status(&mut self, fic_id: u64, status: ReadingStatus) -> Result<(), FicflowError> {
-        let repo = self.repo();
-        let updated = update_status::update_reading_status(&repo, fic_id, status)?;
-        self.cache.replace_fic(updated);
-        Ok(())
+        self.edit_user_fields(fic_id, "Changed status", |repo| {
+            update_status::update_reading_status(repo, fic_id, status)
+        })
+    }
+
+    /// Opens the fic in the browser at the chapter after the last one
+    /// read, and returns that chapter. Marking it In Progress, when the
+    /// config asks for that, is undoable like any other edit.
+    pub fn continue_reading(&mut self, fic_id: u64) -> Result<u32, FicflowError> {
+        let opener = CommandOpener::new(self.config.resolved_opener());
+        let base_url = self.config.reading_base_url().to_string();
+        let mark_in_progress = self.config.open_marks_in_progress;
+        let mut chapter = 0;
+        self.edit_user_fields(fic_id, "Started reading", |repo| {
+            let (fic, next) = open_fic(repo, &opener, fic_id, &base_url, mark_in_progress)?;
+            chapter = next;
+            Ok(fic)
+        })?;
+        Ok(chapter)
     }
 
     pub fn set_last_chapter(&mut self, fic_id: u64, chapter: u32) -> Result<(), FicflowError> {
-        let repo = self.repo();
-        let updated = update_chapters::update_last_chapter_read(&repo, fic_id, chapter)?;
-        self.cache.replace_fic(updated);
-        Ok(())
+        self.edit_user_fields(fic_id, "Changed last chapter read", |repo| {
+            update_chapters::update_last_chapter_read(repo, fic_id, chapter)
+        })
     }
 
     pub fn set_read_count(&mut self, fic_id: u64, count: u32) -> Result<(), FicflowError> {
-        let repo = self.repo();
-        let updated = update_read_count::update_read_count(&repo, fic_id, count)?;
-        self.cache.replace_fic(updated);
-        Ok(())
+        self.edit_user_fields(fic_id, "Changed read count", |repo| {
+            update_read_count::update_read_count(repo, fic_id, count)
+        })
     }
 
     pub fn set_user_rating(
@@ -577,19 +1403,124 @@ impl FicflowApp {
         fic_id: u64,
         rating: Option<UserRating>,
     ) -> Result<(), FicflowError> {
-        let repo = self.repo();
-        let updated = update_rating::update_user_rating(&repo, fic_id, rating)?;
-        self.cache.replace_fic(updated);
-        Ok(())
+        self.edit_user_fields(fic_id, "Changed rating", |repo| {
+            update_rating::update_user_rating(repo, fic_id, rating)
+        })
     }
 
     pub fn set_note(&mut self, fic_id: u64, note: Option<&str>) -> Result<(), FicflowError> {
+        self.edit_user_fields(fic_id, "Edited note", |repo| {
+            update_note::update_personal_note(repo, fic_id, note)
+        })
+    }
+
+    pub fn set_personal_tags(&mut self, fic_id: u64, tags: &[String]) -> Result<(), FicflowError> {
+        self.edit_user_fields(fic_id, "Edited tags", |repo| {
+            update_personal_tags::update_personal_tags(repo, fic_id, tags)
+        })
+    }
+
+    /// Runs a single-fic user-field update and records what it changed,
+    /// including any knock-on fields (finishing the last chapter also
+    /// sets the status, for instance).
+    fn edit_user_fields(
+        &mut self,
+        fic_id: u64,
+        label: &str,
+        update: impl FnOnce(&SqliteRepository<'_>) -> Result<Fanfiction, FicflowError>,
+    ) -> Result<(), FicflowError> {
         let repo = self.repo();
-        let updated = update_note::update_personal_note(&repo, fic_id, note)?;
-        self.cache.replace_fic(updated);
+        let before = get_fanfiction(&repo, fic_id)?.user_fields();
+        let updated = update(&repo)?;
+        let change = fields_change(fic_id, before, &updated);
+        self.cache.reload_fics(&self.connection);
+        self.record(label, change.into_iter().collect());
         Ok(())
     }
 
+    /// Pushes an undo entry and offers it in the undo prompt.
+    fn record(&mut self, label: impl Into<String>, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+        let label = label.into();
+        self.undo_prompt = Some(UndoPrompt {
+            label: label.clone(),
+            shown_at: Instant::now(),
+        });
+        self.history.record(label, changes);
+    }
+
+    fn record_deletions(&mut self, ids: &[u64]) {
+        let changes = ids
+            .iter()
+            .map(|&fic_id| Change::FicDeleted { fic_id })
+            .collect();
+        self.record(format!("Deleted {} fanfiction(s)", ids.len()), changes);
+    }
+
+    /// Reverts the most recent edit. An entry that fails to apply is
+    /// rolled back and stays on top of the stack, so it can be retried
+    /// once whatever blocked it is out of the way.
+    pub fn undo(&mut self) -> Result<(), FicflowError> {
+        let Some(entry) = self.history.take_undo() else {
+            return Ok(());
+        };
+        self.undo_prompt = None;
+        let result = self.mutate(|repo| entry.undo(repo));
+        self.reload_after_history();
+        match result {
+            Ok(()) => {
+                self.toasts.info(format!("Undone: {}", entry.label));
+                self.history.push_undone(entry);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't undo: {}", err));
+                self.history.return_undo(entry);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn redo(&mut self) -> Result<(), FicflowError> {
+        let Some(entry) = self.history.take_redo() else {
+            return Ok(());
+        };
+        self.undo_prompt = None;
+        let result = self.mutate(|repo| entry.redo(repo));
+        self.reload_after_history();
+        match result {
+            Ok(()) => {
+                self.toasts.info(format!("Redone: {}", entry.label));
+                self.history.push_redone(entry);
+                Ok(())
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't redo: {}", err));
+                self.history.return_redo(entry);
+                Err(err)
+            }
+        }
+    }
+
+    /// A history entry can touch fics, shelves and the trash at once, so
+    /// everything it might have changed is reloaded.
+    fn reload_after_history(&mut self) {
+        self.cache.reload_fics(&self.connection);
+        self.cache.reload_shelves(&self.connection);
+        self.cache.reload_trash(&self.connection);
+        self.refresh_shelf_counts();
+        if matches!(self.library_scope(), View::Shelf(_)) {
+            self.refresh_shelf_members();
+        }
+        self.prune_selection_to_view();
+    }
+
+    fn cached_shelf(&self, shelf_id: u64) -> Option<&Shelf> {
+        self.cache.shelves.iter().find(|s| s.id == shelf_id)
+    }
+
     fn save_config(&self) {
         if let Err(err) = self.config.save() {
             log::warn!("Failed to save config: {}", err);
@@ -641,6 +1572,78 @@ impl FicflowApp {
                     self.active_modal = ActiveModal::ConfirmRestore(backup);
                 }
             }
+            settings_view::LibraryRequest::RestoreBackup(backup) => {
+                self.active_modal = ActiveModal::ConfirmRestore(backup);
+            }
+            settings_view::LibraryRequest::BackUpNow => {
+                match self.create_backup(BackupReason::Manual) {
+                    Ok(_) => {
+                        self.toasts.success("Library backed up");
+                    }
+                    Err(err) => {
+                        self.toasts.error(format!("Couldn't back up: {}", err));
+                    }
+                }
+            }
+            settings_view::LibraryRequest::Merge => {
+                let mut dialog = rfd::FileDialog::new()
+                    .add_filter("SQLite database", &["db"])
+                    .set_title("Choose a library to merge in")
+                    .set_parent(frame);
+                if let Some(dir) = &start_dir {
+                    dialog = dialog.set_directory(dir);
+                }
+                if let Some(path) = dialog.pick_file() {
+                    let _ = self.preview_library_merge(&path);
+                }
+            }
+            settings_view::LibraryRequest::ImportCalibre => {
+                let picked = rfd::FileDialog::new()
+                    .add_filter("Calibre library", &["db"])
+                    .set_title("Choose Calibre's metadata.db")
+                    .set_parent(frame)
+                    .pick_file();
+                if let Some(path) = picked {
+                    let _ = self.preview_calibre_import(&path);
+                }
+            }
+            settings_view::LibraryRequest::ImportBookmarks => {
+                let picked = rfd::FileDialog::new()
+                    .add_filter("Bookmarks export", &["html", "htm"])
+                    .set_title("Choose a bookmarks file")
+                    .set_parent(frame)
+                    .pick_file();
+                if let Some(path) = picked {
+                    let _ = self.preview_link_import(ImportSource::Bookmarks, &path);
+                }
+            }
+            settings_view::LibraryRequest::ImportFirefox => {
+                let picked = rfd::FileDialog::new()
+                    .add_filter("Firefox places", &["sqlite"])
+                    .set_title("Choose a copy of Firefox's places.sqlite")
+                    .set_parent(frame)
+                    .pick_file();
+                if let Some(path) = picked {
+                    let _ = self.preview_link_import(ImportSource::Firefox, &path);
+                }
+            }
+        }
+    }
+
+    /// Asks where to save "Export visible rows…", parented like
+    /// `open_library_picker`.
+    fn open_export_picker(&mut self, request: ExportRequest, frame: &eframe::Frame) {
+        let (filter, extension) = match request.format {
+            ExportFormat::Tsv => ("Tab-separated values", "tsv"),
+            _ => ("Comma-separated values", "csv"),
+        };
+        let path = rfd::FileDialog::new()
+            .add_filter(filter, &[extension])
+            .set_file_name(format!("ficflow.{}", extension))
+            .set_parent(frame)
+            .save_file();
+        if let Some(path) = path {
+            let _ = self.export_visible_rows(&path, &request);
         }
     }
 
@@ -665,6 +1668,7 @@ impl FicflowApp {
                 self.config.library_path = Some(target.clone());
                 self.current_db_path = target;
                 self.save_config();
+                self.clear_history();
                 self.toasts
                     .info("Library location updated — restart Ficflow to load it.");
             }
@@ -675,11 +1679,71 @@ impl FicflowApp {
         }
     }
 
+    /// Backs up the library now, dropping the oldest backups beyond the
+    /// configured count.
+    pub fn create_backup(&mut self, reason: BackupReason) -> Result<BackupInfo, FicflowError> {
+        let backup_ops = SqliteBackups::new(&self.connection, &self.current_db_path);
+        let result = create_backup(&backup_ops, reason, self.config.backup_keep);
+        self.backups = list_backups(&backup_ops).unwrap_or_default();
+        result
+    }
+
+    /// The library's backups, newest first, as Settings lists them.
+    pub fn backups(&self) -> &[BackupInfo] {
+        &self.backups
+    }
+
+    /// Problems the startup check found that haven't been repaired.
+    pub fn library_issues(&self) -> &[LibraryIssue] {
+        &self.library_issues
+    }
+
+    /// The banner's "Repair": backs the library up, makes the safe
+    /// repairs, and reloads everything they may have touched.
+    pub fn repair_library(&mut self) {
+        if let Err(err) = self.create_backup(BackupReason::Repair) {
+            self.toasts
+                .error(format!("Couldn't back up before repairing: {}", err));
+            return;
+        }
+        match repair_library(&self.repo()) {
+            Ok(report) => {
+                self.toasts
+                    .success(format!("Repaired {} problem(s)", report.repaired.len()));
+                self.library_issues = report.remaining;
+                self.cache.reload_fics(&self.connection);
+                self.cache.reload_shelves(&self.connection);
+                self.cache.reload_saved_searches(&self.connection);
+                if matches!(self.library_scope(), View::Shelf(_)) {
+                    self.refresh_shelf_members();
+                }
+                self.refresh_selection_shelf_ids();
+            }
+            Err(err) => {
+                self.toasts.error(format!("Couldn't repair: {}", err));
+            }
+        }
+    }
+
     /// Copy a backup over the current library, leaving the configured
-    /// location unchanged. Takes effect on the next restart.
-    fn restore_library_backup(&mut self, backup: PathBuf) {
+    /// location unchanged. Takes effect on the next restart. The library
+    /// being replaced is backed up first, so a restore can be undone.
+    pub fn restore_library_backup(&mut self, backup: PathBuf) {
+        // Not pruned here: with the list full, pruning would delete the
+        // oldest backup, which may be the one being restored.
+        let backup_ops = SqliteBackups::new(&self.connection, &self.current_db_path);
+        let taken = backup_ops.create_backup(BackupReason::Restore);
+        self.backups = list_backups(&backup_ops).unwrap_or_default();
+        if let Err(err) = taken {
+            self.toasts.error(format!(
+                "Couldn't back up the current library, so nothing was restored: {}",
+                err
+            ));
+            return;
+        }
         match restore_backup(&backup, &self.current_db_path) {
             Ok(()) => {
+                self.clear_history();
                 self.toasts
                     .info("Backup restored — restart Ficflow to load it.");
             }
@@ -690,6 +1754,12 @@ impl FicflowApp {
         }
     }
 
+    /// Past edits refer to rows in the library being left behind.
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.undo_prompt = None;
+    }
+
     fn dispatch_details_outcome(&mut self, fic_id: u64, outcome: details_panel::Outcome) {
         use details_panel::Outcome;
         match outcome {
@@ -723,6 +1793,11 @@ impl FicflowApp {
                     self.toasts.error(format!("Couldn't update note: {}", err));
                 }
             }
+            Outcome::SetPersonalTags(tags) => {
+                if let Err(err) = self.set_personal_tags(fic_id, &tags) {
+                    self.toasts.error(format!("Couldn't update tags: {}", err));
+                }
+            }
             Outcome::AddToShelf(shelf_id) => {
                 if let Err(err) = self.add_fic_to_shelf(fic_id, shelf_id) {
                     self.toasts.error(format!("Couldn't add to shelf: {}", err));
@@ -741,12 +1816,26 @@ impl FicflowApp {
             Outcome::RequestRefresh => {
                 self.refresh_selected();
             }
+            Outcome::ContinueReading => match self.continue_reading(fic_id) {
+                Ok(chapter) => {
+                    self.toasts
+                        .success(format!("Opened chapter {} in the browser", chapter));
+                }
+                Err(err) => {
+                    self.toasts.error(format!("Couldn't open fic: {}", err));
+                }
+            },
             Outcome::CreateAutoShelfFromTag(field, value) => {
-                self.active_modal = ActiveModal::AutoShelf(AutoShelfState::prefilled(field, value));
+                self.open_auto_shelf_modal(AutoShelfState::prefilled(field, value));
             }
         }
     }
 
+    fn open_auto_shelf_modal(&mut self, state: AutoShelfState) {
+        self.auto_shelf_values = auto_shelf::build_distinct_values(&self.connection);
+        self.active_modal = ActiveModal::AutoShelf(state);
+    }
+
     fn toast_bulk_result(&mut self, action: &str, succeeded: usize, failed: usize) {
         if failed == 0 {
             self.toasts
@@ -766,12 +1855,10 @@ impl FicflowApp {
     /// Surfaces refresh failures as toasts; the op's own result is
     /// forwarded.
     fn mutate<R>(&mut self, op: impl FnOnce(&SqliteRepository<'_>) -> R) -> R {
-        let (result, refresh_errors) = self.cache.mutate(
-            &self.connection,
-            &self.current_view,
-            self.selection.current(),
-            op,
-        );
+        let scope = self.library_scope();
+        let (result, refresh_errors) =
+            self.cache
+                .mutate(&self.connection, &scope, self.selection.current(), op);
         for err in refresh_errors {
             self.toasts
                 .error(format!("Couldn't refresh after change: {}", err));
@@ -832,21 +1919,17 @@ impl FicflowApp {
     }
 
     fn refresh_shelf_members(&mut self) {
-        if let Err(err) = self
-            .cache
-            .refresh_shelf_members(&self.connection, &self.current_view)
-        {
+        let scope = self.library_scope();
+        if let Err(err) = self.cache.refresh_shelf_members(&self.connection, &scope) {
             self.toasts
                 .error(format!("Couldn't load shelf contents: {}", err));
         }
     }
 
     fn prune_selection_to_view(&mut self) {
-        let changed = self.selection.prune_to_view(
-            &self.cache.fics,
-            &self.current_view,
-            &self.cache.shelf_members,
-        );
+        let scope = self.library_scope();
+        let in_view = self.cache.view_fic_ids(&self.connection, &scope);
+        let changed = self.selection.prune_to_view(&scope, &in_view);
         // The post-render diff in `paint_central` captures `prev_selection`
         // *after* this prune, so it won't catch the change — refresh here.
         if changed {
@@ -869,21 +1952,58 @@ impl FicflowApp {
     }
 }
 
-fn compute_library_counts(fics: &[Fanfiction]) -> LibraryCounts {
-    let mut counts = LibraryCounts {
-        all: fics.len(),
-        ..LibraryCounts::default()
-    };
-    for f in fics {
-        match f.reading_status {
-            ReadingStatus::InProgress => counts.in_progress += 1,
-            ReadingStatus::Read => counts.read += 1,
-            ReadingStatus::PlanToRead => counts.plan_to_read += 1,
-            ReadingStatus::Paused => counts.paused += 1,
-            ReadingStatus::Abandoned => counts.abandoned += 1,
-        }
-    }
-    counts
+fn bulk_edit_action(edit: &BulkEdit) -> &'static str {
+    match edit {
+        BulkEdit::Status(_) => "Status updated",
+        BulkEdit::UserRating(_) => "Rating updated",
+        BulkEdit::ReadCount(_) => "Read count updated",
+        BulkEdit::MarkFullyRead => "Marked as read",
+        BulkEdit::AppendNote(_) => "Note appended",
+        BulkEdit::AddPersonalTag(_) => "Tag added",
+        BulkEdit::RemovePersonalTag(_) => "Tag removed",
+    }
+}
+
+fn fields_change(fic_id: u64, before: UserFields, updated: &Fanfiction) -> Option<Change> {
+    let after = updated.user_fields();
+    (before != after).then_some(Change::FicFields {
+        fic_id,
+        before,
+        after,
+    })
+}
+
+/// Adds (or removes) each fic to the shelf, returning the memberships
+/// that actually flipped and any failures. Fics already in the requested
+/// state aren't recorded, so undoing doesn't take away a membership the
+/// edit never gave.
+fn change_membership(
+    repo: &SqliteRepository<'_>,
+    ids: &[u64],
+    shelf_id: u64,
+    add: bool,
+) -> (Vec<Change>, Vec<FicflowError>) {
+    let mut changes = Vec::new();
+    let mut errors = Vec::new();
+    for &fic_id in ids {
+        let was_member = list_shelves_for_fic(repo, fic_id)
+            .is_ok_and(|shelves| shelves.iter().any(|s| s.id == shelf_id));
+        let result = if add {
+            add_to_shelf(repo, fic_id, shelf_id)
+        } else {
+            remove_from_shelf::remove_from_shelf(repo, fic_id, shelf_id)
+        };
+        match result {
+            Ok(()) if was_member != add => changes.push(Change::ShelfMembership {
+                fic_id,
+                shelf_id,
+                added: add,
+            }),
+            Ok(()) => {}
+            Err(err) => errors.push(err),
+        }
+    }
+    (changes, errors)
 }
 
 impl eframe::App for FicflowApp {
@@ -892,6 +2012,9 @@ impl eframe::App for FicflowApp {
         if let Some(request) = self.pending_library_request.take() {
             self.open_library_picker(request, frame);
         }
+        if let Some(request) = self.pending_export.take() {
+            self.open_export_picker(request, frame);
+        }
     }
 
     /// Transparent so the chrome's painted edges show through the
@@ -901,6 +2024,9 @@ impl eframe::App for FicflowApp {
     }
 
     fn on_exit(&mut self) {
+        if let Err(err) = self.create_backup(BackupReason::Exit) {
+            log::warn!("Failed to back up the library on exit: {}", err);
+        }
         self.checkpoint_wal();
     }
 }
@@ -933,6 +2059,7 @@ impl FicflowApp {
                 let content_rect = self.chrome.content_rect(screen);
                 ui.scope_builder(egui::UiBuilder::new().max_rect(content_rect), |host| {
                     self.paint_header(host);
+                    self.paint_health_banner(host);
                     self.paint_sidebar(host);
                     self.paint_details_panel(host);
                     self.paint_selection_bar(host);
@@ -940,6 +2067,8 @@ impl FicflowApp {
                 });
             });
         self.paint_modals(&ctx);
+        self.paint_undo_prompt(&ctx);
+        self.drain_instance_messages(&ctx);
         self.drain_worker_events(&ctx);
         self.draw_drag_preview(&ctx);
         self.toasts.show(&ctx);
@@ -951,6 +2080,23 @@ impl FicflowApp {
         ctx.request_repaint();
     }
 
+    fn paint_undo_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &self.undo_prompt else {
+            return;
+        };
+        if prompt.shown_at.elapsed() >= Duration::from_secs(undo_prompt::UNDO_PROMPT_SECS) {
+            self.undo_prompt = None;
+            return;
+        }
+        match undo_prompt::draw(ctx, &prompt.label) {
+            undo_prompt::Outcome::None => {}
+            undo_prompt::Outcome::Undo => {
+                let _ = self.undo();
+            }
+            undo_prompt::Outcome::Dismiss => self.undo_prompt = None,
+        }
+    }
+
     fn paint_header(&self, host: &mut egui::Ui) {
         egui::Panel::top("ficflow-header").show_inside(host, |ui| {
             ui.add_space(4.0);
@@ -969,11 +2115,21 @@ impl FicflowApp {
         });
     }
 
+    fn paint_health_banner(&mut self, host: &mut egui::Ui) {
+        if self.library_issues.is_empty() || self.health_banner_dismissed {
+            return;
+        }
+        match health_banner::draw(host, &self.library_issues) {
+            health_banner::Outcome::None => {}
+            health_banner::Outcome::Repair => self.repair_library(),
+            health_banner::Outcome::Dismiss => self.health_banner_dismissed = true,
+        }
+    }
+
     /// Sidebar can mutate `current_view`; the prev/post diff at the
     /// end refreshes derived caches and prunes the selection.
     fn paint_sidebar(&mut self, host: &mut egui::Ui) {
         let prev_view = self.current_view.clone();
-        let library_counts = compute_library_counts(&self.cache.fics);
         let mut outcome = sidebar::Outcome::None;
         egui::Panel::left("ficflow-sidebar")
             .default_size(160.0)
@@ -985,8 +2141,12 @@ impl FicflowApp {
                     SidebarState {
                         current_view: &mut self.current_view,
                         shelves: &self.cache.shelves,
-                        library_counts: &library_counts,
+                        saved_searches: &self.cache.saved_searches,
+                        library_counts: &self.cache.library_counts,
                         shelf_counts: &self.cache.shelf_counts,
+                        saved_search_counts: &self.cache.saved_search_counts,
+                        trash_count: self.cache.trashed_fics.len()
+                            + self.cache.trashed_shelves.len(),
                         running_tasks: self.task_executor.running_count(),
                     },
                 );
@@ -1009,7 +2169,7 @@ impl FicflowApp {
                     .find(|s| s.id == id)
                     .and_then(AutoShelfState::from_shelf)
                 {
-                    self.active_modal = ActiveModal::AutoShelf(state);
+                    self.open_auto_shelf_modal(state);
                 }
             }
             sidebar::Outcome::OpenDeleteShelfConfirm(id) => {
@@ -1027,13 +2187,25 @@ impl FicflowApp {
             sidebar::Outcome::TogglePinShelf(shelf_id) => {
                 let _ = self.toggle_pin_shelf(shelf_id);
             }
+            sidebar::Outcome::OpenRenameSavedSearchModal(id) => {
+                if let Some(search) = self.cache.saved_searches.iter().find(|s| s.id == id) {
+                    self.active_modal = ActiveModal::RenameSavedSearch(
+                        saved_search_modals::RenameState::new(search),
+                    );
+                }
+            }
+            sidebar::Outcome::MoveSavedSearch {
+                saved_search_id,
+                new_position,
+            } => {
+                let _ = self.move_saved_search(saved_search_id, new_position);
+            }
+            sidebar::Outcome::DeleteSavedSearch(id) => {
+                let _ = self.delete_saved_search(id);
+            }
         }
         if self.current_view != prev_view {
-            if matches!(self.current_view, View::Shelf(_)) {
-                self.refresh_shelf_members();
-            } else {
-                self.cache.shelf_members.clear();
-            }
+            self.sync_view_state();
             self.prune_selection_to_view();
             self.persist_current_view();
         }
@@ -1052,17 +2224,23 @@ impl FicflowApp {
             .collect()
     }
 
+    /// A single selection gets the fic's details; a multi-selection gets
+    /// the bulk editor in the same slot.
     fn paint_details_panel(&mut self, host: &mut egui::Ui) {
-        let Selection::Single(id) = *self.selection.current() else {
-            return;
-        };
         if !self.current_view.shows_library() {
             return;
         }
-        // Clone so the immutable borrow on `cache.fics` releases before
-        // dispatching outcomes through `&mut self`.
-        let Some(fic) = self.cache.fics.iter().find(|f| f.id == id).cloned() else {
-            return;
+        let selection = self.selection.current().clone();
+        self.sync_selected_fics();
+        // Clone so the immutable borrow on `cache.selected_fics` releases
+        // before dispatching outcomes through `&mut self`.
+        let single_fic = match &selection {
+            Selection::None => return,
+            Selection::Single(_) => match self.cache.selected_fics.first() {
+                Some(fic) => Some(fic.clone()),
+                None => return,
+            },
+            Selection::Multi(_) => None,
         };
 
         // egui#8055 workaround: we don't use the built-in `.resizable(true)`
@@ -1078,18 +2256,24 @@ impl FicflowApp {
 
         let assignable_shelves = self.assignable_shelves();
         let mut outcome = details_panel::Outcome::None;
+        let mut bulk_outcome = bulk_details_panel::Outcome::None;
         egui::Panel::right("ficflow-details-v2")
             .exact_size(panel_width)
             .resizable(false)
             .show_inside(host, |ui| {
-                outcome = details_panel::draw(
-                    ui,
-                    DetailsState {
-                        fic: &fic,
-                        all_shelves: &assignable_shelves,
-                        selection_shelf_ids: &self.cache.selection_shelf_ids,
-                    },
-                );
+                if let Some(fic) = &single_fic {
+                    outcome = details_panel::draw(
+                        ui,
+                        DetailsState {
+                            fic,
+                            all_shelves: &assignable_shelves,
+                            selection_shelf_ids: &self.cache.selection_shelf_ids,
+                        },
+                    );
+                } else if let Selection::Multi(_) = &selection {
+                    let fics: Vec<&Fanfiction> = self.cache.selected_fics.iter().collect();
+                    bulk_outcome = bulk_details_panel::draw(ui, BulkDetailsState { fics: &fics });
+                }
             });
 
         // Custom drag handle on the panel's left edge. `available_rect_before_wrap`
@@ -1120,7 +2304,16 @@ impl FicflowApp {
                 .set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
         }
 
-        self.dispatch_details_outcome(id, outcome);
+        match selection {
+            Selection::Single(id) => self.dispatch_details_outcome(id, outcome),
+            Selection::Multi(ids) => {
+                if let bulk_details_panel::Outcome::Apply(edit) = bulk_outcome {
+                    let (succeeded, failed) = self.bulk_edit(&ids, &edit);
+                    self.toast_bulk_result(bulk_edit_action(&edit), succeeded, failed);
+                }
+            }
+            Selection::None => {}
+        }
     }
 
     fn paint_selection_bar(&mut self, host: &mut egui::Ui) {
@@ -1168,24 +2361,44 @@ impl FicflowApp {
     fn paint_central(&mut self, host: &mut egui::Ui) {
         let mut table_outcome = TableOutcome::default();
         let mut empty_area_clicked = false;
+        let mut trash_outcome = trash_view::Outcome::None;
+        let mut discover_outcome = discover_view::Outcome::None;
         let prev_selection = self.selection.current().clone();
-        let view_title = self.current_view.header_title(&self.cache.shelves);
+        let view_title = self
+            .current_view
+            .header_title(&self.cache.shelves, &self.cache.saved_searches);
+        // Synced before the header too, so its fic count is current.
+        self.sync_library_rows();
         egui::CentralPanel::default().show_inside(host, |ui| {
             self.draw_central_header(ui, &view_title);
+            self.sync_search();
+            self.sync_library_rows();
             ui.add_space(6.0);
             if self.current_view.shows_library() {
+                let library_is_empty = self.cache.library_counts.all == 0;
+                let (mut rows, search) = self.cache.table_rows(&self.connection);
                 table_outcome = library_view::draw(
                     ui,
                     LibraryViewState {
-                        fics: &self.cache.fics,
+                        library_is_empty,
+                        rows: &mut rows,
                         sort: &mut self.sort,
-                        search_query: &self.search_query,
+                        search,
+                        ranked: self.rank_by_relevance && search.is_active(),
                         visible_columns: &self.config.visible_columns,
                         selection: &mut self.selection,
-                        view: &self.current_view,
-                        shelf_members: &self.cache.shelf_members,
                     },
                 );
+            } else if matches!(self.current_view, View::Trash) {
+                trash_outcome = trash_view::draw(
+                    ui,
+                    TrashViewState {
+                        fics: &self.cache.trashed_fics,
+                        shelves: &self.cache.trashed_shelves,
+                    },
+                );
+            } else if matches!(self.current_view, View::Discover) {
+                discover_outcome = discover_view::draw(ui, &mut self.discover);
             } else if matches!(self.current_view, View::Tasks) {
                 tasks_view::draw(
                     ui,
@@ -1195,7 +2408,8 @@ impl FicflowApp {
                     },
                 );
             } else if matches!(self.current_view, View::Settings) {
-                let outcome = settings_view::draw(ui, &mut self.config, &self.current_db_path);
+                let outcome =
+                    settings_view::draw(ui, &mut self.config, &self.current_db_path, &self.backups);
                 if outcome.config_changed {
                     self.save_config();
                 }
@@ -1218,6 +2432,39 @@ impl FicflowApp {
                 }
             }
         });
+        match trash_outcome {
+            trash_view::Outcome::None => {}
+            trash_view::Outcome::RestoreFic(id) => {
+                let _ = self.restore_fic(id);
+            }
+            trash_view::Outcome::PurgeFic(id) => {
+                let _ = self.purge_fic(id);
+            }
+            trash_view::Outcome::RestoreShelf(id) => {
+                let _ = self.restore_shelf(id);
+            }
+            trash_view::Outcome::PurgeShelf(id) => {
+                let _ = self.purge_shelf(id);
+            }
+            trash_view::Outcome::RequestEmpty => {
+                self.active_modal = ActiveModal::ConfirmEmptyTrash;
+            }
+        }
+        match discover_outcome {
+            discover_view::Outcome::None => {}
+            discover_view::Outcome::Search(page) => {
+                let search = self.discover.search();
+                self.search_ao3(search, page, self.discover.hide_tracked);
+            }
+            discover_view::Outcome::Page(page) => {
+                if let Some((search, _)) = &self.discover.results {
+                    self.search_ao3(search.clone(), page, self.discover.hide_tracked);
+                }
+            }
+            discover_view::Outcome::Add(ids) => {
+                self.add_discovered(&ids);
+            }
+        }
         if empty_area_clicked && self.current_view.shows_library() {
             self.clear_selection();
         }
@@ -1225,12 +2472,14 @@ impl FicflowApp {
             self.refresh_selection_shelf_ids();
         }
         // Selected fic got deleted this frame: drop the orphan selection.
-        if let Selection::Single(id) = *self.selection.current()
-            && !self.cache.fics.iter().any(|f| f.id == id)
-        {
-            self.clear_selection();
+        if let Selection::Single(_) = *self.selection.current() {
+            self.sync_selected_fics();
+            if self.cache.selected_fics.is_empty() {
+                self.clear_selection();
+            }
         }
         if table_outcome.sort_changed {
+            self.rank_by_relevance = false;
             self.config.default_sort = self.sort;
             self.save_config();
         }
@@ -1250,6 +2499,8 @@ impl FicflowApp {
             &mut self.show_column_picker,
             &mut self.config.visible_columns,
         );
+        // The delete confirms list the selection's titles.
+        self.sync_selected_fics();
         // Outcomes extracted into a local `ModalAction` before dispatch
         // so the action handler (which takes `&mut self`) doesn't fight
         // the modal-borrow over `self.active_modal`.
@@ -1267,6 +2518,11 @@ impl FicflowApp {
                 shelf_id: u64,
                 new_name: String,
             },
+            SaveSearch(String),
+            RenameSavedSearch {
+                saved_search_id: u64,
+                new_name: String,
+            },
             DeleteShelf(u64),
             DeleteFics(Vec<u64>),
             RemoveFicsFromShelf {
@@ -1274,9 +2530,20 @@ impl FicflowApp {
                 shelf_id: u64,
             },
             AddFic(String),
+            BulkAdd {
+                text: String,
+                shelf_id: Option<u64>,
+            },
+            Export(ExportRequest),
+            ImportFics(FicImportPlan),
+            QueueLinks(LinkImportPlan),
+            RepreviewMerge,
+            Merge,
             RestoreBackup(PathBuf),
+            EmptyTrash,
             Quit,
         }
+        let shelves = self.assignable_shelves();
         let action = match &mut self.active_modal {
             ActiveModal::None => ModalAction::None,
             ActiveModal::CreateShelf(state) => match shelf_modals::draw_create(ctx, state) {
@@ -1292,9 +2559,26 @@ impl FicflowApp {
                 shelf_modals::RenameOutcome::Cancel => ModalAction::Close,
                 shelf_modals::RenameOutcome::None => ModalAction::None,
             },
+            ActiveModal::SaveSearch(state) => match saved_search_modals::draw_save(ctx, state) {
+                saved_search_modals::SaveOutcome::Submit(name) => ModalAction::SaveSearch(name),
+                saved_search_modals::SaveOutcome::Cancel => ModalAction::Close,
+                saved_search_modals::SaveOutcome::None => ModalAction::None,
+            },
+            ActiveModal::RenameSavedSearch(state) => {
+                match saved_search_modals::draw_rename(ctx, state) {
+                    saved_search_modals::RenameOutcome::Submit {
+                        saved_search_id,
+                        new_name,
+                    } => ModalAction::RenameSavedSearch {
+                        saved_search_id,
+                        new_name,
+                    },
+                    saved_search_modals::RenameOutcome::Cancel => ModalAction::Close,
+                    saved_search_modals::RenameOutcome::None => ModalAction::None,
+                }
+            }
             ActiveModal::AutoShelf(state) => {
-                let distinct_values = auto_shelf::build_distinct_values(&self.cache.fics);
-                match shelf_modals::draw_auto_shelf(ctx, state, &distinct_values) {
+                match shelf_modals::draw_auto_shelf(ctx, state, &self.auto_shelf_values) {
                     shelf_modals::AutoShelfOutcome::Submit {
                         shelf_id,
                         name,
@@ -1316,7 +2600,7 @@ impl FicflowApp {
                 }
             }
             ActiveModal::DeleteFics(ids) => {
-                match bulk_modals::draw_delete_confirm(ctx, ids, &self.cache.fics) {
+                match bulk_modals::draw_delete_confirm(ctx, ids, &self.cache.selected_fics) {
                     bulk_modals::DeleteOutcome::Confirm(ids) => ModalAction::DeleteFics(ids),
                     bulk_modals::DeleteOutcome::Cancel => ModalAction::Close,
                     bulk_modals::DeleteOutcome::None => ModalAction::None,
@@ -1331,7 +2615,12 @@ impl FicflowApp {
                     .find(|s| s.id == shelf_id)
                     .map(|s| s.name.as_str())
                     .unwrap_or("shelf");
-                match bulk_modals::draw_remove_or_delete(ctx, ids, shelf_name, &self.cache.fics) {
+                match bulk_modals::draw_remove_or_delete(
+                    ctx,
+                    ids,
+                    shelf_name,
+                    &self.cache.selected_fics,
+                ) {
                     bulk_modals::RemoveOrDeleteOutcome::RemoveFromShelf(ids) => {
                         ModalAction::RemoveFicsFromShelf { ids, shelf_id }
                     }
@@ -1342,11 +2631,40 @@ impl FicflowApp {
                     bulk_modals::RemoveOrDeleteOutcome::None => ModalAction::None,
                 }
             }
-            ActiveModal::AddFic(state) => match add_fic_dialog::draw(ctx, state) {
+            ActiveModal::AddFic(state) => match add_fic_dialog::draw(ctx, state, &shelves) {
                 add_fic_dialog::Outcome::Submit(input) => ModalAction::AddFic(input),
+                add_fic_dialog::Outcome::SubmitBulk { text, shelf_id } => {
+                    ModalAction::BulkAdd { text, shelf_id }
+                }
                 add_fic_dialog::Outcome::Cancel => ModalAction::Close,
                 add_fic_dialog::Outcome::None => ModalAction::None,
             },
+            ActiveModal::ExportRows(state) => match export_modal::draw(
+                ctx,
+                state,
+                self.cache.rows.len(),
+                &self.config.visible_columns,
+            ) {
+                export_modal::Outcome::Submit(request) => ModalAction::Export(request),
+                export_modal::Outcome::Cancel => ModalAction::Close,
+                export_modal::Outcome::None => ModalAction::None,
+            },
+            ActiveModal::ImportFics(state) => match fic_import_modal::draw(ctx, state) {
+                fic_import_modal::Outcome::Import => ModalAction::ImportFics(state.plan.clone()),
+                fic_import_modal::Outcome::Cancel => ModalAction::Close,
+                fic_import_modal::Outcome::None => ModalAction::None,
+            },
+            ActiveModal::ImportLinks(state) => match link_import_modal::draw(ctx, state) {
+                link_import_modal::Outcome::Queue => ModalAction::QueueLinks(state.plan.clone()),
+                link_import_modal::Outcome::Cancel => ModalAction::Close,
+                link_import_modal::Outcome::None => ModalAction::None,
+            },
+            ActiveModal::MergeLibrary(state) => match merge_modal::draw(ctx, state) {
+                merge_modal::Outcome::PolicyChanged => ModalAction::RepreviewMerge,
+                merge_modal::Outcome::Merge => ModalAction::Merge,
+                merge_modal::Outcome::Cancel => ModalAction::Close,
+                merge_modal::Outcome::None => ModalAction::None,
+            },
             ActiveModal::ConfirmQuit => {
                 match quit_modal::draw_confirm(ctx, self.task_executor.running_count()) {
                     quit_modal::Outcome::Quit => ModalAction::Quit,
@@ -1359,6 +2677,15 @@ impl FicflowApp {
                 restore_modal::Outcome::Cancel => ModalAction::Close,
                 restore_modal::Outcome::None => ModalAction::None,
             },
+            ActiveModal::ConfirmEmptyTrash => match trash_modal::draw_empty_confirm(
+                ctx,
+                self.cache.trashed_fics.len(),
+                self.cache.trashed_shelves.len(),
+            ) {
+                trash_modal::Outcome::Confirm => ModalAction::EmptyTrash,
+                trash_modal::Outcome::Cancel => ModalAction::Close,
+                trash_modal::Outcome::None => ModalAction::None,
+            },
         };
         match action {
             ModalAction::None => {}
@@ -1368,7 +2695,7 @@ impl FicflowApp {
                 self.active_modal = ActiveModal::None;
             }
             ModalAction::SwitchToAutoShelf => {
-                self.active_modal = ActiveModal::AutoShelf(AutoShelfState::new());
+                self.open_auto_shelf_modal(AutoShelfState::new());
             }
             ModalAction::UpsertAutoShelf {
                 shelf_id,
@@ -1382,6 +2709,17 @@ impl FicflowApp {
                 let _ = self.rename_shelf(shelf_id, new_name);
                 self.active_modal = ActiveModal::None;
             }
+            ModalAction::SaveSearch(name) => {
+                let _ = self.save_search(name);
+                self.active_modal = ActiveModal::None;
+            }
+            ModalAction::RenameSavedSearch {
+                saved_search_id,
+                new_name,
+            } => {
+                let _ = self.rename_saved_search(saved_search_id, new_name);
+                self.active_modal = ActiveModal::None;
+            }
             ModalAction::DeleteShelf(id) => {
                 let _ = self.delete_shelf(id);
                 self.active_modal = ActiveModal::None;
@@ -1396,13 +2734,40 @@ impl FicflowApp {
                 self.active_modal = ActiveModal::None;
             }
             ModalAction::AddFic(input) => {
-                self.task_executor.enqueue_add(input);
+                self.task_executor.enqueue_add(input, AddOptions::default());
+                self.active_modal = ActiveModal::None;
+            }
+            ModalAction::BulkAdd { text, shelf_id } => {
+                // Leave the dialog open when nothing was queued, so the
+                // text can be fixed.
+                if self.submit_bulk_add(&text, shelf_id).is_ok() {
+                    self.active_modal = ActiveModal::None;
+                }
+            }
+            ModalAction::Export(request) => {
+                self.pending_export = Some(request);
+                self.active_modal = ActiveModal::None;
+            }
+            ModalAction::ImportFics(plan) => {
+                let _ = self.apply_fic_import(plan);
+                self.active_modal = ActiveModal::None;
+            }
+            ModalAction::QueueLinks(plan) => {
+                let _ = self.queue_link_import(plan);
                 self.active_modal = ActiveModal::None;
             }
+            ModalAction::RepreviewMerge => self.refresh_merge_preview(),
+            ModalAction::Merge => {
+                let _ = self.confirm_library_merge();
+            }
             ModalAction::RestoreBackup(backup) => {
                 self.restore_library_backup(backup);
                 self.active_modal = ActiveModal::None;
             }
+            ModalAction::EmptyTrash => {
+                let _ = self.empty_trash();
+                self.active_modal = ActiveModal::None;
+            }
             ModalAction::Quit => {
                 self.confirm_quit();
                 ctx.send_viewport_cmd(egui::ViewportCommand::Close);
@@ -1413,6 +2778,30 @@ impl FicflowApp {
         }
     }
 
+    fn drain_instance_messages(&mut self, ctx: &egui::Context) {
+        let Some(instance) = &self.instance else {
+            return;
+        };
+        for message in instance.take_messages() {
+            match message {
+                Message::Focus => {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                }
+                Message::Add { fic } => {
+                    self.task_executor.enqueue_add(fic, AddOptions::default());
+                }
+                Message::Reload => {
+                    // Anything could have changed, so it's a history
+                    // reload plus what history never touches.
+                    self.reload_after_history();
+                    self.cache.reload_saved_searches(&self.connection);
+                    self.refresh_selection_shelf_ids();
+                }
+            }
+        }
+    }
+
     fn drain_worker_events(&mut self, ctx: &egui::Context) {
         let completions = self.task_executor.take_completions();
         if !completions.is_empty() {
@@ -1421,17 +2810,29 @@ impl FicflowApp {
                     .success(format!("Added \u{201C}{}\u{201D}", title));
             }
             self.cache.reload_fics(&self.connection);
-            if matches!(self.current_view, View::Shelf(_)) {
+            if matches!(self.library_scope(), View::Shelf(_)) {
                 self.refresh_shelf_members();
             }
             self.refresh_selection_shelf_ids();
+            let in_library = self.cache.view_fic_ids(&self.connection, &View::AllFics);
+            self.discover.mark_tracked(&in_library);
+        }
+        if let Some((search, result)) = self.task_executor.take_search_result() {
+            self.discover.searching = false;
+            match result {
+                Ok(page) => {
+                    self.discover.results = Some((search, page));
+                    self.discover.checked.clear();
+                }
+                Err(err) => self.discover.error = Some(format!("Couldn't search AO3: {}", err)),
+            }
         }
         let refreshes = self.task_executor.take_refreshes();
         if !refreshes.is_empty() {
             self.toasts
                 .success(format!("Refreshed {} fanfiction(s)", refreshes.len()));
             self.cache.reload_fics(&self.connection);
-            if matches!(self.current_view, View::Shelf(_)) {
+            if matches!(self.library_scope(), View::Shelf(_)) {
                 self.refresh_shelf_members();
             }
         }
@@ -1466,12 +2867,7 @@ impl FicflowApp {
             if !self.current_view.shows_library() {
                 return;
             }
-            let visible = library_view::visible_count(
-                &self.cache.fics,
-                &self.current_view,
-                &self.cache.shelf_members,
-                &self.search_query,
-            );
+            let visible = self.cache.rows.len();
             let suffix = if visible == 1 { "fic" } else { "fics" };
             ui.label(egui::RichText::new(format!("{} {}", visible, suffix)).weak());
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -1487,10 +2883,29 @@ impl FicflowApp {
                 {
                     self.show_column_picker = !self.show_column_picker;
                 }
+                let export_label = if compact {
+                    "\u{2913}"
+                } else {
+                    "Export\u{2026}"
+                };
+                if ui
+                    .button(export_label)
+                    .on_hover_text("Export visible rows\u{2026}")
+                    .clicked()
+                {
+                    self.active_modal = ActiveModal::ExportRows(ExportState::default());
+                }
                 let add_label = if compact { "+" } else { "+ Add Fic" };
                 if ui.button(add_label).on_hover_text("Add Fic").clicked() {
                     self.active_modal = ActiveModal::AddFic(AddFicState::new());
                 }
+                if !self.search_query.trim().is_empty() {
+                    let save_label = if compact { "\u{2606}" } else { "Save Search" };
+                    if ui.button(save_label).on_hover_text("Save Search").clicked() {
+                        self.active_modal =
+                            ActiveModal::SaveSearch(SaveState::new(&self.search_query));
+                    }
+                }
             });
         });
 
@@ -1545,7 +2960,7 @@ impl FicflowApp {
                         ui.label(egui::RichText::new("\u{1F50D}").color(weak));
                         let resp = ui.add(
                             egui::TextEdit::singleline(&mut self.search_query)
-                                .hint_text("Search title, author, fandom, tags…")
+                                .hint_text("Search titles, summaries, tags, notes…")
                                 .frame(egui::Frame::NONE)
                                 .desired_width(f32::INFINITY),
                         );
@@ -1567,10 +2982,8 @@ impl FicflowApp {
             match payload.as_slice() {
                 [single] => self
                     .cache
-                    .fics
-                    .iter()
-                    .find(|f| f.id == *single)
-                    .map(|f| f.title.clone())
+                    .fic(&self.connection, *single)
+                    .map(|f| f.title)
                     .unwrap_or_else(|| "(unknown)".to_string()),
                 ids => format!("{} fanfictions", ids.len()),
             }
@@ -1605,12 +3018,13 @@ impl FicflowApp {
 impl FicflowApp {
     fn handle_drop_on_shelf(&mut self, shelf_id: u64, fic_ids: &[u64]) {
         let attempted = fic_ids.len();
-        let errors = self.mutate(|repo| {
-            fic_ids
-                .iter()
-                .filter(|id| add_to_shelf(repo, **id, shelf_id).is_err())
-                .count()
-        });
+        let (changes, errors) =
+            self.mutate(|repo| change_membership(repo, fic_ids, shelf_id, true));
+        let errors = errors.len();
+        self.record(
+            format!("Added {} fanfiction(s) to shelf", changes.len()),
+            changes,
+        );
         if errors == 0 {
             self.toasts
                 .success(format!("Added {} fanfiction(s) to shelf", attempted));
@@ -1633,7 +3047,9 @@ impl FicflowApp {
                 .collect()
         });
         let errors = total - surviving.len();
-        self.cache.remove_fics(&surviving);
+        self.cache.reload_fics(&self.connection);
+        self.cache.reload_trash(&self.connection);
+        self.record_deletions(&surviving);
         if errors == 0 {
             self.toasts
                 .success(format!("Deleted {} fanfictions", total));
@@ -1676,6 +3092,16 @@ impl FicflowApp {
             .input_mut(|i| i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::N)));
         let ctrl_f = ctx
             .input_mut(|i| i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::F)));
+        // Shift+Z first: `consume_shortcut` matches logically, so the plain
+        // Ctrl+Z shortcut would also swallow Ctrl+Shift+Z.
+        let ctrl_shift_z = ctx.input_mut(|i| {
+            i.consume_shortcut(&KeyboardShortcut::new(
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::Z,
+            ))
+        });
+        let ctrl_z = ctx
+            .input_mut(|i| i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Z)));
 
         if pressed_esc && !matches!(self.selection.current(), Selection::None) {
             self.clear_selection();
@@ -1707,13 +3133,7 @@ impl FicflowApp {
         }
 
         if ctrl_a && self.current_view.shows_library() {
-            let ids = library_view::visible_ids(
-                &self.cache.fics,
-                &self.current_view,
-                &self.cache.shelf_members,
-                &self.search_query,
-                self.sort,
-            );
+            let ids = self.visible_ids();
             self.selection.select_many(&ids);
             self.refresh_selection_shelf_ids();
         }
@@ -1725,5 +3145,12 @@ impl FicflowApp {
         if ctrl_f && self.current_view.shows_library() {
             self.focus_search_pending = true;
         }
+
+        // Failures are already toasted by `undo` / `redo`.
+        if ctrl_shift_z {
+            let _ = self.redo();
+        } else if ctrl_z {
+            let _ = self.undo();
+        }
     }
 }
@@ -1,44 +1,52 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::HashSet;
 
-use crate::domain::fanfiction::Fanfiction;
-use crate::domain::shelf::{AutoShelfCriteria, Clause, ClauseLogic};
-
-fn contains_ci(values: Option<&[String]>, needle: &str) -> bool {
-    values
-        .unwrap_or(&[])
-        .iter()
-        .any(|v| v.eq_ignore_ascii_case(needle))
-}
-
-fn eval_clause(fic: &Fanfiction, clause: &Clause) -> bool {
-    match clause {
-        Clause::Tag(v) => contains_ci(fic.tags.as_deref(), v),
-        Clause::Fandom(v) => contains_ci(Some(&fic.fandoms), v),
-        Clause::Relationship(v) => contains_ci(fic.relationships.as_deref(), v),
-        Clause::Character(v) => contains_ci(fic.characters.as_deref(), v),
-        Clause::Author(v) => contains_ci(Some(&fic.authors), v),
-        Clause::Status(status) => fic.reading_status == *status,
-    }
-}
+use rusqlite::Connection;
 
-/// Whether `fic` satisfies `criteria`. An empty clause list matches no
-/// fics, under either AND or OR, so an auto-shelf never accidentally
-/// includes the whole library before its first clause is added.
-pub fn matches(fic: &Fanfiction, criteria: &AutoShelfCriteria) -> bool {
-    if criteria.clauses.is_empty() {
-        return false;
-    }
-    match criteria.logic {
-        ClauseLogic::And => criteria.clauses.iter().all(|c| eval_clause(fic, c)),
-        ClauseLogic::Or => criteria.clauses.iter().any(|c| eval_clause(fic, c)),
-    }
-}
+use crate::application::count_tags::count_tags;
+use crate::application::find_fics_by_tag::find_fic_ids_by_tag;
+use crate::application::query_fic_ids::query_fic_ids;
+use crate::domain::fanfiction::{FicFilter, FicQuery, TagKind};
+use crate::domain::shelf::{AutoShelfCriteria, Clause, ClauseLogic};
+use crate::error::FicflowError;
+use crate::infrastructure::SqliteRepository;
 
-pub fn matching_fic_ids(fics: &[Fanfiction], criteria: &AutoShelfCriteria) -> HashSet<u64> {
-    fics.iter()
-        .filter(|f| matches(f, criteria))
-        .map(|f| f.id)
-        .collect()
+/// The live fics `criteria` matches, read clause by clause from the tag
+/// index — which folds ASCII case like `Clause::matches` does — and
+/// combined in memory.
+pub fn matching_fic_ids(
+    connection: &Connection,
+    criteria: &AutoShelfCriteria,
+) -> Result<HashSet<u64>, FicflowError> {
+    let repo = SqliteRepository::new(connection);
+    let sets = criteria.clauses.iter().map(|clause| {
+        if clause.is_blank() {
+            return Ok(HashSet::new());
+        }
+        let ids = match clause {
+            Clause::Tag(v) => find_fic_ids_by_tag(&repo, TagKind::Tag, v)?,
+            Clause::Fandom(v) => find_fic_ids_by_tag(&repo, TagKind::Fandom, v)?,
+            Clause::Relationship(v) => find_fic_ids_by_tag(&repo, TagKind::Relationship, v)?,
+            Clause::Character(v) => find_fic_ids_by_tag(&repo, TagKind::Character, v)?,
+            Clause::Author(v) => find_fic_ids_by_tag(&repo, TagKind::Author, v)?,
+            Clause::Status(status) => {
+                let query = FicQuery {
+                    filter: FicFilter {
+                        status: Some(*status),
+                        ..FicFilter::default()
+                    },
+                    ..FicQuery::default()
+                };
+                query_fic_ids(&repo, &query)?
+            }
+        };
+        Ok(ids.into_iter().collect())
+    });
+    let sets: Vec<HashSet<u64>> = sets.collect::<Result<_, FicflowError>>()?;
+    let members = sets.into_iter().reduce(|acc, set| match criteria.logic {
+        ClauseLogic::And => acc.intersection(&set).copied().collect(),
+        ClauseLogic::Or => acc.union(&set).copied().collect(),
+    });
+    Ok(members.unwrap_or_default())
 }
 
 #[derive(Debug, Clone, Default)]
@@ -50,26 +58,23 @@ pub struct DistinctValues {
     pub authors: Vec<String>,
 }
 
-pub fn build_distinct_values(fics: &[Fanfiction]) -> DistinctValues {
-    let mut tags = BTreeSet::new();
-    let mut fandoms = BTreeSet::new();
-    let mut relationships = BTreeSet::new();
-    let mut characters = BTreeSet::new();
-    let mut authors = BTreeSet::new();
-
-    for fic in fics {
-        tags.extend(fic.tags.iter().flatten().cloned());
-        fandoms.extend(fic.fandoms.iter().cloned());
-        relationships.extend(fic.relationships.iter().flatten().cloned());
-        characters.extend(fic.characters.iter().flatten().cloned());
-        authors.extend(fic.authors.iter().cloned());
-    }
+/// Every distinct value the auto-shelf clause builder offers, read from
+/// the tag index rather than scanned out of the loaded fics.
+pub fn build_distinct_values(connection: &Connection) -> DistinctValues {
+    let repo = SqliteRepository::new(connection);
+    let names = |kind: TagKind| match count_tags(&repo, kind) {
+        Ok(counts) => counts.into_iter().map(|c| c.name).collect(),
+        Err(err) => {
+            log::error!("Failed to load {} tags: {}", kind, err);
+            Vec::new()
+        }
+    };
 
     DistinctValues {
-        tags: tags.into_iter().collect(),
-        fandoms: fandoms.into_iter().collect(),
-        relationships: relationships.into_iter().collect(),
-        characters: characters.into_iter().collect(),
-        authors: authors.into_iter().collect(),
+        tags: names(TagKind::Tag),
+        fandoms: names(TagKind::Fandom),
+        relationships: names(TagKind::Relationship),
+        characters: names(TagKind::Character),
+        authors: names(TagKind::Author),
     }
 }
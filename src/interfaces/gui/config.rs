@@ -2,103 +2,49 @@
 //! dir (`~/.config/ficflow/config.toml` on Linux). Holds visible
 //! columns, the default sort for the library table, the
 //! maximized/fullscreen window state, the text-zoom level, the theme
-//! choice, and the library location — read at startup, written when the
-//! user changes them.
+//! choice, the library location and the trash auto-purge age — read at
+//! startup, written when the user changes them.
 //!
 //! Lives under `interfaces/gui/` because almost every field is a GUI
-//! concern with no meaning to the CLI. The one exception is
-//! `library_path`: it's the single source of truth for where the
-//! database lives, so both the GUI and the CLI resolve it through
-//! `resolved_db_path`.
+//! concern with no meaning to the CLI — though `ficflow config` can read
+//! and write the user-facing ones, listed in `Setting`. The exceptions are `library_path`,
+//! the single source of truth for where the database lives (both the GUI
+//! and the CLI resolve it through `resolved_db_path`), and
+//! `trash_auto_purge_days`, applied by whichever of the two opens the
+//! library, plus the reading settings `ficflow open` shares with the
+//! "Continue reading" button.
 
 use std::env;
 use std::io;
 use std::path::PathBuf;
 
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
+use crate::application::query_fics::{parse_column_keys, parse_sort_pref};
+use crate::domain::backup::DEFAULT_BACKUP_KEEP;
 use crate::domain::fanfiction::ReadingStatus;
+pub use crate::domain::fanfiction::{ColumnKey, SortDirection, SortPref};
 use crate::error::FicflowError;
+use crate::infrastructure::external::ao3::fetcher::PRIMARY_AO3_URL;
 
 const DB_PATH_ENV: &str = "FICFLOW_DB_PATH";
+const OPENER_ENV: &str = "FICFLOW_OPENER";
 
 const CONFIG_FILE: &str = "config.toml";
+const API_TOKEN_FILE: &str = "api-token";
 const DB_FILE: &str = "fanfictions.db";
 const APP_DIR: &str = "ficflow";
 
 /// Serializable shadow of the library-facing `View` variants — the ones
-/// worth restoring on next launch. `Tasks` and `Settings` are transient
+/// worth restoring on next launch. `Trash`, `Tasks` and `Settings` are transient
 /// pages, not "tabs", so they're deliberately excluded.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PersistedView {
     AllFics,
     ByStatus(ReadingStatus),
     Shelf(u64),
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ColumnKey {
-    Title,
-    Author,
-    Fandom,
-    Pairing,
-    AO3Rating,
-    Warnings,
-    Status,
-    Complete,
-    LastChapter,
-    Words,
-    Kudos,
-    Hits,
-    Rating,
-    Reads,
-    Language,
-    DatePublished,
-    Updated,
-}
-
-impl ColumnKey {
-    pub const ALL: [ColumnKey; 17] = [
-        ColumnKey::Title,
-        ColumnKey::Author,
-        ColumnKey::Fandom,
-        ColumnKey::Pairing,
-        ColumnKey::AO3Rating,
-        ColumnKey::Warnings,
-        ColumnKey::Status,
-        ColumnKey::Complete,
-        ColumnKey::LastChapter,
-        ColumnKey::Words,
-        ColumnKey::Kudos,
-        ColumnKey::Hits,
-        ColumnKey::Rating,
-        ColumnKey::Reads,
-        ColumnKey::Language,
-        ColumnKey::DatePublished,
-        ColumnKey::Updated,
-    ];
-
-    pub fn label(self) -> &'static str {
-        match self {
-            ColumnKey::Title => "Title",
-            ColumnKey::Author => "Author",
-            ColumnKey::Fandom => "Fandom",
-            ColumnKey::Pairing => "Pairing",
-            ColumnKey::AO3Rating => "AO3 Rating",
-            ColumnKey::Warnings => "Warnings",
-            ColumnKey::Status => "Status",
-            ColumnKey::Complete => "Complete",
-            ColumnKey::LastChapter => "Last Ch.",
-            ColumnKey::Words => "Words",
-            ColumnKey::Kudos => "Kudos",
-            ColumnKey::Hits => "Hits",
-            ColumnKey::Rating => "Rating",
-            ColumnKey::Reads => "Reads",
-            ColumnKey::Language => "Language",
-            ColumnKey::DatePublished => "Published",
-            ColumnKey::Updated => "Updated",
-        }
-    }
+    SavedSearch(u64),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -120,6 +66,15 @@ impl ThemeChoice {
         }
     }
 
+    /// The name `ficflow config` takes, e.g. `dark`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemeChoice::System => "system",
+            ThemeChoice::Clear => "clear",
+            ThemeChoice::Dark => "dark",
+        }
+    }
+
     fn preference(self) -> egui::ThemePreference {
         match self {
             ThemeChoice::System => egui::ThemePreference::System,
@@ -129,27 +84,6 @@ impl ThemeChoice {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum SortDirection {
-    Ascending,
-    Descending,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SortPref {
-    pub column: ColumnKey,
-    pub direction: SortDirection,
-}
-
-impl Default for SortPref {
-    fn default() -> Self {
-        Self {
-            column: ColumnKey::Updated,
-            direction: SortDirection::Descending,
-        }
-    }
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppConfig {
     pub visible_columns: Vec<ColumnKey>,
@@ -177,14 +111,45 @@ pub struct AppConfig {
     /// default. Shared with the CLI via `resolved_db_path`.
     #[serde(default)]
     pub library_path: Option<PathBuf>,
+    /// Trashed fics and shelves older than this many days are purged when
+    /// the library is opened. `None` keeps them until emptied by hand.
+    #[serde(default)]
+    pub trash_auto_purge_days: Option<u32>,
+    /// How many library backups of each reason to keep in the `backups/`
+    /// folder; older ones are deleted as new ones of the same reason are
+    /// taken.
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    /// The AO3 site fics are opened on for reading. `None` means
+    /// archiveofourown.org itself.
+    #[serde(default)]
+    pub ao3_mirror: Option<String>,
+    /// Command that opens a link, given the URL as its last argument.
+    /// `None` uses the platform's default browser.
+    #[serde(default)]
+    pub opener: Option<String>,
+    /// Whether opening a planned or paused fic to read marks it In
+    /// Progress.
+    #[serde(default = "default_open_marks_in_progress")]
+    pub open_marks_in_progress: bool,
 }
 
 pub const TEXT_ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+pub const BACKUP_KEEP_RANGE: std::ops::RangeInclusive<usize> = 1..=100;
+pub const TRASH_PURGE_DAYS_RANGE: std::ops::RangeInclusive<u32> = 1..=3650;
 
 fn default_text_zoom() -> f32 {
     1.0
 }
 
+fn default_backup_keep() -> usize {
+    DEFAULT_BACKUP_KEEP
+}
+
+fn default_open_marks_in_progress() -> bool {
+    true
+}
+
 pub fn clamp_zoom(zoom: f32) -> f32 {
     if zoom.is_finite() {
         zoom.clamp(*TEXT_ZOOM_RANGE.start(), *TEXT_ZOOM_RANGE.end())
@@ -226,6 +191,11 @@ impl Default for AppConfig {
             text_zoom: 1.0,
             theme: ThemeChoice::System,
             library_path: None,
+            trash_auto_purge_days: None,
+            backup_keep: DEFAULT_BACKUP_KEEP,
+            ao3_mirror: None,
+            opener: None,
+            open_marks_in_progress: true,
         }
     }
 }
@@ -262,6 +232,16 @@ impl AppConfig {
         }
     }
 
+    pub fn reading_base_url(&self) -> &str {
+        self.ao3_mirror.as_deref().unwrap_or(PRIMARY_AO3_URL)
+    }
+
+    /// The opener command, with the `FICFLOW_OPENER` env override (which
+    /// is how tests keep browsers from launching) ahead of the config.
+    pub fn resolved_opener(&self) -> Option<String> {
+        env::var(OPENER_ENV).ok().or_else(|| self.opener.clone())
+    }
+
     pub fn reorder_visible_column(&mut self, col: ColumnKey, target: ColumnKey, place_after: bool) {
         if col == target {
             return;
@@ -299,13 +279,256 @@ impl AppConfig {
     }
 }
 
-fn config_path() -> Option<PathBuf> {
+/// The settings `ficflow config` reads and writes, named as in the TOML
+/// file. Window state and the last open view are the GUI's own to keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Theme,
+    TextZoom,
+    VisibleColumns,
+    DefaultSort,
+    LibraryPath,
+    TrashAutoPurgeDays,
+    BackupKeep,
+    Ao3Mirror,
+    Opener,
+    OpenMarksInProgress,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 10] = [
+        Setting::Theme,
+        Setting::TextZoom,
+        Setting::VisibleColumns,
+        Setting::DefaultSort,
+        Setting::LibraryPath,
+        Setting::TrashAutoPurgeDays,
+        Setting::BackupKeep,
+        Setting::Ao3Mirror,
+        Setting::Opener,
+        Setting::OpenMarksInProgress,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Setting::Theme => "theme",
+            Setting::TextZoom => "text_zoom",
+            Setting::VisibleColumns => "visible_columns",
+            Setting::DefaultSort => "default_sort",
+            Setting::LibraryPath => "library_path",
+            Setting::TrashAutoPurgeDays => "trash_auto_purge_days",
+            Setting::BackupKeep => "backup_keep",
+            Setting::Ao3Mirror => "ao3_mirror",
+            Setting::Opener => "opener",
+            Setting::OpenMarksInProgress => "open_marks_in_progress",
+        }
+    }
+
+    /// Takes dashes for underscores too, so `text-zoom` works.
+    pub fn from_key(key: &str) -> Result<Setting, FicflowError> {
+        let key = key.trim().to_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|setting| setting.key() == key)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.into_iter().map(Setting::key).collect();
+                FicflowError::InvalidInput(format!(
+                    "Unknown setting: '{}'. Valid settings are: {}",
+                    key,
+                    known.join(", ")
+                ))
+            })
+    }
+
+    /// What the value looks like, for error messages and `config list`.
+    pub fn hint(self) -> String {
+        match self {
+            Setting::Theme => {
+                let names: Vec<&str> = ThemeChoice::ALL
+                    .into_iter()
+                    .map(ThemeChoice::name)
+                    .collect();
+                names.join(" | ")
+            }
+            Setting::TextZoom => {
+                format!("{} to {}", TEXT_ZOOM_RANGE.start(), TEXT_ZOOM_RANGE.end())
+            }
+            Setting::VisibleColumns => "comma-separated columns, e.g. title,author,words".into(),
+            Setting::DefaultSort => "column, optionally with :asc or :desc".into(),
+            Setting::LibraryPath => "library file, or a folder to move it into".into(),
+            Setting::TrashAutoPurgeDays => format!(
+                "{} to {} days; reset to keep the trash",
+                TRASH_PURGE_DAYS_RANGE.start(),
+                TRASH_PURGE_DAYS_RANGE.end()
+            ),
+            Setting::BackupKeep => format!(
+                "{} to {} backups",
+                BACKUP_KEEP_RANGE.start(),
+                BACKUP_KEEP_RANGE.end()
+            ),
+            Setting::Ao3Mirror => "http(s) address of an AO3 site".into(),
+            Setting::Opener => "command the link is appended to".into(),
+            Setting::OpenMarksInProgress => "true | false".into(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// The setting's value as `ficflow config` prints it; `None` when it's
+    /// unset and the built-in default applies.
+    pub fn setting(&self, setting: Setting) -> Option<String> {
+        match setting {
+            Setting::Theme => Some(self.theme.name().to_string()),
+            Setting::TextZoom => Some(self.text_zoom.to_string()),
+            Setting::VisibleColumns => {
+                let names: Vec<&str> = self.visible_columns.iter().map(|c| c.name()).collect();
+                Some(names.join(","))
+            }
+            Setting::DefaultSort => Some(format!(
+                "{}:{}",
+                self.default_sort.column.name(),
+                match self.default_sort.direction {
+                    SortDirection::Ascending => "asc",
+                    SortDirection::Descending => "desc",
+                }
+            )),
+            Setting::LibraryPath => self
+                .library_path
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            Setting::TrashAutoPurgeDays => self.trash_auto_purge_days.map(|d| d.to_string()),
+            Setting::BackupKeep => Some(self.backup_keep.to_string()),
+            Setting::Ao3Mirror => self.ao3_mirror.clone(),
+            Setting::Opener => self.opener.clone(),
+            Setting::OpenMarksInProgress => Some(self.open_marks_in_progress.to_string()),
+        }
+    }
+
+    /// Parses and checks `value` before storing it, rejecting anything
+    /// the GUI's own controls wouldn't allow. `library_path` is only
+    /// recorded here; moving the file there is the caller's job.
+    pub fn set_setting(&mut self, setting: Setting, value: &str) -> Result<(), FicflowError> {
+        let value = value.trim();
+        let invalid = || {
+            FicflowError::InvalidInput(format!(
+                "Invalid value for {}: '{}'. Expected {}",
+                setting.key(),
+                value,
+                setting.hint()
+            ))
+        };
+        match setting {
+            Setting::Theme => {
+                self.theme = ThemeChoice::ALL
+                    .into_iter()
+                    .find(|theme| theme.name() == value.to_lowercase())
+                    .ok_or_else(invalid)?;
+            }
+            Setting::TextZoom => {
+                let zoom: f32 = value.parse().map_err(|_| invalid())?;
+                if !TEXT_ZOOM_RANGE.contains(&zoom) {
+                    return Err(invalid());
+                }
+                self.text_zoom = zoom;
+            }
+            Setting::VisibleColumns => {
+                let columns = parse_column_keys(value)?;
+                if let Some(twice) = columns
+                    .iter()
+                    .enumerate()
+                    .find(|(i, column)| columns[..*i].contains(column))
+                    .map(|(_, column)| column)
+                {
+                    return Err(FicflowError::InvalidInput(format!(
+                        "Column '{}' is listed twice",
+                        twice.name()
+                    )));
+                }
+                self.visible_columns = columns;
+            }
+            Setting::DefaultSort => self.default_sort = parse_sort_pref(value)?,
+            Setting::LibraryPath => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+                self.library_path = Some(std::path::absolute(value)?);
+            }
+            Setting::TrashAutoPurgeDays => {
+                let days: u32 = value.parse().map_err(|_| invalid())?;
+                if !TRASH_PURGE_DAYS_RANGE.contains(&days) {
+                    return Err(invalid());
+                }
+                self.trash_auto_purge_days = Some(days);
+            }
+            Setting::BackupKeep => {
+                let keep: usize = value.parse().map_err(|_| invalid())?;
+                if !BACKUP_KEEP_RANGE.contains(&keep) {
+                    return Err(invalid());
+                }
+                self.backup_keep = keep;
+            }
+            Setting::Ao3Mirror => {
+                let url = Url::parse(value).map_err(|_| invalid())?;
+                if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                    return Err(invalid());
+                }
+                let base = value.trim_end_matches('/');
+                // Same as the Settings page: the main site is stored as unset.
+                self.ao3_mirror = (base != PRIMARY_AO3_URL).then(|| base.to_string());
+            }
+            Setting::Opener => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+                self.opener = Some(value.to_string());
+            }
+            Setting::OpenMarksInProgress => {
+                self.open_marks_in_progress = match value.to_lowercase().as_str() {
+                    "true" | "yes" | "on" => true,
+                    "false" | "no" | "off" => false,
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts the setting back to its built-in default.
+    pub fn reset_setting(&mut self, setting: Setting) {
+        let default = AppConfig::default();
+        match setting {
+            Setting::Theme => self.theme = default.theme,
+            Setting::TextZoom => self.text_zoom = default.text_zoom,
+            Setting::VisibleColumns => self.visible_columns = default.visible_columns,
+            Setting::DefaultSort => self.default_sort = default.default_sort,
+            Setting::LibraryPath => self.library_path = default.library_path,
+            Setting::TrashAutoPurgeDays => {
+                self.trash_auto_purge_days = default.trash_auto_purge_days
+            }
+            Setting::BackupKeep => self.backup_keep = default.backup_keep,
+            Setting::Ao3Mirror => self.ao3_mirror = default.ao3_mirror,
+            Setting::Opener => self.opener = default.opener,
+            Setting::OpenMarksInProgress => {
+                self.open_marks_in_progress = default.open_marks_in_progress
+            }
+        }
+    }
+}
+
+/// Where the config file lives, whether or not it's been written yet.
+pub fn config_path() -> Option<PathBuf> {
     dirs_next::config_dir().map(|d| d.join(APP_DIR).join(CONFIG_FILE))
 }
 
+/// Where `ficflow serve` keeps the token its clients authenticate with,
+/// next to the config file.
+pub fn api_token_path() -> Option<PathBuf> {
+    dirs_next::config_dir().map(|d| d.join(APP_DIR).join(API_TOKEN_FILE))
+}
+
 /// The platform default library path (`~/.local/share/ficflow/fanfictions.db`
 /// on Linux), used when neither `FICFLOW_DB_PATH` nor `library_path` is set.
-fn default_db_path() -> Result<PathBuf, FicflowError> {
+pub fn default_db_path() -> Result<PathBuf, FicflowError> {
     let mut path = dirs_next::data_local_dir()
         .ok_or_else(|| FicflowError::Other("Failed to determine user data directory".into()))?;
     path.push(APP_DIR);
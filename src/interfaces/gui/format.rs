@@ -1,5 +1,6 @@
-//! Display helpers shared by GUI views — wording is a UI choice
-//! (the CLI may render the same domain enums with terser labels).
+//! Display helpers shared by GUI views, and borrowed by the TUI —
+//! wording is a UI choice (the CLI may render the same domain enums
+//! with terser labels).
 
 use chrono::{Datelike, NaiveDate};
 
@@ -0,0 +1,208 @@
+//! Undo/redo for edits made in the GUI. Each recorded `Change` can put
+//! itself back in either direction, so undoing an entry and redoing it
+//! are the same walk over its changes, in opposite orders. Each walk
+//! runs in one transaction, so an entry is applied whole or not at all.
+
+use std::collections::VecDeque;
+
+use crate::application::{
+    add_to_shelf::add_to_shelf, delete_fic::delete_fic, move_shelf::move_shelf,
+    pin_shelf::pin_shelf, remove_from_shelf::remove_from_shelf, rename_shelf::rename_shelf,
+    restore_fic::restore_fic, set_user_fields::set_user_fields, unpin_shelf::unpin_shelf,
+};
+use crate::domain::fanfiction::UserFields;
+use crate::error::FicflowError;
+use crate::infrastructure::SqliteRepository;
+
+/// Oldest entries fall off the bottom of the undo stack past this.
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    FicFields {
+        fic_id: u64,
+        before: UserFields,
+        after: UserFields,
+    },
+    /// `added` is the direction of the original edit.
+    ShelfMembership {
+        fic_id: u64,
+        shelf_id: u64,
+        added: bool,
+    },
+    ShelfName {
+        shelf_id: u64,
+        before: String,
+        after: String,
+    },
+    ShelfParent {
+        shelf_id: u64,
+        before: Option<u64>,
+        after: Option<u64>,
+    },
+    ShelfPinned {
+        shelf_id: u64,
+        pinned: bool,
+    },
+    /// A soft delete; undoing it restores the fic from the trash.
+    FicDeleted {
+        fic_id: u64,
+    },
+}
+
+impl Change {
+    /// `forward` re-does the original edit; otherwise it is reverted.
+    pub fn apply(&self, repo: &SqliteRepository<'_>, forward: bool) -> Result<(), FicflowError> {
+        match self {
+            Change::FicFields {
+                fic_id,
+                before,
+                after,
+            } => {
+                let fields = if forward { after } else { before };
+                set_user_fields(repo, *fic_id, fields).map(|_| ())
+            }
+            Change::ShelfMembership {
+                fic_id,
+                shelf_id,
+                added,
+            } => {
+                if *added == forward {
+                    add_to_shelf(repo, *fic_id, *shelf_id)
+                } else {
+                    remove_from_shelf(repo, *fic_id, *shelf_id)
+                }
+            }
+            Change::ShelfName {
+                shelf_id,
+                before,
+                after,
+            } => {
+                let name = if forward { after } else { before };
+                rename_shelf(repo, *shelf_id, name).map(|_| ())
+            }
+            Change::ShelfParent {
+                shelf_id,
+                before,
+                after,
+            } => {
+                let parent = if forward { *after } else { *before };
+                move_shelf(repo, *shelf_id, parent).map(|_| ())
+            }
+            Change::ShelfPinned { shelf_id, pinned } => {
+                if *pinned == forward {
+                    pin_shelf(repo, *shelf_id).map(|_| ())
+                } else {
+                    unpin_shelf(repo, *shelf_id).map(|_| ())
+                }
+            }
+            Change::FicDeleted { fic_id } => {
+                if forward {
+                    delete_fic(repo, *fic_id)
+                } else {
+                    restore_fic(repo, *fic_id).map(|_| ())
+                }
+            }
+        }
+    }
+}
+
+/// One user action — a bulk edit records all of its changes as a single
+/// entry so one undo reverts the lot.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub label: String,
+    pub changes: Vec<Change>,
+}
+
+impl Entry {
+    pub fn undo(&self, repo: &SqliteRepository<'_>) -> Result<(), FicflowError> {
+        repo.in_transaction(|repo| {
+            self.changes
+                .iter()
+                .rev()
+                .try_for_each(|change| change.apply(repo, false))
+        })
+    }
+
+    pub fn redo(&self, repo: &SqliteRepository<'_>) -> Result<(), FicflowError> {
+        repo.in_transaction(|repo| {
+            self.changes
+                .iter()
+                .try_for_each(|change| change.apply(repo, true))
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl History {
+    /// A fresh edit invalidates anything that was undone before it.
+    /// Empty change lists (an edit that turned out to be a no-op) are
+    /// dropped.
+    pub fn record(&mut self, label: impl Into<String>, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.push_undo(Entry {
+            label: label.into(),
+            changes,
+        });
+    }
+
+    pub fn take_undo(&mut self) -> Option<Entry> {
+        self.undo.pop_back()
+    }
+
+    pub fn take_redo(&mut self) -> Option<Entry> {
+        self.redo.pop()
+    }
+
+    pub fn push_undone(&mut self, entry: Entry) {
+        self.redo.push(entry);
+    }
+
+    pub fn push_redone(&mut self, entry: Entry) {
+        self.push_undo(entry);
+    }
+
+    /// Puts back an entry whose undo failed, which left the library as
+    /// it was, so it can be tried again.
+    pub fn return_undo(&mut self, entry: Entry) {
+        self.undo.push_back(entry);
+    }
+
+    /// `return_undo` for a failed redo.
+    pub fn return_redo(&mut self, entry: Entry) {
+        self.redo.push(entry);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    fn push_undo(&mut self, entry: Entry) {
+        self.undo.push_back(entry);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+    }
+}
@@ -1,75 +1,354 @@
 //! In-memory caches the GUI's panels read from + the `mutate()`
-//! funnel that keeps them coherent after fic-shelf-changing ops.
+//! funnel that keeps them coherent after fic-shelf-changing ops. Fics
+//! themselves are never all held at once: the table reads its rows a
+//! page at a time and the details panel reads the selection by id.
 
 use std::collections::{HashMap, HashSet};
 
 use rusqlite::Connection;
 
 use crate::application::{
-    count_fics_per_shelf::count_fics_per_shelf, list_fics::list_fics,
-    list_shelf_fics::list_shelf_fics, list_shelves::list_shelves,
-    list_shelves_for_fic::list_shelves_for_fic,
+    count_fics_per_shelf::count_fics_per_shelf, get_fic::get_fanfiction,
+    list_saved_searches::list_saved_searches, list_shelf_fics::list_shelf_fics,
+    list_shelves::list_shelves, list_shelves_for_fic::list_shelves_for_fic,
+    list_trashed_fics::list_trashed_fics, list_trashed_shelves::list_trashed_shelves,
+    query_fic_ids::query_fic_ids, query_fics::query_fics, search_fics::search_fics,
 };
-use crate::domain::fanfiction::Fanfiction;
+use crate::domain::fanfiction::{Fanfiction, FicFilter, FicQuery, ReadingStatus, SortPref};
+use crate::domain::saved_search::{SavedSearch, SearchScope};
 use crate::domain::shelf::{Shelf, ShelfKind};
+use crate::domain::trash::{TrashedFic, TrashedShelf};
 use crate::error::FicflowError;
 use crate::infrastructure::SqliteRepository;
 
 use super::auto_shelf;
+use super::search_results::SearchResults;
 use super::selection::Selection;
 use super::view::View;
+use super::views::LibraryCounts;
+use super::views::library_view::TableRows;
+
+/// How many of the library table's rows are read from the repository at
+/// a time.
+const ROW_PAGE: u32 = 200;
 
 pub struct LibraryCache {
-    pub fics: Vec<Fanfiction>,
     pub shelves: Vec<Shelf>,
-    /// Fic ids in the active `View::Shelf(_)`; empty outside shelf views.
+    pub saved_searches: Vec<SavedSearch>,
+    /// Fic ids in the active `View::Shelf(_)` (or the shelf a saved search
+    /// is scoped to); empty outside shelf views.
     pub shelf_members: HashSet<u64>,
     /// Shelf ids the `Selection::Single(_)` fic belongs to; empty otherwise.
     pub selection_shelf_ids: HashSet<u64>,
+    /// The selected fics, read by id. Memoised on `selected_key` by
+    /// `sync_selected_fics`; a fic that's gone from the library is
+    /// missing here.
+    pub selected_fics: Vec<Fanfiction>,
+    selected_key: Option<(Selection, u64)>,
+    /// Sidebar counts for the whole library and each status.
+    pub library_counts: LibraryCounts,
     /// Sidebar count per shelf; missing keys default to 0.
     pub shelf_counts: HashMap<u64, usize>,
     /// Live-computed membership for every auto-shelf, keyed by shelf id.
-    /// Never backed by `fic_shelf` rows — recomputed from `fics`/`shelves`
-    /// on every mutation, same as `shelf_counts`.
+    /// Never backed by `fic_shelf` rows — re-read from the tag index on
+    /// every mutation, same as `shelf_counts`.
     pub auto_shelf_members: HashMap<u64, HashSet<u64>>,
+    /// Sidebar count per saved search; missing keys default to 0.
+    pub saved_search_counts: HashMap<u64, usize>,
+    /// Hits for the search box. FTS results come from the DB, so every
+    /// method that follows a change to the library re-runs it.
+    pub search: SearchResults,
+    /// What the Trash view lists. Reloaded whenever a fic or shelf is
+    /// deleted, restored or purged.
+    pub trashed_fics: Vec<TrashedFic>,
+    pub trashed_shelves: Vec<TrashedShelf>,
+    /// The library table's rows as fic ids, in display order. Memoised
+    /// on `rows_key` by `sync_rows`.
+    pub rows: Vec<u64>,
+    /// Where the table reads the fics behind `rows` from.
+    row_source: RowSource,
+    rows_key: Option<RowsKey>,
+    /// Bumped by every change that can move a fic in or out of a view or
+    /// reorder it, so `rows_key` goes stale without tracking what changed.
+    generation: u64,
+}
+
+/// Where the table's pages of fics come from as it scrolls. Loaded pages
+/// are keyed by page number and dropped whenever `rows` is recomputed.
+enum RowSource {
+    /// Rows in the order their `FicQuery` sorts them, read back with it.
+    Paged {
+        query: FicQuery,
+        pages: HashMap<usize, Vec<Fanfiction>>,
+    },
+    /// Rows in an order no `FicQuery` can express, read by id.
+    ById {
+        pages: HashMap<usize, Vec<Fanfiction>>,
+    },
+}
+
+#[derive(PartialEq)]
+struct RowsKey {
+    view: View,
+    sort: SortPref,
+    ranked: bool,
+    query: String,
+    generation: u64,
 }
 
 impl LibraryCache {
     pub fn load(connection: &Connection) -> Self {
-        let fics = load_fics_inner(connection);
-        let shelves = load_shelves_inner(connection);
-        let auto_shelf_members = compute_auto_shelf_members(&fics, &shelves);
-        let mut shelf_counts = count_fics_per_shelf_inner(connection);
-        overlay_auto_shelf_counts(&mut shelf_counts, &auto_shelf_members);
-        Self {
-            fics,
-            shelves,
+        let mut cache = Self {
+            shelves: load_shelves_inner(connection),
+            saved_searches: load_saved_searches_inner(connection),
             shelf_members: HashSet::new(),
             selection_shelf_ids: HashSet::new(),
-            shelf_counts,
-            auto_shelf_members,
-        }
+            selected_fics: Vec::new(),
+            selected_key: None,
+            library_counts: LibraryCounts::default(),
+            shelf_counts: count_fics_per_shelf_inner(connection),
+            auto_shelf_members: HashMap::new(),
+            saved_search_counts: HashMap::new(),
+            search: SearchResults::default(),
+            trashed_fics: Vec::new(),
+            trashed_shelves: Vec::new(),
+            rows: Vec::new(),
+            row_source: RowSource::ById {
+                pages: HashMap::new(),
+            },
+            rows_key: None,
+            generation: 0,
+        };
+        cache.refresh_auto_shelf_members(connection);
+        cache.reload_trash(connection);
+        cache
     }
 
+    /// Follows any change to the library's fics: re-runs the search box
+    /// query and recounts.
     pub fn reload_fics(&mut self, connection: &Connection) {
-        self.fics = load_fics_inner(connection);
-        self.refresh_auto_shelf_members();
+        let query = self.search.query().to_string();
+        self.search = run_search_inner(connection, &query);
+        self.refresh_auto_shelf_members(connection);
     }
 
     pub fn reload_shelves(&mut self, connection: &Connection) {
         self.shelves = load_shelves_inner(connection);
-        self.refresh_auto_shelf_members();
+        self.refresh_auto_shelf_members(connection);
     }
 
-    /// Pure — recomputes `auto_shelf_members` (and the counts derived
-    /// from it) from `self.fics`/`self.shelves`. Called after every
-    /// mutation of either, never per-frame. Overlaying counts here too
-    /// (not only in `refresh_shelf_counts`) matters because call sites
-    /// like `replace_fic` (e.g. a status change) update membership
+    /// Failures log and leave the trash looking empty.
+    pub fn reload_trash(&mut self, connection: &Connection) {
+        let repo = SqliteRepository::new(connection);
+        self.trashed_fics = list_trashed_fics(&repo).unwrap_or_else(|err| {
+            log::error!("Failed to load trashed fanfictions: {}", err);
+            Vec::new()
+        });
+        self.trashed_shelves = list_trashed_shelves(&repo).unwrap_or_else(|err| {
+            log::error!("Failed to load trashed shelves: {}", err);
+            Vec::new()
+        });
+    }
+
+    pub fn reload_saved_searches(&mut self, connection: &Connection) {
+        self.saved_searches = load_saved_searches_inner(connection);
+        self.refresh_saved_search_counts(connection);
+    }
+
+    /// Runs `query` against the index if it differs from the current
+    /// search box query. Returns whether it did.
+    pub fn set_search_query(&mut self, connection: &Connection, query: &str) -> bool {
+        if query == self.search.query() {
+            return false;
+        }
+        self.search = run_search_inner(connection, query);
+        self.generation += 1;
+        true
+    }
+
+    /// Re-reads `auto_shelf_members` from the tag index, and every count
+    /// derived from the library. Called after every mutation, never
+    /// per-frame. Overlaying counts here too (not only in
+    /// `refresh_shelf_counts`) matters because call sites like
+    /// `reload_fics` (e.g. after a status change) update membership
     /// without going through `refresh_shelf_counts`.
-    pub fn refresh_auto_shelf_members(&mut self) {
-        self.auto_shelf_members = compute_auto_shelf_members(&self.fics, &self.shelves);
+    pub fn refresh_auto_shelf_members(&mut self, connection: &Connection) {
+        self.auto_shelf_members = compute_auto_shelf_members(connection, &self.shelves);
         overlay_auto_shelf_counts(&mut self.shelf_counts, &self.auto_shelf_members);
+        self.library_counts = count_library_inner(connection);
+        self.refresh_saved_search_counts(connection);
+        self.generation += 1;
+    }
+
+    /// Recomputes `rows` when the view, sort, query or library changed
+    /// since the last call; a no-op otherwise, so it's safe per frame.
+    pub fn sync_rows(
+        &mut self,
+        connection: &Connection,
+        view: &View,
+        sort: SortPref,
+        ranked: bool,
+    ) {
+        let key = RowsKey {
+            view: view.clone(),
+            sort,
+            ranked,
+            query: self.search.query().to_string(),
+            generation: self.generation,
+        };
+        if self.rows_key.as_ref() == Some(&key) {
+            return;
+        }
+        (self.rows, self.row_source) = self.row_ids_and_source(connection, view, sort, ranked);
+        self.rows_key = Some(key);
+    }
+
+    /// The rows `sync_rows` would store, without memoising.
+    pub fn compute_rows(
+        &self,
+        connection: &Connection,
+        view: &View,
+        sort: SortPref,
+        ranked: bool,
+    ) -> Vec<u64> {
+        self.row_ids_and_source(connection, view, sort, ranked).0
+    }
+
+    /// The table's rows, and the search results it marks matches with,
+    /// borrowed together.
+    pub fn table_rows<'a>(
+        &'a mut self,
+        connection: &'a Connection,
+    ) -> (CachedRows<'a>, &'a SearchResults) {
+        let search = &self.search;
+        let rows = CachedRows {
+            ids: &self.rows,
+            source: &mut self.row_source,
+            connection,
+        };
+        (rows, search)
+    }
+
+    /// Rows are filtered and sorted in SQL and paged back with the same
+    /// query. Auto-shelves are then narrowed to their members and ranked
+    /// rows reordered by search relevance — neither expressible as a
+    /// `FicQuery` — so those pages are read by id instead. A failed
+    /// query logs and leaves the table empty.
+    fn row_ids_and_source(
+        &self,
+        connection: &Connection,
+        view: &View,
+        sort: SortPref,
+        ranked: bool,
+    ) -> (Vec<u64>, RowSource) {
+        let pages = HashMap::new();
+        let Some((mut filter, members_only)) = self.fic_filter(view) else {
+            return (Vec::new(), RowSource::ById { pages });
+        };
+        if self.search.is_active() {
+            filter.text = Some(self.search.query().to_string());
+        }
+        let query = FicQuery {
+            filter,
+            sort: vec![sort],
+            ..FicQuery::default()
+        };
+        let repo = SqliteRepository::new(connection);
+        let mut ids = query_fic_ids(&repo, &query).unwrap_or_else(|err| {
+            log::error!("Failed to query the library: {}", err);
+            Vec::new()
+        });
+        let ranked = ranked && self.search.is_active();
+        if !members_only && !ranked {
+            return (ids, RowSource::Paged { query, pages });
+        }
+        if members_only {
+            ids.retain(|id| self.shelf_members.contains(id));
+        }
+        if ranked {
+            ids.sort_by_key(|&id| self.search.rank(id));
+        }
+        (ids, RowSource::ById { pages })
+    }
+
+    /// The filter `view` reads its fics with, and whether they still need
+    /// narrowing to `shelf_members` — true for auto-shelves, whose members
+    /// only exist in `auto_shelf_members`. `None` for views without a
+    /// library table.
+    fn fic_filter(&self, view: &View) -> Option<(FicFilter, bool)> {
+        let filter = match view {
+            View::AllFics => FicFilter::default(),
+            View::ByStatus(status) => FicFilter {
+                status: Some(*status),
+                ..FicFilter::default()
+            },
+            View::Shelf(id) => match self.shelves.iter().find(|s| s.id == *id)?.kind {
+                ShelfKind::Normal => FicFilter {
+                    shelf_id: Some(*id),
+                    ..FicFilter::default()
+                },
+                ShelfKind::Auto(_) => return Some((FicFilter::default(), true)),
+            },
+            View::SavedSearch(_) | View::Discover | View::Trash | View::Tasks | View::Settings => {
+                return None;
+            }
+        };
+        Some((filter, false))
+    }
+
+    /// Every fic `view` shows before the search box narrows it. Failures
+    /// log and leave the set empty.
+    pub fn view_fic_ids(&self, connection: &Connection, view: &View) -> HashSet<u64> {
+        let Some((filter, members_only)) = self.fic_filter(view) else {
+            return HashSet::new();
+        };
+        let repo = SqliteRepository::new(connection);
+        let query = FicQuery {
+            filter,
+            ..FicQuery::default()
+        };
+        match query_fic_ids(&repo, &query) {
+            Ok(ids) => ids
+                .into_iter()
+                .filter(|id| !members_only || self.shelf_members.contains(id))
+                .collect(),
+            Err(err) => {
+                log::error!("Failed to query the library: {}", err);
+                HashSet::new()
+            }
+        }
+    }
+
+    /// Counts each saved search's matches in SQL. Searches scoped to an
+    /// auto-shelf count its members, narrowed to the query's hits.
+    /// Failures leave the search out of the map (it counts 0), same as
+    /// `refresh_shelf_counts`.
+    fn refresh_saved_search_counts(&mut self, connection: &Connection) {
+        let repo = SqliteRepository::new(connection);
+        self.saved_search_counts = self
+            .saved_searches
+            .iter()
+            .filter_map(|search| {
+                let text = Some(search.query.clone()).filter(|q| !q.trim().is_empty());
+                let (filter, members_only) = self.fic_filter(&View::from(search.scope))?;
+                let filter = FicFilter { text, ..filter };
+                let count = if members_only {
+                    let SearchScope::Shelf(id) = search.scope else {
+                        return None;
+                    };
+                    let members = self.auto_shelf_members.get(&id)?;
+                    let query = FicQuery {
+                        filter,
+                        ..FicQuery::default()
+                    };
+                    let ids = query_fic_ids(&repo, &query).ok()?;
+                    ids.iter().filter(|id| members.contains(id)).count()
+                } else {
+                    count_fics_inner(&repo, filter).ok()?
+                };
+                Some((search.id, count))
+            })
+            .collect();
     }
 
     pub fn refresh_shelf_members(
@@ -77,6 +356,7 @@ impl LibraryCache {
         connection: &Connection,
         view: &View,
     ) -> Result<(), FicflowError> {
+        self.generation += 1;
         self.shelf_members.clear();
         if let View::Shelf(id) = view {
             match self.shelves.iter().find(|s| s.id == *id).map(|s| &s.kind) {
@@ -96,6 +376,46 @@ impl LibraryCache {
         Ok(())
     }
 
+    /// Re-reads `selected_fics` when the selection or the library changed
+    /// since the last call; a no-op otherwise, so it's safe per frame.
+    pub fn sync_selected_fics(&mut self, connection: &Connection, selection: &Selection) {
+        let key = (selection.clone(), self.generation);
+        if self.selected_key.as_ref() == Some(&key) {
+            return;
+        }
+        let repo = SqliteRepository::new(connection);
+        self.selected_fics = selection
+            .ids()
+            .iter()
+            .filter_map(|&id| match get_fanfiction(&repo, id) {
+                Ok(fic) => Some(fic),
+                Err(FicflowError::NotFound { .. }) => None,
+                Err(err) => {
+                    log::error!("Failed to load fanfiction {}: {}", id, err);
+                    None
+                }
+            })
+            .collect();
+        self.selected_key = Some(key);
+    }
+
+    /// A live fic by id, from `selected_fics` when it's there. Failures
+    /// log and read as missing.
+    pub fn fic(&self, connection: &Connection, id: u64) -> Option<Fanfiction> {
+        if let Some(fic) = self.selected_fics.iter().find(|f| f.id == id) {
+            return Some(fic.clone());
+        }
+        let repo = SqliteRepository::new(connection);
+        match get_fanfiction(&repo, id) {
+            Ok(fic) => Some(fic),
+            Err(FicflowError::NotFound { .. }) => None,
+            Err(err) => {
+                log::error!("Failed to load fanfiction {}: {}", id, err);
+                None
+            }
+        }
+    }
+
     pub fn refresh_selection_shelf_ids(
         &mut self,
         connection: &Connection,
@@ -117,31 +437,8 @@ impl LibraryCache {
     pub fn refresh_shelf_counts(&mut self, connection: &Connection) {
         self.shelf_counts = count_fics_per_shelf_inner(connection);
         overlay_auto_shelf_counts(&mut self.shelf_counts, &self.auto_shelf_members);
-    }
-
-    pub fn replace_fic(&mut self, updated: Fanfiction) {
-        if let Some(slot) = self.fics.iter_mut().find(|f| f.id == updated.id) {
-            *slot = updated;
-        }
-        self.refresh_auto_shelf_members();
-    }
-
-    /// Batch form of `replace_fic` — updates every entry first, then
-    /// recomputes auto-shelf membership once instead of once per fic
-    /// (bulk operations like `bulk_set_status` can touch dozens of fics
-    /// at a time, and each recompute is a full shelves × fics scan).
-    pub fn replace_fics(&mut self, updated: Vec<Fanfiction>) {
-        for updated in updated {
-            if let Some(slot) = self.fics.iter_mut().find(|f| f.id == updated.id) {
-                *slot = updated;
-            }
-        }
-        self.refresh_auto_shelf_members();
-    }
-
-    pub fn remove_fics(&mut self, ids: &[u64]) {
-        self.fics.retain(|f| !ids.contains(&f.id));
-        self.refresh_auto_shelf_members();
+        self.refresh_saved_search_counts(connection);
+        self.generation += 1;
     }
 
     /// Returns `(op_result, refresh_errors)` — refresh failures are
@@ -173,14 +470,90 @@ impl LibraryCache {
     }
 }
 
+/// `LibraryCache`'s rows as the library table reads them.
+pub struct CachedRows<'a> {
+    ids: &'a [u64],
+    source: &'a mut RowSource,
+    connection: &'a Connection,
+}
+
+impl TableRows for CachedRows<'_> {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn ids(&self) -> &[u64] {
+        self.ids
+    }
+
+    fn fic(&mut self, index: usize) -> Option<&Fanfiction> {
+        let page = index / ROW_PAGE as usize;
+        match self.source {
+            RowSource::Paged { query, pages } => pages
+                .entry(page)
+                .or_insert_with(|| load_row_page(self.connection, query, page))
+                .get(index % ROW_PAGE as usize),
+            RowSource::ById { pages } => {
+                let id = *self.ids.get(index)?;
+                pages
+                    .entry(page)
+                    .or_insert_with(|| load_row_page_by_id(self.connection, self.ids, page))
+                    .iter()
+                    .find(|f| f.id == id)
+            }
+        }
+    }
+}
+
+/// Failures log and leave the page's rows blank.
+fn load_row_page(connection: &Connection, query: &FicQuery, page: usize) -> Vec<Fanfiction> {
+    let repo = SqliteRepository::new(connection);
+    let page_query = FicQuery {
+        limit: Some(ROW_PAGE),
+        offset: page as u32 * ROW_PAGE,
+        ..query.clone()
+    };
+    match query_fics(&repo, &page_query) {
+        Ok(page) => page.fics,
+        Err(err) => {
+            log::error!("Failed to load the library's rows: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// Failures log and leave the fics missing from the page, so their
+/// rows stay blank.
+fn load_row_page_by_id(connection: &Connection, ids: &[u64], page: usize) -> Vec<Fanfiction> {
+    let repo = SqliteRepository::new(connection);
+    ids.iter()
+        .skip(page * ROW_PAGE as usize)
+        .take(ROW_PAGE as usize)
+        .filter_map(|id| match get_fanfiction(&repo, *id) {
+            Ok(fic) => Some(fic),
+            Err(err) => {
+                log::error!("Failed to load the library's rows: {}", err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Failures log and leave the shelf out of the map (it counts 0).
 fn compute_auto_shelf_members(
-    fics: &[Fanfiction],
+    connection: &Connection,
     shelves: &[Shelf],
 ) -> HashMap<u64, HashSet<u64>> {
     shelves
         .iter()
         .filter_map(|s| match &s.kind {
-            ShelfKind::Auto(criteria) => Some((s.id, auto_shelf::matching_fic_ids(fics, criteria))),
+            ShelfKind::Auto(criteria) => match auto_shelf::matching_fic_ids(connection, criteria) {
+                Ok(members) => Some((s.id, members)),
+                Err(err) => {
+                    log::error!("Failed to match auto-shelf {}: {}", s.name, err);
+                    None
+                }
+            },
             ShelfKind::Normal => None,
         })
         .collect()
@@ -195,14 +568,36 @@ fn overlay_auto_shelf_counts(
     }
 }
 
-fn load_fics_inner(connection: &Connection) -> Vec<Fanfiction> {
+/// How many live fics `filter` matches, without reading any of them.
+fn count_fics_inner(repo: &SqliteRepository<'_>, filter: FicFilter) -> Result<usize, FicflowError> {
+    let query = FicQuery {
+        filter,
+        limit: Some(0),
+        ..FicQuery::default()
+    };
+    Ok(query_fics(repo, &query)?.total)
+}
+
+/// Failures log and count 0, like `count_fics_per_shelf_inner`.
+fn count_library_inner(connection: &Connection) -> LibraryCounts {
     let repo = SqliteRepository::new(connection);
-    match list_fics(&repo) {
-        Ok(fics) => fics,
-        Err(err) => {
-            log::error!("Failed to load fanfictions: {}", err);
-            Vec::new()
-        }
+    let count = |status: Option<ReadingStatus>| {
+        let filter = FicFilter {
+            status,
+            ..FicFilter::default()
+        };
+        count_fics_inner(&repo, filter).unwrap_or_else(|err| {
+            log::error!("Failed to count the library: {}", err);
+            0
+        })
+    };
+    LibraryCounts {
+        all: count(None),
+        in_progress: count(Some(ReadingStatus::InProgress)),
+        read: count(Some(ReadingStatus::Read)),
+        plan_to_read: count(Some(ReadingStatus::PlanToRead)),
+        paused: count(Some(ReadingStatus::Paused)),
+        abandoned: count(Some(ReadingStatus::Abandoned)),
     }
 }
 
@@ -217,6 +612,33 @@ fn load_shelves_inner(connection: &Connection) -> Vec<Shelf> {
     }
 }
 
+fn load_saved_searches_inner(connection: &Connection) -> Vec<SavedSearch> {
+    let repo = SqliteRepository::new(connection);
+    match list_saved_searches(&repo) {
+        Ok(searches) => searches,
+        Err(err) => {
+            log::error!("Failed to load saved searches: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// Failures log and fall back to no hits, so the table shows "no
+/// matches" rather than the unfiltered library.
+fn run_search_inner(connection: &Connection, query: &str) -> SearchResults {
+    if query.trim().is_empty() {
+        return SearchResults::new(query, Vec::new());
+    }
+    let repo = SqliteRepository::new(connection);
+    match search_fics(&repo, query) {
+        Ok(hits) => SearchResults::new(query, hits),
+        Err(err) => {
+            log::error!("Failed to search the library: {}", err);
+            SearchResults::new(query, Vec::new())
+        }
+    }
+}
+
 fn count_fics_per_shelf_inner(connection: &Connection) -> HashMap<u64, usize> {
     let repo = SqliteRepository::new(connection);
     count_fics_per_shelf(&repo).unwrap_or_default()
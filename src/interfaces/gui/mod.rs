@@ -3,11 +3,13 @@ mod assets;
 mod auto_shelf;
 mod chrome;
 mod config;
-mod format;
+pub(crate) mod format;
+mod history;
 mod library_cache;
+mod search_results;
 mod selection;
 mod selection_controller;
-mod tasks;
+pub(crate) mod tasks;
 mod theme;
 mod view;
 mod views;
@@ -15,13 +17,65 @@ mod widgets;
 
 use std::process::ExitCode;
 
+use crate::interfaces::instance::{self, Claim, Instance, Message, link};
+
 pub use app::{FicflowApp, FicflowConfig, InitError};
-pub use config::{AppConfig, ColumnKey, SortDirection, SortPref, ThemeChoice};
+pub use config::{
+    AppConfig, ColumnKey, Setting, SortDirection, SortPref, ThemeChoice, api_token_path,
+    config_path, default_db_path,
+};
 pub use selection::Selection;
-pub use tasks::{TaskKind, TaskState, TaskStatus};
+pub use tasks::{AddOptions, TaskKind, TaskState, TaskStatus};
 pub use view::View;
+pub use views::modals::export_modal::ExportRequest;
 
 pub fn run_gui() -> ExitCode {
+    launch(None)
+}
+
+/// Opens a `ficflow://` link: in the window already open on the library
+/// when there is one, else in a new window that acts on it once loaded.
+pub fn open_link(link: &str) -> ExitCode {
+    match link::parse_link(link) {
+        Ok(message) => launch(Some(message)),
+        Err(err) => {
+            log::error!("{}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+/// Starts the GUI, unless a window already has the library: then that
+/// window gets `message`, or is brought to the front, and this process
+/// exits.
+fn launch(message: Option<Message>) -> ExitCode {
+    let socket = AppConfig::load()
+        .resolved_db_path()
+        .map(|db_path| instance::socket_path(&db_path));
+    let instance = match socket.and_then(|socket| Ok((Instance::claim(&socket)?, socket))) {
+        Ok((Claim::First(instance), _)) => Some(instance),
+        Ok((Claim::Running, socket)) => {
+            let sent = message.clone().unwrap_or(Message::Focus);
+            match instance::send(&socket, &sent) {
+                Ok(true) => return ExitCode::SUCCESS,
+                // It closed since the claim, so this window goes ahead.
+                Ok(false) => None,
+                Err(err) => {
+                    log::warn!("The open Ficflow window didn't answer: {}", err);
+                    None
+                }
+            }
+        }
+        // Also when the library can't be resolved, which the app then
+        // reports itself.
+        Err(err) => {
+            log::warn!("Couldn't check for an open Ficflow window: {}", err);
+            None
+        }
+    };
+    if let (Some(instance), Some(message)) = (&instance, message) {
+        instance.push(message);
+    }
     // Borderless + transparent so the Art Nouveau chrome paints in
     // place of the OS title bar (`FicflowApp::clear_color` returns
     // `[0;4]` so the alpha channel reaches the compositor).
@@ -48,10 +102,13 @@ pub fn run_gui() -> ExitCode {
         "Ficflow",
         native_options,
         Box::new(|cc| {
-            let app = FicflowApp::new(cc).map_err(|e| {
+            let mut app = FicflowApp::new(cc).map_err(|e| {
                 log::error!("Failed to initialise GUI: {}", e);
                 Box::new(e) as Box<dyn std::error::Error + Send + Sync>
             })?;
+            if let Some(instance) = instance {
+                app.listen(&cc.egui_ctx, instance);
+            }
             Ok(Box::new(app))
         }),
     );
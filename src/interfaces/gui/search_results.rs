@@ -0,0 +1,57 @@
+//! The active search box query's full-text hits, in the shape the
+//! library table reads: membership, relevance rank and snippet per fic.
+
+use std::collections::HashMap;
+
+use crate::domain::fanfiction::{SearchHit, Snippet};
+
+#[derive(Default)]
+pub struct SearchResults {
+    query: String,
+    /// Relevance rank per matching fic, 0 = best. `None` while the query
+    /// is blank, which matches every fic.
+    ranks: Option<HashMap<u64, usize>>,
+    snippets: HashMap<u64, Snippet>,
+}
+
+impl SearchResults {
+    pub fn new(query: &str, hits: Vec<SearchHit>) -> Self {
+        if query.trim().is_empty() {
+            return Self {
+                query: query.to_string(),
+                ..Self::default()
+            };
+        }
+        let mut ranks = HashMap::with_capacity(hits.len());
+        let mut snippets = HashMap::with_capacity(hits.len());
+        for (rank, hit) in hits.into_iter().enumerate() {
+            ranks.insert(hit.fic.id, rank);
+            snippets.insert(hit.fic.id, hit.snippet);
+        }
+        Self {
+            query: query.to_string(),
+            ranks: Some(ranks),
+            snippets,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ranks.is_some()
+    }
+
+    /// `usize::MAX` for non-matches, so they sort last.
+    pub fn rank(&self, fic_id: u64) -> usize {
+        self.ranks
+            .as_ref()
+            .and_then(|r| r.get(&fic_id).copied())
+            .unwrap_or(usize::MAX)
+    }
+
+    pub fn snippet(&self, fic_id: u64) -> Option<&Snippet> {
+        self.snippets.get(&fic_id)
+    }
+}
@@ -14,6 +14,14 @@ impl Selection {
             Selection::Multi(ids) => ids.contains(&id),
         }
     }
+
+    pub fn ids(&self) -> &[u64] {
+        match self {
+            Selection::None => &[],
+            Selection::Single(id) => std::slice::from_ref(id),
+            Selection::Multi(ids) => ids,
+        }
+    }
 }
 
 impl From<Vec<u64>> for Selection {
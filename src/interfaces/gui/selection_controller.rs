@@ -2,8 +2,6 @@
 
 use std::collections::HashSet;
 
-use crate::domain::fanfiction::Fanfiction;
-
 use super::selection::Selection;
 use super::view::View;
 
@@ -29,11 +27,7 @@ impl SelectionController {
     }
 
     pub fn ids_vec(&self) -> Vec<u64> {
-        match &self.selection {
-            Selection::None => Vec::new(),
-            Selection::Single(id) => vec![*id],
-            Selection::Multi(ids) => ids.clone(),
-        }
+        self.selection.ids().to_vec()
     }
 
     pub fn select_single(&mut self, id: u64) {
@@ -53,27 +47,21 @@ impl SelectionController {
 
     /// `mods` from the click itself so ctrl/shift work right across
     /// platforms (`Modifiers::command` is Cmd on macOS, Ctrl elsewhere).
-    pub fn handle_row_click(
-        &mut self,
-        visible: &[&Fanfiction],
-        clicked_idx: usize,
-        mods: egui::Modifiers,
-    ) {
-        let clicked_id = visible[clicked_idx].id;
+    pub fn handle_row_click(&mut self, visible: &[u64], clicked_idx: usize, mods: egui::Modifiers) {
+        let clicked_id = visible[clicked_idx];
 
         if mods.shift {
             let anchor_id = self.last_clicked_id.unwrap_or(clicked_id);
             let anchor_idx = visible
                 .iter()
-                .position(|f| f.id == anchor_id)
+                .position(|&id| id == anchor_id)
                 .unwrap_or(clicked_idx);
             let (start, end) = if anchor_idx <= clicked_idx {
                 (anchor_idx, clicked_idx)
             } else {
                 (clicked_idx, anchor_idx)
             };
-            let ids: Vec<u64> = visible[start..=end].iter().map(|f| f.id).collect();
-            self.selection = ids.into();
+            self.selection = visible[start..=end].to_vec().into();
             // Don't move the anchor — keeps successive shift-clicks consistent.
         } else if mods.command {
             let mut current = self.ids_vec();
@@ -90,37 +78,21 @@ impl SelectionController {
         }
     }
 
-    /// Returns true when the selection actually changed.
-    pub fn prune_to_view(
-        &mut self,
-        fics: &[Fanfiction],
-        view: &View,
-        shelf_members: &HashSet<u64>,
-    ) -> bool {
+    /// Keeps the selected fics `in_view` holds. Returns true when the
+    /// selection actually changed.
+    pub fn prune_to_view(&mut self, view: &View, in_view: &HashSet<u64>) -> bool {
         let before = self.selection.clone();
 
         if !view.shows_library() {
             self.clear();
         } else {
-            let visible_ids: Vec<u64> = match &self.selection {
-                Selection::None => Vec::new(),
-                Selection::Single(id) => fics
-                    .iter()
-                    .find(|f| f.id == *id)
-                    .filter(|f| view.includes(f, shelf_members))
-                    .map(|f| f.id)
-                    .into_iter()
-                    .collect(),
-                Selection::Multi(ids) => ids
-                    .iter()
-                    .filter_map(|id| {
-                        fics.iter()
-                            .find(|f| f.id == *id)
-                            .filter(|f| view.includes(f, shelf_members))
-                            .map(|f| f.id)
-                    })
-                    .collect(),
-            };
+            let visible_ids: Vec<u64> = self
+                .selection
+                .ids()
+                .iter()
+                .copied()
+                .filter(|id| in_view.contains(id))
+                .collect();
             self.selection = visible_ids.into();
             if matches!(self.selection, Selection::None) {
                 self.last_clicked_id = None;
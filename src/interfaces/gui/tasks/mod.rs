@@ -14,6 +14,9 @@ use std::thread;
 
 use chrono::{DateTime, Utc};
 
+use crate::domain::discover::{Ao3Search, BlurbPage};
+use crate::error::FicflowError;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TaskKind {
     Add,
@@ -33,6 +36,8 @@ pub struct TaskState {
     pub kind: TaskKind,
     /// Original user input — kept so "Retry" can re-enqueue the same request.
     pub input: String,
+    /// What an Add does once the fic is saved; kept for "Retry" too.
+    pub options: AddOptions,
     /// What the user sees in the Tasks view: starts as the input, becomes the
     /// fic title once an add succeeds.
     pub display: String,
@@ -40,21 +45,54 @@ pub struct TaskState {
     pub started_at: DateTime<Utc>,
 }
 
+/// Follow-ups to an add, run once the fic is in the library.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AddOptions {
+    pub shelf_id: Option<u64>,
+    /// When the reader last opened the work, from browser history; the
+    /// status and last chapter read are guessed from it.
+    pub last_visited: Option<DateTime<Utc>>,
+}
+
 pub(super) enum WorkerCommand {
-    AddFic { task_id: u64, input: String },
-    RefreshFic { task_id: u64, fic_id: u64 },
+    AddFic {
+        task_id: u64,
+        input: String,
+        options: AddOptions,
+    },
+    RefreshFic {
+        task_id: u64,
+        fic_id: u64,
+    },
+    /// One page of a Discover search. Not a task: it isn't listed in the
+    /// Tasks view and there's nothing to retry.
+    SearchAo3 {
+        search_id: u64,
+        search: Ao3Search,
+        page: u32,
+        hide_tracked: bool,
+    },
 }
 
+/// A search's id, what it searched for, and what it found.
+pub(super) type SearchAnswer = (u64, Ao3Search, Result<BlurbPage, FicflowError>);
+
 pub(super) struct WorkerInbox {
     pub tasks: Mutex<Vec<TaskState>>,
     /// Titles of fics that were just successfully added. The GUI drains this
-    /// each frame to (a) reload its `fics` cache and (b) toast a confirmation
+    /// each frame to (a) reload its library cache and (b) toast a confirmation
     /// per added fic.
     pub recent_completions: Mutex<Vec<String>>,
     /// IDs of fics that were just successfully refreshed. The GUI drains
     /// this to reload the in-memory cache so the new metadata + bumped
     /// `last_checked_date` show in the details panel.
     pub recent_refreshes: Mutex<Vec<u64>>,
+    /// The newest search asked for. The worker skips older ones still
+    /// queued, and only this one's result is handed to the GUI.
+    pub latest_search: AtomicU64,
+    /// The newest search the worker has answered.
+    pub answered_search: AtomicU64,
+    pub search_result: Mutex<Option<SearchAnswer>>,
 }
 
 impl WorkerInbox {
@@ -63,6 +101,9 @@ impl WorkerInbox {
             tasks: Mutex::new(Vec::new()),
             recent_completions: Mutex::new(Vec::new()),
             recent_refreshes: Mutex::new(Vec::new()),
+            latest_search: AtomicU64::new(0),
+            answered_search: AtomicU64::new(0),
+            search_result: Mutex::new(None),
         }
     }
 }
@@ -96,12 +137,15 @@ impl TaskExecutor {
         }
     }
 
-    pub fn enqueue_add(&self, input: String) {
+    /// Enqueue an add from an id or URL, with `options` applied as soon
+    /// as the fic is saved. Returns the new task's id.
+    pub fn enqueue_add(&self, input: String, options: AddOptions) -> u64 {
         let task_id = self.next_id.fetch_add(1, Ordering::Relaxed);
         let task = TaskState {
             id: task_id,
             kind: TaskKind::Add,
             input: input.clone(),
+            options: options.clone(),
             display: input.clone(),
             status: TaskStatus::Running,
             started_at: Utc::now(),
@@ -115,7 +159,12 @@ impl TaskExecutor {
         // Ao3Fetcher::new errored, logged at thread start). Either way
         // there's nothing we can do here; the task stays Running and
         // the user sees a stuck spinner — acceptable trade-off.
-        let _ = self.sender.send(WorkerCommand::AddFic { task_id, input });
+        let _ = self.sender.send(WorkerCommand::AddFic {
+            task_id,
+            input,
+            options,
+        });
+        task_id
     }
 
     /// Enqueue a refresh of an existing fic. `display` carries the fic's
@@ -127,6 +176,7 @@ impl TaskExecutor {
             id: task_id,
             kind: TaskKind::Refresh,
             input: fic_id.to_string(),
+            options: AddOptions::default(),
             display,
             status: TaskStatus::Running,
             started_at: Utc::now(),
@@ -137,6 +187,34 @@ impl TaskExecutor {
             .send(WorkerCommand::RefreshFic { task_id, fic_id });
     }
 
+    /// Asks the worker for a page of `search`, superseding any search
+    /// still waiting. It queues behind adds and refreshes already sent,
+    /// which share AO3's rate limit.
+    pub fn enqueue_search(&self, search: Ao3Search, page: u32, hide_tracked: bool) {
+        let search_id = self.inbox.latest_search.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = self.sender.send(WorkerCommand::SearchAo3 {
+            search_id,
+            search,
+            page,
+            hide_tracked,
+        });
+    }
+
+    /// The latest search and the page it turned up, once, when it's in.
+    pub fn take_search_result(&self) -> Option<(Ao3Search, Result<BlurbPage, FicflowError>)> {
+        let latest = self.inbox.latest_search.load(Ordering::Relaxed);
+        let mut slot = self.inbox.search_result.lock().unwrap();
+        match slot.take() {
+            Some((search_id, search, result)) if search_id == latest => Some((search, result)),
+            _ => None,
+        }
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.inbox.answered_search.load(Ordering::Relaxed)
+            < self.inbox.latest_search.load(Ordering::Relaxed)
+    }
+
     pub fn snapshot(&self) -> Vec<TaskState> {
         self.inbox.tasks.lock().unwrap().clone()
     }
@@ -159,19 +237,21 @@ impl TaskExecutor {
             pos.and_then(|i| {
                 if matches!(tasks[i].status, TaskStatus::Failed(_)) {
                     let removed = tasks.remove(i);
-                    Some((removed.kind, removed.input, removed.display))
+                    Some(removed)
                 } else {
                     None
                 }
             })
         };
-        let Some((kind, input, display)) = snapshot else {
+        let Some(task) = snapshot else {
             return;
         };
-        match kind {
-            TaskKind::Add => self.enqueue_add(input),
-            TaskKind::Refresh => match input.parse::<u64>() {
-                Ok(fic_id) => self.enqueue_refresh(fic_id, display),
+        match task.kind {
+            TaskKind::Add => {
+                self.enqueue_add(task.input, task.options);
+            }
+            TaskKind::Refresh => match task.input.parse::<u64>() {
+                Ok(fic_id) => self.enqueue_refresh(fic_id, task.display),
                 Err(_) => log::warn!("retry: refused to retry refresh task with non-numeric id"),
             },
         }
@@ -191,21 +271,22 @@ impl TaskExecutor {
     }
 
     /// Drains the queue of titles for fics added since the last call.
-    /// Caller should reload its `fics` cache when this returns a non-empty
+    /// Caller should reload its library cache when this returns a non-empty
     /// vec, and may surface one toast per title.
     pub fn take_completions(&self) -> Vec<String> {
         mem::take(&mut *self.inbox.recent_completions.lock().unwrap())
     }
 
     /// Drains the queue of fic IDs that were successfully refreshed. The
-    /// GUI reloads its `fics` cache so the new metadata (and the bumped
+    /// GUI reloads its library cache so the new metadata (and the bumped
     /// `last_checked_date`) are visible immediately.
     pub fn take_refreshes(&self) -> Vec<u64> {
         mem::take(&mut *self.inbox.recent_refreshes.lock().unwrap())
     }
 
+    /// Tasks in flight, or a search waiting for its page.
     pub fn has_running(&self) -> bool {
-        self.running_count() > 0
+        self.running_count() > 0 || self.is_searching()
     }
 
     pub fn running_count(&self) -> usize {
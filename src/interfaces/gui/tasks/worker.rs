@@ -1,19 +1,23 @@
 use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc::Receiver;
 
 use crate::application::add_fic::add_fanfiction;
+use crate::application::add_to_shelf::add_to_shelf;
+use crate::application::apply_visit_hint::apply_visit_hint;
 use crate::application::check_updates::check_fic_updates;
+use crate::application::search_ao3::search_ao3;
 use crate::error::FicflowError;
 use crate::infrastructure::SqliteRepository;
 use crate::infrastructure::external::ao3::fetcher::Ao3Fetcher;
 use crate::infrastructure::persistence::database::connection::open_configured_db;
 use crate::interfaces::utils::url_parser::extract_ao3_id;
 
-use super::{TaskStatus, WorkerCommand, WorkerInbox};
+use super::{AddOptions, TaskStatus, WorkerCommand, WorkerInbox};
 
-pub fn run(
+pub(super) fn run(
     rx: Receiver<WorkerCommand>,
     inbox: Arc<WorkerInbox>,
     urls: Vec<String>,
@@ -40,15 +44,20 @@ pub fn run(
 
     while let Ok(cmd) = rx.recv() {
         match cmd {
-            WorkerCommand::AddFic { task_id, input } => {
+            WorkerCommand::AddFic {
+                task_id,
+                input,
+                options,
+            } => {
                 // Catch panics inside the AO3 scraper / DB save so a
                 // bug in HTML parsing (e.g. an unwrap in a malformed-
                 // page edge case) doesn't kill the worker thread —
                 // which would leave THIS task stuck on Running and
                 // every subsequent command silently dropped.
-                let outcome =
-                    catch_unwind(AssertUnwindSafe(|| process_add(&fetcher, &repo, &input)))
-                        .unwrap_or_else(|payload| Err(panic_to_error(payload)));
+                let outcome = catch_unwind(AssertUnwindSafe(|| {
+                    process_add(&fetcher, &repo, &input, &options)
+                }))
+                .unwrap_or_else(|payload| Err(panic_to_error(payload)));
 
                 let mut tasks = inbox.tasks.lock().unwrap();
                 if let Some(task) = tasks.iter_mut().find(|t| t.id == task_id) {
@@ -90,6 +99,23 @@ pub fn run(
                     inbox.recent_refreshes.lock().unwrap().push(fic_id);
                 }
             }
+            WorkerCommand::SearchAo3 {
+                search_id,
+                search,
+                page,
+                hide_tracked,
+            } => {
+                // A newer search was asked for while this one queued.
+                if search_id < inbox.latest_search.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let outcome = catch_unwind(AssertUnwindSafe(|| {
+                    search_ao3(&fetcher, &repo, &search, page, hide_tracked)
+                }))
+                .unwrap_or_else(|payload| Err(panic_to_error(payload)));
+                *inbox.search_result.lock().unwrap() = Some((search_id, search, outcome));
+                inbox.answered_search.store(search_id, Ordering::Relaxed);
+            }
         }
     }
 }
@@ -111,7 +137,21 @@ fn process_add(
     fetcher: &Ao3Fetcher,
     repo: &SqliteRepository<'_>,
     input: &str,
+    options: &AddOptions,
 ) -> Result<String, FicflowError> {
     let fic_id = extract_ao3_id(input).map_err(FicflowError::InvalidInput)?;
-    add_fanfiction(fetcher, repo, fic_id)
+    let title = add_fanfiction(fetcher, repo, fic_id)?;
+    // The fic is in the library either way; failing the task here would
+    // only make "Retry" trip over AlreadyExists.
+    if let Some(shelf_id) = options.shelf_id
+        && let Err(err) = add_to_shelf(repo, fic_id, shelf_id)
+    {
+        log::warn!("added fic {} but couldn't shelve it: {}", fic_id, err);
+    }
+    if let Some(visited) = options.last_visited
+        && let Err(err) = apply_visit_hint(repo, fic_id, visited)
+    {
+        log::warn!("added fic {} but couldn't apply its visit: {}", fic_id, err);
+    }
+    Ok(title)
 }
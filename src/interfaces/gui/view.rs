@@ -1,7 +1,6 @@
-use std::collections::HashSet;
-
 use super::config::PersistedView;
-use crate::domain::fanfiction::{Fanfiction, ReadingStatus};
+use crate::domain::fanfiction::ReadingStatus;
+use crate::domain::saved_search::{SavedSearch, SearchScope};
 use crate::domain::shelf::Shelf;
 
 /// Which view the user is looking at — drives the header title and the
@@ -12,6 +11,13 @@ pub enum View {
     AllFics,
     ByStatus(ReadingStatus),
     Shelf(u64),
+    /// A saved search: its scope view with its query and sort applied.
+    /// Filtering always goes through the resolved scope (see
+    /// `View::scope`), never this variant directly.
+    SavedSearch(u64),
+    /// Finding fics on AO3 to add.
+    Discover,
+    Trash,
     Tasks,
     Settings,
 }
@@ -19,8 +25,9 @@ pub enum View {
 impl View {
     /// Header title for the current view. For shelf views we look up the
     /// name from the loaded shelf list; if the shelf disappeared (deleted
-    /// behind our back), fall back to a generic label.
-    pub fn header_title(&self, shelves: &[Shelf]) -> String {
+    /// behind our back), fall back to a generic label. Saved searches
+    /// work the same way against the loaded saved-search list.
+    pub fn header_title(&self, shelves: &[Shelf], saved_searches: &[SavedSearch]) -> String {
         match self {
             View::AllFics => "ALL FICTIONS".to_string(),
             View::ByStatus(status) => match status {
@@ -35,43 +42,74 @@ impl View {
                 .find(|s| s.id == *id)
                 .map(|s| s.name.to_uppercase())
                 .unwrap_or_else(|| "SHELF".to_string()),
+            View::SavedSearch(id) => saved_searches
+                .iter()
+                .find(|s| s.id == *id)
+                .map(|s| s.name.to_uppercase())
+                .unwrap_or_else(|| "SAVED SEARCH".to_string()),
+            View::Discover => "DISCOVER".to_string(),
+            View::Trash => "TRASH".to_string(),
             View::Tasks => "TASKS".to_string(),
             View::Settings => "SETTINGS".to_string(),
         }
     }
 
-    /// Whether a given fic should appear in the table when this view is active.
-    /// `shelf_members` is consulted only for `View::Shelf(_)` and is expected
-    /// to be the cached id-set the app maintains when a shelf view is selected.
-    pub fn includes(&self, fic: &Fanfiction, shelf_members: &HashSet<u64>) -> bool {
+    /// True when this view shows the library table at all (vs. a stub page).
+    pub fn shows_library(&self) -> bool {
+        matches!(
+            self,
+            View::AllFics | View::ByStatus(_) | View::Shelf(_) | View::SavedSearch(_)
+        )
+    }
+
+    /// The view whose filter applies when this one is active: a saved
+    /// search resolves to the view it was saved from (or `AllFics` if
+    /// the saved search is gone), every other view to itself.
+    pub fn scope(&self, saved_searches: &[SavedSearch]) -> View {
         match self {
-            View::AllFics => true,
-            View::ByStatus(status) => fic.reading_status == *status,
-            View::Shelf(_) => shelf_members.contains(&fic.id),
-            View::Tasks | View::Settings => false,
+            View::SavedSearch(id) => saved_searches
+                .iter()
+                .find(|s| s.id == *id)
+                .map(|s| View::from(s.scope))
+                .unwrap_or_default(),
+            other => other.clone(),
         }
     }
 
-    /// True when this view shows the library table at all (vs. a stub page).
-    pub fn shows_library(&self) -> bool {
-        matches!(self, View::AllFics | View::ByStatus(_) | View::Shelf(_))
+    /// The scope a search typed in this view would be saved with, or
+    /// `None` for views without a library table.
+    pub fn to_search_scope(&self) -> Option<SearchScope> {
+        match self {
+            View::AllFics => Some(SearchScope::AllFics),
+            View::ByStatus(status) => Some(SearchScope::ByStatus(*status)),
+            View::Shelf(id) => Some(SearchScope::Shelf(*id)),
+            View::SavedSearch(_) | View::Discover | View::Trash | View::Tasks | View::Settings => {
+                None
+            }
+        }
     }
 
-    /// Converts to the persistable subset of views, or `None` for `Tasks`
-    /// and `Settings`, which aren't "tabs" worth restoring on next launch.
+    /// Converts to the persistable subset of views, or `None` for
+    /// `Discover`, `Trash`, `Tasks` and `Settings`, which aren't "tabs"
+    /// worth restoring on next launch.
     pub fn to_persisted(&self) -> Option<PersistedView> {
         match self {
             View::AllFics => Some(PersistedView::AllFics),
             View::ByStatus(status) => Some(PersistedView::ByStatus(*status)),
             View::Shelf(id) => Some(PersistedView::Shelf(*id)),
-            View::Tasks | View::Settings => None,
+            View::SavedSearch(id) => Some(PersistedView::SavedSearch(*id)),
+            View::Discover | View::Trash | View::Tasks | View::Settings => None,
         }
     }
 
-    /// Resolves a persisted view against the currently loaded shelves,
-    /// falling back to `None` if a persisted shelf id no longer exists
-    /// (the shelf was deleted since the config was last saved).
-    pub fn from_persisted(persisted: PersistedView, shelves: &[Shelf]) -> Option<Self> {
+    /// Resolves a persisted view against the currently loaded shelves and
+    /// saved searches, falling back to `None` if a persisted id no longer
+    /// exists (it was deleted since the config was last saved).
+    pub fn from_persisted(
+        persisted: PersistedView,
+        shelves: &[Shelf],
+        saved_searches: &[SavedSearch],
+    ) -> Option<Self> {
         match persisted {
             PersistedView::AllFics => Some(View::AllFics),
             PersistedView::ByStatus(status) => Some(View::ByStatus(status)),
@@ -79,6 +117,20 @@ impl View {
                 .iter()
                 .any(|s| s.id == id)
                 .then_some(View::Shelf(id)),
+            PersistedView::SavedSearch(id) => saved_searches
+                .iter()
+                .any(|s| s.id == id)
+                .then_some(View::SavedSearch(id)),
+        }
+    }
+}
+
+impl From<SearchScope> for View {
+    fn from(scope: SearchScope) -> Self {
+        match scope {
+            SearchScope::AllFics => View::AllFics,
+            SearchScope::ByStatus(status) => View::ByStatus(status),
+            SearchScope::Shelf(id) => View::Shelf(id),
         }
     }
 }
@@ -0,0 +1,214 @@
+//! The details panel for a multi-selection. Same contract as
+//! `details_panel`: fics in, one `Outcome` out. Each control shows the
+//! shared value when every selected fic agrees and a "mixed" marker when
+//! they don't; whatever the user sets goes to all of them.
+
+use std::collections::BTreeSet;
+
+use egui::{Align, ComboBox, DragValue, Layout, RichText, ScrollArea, TextEdit, Ui};
+
+use crate::domain::fanfiction::{BulkEdit, Fanfiction, ReadingStatus};
+
+use super::super::format::format_status;
+use super::super::widgets::personal_tags::{self, TagEdit};
+use super::super::widgets::star_rating;
+
+const MIXED: &str = "Mixed";
+
+pub struct BulkDetailsState<'a> {
+    pub fics: &'a [&'a Fanfiction],
+}
+
+pub enum Outcome {
+    None,
+    Apply(BulkEdit),
+}
+
+pub fn draw(ui: &mut Ui, state: BulkDetailsState<'_>) -> Outcome {
+    let BulkDetailsState { fics } = state;
+    let mut outcome = Outcome::None;
+
+    egui::Panel::top("bulk-details-header")
+        .resizable(false)
+        .show_separator_line(true)
+        .frame(egui::Frame::NONE.inner_margin(egui::Margin::symmetric(8, 8)))
+        .show_inside(ui, |ui| {
+            ui.label(
+                RichText::new(format!("{} fanfictions selected", fics.len()))
+                    .heading()
+                    .strong(),
+            );
+            ui.label(RichText::new("Changes apply to every selected fic.").weak());
+        });
+
+    egui::CentralPanel::default()
+        .frame(egui::Frame::NONE.inner_margin(egui::Margin::symmetric(8, 6)))
+        .show_inside(ui, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    if let Some(edit) = draw_fields(ui, fics) {
+                        outcome = Outcome::Apply(edit);
+                    }
+                });
+        });
+
+    outcome
+}
+
+fn draw_fields(ui: &mut Ui, fics: &[&Fanfiction]) -> Option<BulkEdit> {
+    let mut edit = None;
+
+    ui.label(RichText::new("YOUR INFO").strong().small());
+    ui.add_space(4.0);
+
+    kv_row(ui, "Status", |ui| {
+        if let Some(status) = draw_status(ui, fics) {
+            edit = Some(BulkEdit::Status(status));
+        }
+    });
+    kv_row(ui, "Chapter", |ui| {
+        let done = fics
+            .iter()
+            .filter(|f| f.last_chapter_read == Some(f.chapters_published))
+            .count();
+        ui.label(RichText::new(format!("{}/{} fully read", done, fics.len())).weak());
+        if ui
+            .add_enabled(done < fics.len(), egui::Button::new("Mark all as read"))
+            .clicked()
+        {
+            edit = Some(BulkEdit::MarkFullyRead);
+        }
+    });
+    kv_row(ui, "Reads", |ui| {
+        let shared = shared_value(fics, |f| f.read_count);
+        let mut reads = shared.unwrap_or(0);
+        if ui.add(DragValue::new(&mut reads)).changed() {
+            edit = Some(BulkEdit::ReadCount(reads));
+        }
+        if shared.is_none() {
+            mixed_marker(ui);
+        }
+    });
+    kv_row(ui, "Rating", |ui| {
+        let shared = shared_value(fics, |f| f.user_rating);
+        let mut rating = shared.flatten();
+        if star_rating::star_rating(ui, &mut rating) {
+            edit = Some(BulkEdit::UserRating(rating));
+        }
+        if shared.is_none() {
+            mixed_marker(ui);
+        }
+    });
+    kv_row(ui, "Tags", |ui| {
+        let (common, partial) = split_tags(fics);
+        let salt = ("bulk", fics.len());
+        match personal_tags::personal_tags(ui, salt, &common, &partial) {
+            Some(TagEdit::Add(tag)) => edit = Some(BulkEdit::AddPersonalTag(tag)),
+            Some(TagEdit::Remove(tag)) => edit = Some(BulkEdit::RemovePersonalTag(tag)),
+            None => {}
+        }
+    });
+
+    ui.add_space(4.0);
+    let with_notes = fics.iter().filter(|f| f.personal_note.is_some()).count();
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Append to notes").weak());
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.label(
+                RichText::new(format!("{}/{} have notes", with_notes, fics.len()))
+                    .weak()
+                    .small(),
+            );
+        });
+    });
+    if let Some(text) = draw_note_append(ui) {
+        edit = Some(BulkEdit::AppendNote(text));
+    }
+
+    edit
+}
+
+fn kv_row(ui: &mut Ui, label: &str, value: impl FnOnce(&mut Ui)) {
+    ui.add_space(2.0);
+    ui.horizontal(|ui| {
+        ui.allocate_ui_with_layout(
+            egui::vec2(80.0, 22.0),
+            Layout::left_to_right(Align::Center),
+            |ui| {
+                ui.label(RichText::new(label).weak());
+            },
+        );
+        value(ui);
+    });
+}
+
+fn mixed_marker(ui: &mut Ui) {
+    ui.label(RichText::new(MIXED).italics().weak())
+        .on_hover_text("The selected fics have different values");
+}
+
+/// `Some` when every fic has the same value.
+fn shared_value<T: PartialEq>(fics: &[&Fanfiction], field: impl Fn(&Fanfiction) -> T) -> Option<T> {
+    let mut values = fics.iter().map(|f| field(f));
+    let first = values.next()?;
+    values.all(|v| v == first).then_some(first)
+}
+
+fn draw_status(ui: &mut Ui, fics: &[&Fanfiction]) -> Option<ReadingStatus> {
+    let shared = shared_value(fics, |f| f.reading_status);
+    let selected_text = shared.as_ref().map(format_status).unwrap_or(MIXED);
+    let mut chosen = None;
+    ComboBox::from_id_salt("bulk-status-combo")
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            for status in [
+                ReadingStatus::InProgress,
+                ReadingStatus::Read,
+                ReadingStatus::PlanToRead,
+                ReadingStatus::Paused,
+                ReadingStatus::Abandoned,
+            ] {
+                if ui
+                    .selectable_label(shared == Some(status), format_status(&status))
+                    .clicked()
+                {
+                    chosen = Some(status);
+                }
+            }
+        });
+    chosen.filter(|status| shared != Some(*status))
+}
+
+/// Tags on every selected fic, then tags on only some of them. Both in
+/// name order.
+fn split_tags(fics: &[&Fanfiction]) -> (Vec<String>, Vec<String>) {
+    let all: BTreeSet<&String> = fics.iter().flat_map(|f| &f.personal_tags).collect();
+    all.into_iter()
+        .cloned()
+        .partition(|tag| fics.iter().all(|f| f.personal_tags.contains(tag)))
+}
+
+/// Multiline draft with an explicit button — unlike the single-fic
+/// note, appending isn't idempotent, so it shouldn't fire on focus loss.
+fn draw_note_append(ui: &mut Ui) -> Option<String> {
+    let id = ui.id().with("bulk-note-draft");
+    let mut buf: String = ui
+        .data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_default();
+    ui.add(
+        TextEdit::multiline(&mut buf)
+            .desired_rows(3)
+            .desired_width(f32::INFINITY),
+    );
+    let mut appended = None;
+    if ui
+        .add_enabled(!buf.trim().is_empty(), egui::Button::new("Append"))
+        .clicked()
+    {
+        appended = Some(buf.trim().to_string());
+        buf.clear();
+    }
+    ui.data_mut(|d| d.insert_temp(id, buf));
+    appended
+}
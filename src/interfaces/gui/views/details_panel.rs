@@ -16,6 +16,7 @@ use crate::domain::fanfiction::{
 use crate::domain::shelf::Shelf;
 
 use super::super::format::{format_status, format_thousands};
+use super::super::widgets::personal_tags::{self, TagEdit};
 use super::super::widgets::shelves_dropdown::{self, DropdownOutcome};
 use super::super::widgets::star_rating;
 use super::modals::shelf_modals::ClauseFieldKind;
@@ -37,10 +38,13 @@ pub enum Outcome {
     SetUserRating(Option<UserRating>),
     /// `None` means "clear the note" (NULL in DB).
     SetNote(Option<String>),
+    SetPersonalTags(Vec<String>),
     AddToShelf(u64),
     RemoveFromShelf(u64),
     RequestDelete,
     RequestRefresh,
+    /// Open the fic in the browser at the chapter to read next.
+    ContinueReading,
     CreateAutoShelfFromTag(ClauseFieldKind, String),
 }
 
@@ -157,8 +161,17 @@ fn draw_header(ui: &mut Ui, fic: &Fanfiction) -> Outcome {
     });
 
     ui.add_space(4.0);
-    let url = format!("https://archiveofourown.org/works/{}", fic.id);
+    let url = fic.url();
     ui.hyperlink_to(RichText::new(&url).small(), &url);
+
+    ui.add_space(6.0);
+    let label = match fic.last_chapter_read {
+        Some(_) => format!("\u{25B6}  Continue reading (ch. {})", fic.next_chapter()),
+        None => "\u{25B6}  Start reading".to_string(),
+    };
+    if ui.button(label).clicked() {
+        outcome = Outcome::ContinueReading;
+    }
     outcome
 }
 
@@ -218,6 +231,17 @@ fn draw_your_info(
         }
     });
 
+    kv_row(ui, "Tags", |ui| {
+        if let Some(edit) = personal_tags::personal_tags(ui, fic.id, &fic.personal_tags, &[]) {
+            let mut tags = fic.personal_tags.clone();
+            match edit {
+                TagEdit::Add(tag) => tags.push(tag),
+                TagEdit::Remove(tag) => tags.retain(|t| *t != tag),
+            }
+            outcome = Outcome::SetPersonalTags(tags);
+        }
+    });
+
     ui.add_space(4.0);
     ui.label(RichText::new("Notes").weak());
     if let Some(value) = draw_note(ui, fic) {
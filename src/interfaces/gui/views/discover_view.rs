@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use egui::{Align, Key, Layout, RichText, ScrollArea, Ui};
+
+use super::super::format::format_thousands;
+use crate::domain::discover::{Ao3Search, BlurbPage, WorkBlurb};
+
+/// The Discover view's form and the page it last got back. Kept on the
+/// app so switching views doesn't lose a search.
+pub struct DiscoverState {
+    pub terms: String,
+    /// Whether `terms` names a tag, or is words for AO3's works search.
+    pub by_tag: bool,
+    pub hide_tracked: bool,
+    /// The search the page below came from, which the form may have
+    /// moved on from since.
+    pub results: Option<(Ao3Search, BlurbPage)>,
+    pub checked: HashSet<u64>,
+    /// Sent to the task executor; shown as queued until they land.
+    pub queued: HashSet<u64>,
+    pub searching: bool,
+    pub error: Option<String>,
+}
+
+impl Default for DiscoverState {
+    fn default() -> Self {
+        Self {
+            terms: String::new(),
+            by_tag: true,
+            hide_tracked: false,
+            results: None,
+            checked: HashSet::new(),
+            queued: HashSet::new(),
+            searching: false,
+            error: None,
+        }
+    }
+}
+
+impl DiscoverState {
+    pub fn search(&self) -> Ao3Search {
+        let terms = self.terms.trim().to_string();
+        if self.by_tag {
+            Ao3Search::Tag(terms)
+        } else {
+            Ao3Search::Text(terms)
+        }
+    }
+
+    /// Re-flags the shown works against the library after adds land.
+    pub fn mark_tracked(&mut self, in_library: &HashSet<u64>) {
+        if let Some((_, page)) = &mut self.results {
+            for blurb in &mut page.blurbs {
+                blurb.tracked = in_library.contains(&blurb.id);
+            }
+        }
+        self.queued.retain(|id| !in_library.contains(id));
+        self.checked.retain(|id| !in_library.contains(id));
+    }
+}
+
+/// At most one per frame.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    None,
+    /// Run the form's search from `page`.
+    Search(u32),
+    /// Turn to another page of the shown search.
+    Page(u32),
+    Add(Vec<u64>),
+}
+
+pub fn draw(ui: &mut Ui, state: &mut DiscoverState) -> Outcome {
+    let mut outcome = Outcome::None;
+
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut state.by_tag, true, "Tag");
+        ui.selectable_value(&mut state.by_tag, false, "Search");
+        let hint = if state.by_tag {
+            "Tag name, e.g. Wingfic"
+        } else {
+            "Words to search AO3 for"
+        };
+        let field = ui.add(
+            egui::TextEdit::singleline(&mut state.terms)
+                .hint_text(hint)
+                .desired_width(280.0),
+        );
+        let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        let ready = !state.terms.trim().is_empty();
+        if (ui.add_enabled(ready, egui::Button::new("Search")).clicked() || submitted) && ready {
+            outcome = Outcome::Search(1);
+        }
+        if ui
+            .checkbox(&mut state.hide_tracked, "Hide fics in my library")
+            .changed()
+            && let Some((_, page)) = &state.results
+        {
+            outcome = Outcome::Page(page.page);
+        }
+        if state.searching {
+            ui.spinner();
+            ui.label(RichText::new("Searching AO3…").weak());
+        }
+    });
+    if let Some(error) = &state.error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+    ui.separator();
+
+    let Some((search, page)) = &state.results else {
+        ui.add_space(8.0);
+        ui.label(
+            RichText::new(
+                "Look up a tag's works, or search AO3, to find fics to add. Works \
+                already in your library are marked.",
+            )
+            .italics()
+            .weak(),
+        );
+        return outcome;
+    };
+
+    ui.horizontal(|ui| {
+        let mut heading = format!("Page {} of {} of {}", page.page, page.page_count, search);
+        if page.hidden > 0 {
+            heading.push_str(&format!(" ({} in your library hidden)", page.hidden));
+        }
+        ui.label(RichText::new(heading).weak());
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let checked: Vec<u64> = page
+                .blurbs
+                .iter()
+                .filter(|b| state.checked.contains(&b.id))
+                .map(|b| b.id)
+                .collect();
+            if ui
+                .add_enabled(
+                    !checked.is_empty(),
+                    egui::Button::new(format!("Add checked ({})", checked.len())),
+                )
+                .clicked()
+            {
+                outcome = Outcome::Add(checked);
+            }
+        });
+    });
+    ui.separator();
+
+    if page.blurbs.is_empty() {
+        ui.add_space(8.0);
+        ui.label(RichText::new("Nothing new on this page.").italics().weak());
+    }
+
+    ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .max_height(ui.available_height() - 32.0)
+        .show(ui, |ui| {
+            for blurb in &page.blurbs {
+                let queued = state.queued.contains(&blurb.id);
+                if let Some(add) = blurb_row(ui, blurb, queued, &mut state.checked) {
+                    outcome = Outcome::Add(vec![add]);
+                }
+            }
+        });
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(page.page > 1, egui::Button::new("\u{2190} Previous"))
+            .clicked()
+        {
+            outcome = Outcome::Page(page.page - 1);
+        }
+        if ui
+            .add_enabled(page.has_next(), egui::Button::new("Next \u{2192}"))
+            .clicked()
+        {
+            outcome = Outcome::Page(page.page + 1);
+        }
+    });
+    outcome
+}
+
+/// One work, with its checkbox and Add button. Returns the work's id
+/// when Add is clicked.
+fn blurb_row(
+    ui: &mut Ui,
+    blurb: &WorkBlurb,
+    queued: bool,
+    checked: &mut HashSet<u64>,
+) -> Option<u64> {
+    let mut add = None;
+    let addable = !blurb.tracked && !queued;
+    ui.horizontal(|ui| {
+        let mut is_checked = checked.contains(&blurb.id);
+        if ui
+            .add_enabled(addable, egui::Checkbox::without_text(&mut is_checked))
+            .changed()
+        {
+            if is_checked {
+                checked.insert(blurb.id);
+            } else {
+                checked.remove(&blurb.id);
+            }
+        }
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::Label::new(RichText::new(&blurb.title).strong())
+                        .truncate()
+                        .selectable(false),
+                );
+                ui.label(RichText::new(format!("by {}", blurb.authors.join(", "))).weak());
+            });
+            let chapters = match blurb.chapters_total {
+                Some(total) => format!("{}/{}", blurb.chapters_published, total),
+                None => format!("{}/?", blurb.chapters_published),
+            };
+            ui.label(
+                RichText::new(format!(
+                    "{}  ·  {} words  ·  {} chapters  ·  {} kudos",
+                    blurb.fandoms.join(", "),
+                    format_thousands(blurb.words),
+                    chapters,
+                    format_thousands(blurb.kudos)
+                ))
+                .small()
+                .weak(),
+            );
+        });
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if blurb.tracked {
+                ui.label(RichText::new("In library").weak());
+            } else if queued {
+                ui.label(RichText::new("Adding…").weak());
+            } else if ui.button("Add").clicked() {
+                add = Some(blurb.id);
+            }
+        });
+    });
+    ui.separator();
+    add
+}
@@ -0,0 +1,49 @@
+use egui::{CollapsingHeader, RichText, Ui};
+
+use crate::domain::health::LibraryIssue;
+
+pub enum Outcome {
+    None,
+    Repair,
+    Dismiss,
+}
+
+/// A strip under the header while the startup check's problems are
+/// unresolved, with the list folded away under "Details".
+pub fn draw(host: &mut Ui, issues: &[LibraryIssue]) -> Outcome {
+    let mut outcome = Outcome::None;
+    egui::Panel::top("ficflow-health-banner").show_inside(host, |ui| {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!("Found {} problems in this library.", issues.len()))
+                    .color(ui.visuals().warn_fg_color)
+                    .strong(),
+            );
+            if issues.iter().any(|i| i.repair().is_some()) {
+                if ui
+                    .button("Repair")
+                    .on_hover_text("Backs the library up first")
+                    .clicked()
+                {
+                    outcome = Outcome::Repair;
+                }
+            } else {
+                ui.label(RichText::new("Restore a backup from Settings.").weak());
+            }
+            if ui.small_button("\u{2715}").clicked() {
+                outcome = Outcome::Dismiss;
+            }
+        });
+        CollapsingHeader::new("Details")
+            .id_salt("ficflow-health-details")
+            .show(ui, |ui| {
+                for issue in issues {
+                    let repair = issue.repair().unwrap_or("no safe repair");
+                    ui.label(format!("{} ({})", issue.describe(), repair));
+                }
+            });
+        ui.add_space(4.0);
+    });
+    outcome
+}
@@ -1,29 +1,42 @@
-use std::cmp::Ordering;
-
 use egui::{Align, Color32, Layout, RichText, Sense, Stroke, StrokeKind, Ui};
 use egui_extras::{Column, TableBuilder};
 
-use std::collections::HashSet;
-
 use super::super::config::{ColumnKey, SortDirection, SortPref};
-use crate::domain::fanfiction::{ArchiveWarnings, Fanfiction, Rating, ReadingStatus};
+use crate::domain::fanfiction::{ArchiveWarnings, Fanfiction, Rating, ReadingStatus, Snippet};
 
 use super::super::format::{format_status, format_thousands};
+use super::super::search_results::SearchResults;
 use super::super::selection_controller::SelectionController;
 use super::super::theme;
-use super::super::view::View;
 
 const HEADER_HEIGHT: f32 = 22.0;
 const ROW_HEIGHT: f32 = 28.0;
+/// Rows grow while searching to fit the match snippet under the title.
+const SNIPPET_ROW_HEIGHT: f32 = 44.0;
+const NATURAL_WIDTH_SAMPLE: usize = 200;
+
+/// The table's rows, already filtered and ordered (see
+/// `LibraryCache::sync_rows`). Only the rows being drawn are asked for,
+/// so a source can load them a page at a time.
+pub trait TableRows {
+    fn len(&self) -> usize;
+    /// Every row's fic id, in order.
+    fn ids(&self) -> &[u64];
+    /// `None` when the row couldn't be loaded; it's drawn blank.
+    fn fic(&mut self, index: usize) -> Option<&Fanfiction>;
+}
 
 pub struct LibraryViewState<'a> {
-    pub fics: &'a [Fanfiction],
+    /// Tells "empty library" from "nothing in this view" when `rows` is
+    /// empty.
+    pub library_is_empty: bool,
+    pub rows: &'a mut dyn TableRows,
     pub sort: &'a mut SortPref,
-    pub search_query: &'a str,
+    pub search: &'a SearchResults,
+    /// `rows` are in relevance order rather than `sort`'s.
+    pub ranked: bool,
     pub visible_columns: &'a [ColumnKey],
     pub selection: &'a mut SelectionController,
-    pub view: &'a View,
-    pub shelf_members: &'a HashSet<u64>,
 }
 
 #[derive(Default)]
@@ -35,67 +48,32 @@ pub struct TableOutcome {
 
 pub fn draw(ui: &mut Ui, state: LibraryViewState<'_>) -> TableOutcome {
     let LibraryViewState {
-        fics,
+        library_is_empty,
+        rows,
         sort,
-        search_query,
+        search,
+        ranked,
         visible_columns,
         selection,
-        view,
-        shelf_members,
     } = state;
 
-    let visible: Vec<&Fanfiction> = filter_and_sort(fics, search_query, *sort, view, shelf_members);
-    draw_table(
-        ui,
-        fics,
-        &visible,
-        sort,
+    let table = TableParams {
         visible_columns,
-        selection,
-        search_query,
-    )
-}
-
-/// All fic ids that pass the current view filter + search query, in sort order.
-/// Used by `Ctrl+A` to select-all-visible without library_view re-rendering.
-pub fn visible_ids(
-    fics: &[Fanfiction],
-    view: &View,
-    shelf_members: &HashSet<u64>,
-    search_query: &str,
-    sort: SortPref,
-) -> Vec<u64> {
-    filter_and_sort(fics, search_query, sort, view, shelf_members)
-        .into_iter()
-        .map(|f| f.id)
-        .collect()
-}
-
-/// Count of fics that match the current view filter + search query. Cheaper
-/// than `visible_ids().len()` since it skips the sort + Vec allocation, but
-/// produces the same number — used by the central-panel header counter.
-pub fn visible_count(
-    fics: &[Fanfiction],
-    view: &View,
-    shelf_members: &HashSet<u64>,
-    search_query: &str,
-) -> usize {
-    fics.iter()
-        .filter(|f| view.includes(f, shelf_members))
-        .filter(|f| matches_search(f, search_query))
-        .count()
+        search,
+        ranked,
+    };
+    draw_table(ui, library_is_empty, rows, sort, selection, &table)
 }
 
 fn draw_table(
     ui: &mut Ui,
-    all_fics: &[Fanfiction],
-    fics: &[&Fanfiction],
+    library_is_empty: bool,
+    rows: &mut dyn TableRows,
     sort: &mut SortPref,
-    visible_columns: &[ColumnKey],
     selection: &mut SelectionController,
-    search_query: &str,
+    table: &TableParams<'_>,
 ) -> TableOutcome {
-    if visible_columns.is_empty() {
+    if table.visible_columns.is_empty() {
         ui.label(
             RichText::new("All columns are hidden — open the column picker to enable some.")
                 .italics()
@@ -103,10 +81,10 @@ fn draw_table(
         );
         return TableOutcome::default();
     }
-    if fics.is_empty() {
-        let message = if all_fics.is_empty() {
+    if rows.len() == 0 {
+        let message = if library_is_empty {
             "No fanfictions yet. Click \u{201C}+ Add Fic\u{201D} in the header to add one."
-        } else if !search_query.trim().is_empty() {
+        } else if table.search.is_active() {
             "No fanfictions match your search."
         } else {
             "No fanfictions in this view yet."
@@ -119,11 +97,12 @@ fn draw_table(
     // Compute auto-fit decision against the *outer* width — before any
     // ScrollArea wrapper expands `ui.available_width()` to infinity.
     let outer_avail = ui.available_width();
-    let natural = natural_widths(ui, fics, visible_columns, *sort);
+    let shown_sort = (!table.ranked).then_some(*sort);
+    let natural = natural_widths(ui, rows, table.visible_columns, shown_sort);
     let auto_fit = natural.iter().sum::<f32>() <= outer_avail;
 
     if auto_fit {
-        build_table(ui, fics, sort, visible_columns, selection, &natural, true)
+        build_table(ui, rows, sort, selection, table, &natural, true)
     } else {
         // Doesn't fit — wrap in a horizontal ScrollArea so the user can
         // pan to overflowed columns. Fixed-width columns only inside,
@@ -131,25 +110,38 @@ fn draw_table(
         // unbounded inner width.
         egui::ScrollArea::horizontal()
             .show(ui, |ui| {
-                build_table(ui, fics, sort, visible_columns, selection, &natural, false)
+                build_table(ui, rows, sort, selection, table, &natural, false)
             })
             .inner
     }
 }
 
+/// Read-only inputs threaded from `draw` down to `build_table`.
+struct TableParams<'a> {
+    visible_columns: &'a [ColumnKey],
+    search: &'a SearchResults,
+    /// Rows are in relevance order, so no column shows a sort glyph.
+    ranked: bool,
+}
+
 /// Inner table-building. Called once for the auto-fit case (where the
 /// table fills the available width) and once inside a horizontal
 /// ScrollArea for the overflow case (where the table extends past the
 /// viewport and the user pans to see the rest).
 fn build_table(
     ui: &mut Ui,
-    fics: &[&Fanfiction],
+    rows: &mut dyn TableRows,
     sort: &mut SortPref,
-    visible_columns: &[ColumnKey],
     selection: &mut SelectionController,
+    table: &TableParams<'_>,
     natural: &[f32],
     auto_fit: bool,
 ) -> TableOutcome {
+    let TableParams {
+        visible_columns,
+        search,
+        ranked,
+    } = *table;
     let mut outcome = TableOutcome::default();
     let mut builder = TableBuilder::new(ui)
         .striped(true)
@@ -180,31 +172,44 @@ fn build_table(
     builder
         .header(HEADER_HEIGHT, |mut header| {
             for col in visible_columns {
-                header_cell(&mut header, *col, sort, &mut outcome);
+                header_cell(&mut header, *col, sort, ranked, &mut outcome);
             }
         })
         .body(|body| {
-            body.rows(ROW_HEIGHT, fics.len(), |mut row| {
-                let fic = fics[row.index()];
+            let row_height = if search.is_active() {
+                SNIPPET_ROW_HEIGHT
+            } else {
+                ROW_HEIGHT
+            };
+            body.rows(row_height, rows.len(), |mut row| {
                 let row_idx = row.index();
-                row.set_selected(selection.contains(fic.id));
+                let Some(fic) = rows.fic(row_idx) else {
+                    return;
+                };
+                let fic_id = fic.id;
+                row.set_selected(selection.contains(fic_id));
                 for col in visible_columns {
-                    row.col(|ui| render_cell(ui, fic, *col));
+                    row.col(|ui| match (col, search.snippet(fic.id)) {
+                        (ColumnKey::Title, Some(snippet)) => {
+                            render_title_with_snippet(ui, fic, snippet)
+                        }
+                        _ => render_cell(ui, fic, *col),
+                    });
                 }
                 let resp = row.response();
                 if resp.clicked() {
                     let mods = resp.ctx.input(|i| i.modifiers);
-                    selection.handle_row_click(fics, row_idx, mods);
+                    selection.handle_row_click(rows.ids(), row_idx, mods);
                 }
                 if resp.drag_started() {
                     // If the user starts dragging a row that's already part
                     // of the selection, we drag the whole selection. Else
                     // drag just that row (without changing the selection,
                     // which would feel surprising).
-                    let drag_ids: Vec<u64> = if selection.contains(fic.id) {
+                    let drag_ids: Vec<u64> = if selection.contains(fic_id) {
                         selection.ids_vec()
                     } else {
-                        vec![fic.id]
+                        vec![fic_id]
                     };
                     resp.dnd_set_drag_payload(drag_ids);
                 }
@@ -252,12 +257,14 @@ fn header_cell(
     header: &mut egui_extras::TableRow<'_, '_>,
     column: ColumnKey,
     sort: &mut SortPref,
+    ranked: bool,
     outcome: &mut TableOutcome,
 ) {
+    let shown_sort = (!ranked).then_some(*sort);
     // `selectable(false)` keeps the Label from swallowing the click
     // before it reaches the outer cell response (used for sort toggle).
     let (rect, resp) = header.col(|ui| {
-        let text = format!("{}{}", column.label(), sort_glyph(*sort, column));
+        let text = format!("{}{}", column.label(), sort_glyph(shown_sort, column));
         ui.with_layout(
             Layout::centered_and_justified(egui::Direction::LeftToRight),
             |ui| {
@@ -273,7 +280,16 @@ fn header_cell(
         );
     });
     if resp.clicked() {
-        toggle_sort(sort, column);
+        if ranked {
+            // Leaving relevance order: start the clicked column afresh
+            // rather than flipping whatever `sort` was before the search.
+            *sort = SortPref {
+                column,
+                direction: SortDirection::Descending,
+            };
+        } else {
+            toggle_sort(sort, column);
+        }
         outcome.sort_changed = true;
     }
     if resp.drag_started() {
@@ -316,28 +332,30 @@ fn header_cell(
 }
 
 /// Longest of (header text, content text) per column, plus padding —
-/// drives the auto-fit-vs-overflow decision.
+/// drives the auto-fit-vs-overflow decision. Only the first
+/// `NATURAL_WIDTH_SAMPLE` rows are measured: this runs every frame, and
+/// laying out every cell of a large library would dominate the frame.
 fn natural_widths(
     ui: &Ui,
-    fics: &[&Fanfiction],
+    rows: &mut dyn TableRows,
     visible_columns: &[ColumnKey],
-    sort: SortPref,
+    shown_sort: Option<SortPref>,
 ) -> Vec<f32> {
     let body_font = egui::TextStyle::Body.resolve(ui.style());
     visible_columns
         .iter()
         .map(|col| {
-            let header_text = format!("{}{}", col.label(), sort_glyph(sort, *col));
+            let header_text = format!("{}{}", col.label(), sort_glyph(shown_sort, *col));
             let header_w = ui
                 .painter()
                 .layout_no_wrap(header_text, body_font.clone(), egui::Color32::WHITE)
                 .size()
                 .x;
-            let content_w = fics
-                .iter()
-                .map(|f| {
+            let content_w = (0..rows.len().min(NATURAL_WIDTH_SAMPLE))
+                .filter_map(|i| rows.fic(i).map(|f| cell_text(f, *col)))
+                .map(|text| {
                     ui.painter()
-                        .layout_no_wrap(cell_text(f, *col), body_font.clone(), egui::Color32::WHITE)
+                        .layout_no_wrap(text, body_font.clone(), egui::Color32::WHITE)
                         .size()
                         .x
                 })
@@ -435,6 +453,42 @@ fn render_cell(ui: &mut Ui, fic: &Fanfiction, column: ColumnKey) {
     }
 }
 
+/// Title over the best-matching excerpt, matched terms picked out in
+/// the accent colour.
+fn render_title_with_snippet(ui: &mut Ui, fic: &Fanfiction, snippet: &Snippet) {
+    ui.vertical(|ui| {
+        ui.spacing_mut().item_spacing.y = 0.0;
+        ui.add(egui::Label::new(&fic.title).truncate().selectable(false));
+        ui.add(
+            egui::Label::new(snippet_job(ui, snippet))
+                .truncate()
+                .selectable(false),
+        );
+    });
+}
+
+fn snippet_job(ui: &Ui, snippet: &Snippet) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let weak = ui.visuals().weak_text_color();
+    let accent = theme::accent(ui.visuals());
+    let mut job = egui::text::LayoutJob::default();
+    for (text, highlighted) in snippet.segments() {
+        let format = if highlighted {
+            egui::TextFormat {
+                font_id: font_id.clone(),
+                color: accent,
+                background: accent.gamma_multiply(0.18),
+                ..Default::default()
+            }
+        } else {
+            egui::TextFormat::simple(font_id.clone(), weak)
+        };
+        // Summaries and notes can span lines; the excerpt stays on one.
+        job.append(&text.replace('\n', " "), 0.0, format);
+    }
+    job
+}
+
 fn is_centered_column(col: ColumnKey) -> bool {
     matches!(
         col,
@@ -516,10 +570,10 @@ fn toggle_sort(sort: &mut SortPref, column: ColumnKey) {
     }
 }
 
-fn sort_glyph(sort: SortPref, column: ColumnKey) -> &'static str {
-    if sort.column != column {
+fn sort_glyph(sort: Option<SortPref>, column: ColumnKey) -> &'static str {
+    let Some(sort) = sort.filter(|s| s.column == column) else {
         return "";
-    }
+    };
     match sort.direction {
         // ▲ ascending (smallest first), ▼ descending — same convention
         // most table UIs use, just rendered with proper triangle glyphs
@@ -550,109 +604,3 @@ fn format_rating(fic: &Fanfiction) -> String {
     }
     out
 }
-
-fn filter_and_sort<'a>(
-    fics: &'a [Fanfiction],
-    query: &str,
-    sort: SortPref,
-    view: &View,
-    shelf_members: &HashSet<u64>,
-) -> Vec<&'a Fanfiction> {
-    let mut visible: Vec<&Fanfiction> = fics
-        .iter()
-        .filter(|f| view.includes(f, shelf_members))
-        .filter(|f| matches_search(f, query))
-        .collect();
-    visible.sort_by(|a, b| {
-        let ord = compare(a, b, sort.column);
-        match sort.direction {
-            SortDirection::Ascending => ord,
-            SortDirection::Descending => ord.reverse(),
-        }
-    });
-    visible
-}
-
-fn matches_search(fic: &Fanfiction, query: &str) -> bool {
-    let q = query.trim().to_lowercase();
-    if q.is_empty() {
-        return true;
-    }
-    let needle = |s: &str| s.to_lowercase().contains(&q);
-    needle(&fic.title)
-        || fic.authors.iter().any(|s| needle(s))
-        || fic.fandoms.iter().any(|s| needle(s))
-        || fic
-            .characters
-            .as_deref()
-            .is_some_and(|v| v.iter().any(|s| needle(s)))
-        || fic
-            .relationships
-            .as_deref()
-            .is_some_and(|v| v.iter().any(|s| needle(s)))
-        || fic
-            .tags
-            .as_deref()
-            .is_some_and(|v| v.iter().any(|s| needle(s)))
-}
-
-fn compare(a: &Fanfiction, b: &Fanfiction, column: ColumnKey) -> Ordering {
-    match column {
-        ColumnKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
-        ColumnKey::Author => a
-            .authors
-            .first()
-            .map(|s| s.to_lowercase())
-            .cmp(&b.authors.first().map(|s| s.to_lowercase())),
-        ColumnKey::Fandom => first_lower(&a.fandoms).cmp(&first_lower(&b.fandoms)),
-        ColumnKey::Pairing => a
-            .relationships
-            .as_deref()
-            .map(first_lower)
-            .cmp(&b.relationships.as_deref().map(first_lower)),
-        ColumnKey::AO3Rating => ao3_rating_order(&a.rating).cmp(&ao3_rating_order(&b.rating)),
-        ColumnKey::Warnings => a
-            .warnings
-            .first()
-            .map(format_warning)
-            .cmp(&b.warnings.first().map(format_warning)),
-        ColumnKey::Status => status_order(&a.reading_status).cmp(&status_order(&b.reading_status)),
-        ColumnKey::Complete => a.complete.cmp(&b.complete),
-        ColumnKey::LastChapter => a.last_chapter_read.cmp(&b.last_chapter_read),
-        ColumnKey::Words => a.words.cmp(&b.words),
-        ColumnKey::Kudos => a.kudos.cmp(&b.kudos),
-        ColumnKey::Hits => a.hits.cmp(&b.hits),
-        ColumnKey::Rating => a
-            .user_rating
-            .map(|r| r as u8)
-            .cmp(&b.user_rating.map(|r| r as u8)),
-        ColumnKey::Reads => a.read_count.cmp(&b.read_count),
-        ColumnKey::Language => a.language.to_lowercase().cmp(&b.language.to_lowercase()),
-        ColumnKey::DatePublished => a.date_published.cmp(&b.date_published),
-        ColumnKey::Updated => a.date_updated.cmp(&b.date_updated),
-    }
-}
-
-fn first_lower(v: &[String]) -> Option<String> {
-    v.first().map(|s| s.to_lowercase())
-}
-
-fn ao3_rating_order(r: &Rating) -> u8 {
-    match r {
-        Rating::NotRated => 0,
-        Rating::General => 1,
-        Rating::TeenAndUp => 2,
-        Rating::Mature => 3,
-        Rating::Explicit => 4,
-    }
-}
-
-fn status_order(s: &ReadingStatus) -> u8 {
-    match s {
-        ReadingStatus::InProgress => 0,
-        ReadingStatus::Read => 1,
-        ReadingStatus::PlanToRead => 2,
-        ReadingStatus::Paused => 3,
-        ReadingStatus::Abandoned => 4,
-    }
-}
@@ -1,12 +1,20 @@
+pub mod bulk_details_panel;
 pub mod details_panel;
+pub mod discover_view;
+pub mod health_banner;
 pub mod library_view;
 pub mod modals;
 pub mod selection_bar;
 pub mod settings_view;
 pub mod sidebar;
 pub mod tasks_view;
+pub mod trash_view;
+pub mod undo_prompt;
 
+pub use bulk_details_panel::BulkDetailsState;
+pub use discover_view::DiscoverState;
 pub use library_view::{LibraryViewState, TableOutcome};
 pub use selection_bar::SelectionBarState;
 pub use sidebar::{LibraryCounts, SidebarState};
 pub use tasks_view::{TaskFilter, TasksViewState};
+pub use trash_view::TrashViewState;
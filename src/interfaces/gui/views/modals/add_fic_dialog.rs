@@ -1,16 +1,29 @@
-use egui::{Context, Window};
+use egui::{Context, RichText, Window};
+
+use crate::domain::shelf::Shelf;
+use crate::interfaces::utils::url_parser::extract_ao3_ids;
 
 /// Buffer for the in-progress AO3 URL or fic id. The "is the modal
 /// open?" answer lives in the parent `ActiveModal` enum — when this
 /// struct exists at all, the modal is open.
 pub struct AddFicState {
     pub input: String,
+    /// Paste mode: `text` is any block of text to pull work links from.
+    pub bulk: bool,
+    pub text: String,
+    /// Works found in `text`, recounted whenever it changes.
+    pub found: usize,
+    pub shelf_id: Option<u64>,
 }
 
 impl AddFicState {
     pub fn new() -> Self {
         Self {
             input: String::new(),
+            bulk: false,
+            text: String::new(),
+            found: 0,
+            shelf_id: None,
         }
     }
 }
@@ -24,12 +37,14 @@ impl Default for AddFicState {
 pub enum Outcome {
     None,
     Submit(String),
+    SubmitBulk { text: String, shelf_id: Option<u64> },
     Cancel,
 }
 
 /// Draws the add-fic modal. Caller is responsible for only invoking
-/// this when `ActiveModal::AddFic(_)` is the current modal.
-pub fn draw(ctx: &Context, state: &mut AddFicState) -> Outcome {
+/// this when `ActiveModal::AddFic(_)` is the current modal. `shelves`
+/// are the ones paste mode can put the new fics on.
+pub fn draw(ctx: &Context, state: &mut AddFicState, shelves: &[Shelf]) -> Outcome {
     let mut still_open = true;
     let mut outcome = Outcome::None;
     Window::new("Add fanfiction")
@@ -39,29 +54,106 @@ pub fn draw(ctx: &Context, state: &mut AddFicState) -> Outcome {
         .pivot(egui::Align2::CENTER_CENTER)
         .default_pos(ctx.content_rect().center())
         .show(ctx, |ui| {
-            ui.label("AO3 URL or fic ID:");
-            let resp = ui.text_edit_singleline(&mut state.input);
-            // Auto-focus on first frame so the user can type immediately.
-            if !resp.has_focus() && state.input.is_empty() {
-                resp.request_focus();
-            }
-            ui.add_space(6.0);
             ui.horizontal(|ui| {
-                let trimmed = state.input.trim().to_string();
-                let enabled = !trimmed.is_empty();
-                let pressed_enter =
-                    resp.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter)) && enabled;
-                let clicked = ui.add_enabled(enabled, egui::Button::new("Add")).clicked();
-                if clicked || pressed_enter {
-                    outcome = Outcome::Submit(trimmed);
-                }
-                if ui.button("Cancel").clicked() {
-                    outcome = Outcome::Cancel;
-                }
+                ui.selectable_value(&mut state.bulk, false, "One fic");
+                ui.selectable_value(&mut state.bulk, true, "Paste many");
             });
+            ui.add_space(4.0);
+            if state.bulk {
+                outcome = draw_bulk(ui, state, shelves);
+            } else {
+                outcome = draw_single(ctx, ui, state);
+            }
         });
     if !still_open {
         outcome = Outcome::Cancel;
     }
     outcome
 }
+
+fn draw_single(ctx: &Context, ui: &mut egui::Ui, state: &mut AddFicState) -> Outcome {
+    let mut outcome = Outcome::None;
+    ui.label("AO3 URL or fic ID:");
+    let resp = ui.text_edit_singleline(&mut state.input);
+    // Auto-focus on first frame so the user can type immediately.
+    if !resp.has_focus() && state.input.is_empty() {
+        resp.request_focus();
+    }
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        let trimmed = state.input.trim().to_string();
+        let enabled = !trimmed.is_empty();
+        let pressed_enter =
+            resp.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter)) && enabled;
+        let clicked = ui.add_enabled(enabled, egui::Button::new("Add")).clicked();
+        if clicked || pressed_enter {
+            outcome = Outcome::Submit(trimmed);
+        }
+        if ui.button("Cancel").clicked() {
+            outcome = Outcome::Cancel;
+        }
+    });
+    outcome
+}
+
+fn draw_bulk(ui: &mut egui::Ui, state: &mut AddFicState, shelves: &[Shelf]) -> Outcome {
+    let mut outcome = Outcome::None;
+    ui.label("Paste links or IDs, e.g. a rec list or a chat message:");
+    let resp = ui.add(
+        egui::TextEdit::multiline(&mut state.text)
+            .desired_rows(8)
+            .desired_width(360.0),
+    );
+    if resp.changed() {
+        state.found = extract_ao3_ids(&state.text).len();
+    }
+    let suffix = if state.found == 1 { "work" } else { "works" };
+    ui.label(
+        RichText::new(format!(
+            "{} {} found. Ones already in your library are skipped.",
+            state.found, suffix
+        ))
+        .weak(),
+    );
+    ui.add_space(4.0);
+
+    // Drop a pick whose shelf has gone away since.
+    if state
+        .shelf_id
+        .is_some_and(|id| !shelves.iter().any(|s| s.id == id))
+    {
+        state.shelf_id = None;
+    }
+    ui.horizontal(|ui| {
+        ui.label("Put them on:");
+        let selected = state
+            .shelf_id
+            .and_then(|id| shelves.iter().find(|s| s.id == id))
+            .map_or("No shelf", |s| s.name.as_str());
+        egui::ComboBox::from_id_salt("bulk-add-shelf")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut state.shelf_id, None, "No shelf");
+                for shelf in shelves {
+                    ui.selectable_value(&mut state.shelf_id, Some(shelf.id), &shelf.name);
+                }
+            });
+    });
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(state.found > 0, egui::Button::new("Add all"))
+            .clicked()
+        {
+            outcome = Outcome::SubmitBulk {
+                text: state.text.clone(),
+                shelf_id: state.shelf_id,
+            };
+        }
+        if ui.button("Cancel").clicked() {
+            outcome = Outcome::Cancel;
+        }
+    });
+    outcome
+}
@@ -0,0 +1,122 @@
+use egui::{Context, RichText, Window};
+
+use crate::application::export_fic_table::DEFAULT_LIST_SEPARATOR;
+use crate::domain::fanfiction::ColumnKey;
+use crate::domain::transfer::{ExportFormat, TableField};
+
+/// Options for "Export visible rows…". The rows and the table's own
+/// columns come from the app; the modal only adds the format, the
+/// fields the table doesn't show, and the multi-value separator.
+pub struct ExportState {
+    pub format: ExportFormat,
+    pub extras: Vec<TableField>,
+    pub separator: String,
+}
+
+impl Default for ExportState {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            extras: Vec::new(),
+            separator: DEFAULT_LIST_SEPARATOR.to_string(),
+        }
+    }
+}
+
+/// What to write once the user has picked a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportRequest {
+    pub format: ExportFormat,
+    pub extras: Vec<TableField>,
+    pub separator: String,
+}
+
+pub enum Outcome {
+    None,
+    Submit(ExportRequest),
+    Cancel,
+}
+
+pub fn draw(
+    ctx: &Context,
+    state: &mut ExportState,
+    row_count: usize,
+    columns: &[ColumnKey],
+) -> Outcome {
+    let mut still_open = true;
+    let mut outcome = Outcome::None;
+    Window::new("Export visible rows")
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            let suffix = if row_count == 1 { "fic" } else { "fics" };
+            ui.label(format!(
+                "{} {}, in the current search and sort order.",
+                row_count, suffix
+            ));
+            let labels: Vec<&str> = columns.iter().map(|c| c.label()).collect();
+            ui.label(RichText::new(format!("Columns: {}", labels.join(", "))).weak());
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Format:");
+                ui.radio_value(&mut state.format, ExportFormat::Csv, "CSV");
+                ui.radio_value(&mut state.format, ExportFormat::Tsv, "TSV");
+            });
+            ui.horizontal(|ui| {
+                ui.label("List separator:")
+                    .on_hover_text("Goes between the values of fields like authors and tags");
+                ui.add(egui::TextEdit::singleline(&mut state.separator).desired_width(60.0));
+            });
+            ui.add_space(6.0);
+
+            ui.label("Also include:");
+            egui::Grid::new("export-extra-fields")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (i, field) in TableField::EXTRAS.into_iter().enumerate() {
+                        let mut checked = state.extras.contains(&field);
+                        if ui.checkbox(&mut checked, field.label()).changed() {
+                            if checked {
+                                state.extras.push(field);
+                            } else {
+                                state.extras.retain(|f| *f != field);
+                            }
+                        }
+                        if i % 2 == 1 {
+                            ui.end_row();
+                        }
+                    }
+                });
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                let enabled = !columns.is_empty() || !state.extras.is_empty();
+                if ui
+                    .add_enabled(enabled, egui::Button::new("Export\u{2026}"))
+                    .clicked()
+                {
+                    // Keep the extras in dialog order, not click order.
+                    let extras = TableField::EXTRAS
+                        .into_iter()
+                        .filter(|f| state.extras.contains(f))
+                        .collect();
+                    outcome = Outcome::Submit(ExportRequest {
+                        format: state.format,
+                        extras,
+                        separator: state.separator.clone(),
+                    });
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = Outcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = Outcome::Cancel;
+    }
+    outcome
+}
@@ -0,0 +1,151 @@
+use egui::{Context, RichText, ScrollArea, Window};
+
+use crate::domain::fanfiction::UserRating;
+use crate::domain::transfer::{ConflictChoice, FicImportPlan, ImportConflict};
+
+use super::super::super::format::format_status;
+
+/// The preview of an import from another app's library. The plan is
+/// edited in place: each conflict's choice is what the user picks here.
+pub struct FicImportState {
+    pub plan: FicImportPlan,
+    /// Names the other library in the text, e.g. "Calibre".
+    pub source: String,
+}
+
+pub enum Outcome {
+    None,
+    Import,
+    Cancel,
+}
+
+pub fn draw(ctx: &Context, state: &mut FicImportState) -> Outcome {
+    let mut still_open = true;
+    let mut outcome = Outcome::None;
+    let source = state.source.clone();
+    let plan = &mut state.plan;
+    Window::new(format!("Import from {}", source))
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            let found = plan.new.len() + plan.conflicts.len() + plan.unchanged;
+            ui.label(format!("Found {} AO3 works in {}.", found, source));
+            ui.add_space(6.0);
+
+            ui.label(format!("{} new", plan.new.len()));
+            if !plan.new.is_empty() {
+                ui.label(
+                    RichText::new("Their details are fetched from AO3 after the import.")
+                        .weak()
+                        .italics(),
+                );
+            }
+            ui.label(format!("{} already up to date", plan.unchanged));
+
+            if !plan.conflicts.is_empty() {
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} with a different status or rating{}",
+                            plan.conflicts.len(),
+                            if plan.conflicts.iter().any(|c| c.trashed) {
+                                ", or in your trash"
+                            } else {
+                                ""
+                            }
+                        ))
+                        .strong(),
+                    );
+                    if ui.small_button("Keep all mine").clicked() {
+                        plan.set_all_choices(ConflictChoice::KeepLocal);
+                    }
+                    if ui
+                        .small_button(format!("Use all from {}", source))
+                        .clicked()
+                    {
+                        plan.set_all_choices(ConflictChoice::UseIncoming);
+                    }
+                });
+                ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for conflict in &mut plan.conflicts {
+                        draw_conflict(ui, conflict, &source);
+                        ui.separator();
+                    }
+                });
+            }
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                let has_work = !plan.new.is_empty() || !plan.conflicts.is_empty();
+                if ui
+                    .add_enabled(has_work, egui::Button::new("Import"))
+                    .clicked()
+                {
+                    outcome = Outcome::Import;
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = Outcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = Outcome::Cancel;
+    }
+    outcome
+}
+
+fn draw_conflict(ui: &mut egui::Ui, conflict: &mut ImportConflict, source: &str) {
+    let ImportConflict {
+        local,
+        incoming,
+        choice,
+        trashed,
+    } = conflict;
+    ui.label(RichText::new(&local.title).strong());
+    if *trashed {
+        ui.label("In your trash");
+    }
+    if let Some(status) = incoming.reading_status
+        && status != local.reading_status
+    {
+        ui.label(format!(
+            "Status: {} here, {} in {}",
+            format_status(&local.reading_status),
+            format_status(&status),
+            source
+        ));
+    }
+    if let Some(rating) = incoming.user_rating
+        && Some(rating) != local.user_rating
+    {
+        ui.label(format!(
+            "Rating: {} here, {} in {}",
+            stars(local.user_rating),
+            stars(Some(rating)),
+            source
+        ));
+    }
+    let (keep, take) = if *trashed {
+        (
+            "Leave in trash".to_string(),
+            format!("Restore with {}'s", source),
+        )
+    } else {
+        ("Keep mine".to_string(), format!("Use {}'s", source))
+    };
+    ui.horizontal(|ui| {
+        ui.radio_value(choice, ConflictChoice::KeepLocal, keep);
+        ui.radio_value(choice, ConflictChoice::UseIncoming, take);
+    });
+}
+
+fn stars(rating: Option<UserRating>) -> String {
+    match rating {
+        Some(rating) => "\u{2605}".repeat(rating as usize),
+        None => "unrated".to_string(),
+    }
+}
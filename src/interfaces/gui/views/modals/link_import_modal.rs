@@ -0,0 +1,103 @@
+use egui::{Context, RichText, ScrollArea, Window};
+
+use crate::domain::transfer::{FoundWork, LinkImportPlan};
+
+/// The review screen for AO3 links found in a browser's bookmarks or
+/// history. Works are ticked off in place; nothing is queued until the
+/// user confirms.
+pub struct LinkImportState {
+    pub plan: LinkImportPlan,
+    /// Where the links came from, e.g. "bookmarks".
+    pub source: String,
+}
+
+pub enum Outcome {
+    None,
+    Queue,
+    Cancel,
+}
+
+pub fn draw(ctx: &Context, state: &mut LinkImportState) -> Outcome {
+    let mut still_open = true;
+    let mut outcome = Outcome::None;
+    let plan = &mut state.plan;
+    Window::new(format!("AO3 links in {}", state.source))
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} works not in your library yet, {} already there.",
+                plan.works.len(),
+                plan.already_tracked
+            ));
+            ui.label(
+                RichText::new(
+                    "The ones you tick are fetched from AO3. A last visit sets a first guess \
+                    at the status and chapter read.",
+                )
+                .weak()
+                .italics(),
+            );
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.small_button("Select all").clicked() {
+                    plan.select_all(true);
+                }
+                if ui.small_button("Select none").clicked() {
+                    plan.select_all(false);
+                }
+            });
+            if plan.works.iter().any(|w| w.folder.is_some()) {
+                ui.checkbox(
+                    &mut plan.use_folders,
+                    "Put each fic on a shelf named after its bookmark folder",
+                );
+            }
+            ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+                for work in &mut plan.works {
+                    draw_work(ui, work, plan.use_folders);
+                }
+            });
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                let count = plan.selected().count();
+                if ui
+                    .add_enabled(count > 0, egui::Button::new(format!("Add {}", count)))
+                    .clicked()
+                {
+                    outcome = Outcome::Queue;
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = Outcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = Outcome::Cancel;
+    }
+    outcome
+}
+
+fn draw_work(ui: &mut egui::Ui, work: &mut FoundWork, use_folders: bool) {
+    let title = work
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Work {}", work.fic_id));
+    ui.checkbox(&mut work.selected, title);
+    let mut details = Vec::new();
+    if use_folders && let Some(folder) = &work.folder {
+        details.push(format!("Shelf: {}", folder));
+    }
+    if let Some(visited) = work.last_visited {
+        details.push(format!("Last visited {}", visited.format("%Y-%m-%d")));
+    }
+    if !details.is_empty() {
+        ui.indent(work.fic_id, |ui| {
+            ui.label(RichText::new(details.join(" · ")).weak());
+        });
+    }
+}
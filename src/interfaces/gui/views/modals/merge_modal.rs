@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use egui::{Context, RichText, ScrollArea, Window};
+
+use crate::domain::transfer::{
+    LibrarySnapshot, MergeConflict, MergePolicy, MergeReport, MergeSide,
+};
+
+use super::super::super::format::format_status;
+
+/// The preview of merging another library file in. `preview` is a dry
+/// run under `policy`, redone whenever the policy changes, so the
+/// conflict log always says what the merge would keep.
+pub struct MergeState {
+    pub path: PathBuf,
+    pub snapshot: LibrarySnapshot,
+    pub policy: MergePolicy,
+    pub preview: MergeReport,
+}
+
+pub enum Outcome {
+    None,
+    PolicyChanged,
+    Merge,
+    Cancel,
+}
+
+pub fn draw(ctx: &Context, state: &mut MergeState) -> Outcome {
+    let mut still_open = true;
+    let mut outcome = Outcome::None;
+    let name = state
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Window::new(format!("Merge {}", name))
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            let preview = &state.preview;
+            ui.label(format!(
+                "{} fics to add, {} to update, {} already the same.",
+                preview.fics_added, preview.fics_updated, preview.fics_unchanged
+            ));
+            ui.label(format!(
+                "{} shelves to add, {} matched by name.",
+                preview.shelves_added, preview.shelves_matched
+            ));
+            ui.label(
+                RichText::new(
+                    "Fics are matched by AO3 ID and keep whichever copy's AO3 details are \
+                    newer. The other file isn't changed.",
+                )
+                .weak()
+                .italics(),
+            );
+
+            ui.add_space(6.0);
+            ui.label("When progress, rating, note or tags differ:");
+            ui.horizontal(|ui| {
+                for policy in MergePolicy::ALL {
+                    if ui
+                        .radio_value(&mut state.policy, policy, policy.label())
+                        .changed()
+                    {
+                        outcome = Outcome::PolicyChanged;
+                    }
+                }
+            });
+
+            if !state.preview.conflicts.is_empty() {
+                ui.add_space(6.0);
+                ui.label(
+                    RichText::new(format!("{} conflicts", state.preview.conflicts.len())).strong(),
+                );
+                ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for conflict in &state.preview.conflicts {
+                        draw_conflict(ui, conflict);
+                        ui.separator();
+                    }
+                });
+            }
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.button("Merge").clicked() {
+                    outcome = Outcome::Merge;
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = Outcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = Outcome::Cancel;
+    }
+    outcome
+}
+
+fn draw_conflict(ui: &mut egui::Ui, conflict: &MergeConflict) {
+    let MergeConflict {
+        mine, theirs, kept, ..
+    } = conflict;
+    ui.label(RichText::new(&conflict.title).strong());
+    if mine.reading_status != theirs.reading_status {
+        ui.label(format!(
+            "Status: {} here, {} in the file",
+            format_status(&mine.reading_status),
+            format_status(&theirs.reading_status)
+        ));
+    }
+    if mine.last_chapter_read != theirs.last_chapter_read {
+        let chapter = |c: Option<u32>| c.map_or("none".to_string(), |c| c.to_string());
+        ui.label(format!(
+            "Last chapter read: {} here, {} in the file",
+            chapter(mine.last_chapter_read),
+            chapter(theirs.last_chapter_read)
+        ));
+    }
+    let mut others = Vec::new();
+    if mine.read_count != theirs.read_count {
+        others.push("read count");
+    }
+    if mine.user_rating != theirs.user_rating {
+        others.push("rating");
+    }
+    if mine.personal_note != theirs.personal_note {
+        others.push("note");
+    }
+    if mine.personal_tags != theirs.personal_tags {
+        others.push("personal tags");
+    }
+    if !others.is_empty() {
+        ui.label(format!("Also differs: {}", others.join(", ")));
+    }
+    let kept = match kept {
+        MergeSide::Mine => "Keeping yours",
+        MergeSide::Theirs => "Taking the file's",
+    };
+    ui.label(RichText::new(kept).weak());
+}
@@ -1,12 +1,19 @@
 //! Floating windows opened on demand from the main UI: column picker,
-//! add-fic input, shelf create/delete confirmations, and the bulk-delete
-//! confirmation. They share no internal state with the views that own
+//! add-fic input, shelf create/delete confirmations, saved-search
+//! naming, export options, the import and merge previews, and the bulk-delete and
+//! empty-trash confirmations. They share no internal state with the views that own
 //! their open/closed flags — the parent passes `&mut state` and the
 //! modal returns an Outcome enum the parent dispatches on.
 
 pub mod add_fic_dialog;
 pub mod bulk_modals;
 pub mod column_picker;
+pub mod export_modal;
+pub mod fic_import_modal;
+pub mod link_import_modal;
+pub mod merge_modal;
 pub mod quit_modal;
 pub mod restore_modal;
+pub mod saved_search_modals;
 pub mod shelf_modals;
+pub mod trash_modal;
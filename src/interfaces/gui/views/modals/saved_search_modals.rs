@@ -0,0 +1,146 @@
+use egui::{Context, Window};
+
+use crate::domain::saved_search::SavedSearch;
+
+/// Name buffer for "Save search". The query, scope and sort are taken
+/// from the app at submit time, so the modal only collects the name.
+#[derive(Default)]
+pub struct SaveState {
+    pub name: String,
+    focused_once: bool,
+}
+
+impl SaveState {
+    /// Pre-fills the name with the search text, which is usually a
+    /// decent first guess the user can type over.
+    pub fn new(query: &str) -> Self {
+        Self {
+            name: query.trim().to_string(),
+            focused_once: false,
+        }
+    }
+}
+
+pub enum SaveOutcome {
+    None,
+    Submit(String),
+    Cancel,
+}
+
+pub struct RenameState {
+    pub saved_search_id: u64,
+    pub name: String,
+    focused_once: bool,
+}
+
+impl RenameState {
+    pub fn new(search: &SavedSearch) -> Self {
+        Self {
+            saved_search_id: search.id,
+            name: search.name.clone(),
+            focused_once: false,
+        }
+    }
+}
+
+pub enum RenameOutcome {
+    None,
+    Submit {
+        saved_search_id: u64,
+        new_name: String,
+    },
+    Cancel,
+}
+
+pub fn draw_save(ctx: &Context, state: &mut SaveState) -> SaveOutcome {
+    match name_prompt(
+        ctx,
+        "Save search",
+        "Save",
+        &mut state.name,
+        &mut state.focused_once,
+    ) {
+        PromptOutcome::Submit(name) => SaveOutcome::Submit(name),
+        PromptOutcome::Cancel => SaveOutcome::Cancel,
+        PromptOutcome::None => SaveOutcome::None,
+    }
+}
+
+pub fn draw_rename(ctx: &Context, state: &mut RenameState) -> RenameOutcome {
+    match name_prompt(
+        ctx,
+        "Rename saved search",
+        "Rename",
+        &mut state.name,
+        &mut state.focused_once,
+    ) {
+        PromptOutcome::Submit(new_name) => RenameOutcome::Submit {
+            saved_search_id: state.saved_search_id,
+            new_name,
+        },
+        PromptOutcome::Cancel => RenameOutcome::Cancel,
+        PromptOutcome::None => RenameOutcome::None,
+    }
+}
+
+enum PromptOutcome {
+    None,
+    Submit(String),
+    Cancel,
+}
+
+/// Single-field name window shared by the save and rename modals. The
+/// name is fully selected on first paint so typing replaces it.
+fn name_prompt(
+    ctx: &Context,
+    title: &str,
+    submit_label: &str,
+    name: &mut String,
+    focused_once: &mut bool,
+) -> PromptOutcome {
+    let mut still_open = true;
+    let mut outcome = PromptOutcome::None;
+    Window::new(title)
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            ui.label("Name:");
+            let resp = ui.text_edit_singleline(name);
+            if !*focused_once {
+                resp.request_focus();
+                if let Some(mut tes) = egui::TextEdit::load_state(ctx, resp.id) {
+                    let end = name.chars().count();
+                    tes.cursor
+                        .set_char_range(Some(egui::text::CCursorRange::two(
+                            egui::text::CCursor::new(0),
+                            egui::text::CCursor::new(end),
+                        )));
+                    tes.store(ctx, resp.id);
+                }
+                *focused_once = true;
+            }
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                let submit_enabled = !name.trim().is_empty();
+                let submit_clicked = ui
+                    .add_enabled(submit_enabled, egui::Button::new(submit_label))
+                    .clicked();
+                let pressed_enter = resp.lost_focus()
+                    && ctx.input(|i| i.key_pressed(egui::Key::Enter))
+                    && submit_enabled;
+                if submit_clicked || pressed_enter {
+                    outcome = PromptOutcome::Submit(name.trim().to_string());
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = PromptOutcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = PromptOutcome::Cancel;
+    }
+    outcome
+}
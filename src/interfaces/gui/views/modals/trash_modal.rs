@@ -0,0 +1,43 @@
+use egui::{Context, Window};
+
+pub enum Outcome {
+    None,
+    Confirm,
+    Cancel,
+}
+
+pub fn draw_empty_confirm(ctx: &Context, fics: usize, shelves: usize) -> Outcome {
+    let mut still_open = true;
+    let mut outcome = Outcome::None;
+    Window::new("Empty trash")
+        .open(&mut still_open)
+        .resizable(false)
+        .collapsible(false)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .default_pos(ctx.content_rect().center())
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Permanently delete {} fanfiction(s) and {} shelf(s)?",
+                fics, shelves
+            ));
+            ui.add_space(6.0);
+            ui.label(
+                egui::RichText::new("This cannot be undone.")
+                    .weak()
+                    .italics(),
+            );
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.button("Empty trash").clicked() {
+                    outcome = Outcome::Confirm;
+                }
+                if ui.button("Cancel").clicked() {
+                    outcome = Outcome::Cancel;
+                }
+            });
+        });
+    if !still_open {
+        outcome = Outcome::Cancel;
+    }
+    outcome
+}
@@ -1,20 +1,39 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use chrono::NaiveDate;
 use egui::{RichText, ScrollArea, Ui};
 
-use super::super::config::{self, AppConfig, TEXT_ZOOM_RANGE, ThemeChoice};
+use super::super::config::{
+    self, AppConfig, BACKUP_KEEP_RANGE, TEXT_ZOOM_RANGE, TRASH_PURGE_DAYS_RANGE, ThemeChoice,
+};
 use super::super::format::erisian_date;
+use crate::domain::backup::BackupInfo;
+use crate::infrastructure::external::ao3::fetcher::{ALT_AO3_URL, PRIMARY_AO3_URL, PROXY_AO3_URL};
+use crate::interfaces::utils::formatter::format_file_size;
 use crate::version::{LICENSE, RELEASE_DATE, VERSION};
 
 const ZOOM_STEP: f32 = 0.1;
+const DEFAULT_TRASH_PURGE_DAYS: u32 = 30;
+/// The sites "Continue reading" can open fics on, with their labels.
+const READING_MIRRORS: [(&str, &str); 3] = [
+    (PRIMARY_AO3_URL, "archiveofourown.org"),
+    (ALT_AO3_URL, "archiveofourown.gay"),
+    (PROXY_AO3_URL, "Proxy (xn--iao3-lw4b.ws)"),
+];
 
-/// A click on one of the Library buttons. The native file picker is opened
-/// by the app layer (which owns the window handle needed to parent the
-/// dialog), not here.
+/// A click on one of the Library or Backups buttons. The native file picker
+/// is opened by the app layer (which owns the window handle needed to parent
+/// the dialog), not here.
 pub enum LibraryRequest {
     ChangeLocation,
     Restore,
+    /// One of the listed backups, which skips the picker.
+    RestoreBackup(PathBuf),
+    BackUpNow,
+    Merge,
+    ImportCalibre,
+    ImportBookmarks,
+    ImportFirefox,
 }
 
 pub struct SettingsOutcome {
@@ -22,7 +41,13 @@ pub struct SettingsOutcome {
     pub request: Option<LibraryRequest>,
 }
 
-pub fn draw(ui: &mut Ui, config: &mut AppConfig, current_db_path: &Path) -> SettingsOutcome {
+/// `backups` are the library's own, newest first.
+pub fn draw(
+    ui: &mut Ui,
+    config: &mut AppConfig,
+    current_db_path: &Path,
+    backups: &[BackupInfo],
+) -> SettingsOutcome {
     let mut changed = false;
     let mut request = None;
 
@@ -95,12 +120,164 @@ pub fn draw(ui: &mut Ui, config: &mut AppConfig, current_db_path: &Path) -> Sett
                 if ui.button("Restore from backup…").clicked() {
                     request = Some(LibraryRequest::Restore);
                 }
+                if ui.button("Merge another library…").clicked() {
+                    request = Some(LibraryRequest::Merge);
+                }
             });
             ui.label(
                 RichText::new(
                     "Copy this file elsewhere to back up your library. Restore replaces your \
                     current library with a backup .db, copying it here without changing the \
-                    location above. Changes take effect after a restart.",
+                    location above. Changes take effect after a restart. Merge instead adds \
+                    another library's fics and shelves to this one, e.g. from another computer.",
+                )
+                .weak()
+                .italics(),
+            );
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if ui.button("Import from Calibre…").clicked() {
+                    request = Some(LibraryRequest::ImportCalibre);
+                }
+                if ui.button("Import bookmarks…").clicked() {
+                    request = Some(LibraryRequest::ImportBookmarks);
+                }
+                if ui.button("Import Firefox history…").clicked() {
+                    request = Some(LibraryRequest::ImportFirefox);
+                }
+            });
+            ui.label(
+                RichText::new(
+                    "Adds the AO3 works in a Calibre library kept with FanFicFare, with its \
+                    read column and ratings, or the ones linked from a browser's bookmarks \
+                    export or a copy of Firefox's places.sqlite. You'll see what was found \
+                    before anything is added.",
+                )
+                .weak()
+                .italics(),
+            );
+
+            ui.add_space(12.0);
+            ui.label(RichText::new("Backups").strong());
+            ui.horizontal(|ui| {
+                ui.label("Keep the last");
+                if ui
+                    .add(egui::DragValue::new(&mut config.backup_keep).range(BACKUP_KEEP_RANGE))
+                    .changed()
+                {
+                    changed = true;
+                }
+                ui.label("backups of each kind");
+                if ui.button("Back up now").clicked() {
+                    request = Some(LibraryRequest::BackUpNow);
+                }
+            });
+            ui.label(
+                RichText::new(
+                    "Taken once a day, when Ficflow closes, and before an upgrade, a wipe or a \
+                    restore, into a backups folder next to the library.",
+                )
+                .weak()
+                .italics(),
+            );
+            if backups.is_empty() {
+                ui.label(RichText::new("No backups yet.").weak());
+            } else {
+                egui::Grid::new("settings-backups")
+                    .num_columns(4)
+                    .spacing([12.0, 4.0])
+                    .show(ui, |ui| {
+                        for backup in backups {
+                            ui.label(
+                                backup
+                                    .created_at
+                                    .with_timezone(&chrono::Local)
+                                    .format("%Y-%m-%d %H:%M")
+                                    .to_string(),
+                            );
+                            ui.label(RichText::new(backup.reason.as_str()).weak());
+                            ui.label(format_file_size(backup.size_bytes));
+                            if ui.small_button("Restore…").clicked() {
+                                request = Some(LibraryRequest::RestoreBackup(backup.path.clone()));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            ui.add_space(12.0);
+            ui.label(RichText::new("Trash").strong());
+            ui.horizontal(|ui| {
+                let mut auto_purge = config.trash_auto_purge_days.is_some();
+                if ui
+                    .checkbox(&mut auto_purge, "Empty automatically after")
+                    .changed()
+                {
+                    config.trash_auto_purge_days = auto_purge.then_some(DEFAULT_TRASH_PURGE_DAYS);
+                    changed = true;
+                }
+                let mut days = config
+                    .trash_auto_purge_days
+                    .unwrap_or(DEFAULT_TRASH_PURGE_DAYS);
+                let drag = ui.add_enabled(
+                    auto_purge,
+                    egui::DragValue::new(&mut days)
+                        .range(TRASH_PURGE_DAYS_RANGE)
+                        .suffix(" days"),
+                );
+                if drag.changed() {
+                    config.trash_auto_purge_days = Some(days);
+                    changed = true;
+                }
+            });
+            ui.label(
+                RichText::new(
+                    "Deleted fics and shelves older than this are removed for good the next \
+                    time the library is opened.",
+                )
+                .weak()
+                .italics(),
+            );
+
+            ui.add_space(12.0);
+            ui.label(RichText::new("Reading").strong());
+            ui.horizontal(|ui| {
+                ui.label("Open fics on");
+                for (url, label) in READING_MIRRORS {
+                    if ui
+                        .selectable_label(config.reading_base_url() == url, label)
+                        .clicked()
+                        && config.reading_base_url() != url
+                    {
+                        config.ao3_mirror = (url != PRIMARY_AO3_URL).then(|| url.to_string());
+                        changed = true;
+                    }
+                }
+            });
+            if ui
+                .checkbox(
+                    &mut config.open_marks_in_progress,
+                    "Mark planned and paused fics In Progress when opened",
+                )
+                .changed()
+            {
+                changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Open links with");
+                let mut opener = config.opener.clone().unwrap_or_default();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut opener).hint_text("System default"))
+                    .changed()
+                {
+                    config.opener = Some(opener).filter(|o| !o.trim().is_empty());
+                    changed = true;
+                }
+            });
+            ui.label(
+                RichText::new(
+                    "A command such as \"firefox --new-tab\", given the chapter's link as its \
+                    last argument. Leave it empty for your default browser.",
                 )
                 .weak()
                 .italics(),
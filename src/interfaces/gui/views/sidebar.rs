@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use egui::{Color32, RichText, Stroke, StrokeKind, Ui};
 
 use crate::domain::fanfiction::ReadingStatus;
+use crate::domain::saved_search::SavedSearch;
 use crate::domain::shelf::{Shelf, ShelfKind};
 
 use super::super::theme;
@@ -22,9 +23,15 @@ pub struct SidebarState<'a> {
     /// Mutated in-place by row clicks; caller diffs against `prev_view`.
     pub current_view: &'a mut View,
     pub shelves: &'a [Shelf],
+    /// In sidebar order; the section is hidden while empty.
+    pub saved_searches: &'a [SavedSearch],
     pub library_counts: &'a LibraryCounts,
     /// Missing shelf ids render as 0.
     pub shelf_counts: &'a HashMap<u64, usize>,
+    /// Missing saved-search ids render as 0.
+    pub saved_search_counts: &'a HashMap<u64, usize>,
+    /// Trashed fics and shelves together.
+    pub trash_count: usize,
     pub running_tasks: usize,
 }
 
@@ -45,6 +52,12 @@ pub enum Outcome {
         new_parent: Option<u64>,
     },
     TogglePinShelf(u64),
+    OpenRenameSavedSearchModal(u64),
+    MoveSavedSearch {
+        saved_search_id: u64,
+        new_position: u32,
+    },
+    DeleteSavedSearch(u64),
 }
 
 /// Dnd payload for dragging a shelf row — distinct from the `Vec<u64>`
@@ -56,13 +69,16 @@ pub fn draw(ui: &mut Ui, state: SidebarState<'_>) -> Outcome {
     let SidebarState {
         current_view,
         shelves,
+        saved_searches,
         library_counts,
         shelf_counts,
+        saved_search_counts,
+        trash_count,
         running_tasks,
     } = state;
     let mut outcome = Outcome::None;
 
-    // Pin Tasks/Settings to the bottom.
+    // Pin Discover/Trash/Tasks/Settings to the bottom.
     // `Frame::none()` because the panel's default inner_margin (~8px each
     // side) was making Library + Tasks/Settings rows narrower than the
     // edge-to-edge shelf rows in the (Frame::none) central panel below.
@@ -74,6 +90,24 @@ pub fn draw(ui: &mut Ui, state: SidebarState<'_>) -> Outcome {
         .frame(egui::Frame::NONE)
         .show_inside(ui, |ui| {
             ui.add_space(6.0);
+            view_row(
+                ui,
+                current_view,
+                View::Discover,
+                "Discover",
+                None,
+                None,
+                None,
+            );
+            view_row(
+                ui,
+                current_view,
+                View::Trash,
+                "Trash",
+                None,
+                Some(trash_count),
+                None,
+            );
             view_row(
                 ui,
                 current_view,
@@ -95,10 +129,10 @@ pub fn draw(ui: &mut Ui, state: SidebarState<'_>) -> Outcome {
             ui.add_space(6.0);
         });
 
-    // Pin LIBRARY section + the SHELVES header to the top so the user
-    // never loses access to status filters or the "+ shelf" button when
-    // a long shelf list scrolls. Only the shelf rows themselves go in
-    // the scrollable Central area below.
+    // Pin LIBRARY section, saved searches + the SHELVES header to the top
+    // so the user never loses access to status filters or the "+ shelf"
+    // button when a long shelf list scrolls. Only the shelf rows themselves
+    // go in the scrollable Central area below.
     egui::Panel::top("ficflow-sidebar-top")
         .resizable(false)
         .show_separator_line(false)
@@ -163,10 +197,24 @@ pub fn draw(ui: &mut Ui, state: SidebarState<'_>) -> Outcome {
             );
 
             // Match the line above Tasks/Settings — same separator
-            // style so the three sections feel like peers.
+            // style so the sections feel like peers.
             ui.add_space(8.0);
             ui.separator();
             ui.add_space(4.0);
+            if !saved_searches.is_empty() {
+                section_label(ui, "SAVED SEARCHES");
+                ui.add_space(2.0);
+                saved_search_rows(
+                    ui,
+                    current_view,
+                    saved_searches,
+                    saved_search_counts,
+                    &mut outcome,
+                );
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(4.0);
+            }
             match shelves_header(ui) {
                 HeaderOutcome::None => {}
                 HeaderOutcome::AddClicked => {
@@ -222,6 +270,7 @@ const LIBRARY_ICON_READ: &str = "\u{2713}"; // ✓
 const LIBRARY_ICON_PLAN: &str = "\u{25CB}"; // ○
 const LIBRARY_ICON_PAUSED: &str = "\u{23F8}"; // ⏸
 const LIBRARY_ICON_ABANDONED: &str = "\u{2717}"; // ✗
+const SAVED_SEARCH_ICON: &str = "\u{1F50D}"; // 🔍
 
 /// Horizontal breathing room between visual content (highlight rounding,
 /// badges, the SHELVES "+" button) and the panel edges. Without it the
@@ -402,6 +451,66 @@ fn shelf_rows(
     }
 }
 
+/// Saved searches are a flat, user-ordered list, so reordering goes
+/// through the context menu rather than drag-and-drop (which the shelf
+/// rows already use for nesting).
+fn saved_search_rows(
+    ui: &mut Ui,
+    current_view: &mut View,
+    saved_searches: &[SavedSearch],
+    saved_search_counts: &HashMap<u64, usize>,
+    outcome: &mut Outcome,
+) {
+    let last = saved_searches.len().saturating_sub(1);
+    for (index, search) in saved_searches.iter().enumerate() {
+        let count = saved_search_counts.get(&search.id).copied().unwrap_or(0);
+        let (resp, _, _) = view_row(
+            ui,
+            current_view,
+            View::SavedSearch(search.id),
+            &search.name,
+            Some(SAVED_SEARCH_ICON),
+            Some(count),
+            None,
+        );
+        let resp = if search.query.is_empty() {
+            resp
+        } else {
+            resp.on_hover_text(&search.query)
+        };
+        resp.context_menu(|ui| {
+            if ui.button("Rename saved search").clicked() {
+                *outcome = Outcome::OpenRenameSavedSearchModal(search.id);
+                ui.close();
+            }
+            if ui
+                .add_enabled(index > 0, egui::Button::new("Move up"))
+                .clicked()
+            {
+                *outcome = Outcome::MoveSavedSearch {
+                    saved_search_id: search.id,
+                    new_position: index as u32 - 1,
+                };
+                ui.close();
+            }
+            if ui
+                .add_enabled(index < last, egui::Button::new("Move down"))
+                .clicked()
+            {
+                *outcome = Outcome::MoveSavedSearch {
+                    saved_search_id: search.id,
+                    new_position: index as u32 + 1,
+                };
+                ui.close();
+            }
+            if ui.button("Delete saved search").clicked() {
+                *outcome = Outcome::DeleteSavedSearch(search.id);
+                ui.close();
+            }
+        });
+    }
+}
+
 fn section_label(ui: &mut Ui, text: &str) {
     ui.horizontal(|ui| {
         ui.add_space(INNER_MARGIN_X + 8.0);
@@ -495,7 +604,7 @@ fn view_row(
     };
 
     // Left side: icon column reserved only when there *is* an icon. Rows
-    // without one (Shelves, Tasks, Settings) sit flush-left with no
+    // without one (Shelves, Trash, Tasks, Settings) sit flush-left with no
     // phantom indent. Tree rows (shelves) get a depth indent plus a
     // disclosure-triangle column instead, reserved for leaves too so
     // sibling labels line up.
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Local, Utc};
+use egui::{Align, Layout, RichText, ScrollArea, Ui};
+
+use crate::domain::trash::{TrashedFic, TrashedShelf};
+
+pub struct TrashViewState<'a> {
+    pub fics: &'a [TrashedFic],
+    pub shelves: &'a [TrashedShelf],
+}
+
+/// At most one button is clicked per frame.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    None,
+    RestoreFic(u64),
+    PurgeFic(u64),
+    RestoreShelf(u64),
+    PurgeShelf(u64),
+    RequestEmpty,
+}
+
+pub fn draw(ui: &mut Ui, state: TrashViewState<'_>) -> Outcome {
+    let TrashViewState { fics, shelves } = state;
+    let mut outcome = Outcome::None;
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!(
+                "{} fanfiction(s), {} shelf(s)",
+                fics.len(),
+                shelves.len()
+            ))
+            .weak(),
+        );
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let empty = fics.is_empty() && shelves.is_empty();
+            if ui
+                .add_enabled(!empty, egui::Button::new("Empty trash"))
+                .clicked()
+            {
+                outcome = Outcome::RequestEmpty;
+            }
+        });
+    });
+    ui.separator();
+
+    if fics.is_empty() && shelves.is_empty() {
+        ui.add_space(8.0);
+        ui.label(RichText::new("The trash is empty.").italics().weak());
+        return outcome;
+    }
+
+    ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            if !shelves.is_empty() {
+                ui.label(RichText::new("Shelves").strong());
+                for trashed in shelves {
+                    let shelf = &trashed.shelf;
+                    match trash_row(ui, &shelf.name, None, trashed.deleted_at) {
+                        RowAction::Restore => outcome = Outcome::RestoreShelf(shelf.id),
+                        RowAction::Purge => outcome = Outcome::PurgeShelf(shelf.id),
+                        RowAction::None => {}
+                    }
+                }
+                ui.add_space(12.0);
+            }
+            if !fics.is_empty() {
+                ui.label(RichText::new("Fanfictions").strong());
+                for trashed in fics {
+                    let fic = &trashed.fic;
+                    let authors = fic.authors.join(", ");
+                    match trash_row(ui, &fic.title, Some(&authors), trashed.deleted_at) {
+                        RowAction::Restore => outcome = Outcome::RestoreFic(fic.id),
+                        RowAction::Purge => outcome = Outcome::PurgeFic(fic.id),
+                        RowAction::None => {}
+                    }
+                }
+            }
+        });
+    outcome
+}
+
+enum RowAction {
+    None,
+    Restore,
+    Purge,
+}
+
+fn trash_row(
+    ui: &mut Ui,
+    name: &str,
+    detail: Option<&str>,
+    deleted_at: DateTime<Utc>,
+) -> RowAction {
+    let mut action = RowAction::None;
+    ui.horizontal(|ui| {
+        ui.add(egui::Label::new(name).truncate().selectable(false));
+        if let Some(detail) = detail {
+            ui.label(RichText::new(detail).weak());
+        }
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if ui.button("Delete forever").clicked() {
+                action = RowAction::Purge;
+            }
+            if ui.button("Restore").clicked() {
+                action = RowAction::Restore;
+            }
+            let local = deleted_at.with_timezone(&Local);
+            ui.label(RichText::new(format!("Deleted {}", local.format("%-d %b %Y"))).weak())
+                .on_hover_text(local.format("%-d %B %Y, %H:%M").to_string());
+        });
+    });
+    ui.separator();
+    action
+}
@@ -0,0 +1,35 @@
+use egui::{Align2, Context, RichText, Vec2};
+
+/// How long the prompt stays up after an edit.
+pub const UNDO_PROMPT_SECS: u64 = 6;
+
+pub enum Outcome {
+    None,
+    Undo,
+    Dismiss,
+}
+
+/// A toast-like strip with an "Undo" button. egui-notify toasts can't
+/// carry buttons, so this one is drawn by hand at the bottom of the
+/// window.
+pub fn draw(ctx: &Context, label: &str) -> Outcome {
+    let mut outcome = Outcome::None;
+    egui::Area::new(egui::Id::new("ficflow-undo-prompt"))
+        .anchor(Align2::CENTER_BOTTOM, Vec2::new(0.0, -24.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    ui.add_space(12.0);
+                    if ui.button(RichText::new("Undo").strong()).clicked() {
+                        outcome = Outcome::Undo;
+                    }
+                    if ui.small_button("\u{2715}").clicked() {
+                        outcome = Outcome::Dismiss;
+                    }
+                });
+            });
+        });
+    outcome
+}
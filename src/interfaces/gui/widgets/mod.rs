@@ -1,3 +1,4 @@
 pub mod autocomplete_input;
+pub mod personal_tags;
 pub mod shelves_dropdown;
 pub mod star_rating;
@@ -0,0 +1,60 @@
+use egui::{RichText, TextEdit, Ui};
+
+pub enum TagEdit {
+    Add(String),
+    Remove(String),
+}
+
+/// The reader's own tags as removable chips, followed by a small field
+/// that adds a tag on Enter. `partial` tags (carried by only some of a
+/// multi-selection) are drawn weak; removing one still removes it from
+/// every fic that has it.
+///
+/// The draft text lives in egui temp memory under `id_salt`, so callers
+/// should salt per fic (or per selection) to keep drafts apart.
+pub fn personal_tags(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    tags: &[String],
+    partial: &[String],
+) -> Option<TagEdit> {
+    let mut edit = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+        for (tag, weak) in tags
+            .iter()
+            .map(|t| (t, false))
+            .chain(partial.iter().map(|t| (t, true)))
+        {
+            let text = RichText::new(format!("{}  \u{2715}", tag)).small();
+            let text = if weak { text.weak() } else { text };
+            let hover = if weak {
+                "On some of the selected fics — click to remove"
+            } else {
+                "Click to remove"
+            };
+            if ui.small_button(text).on_hover_text(hover).clicked() {
+                edit = Some(TagEdit::Remove(tag.clone()));
+            }
+        }
+
+        let id = ui.id().with(("personal-tag-draft", id_salt));
+        let mut draft: String = ui
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_default();
+        let resp = ui.add(
+            TextEdit::singleline(&mut draft)
+                .hint_text("+ tag")
+                .desired_width(80.0),
+        );
+        if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let tag = draft.trim().to_string();
+            draft.clear();
+            if !tag.is_empty() {
+                edit = Some(TagEdit::Add(tag));
+            }
+        }
+        ui.data_mut(|d| d.insert_temp(id, draft));
+    });
+    edit
+}
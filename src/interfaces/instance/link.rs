@@ -0,0 +1,124 @@
+//! `ficflow://` links, so a page or bookmarklet can add a work to the
+//! open window:
+//!
+//! ```text
+//! ficflow://add?url=https%3A%2F%2Farchiveofourown.org%2Fworks%2F123
+//! ```
+//!
+//! The desktop starts `ficflow <link>` for them once the `.desktop` entry
+//! below is installed and registered as the scheme's handler.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use reqwest::Url;
+
+use super::Message;
+use crate::error::FicflowError;
+use crate::interfaces::utils::url_parser::extract_ao3_id;
+
+pub const SCHEME: &str = "ficflow";
+
+/// The entry `install` writes, apart from the app's own launcher entry
+/// so it stays out of application menus.
+const DESKTOP_FILE: &str = "ficflow-url-handler.desktop";
+
+/// True for anything that looks like a `ficflow:` link, valid or not.
+pub fn is_link(arg: &str) -> bool {
+    arg.len() > SCHEME.len()
+        && arg[..SCHEME.len()].eq_ignore_ascii_case(SCHEME)
+        && arg[SCHEME.len()..].starts_with(':')
+}
+
+/// The message a link asks for. Only `add` exists so far, naming the
+/// work as `url` (a link into it, or its ID).
+pub fn parse_link(link: &str) -> Result<Message, FicflowError> {
+    let invalid = |reason: &str| {
+        FicflowError::InvalidInput(format!("Invalid ficflow link '{}': {}", link, reason))
+    };
+    let url = Url::parse(link.trim()).map_err(|_| invalid("not a URL"))?;
+    if url.scheme() != SCHEME {
+        return Err(invalid("expected a ficflow:// link"));
+    }
+    match url.host_str() {
+        Some("add") => {
+            let work = url
+                .query_pairs()
+                .find(|(key, _)| key == "url")
+                .map(|(_, value)| value.trim().to_string())
+                .ok_or_else(|| invalid("missing ?url="))?;
+            extract_ao3_id(&work).map_err(|_| invalid("url isn't an AO3 work"))?;
+            Ok(Message::Add { fic: work })
+        }
+        _ => Err(invalid("the only action is ficflow://add")),
+    }
+}
+
+/// A desktop entry that hands `ficflow://` links to `exe`.
+pub fn desktop_entry(exe: &Path) -> String {
+    // The spec's quoting for `Exec`: double quotes, with `"`, `` ` ``,
+    // `$` and `\` escaped inside them.
+    let mut quoted = String::new();
+    for c in exe.display().to_string().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Ficflow\n\
+         Comment=Add AO3 works from ficflow:// links\n\
+         Exec=\"{}\" %u\n\
+         Icon=ficflow\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        quoted, SCHEME
+    )
+}
+
+/// Where `install` puts the entry.
+pub fn desktop_file_path() -> Option<PathBuf> {
+    Some(
+        dirs_next::data_dir()?
+            .join("applications")
+            .join(DESKTOP_FILE),
+    )
+}
+
+/// What `install` did. `registered` is false when `xdg-mime` couldn't
+/// be run, leaving the desktop to pick the entry up on its own.
+pub struct Installed {
+    pub desktop_file: PathBuf,
+    pub registered: bool,
+}
+
+/// Writes the entry for `exe` and makes it the scheme's handler. Only
+/// freedesktop desktops read `.desktop` files; macOS and Windows learn
+/// URL schemes from the app bundle and the registry instead.
+pub fn install(exe: &Path) -> Result<Installed, FicflowError> {
+    if !cfg!(all(unix, not(target_os = "macos"))) {
+        return Err(FicflowError::Other(
+            "ficflow:// links can only be registered on Linux and BSD desktops".into(),
+        ));
+    }
+    let desktop_file = desktop_file_path()
+        .ok_or_else(|| FicflowError::Other("no data directory available".into()))?;
+    if let Some(parent) = desktop_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&desktop_file, desktop_entry(exe))?;
+
+    let registered = Command::new("xdg-mime")
+        .args(["default", DESKTOP_FILE])
+        .arg(format!("x-scheme-handler/{}", SCHEME))
+        .status()
+        .is_ok_and(|status| status.success());
+    Ok(Installed {
+        desktop_file,
+        registered,
+    })
+}
@@ -0,0 +1,345 @@
+//! One window per library. The first GUI to open a library listens on a
+//! Unix socket named after it. From then on a second launch brings that
+//! window forward, and `ficflow add` and `ficflow://` links hand the fic
+//! to it so the window adds it itself. Any other CLI command that writes
+//! is refused while the window is open. `ficflow serve` and the native
+//! host run alongside it instead, and send `reload` after each write so
+//! the window's `LibraryCache` catches up. A connection carries one
+//! message, a JSON object on a line such as
+//!
+//! ```json
+//! {"type": "add", "fic": "https://archiveofourown.org/works/123"}
+//! ```
+//!
+//! which the window answers with `{"ok": true}` once it has queued it.
+//! Other platforms have no socket, so there every launch runs on its own.
+
+pub mod link;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+#[cfg(unix)]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FicflowError;
+
+/// How long either side waits on the other before giving up.
+#[cfg(unix)]
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// Bring the window to the front; what a second `ficflow` sends.
+    Focus,
+    /// Queue an add on the window's task list. `fic` is an AO3 ID or
+    /// any link into the work.
+    Add { fic: String },
+    /// Something else wrote to the library; reload what's shown.
+    Reload,
+}
+
+/// Where the window for the library at `db_path` listens: the runtime
+/// folder, or else a `ficflow-<uid>` folder in the temp folder, under a
+/// hash of the library's path since socket paths are limited to about
+/// 100 bytes. The folder is resolved so a symlinked or relative path to
+/// the same library finds the same socket.
+pub fn socket_path(db_path: &Path) -> PathBuf {
+    let resolved = match (db_path.parent(), db_path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| db_path.to_path_buf()),
+        _ => db_path.to_path_buf(),
+    };
+    // FNV-1a rather than `DefaultHasher`, whose output may change
+    // between Rust releases and so between two ficflow builds.
+    let hash = resolved
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    let dir = dirs_next::runtime_dir().unwrap_or_else(private_temp_dir);
+    dir.join(format!("ficflow-{:016x}.sock", hash))
+}
+
+/// The temp folder is shared by every user, so the sockets go in a
+/// folder of this user's own inside it; `unix::bind` makes sure nobody
+/// else owns or can enter it.
+fn private_temp_dir() -> PathBuf {
+    #[cfg(unix)]
+    {
+        // SAFETY: `getuid` has no preconditions and can't fail.
+        let uid = unsafe { libc::getuid() };
+        std::env::temp_dir().join(format!("ficflow-{}", uid))
+    }
+    #[cfg(not(unix))]
+    {
+        std::env::temp_dir()
+    }
+}
+
+/// What `Instance::claim` found.
+pub enum Claim {
+    /// No window had the library; this process has it now.
+    First(Instance),
+    /// A window already has the library and answers on the socket.
+    Running,
+}
+
+/// This process's hold on a library, and the messages sent to it. The
+/// socket is removed when it's dropped.
+pub struct Instance {
+    inbox: Arc<Inbox>,
+    /// `None` on platforms without Unix sockets.
+    socket: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct Inbox {
+    messages: Mutex<Vec<Message>>,
+    /// Called after each delivery, so an idle window wakes to read it.
+    wake: Mutex<Option<Box<dyn Fn() + Send>>>,
+    closed: AtomicBool,
+}
+
+impl Inbox {
+    fn deliver(&self, message: Message) {
+        self.messages.lock().unwrap().push(message);
+        if let Some(wake) = self.wake.lock().unwrap().as_ref() {
+            wake();
+        }
+    }
+}
+
+impl Instance {
+    /// Takes the library whose socket is `socket`, unless a window that
+    /// still answers has it. A socket left behind by a window that
+    /// crashed is taken over.
+    pub fn claim(socket: &Path) -> Result<Claim, FicflowError> {
+        #[cfg(unix)]
+        {
+            let Some(listener) = unix::bind(socket)? else {
+                return Ok(Claim::Running);
+            };
+            let inbox = Arc::new(Inbox::default());
+            let listening = Arc::clone(&inbox);
+            std::thread::Builder::new()
+                .name("ficflow-instance".into())
+                .spawn(move || unix::listen(listener, &listening))
+                .map_err(FicflowError::from)?;
+            Ok(Claim::First(Instance {
+                inbox,
+                socket: Some(socket.to_path_buf()),
+            }))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = socket;
+            Ok(Claim::First(Instance {
+                inbox: Arc::new(Inbox::default()),
+                socket: None,
+            }))
+        }
+    }
+
+    /// Queues `message` as if it had come over the socket, e.g. the link
+    /// the window was started with.
+    pub fn push(&self, message: Message) {
+        self.inbox.deliver(message);
+    }
+
+    /// The messages received since the last call, oldest first.
+    pub fn take_messages(&self) -> Vec<Message> {
+        std::mem::take(&mut *self.inbox.messages.lock().unwrap())
+    }
+
+    /// Runs `wake` on the listening thread whenever a message arrives.
+    pub fn wake_with(&self, wake: impl Fn() + Send + 'static) {
+        *self.inbox.wake.lock().unwrap() = Some(Box::new(wake));
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        let Some(socket) = &self.socket else {
+            return;
+        };
+        self.inbox.closed.store(true, Ordering::SeqCst);
+        // The listening thread is blocked in `accept`; a connection
+        // wakes it to see the flag.
+        #[cfg(unix)]
+        let _ = std::os::unix::net::UnixStream::connect(socket);
+        let _ = std::fs::remove_file(socket);
+    }
+}
+
+/// Hands `message` to the window that has the library at `socket`.
+/// `Ok(false)` when no window is listening, so the caller does the work
+/// itself.
+pub fn send(socket: &Path, message: &Message) -> Result<bool, FicflowError> {
+    #[cfg(unix)]
+    {
+        unix::send(socket, message)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (socket, message);
+        Ok(false)
+    }
+}
+
+/// Whether a window has the library at `socket` open.
+pub fn is_open(socket: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        // An empty connection is how `unix::bind` checks too.
+        std::os::unix::net::UnixStream::connect(socket).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = socket;
+        false
+    }
+}
+
+/// Asks the window open on the library at `db_path`, if any, to reload
+/// after this process wrote to it. Failing only leaves the window
+/// behind, so it's logged rather than returned.
+pub fn request_reload(db_path: &Path) {
+    if let Err(err) = send(&socket_path(db_path), &Message::Reload) {
+        log::warn!("Failed to tell the open Ficflow window to reload: {}", err);
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::{self, DirBuilder};
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::atomic::Ordering;
+
+    use serde_json::{Value, json};
+
+    use super::{Inbox, Message, REPLY_TIMEOUT};
+    use crate::error::FicflowError;
+
+    /// The listener, or `None` when a live window already has `socket`.
+    pub(super) fn bind(socket: &Path) -> Result<Option<UnixListener>, FicflowError> {
+        if let Some(parent) = socket.parent() {
+            private_dir(parent)?;
+        }
+        let listener = match UnixListener::bind(socket) {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                if UnixStream::connect(socket).is_ok() {
+                    return Ok(None);
+                }
+                fs::remove_file(socket)?;
+                UnixListener::bind(socket)?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        // Anyone who can connect can add to the library. The folder
+        // already keeps others out; this covers a copied or moved socket.
+        fs::set_permissions(socket, fs::Permissions::from_mode(0o600))?;
+        Ok(Some(listener))
+    }
+
+    /// Creates `dir` readable by this user alone, or checks that it
+    /// already is, before a socket is bound in it. Someone else's folder,
+    /// or a symlink planted where it should be, is refused rather than
+    /// listened in.
+    fn private_dir(dir: &Path) -> Result<(), FicflowError> {
+        match DirBuilder::new().recursive(true).mode(0o700).create(dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+        let metadata = fs::symlink_metadata(dir)?;
+        // SAFETY: `getuid` has no preconditions and can't fail.
+        let uid = unsafe { libc::getuid() };
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(FicflowError::Other(format!(
+                "{} must be a folder only you can open",
+                dir.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Answers connections one at a time until the instance is dropped.
+    pub(super) fn listen(listener: UnixListener, inbox: &Inbox) {
+        for stream in listener.incoming() {
+            if inbox.closed.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    if let Err(err) = answer(&stream, inbox) {
+                        log::warn!("Failed to answer another ficflow: {}", err);
+                    }
+                }
+                Err(err) => log::warn!("Failed to accept a connection: {}", err),
+            }
+        }
+    }
+
+    fn answer(stream: &UnixStream, inbox: &Inbox) -> io::Result<()> {
+        stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        if line.trim().is_empty() {
+            // `bind`'s check that the window is alive.
+            return Ok(());
+        }
+        let reply = match serde_json::from_str::<Message>(&line) {
+            Ok(message) => {
+                inbox.deliver(message);
+                json!({ "ok": true })
+            }
+            Err(e) => json!({ "ok": false, "error": format!("unrecognized message: {}", e) }),
+        };
+        writeln!(&mut &*stream, "{}", reply)
+    }
+
+    pub(super) fn send(socket: &Path, message: &Message) -> Result<bool, FicflowError> {
+        let mut stream = match UnixStream::connect(socket) {
+            Ok(stream) => stream,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return Ok(false);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+        writeln!(stream, "{}", serde_json::to_string(message)?)?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply)?;
+        if reply.trim().is_empty() {
+            return Err(FicflowError::Other(
+                "the open Ficflow window closed without answering".into(),
+            ));
+        }
+        let reply: Value = serde_json::from_str(&reply)?;
+        if reply["ok"] == true {
+            Ok(true)
+        } else {
+            Err(FicflowError::Other(format!(
+                "the open Ficflow window refused the message: {}",
+                reply["error"]
+            )))
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub mod cli;
 pub mod gui;
+pub mod instance;
+pub mod native_host;
+pub mod server;
+pub mod tui;
 pub mod utils;
@@ -0,0 +1,214 @@
+//! The manifest that tells a browser how to start the host. A browser
+//! runs the manifest's `path` with only its own arguments, so the path
+//! is a small launcher script that runs `ficflow native-host`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::error::FicflowError;
+
+/// What extensions pass to `connectNative` / `sendNativeMessage`.
+pub const HOST_NAME: &str = "ficflow";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Browser {
+    Firefox,
+    Chrome,
+    Chromium,
+    Brave,
+    Edge,
+}
+
+impl Browser {
+    pub const NAMES: [&'static str; 5] = ["firefox", "chrome", "chromium", "brave", "edge"];
+
+    pub fn parse(input: &str) -> Result<Browser, FicflowError> {
+        match input.trim().to_lowercase().as_str() {
+            "firefox" => Ok(Browser::Firefox),
+            "chrome" => Ok(Browser::Chrome),
+            "chromium" => Ok(Browser::Chromium),
+            "brave" => Ok(Browser::Brave),
+            "edge" => Ok(Browser::Edge),
+            other => Err(FicflowError::InvalidInput(format!(
+                "Unknown browser: '{}'. Valid options are: {}",
+                other,
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+
+    /// Everything but Firefox takes Chromium's manifest.
+    fn is_chromium(self) -> bool {
+        self != Browser::Firefox
+    }
+
+    /// The per-user folder the browser looks for host manifests in.
+    /// `None` on Windows, where hosts are found through the registry.
+    pub fn manifest_dir(self) -> Option<PathBuf> {
+        if cfg!(target_os = "macos") {
+            let support = dirs_next::data_dir()?;
+            let vendor = match self {
+                Browser::Firefox => "Mozilla",
+                Browser::Chrome => "Google/Chrome",
+                Browser::Chromium => "Chromium",
+                Browser::Brave => "BraveSoftware/Brave-Browser",
+                Browser::Edge => "Microsoft Edge",
+            };
+            Some(support.join(vendor).join("NativeMessagingHosts"))
+        } else if cfg!(windows) {
+            None
+        } else {
+            match self {
+                Browser::Firefox => {
+                    Some(dirs_next::home_dir()?.join(".mozilla/native-messaging-hosts"))
+                }
+                _ => {
+                    let vendor = match self {
+                        Browser::Chrome => "google-chrome",
+                        Browser::Brave => "BraveSoftware/Brave-Browser",
+                        Browser::Edge => "microsoft-edge",
+                        _ => "chromium",
+                    };
+                    Some(
+                        dirs_next::config_dir()?
+                            .join(vendor)
+                            .join("NativeMessagingHosts"),
+                    )
+                }
+            }
+        }
+    }
+
+    /// The registry key a Windows install points at the manifest.
+    fn registry_key(self) -> String {
+        let vendor = match self {
+            Browser::Firefox => "Mozilla",
+            Browser::Chrome | Browser::Brave => "Google\\Chrome",
+            Browser::Chromium => "Chromium",
+            Browser::Edge => "Microsoft\\Edge",
+        };
+        format!(
+            "HKCU\\Software\\{}\\NativeMessagingHosts\\{}",
+            vendor, HOST_NAME
+        )
+    }
+}
+
+/// The host manifest for `browser`, letting only `extension_id` connect.
+/// Firefox IDs look like `name@example.org`; Chromium ones are 32
+/// letters from a to p.
+pub fn manifest(
+    browser: Browser,
+    extension_id: &str,
+    launcher: &Path,
+) -> Result<Value, FicflowError> {
+    let extension_id = extension_id.trim();
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": "Ficflow: add AO3 works and sync reading progress",
+        "path": launcher,
+        "type": "stdio",
+    });
+    if browser.is_chromium() {
+        let valid =
+            extension_id.len() == 32 && extension_id.bytes().all(|b| (b'a'..=b'p').contains(&b));
+        if !valid {
+            return Err(invalid_id(extension_id, "32 letters from a to p"));
+        }
+        manifest["allowed_origins"] = json!([format!("chrome-extension://{}/", extension_id)]);
+    } else {
+        let valid = extension_id.contains('@')
+            || (extension_id.starts_with('{') && extension_id.ends_with('}'));
+        if !valid {
+            return Err(invalid_id(
+                extension_id,
+                "like name@example.org or a {UUID}",
+            ));
+        }
+        manifest["allowed_extensions"] = json!([extension_id]);
+    }
+    Ok(manifest)
+}
+
+fn invalid_id(extension_id: &str, expected: &str) -> FicflowError {
+    FicflowError::InvalidInput(format!(
+        "Invalid extension ID: '{}'. Expected {}",
+        extension_id, expected
+    ))
+}
+
+/// Where an install puts the launcher, next to the config file.
+pub fn launcher_path() -> Option<PathBuf> {
+    let file = if cfg!(windows) {
+        "native-host.bat"
+    } else {
+        "native-host.sh"
+    };
+    Some(dirs_next::config_dir()?.join("ficflow").join(file))
+}
+
+/// What `install` wrote, and on Windows the registry entry still needed.
+pub struct Installed {
+    pub manifest: PathBuf,
+    pub launcher: PathBuf,
+    pub registry: Option<String>,
+}
+
+/// Writes the launcher for `exe` and the browser's manifest. On Windows
+/// the manifest goes next to the launcher and the browser is pointed at
+/// it with `reg add`, which is left for the user to run.
+pub fn install(
+    browser: Browser,
+    extension_id: &str,
+    exe: &Path,
+) -> Result<Installed, FicflowError> {
+    let launcher = launcher_path().ok_or_else(|| {
+        FicflowError::Other("no config directory available on this platform".into())
+    })?;
+    let manifest = manifest(browser, extension_id, &launcher)?;
+
+    if let Some(parent) = launcher.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_launcher(&launcher, exe)?;
+
+    let dir = match browser.manifest_dir() {
+        Some(dir) => dir,
+        None => launcher.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    fs::create_dir_all(&dir)?;
+    let manifest_path = dir.join(format!("{}.json", HOST_NAME));
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    let registry = cfg!(windows).then(|| {
+        format!(
+            "reg add \"{}\" /ve /t REG_SZ /d \"{}\" /f",
+            browser.registry_key(),
+            manifest_path.display()
+        )
+    });
+    Ok(Installed {
+        manifest: manifest_path,
+        launcher,
+        registry,
+    })
+}
+
+fn write_launcher(launcher: &Path, exe: &Path) -> Result<(), FicflowError> {
+    if cfg!(windows) {
+        let script = format!("@echo off\r\n\"{}\" native-host %*\r\n", exe.display());
+        fs::write(launcher, script)?;
+    } else {
+        let quoted = exe.display().to_string().replace('\'', "'\\''");
+        let script = format!("#!/bin/sh\nexec '{}' native-host \"$@\"\n", quoted);
+        fs::write(launcher, script)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(launcher, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! Browser native-messaging host (`ficflow native-host`). The browser
+//! starts it when an extension connects and exchanges JSON messages over
+//! stdin/stdout, each prefixed with its length as a 32-bit integer in
+//! native byte order. A request looks like
+//!
+//! ```json
+//! {"id": 7, "type": "progress", "work": "https://archiveofourown.org/works/123/chapters/456", "chapter": 4}
+//! ```
+//!
+//! and is answered with `{"id": 7, "ok": true, ...}`, or `{"id": 7,
+//! "ok": false, "error": {"code", "message"}}` with the same error codes
+//! as the CLI's JSON output. `id` is echoed back when given so the
+//! extension can match replies to requests. The types:
+//!
+//! * `ping` — answers with ficflow's version, to check the host works.
+//! * `add` — fetches `work` from AO3 and adds it; answers with the fic.
+//! * `progress` — sets the last chapter read of a tracked `work`.
+//! * `status` — `tracked`, and the fic when it is.
+//!
+//! `work` is an AO3 ID or any link into the work. Everything printed to
+//! stdout is a message, so diagnostics only ever go to stderr. A Ficflow
+//! window open on the library is asked to reload after each change.
+
+pub mod manifest;
+
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::application::{
+    add_fic::add_fanfiction, get_fic::get_fanfiction, update_chapters::update_last_chapter_read,
+};
+use crate::domain::fanfiction::{FanfictionFetcher, FanfictionOps};
+use crate::error::FicflowError;
+use crate::interfaces::instance;
+use crate::interfaces::utils::url_parser::extract_ao3_id;
+
+/// Browsers cap what a host may send at 1 MB; requests are held to the
+/// same, which is far more than any of ours needs.
+const MAX_MESSAGE_BYTES: u32 = 1024 * 1024;
+
+/// Answers messages from `input` on `output` until the browser closes
+/// the pipe. A malformed message gets an error reply and the host keeps
+/// going; only a broken pipe ends it early.
+pub fn run_native_host(
+    fetcher: &dyn FanfictionFetcher,
+    fanfiction_ops: &dyn FanfictionOps,
+    db_path: &Path,
+    mut input: impl Read,
+    mut output: impl Write,
+) -> Result<(), FicflowError> {
+    while let Some(message) = read_message(&mut input)? {
+        let (reply, wrote) = match message {
+            Ok(body) => answer(fetcher, fanfiction_ops, &body),
+            Err(err) => (error_reply(None, &err), false),
+        };
+        write_message(&mut output, &reply)?;
+        if wrote {
+            instance::request_reload(db_path);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Request {
+    Ping,
+    Add { work: Value },
+    Progress { work: Value, chapter: u32 },
+    Status { work: Value },
+}
+
+impl Request {
+    fn writes(&self) -> bool {
+        matches!(self, Request::Add { .. } | Request::Progress { .. })
+    }
+}
+
+/// The reply, and whether the request changed the library.
+fn answer(
+    fetcher: &dyn FanfictionFetcher,
+    fanfiction_ops: &dyn FanfictionOps,
+    body: &[u8],
+) -> (Value, bool) {
+    let message: Value = match serde_json::from_slice(body) {
+        Ok(message) => message,
+        Err(e) => {
+            let err = FicflowError::InvalidInput(format!("message isn't JSON: {}", e));
+            return (error_reply(None, &err), false);
+        }
+    };
+    let id = message.get("id").cloned();
+    let answered = Request::deserialize(&message)
+        .map_err(|e| FicflowError::InvalidInput(format!("unrecognized message: {}", e)))
+        .and_then(|request| {
+            let writes = request.writes();
+            handle(fetcher, fanfiction_ops, request).map(|reply| (reply, writes))
+        });
+    match answered {
+        Ok((mut reply, wrote)) => {
+            reply["ok"] = json!(true);
+            if let Some(id) = id {
+                reply["id"] = id;
+            }
+            (reply, wrote)
+        }
+        Err(err) => (error_reply(id, &err), false),
+    }
+}
+
+fn handle(
+    fetcher: &dyn FanfictionFetcher,
+    fanfiction_ops: &dyn FanfictionOps,
+    request: Request,
+) -> Result<Value, FicflowError> {
+    match request {
+        Request::Ping => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+        Request::Add { work } => {
+            let fic_id = work_id(&work)?;
+            add_fanfiction(fetcher, fanfiction_ops, fic_id)?;
+            let fic = get_fanfiction(fanfiction_ops, fic_id)?;
+            Ok(json!({ "fic": fic }))
+        }
+        Request::Progress { work, chapter } => {
+            let fic = update_last_chapter_read(fanfiction_ops, work_id(&work)?, chapter)?;
+            Ok(json!({ "fic": fic }))
+        }
+        Request::Status { work } => match get_fanfiction(fanfiction_ops, work_id(&work)?) {
+            Ok(fic) => Ok(json!({ "tracked": true, "fic": fic })),
+            Err(FicflowError::NotFound { .. }) => Ok(json!({ "tracked": false })),
+            Err(err) => Err(err),
+        },
+    }
+}
+
+/// A work named by its numeric ID, or an ID or link as a string.
+fn work_id(work: &Value) -> Result<u64, FicflowError> {
+    match work {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => extract_ao3_id(s.trim()).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        FicflowError::InvalidInput(format!(
+            "'work' must be an AO3 ID or work link; got {}",
+            work
+        ))
+    })
+}
+
+fn error_reply(id: Option<Value>, err: &FicflowError) -> Value {
+    let mut reply = json!({
+        "ok": false,
+        "error": { "code": err.code(), "message": err.to_string() },
+    });
+    if let Some(id) = id {
+        reply["id"] = id;
+    }
+    reply
+}
+
+/// The next message's bytes, `Err` when it's too long to accept (its
+/// bytes are skipped so the next one still lines up), or `None` once the
+/// browser has closed the pipe.
+fn read_message(
+    input: &mut impl Read,
+) -> Result<Option<Result<Vec<u8>, FicflowError>>, FicflowError> {
+    let mut header = [0u8; 4];
+    match input.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_ne_bytes(header);
+    if len > MAX_MESSAGE_BYTES {
+        io::copy(&mut input.take(u64::from(len)), &mut io::sink())?;
+        return Ok(Some(Err(FicflowError::InvalidInput(format!(
+            "message of {} bytes is over the {} byte limit",
+            len, MAX_MESSAGE_BYTES
+        )))));
+    }
+    let mut body = vec![0u8; len as usize];
+    input.read_exact(&mut body)?;
+    Ok(Some(Ok(body)))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<(), FicflowError> {
+    let body = serde_json::to_vec(message)?;
+    let len = u32::try_from(body.len())
+        .map_err(|_| FicflowError::Other("reply too large to send".into()))?;
+    output.write_all(&len.to_ne_bytes())?;
+    output.write_all(&body)?;
+    output.flush()?;
+    Ok(())
+}
@@ -0,0 +1,476 @@
+//! Local HTTP JSON API (`ficflow serve`), for other tools — a browser
+//! extension, a phone on the LAN — to read and change the library. Each
+//! endpoint is a thin wrapper over an application use case; adds go to
+//! the same background worker the GUI uses, and come back as a task the
+//! client polls. Every request needs the token from `api_token_path` as
+//! `Authorization: Bearer <token>`.
+//!
+//! Requests are served one at a time on the calling thread, which owns
+//! the SQLite connection. A Ficflow window open on the library is asked
+//! to reload after each change and each add that lands.
+//!
+//! | Method | Path | Does |
+//! |---|---|---|
+//! | GET | `/fics?status=` | List fics, optionally with one reading status |
+//! | POST | `/fics` | `{"fic": id or URL, "shelf": id?}` — queue an add, `202` with its task |
+//! | GET | `/fics/{id}` | One fic |
+//! | PATCH | `/fics/{id}` | Any of `status`, `chapter`, `rating`, `note` (`null` clears) |
+//! | GET | `/shelves` | List shelves |
+//! | GET | `/shelves/{id}/fics` | A shelf's fics |
+//! | PUT / DELETE | `/shelves/{id}/fics/{fic_id}` | Add a fic to, or remove it from, a shelf |
+//! | GET | `/tasks`, `/tasks/{id}` | Queued adds and how they went |
+
+mod token;
+
+use std::io::{self, Read};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::application::{
+    add_to_shelf::add_to_shelf, get_fic::get_fanfiction, list_fics::list_fics,
+    list_shelf_fics::list_shelf_fics, list_shelves::list_shelves,
+    remove_from_shelf::remove_from_shelf, update_chapters::set_last_chapter_read,
+    update_rating::parse_user_rating, update_status::parse_reading_status,
+};
+use crate::domain::repository::Repository;
+use crate::error::FicflowError;
+use crate::infrastructure::external::ao3::fetcher::ao3_urls_from_env;
+use crate::interfaces::gui::api_token_path;
+use crate::interfaces::gui::tasks::{AddOptions, TaskExecutor, TaskKind, TaskState, TaskStatus};
+use crate::interfaces::instance;
+use crate::interfaces::utils::url_parser::extract_ao3_id;
+
+pub use token::load_or_create_token;
+
+/// Where `ficflow serve` listens when `--bind` isn't given.
+pub const DEFAULT_BIND: &str = "127.0.0.1:7341";
+
+/// Request bodies are a few fields of JSON; anything bigger is refused.
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// How long the server waits for a request before checking for adds
+/// that landed.
+const TASK_POLL: Duration = Duration::from_millis(500);
+
+/// Serves the library at `bind` until the process is stopped.
+pub fn run_server(
+    repository: &dyn Repository,
+    bind: &str,
+    db_path: &Path,
+) -> Result<(), FicflowError> {
+    let token_path = api_token_path().ok_or_else(|| {
+        FicflowError::Other("no config directory available on this platform".into())
+    })?;
+    let token = load_or_create_token(&token_path)?;
+    let (urls, max_cycles) = ao3_urls_from_env();
+    let server = ApiServer::bind(bind, token, urls, max_cycles, db_path.to_path_buf())?;
+    if let Some(addr) = server.local_addr() {
+        println!("Serving the library at http://{}", addr);
+    }
+    println!(
+        "Clients send the token in {} as `Authorization: Bearer <token>`. Stop with Ctrl+C.",
+        token_path.display()
+    );
+    server.run(repository);
+    Ok(())
+}
+
+pub struct ApiServer {
+    http: Server,
+    token: String,
+    /// Runs queued adds against AO3 on its own connection to the library.
+    tasks: TaskExecutor,
+    db_path: PathBuf,
+}
+
+impl ApiServer {
+    pub fn bind(
+        bind: &str,
+        token: String,
+        ao3_urls: Vec<String>,
+        max_cycles: u32,
+        db_path: PathBuf,
+    ) -> Result<Self, FicflowError> {
+        let addr: SocketAddr = bind
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| {
+                FicflowError::InvalidInput(format!(
+                    "Invalid address to serve on: '{}'. Expected HOST:PORT, e.g. {}",
+                    bind, DEFAULT_BIND
+                ))
+            })?;
+        let http = Server::http(addr).map_err(|e| {
+            FicflowError::Io(io::Error::other(format!(
+                "couldn't listen on {}: {}",
+                addr, e
+            )))
+        })?;
+        Ok(Self {
+            http,
+            token,
+            tasks: TaskExecutor::spawn(ao3_urls, max_cycles, db_path.clone()),
+            db_path,
+        })
+    }
+
+    /// The address actually bound, which differs from the one asked for
+    /// when its port was 0.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    pub fn run(&self, repository: &dyn Repository) {
+        loop {
+            // Woken now and then without a request, so an add that lands
+            // reaches an open window without waiting for the next one.
+            let request = match self.http.recv_timeout(TASK_POLL) {
+                Ok(request) => request,
+                Err(err) => {
+                    log::warn!("Stopped serving: {}", err);
+                    return;
+                }
+            };
+            // Nothing here shows the worker's toasts; dropping them keeps
+            // the queues from growing for as long as the server runs.
+            let added = !self.tasks.take_completions().is_empty();
+            self.tasks.take_refreshes();
+            let wrote = match request {
+                Some(request) => self.answer(repository, request),
+                None => false,
+            };
+            if added || wrote {
+                instance::request_reload(&self.db_path);
+            }
+        }
+    }
+
+    /// Answers `request`, returning whether it changed the library.
+    fn answer(&self, repository: &dyn Repository, mut request: Request) -> bool {
+        // Adds are queued, and counted once they land.
+        let writes = matches!(
+            request.method(),
+            Method::Patch | Method::Put | Method::Delete
+        );
+        let (status, body) = match self.handle(repository, &mut request) {
+            Ok(Reply::Json(status, value)) => (status, Some(value)),
+            Ok(Reply::NoContent) => (204, None),
+            Err(err) => (err.status(), Some(err.body())),
+        };
+        log::debug!("{} {} -> {}", request.method(), request.url(), status);
+        let response = match body {
+            Some(value) => Response::from_string(value.to_string())
+                .with_status_code(status)
+                .with_header(json_header()),
+            None => Response::from_string(String::new()).with_status_code(status),
+        };
+        if let Err(err) = request.respond(response) {
+            log::warn!("Failed to answer an API request: {}", err);
+        }
+        writes && status < 300
+    }
+
+    fn handle(
+        &self,
+        repository: &dyn Repository,
+        request: &mut Request,
+    ) -> Result<Reply, ApiError> {
+        if !self.is_authorized(request) {
+            return Err(ApiError::Unauthorized);
+        }
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let method = request.method().clone();
+
+        match (&method, segments.as_slice()) {
+            (Method::Get, ["fics"]) => {
+                let status = query_param(query, "status")
+                    .map(|s| parse_reading_status(&s))
+                    .transpose()?;
+                let fics: Vec<_> = list_fics(repository)?
+                    .into_iter()
+                    .filter(|fic| status.is_none_or(|s| fic.reading_status == s))
+                    .collect();
+                ok(&fics)
+            }
+            (Method::Post, ["fics"]) => {
+                let body: AddFic = read_json(request)?;
+                let fic_id = extract_ao3_id(body.fic.trim()).map_err(FicflowError::InvalidInput)?;
+                let options = AddOptions {
+                    shelf_id: body.shelf,
+                    ..AddOptions::default()
+                };
+                let task_id = self.tasks.enqueue_add(fic_id.to_string(), options);
+                let task = self.task(task_id)?;
+                Ok(Reply::Json(202, task_json(&task)))
+            }
+            (Method::Get, ["fics", id]) => ok(&get_fanfiction(repository, parse_id(id)?)?),
+            (Method::Patch, ["fics", id]) => {
+                let fic_id = parse_id(id)?;
+                let changes: FicChanges = read_json(request)?;
+                ok(&apply_changes(repository, fic_id, changes)?)
+            }
+            (Method::Get, ["shelves"]) => ok(&list_shelves(repository)?),
+            (Method::Get, ["shelves", id, "fics"]) => {
+                ok(&list_shelf_fics(repository, parse_id(id)?)?)
+            }
+            (Method::Put, ["shelves", shelf_id, "fics", fic_id]) => {
+                add_to_shelf(repository, parse_id(fic_id)?, parse_id(shelf_id)?)?;
+                Ok(Reply::NoContent)
+            }
+            (Method::Delete, ["shelves", shelf_id, "fics", fic_id]) => {
+                remove_from_shelf(repository, parse_id(fic_id)?, parse_id(shelf_id)?)?;
+                Ok(Reply::NoContent)
+            }
+            (Method::Get, ["tasks"]) => {
+                let tasks: Vec<Value> = self.tasks.snapshot().iter().map(task_json).collect();
+                Ok(Reply::Json(200, Value::Array(tasks)))
+            }
+            (Method::Get, ["tasks", id]) => {
+                let id = parse_id(id)?;
+                Ok(Reply::Json(200, task_json(&self.task(id)?)))
+            }
+            _ if is_route(&segments) => Err(ApiError::MethodNotAllowed),
+            _ => Err(ApiError::NoRoute),
+        }
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+            .is_some_and(|given| token::token_matches(&self.token, given.trim()))
+    }
+
+    fn task(&self, task_id: u64) -> Result<TaskState, ApiError> {
+        self.tasks
+            .snapshot()
+            .into_iter()
+            .find(|task| task.id == task_id)
+            .ok_or(ApiError::NoTask(task_id))
+    }
+}
+
+enum Reply {
+    Json(u16, Value),
+    NoContent,
+}
+
+fn ok<T: serde::Serialize>(value: &T) -> Result<Reply, ApiError> {
+    let value = serde_json::to_value(value).map_err(FicflowError::from)?;
+    Ok(Reply::Json(200, value))
+}
+
+/// Whether the path names an endpoint under some method, so a wrong
+/// method gets 405 instead of 404.
+fn is_route(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        ["fics"]
+            | ["fics", _]
+            | ["shelves"]
+            | ["shelves", _, "fics"]
+            | ["shelves", _, "fics", _]
+            | ["tasks"]
+            | ["tasks", _]
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AddFic {
+    fic: String,
+    #[serde(default)]
+    shelf: Option<u64>,
+}
+
+/// A `PATCH /fics/{id}` body. Fields left out aren't touched; `rating`
+/// and `note` are kept as raw JSON so an explicit `null` can clear them.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FicChanges {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    chapter: Option<u32>,
+    #[serde(default, deserialize_with = "present")]
+    rating: Option<Value>,
+    #[serde(default, deserialize_with = "present")]
+    note: Option<Value>,
+}
+
+/// Keeps an explicit `null` as `Some(Value::Null)`; plain `Option`
+/// would read it the same as a missing field.
+fn present<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Applies the changes to the fic with the same rules as their single
+/// updates and saves it once, so a request changes all of its fields or
+/// none. Status goes last: moving the chapter can start or finish the
+/// fic, and an explicit status should win over that.
+fn apply_changes(
+    repository: &dyn Repository,
+    fic_id: u64,
+    changes: FicChanges,
+) -> Result<crate::domain::fanfiction::Fanfiction, FicflowError> {
+    let status = changes
+        .status
+        .as_deref()
+        .map(parse_reading_status)
+        .transpose()?;
+    let rating = match changes.rating {
+        None => None,
+        Some(Value::Null) => Some(None),
+        Some(Value::Number(n)) => Some(parse_user_rating(&n.to_string())?),
+        Some(Value::String(s)) => Some(parse_user_rating(&s)?),
+        Some(other) => {
+            return Err(FicflowError::InvalidInput(format!(
+                "rating must be 1-5, a name like \"five\", or null; got {}",
+                other
+            )));
+        }
+    };
+    let note = match changes.note {
+        None => None,
+        Some(Value::Null) => Some(None),
+        Some(Value::String(s)) => Some(Some(s)),
+        Some(other) => {
+            return Err(FicflowError::InvalidInput(format!(
+                "note must be a string or null; got {}",
+                other
+            )));
+        }
+    };
+
+    let mut fic = get_fanfiction(repository, fic_id)?;
+    if let Some(chapter) = changes.chapter {
+        set_last_chapter_read(&mut fic, chapter);
+    }
+    if let Some(rating) = rating {
+        fic.user_rating = rating;
+    }
+    if let Some(note) = note {
+        fic.personal_note = note;
+    }
+    if let Some(status) = status {
+        fic.reading_status = status;
+    }
+    repository.save_fanfictions(std::slice::from_ref(&fic))?;
+    Ok(fic)
+}
+
+fn task_json(task: &TaskState) -> Value {
+    let (status, error) = match &task.status {
+        TaskStatus::Running => ("running", None),
+        TaskStatus::Done => ("done", None),
+        TaskStatus::Failed(reason) => ("failed", Some(reason)),
+    };
+    json!({
+        "id": task.id,
+        "kind": match task.kind {
+            TaskKind::Add => "add",
+            TaskKind::Refresh => "refresh",
+        },
+        "input": task.input,
+        // The input until an add lands, then the fic's title.
+        "title": task.display,
+        "status": status,
+        "error": error,
+        "started_at": task.started_at,
+    })
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_string(&mut body)
+        .map_err(FicflowError::from)?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(FicflowError::InvalidInput("request body is too large".into()).into());
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| FicflowError::InvalidInput(format!("invalid JSON body: {}", e)).into())
+}
+
+fn parse_id(segment: &str) -> Result<u64, ApiError> {
+    segment.parse().map_err(|_| {
+        FicflowError::InvalidInput(format!("'{}' is not a numeric ID", segment)).into()
+    })
+}
+
+/// The first value of `key` in a query string, percent-decoded, with
+/// `+` read as a space.
+fn query_param(query: &str, key: &str) -> Option<String> {
+    // `Url` does the form decoding; the host is only there to parse.
+    let url = Url::parse(&format!("http://localhost/?{}", query)).ok()?;
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json").expect("static header is valid")
+}
+
+/// Why a request was refused. Library errors keep their `code()`, so
+/// clients see the same names the CLI's JSON errors use.
+enum ApiError {
+    Library(FicflowError),
+    Unauthorized,
+    NoRoute,
+    MethodNotAllowed,
+    NoTask(u64),
+}
+
+impl From<FicflowError> for ApiError {
+    fn from(err: FicflowError) -> Self {
+        ApiError::Library(err)
+    }
+}
+
+impl ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            ApiError::Library(err) => match err.exit_code() {
+                2 => 400,
+                3 => 404,
+                4 => 409,
+                5 => 502,
+                _ => 500,
+            },
+            ApiError::Unauthorized => 401,
+            ApiError::NoRoute | ApiError::NoTask(_) => 404,
+            ApiError::MethodNotAllowed => 405,
+        }
+    }
+
+    fn body(&self) -> Value {
+        let (code, message) = match self {
+            ApiError::Library(err) => (err.code(), err.to_string()),
+            ApiError::Unauthorized => (
+                "unauthorized",
+                "missing or wrong `Authorization: Bearer <token>` header".to_string(),
+            ),
+            ApiError::NoRoute => ("no_route", "no such endpoint".to_string()),
+            ApiError::MethodNotAllowed => (
+                "method_not_allowed",
+                "that endpoint doesn't take this method".to_string(),
+            ),
+            ApiError::NoTask(id) => ("task_not_found", format!("task {} not found", id)),
+        };
+        json!({ "error": { "code": code, "message": message } })
+    }
+}
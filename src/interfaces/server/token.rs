@@ -0,0 +1,48 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::FicflowError;
+
+/// 32 random bytes, written as hex.
+const TOKEN_BYTES: usize = 32;
+
+/// Reads the API token kept at `path`, generating one the first time.
+/// The file is only readable by its owner, since the token grants full
+/// write access to the library.
+pub fn load_or_create_token(path: &Path) -> Result<String, FicflowError> {
+    if let Ok(existing) = fs::read_to_string(path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| FicflowError::Other(format!("couldn't generate an API token: {}", e)))?;
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+/// Compares without stopping at the first differing byte, so response
+/// times don't give the token away.
+pub fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
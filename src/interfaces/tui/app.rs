@@ -0,0 +1,514 @@
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::{ListState, TableState};
+
+use crate::application::list_shelves::list_shelves;
+use crate::application::query_fics::query_fics;
+use crate::application::update_chapters::update_last_chapter_read;
+use crate::application::update_note::update_personal_note;
+use crate::application::update_rating::{parse_user_rating, update_user_rating};
+use crate::application::update_status::{parse_reading_status, update_reading_status};
+use crate::domain::fanfiction::{
+    ColumnKey, Fanfiction, FicFilter, FicQuery, ReadingStatus, SortDirection, SortPref,
+};
+use crate::domain::repository::Repository;
+use crate::domain::shelf::{Shelf, ShelfKind};
+use crate::error::FicflowError;
+use crate::interfaces::gui::format::format_status;
+use crate::interfaces::gui::tasks::{AddOptions, TaskExecutor, TaskState};
+
+use super::view;
+
+/// The columns the library table shows, which are also the ones `s`
+/// cycles the sort through.
+pub const TABLE_COLUMNS: [ColumnKey; 6] = [
+    ColumnKey::Title,
+    ColumnKey::Author,
+    ColumnKey::Status,
+    ColumnKey::LastChapter,
+    ColumnKey::Words,
+    ColumnKey::Updated,
+];
+
+const STATUSES: [ReadingStatus; 5] = [
+    ReadingStatus::InProgress,
+    ReadingStatus::Read,
+    ReadingStatus::PlanToRead,
+    ReadingStatus::Paused,
+    ReadingStatus::Abandoned,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Sidebar,
+    Library,
+    Details,
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Sidebar => Pane::Library,
+            Pane::Library => Pane::Details,
+            Pane::Details => Pane::Sidebar,
+        }
+    }
+
+    fn previous(self) -> Pane {
+        match self {
+            Pane::Sidebar => Pane::Details,
+            Pane::Library => Pane::Sidebar,
+            Pane::Details => Pane::Library,
+        }
+    }
+}
+
+/// One line of the sidebar: the whole library, a reading status, or a
+/// shelf indented under its parent.
+#[derive(Debug, Clone)]
+pub enum SidebarEntry {
+    AllFics,
+    Status(ReadingStatus),
+    Shelf { shelf: Shelf, depth: usize },
+}
+
+impl SidebarEntry {
+    pub fn label(&self) -> String {
+        match self {
+            SidebarEntry::AllFics => "All fics".to_string(),
+            SidebarEntry::Status(status) => format_status(status).to_string(),
+            SidebarEntry::Shelf { shelf, depth } => {
+                format!("{}{}", "  ".repeat(*depth), shelf.name)
+            }
+        }
+    }
+}
+
+/// The fields of the selected fic the details pane edits in place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Status,
+    Chapter,
+    Rating,
+    Note,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::Status, Field::Chapter, Field::Rating, Field::Note];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Status => "Status",
+            Field::Chapter => "Chapter",
+            Field::Rating => "Rating",
+            Field::Note => "Note",
+        }
+    }
+
+    /// The field's value as typed into the edit line; what the parsers
+    /// for the field accept back.
+    fn text(self, fic: &Fanfiction) -> String {
+        match self {
+            Field::Status => match fic.reading_status {
+                ReadingStatus::InProgress => "inprogress",
+                ReadingStatus::Read => "read",
+                ReadingStatus::PlanToRead => "plantoread",
+                ReadingStatus::Paused => "paused",
+                ReadingStatus::Abandoned => "abandoned",
+            }
+            .to_string(),
+            Field::Chapter => fic
+                .last_chapter_read
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+            Field::Rating => fic
+                .user_rating
+                .map(|r| (r as u8).to_string())
+                .unwrap_or_default(),
+            Field::Note => fic.personal_note.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// What the bottom line is collecting keystrokes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    Search,
+    Add,
+    Edit(Field),
+}
+
+impl Prompt {
+    pub fn label(self) -> &'static str {
+        match self {
+            Prompt::Search => "Search",
+            Prompt::Add => "Add (AO3 ID or URL)",
+            Prompt::Edit(field) => field.label(),
+        }
+    }
+}
+
+pub struct TuiApp<'a> {
+    repository: &'a dyn Repository,
+    tasks: TaskExecutor,
+    pub(super) sidebar: Vec<SidebarEntry>,
+    pub(super) sidebar_state: ListState,
+    pub(super) fics: Vec<Fanfiction>,
+    pub(super) table_state: TableState,
+    pub(super) sort: SortPref,
+    pub(super) search: String,
+    pub(super) focus: Pane,
+    pub(super) field: Field,
+    pub(super) prompt: Option<(Prompt, String)>,
+    /// The last thing that happened, shown until the next one; errors
+    /// are flagged so they can be drawn in red.
+    pub(super) message: Option<(String, bool)>,
+    quit: bool,
+}
+
+impl<'a> TuiApp<'a> {
+    /// `urls`, `max_cycles` and `db_path` are for the background worker,
+    /// which opens its own connection to the same library.
+    pub fn new(
+        repository: &'a dyn Repository,
+        urls: Vec<String>,
+        max_cycles: u32,
+        db_path: PathBuf,
+    ) -> Result<Self, FicflowError> {
+        let mut app = TuiApp {
+            repository,
+            tasks: TaskExecutor::spawn(urls, max_cycles, db_path),
+            sidebar: Vec::new(),
+            sidebar_state: ListState::default().with_selected(Some(0)),
+            fics: Vec::new(),
+            table_state: TableState::default(),
+            sort: SortPref {
+                column: ColumnKey::Title,
+                direction: SortDirection::Ascending,
+            },
+            search: String::new(),
+            focus: Pane::Library,
+            field: Field::Status,
+            prompt: None,
+            message: None,
+            quit: false,
+        };
+        app.load_sidebar()?;
+        app.fics = app.load_fics()?;
+        app.table_state.select((!app.fics.is_empty()).then_some(0));
+        Ok(app)
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// The fics in the table, in the order shown.
+    pub fn fics(&self) -> &[Fanfiction] {
+        &self.fics
+    }
+
+    pub fn selected_fic(&self) -> Option<&Fanfiction> {
+        self.table_state.selected().and_then(|i| self.fics.get(i))
+    }
+
+    pub fn focus(&self) -> Pane {
+        self.focus
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    pub fn tasks(&self) -> Vec<TaskState> {
+        self.tasks.snapshot()
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        view::draw(frame, self);
+    }
+
+    /// Picks up finished adds and refreshes, and reloads the table when
+    /// any landed.
+    pub fn poll_tasks(&mut self) {
+        let added = self.tasks.take_completions();
+        let refreshed = self.tasks.take_refreshes();
+        if let Some(title) = added.last() {
+            self.say(format!("Added \"{}\"", title));
+        } else if !refreshed.is_empty() {
+            self.say(format!("Refreshed {} fic(s)", refreshed.len()));
+        }
+        if !added.is_empty() || !refreshed.is_empty() {
+            self.reload();
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.prompt.is_some() {
+            self.handle_prompt_key(key);
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Enter => match self.focus {
+                Pane::Sidebar => self.focus = Pane::Library,
+                Pane::Library => self.focus = Pane::Details,
+                Pane::Details => self.start_edit(),
+            },
+            KeyCode::Char('/') => self.prompt = Some((Prompt::Search, self.search.clone())),
+            KeyCode::Esc if !self.search.is_empty() => {
+                self.search.clear();
+                self.reload();
+            }
+            KeyCode::Char('s') => {
+                let at = TABLE_COLUMNS
+                    .iter()
+                    .position(|c| *c == self.sort.column)
+                    .map_or(0, |i| (i + 1) % TABLE_COLUMNS.len());
+                self.sort.column = TABLE_COLUMNS[at];
+                self.reload();
+            }
+            KeyCode::Char('S') => {
+                self.sort.direction = match self.sort.direction {
+                    SortDirection::Ascending => SortDirection::Descending,
+                    SortDirection::Descending => SortDirection::Ascending,
+                };
+                self.reload();
+            }
+            KeyCode::Char('a') => self.prompt = Some((Prompt::Add, String::new())),
+            KeyCode::Char('u') => {
+                if let Some(fic) = self.selected_fic() {
+                    let (id, title) = (fic.id, fic.title.clone());
+                    self.tasks.enqueue_refresh(id, title.clone());
+                    self.say(format!("Checking AO3 for \"{}\"", title));
+                }
+            }
+            KeyCode::Char('c') => self.tasks.clear_completed(),
+            _ => {}
+        }
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) {
+        let Some((prompt, text)) = self.prompt.as_mut() else {
+            return;
+        };
+        let prompt = *prompt;
+        match key.code {
+            KeyCode::Esc => {
+                self.prompt = None;
+                if prompt == Prompt::Search {
+                    self.search.clear();
+                    self.reload();
+                }
+            }
+            KeyCode::Enter => {
+                let text = self.prompt.take().map(|(_, text)| text).unwrap_or_default();
+                self.submit(prompt, text);
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                if prompt == Prompt::Search {
+                    self.search = text.clone();
+                    self.reload();
+                }
+            }
+            KeyCode::Char(c) => {
+                text.push(c);
+                // Search narrows the table as it's typed.
+                if prompt == Prompt::Search {
+                    self.search = text.clone();
+                    self.reload();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn submit(&mut self, prompt: Prompt, text: String) {
+        match prompt {
+            Prompt::Search => {}
+            Prompt::Add => {
+                let input = text.trim().to_string();
+                if !input.is_empty() {
+                    self.say(format!("Adding {}", input));
+                    self.tasks.enqueue_add(input, AddOptions::default());
+                }
+            }
+            Prompt::Edit(field) => {
+                let Some(fic_id) = self.selected_fic().map(|f| f.id) else {
+                    return;
+                };
+                match self.save_field(fic_id, field, text.trim()) {
+                    Ok(fic) => {
+                        self.say(format!("Saved {} for \"{}\"", field.label(), fic.title));
+                        self.reload();
+                    }
+                    Err(e) => self.complain(&e),
+                }
+            }
+        }
+    }
+
+    fn save_field(
+        &self,
+        fic_id: u64,
+        field: Field,
+        text: &str,
+    ) -> Result<Fanfiction, FicflowError> {
+        match field {
+            Field::Status => {
+                update_reading_status(self.repository, fic_id, parse_reading_status(text)?)
+            }
+            Field::Chapter => {
+                let chapter = text.parse().map_err(|_| {
+                    FicflowError::InvalidInput(format!(
+                        "Invalid chapter: '{}'. Enter the number of the last chapter you read",
+                        text
+                    ))
+                })?;
+                update_last_chapter_read(self.repository, fic_id, chapter)
+            }
+            Field::Rating => update_user_rating(self.repository, fic_id, parse_user_rating(text)?),
+            Field::Note => {
+                let note = (!text.is_empty()).then_some(text);
+                update_personal_note(self.repository, fic_id, note)
+            }
+        }
+    }
+
+    fn start_edit(&mut self) {
+        if let Some(fic) = self.selected_fic() {
+            let text = self.field.text(fic);
+            self.prompt = Some((Prompt::Edit(self.field), text));
+        }
+    }
+
+    fn move_cursor(&mut self, step: isize) {
+        match self.focus {
+            Pane::Sidebar => {
+                let at = step_index(self.sidebar_state.selected(), step, self.sidebar.len());
+                if at != self.sidebar_state.selected() {
+                    self.sidebar_state.select(at);
+                    self.reload();
+                    self.table_state
+                        .select((!self.fics.is_empty()).then_some(0));
+                }
+            }
+            Pane::Library => {
+                let at = step_index(self.table_state.selected(), step, self.fics.len());
+                self.table_state.select(at);
+            }
+            Pane::Details => {
+                let at = step_index(
+                    Field::ALL.iter().position(|f| *f == self.field),
+                    step,
+                    Field::ALL.len(),
+                );
+                self.field = Field::ALL[at.unwrap_or(0)];
+            }
+        }
+    }
+
+    fn load_sidebar(&mut self) -> Result<(), FicflowError> {
+        let shelves = list_shelves(self.repository)?;
+        let mut entries = vec![SidebarEntry::AllFics];
+        entries.extend(STATUSES.into_iter().map(SidebarEntry::Status));
+        push_shelves(&mut entries, &shelves, None, 0);
+        self.sidebar = entries;
+        Ok(())
+    }
+
+    fn load_fics(&self) -> Result<Vec<Fanfiction>, FicflowError> {
+        let mut filter = FicFilter {
+            text: (!self.search.trim().is_empty()).then(|| self.search.clone()),
+            ..FicFilter::default()
+        };
+        let mut criteria = None;
+        match self
+            .sidebar_state
+            .selected()
+            .and_then(|i| self.sidebar.get(i))
+        {
+            Some(SidebarEntry::Status(status)) => filter.status = Some(*status),
+            Some(SidebarEntry::Shelf { shelf, .. }) => match &shelf.kind {
+                ShelfKind::Normal => filter.shelf_id = Some(shelf.id),
+                // Auto-shelves have no stored members to filter on.
+                ShelfKind::Auto(auto) => criteria = Some(auto),
+            },
+            Some(SidebarEntry::AllFics) | None => {}
+        }
+        let query = FicQuery {
+            filter,
+            sort: vec![self.sort],
+            ..FicQuery::default()
+        };
+        let fics = query_fics(self.repository, &query)?.fics;
+        Ok(match criteria {
+            Some(criteria) => fics.into_iter().filter(|f| criteria.matches(f)).collect(),
+            None => fics,
+        })
+    }
+
+    /// Re-reads the table, keeping the same fic selected when it's still
+    /// there.
+    fn reload(&mut self) {
+        let selected = self.selected_fic().map(|f| f.id);
+        match self.load_fics() {
+            Ok(fics) => self.fics = fics,
+            Err(e) => return self.complain(&e),
+        }
+        let at = selected
+            .and_then(|id| self.fics.iter().position(|f| f.id == id))
+            .or_else(|| (!self.fics.is_empty()).then_some(0));
+        self.table_state.select(at);
+    }
+
+    fn say(&mut self, text: String) {
+        self.message = Some((text, false));
+    }
+
+    fn complain(&mut self, err: &FicflowError) {
+        let text = match err {
+            FicflowError::InvalidInput(msg)
+            | FicflowError::TitleNotFound(msg)
+            | FicflowError::ShelfNameNotFound(msg) => msg.clone(),
+            other => other.to_string(),
+        };
+        self.message = Some((text, true));
+    }
+}
+
+/// Shelves under `parent`, each followed by its own sub-shelves.
+fn push_shelves(
+    entries: &mut Vec<SidebarEntry>,
+    shelves: &[Shelf],
+    parent: Option<u64>,
+    depth: usize,
+) {
+    for shelf in shelves.iter().filter(|s| s.parent_shelf_id == parent) {
+        entries.push(SidebarEntry::Shelf {
+            shelf: shelf.clone(),
+            depth,
+        });
+        push_shelves(entries, shelves, Some(shelf.id), depth + 1);
+    }
+}
+
+/// Moves `current` by `step` within `0..len`, stopping at the ends.
+fn step_index(current: Option<usize>, step: isize, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let at = current.map_or(0, |i| i.saturating_add_signed(step));
+    Some(at.min(len - 1))
+}
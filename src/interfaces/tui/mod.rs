@@ -0,0 +1,48 @@
+//! Keyboard-driven terminal front end (`ficflow tui`), for reading the
+//! library over SSH without the GUI. It works on the same SQLite file
+//! through the application use cases, and hands AO3 fetches to the same
+//! background worker the GUI uses, so the screen keeps redrawing while
+//! an add or refresh runs.
+
+mod app;
+mod view;
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::domain::repository::Repository;
+use crate::error::FicflowError;
+use crate::infrastructure::external::ao3::fetcher::ao3_urls_from_env;
+
+pub use app::{Field, Pane, SidebarEntry, TuiApp};
+
+/// How long to wait for a key before checking on background tasks.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Takes over the terminal until the user quits, and puts it back the
+/// way it was even when drawing fails.
+pub fn run_tui(repository: &dyn Repository, db_path: &Path) -> Result<(), FicflowError> {
+    let (urls, max_cycles) = ao3_urls_from_env();
+    let mut app = TuiApp::new(repository, urls, max_cycles, db_path.to_path_buf())?;
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    outcome.map_err(FicflowError::Io)
+}
+
+fn event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut TuiApp) -> io::Result<()> {
+    while !app.should_quit() {
+        terminal.draw(|frame| app.draw(frame))?;
+        if event::poll(TICK)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+        app.poll_tasks();
+    }
+    Ok(())
+}
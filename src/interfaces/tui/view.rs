@@ -0,0 +1,209 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, List, ListItem, Paragraph, Row, Table, Wrap};
+
+use crate::domain::fanfiction::{ColumnKey, Fanfiction, SortDirection};
+use crate::interfaces::gui::format::{format_status, format_thousands};
+use crate::interfaces::gui::tasks::{TaskKind, TaskStatus};
+
+use super::app::{Field, Pane, SidebarEntry, TABLE_COLUMNS, TuiApp};
+
+const KEY_HINTS: &str =
+    "Tab pane  j/k move  Enter edit  / search  s/S sort  a add  u refresh  c clear tasks  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut TuiApp) {
+    let [main, prompt_line, task_line] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [sidebar, library, details] = Layout::horizontal([
+        Constraint::Length(24),
+        Constraint::Min(30),
+        Constraint::Length(44),
+    ])
+    .areas(main);
+
+    draw_sidebar(frame, app, sidebar);
+    draw_library(frame, app, library);
+    draw_details(frame, app, details);
+    draw_prompt_line(frame, app, prompt_line);
+    draw_task_line(frame, app, task_line);
+}
+
+fn pane_block(title: String, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::default().fg(Color::Yellow))
+    } else {
+        block
+    }
+}
+
+fn draw_sidebar(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let items: Vec<ListItem> = app
+        .sidebar
+        .iter()
+        .map(|entry| {
+            let item = ListItem::new(entry.label());
+            match entry {
+                SidebarEntry::Shelf { .. } => item,
+                _ => item.bold(),
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block(
+            "Library".to_string(),
+            app.focus == Pane::Sidebar,
+        ))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.sidebar_state);
+}
+
+fn draw_library(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let view_name = app
+        .sidebar_state
+        .selected()
+        .and_then(|i| app.sidebar.get(i))
+        .map(|entry| entry.label().trim().to_string())
+        .unwrap_or_default();
+    let mut title = format!("{} ({})", view_name, app.fics.len());
+    if !app.search.is_empty() {
+        title.push_str(&format!(" matching \"{}\"", app.search));
+    }
+
+    let header = Row::new(TABLE_COLUMNS.map(|column| {
+        let arrow = match app.sort.direction {
+            SortDirection::Ascending => " ▲",
+            SortDirection::Descending => " ▼",
+        };
+        let label = column.label().to_string();
+        Cell::from(if column == app.sort.column {
+            label + arrow
+        } else {
+            label
+        })
+    }))
+    .bold();
+    let rows = app
+        .fics
+        .iter()
+        .map(|fic| Row::new(TABLE_COLUMNS.map(|column| Cell::from(cell(fic, column)))));
+    let widths = [
+        Constraint::Fill(3),
+        Constraint::Fill(2),
+        Constraint::Length(12),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(pane_block(title, app.focus == Pane::Library))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn cell(fic: &Fanfiction, column: ColumnKey) -> String {
+    match column {
+        ColumnKey::Title => fic.title.clone(),
+        ColumnKey::Author => fic.authors.join(", "),
+        ColumnKey::Status => format_status(&fic.reading_status).to_string(),
+        ColumnKey::LastChapter => format!(
+            "{}/{}",
+            fic.last_chapter_read.unwrap_or(0),
+            fic.chapters_total
+                .map_or("?".to_string(), |total| total.to_string())
+        ),
+        ColumnKey::Words => format_thousands(fic.words),
+        ColumnKey::Updated => fic.date_updated.format("%Y-%m-%d").to_string(),
+        _ => String::new(),
+    }
+}
+
+fn draw_details(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let block = pane_block("Details".to_string(), app.focus == Pane::Details);
+    let Some(fic) = app.selected_fic() else {
+        frame.render_widget(Paragraph::new("No fic selected.").block(block), area);
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(fic.title.clone().bold()),
+        Line::from(format!("by {}", fic.authors.join(", "))),
+        Line::from(fic.fandoms.join(", ").italic()),
+        Line::from(format!(
+            "{} words, {} chapters published{}",
+            format_thousands(fic.words),
+            fic.chapters_published,
+            if fic.complete { ", complete" } else { "" }
+        )),
+        Line::default(),
+    ];
+    for field in Field::ALL {
+        let value = match field {
+            Field::Status => format_status(&fic.reading_status).to_string(),
+            Field::Chapter => fic
+                .last_chapter_read
+                .map_or("none".to_string(), |c| c.to_string()),
+            Field::Rating => fic
+                .user_rating
+                .map_or("none".to_string(), |r| "★".repeat(r as usize)),
+            Field::Note => fic.personal_note.clone().unwrap_or_default(),
+        };
+        let label = Span::from(format!("{:<8} ", field.label())).bold();
+        let line = Line::from(vec![label, Span::from(value)]);
+        lines.push(if app.focus == Pane::Details && app.field == field {
+            line.reversed()
+        } else {
+            line
+        });
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(fic.summary.clone()));
+
+    let details = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+    frame.render_widget(details, area);
+}
+
+fn draw_prompt_line(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let line = match (&app.prompt, &app.message) {
+        (Some((prompt, text)), _) => Line::from(vec![
+            Span::from(format!("{}: ", prompt.label())).bold(),
+            Span::from(format!("{}█", text)),
+        ]),
+        (None, Some((text, true))) => Line::from(text.clone().red()),
+        (None, Some((text, false))) => Line::from(text.clone()),
+        (None, None) => Line::from(KEY_HINTS.dim()),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+/// The newest tasks first, as many as fit.
+fn draw_task_line(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let tasks = app.tasks();
+    let running = tasks
+        .iter()
+        .filter(|t| matches!(t.status, TaskStatus::Running))
+        .count();
+    let mut spans = vec![Span::from(format!("Tasks: {} running", running)).bold()];
+    for task in tasks.iter().rev().take(4) {
+        let verb = match task.kind {
+            TaskKind::Add => "Add",
+            TaskKind::Refresh => "Refresh",
+        };
+        let text = match &task.status {
+            TaskStatus::Running => format!("  ⋯ {} {}", verb, task.display).yellow(),
+            TaskStatus::Done => format!("  ✓ {} {}", verb, task.display).green(),
+            TaskStatus::Failed(reason) => {
+                format!("  ✗ {} {}: {}", verb, task.display, reason).red()
+            }
+        };
+        spans.push(text);
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
@@ -14,4 +14,20 @@ pub mod formatter {
 
         result
     }
+
+    /// Bytes as the largest unit that keeps the number at least 1, with
+    /// one decimal past bytes, e.g. "1.4 MB".
+    pub fn format_file_size(bytes: u64) -> String {
+        const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+        if bytes < 1024 {
+            return format!("{} B", bytes);
+        }
+        let mut size = bytes as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        format!("{:.1} {}", size, UNITS[unit])
+    }
 }
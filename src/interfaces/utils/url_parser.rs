@@ -1,11 +1,19 @@
 use regex::Regex;
 
+/// An AO3 work link, with its ID captured. The ID has to end the link or
+/// be followed by something that isn't part of a word, such as `/`, `?`,
+/// `#`, a space or punctuation, so `works/123abc` isn't read as work 123.
+/// Shared by the single and the bulk parser so they agree on what a work
+/// link is.
+const WORK_URL: &str = r"(?:archiveofourown\.org/|//)works/(\d+)\b";
+
 /// Extracts a fanfiction ID from various input formats:
 /// - Plain numeric ID: "12345678"
 /// - Full URL: "https://archiveofourown.org/works/12345678"
 /// - Headless URL: "archiveofourown.org/works/12345678"
 /// - Chapter URL: "https://archiveofourown.org/works/12345678/chapters/123456"
 /// - Comment URL: "https://archiveofourown.org/works/12345678/comments/123456"
+/// - URL with a query or fragment: "https://archiveofourown.org/works/12345678?view_adult=true"
 ///
 /// Returns the extracted ID as u64 or an error if the input doesn't contain a valid ID.
 pub fn extract_ao3_id(input: &str) -> Result<u64, String> {
@@ -14,12 +22,7 @@ pub fn extract_ao3_id(input: &str) -> Result<u64, String> {
         return Ok(id);
     }
 
-    // Regular expression to match AO3 work IDs in URLs
-    // This matches patterns like:
-    // - archiveofourown.org/works/12345678
-    // - archiveofourown.org/works/12345678/chapters/123456
-    // - archiveofourown.org/works/12345678/comments/123456
-    let re = Regex::new(r"(?:archiveofourown\.org/|//)works/(\d+)(?:/|$)").unwrap();
+    let re = Regex::new(WORK_URL).unwrap();
 
     if let Some(captures) = re.captures(input)
         && let Some(id_match) = captures.get(1)
@@ -32,3 +35,35 @@ pub fn extract_ao3_id(input: &str) -> Result<u64, String> {
 
     Err(format!("Could not extract AO3 ID from '{}'", input))
 }
+
+/// Every AO3 work ID in a block of free text, such as a pasted rec list
+/// or chat message, in the order they first appear and without repeats.
+///
+/// Work URLs are picked up anywhere in the text. Bare IDs only count on
+/// lines that hold nothing but IDs (separated by spaces or commas), so
+/// stray numbers in prose like "chapter 12" aren't mistaken for works.
+pub fn extract_ao3_ids(text: &str) -> Vec<u64> {
+    let url = Regex::new(WORK_URL).unwrap();
+    let id_line = Regex::new(r"^[\d\s,]+$").unwrap();
+
+    let mut ids = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let found: Vec<u64> = if id_line.is_match(trimmed) {
+            trimmed
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|token| token.parse().ok())
+                .collect()
+        } else {
+            url.captures_iter(trimmed)
+                .filter_map(|c| c[1].parse().ok())
+                .collect()
+        };
+        for id in found {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
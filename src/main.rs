@@ -2,11 +2,23 @@
 
 use std::process::ExitCode;
 
+use clap_complete::CompleteEnv;
+
 use ficflow::infrastructure::external::ao3::fetcher::ao3_urls_from_env;
-use ficflow::infrastructure::{Ao3Fetcher, SqliteRepository, open_configured_db};
+use ficflow::infrastructure::{
+    Ao3Fetcher, CommandOpener, SqliteBackups, SqliteRepository, open_configured_db,
+};
+use ficflow::interfaces::cli::command::parse_cli_commands;
 use ficflow::interfaces::gui::AppConfig;
+use ficflow::interfaces::instance::link;
 
 fn main() -> ExitCode {
+    // With COMPLETE=<shell> set, prints the shell's completion script or
+    // the candidates for one Tab press, then exits.
+    CompleteEnv::with_factory(ficflow::interfaces::cli::command::cli)
+        .bin("ficflow")
+        .complete();
+
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     if bare_invocation() {
@@ -14,17 +26,32 @@ fn main() -> ExitCode {
         // inside `FicflowApp::with_config(_, FicflowConfig::default())`,
         // so we don't pre-construct anything here.
         ficflow::interfaces::gui::run_gui()
+    } else if let Some(link) = opened_link() {
+        ficflow::interfaces::gui::open_link(&link)
     } else {
+        // Parsed first, so `--help` and typos exit before the library is
+        // opened.
+        let (output, command) = parse_cli_commands();
         // CLI takes them by reference because the dispatcher is
         // synchronous and trait-object-based.
         let (urls, max_cycles) = ao3_urls_from_env();
         let fetcher = Ao3Fetcher::new(urls, max_cycles).expect("Failed to create Ao3Fetcher");
-        let db_path = AppConfig::load()
+        let config = AppConfig::load();
+        let db_path = config
             .resolved_db_path()
             .expect("Failed to resolve library path");
         let conn = open_configured_db(&db_path).expect("Failed to establish database connection");
         let repository = SqliteRepository::new(&conn);
-        ficflow::interfaces::cli::run_cli(&fetcher, &repository)
+        let backups = SqliteBackups::new(&conn, &db_path);
+        ficflow::interfaces::cli::run_cli(
+            &fetcher,
+            &repository,
+            &backups,
+            &config,
+            &CommandOpener::new(config.resolved_opener()),
+            &db_path,
+            (output, command),
+        )
     }
 }
 
@@ -33,3 +60,11 @@ fn main() -> ExitCode {
 fn bare_invocation() -> bool {
     std::env::args().len() <= 1
 }
+
+/// The `ficflow://` link when the desktop started us to open one, which it
+/// does as `ficflow <link>`.
+fn opened_link() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    let link = args.next().filter(|arg| link::is_link(arg))?;
+    args.next().is_none().then_some(link)
+}
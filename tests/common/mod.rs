@@ -4,7 +4,7 @@ use chrono::Utc;
 use httpmock::MockServer;
 use rusqlite::Connection;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 use ficflow::{
@@ -244,6 +244,20 @@ pub mod fixtures {
         )
     }
 
+    /// Sets up a mock AO3 server whose "Wingfic" tag lists the works in
+    /// `ao3_tag_works.html` on every page, and which serves the first of
+    /// them, Featherlight (53960491), so it can be added.
+    pub fn given_mock_ao3_tag_listing() -> MockServer {
+        let (mock_server, _) = given_mock_ao3_server();
+        let listing = fs::read_to_string("tests/fixtures/ao3_tag_works.html")
+            .expect("Failed to read mock tag listing HTML file");
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/tags/Wingfic/works");
+            then.status(200).body(listing);
+        });
+        mock_server
+    }
+
     /// Creates a sample fanfiction for testing.
     pub fn given_sample_fanfiction(id: u64, title: &str) -> Fanfiction {
         Fanfiction {
@@ -273,6 +287,7 @@ pub mod fixtures {
             read_count: 0,
             user_rating: None,
             personal_note: None,
+            personal_tags: Vec::new(),
             last_checked_date: Utc::now(),
         }
     }
@@ -295,6 +310,130 @@ pub mod fixtures {
         Ok(())
     }
 
+    /// Writes a Calibre `metadata.db` into `dir`, cut down to the tables
+    /// the importer reads, and returns its path. Four books:
+    ///  - 7001 "Comet Tail": AO3 URL identifier, two authors, an HTML
+    ///    summary, `#read` set and 4 stars (8 in Calibre's half-stars)
+    ///  - 7002 "Harbour": `ao3:` identifier, `#read` cleared, unrated
+    ///  - "Original Novel": only an ISBN, so not an AO3 work
+    ///  - 7003 "Paper Moons": chapter URL, `#read` set, 5 stars
+    pub fn given_calibre_library(dir: &Path) -> PathBuf {
+        let path = dir.join("metadata.db");
+        let conn = Connection::open(&path).expect("Failed to create Calibre library");
+        conn.execute_batch(
+            "CREATE TABLE books (id INTEGER PRIMARY KEY, title TEXT NOT NULL, pubdate TIMESTAMP);
+             CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE books_authors_link (id INTEGER PRIMARY KEY, book INTEGER, author INTEGER);
+             CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE books_tags_link (id INTEGER PRIMARY KEY, book INTEGER, tag INTEGER);
+             CREATE TABLE comments (id INTEGER PRIMARY KEY, book INTEGER, text TEXT);
+             CREATE TABLE identifiers (id INTEGER PRIMARY KEY, book INTEGER, type TEXT, val TEXT);
+             CREATE TABLE ratings (id INTEGER PRIMARY KEY, rating INTEGER);
+             CREATE TABLE books_ratings_link (id INTEGER PRIMARY KEY, book INTEGER, rating INTEGER);
+             CREATE TABLE custom_columns (id INTEGER PRIMARY KEY, label TEXT, name TEXT,
+                 datatype TEXT, normalized BOOL);
+             CREATE TABLE custom_column_1 (id INTEGER PRIMARY KEY, book INTEGER, value BOOL);
+
+             INSERT INTO books VALUES
+                 (1, 'Comet Tail', '2020-05-06 07:08:09.123456+00:00'),
+                 (2, 'Harbour', '0101-01-01 00:00:00+00:00'),
+                 (3, 'Original Novel', NULL),
+                 (4, 'Paper Moons', '2021-01-02 03:04:05+00:00');
+             INSERT INTO authors VALUES (1, 'Ann'), (2, 'Bo');
+             INSERT INTO books_authors_link VALUES (1, 1, 1), (2, 1, 2), (3, 2, 2), (4, 4, 1);
+             INSERT INTO tags VALUES (1, 'Fluff');
+             INSERT INTO books_tags_link VALUES (1, 1, 1);
+             INSERT INTO comments VALUES (1, 1, '<p>First line.</p><p>Second.</p>');
+             INSERT INTO identifiers VALUES
+                 (1, 1, 'url', 'https://archiveofourown.org/works/7001'),
+                 (2, 2, 'ao3', '7002'),
+                 (3, 3, 'isbn', '9780000000000'),
+                 (4, 4, 'url', 'http://archiveofourown.org/works/7003/chapters/55');
+             INSERT INTO ratings VALUES (1, 8), (2, 10);
+             INSERT INTO books_ratings_link VALUES (1, 1, 1), (2, 4, 2);
+             INSERT INTO custom_columns VALUES (1, 'read', 'Read', 'bool', 0);
+             INSERT INTO custom_column_1 VALUES (1, 1, 1), (2, 2, 0), (3, 4, 1);",
+        )
+        .expect("Failed to fill Calibre library");
+        path
+    }
+
+    /// Writes a bookmarks export the way Firefox does and returns its
+    /// path. Links, in document order:
+    ///  - 8001 at the top level, last visited 2023-11-14 22:13:20 UTC
+    ///  - 8002 with a query string, in the Bookmarks Toolbar
+    ///  - 8003 as a chapter URL, in "Hurt/Comfort" inside the toolbar
+    ///  - a recipe site in "Hurt/Comfort", which isn't AO3
+    ///  - 8001 again, in "Fic recs"
+    pub fn given_bookmarks_file(dir: &Path) -> PathBuf {
+        let path = dir.join("bookmarks.html");
+        fs::write(
+            &path,
+            r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks Menu</H1>
+<DL><p>
+    <DT><A HREF="https://archiveofourown.org/works/8001" ADD_DATE="1600000000" LAST_VISIT="1700000000">Starlight - Chapter 1 - Ann - Fandom [Archive of Our Own]</A>
+    <DT><H3 ADD_DATE="1600000000" PERSONAL_TOOLBAR_FOLDER="true">Bookmarks Toolbar</H3>
+    <DL><p>
+        <DT><A HREF="https://archiveofourown.org/works/8002?view_adult=true">Tidewater</A>
+        <DT><H3>Hurt/Comfort</H3>
+        <DL><p>
+            <DT><A HREF="https://archiveofourown.org/works/8003/chapters/12">Lanterns</A>
+            <DT><A HREF="https://example.com/recipes">Soup</A>
+        </DL><p>
+    </DL><p>
+    <DT><H3>Fic recs</H3>
+    <DL><p>
+        <DT><A HREF="https://archiveofourown.org/works/8001">Starlight again</A>
+    </DL><p>
+</DL><p>
+"#,
+        )
+        .expect("Failed to write bookmarks file");
+        path
+    }
+
+    /// Writes a `places.sqlite` cut down to the columns the importer
+    /// reads and returns its path:
+    ///  - 8103 bookmarked as "Quiet Hours" in the "Slow Burn" folder,
+    ///    never visited, and also tagged "angst"
+    ///  - 8101 bookmarked as "Ember" on the toolbar, last visited
+    ///    2023-11-14 22:13:20 UTC
+    ///  - 8102 only in the history, through a chapter URL
+    ///  - a non-AO3 page in the history
+    pub fn given_firefox_places(dir: &Path) -> PathBuf {
+        let path = dir.join("places.sqlite");
+        let conn = Connection::open(&path).expect("Failed to create places.sqlite");
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT,
+                 last_visit_date INTEGER);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                 parent INTEGER, title TEXT, guid TEXT);
+
+             INSERT INTO moz_places VALUES
+                 (1, 'https://archiveofourown.org/works/8101',
+                     'Ember - Chapter 3 - Bo - Fandom [Archive of Our Own]', 1700000000000000),
+                 (2, 'https://archiveofourown.org/works/8102/chapters/77',
+                     'Driftwood - Chapter 2 - Ann - Fandom [Archive of Our Own]', 1650000000000000),
+                 (3, 'https://example.com/', 'Example', 1690000000000000),
+                 (4, 'https://archiveofourown.org/works/8103', NULL, NULL);
+             INSERT INTO moz_bookmarks VALUES
+                 (1, 2, NULL, 0, '', 'root________'),
+                 (2, 2, NULL, 1, 'menu', 'menu________'),
+                 (3, 2, NULL, 1, 'toolbar', 'toolbar_____'),
+                 (4, 2, NULL, 1, 'tags', 'tags________'),
+                 (5, 2, NULL, 2, 'Slow Burn', 'slowburn0001'),
+                 (6, 1, 4, 5, 'Quiet Hours', 'bookmark0001'),
+                 (7, 1, 1, 3, 'Ember', 'bookmark0002'),
+                 (8, 2, NULL, 4, 'angst', 'tagfolder001'),
+                 (9, 1, 4, 8, NULL, 'bookmark0003');",
+        )
+        .expect("Failed to fill places.sqlite");
+        path
+    }
+
     /// Fetches a fanfiction using the provided fetcher.
     pub fn when_fetching_fanfiction(
         fetcher: &dyn FanfictionFetcher,
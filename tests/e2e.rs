@@ -10,6 +10,29 @@
 //!    of the soft-deleted state). The CLI exposes the raw `shelf add
 //!    <fic-id> <shelf-id>` path, which is where the not-found
 //!    rejection lives.
+//!  * `search-local`'s ranked table with bracketed matches.
+//!  * Bringing back what `wipe` trashed with `trash restore`, and
+//!    `trash purge`'s confirmation-gated bulk path.
+//!  * `export` / `import` moving a library between two databases.
+//!  * `export --format csv` with its own column, filter and sort flags.
+//!  * `import --from calibre`'s printed preview and `--on-conflict`.
+//!  * `add --from-file` reading a rec list, which the GUI only takes
+//!    as pasted text.
+//!  * `import --from firefox|bookmarks`'s printed review and folder
+//!    shelves.
+//!  * `merge`'s printed conflict log and `--policy`.
+//!  * `backup create|list|prune`, and the backup `wipe` takes first.
+//!  * `doctor`'s report, exit status and `--fix`.
+//!  * `list`'s filter, sort, column and limit flags.
+//!  * `--output json|ndjson` and the JSON error object with its exit
+//!    code.
+//!  * Naming fics by URL or title and shelves by name or path, with the
+//!    ambiguity errors, and `COMPLETE=<shell>` suggesting them.
+//!  * `open` handing the next chapter's link to the opener command, and
+//!    `--keep-status`.
+//!  * `search`'s table of AO3 results, `--hide-tracked` and its JSON.
+//!  * `config get|set|list|path|reset`, its validation, and moving the
+//!    library by setting `library_path`.
 
 use std::env;
 use std::error::Error;
@@ -23,6 +46,7 @@ use common::{assertions, fixtures};
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ficflow::domain::fanfiction::ReadingStatus;
     use rusqlite::Connection;
     use tempfile::TempDir;
 
@@ -162,6 +186,360 @@ mod tests {
         Ok(())
     }
 
+    /// `search-local` finds a fic by a word only its summary contains
+    /// and brackets the match in the snippet column.
+    #[test]
+    fn test_search_local_matches_summary() -> Result<(), Box<dyn Error>> {
+        let test_db = setup_test_db();
+        let mut fic = fixtures::given_sample_fanfiction(4242, "Harbour Lights");
+        fic.summary = "Two keepers share one lighthouse.".to_string();
+        fixtures::when_fanfiction_added_to_db(&test_db.conn, &fic)?;
+
+        let (out, err, status) = run_cli_command(
+            &["search-local", "lighthouse"],
+            &test_db.db_path,
+            "http://127.0.0.1:1",
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Harbour Lights", "[lighthouse]"]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["search-local", "lantern"],
+            &test_db.db_path,
+            "http://127.0.0.1:1",
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["No fanfictions in your library match"]),
+            Some(&out),
+        );
+        Ok(())
+    }
+
+    /// A wiped fic is listed by `trash list`, comes back with `trash
+    /// restore`, and is gone for good after `trash purge` empties the
+    /// trash.
+    #[test]
+    fn test_trash_restore_and_purge_after_wipe() -> Result<(), Box<dyn Error>> {
+        let test_db = setup_test_db();
+        let db_path = &test_db.db_path;
+        let base = "http://127.0.0.1:1";
+        for (id, title) in [(4301, "Lantern Keeper"), (4302, "Tide Clock")] {
+            fixtures::when_fanfiction_added_to_db(
+                &test_db.conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )?;
+        }
+        let non_interactive = Some(("FICFLOW_NON_INTERACTIVE", "1"));
+        let (_, err, status) = run_cli_command(&["wipe"], db_path, base, non_interactive);
+        assertions::then_command_succeeded(status, &err, None, None);
+
+        let (out, err, status) = run_cli_command(&["trash", "list"], db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Lantern Keeper", "Tide Clock"]),
+            Some(&out),
+        );
+
+        let (out, err, status) =
+            run_cli_command(&["trash", "restore", "4301"], db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["Lantern Keeper"]), Some(&out));
+
+        let (out, err, status) =
+            run_cli_command(&["trash", "purge"], db_path, base, non_interactive);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Permanently deleted 1 fanfictions"]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(&["list"], db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["Lantern Keeper"]), Some(&out));
+        let (_, err, status) = run_cli_command(&["trash", "restore", "4302"], db_path, base, None);
+        assert_ne!(status, 0, "purged fic should no longer be restorable");
+        assert!(err.contains("not in the trash"), "stderr: {}", err);
+        Ok(())
+    }
+
+    /// `export` writes a file another library can `import`; a dry run
+    /// reports the same counts without writing anything.
+    #[test]
+    fn test_export_then_import_into_another_library() -> Result<(), Box<dyn Error>> {
+        let source = setup_test_db();
+        let target = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        for (id, title) in [(4401, "Paper Moons"), (4402, "Salt Roads")] {
+            fixtures::when_fanfiction_added_to_db(
+                &source.conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )?;
+        }
+        let file = source._temp_dir.path().join("library.json");
+        let file_arg = file.to_str().unwrap();
+
+        let (_, err, status) = run_cli_command(
+            &["export", "--output", file_arg],
+            &source.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        let (out, err, status) = run_cli_command(&["export"], &source.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["\"schema_version\": 1"]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["import", file_arg, "--dry-run"],
+            &target.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Dry run", "Would add 2 fanfictions"]),
+            Some(&out),
+        );
+        let (out, _, _) = run_cli_command(&["list"], &target.db_path, base, None);
+        assert!(!out.contains("Paper Moons"), "dry run wrote to the library");
+
+        let (out, err, status) =
+            run_cli_command(&["import", file_arg], &target.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Added 2 fanfictions"]),
+            Some(&out),
+        );
+        let (out, err, status) = run_cli_command(&["list"], &target.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Paper Moons", "Salt Roads"]),
+            Some(&out),
+        );
+        Ok(())
+    }
+
+    /// `export --format csv` prints just the selected columns for the
+    /// rows the filter keeps, in the requested order.
+    #[test]
+    fn test_export_csv_with_columns_filter_and_sort() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        for (id, title, words) in [(4501, "Short, Sweet", 800), (4502, "Long Haul", 90000)] {
+            let mut fic = fixtures::given_sample_fanfiction(id, title);
+            fic.words = words;
+            fixtures::when_fanfiction_added_to_db(&db.conn, &fic)?;
+        }
+        let mut read = fixtures::given_sample_fanfiction(4503, "Done Already");
+        read.reading_status = ReadingStatus::Read;
+        fixtures::when_fanfiction_added_to_db(&db.conn, &read)?;
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "export",
+                "--format",
+                "csv",
+                "--columns",
+                "id,title,words,tags",
+                "--separator",
+                "|",
+                "--status",
+                "plantoread",
+                "--sort",
+                "words:desc",
+            ],
+            &db.db_path,
+            base,
+            None,
+        );
+
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert_eq!(
+            out,
+            "ID,Title,Words,Tags\n\
+             4502,Long Haul,90000,Tag 1|Tag 2\n\
+             4501,\"Short, Sweet\",800,Tag 1|Tag 2\n"
+        );
+
+        let (_, err, status) = run_cli_command(
+            &["export", "--format", "csv", "--columns", "title,shoe-size"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assert_ne!(status, 0);
+        assert!(
+            err.contains("Unknown column: 'shoe-size'"),
+            "stderr: {}",
+            err
+        );
+        Ok(())
+    }
+
+    /// Cells that a spreadsheet would evaluate are exported with a
+    /// leading `'`, whatever column they're in.
+    #[test]
+    fn test_export_csv_defuses_formulas() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let mut fic = fixtures::given_sample_fanfiction(4601, "=HYPERLINK(\"http://x\")");
+        fic.personal_note = Some("@mention".into());
+        fixtures::when_fanfiction_added_to_db(&db.conn, &fic)?;
+        let mut plain = fixtures::given_sample_fanfiction(4602, "Plain - Title");
+        plain.personal_note = Some("-1 for pacing".into());
+        fixtures::when_fanfiction_added_to_db(&db.conn, &plain)?;
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "export",
+                "--format",
+                "csv",
+                "--columns",
+                "id,title,note",
+                "--sort",
+                "title",
+            ],
+            &db.db_path,
+            "http://127.0.0.1:1",
+            None,
+        );
+
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert_eq!(
+            out,
+            "ID,Title,Note\n\
+             4601,\"'=HYPERLINK(\"\"http://x\"\")\",'@mention\n\
+             4602,Plain - Title,'-1 for pacing\n"
+        );
+        Ok(())
+    }
+
+    /// `add --from-file` adds each linked work once, skips the ones the
+    /// library already has, and shelves what it added.
+    #[test]
+    fn test_add_from_file_skips_tracked_and_shelves() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let (mock_server, fic_id) = fixtures::given_mock_ao3_server();
+        let base = mock_server.base_url();
+        let tracked = fixtures::given_sample_fanfiction(4601, "Already Here");
+        fixtures::when_fanfiction_added_to_db(&db.conn, &tracked)?;
+        let (_, err, status) =
+            run_cli_command(&["shelf", "create", "Recs"], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+
+        let list = db._temp_dir.path().join("recs.txt");
+        std::fs::write(
+            &list,
+            format!(
+                "- https://archiveofourown.org/works/{fic_id} (chapter 3 made me cry)\n\
+                 - https://archiveofourown.org/works/{fic_id}/chapters/99\n\
+                 4601\n"
+            ),
+        )?;
+        let (out, err, status) = run_cli_command(
+            &["add", "--from-file", list.to_str().unwrap(), "--shelf", "1"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert!(
+            out.contains("Found 2 AO3 work(s), 1 already in your library."),
+            "stdout: {}",
+            out
+        );
+        assert_eq!(out.matches("Added: ").count(), 1, "stdout: {}", out);
+
+        let (out, err, status) = run_cli_command(&["shelf", "show", "1"], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert!(out.contains(&fic_id.to_string()), "stdout: {}", out);
+        assert!(!out.contains("Already Here"), "stdout: {}", out);
+
+        let (_, _, status) =
+            run_cli_command(&["add", "--shelf", "1", "12345"], &db.db_path, &base, None);
+        assert_ne!(status, 0, "--shelf only goes with --from-file");
+        Ok(())
+    }
+
+    /// `import --from firefox --dry-run` prints what it found without
+    /// adding anything; a bookmarks import then adds the works, shelved
+    /// under their folder.
+    #[test]
+    fn test_import_browser_links_reviews_then_adds() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let (mock_server, fic_id) = fixtures::given_mock_ao3_server();
+        let base = mock_server.base_url();
+        let places = fixtures::given_firefox_places(db._temp_dir.path());
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "import",
+                places.to_str().unwrap(),
+                "--from",
+                "firefox",
+                "--dry-run",
+            ],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert!(
+            out.contains("Found 3 AO3 works in the Firefox profile."),
+            "stdout: {}",
+            out
+        );
+        assert!(
+            out.contains("8103       Quiet Hours [shelf: Slow Burn]"),
+            "stdout: {}",
+            out
+        );
+        assert!(out.contains("[last visited 2023-11-14]"), "stdout: {}", out);
+        assert!(
+            out.contains("Dry run: nothing was changed."),
+            "stdout: {}",
+            out
+        );
+
+        let bookmarks = db._temp_dir.path().join("bookmarks.html");
+        std::fs::write(
+            &bookmarks,
+            format!(
+                "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<DL><p>\n<DT><H3>Fic recs</H3>\n<DL><p>\n\
+                 <DT><A HREF=\"https://archiveofourown.org/works/{fic_id}\">Rec</A>\n</DL><p>\n</DL><p>\n"
+            ),
+        )?;
+        let (out, err, status) = run_cli_command(
+            &["import", bookmarks.to_str().unwrap(), "--from", "bookmarks"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert!(out.contains("Added 1 fanfictions"), "stdout: {}", out);
+
+        let (out, err, status) = run_cli_command(&["shelf", "list"], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert!(out.contains("Fic recs"), "stdout: {}", out);
+        let (out, _, _) = run_cli_command(&["shelf", "show", "1"], &db.db_path, &base, None);
+        assert!(out.contains(&fic_id.to_string()), "stdout: {}", out);
+        Ok(())
+    }
+
     /// Add → get → wipe round-trip via the CLI binary. Covers the
     /// `get` command's pretty-print output and the `wipe` happy path
     /// — neither has a GUI counterpart.
@@ -398,4 +776,870 @@ mod tests {
         );
         Ok(())
     }
+
+    /// `config set` checks values before writing them, and setting
+    /// `library_path` to a folder moves the library file into it.
+    #[test]
+    fn test_config_reads_validates_and_moves_the_library() -> Result<(), Box<dyn Error>> {
+        let config_home = TempDir::new()?;
+        let old_lib = TempDir::new()?;
+        let configured_db = old_lib.path().join("my-library.db");
+        write_config_with_library_path(config_home.path(), &configured_db);
+        let (_, status) = run_cli(&["shelf", "create", "Moved"], config_home.path(), None);
+        assert_eq!(status, 0);
+
+        let (path_out, _) = run_cli(&["config", "path"], config_home.path(), None);
+        assert_eq!(
+            PathBuf::from(path_out.trim()),
+            config_home.path().join("ficflow").join("config.toml")
+        );
+
+        let (_, status) = run_cli(
+            &["config", "set", "theme", "Dark"],
+            config_home.path(),
+            None,
+        );
+        assert_eq!(status, 0);
+        let (theme, _) = run_cli(&["config", "get", "theme"], config_home.path(), None);
+        assert_eq!(theme.trim(), "dark");
+
+        for bad in [
+            ["text-zoom", "3"],
+            ["visible_columns", "title,nope"],
+            ["theme", "sepia"],
+            ["ao3_mirror", "ftp://example.org"],
+            ["no_such_setting", "1"],
+        ] {
+            let (_, status) = run_cli(&["config", "set", bad[0], bad[1]], config_home.path(), None);
+            assert_eq!(
+                status, 2,
+                "config set {} {} should be rejected",
+                bad[0], bad[1]
+            );
+        }
+
+        let new_lib = TempDir::new()?;
+        let (moved_out, status) = run_cli(
+            &[
+                "config",
+                "set",
+                "library_path",
+                new_lib.path().to_str().unwrap(),
+            ],
+            config_home.path(),
+            None,
+        );
+        assert_eq!(status, 0, "{}", moved_out);
+        let moved_db = new_lib.path().join("my-library.db");
+        assert!(
+            moved_db.exists(),
+            "the library should be moved into the folder"
+        );
+        assert!(!configured_db.exists());
+        let (shelves, _) = run_cli(&["shelf", "list"], config_home.path(), None);
+        assert!(shelves.contains("Moved"), "got: {}", shelves);
+
+        let (listed, _) = run_cli(
+            &["--output", "json", "config", "list"],
+            config_home.path(),
+            None,
+        );
+        let listed: serde_json::Value = serde_json::from_str(&listed)?;
+        let value_of = |key: &str| {
+            listed
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|entry| entry["key"] == key)
+                .map(|entry| entry["value"].clone())
+                .unwrap()
+        };
+        assert_eq!(value_of("theme"), "dark");
+        assert_eq!(value_of("library_path"), moved_db.display().to_string());
+        assert!(value_of("opener").is_null());
+
+        let (_, status) = run_cli(&["config", "reset", "theme"], config_home.path(), None);
+        assert_eq!(status, 0);
+        let (theme, _) = run_cli(&["config", "get", "theme"], config_home.path(), None);
+        assert_eq!(theme.trim(), "system");
+        Ok(())
+    }
+
+    /// A dry run lists what a Calibre import would do, conflicts
+    /// included; `--on-conflict theirs` then takes Calibre's values.
+    #[test]
+    fn test_import_from_calibre_previews_then_applies() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        let calibre = fixtures::given_calibre_library(db._temp_dir.path());
+        let calibre_arg = calibre.to_str().unwrap();
+        fixtures::when_fanfiction_added_to_db(
+            &db.conn,
+            &fixtures::given_sample_fanfiction(7001, "Comet Tail"),
+        )?;
+
+        let (out, err, status) = run_cli_command(
+            &["import", calibre_arg, "--from", "calibre", "--dry-run"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&[
+                "Found 3 AO3 works",
+                "2 not in your library yet",
+                "status read (yours: plan to read), rating 4/5 (yours: none) [keeping yours]",
+                "Dry run: nothing was changed.",
+            ]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "import",
+                calibre_arg,
+                "--from",
+                "calibre",
+                "--on-conflict",
+                "theirs",
+                "--no-fetch",
+            ],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Added 2 fanfictions", "status or rating for 1"]),
+            Some(&out),
+        );
+        let (out, err, status) = run_cli_command(&["get", "7001"], &db.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Reading Status:      Read"]),
+            Some(&out),
+        );
+        let (out, _, _) = run_cli_command(&["list"], &db.db_path, base, None);
+        assert!(out.contains("Paper Moons"), "stdout: {}", out);
+        Ok(())
+    }
+
+    /// `merge` folds another library's .db in: the conflict log names
+    /// the fic whose status differs, and `--policy theirs` takes the
+    /// other side's.
+    #[test]
+    fn test_merge_another_library_with_policy() -> Result<(), Box<dyn Error>> {
+        let mine = setup_test_db();
+        let other = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        fixtures::when_fanfiction_added_to_db(
+            &mine.conn,
+            &fixtures::given_sample_fanfiction(4801, "Tidewater"),
+        )?;
+        let mut read = fixtures::given_sample_fanfiction(4801, "Tidewater");
+        read.reading_status = ReadingStatus::Read;
+        fixtures::when_fanfiction_added_to_db(&other.conn, &read)?;
+        fixtures::when_fanfiction_added_to_db(
+            &other.conn,
+            &fixtures::given_sample_fanfiction(4802, "Lamplight"),
+        )?;
+        let other_arg = other.db_path.to_str().unwrap();
+
+        let (out, err, status) = run_cli_command(
+            &["merge", other_arg, "--dry-run"],
+            &mine.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&[
+                "Dry run",
+                "Would add 1 fanfictions",
+                "Conflicts (1):",
+                "Tidewater: status plan to read (theirs: read)",
+            ]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["merge", other_arg, "--policy", "theirs"],
+            &mine.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Merge complete.", "[took theirs]"]),
+            Some(&out),
+        );
+        let status: String = mine.conn.query_row(
+            "SELECT reading_status FROM fanfiction WHERE id = 4801",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(status, "Read");
+        let (out, _, _) = run_cli_command(&["list"], &mine.db_path, base, None);
+        assert!(out.contains("Lamplight"), "stdout: {}", out);
+        Ok(())
+    }
+
+    /// A command that writes takes the day's backup and one that only
+    /// reads doesn't; `backup create` adds one, `wipe` takes its own
+    /// before wiping, and `prune --keep` trims each kind.
+    #[test]
+    fn test_backup_create_list_prune_and_before_wipe() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        fixtures::when_fanfiction_added_to_db(
+            &db.conn,
+            &fixtures::given_sample_fanfiction(4901, "Ferry Lights"),
+        )?;
+
+        for args in [&["backup", "list"][..], &["config", "path"], &["list"]] {
+            let (out, err, status) = run_cli_command(args, &db.db_path, base, None);
+            assertions::then_command_succeeded(status, &err, None, Some(&out));
+        }
+        let (_, _, status) = run_cli_command(&["lsit"], &db.db_path, base, None);
+        assert_eq!(status, 2);
+        let (out, _, _) = run_cli_command(&["backup", "list"], &db.db_path, base, None);
+        assert!(out.contains("No backups yet."), "stdout: {}", out);
+        let (out, err, status) =
+            run_cli_command(&["shelf", "create", "Ferries"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, None, Some(&out));
+
+        let (out, err, status) = run_cli_command(&["backup", "create"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["Backed up to"]), Some(&out));
+        let (_, err, status) = run_cli_command(
+            &["wipe"],
+            &db.db_path,
+            base,
+            Some(("FICFLOW_NON_INTERACTIVE", "1")),
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+
+        let (out, err, status) = run_cli_command(&["backup", "list"], &db.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Found 3 backups", "daily", "manual", "wipe"]),
+            Some(&out),
+        );
+        let wipe_backup = out
+            .split_whitespace()
+            .find(|word| word.ends_with("-wipe.db"))
+            .expect("wipe backup listed");
+        let saved = Connection::open(wipe_backup)?;
+        let title: String =
+            saved.query_row("SELECT title FROM fanfiction WHERE id = 4901", [], |r| {
+                r.get(0)
+            })?;
+        assert_eq!(title, "Ferry Lights");
+
+        let (_, err, status) = run_cli_command(&["backup", "create"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        let (out, err, status) =
+            run_cli_command(&["backup", "prune", "--keep", "1"], &db.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Deleted 1 backups, keeping the newest 1 of each kind."]),
+            Some(&out),
+        );
+        let (out, _, _) = run_cli_command(&["backup", "list"], &db.db_path, base, None);
+        assert!(out.contains("Found 3 backups"), "stdout: {}", out);
+        assert!(out.contains("wipe"), "stdout: {}", out);
+        Ok(())
+    }
+
+    #[test]
+    fn test_doctor_reports_then_fixes_problems() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        fixtures::when_fanfiction_added_to_db(
+            &db.conn,
+            &fixtures::given_sample_fanfiction(5001, "Cracked Bell"),
+        )?;
+
+        let (out, err, status) = run_cli_command(&["doctor"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["No problems found."]), Some(&out));
+
+        db.conn.execute(
+            "UPDATE fanfiction SET personal_tags = 'reread' WHERE id = 5001",
+            [],
+        )?;
+        let (out, _, status) = run_cli_command(&["doctor"], &db.db_path, base, None);
+        assert_ne!(status, 0, "stdout: {}", out);
+        assert!(out.contains("Found 1 problems"), "stdout: {}", out);
+        assert!(
+            out.contains("Fic 5001 has an unreadable personal_tags list"),
+            "stdout: {}",
+            out
+        );
+
+        let (out, err, status) = run_cli_command(&["doctor", "--fix"], &db.db_path, base, None);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Backed up to", "-repair.db", "Repaired 1 problems."]),
+            Some(&out),
+        );
+        let (out, err, status) = run_cli_command(&["get", "5001"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["Cracked Bell"]), Some(&out));
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_filters_sorts_and_picks_columns() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        let specs = [
+            (
+                5101,
+                "Quiet Harbor",
+                ReadingStatus::Read,
+                12_000,
+                true,
+                "Angst",
+            ),
+            (
+                5102,
+                "Loud Harbor",
+                ReadingStatus::Read,
+                48_000,
+                false,
+                "Angst",
+            ),
+            (5103, "Tiny Harbor", ReadingStatus::Read, 900, true, "Angst"),
+            (
+                5104,
+                "Other Harbor",
+                ReadingStatus::PlanToRead,
+                60_000,
+                true,
+                "Angst",
+            ),
+            (
+                5105,
+                "Fluffy Harbor",
+                ReadingStatus::Read,
+                30_000,
+                true,
+                "Fluff",
+            ),
+        ];
+        for (id, title, status, words, complete, tag) in specs {
+            let mut fic = fixtures::given_sample_fanfiction(id, title);
+            fic.reading_status = status;
+            fic.words = words;
+            fic.complete = complete;
+            fic.kudos = id as u32;
+            fic.tags = Some(vec![tag.to_string()]);
+            fixtures::when_fanfiction_added_to_db(&db.conn, &fic)?;
+        }
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "list",
+                "--status",
+                "read",
+                "--tag",
+                "angst",
+                "--complete",
+                "--min-words",
+                "1000",
+                "--sort",
+                "words:desc",
+                "--columns",
+                "title,kudos",
+            ],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Found 1 fanfictions", "Quiet Harbor", "Kudos", "5,101"]),
+            Some(&out),
+        );
+        assert!(!out.contains("Words"), "stdout: {}", out);
+        for excluded in [
+            "Loud Harbor",
+            "Tiny Harbor",
+            "Other Harbor",
+            "Fluffy Harbor",
+        ] {
+            assert!(!out.contains(excluded), "stdout: {}", out);
+        }
+
+        let (out, err, status) = run_cli_command(
+            &["list", "--complete=no", "--fandom", "test fandom"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, Some(&["Loud Harbor"]), Some(&out));
+        assert!(out.contains("Found 1 fanfictions"), "stdout: {}", out);
+
+        let (out, err, status) = run_cli_command(
+            &["list", "--sort", "words:desc", "--limit", "2"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Showing 2 of 5 fanfictions", "Other Harbor", "Loud Harbor"]),
+            Some(&out),
+        );
+        assert!(
+            out.find("Other Harbor") < out.find("Loud Harbor"),
+            "stdout: {}",
+            out
+        );
+
+        let (_, err, status) = run_cli_command(
+            &["list", "--columns", "title,mood"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assert_ne!(status, 0);
+        assert!(err.contains("Unknown column: 'mood'"), "stderr: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_json_prints_entities_and_error_objects() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let (mock_server, fic_id) = fixtures::given_mock_ao3_server();
+        let base = mock_server.base_url();
+        let id = fic_id.to_string();
+
+        let (out, err, status) =
+            run_cli_command(&["--output", "json", "add", &id], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        let added: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(added["id"], fic_id);
+
+        let (out, err, status) = run_cli_command(
+            &["--output", "ndjson", "shelf", "create", "Favorites"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        let shelf: serde_json::Value = serde_json::from_str(out.trim())?;
+        assert_eq!(shelf["name"], "Favorites");
+
+        let (out, err, status) = run_cli_command(
+            &["--output", "ndjson", "status", &id, "read"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        let updated: serde_json::Value = serde_json::from_str(out.trim())?;
+        assert_eq!(updated["reading_status"], "Read");
+
+        let (out, err, status) =
+            run_cli_command(&["--output", "ndjson", "list"], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 1, "stdout: {}", out);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(lines[0])?["id"],
+            fic_id
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["--output", "json", "get", "999"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assert_eq!(status, 3, "stderr: {}", err);
+        assert!(out.is_empty(), "stdout: {}", out);
+        let error: serde_json::Value = serde_json::from_str(&err)?;
+        assert_eq!(error["error"]["code"], "not_found");
+        assert_eq!(error["error"]["exit_code"], 3);
+
+        let (_, err, status) = run_cli_command(
+            &[
+                "--output", "json", "shelf", "create", "Nested", "--parent", "42",
+            ],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assert_eq!(status, 3, "stderr: {}", err);
+        let error: serde_json::Value = serde_json::from_str(&err)?;
+        assert_eq!(error["error"]["code"], "shelf_not_found");
+
+        let (out, err, status) =
+            run_cli_command(&["--output", "json", "doctor"], &db.db_path, &base, None);
+        assertions::then_command_succeeded(status, &err, None, None);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&out)?,
+            serde_json::json!([])
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["--output", "json", "backup", "create"],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        let backup: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(backup["reason"], "Manual");
+        assert!(Path::new(backup["path"].as_str().unwrap()).exists());
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "--output",
+                "ndjson",
+                "search-local",
+                added["title"].as_str().unwrap(),
+            ],
+            &db.db_path,
+            &base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, None);
+        let hit: serde_json::Value = serde_json::from_str(out.lines().next().unwrap_or(""))?;
+        assert_eq!(hit["fic"]["id"], fic_id);
+
+        let (_, err, status) =
+            run_cli_command(&["--output", "json", "tui"], &db.db_path, &base, None);
+        assert_eq!(status, 2, "stderr: {}", err);
+        let error: serde_json::Value = serde_json::from_str(&err)?;
+        assert_eq!(error["error"]["code"], "invalid_input");
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands_take_titles_urls_and_shelf_paths() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        for (id, title) in [
+            (5201, "Harbor Lights"),
+            (5202, "Cold Harbor"),
+            (5203, "Harbor"),
+            (5204, "1984"),
+        ] {
+            let fic = fixtures::given_sample_fanfiction(id, title);
+            fixtures::when_fanfiction_added_to_db(&db.conn, &fic)?;
+        }
+        for args in [
+            &["shelf", "create", "Fandoms"][..],
+            &["shelf", "create", "Drafts", "--parent", "fandoms"],
+            &["shelf", "create", "Drafts"],
+            &["shelf", "create", "2024"],
+        ] {
+            let (out, err, status) = run_cli_command(args, &db.db_path, base, None);
+            assertions::then_command_succeeded(status, &err, None, Some(&out));
+        }
+
+        let (out, err, status) = run_cli_command(
+            &["get", "https://archiveofourown.org/works/5202/chapters/1"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, Some(&["Cold Harbor"]), Some(&out));
+
+        let (out, err, status) =
+            run_cli_command(&["status", "harbor l", "read"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, None, Some(&out));
+        let (out, _, _) = run_cli_command(
+            &["--output", "json", "get", "5201"],
+            &db.db_path,
+            base,
+            None,
+        );
+        let fic: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(fic["reading_status"], "Read");
+
+        let (out, err, status) = run_cli_command(&["get", "HARBOR"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["5203"]), Some(&out));
+        let (_, err, status) = run_cli_command(&["get", "har"], &db.db_path, base, None);
+        assert_eq!(status, 2, "stderr: {}", err);
+        assert!(
+            err.contains("'har' matches 2 fics in your library")
+                && err.contains("Harbor Lights (5201)"),
+            "stderr: {}",
+            err
+        );
+
+        // Numbers nothing has as its ID are names.
+        let (out, err, status) = run_cli_command(&["get", "1984"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["5204"]), Some(&out));
+        let (out, err, status) =
+            run_cli_command(&["shelf", "add", "1984", "2024"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, None, Some(&out));
+        let (out, err, status) =
+            run_cli_command(&["shelf", "show", "2024"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["1984"]), Some(&out));
+        let (_, err, status) = run_cli_command(&["get", "77"], &db.db_path, base, None);
+        assert_eq!(status, 3, "stderr: {}", err);
+        assert!(err.contains("ID 77 not found"), "stderr: {}", err);
+
+        let (_, err, status) = run_cli_command(
+            &["--output", "json", "get", "Lighthouse"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assert_eq!(status, 3, "stderr: {}", err);
+        let error: serde_json::Value = serde_json::from_str(&err)?;
+        assert_eq!(error["error"]["code"], "not_found");
+        let (_, err, status) =
+            run_cli_command(&["shelf", "show", "Lighthouses"], &db.db_path, base, None);
+        assert_eq!(status, 3, "stderr: {}", err);
+        assert!(
+            err.contains("No shelf is named or at 'Lighthouses'"),
+            "stderr: {}",
+            err
+        );
+
+        let (out, err, status) = run_cli_command(
+            &["shelf", "add", "Cold", "Fandoms/Drafts"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, None, Some(&out));
+        let (_, err, status) =
+            run_cli_command(&["shelf", "show", "drafts"], &db.db_path, base, None);
+        assert_eq!(status, 2, "stderr: {}", err);
+        assert!(
+            err.contains("Fandoms/Drafts (2), Drafts (3)"),
+            "stderr: {}",
+            err
+        );
+        let (out, err, status) = run_cli_command(
+            &["shelf", "show", "fandoms / drafts"],
+            &db.db_path,
+            base,
+            None,
+        );
+        assertions::then_command_succeeded(status, &err, Some(&["Cold Harbor"]), Some(&out));
+
+        let (out, err, status) =
+            run_cli_command(&["delete", "Cold Harbor"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, None, Some(&out));
+        let (out, err, status) =
+            run_cli_command(&["trash", "restore", "cold"], &db.db_path, base, None);
+        assertions::then_command_succeeded(status, &err, Some(&["Cold Harbor"]), Some(&out));
+
+        let (out, err, status) = run_cli_command(
+            &["--", "ficflow", "shelf", "add", "Harbor", "Fa"],
+            &db.db_path,
+            base,
+            Some(("COMPLETE", "fish")),
+        );
+        assert_eq!(status, 0, "stderr: {}", err);
+        assert!(out.contains("Fandoms/Drafts\tshelf 2"), "stdout: {}", out);
+        assert!(!out.contains("Drafts\tshelf 3"), "stdout: {}", out);
+        let (out, _, _) = run_cli_command(
+            &["--", "ficflow", "get", "Co"],
+            &db.db_path,
+            base,
+            Some(("COMPLETE", "fish")),
+        );
+        assert!(out.contains("Cold Harbor\tID 5202"), "stdout: {}", out);
+        Ok(())
+    }
+
+    /// A Tab press reads the library and nothing more: one an older
+    /// build left is neither upgraded nor backed up, just not offered.
+    #[test]
+    fn test_completion_leaves_an_older_library_alone() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        fixtures::when_fanfiction_added_to_db(
+            &db.conn,
+            &fixtures::given_sample_fanfiction(5202, "Cold Harbor"),
+        )?;
+        let (out, _, _) = run_cli_command(
+            &["--", "ficflow", "get", "Co"],
+            &db.db_path,
+            base,
+            Some(("COMPLETE", "fish")),
+        );
+        assert!(out.contains("Cold Harbor"), "stdout: {}", out);
+
+        let version: i32 = db.conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        db.conn
+            .execute_batch(&format!("PRAGMA user_version = {};", version - 1))?;
+        let (out, err, status) = run_cli_command(
+            &["--", "ficflow", "get", "Co"],
+            &db.db_path,
+            base,
+            Some(("COMPLETE", "fish")),
+        );
+
+        assert_eq!(status, 0, "stderr: {}", err);
+        assert!(!out.contains("Cold Harbor"), "stdout: {}", out);
+        let after: i32 = db.conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        assert_eq!(after, version - 1);
+        let backups = db.db_path.parent().unwrap().join("backups");
+        assert!(!backups.exists(), "a Tab press took a backup");
+        Ok(())
+    }
+
+    /// Reads the links the stub opener was given. The opener is spawned
+    /// without being waited for, so this polls for the line it writes.
+    fn wait_for_opened(log: &Path, count: usize) -> Vec<String> {
+        for _ in 0..50 {
+            if let Ok(contents) = std::fs::read_to_string(log) {
+                let lines: Vec<String> = contents.lines().map(str::to_string).collect();
+                if lines.len() >= count {
+                    return lines;
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        panic!("the opener wasn't run {} times", count);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_launches_the_next_chapter() -> Result<(), Box<dyn Error>> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let db = setup_test_db();
+        let base = "http://127.0.0.1:1";
+        let mut paused = fixtures::given_sample_fanfiction(5301, "Slow Burn");
+        paused.chapters_published = 5;
+        paused.last_chapter_read = Some(1);
+        paused.reading_status = ReadingStatus::Paused;
+        fixtures::when_fanfiction_added_to_db(&db.conn, &paused)?;
+        let unread = fixtures::given_sample_fanfiction(5302, "Someday");
+        fixtures::when_fanfiction_added_to_db(&db.conn, &unread)?;
+
+        let stub = TempDir::new()?;
+        let log = stub.path().join("opened.txt");
+        let script = stub.path().join("opener.sh");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\nprintf '%s\\n' \"$1\" >> '{}'\n", log.display()),
+        )?;
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+        let opener = Some(("FICFLOW_OPENER", script.to_str().unwrap()));
+
+        let (out, err, status) = run_cli_command(&["open", "slow"], &db.db_path, base, opener);
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&["Opened chapter 2 of \"Slow Burn\""]),
+            Some(&out),
+        );
+        assert_eq!(
+            wait_for_opened(&log, 1)[0],
+            "https://archiveofourown.org/works/5301?view_full_work=true#chapter-2"
+        );
+        let (out, _, _) = run_cli_command(
+            &["--output", "json", "get", "5301"],
+            &db.db_path,
+            base,
+            None,
+        );
+        let fic: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(fic["reading_status"], "InProgress");
+
+        let (out, err, status) = run_cli_command(
+            &["--output", "json", "open", "5302", "--keep-status"],
+            &db.db_path,
+            base,
+            opener,
+        );
+        assert_eq!(status, 0, "stderr: {}", err);
+        let opened: serde_json::Value = serde_json::from_str(&out)?;
+        assert_eq!(opened["chapter"], 1);
+        assert_eq!(opened["url"], "https://archiveofourown.org/works/5302");
+        assert_eq!(opened["fic"]["reading_status"], "PlanToRead");
+        assert_eq!(
+            wait_for_opened(&log, 2)[1],
+            "https://archiveofourown.org/works/5302"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_lists_ao3_results_against_the_library() -> Result<(), Box<dyn Error>> {
+        let db = setup_test_db();
+        let server = fixtures::given_mock_ao3_tag_listing();
+        let tracked = fixtures::given_sample_fanfiction(58812004, "Flightless");
+        fixtures::when_fanfiction_added_to_db(&db.conn, &tracked)?;
+
+        let (out, err, status) = run_cli_command(
+            &["search", "--tag", "Wingfic"],
+            &db.db_path,
+            &server.base_url(),
+            None,
+        );
+        assertions::then_command_succeeded(
+            status,
+            &err,
+            Some(&[
+                "Page 1 of 61 of AO3 works tagged 'Wingfic'",
+                "Featherlight",
+                "Molting Season",
+                "quillfeather, Skyward_Ink",
+                "48,310",
+                "7/?",
+                "More with --page 2",
+            ]),
+            Some(&out),
+        );
+
+        let (out, err, status) = run_cli_command(
+            &[
+                "--output",
+                "json",
+                "search",
+                "--tag",
+                "Wingfic",
+                "--hide-tracked",
+            ],
+            &db.db_path,
+            &server.base_url(),
+            None,
+        );
+        assert_eq!(status, 0, "stderr: {}", err);
+        let found: serde_json::Value = serde_json::from_str(&out)?;
+        let ids: Vec<u64> = found["blurbs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![53960491, 61230077]);
+        assert_eq!(found["hidden"], 1);
+        assert_eq!(found["page_count"], 61);
+
+        let (_, err, status) = run_cli_command(
+            &["search", "--tag", "Wingfc"],
+            &db.db_path,
+            &server.base_url(),
+            None,
+        );
+        assert_eq!(status, 2, "stderr: {}", err);
+        assert!(
+            err.contains("AO3 has no tag named 'Wingfc'"),
+            "stderr: {}",
+            err
+        );
+        Ok(())
+    }
 }
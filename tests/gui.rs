@@ -54,3 +54,36 @@ mod theme;
 
 #[path = "gui/shelf_delete_key.rs"]
 mod shelf_delete_key;
+
+#[path = "gui/saved_searches.rs"]
+mod saved_searches;
+
+#[path = "gui/full_text_search.rs"]
+mod full_text_search;
+
+#[path = "gui/trash.rs"]
+mod trash;
+
+#[path = "gui/undo.rs"]
+mod undo;
+
+#[path = "gui/export.rs"]
+mod export;
+
+#[path = "gui/imports.rs"]
+mod imports;
+
+#[path = "gui/backups.rs"]
+mod backups;
+
+#[path = "gui/health.rs"]
+mod health;
+
+#[path = "gui/reading.rs"]
+mod reading;
+
+#[path = "gui/discover.rs"]
+mod discover;
+
+#[path = "gui/instance.rs"]
+mod instance;
@@ -85,6 +85,38 @@ mod tests {
         assert!(h.app.visible_ids().is_empty());
     }
 
+    #[test]
+    fn auto_shelf_text_clauses_fold_ascii_case_and_and_narrows_them() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut beta = fixtures::given_sample_fanfiction(602, "Beta");
+        beta.fandoms = vec!["Star Trek".to_string()];
+        let mut gamma = fixtures::given_sample_fanfiction(603, "Gamma");
+        gamma.fandoms = vec!["Star Trek".to_string()];
+        gamma.reading_status = ReadingStatus::Read;
+        fixtures::when_fanfiction_added_to_db(&conn, &beta).unwrap();
+        fixtures::when_fanfiction_added_to_db(&conn, &gamma).unwrap();
+
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        h.step_n(1);
+
+        let criteria = AutoShelfCriteria {
+            logic: ClauseLogic::And,
+            clauses: vec![
+                Clause::Fandom("STAR TREK".to_string()),
+                Clause::Status(ReadingStatus::Read),
+            ],
+        };
+        h.app
+            .upsert_auto_shelf(None, "Trek, read", criteria)
+            .expect("valid auto-shelf");
+        let shelf_id = h.app.shelves()[0].id;
+
+        assert_eq!(h.app.shelf_count(shelf_id), 1);
+        h.app.open_view(View::Shelf(shelf_id));
+        h.step();
+        assert_eq!(h.app.visible_ids(), vec![603]);
+    }
+
     #[test]
     fn add_fic_to_shelf_rejects_auto_shelf() {
         let (conn, db_path, td) = fixtures::given_test_database();
@@ -0,0 +1,58 @@
+//! Group N — automatic and manual library backups.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::backup::BackupReason;
+    use rusqlite::Connection;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    fn reasons(h: &GuiHarness) -> Vec<BackupReason> {
+        h.app.backups().iter().map(|b| b.reason).collect()
+    }
+
+    /// N1 — opening takes the day's backup once, closing takes another,
+    /// and restoring one backs up the library it replaces first.
+    #[test]
+    fn backups_taken_on_open_exit_and_before_restore() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(7401, "Tin Lantern"),
+        )
+        .unwrap();
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        assert_eq!(reasons(&h), vec![BackupReason::Daily]);
+
+        h.restart(vec!["http://127.0.0.1:1".into()]);
+        assert_eq!(reasons(&h), vec![BackupReason::Daily], "a second daily");
+
+        let manual = h.app.create_backup(BackupReason::Manual).unwrap();
+        h.conn
+            .execute(
+                "UPDATE fanfiction SET title = 'Renamed' WHERE id = 7401",
+                [],
+            )
+            .unwrap();
+        h.app.restore_library_backup(manual.path.clone());
+        assert_eq!(
+            reasons(&h),
+            vec![
+                BackupReason::Restore,
+                BackupReason::Manual,
+                BackupReason::Daily
+            ]
+        );
+        let before_restore = Connection::open(&h.app.backups()[0].path).unwrap();
+        let title: String = before_restore
+            .query_row("SELECT title FROM fanfiction WHERE id = 7401", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(title, "Renamed");
+
+        eframe::App::on_exit(&mut h.app);
+        assert_eq!(reasons(&h)[0], BackupReason::Exit);
+    }
+}
@@ -1,7 +1,7 @@
 //! Group D — bulk operations on multi-selection.
 //!
 //! Covers `update_status` ×N, `add_to_shelf` ×N, `remove_from_shelf`
-//! ×N, `delete_fic` ×N. Every scenario builds a multi-selection via
+//! ×N, `delete_fic` ×N, and the bulk editor's field edits. Every scenario builds a multi-selection via
 //! `select_fics`, runs the bulk action through the same control-surface
 //! method the per-fic tests use (since the GUI's bulk-handler is
 //! literally a `for id in ids { … }` loop), and asserts every selected
@@ -9,7 +9,7 @@
 
 #[cfg(test)]
 mod tests {
-    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus};
+    use ficflow::domain::fanfiction::{BulkEdit, FanfictionOps, ReadingStatus, UserRating};
     use ficflow::domain::shelf::ShelfOps;
     use ficflow::infrastructure::SqliteRepository;
     use ficflow::interfaces::gui::{Selection, View};
@@ -153,4 +153,98 @@ mod tests {
         // Selection cleared via the post-render orphan-prune path.
         assert!(matches!(h.app.selection(), Selection::None));
     }
+
+    /// D25 — the bulk editor's edits land on every selected fic: rating,
+    /// "mark all as read", appended note and a personal tag.
+    #[test]
+    fn bulk_editor_applies_fields_to_every_selected_fic() {
+        let (mut h, ids) = given_harness_with_n_fics(3);
+        h.app.set_note(ids[0], Some("Earlier thoughts")).unwrap();
+        h.app.select_fics(&ids);
+        h.step();
+
+        for edit in [
+            BulkEdit::UserRating(Some(UserRating::Four)),
+            BulkEdit::MarkFullyRead,
+            BulkEdit::AppendNote("Rec from the discord".into()),
+            BulkEdit::AddPersonalTag("comfort".into()),
+        ] {
+            assert_eq!(h.app.bulk_edit(&ids, &edit), (3, 0));
+        }
+
+        // The sample fics have 1 of 2 chapters out, so catching up on
+        // them starts reading them rather than finishing them.
+        let repo = SqliteRepository::new(&h.conn);
+        for fic in repo.list_fanfictions().unwrap() {
+            assert_eq!(fic.user_rating, Some(UserRating::Four));
+            assert_eq!(fic.reading_status, ReadingStatus::InProgress);
+            assert_eq!(fic.read_count, 0);
+            assert_eq!(fic.last_chapter_read, Some(fic.chapters_published));
+            assert_eq!(fic.personal_tags, vec!["comfort".to_string()]);
+        }
+        let first = repo.get_fanfiction_by_id(ids[0]).unwrap();
+        assert_eq!(
+            first.personal_note.as_deref(),
+            Some("Earlier thoughts\n\nRec from the discord")
+        );
+        let second = repo.get_fanfiction_by_id(ids[1]).unwrap();
+        assert_eq!(
+            second.personal_note.as_deref(),
+            Some("Rec from the discord")
+        );
+    }
+
+    /// D25b — "mark all as read" follows the single-fic chapter rules:
+    /// only a complete work counts as read, a work in progress with no
+    /// known total is only started, and a fic already caught up isn't
+    /// read a second time.
+    #[test]
+    fn bulk_mark_read_only_finishes_complete_works() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut complete = fixtures::given_sample_fanfiction(1, "Complete");
+        complete.chapters_published = 2;
+        let mut open_ended = fixtures::given_sample_fanfiction(2, "Open-ended");
+        open_ended.chapters_total = None;
+        open_ended.chapters_published = 5;
+        let mut finished = fixtures::given_sample_fanfiction(3, "Finished");
+        finished.chapters_published = 2;
+        finished.last_chapter_read = Some(2);
+        finished.reading_status = ReadingStatus::Read;
+        finished.read_count = 1;
+        for fic in [&complete, &open_ended, &finished] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic).unwrap();
+        }
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+
+        assert_eq!(
+            h.app.bulk_edit(&[1, 2, 3], &BulkEdit::MarkFullyRead),
+            (3, 0)
+        );
+
+        let repo = SqliteRepository::new(&h.conn);
+        let complete = repo.get_fanfiction_by_id(1).unwrap();
+        assert_eq!(complete.reading_status, ReadingStatus::Read);
+        assert_eq!(complete.read_count, 1);
+        let open_ended = repo.get_fanfiction_by_id(2).unwrap();
+        assert_eq!(open_ended.last_chapter_read, Some(5));
+        assert_eq!(open_ended.reading_status, ReadingStatus::InProgress);
+        assert_eq!(open_ended.read_count, 0);
+        let finished = repo.get_fanfiction_by_id(3).unwrap();
+        assert_eq!(finished.read_count, 1);
+    }
+
+    /// D26 — ids that no longer exist are counted as failures while the
+    /// rest are still saved; one undo reverts the whole edit.
+    #[test]
+    fn bulk_editor_counts_failures_and_undoes_as_one_step() {
+        let (mut h, ids) = given_harness_with_n_fics(2);
+        let targets = vec![ids[0], ids[1], 999];
+
+        let result = h.app.bulk_edit(&targets, &BulkEdit::ReadCount(4));
+        assert_eq!(result, (2, 1));
+        assert!(h.app.fics().iter().all(|f| f.read_count == 4));
+
+        h.app.undo().unwrap();
+        assert!(h.app.fics().iter().all(|f| f.read_count == 0));
+    }
 }
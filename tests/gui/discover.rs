@@ -0,0 +1,85 @@
+//! Discover: searching an AO3 tag from the GUI, with works already in
+//! the library flagged or hidden, and adding results through the task
+//! executor.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::discover::Ao3Search;
+    use ficflow::interfaces::gui::View;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    const FEATHERLIGHT: u64 = 53960491;
+    const MOLTING_SEASON: u64 = 61230077;
+    const FLIGHTLESS: u64 = 58812004;
+
+    fn wingfic() -> Ao3Search {
+        Ao3Search::Tag("Wingfic".to_string())
+    }
+
+    #[test]
+    fn tag_results_flag_the_library_and_add_through_tasks() {
+        let server = fixtures::given_mock_ao3_tag_listing();
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let tracked = fixtures::given_sample_fanfiction(FLIGHTLESS, "Flightless");
+        fixtures::when_fanfiction_added_to_db(&conn, &tracked).unwrap();
+        let mut h = GuiHarness::with_db(vec![server.base_url()], conn, db_path, td);
+        h.app.open_view(View::Discover);
+        h.step();
+
+        h.app.search_ao3(wingfic(), 1, false);
+        assert!(h.wait_for_tasks(2000), "search didn't finish");
+        let page = h.app.discover_results().expect("a page of results");
+        assert_eq!((page.page, page.page_count), (1, 61));
+        let tracked: Vec<(u64, bool)> = page.blurbs.iter().map(|b| (b.id, b.tracked)).collect();
+        assert_eq!(
+            tracked,
+            vec![
+                (FEATHERLIGHT, false),
+                (MOLTING_SEASON, false),
+                (FLIGHTLESS, true)
+            ]
+        );
+
+        // The tracked one is skipped; the other is queued once.
+        assert_eq!(h.app.add_discovered(&[FEATHERLIGHT, FLIGHTLESS]), 1);
+        assert_eq!(h.app.add_discovered(&[FEATHERLIGHT]), 0);
+        assert!(h.wait_for_tasks(2000), "add didn't finish");
+        assert!(h.app.fics().iter().any(|f| f.id == FEATHERLIGHT));
+        let page = h.app.discover_results().unwrap();
+        assert!(
+            page.blurbs
+                .iter()
+                .find(|b| b.id == FEATHERLIGHT)
+                .unwrap()
+                .tracked
+        );
+
+        h.app.search_ao3(wingfic(), 1, true);
+        assert!(h.wait_for_tasks(2000), "search didn't finish");
+        let page = h.app.discover_results().unwrap();
+        let ids: Vec<u64> = page.blurbs.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![MOLTING_SEASON]);
+        assert_eq!(page.hidden, 2);
+    }
+
+    #[test]
+    fn a_failed_search_keeps_the_last_results() {
+        let server = fixtures::given_mock_ao3_tag_listing();
+        let mut h = GuiHarness::new(vec![server.base_url()]);
+        h.app.open_view(View::Discover);
+
+        h.app.search_ao3(wingfic(), 1, false);
+        assert!(h.wait_for_tasks(2000), "search didn't finish");
+        h.app
+            .search_ao3(Ao3Search::Tag("No Such Tag".to_string()), 1, false);
+        assert!(h.wait_for_tasks(2000), "search didn't finish");
+
+        assert_eq!(h.app.discover_results().unwrap().blurbs.len(), 3);
+        assert_eq!(
+            h.app.discover_error(),
+            Some("Couldn't search AO3: invalid input: AO3 has no tag named 'No Such Tag'")
+        );
+    }
+}
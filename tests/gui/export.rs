@@ -0,0 +1,52 @@
+//! Group L — exporting the visible rows to CSV/TSV.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::transfer::{ExportFormat, TableField};
+    use ficflow::interfaces::gui::{ColumnKey, ExportRequest, SortDirection};
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    /// L1 — only the rows the search leaves are written, in the table's
+    /// sort order, under the visible columns plus the chosen extras.
+    #[test]
+    fn export_writes_visible_rows_in_table_order() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut first = fixtures::given_sample_fanfiction(11, "Comet Tail");
+        first.authors = vec!["Ann".into(), "Bo".into()];
+        for fic in [
+            &first,
+            &fixtures::given_sample_fanfiction(12, "Another Comet"),
+            &fixtures::given_sample_fanfiction(13, "Harbour"),
+        ] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic).unwrap();
+        }
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        h.app.set_search("comet");
+        h.app.set_sort(ColumnKey::Title, SortDirection::Descending);
+        h.step();
+        let out = tempfile::NamedTempFile::new().unwrap();
+
+        let request = ExportRequest {
+            format: ExportFormat::Tsv,
+            extras: vec![TableField::Id],
+            separator: " & ".into(),
+        };
+        let written = h.app.export_visible_rows(out.path(), &request).unwrap();
+
+        assert_eq!(written, 2);
+        let text = std::fs::read_to_string(out.path()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Title\tAuthor\tStatus\tLast Ch.\tRating\tReads\tUpdated\tID"
+        );
+        assert_eq!(
+            lines[1],
+            "Comet Tail\tAnn & Bo\tPlanToRead\t\t\t0\t2025-01-01\t11"
+        );
+        assert!(lines[2].starts_with("Another Comet\t"));
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -0,0 +1,76 @@
+//! Group I — full-text search over summaries, notes and tags.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::interfaces::gui::{ColumnKey, SortDirection};
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    fn given_harness_with_comets() -> GuiHarness {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut in_summary = fixtures::given_sample_fanfiction(901, "Aaa Night");
+        in_summary.summary = "A keeper watches the comet pass.".to_string();
+        let in_title = fixtures::given_sample_fanfiction(902, "Zzz Comet");
+        let unrelated = fixtures::given_sample_fanfiction(903, "Mmm Harbour");
+        for fic in [&in_summary, &in_title, &unrelated] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic).unwrap();
+        }
+        GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td)
+    }
+
+    /// I1 — a fresh query matches summaries too, ranks title hits first,
+    /// and exposes a snippet with the matched term highlighted.
+    #[test]
+    fn search_matches_summaries_in_relevance_order_with_snippets() {
+        let mut h = given_harness_with_comets();
+        h.app.set_search("comet");
+        h.step();
+
+        assert_eq!(h.app.visible_ids(), vec![902, 901]);
+        let snippet = h
+            .app
+            .search_snippet(901)
+            .expect("summary hit has a snippet");
+        let highlighted: Vec<&str> = snippet
+            .highlights
+            .iter()
+            .map(|r| &snippet.text[r.clone()])
+            .collect();
+        assert_eq!(highlighted, vec!["comet"]);
+    }
+
+    /// I2 — picking a column leaves relevance order; a new query goes
+    /// back to it.
+    #[test]
+    fn choosing_a_sort_overrides_relevance_until_the_query_changes() {
+        let mut h = given_harness_with_comets();
+        h.app.set_search("comet");
+        h.app.set_sort(ColumnKey::Title, SortDirection::Ascending);
+        h.step();
+        assert_eq!(h.app.visible_ids(), vec![901, 902]);
+
+        h.app.set_search("comet pass");
+        h.step();
+        assert_eq!(h.app.visible_ids(), vec![901]);
+        h.app.set_search("comet");
+        assert_eq!(h.app.visible_ids(), vec![902, 901]);
+    }
+
+    /// I3 — editing a personal note re-runs the active search, so the
+    /// fic joins the results without retyping the query.
+    #[test]
+    fn editing_a_note_updates_the_active_search() {
+        let mut h = given_harness_with_comets();
+        h.app.set_search("harbour lights");
+        h.step();
+        assert!(h.app.visible_ids().is_empty());
+
+        h.app
+            .set_note(903, Some("the lights of the harbour at dusk"))
+            .unwrap();
+        h.step();
+
+        assert_eq!(h.app.visible_ids(), vec![903]);
+    }
+}
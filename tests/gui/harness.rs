@@ -132,6 +132,24 @@ impl GuiHarness {
         });
     }
 
+    /// Run one frame and return every piece of text it painted, e.g. to
+    /// check which table rows were drawn.
+    pub fn step_and_read_text(&mut self) -> Vec<String> {
+        let raw_input = egui::RawInput {
+            max_texture_side: Some(8192),
+            ..Default::default()
+        };
+        let app = &mut self.app;
+        let output = self.ctx.run_ui(raw_input, |ui| {
+            app.render(ui);
+        });
+        let mut texts = Vec::new();
+        for clipped in &output.shapes {
+            collect_text(&clipped.shape, &mut texts);
+        }
+        texts
+    }
+
     pub fn step_with_close_request(&mut self) -> egui::FullOutput {
         let mut raw_input = egui::RawInput {
             max_texture_side: Some(8192),
@@ -152,6 +170,11 @@ impl GuiHarness {
     /// Run one frame with a single key press injected, so shortcut
     /// handlers (`handle_shortcuts`) see `key_pressed(key)` this frame.
     pub fn step_with_key(&mut self, key: egui::Key) {
+        self.step_with_shortcut(key, egui::Modifiers::default());
+    }
+
+    /// Like `step_with_key`, with modifiers held (e.g. Ctrl+Z).
+    pub fn step_with_shortcut(&mut self, key: egui::Key, modifiers: egui::Modifiers) {
         let mut raw_input = egui::RawInput {
             max_texture_side: Some(8192),
             ..Default::default()
@@ -161,7 +184,7 @@ impl GuiHarness {
             physical_key: None,
             pressed: true,
             repeat: false,
-            modifiers: egui::Modifiers::default(),
+            modifiers,
         });
         let app = &mut self.app;
         let _ = self.ctx.run_ui(raw_input, |ui| {
@@ -197,3 +220,11 @@ impl GuiHarness {
         false
     }
 }
+
+fn collect_text(shape: &egui::Shape, texts: &mut Vec<String>) {
+    match shape {
+        egui::Shape::Text(text) => texts.push(text.galley.text().to_string()),
+        egui::Shape::Vec(shapes) => shapes.iter().for_each(|s| collect_text(s, texts)),
+        _ => {}
+    }
+}
@@ -0,0 +1,47 @@
+//! Group O — the startup library check and its banner.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::backup::BackupReason;
+    use ficflow::domain::health::LibraryIssue;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    /// O1 — a fic with an unreadable column keeps the whole library from
+    /// loading; the banner's repair backs up, clears it, and reloads.
+    #[test]
+    fn startup_check_flags_problem_and_repair_clears_it() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        for (id, title) in [(7501, "Sound"), (7502, "Frayed")] {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "UPDATE fanfiction SET personal_tags = '{' WHERE id = 7502",
+            [],
+        )
+        .unwrap();
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        assert_eq!(
+            h.app.library_issues(),
+            [LibraryIssue::BadFicJson {
+                fic_id: 7502,
+                column: "personal_tags"
+            }]
+        );
+        h.step();
+
+        h.app.repair_library();
+
+        assert!(h.app.library_issues().is_empty());
+        assert_eq!(h.app.backups()[0].reason, BackupReason::Repair);
+        let mut titles: Vec<String> = h.app.fics().into_iter().map(|f| f.title).collect();
+        titles.sort();
+        assert_eq!(titles, ["Frayed", "Sound"]);
+        h.step();
+    }
+}
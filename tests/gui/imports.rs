@@ -0,0 +1,171 @@
+//! Group M — importing from other apps' libraries and browsers, and
+//! merging in another ficflow library.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::fanfiction::ReadingStatus;
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::domain::transfer::{ConflictChoice, ImportSource, MergePolicy, MergeSide};
+    use ficflow::infrastructure::SqliteRepository;
+    use ficflow::interfaces::gui::TaskKind;
+    use tempfile::TempDir;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    /// M1 — the Calibre preview holds conflicts until confirmed; applying
+    /// it adds the new fics, takes the chosen values, and queues an AO3
+    /// refresh for each fic it added.
+    #[test]
+    fn calibre_import_previews_then_queues_refreshes() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut comet = fixtures::given_sample_fanfiction(7001, "Comet Tail");
+        comet.reading_status = ReadingStatus::Paused;
+        fixtures::when_fanfiction_added_to_db(&conn, &comet).unwrap();
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        let calibre_dir = TempDir::new().unwrap();
+        let calibre = fixtures::given_calibre_library(calibre_dir.path());
+
+        h.app.preview_calibre_import(&calibre).unwrap();
+        h.step();
+
+        assert_eq!(h.app.fics().len(), 1, "the preview wrote to the library");
+        let plan = h.app.pending_fic_import_mut().expect("preview is open");
+        assert_eq!(plan.new.len(), 2);
+        assert_eq!(plan.conflicts.len(), 1);
+        plan.conflicts[0].choice = ConflictChoice::UseIncoming;
+        let plan = plan.clone();
+
+        let summary = h.app.apply_fic_import(plan).unwrap();
+        h.step();
+
+        assert_eq!((summary.added, summary.updated), (2, 1));
+        assert_eq!(h.app.fics().len(), 3);
+        let comet = h.app.fics().into_iter().find(|f| f.id == 7001).unwrap();
+        assert_eq!(comet.reading_status, ReadingStatus::Read);
+        let mut refreshed: Vec<u64> = h
+            .app
+            .task_states()
+            .iter()
+            .filter(|t| t.kind == TaskKind::Refresh)
+            .map(|t| t.input.parse().unwrap())
+            .collect();
+        refreshed.sort();
+        assert_eq!(refreshed, vec![7002, 7003]);
+    }
+
+    /// M2 — AO3 links from a bookmarks export: the review screen lists
+    /// the works not tracked yet, only the ticked ones are queued, each
+    /// lands on a shelf named after its folder, and a visit after the
+    /// last update marks it started.
+    #[test]
+    fn bookmark_import_reviews_then_queues_ticked_works() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let (mock_server, fic_id) = fixtures::given_mock_ao3_server();
+        let mut h = GuiHarness::with_db(vec![mock_server.base_url()], conn, db_path, td);
+        h.step_n(1);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        std::fs::write(
+            &path,
+            format!(
+                r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><H3>Fic recs</H3>
+    <DL><p>
+        <DT><A HREF="https://archiveofourown.org/works/{fic_id}" LAST_VISIT="4102444800">Finished it</A>
+        <DT><A HREF="https://archiveofourown.org/works/8899">Maybe later</A>
+    </DL><p>
+</DL><p>
+"#
+            ),
+        )
+        .unwrap();
+
+        h.app
+            .preview_link_import(ImportSource::Bookmarks, &path)
+            .unwrap();
+        h.step();
+
+        assert!(h.app.task_states().is_empty(), "the review queued adds");
+        let plan = h.app.pending_link_import_mut().expect("review is open");
+        assert_eq!(plan.works.len(), 2);
+        plan.works[1].selected = false;
+        let plan = plan.clone();
+
+        assert_eq!(h.app.queue_link_import(plan).unwrap(), 1);
+        assert!(h.wait_for_tasks(80));
+
+        let shelf = h
+            .app
+            .shelves()
+            .iter()
+            .find(|s| s.name == "Fic recs")
+            .unwrap();
+        let repo = SqliteRepository::new(&h.conn);
+        let shelved: Vec<u64> = repo
+            .list_fics_in_shelf(shelf.id)
+            .unwrap()
+            .iter()
+            .map(|f| f.id)
+            .collect();
+        assert_eq!(shelved, vec![fic_id]);
+        let added = h.app.fics().into_iter().find(|f| f.id == fic_id).unwrap();
+        // A visit only shows the reader started the work, and this one
+        // is finished, so it says nothing about the chapter either.
+        assert_eq!(added.reading_status, ReadingStatus::InProgress);
+        assert_eq!(added.read_count, 0);
+        assert_eq!(added.last_chapter_read, None);
+        assert_eq!(h.app.fics().len(), 1, "the unticked work was added");
+    }
+
+    /// M3 — the merge preview is a dry run that follows the chosen
+    /// policy; confirming adds the other library's fics and shelves and
+    /// settles the conflict the way the preview said.
+    #[test]
+    fn merge_preview_follows_policy_then_applies() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(7301, "Glass Harbour"),
+        )
+        .unwrap();
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+
+        let (other, other_path, _other_td) = fixtures::given_test_database();
+        let mut paused = fixtures::given_sample_fanfiction(7301, "Glass Harbour");
+        paused.reading_status = ReadingStatus::Paused;
+        fixtures::when_fanfiction_added_to_db(&other, &paused).unwrap();
+        fixtures::when_fanfiction_added_to_db(
+            &other,
+            &fixtures::given_sample_fanfiction(7302, "Iron Orchard"),
+        )
+        .unwrap();
+        let other_repo = SqliteRepository::new(&other);
+        let shelf = other_repo.create_shelf("From laptop", None).unwrap();
+        other_repo.add_fic_to_shelf(7302, shelf.id).unwrap();
+        drop(other);
+
+        h.app.preview_library_merge(&other_path).unwrap();
+        h.step();
+
+        assert_eq!(h.app.fics().len(), 1, "the preview wrote to the library");
+        let state = h.app.pending_merge_mut().expect("preview is open");
+        assert_eq!(state.preview.fics_added, 1);
+        assert_eq!(state.preview.conflicts[0].kept, MergeSide::Theirs);
+        state.policy = MergePolicy::KeepMine;
+        h.app.refresh_merge_preview();
+        let state = h.app.pending_merge_mut().unwrap();
+        assert_eq!(state.preview.conflicts[0].kept, MergeSide::Mine);
+
+        let report = h.app.confirm_library_merge().unwrap();
+        h.step();
+
+        assert_eq!(report.shelves_added, 1);
+        assert!(h.app.pending_merge_mut().is_none());
+        assert_eq!(h.app.fics().len(), 2);
+        let harbour = h.app.fics().into_iter().find(|f| f.id == 7301).unwrap();
+        assert_eq!(harbour.reading_status, ReadingStatus::PlanToRead);
+        assert!(h.app.shelves().iter().any(|s| s.name == "From laptop"));
+    }
+}
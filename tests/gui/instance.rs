@@ -0,0 +1,42 @@
+//! The window's side of the single-instance socket: what other launches,
+//! the CLI and `ficflow://` links send reaches the running app. The socket
+//! lives in a scratch folder rather than the runtime one.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::interfaces::instance::{self, Claim, Instance, Message};
+    use tempfile::TempDir;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    #[cfg(unix)]
+    #[test]
+    fn messages_sent_to_the_window_add_and_reload() {
+        let (server, fic_id) = fixtures::given_mock_ao3_server();
+        let mut h = GuiHarness::new(vec![server.base_url()]);
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("run").join("ficflow.sock");
+        let Ok(Claim::First(listening)) = Instance::claim(&socket) else {
+            panic!("nothing else has the socket");
+        };
+        h.app.listen(&h.ctx, listening);
+
+        let link = format!("https://archiveofourown.org/works/{}/chapters/1", fic_id);
+        assert!(instance::send(&socket, &Message::Add { fic: link }).unwrap());
+        h.step();
+        assert!(h.app.has_running_tasks(), "the add was queued");
+        assert!(h.wait_for_tasks(2000), "add didn't finish");
+        assert_eq!(h.app.fics().len(), 1);
+        assert_eq!(h.app.fics()[0].id, fic_id);
+
+        // Written behind the window's back, the way the CLI does.
+        let other = fixtures::given_sample_fanfiction(7, "Quiet Harbor");
+        fixtures::when_fanfiction_added_to_db(&h.conn, &other).unwrap();
+        h.step();
+        assert_eq!(h.app.library_count(), 1);
+        assert!(instance::send(&socket, &Message::Reload).unwrap());
+        h.step();
+        assert_eq!(h.app.library_count(), 2);
+    }
+}
@@ -1,12 +1,16 @@
 //! Group A — library essentials.
 //!
-//! Covers `add_fic`, `delete_fic`, `get_fic`, `list_fics`, `check_updates`.
+//! Covers `add_fic` (one at a time and pasted in bulk), `delete_fic`, `get_fic`,
+//! `list_fics`, `check_updates`.
 //! Each test boots a `GuiHarness` against a per-test temp DB and (when
 //! the scenario needs network) an `httpmock` AO3 server.
 
 #[cfg(test)]
 mod tests {
-    use ficflow::interfaces::gui::{Selection, TaskStatus, View};
+    use ficflow::domain::fanfiction::{ColumnKey, SortDirection};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::infrastructure::SqliteRepository;
+    use ficflow::interfaces::gui::{Selection, TaskKind, TaskStatus, View};
 
     use crate::common::fixtures;
     use crate::harness::GuiHarness;
@@ -225,4 +229,69 @@ mod tests {
             "last_checked_date should advance past the seeded 2020 timestamp"
         );
     }
+
+    /// A7 — bulk add from pasted text: every work link is picked out,
+    /// repeats and fics already in the library are skipped, and the
+    /// rest are queued as adds that land on the chosen shelf.
+    #[test]
+    fn bulk_add_from_pasted_text_skips_tracked_and_shelves_the_rest() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let tracked = fixtures::given_sample_fanfiction(777, "Already Here");
+        fixtures::when_fanfiction_added_to_db(&conn, &tracked).unwrap();
+        let (mock_server, fic_id) = fixtures::given_mock_ao3_server();
+        let mut h = GuiHarness::with_db(vec![mock_server.base_url()], conn, db_path, td);
+        h.step_n(1);
+        h.app.create_shelf("Recs").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+
+        let pasted = format!(
+            "recs!! https://archiveofourown.org/works/{fic_id}/chapters/2 is so good\n\
+             and again archiveofourown.org/works/{fic_id}\n\
+             777"
+        );
+        let queued = h.app.submit_bulk_add(&pasted, Some(shelf_id)).unwrap();
+        assert_eq!(queued, 1, "the repeat and the tracked fic are skipped");
+        assert!(h.wait_for_tasks(80));
+
+        let states = h.app.task_states();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].kind, TaskKind::Add);
+        assert!(matches!(states[0].status, TaskStatus::Done));
+        let repo = SqliteRepository::new(&h.conn);
+        let shelved: Vec<u64> = repo
+            .list_fics_in_shelf(shelf_id)
+            .unwrap()
+            .iter()
+            .map(|f| f.id)
+            .collect();
+        assert_eq!(shelved, vec![fic_id]);
+
+        assert!(h.app.submit_bulk_add("nothing to see", None).is_err());
+        assert_eq!(h.app.task_states().len(), 1);
+    }
+
+    /// A8 — the table reads a long view a page at a time: the first
+    /// rows are drawn without the far ones being needed, and an edit
+    /// shows on the next frame instead of a stale page.
+    #[test]
+    fn long_library_is_drawn_a_page_at_a_time_and_stays_current() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        for id in 1..=450 {
+            let fic = fixtures::given_sample_fanfiction(id, &format!("Row {:03}", id));
+            fixtures::when_fanfiction_added_to_db(&conn, &fic).unwrap();
+        }
+        let mut h = GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td);
+        h.app.set_sort(ColumnKey::Title, SortDirection::Ascending);
+
+        let drawn = h.step_and_read_text();
+        assert!(drawn.iter().any(|t| t == "Row 001"), "{:?}", drawn);
+        assert!(!drawn.iter().any(|t| t == "Row 450"));
+        assert_eq!(h.app.visible_ids().len(), 450);
+        assert_eq!(h.app.visible_ids()[449], 450);
+
+        assert!(!drawn.iter().any(|t| t == "737"));
+        h.app.set_read_count(1, 737).unwrap();
+        let drawn = h.step_and_read_text();
+        assert!(drawn.iter().any(|t| t == "737"), "{:?}", drawn);
+    }
 }
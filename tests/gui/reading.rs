@@ -0,0 +1,72 @@
+//! "Continue reading": the details-panel button opens the fic at the
+//! chapter after the last one read, through the configured opener, and
+//! marks a planned fic In Progress as an undoable edit.
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    use ficflow::domain::fanfiction::ReadingStatus;
+    use ficflow::interfaces::gui::AppConfig;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    const AO3: &str = "http://127.0.0.1:1";
+
+    /// Points the harness's config at a script that appends each link
+    /// it's given to `log`, then restarts the app to pick it up.
+    fn given_stub_opener(h: &mut GuiHarness, dir: &Path, log: &Path) {
+        let script = dir.join("opener.sh");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\nprintf '%s\\n' \"$1\" >> '{}'\n", log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut config = AppConfig::load();
+        config.opener = Some(script.display().to_string());
+        config.save().unwrap();
+        h.restart(vec![AO3.into()]);
+    }
+
+    fn wait_for_opened(log: &Path) -> String {
+        for _ in 0..50 {
+            if let Ok(contents) = std::fs::read_to_string(log)
+                && !contents.is_empty()
+            {
+                return contents.trim_end().to_string();
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        panic!("the opener wasn't run");
+    }
+
+    #[test]
+    fn continue_reading_opens_the_next_chapter_and_starts_the_fic() {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut fic = fixtures::given_sample_fanfiction(701, "Long Haul");
+        fic.chapters_published = 12;
+        fic.last_chapter_read = Some(3);
+        fixtures::when_fanfiction_added_to_db(&conn, &fic).unwrap();
+        let mut h = GuiHarness::with_db(vec![AO3.into()], conn, db_path, td);
+        let stub = tempfile::TempDir::new().unwrap();
+        let log = stub.path().join("opened.txt");
+        given_stub_opener(&mut h, stub.path(), &log);
+        h.step();
+
+        assert_eq!(h.app.continue_reading(701).unwrap(), 4);
+        h.step();
+        assert_eq!(
+            wait_for_opened(&log),
+            "https://archiveofourown.org/works/701?view_full_work=true#chapter-4"
+        );
+        assert_eq!(h.app.fics()[0].reading_status, ReadingStatus::InProgress);
+
+        h.app.undo().unwrap();
+        h.step();
+        assert_eq!(h.app.fics()[0].reading_status, ReadingStatus::PlanToRead);
+    }
+}
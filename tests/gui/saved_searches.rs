@@ -0,0 +1,166 @@
+//! Group H — saved searches as sidebar entries.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::fanfiction::{ColumnKey, ReadingStatus, SortDirection};
+    use ficflow::domain::saved_search::SearchScope;
+    use ficflow::interfaces::gui::View;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    fn given_harness_with_fics() -> GuiHarness {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        let mut alpha = fixtures::given_sample_fanfiction(801, "Alpha Dawn");
+        alpha.reading_status = ReadingStatus::Read;
+        alpha.words = 500;
+        let mut beta = fixtures::given_sample_fanfiction(802, "Beta Dawn");
+        beta.words = 9000;
+        let gamma = fixtures::given_sample_fanfiction(803, "Gamma Dusk");
+        for fic in [&alpha, &beta, &gamma] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic).unwrap();
+        }
+        GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td)
+    }
+
+    /// H1 — saving captures the query, the current view as scope, and the
+    /// current sort; the sidebar count reflects the matches.
+    #[test]
+    fn save_search_captures_query_scope_and_sort() {
+        let mut h = given_harness_with_fics();
+        h.app.open_view(View::ByStatus(ReadingStatus::PlanToRead));
+        h.app.set_search("dawn");
+        h.app.set_sort(ColumnKey::Words, SortDirection::Ascending);
+
+        h.app.save_search("Dawn queue").unwrap();
+        h.step();
+
+        let saved = &h.app.saved_searches()[0];
+        assert_eq!(saved.name, "Dawn queue");
+        assert_eq!(saved.query, "dawn");
+        assert_eq!(
+            saved.scope,
+            SearchScope::ByStatus(ReadingStatus::PlanToRead)
+        );
+        assert_eq!(saved.sort.column, ColumnKey::Words);
+        assert_eq!(saved.sort.direction, SortDirection::Ascending);
+        assert_eq!(h.app.saved_search_count(saved.id), 1);
+    }
+
+    /// H2 — opening a saved search restores its query and sort and
+    /// filters by its scope.
+    #[test]
+    fn opening_a_saved_search_restores_query_and_sort() {
+        let mut h = given_harness_with_fics();
+        h.app.set_search("dawn");
+        h.app.set_sort(ColumnKey::Words, SortDirection::Descending);
+        h.app.save_search("Dawn").unwrap();
+        let id = h.app.saved_searches()[0].id;
+
+        h.app.set_search("");
+        h.app.set_sort(ColumnKey::Title, SortDirection::Ascending);
+        h.app.open_view(View::SavedSearch(id));
+        h.step();
+
+        assert_eq!(h.app.search_query(), "dawn");
+        assert_eq!(h.app.sort().column, ColumnKey::Words);
+        assert_eq!(h.app.visible_ids(), vec![802, 801]);
+    }
+
+    /// H3 — the count is live: a status change moving a fic out of the
+    /// search's scope updates it without reopening anything.
+    #[test]
+    fn saved_search_count_tracks_status_changes() {
+        let mut h = given_harness_with_fics();
+        h.app.open_view(View::ByStatus(ReadingStatus::PlanToRead));
+        h.app.set_search("dawn");
+        h.app.save_search("Dawn queue").unwrap();
+        let id = h.app.saved_searches()[0].id;
+        assert_eq!(h.app.saved_search_count(id), 1);
+
+        h.app.set_status(802, ReadingStatus::Read).unwrap();
+
+        assert_eq!(h.app.saved_search_count(id), 0);
+    }
+
+    /// H4 — a shelf-scoped search only counts fics on that shelf, and
+    /// follows membership changes.
+    #[test]
+    fn shelf_scoped_search_follows_shelf_membership() {
+        let mut h = given_harness_with_fics();
+        h.app.create_shelf("Favorites").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.add_fic_to_shelf(801, shelf_id).unwrap();
+        h.app.open_view(View::Shelf(shelf_id));
+        h.app.set_search("dawn");
+        h.app.save_search("Fav dawn").unwrap();
+        let id = h.app.saved_searches()[0].id;
+        assert_eq!(h.app.saved_search_count(id), 1);
+
+        h.app.add_fic_to_shelf(802, shelf_id).unwrap();
+        assert_eq!(h.app.saved_search_count(id), 2);
+
+        h.app.open_view(View::SavedSearch(id));
+        h.step();
+        let mut visible = h.app.visible_ids();
+        visible.sort();
+        assert_eq!(visible, vec![801, 802]);
+    }
+
+    /// H5 — rename and reorder persist and show up in sidebar order.
+    #[test]
+    fn rename_and_reorder_saved_searches() {
+        let mut h = given_harness_with_fics();
+        h.app.set_search("alpha");
+        h.app.save_search("First").unwrap();
+        h.app.set_search("beta");
+        h.app.save_search("Second").unwrap();
+        let second = h.app.saved_searches()[1].id;
+
+        h.app.rename_saved_search(second, "Renamed").unwrap();
+        h.app.move_saved_search(second, 0).unwrap();
+
+        let names: Vec<&str> = h
+            .app
+            .saved_searches()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Renamed", "First"]);
+    }
+
+    /// H6 — a saved search can be the startup view: restarting restores
+    /// the view along with its query.
+    #[test]
+    fn restart_reopens_saved_search_view() {
+        let mut h = given_harness_with_fics();
+        h.app.set_search("dusk");
+        h.app.save_search("Dusk").unwrap();
+        let id = h.app.saved_searches()[0].id;
+        h.app.open_view(View::SavedSearch(id));
+
+        h.restart(vec!["http://127.0.0.1:1".into()]);
+
+        assert_eq!(*h.app.current_view(), View::SavedSearch(id));
+        assert_eq!(h.app.search_query(), "dusk");
+        assert_eq!(h.app.visible_ids(), vec![803]);
+    }
+
+    /// H7 — deleting the open saved search drops back to all fics, and a
+    /// restart doesn't resurrect it.
+    #[test]
+    fn deleting_open_saved_search_falls_back_to_all_fics() {
+        let mut h = given_harness_with_fics();
+        h.app.set_search("dusk");
+        h.app.save_search("Dusk").unwrap();
+        let id = h.app.saved_searches()[0].id;
+        h.app.open_view(View::SavedSearch(id));
+
+        h.app.delete_saved_search(id).unwrap();
+
+        assert_eq!(*h.app.current_view(), View::AllFics);
+        assert!(h.app.saved_searches().is_empty());
+        h.restart(vec!["http://127.0.0.1:1".into()]);
+        assert_eq!(*h.app.current_view(), View::AllFics);
+    }
+}
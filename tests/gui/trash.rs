@@ -0,0 +1,98 @@
+//! Group J — the trash: restoring and purging deleted fics and shelves.
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::infrastructure::SqliteRepository;
+    use ficflow::interfaces::gui::View;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    fn given_harness_with_fics() -> GuiHarness {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        for (id, title) in [(1001, "Kept"), (1002, "Doomed")] {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )
+            .unwrap();
+        }
+        GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td)
+    }
+
+    /// J1 — a deleted fic shows up in the trash and comes back, shelf
+    /// and all, on restore.
+    #[test]
+    fn restoring_a_deleted_fic_puts_it_back_on_its_shelf() {
+        let mut h = given_harness_with_fics();
+        h.app.create_shelf("Favorites").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.add_fic_to_shelf(1001, shelf_id).unwrap();
+        h.app.select_fic(1001);
+        h.app.delete_selected();
+        h.app.open_view(View::Trash);
+        h.step();
+
+        let trashed: Vec<u64> = h.app.trashed_fics().iter().map(|t| t.fic.id).collect();
+        assert_eq!(trashed, vec![1001]);
+        assert_eq!(h.app.shelf_count(shelf_id), 0);
+
+        h.app.restore_fic(1001).unwrap();
+        h.step();
+
+        assert!(h.app.trashed_fics().is_empty());
+        assert!(h.app.fics().iter().any(|f| f.id == 1001));
+        assert_eq!(h.app.shelf_count(shelf_id), 1);
+    }
+
+    /// J2 — a deleted shelf is listed in the trash and reappears in the
+    /// sidebar on restore.
+    #[test]
+    fn restoring_a_deleted_shelf_returns_it_to_the_sidebar() {
+        let mut h = given_harness_with_fics();
+        h.app.create_shelf("Later").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.delete_shelf(shelf_id).unwrap();
+        h.step();
+
+        assert!(h.app.shelves().is_empty());
+        assert_eq!(h.app.trashed_shelves()[0].shelf.id, shelf_id);
+
+        h.app.restore_shelf(shelf_id).unwrap();
+        h.step();
+
+        assert_eq!(h.app.shelves()[0].id, shelf_id);
+        assert!(h.app.trashed_shelves().is_empty());
+    }
+
+    /// J3 — purging one fic and then emptying the trash leaves nothing
+    /// behind in the database.
+    #[test]
+    fn purge_and_empty_remove_items_for_good() {
+        let mut h = given_harness_with_fics();
+        h.app.select_fics(&[1001, 1002]);
+        h.app.delete_selected();
+        h.app.create_shelf("Gone").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.delete_shelf(shelf_id).unwrap();
+        h.step();
+
+        h.app.purge_fic(1002).unwrap();
+        let trashed: Vec<u64> = h.app.trashed_fics().iter().map(|t| t.fic.id).collect();
+        assert_eq!(trashed, vec![1001]);
+
+        h.app.empty_trash().unwrap();
+        h.step();
+
+        assert!(h.app.trashed_fics().is_empty());
+        assert!(h.app.trashed_shelves().is_empty());
+        let rows: u32 = h
+            .conn
+            .query_row("SELECT COUNT(*) FROM fanfiction", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+        let repo = SqliteRepository::new(&h.conn);
+        assert!(repo.list_shelves().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+//! Group K — undo/redo of library edits.
+
+#[cfg(test)]
+mod tests {
+    use egui::{Key, Modifiers};
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus};
+    use ficflow::domain::trash::TrashOps;
+    use ficflow::infrastructure::SqliteRepository;
+
+    use crate::common::fixtures;
+    use crate::harness::GuiHarness;
+
+    fn given_harness_with_fics() -> GuiHarness {
+        let (conn, db_path, td) = fixtures::given_test_database();
+        for (id, title) in [(1001, "First"), (1002, "Second")] {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )
+            .unwrap();
+        }
+        GuiHarness::with_db(vec!["http://127.0.0.1:1".into()], conn, db_path, td)
+    }
+
+    fn status_of(h: &GuiHarness, fic_id: u64) -> ReadingStatus {
+        h.app
+            .fics()
+            .iter()
+            .find(|f| f.id == fic_id)
+            .unwrap()
+            .reading_status
+    }
+
+    /// K1 — a bulk status change is one entry: Ctrl+Z reverts every fic,
+    /// Ctrl+Shift+Z applies it again.
+    #[test]
+    fn bulk_status_change_undoes_and_redoes_as_one_step() {
+        let mut h = given_harness_with_fics();
+        let original = status_of(&h, 1001);
+        h.app
+            .bulk_set_status(&[1001, 1002], ReadingStatus::Abandoned);
+        assert!(h.app.undo_prompt().is_some());
+
+        h.step_with_shortcut(Key::Z, Modifiers::COMMAND);
+        assert_eq!(status_of(&h, 1001), original);
+        assert_eq!(status_of(&h, 1002), original);
+        assert!(!h.app.can_undo());
+        assert!(h.app.can_redo());
+
+        h.step_with_shortcut(Key::Z, Modifiers::COMMAND | Modifiers::SHIFT);
+        assert_eq!(status_of(&h, 1001), ReadingStatus::Abandoned);
+        assert_eq!(status_of(&h, 1002), ReadingStatus::Abandoned);
+        assert!(h.app.can_undo());
+    }
+
+    /// K2 — finishing the last chapter also changes the status; undo
+    /// puts both fields back.
+    #[test]
+    fn undoing_a_chapter_update_restores_its_knock_on_fields() {
+        let mut h = given_harness_with_fics();
+        let before = h
+            .app
+            .fics()
+            .into_iter()
+            .find(|f| f.id == 1001)
+            .unwrap()
+            .clone();
+        let last = before.chapters_published;
+        h.app.set_last_chapter(1001, last).unwrap();
+
+        h.app.undo().unwrap();
+
+        let after = h.app.fics().into_iter().find(|f| f.id == 1001).unwrap();
+        assert_eq!(after.user_fields(), before.user_fields());
+    }
+
+    /// K3 — undoing an add only takes away memberships the add created;
+    /// a fic that was already on the shelf stays.
+    #[test]
+    fn undoing_shelf_add_keeps_prior_members() {
+        let mut h = given_harness_with_fics();
+        h.app.create_shelf("Favorites").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.add_fic_to_shelf(1001, shelf_id).unwrap();
+        h.app.bulk_add_to_shelf(&[1001, 1002], shelf_id);
+        assert_eq!(h.app.shelf_count(shelf_id), 2);
+
+        h.app.undo().unwrap();
+        assert_eq!(h.app.shelf_count(shelf_id), 1);
+
+        h.app.undo().unwrap();
+        assert_eq!(h.app.shelf_count(shelf_id), 0);
+    }
+
+    /// K4 — shelf rename and pin are reverted in turn.
+    #[test]
+    fn shelf_rename_and_pin_can_be_undone() {
+        let mut h = given_harness_with_fics();
+        h.app.create_shelf("Later").unwrap();
+        let shelf_id = h.app.shelves()[0].id;
+        h.app.rename_shelf(shelf_id, "Someday").unwrap();
+        h.app.toggle_pin_shelf(shelf_id).unwrap();
+        assert!(h.app.shelves()[0].pinned);
+
+        h.app.undo().unwrap();
+        assert!(!h.app.shelves()[0].pinned);
+        h.app.undo().unwrap();
+        assert_eq!(h.app.shelves()[0].name, "Later");
+
+        h.app.redo().unwrap();
+        assert_eq!(h.app.shelves()[0].name, "Someday");
+    }
+
+    /// K5 — undoing a delete brings the fic back out of the trash.
+    #[test]
+    fn undoing_a_delete_restores_from_trash() {
+        let mut h = given_harness_with_fics();
+        h.app.select_fics(&[1001, 1002]);
+        h.app.delete_selected();
+        h.step();
+        assert!(h.app.fics().is_empty());
+
+        h.app.undo().unwrap();
+        h.step();
+
+        assert_eq!(h.app.fics().len(), 2);
+        assert!(h.app.trashed_fics().is_empty());
+    }
+
+    /// K5b — an entry that can't be undone in full changes nothing and
+    /// stays on the stack, so it can be retried once the blocker is gone.
+    #[test]
+    fn failed_undo_is_rolled_back_and_can_be_retried() {
+        let mut h = given_harness_with_fics();
+        h.app.select_fics(&[1001, 1002]);
+        h.app.delete_selected();
+        h.step();
+        // Restored behind the history's back, so putting it back fails.
+        let repo = SqliteRepository::new(&h.conn);
+        repo.restore_fic(1001).unwrap();
+
+        assert!(h.app.undo().is_err());
+        h.step();
+        let repo = SqliteRepository::new(&h.conn);
+        assert!(
+            repo.get_fanfiction_by_id(1002).is_err(),
+            "1002 stays trashed"
+        );
+        assert!(h.app.can_undo());
+
+        repo.delete_fanfiction(1001).unwrap();
+        h.app.undo().unwrap();
+        h.step();
+        assert_eq!(h.app.fics().len(), 2);
+        assert!(h.app.can_redo());
+    }
+
+    /// K6 — a new edit after an undo discards the redo branch, and the
+    /// undo stack never grows past its bound.
+    #[test]
+    fn history_drops_redo_on_new_edit_and_stays_bounded() {
+        let mut h = given_harness_with_fics();
+        h.app.set_read_count(1001, 1).unwrap();
+        h.app.undo().unwrap();
+        assert!(h.app.can_redo());
+        h.app.set_read_count(1001, 2).unwrap();
+        assert!(!h.app.can_redo());
+
+        for n in 3..250 {
+            h.app.set_read_count(1001, n).unwrap();
+        }
+        assert_eq!(h.app.undo_depth(), 100);
+    }
+
+    /// K7 — a no-op edit isn't recorded.
+    #[test]
+    fn unchanged_values_are_not_recorded() {
+        let mut h = given_harness_with_fics();
+        let status = status_of(&h, 1001);
+        h.app.set_status(1001, status).unwrap();
+        assert!(!h.app.can_undo());
+        assert!(h.app.undo_prompt().is_none());
+    }
+}
@@ -57,10 +57,10 @@ mod tests {
         assert_eq!(h.app.visible_ids(), vec![3, 1, 2], "alphabetical desc");
     }
 
-    /// F29 — the details panel is mounted only when EXACTLY one fic
-    /// is selected AND the active view is a library view (not Tasks /
-    /// Settings). Empty selection, multi-selection, and non-library
-    /// views all hide it.
+    /// F29 — the details panel is mounted whenever something is
+    /// selected AND the active view is a library view (not Tasks /
+    /// Settings). A multi-selection mounts the bulk editor in its place;
+    /// an empty selection and non-library views hide it.
     #[test]
     fn details_panel_visibility_rule() {
         let mut h = given_three_fics_named("A", "B", "C");
@@ -74,10 +74,9 @@ mod tests {
         h.app.select_fic(1);
         assert!(h.app.details_panel_visible());
 
-        // Multi → hidden (the user can't be looking at a single fic's
-        // details when several are selected).
+        // Multi → visible, as the bulk editor.
         h.app.select_fics(&[1, 2]);
-        assert!(!h.app.details_panel_visible());
+        assert!(h.app.details_panel_visible());
 
         // Single but Tasks view → hidden.
         h.app.select_fic(1);
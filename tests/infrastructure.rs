@@ -3,13 +3,37 @@ mod common;
 
 #[path = "infrastructure/ao3.rs"]
 mod ao3;
+#[path = "infrastructure/ao3_listing.rs"]
+mod ao3_listing;
 #[path = "infrastructure/ao3_real.rs"]
 mod ao3_real;
+#[path = "infrastructure/backup.rs"]
+mod backup;
+#[path = "infrastructure/browser.rs"]
+mod browser;
+#[path = "infrastructure/calibre.rs"]
+mod calibre;
 #[path = "infrastructure/db.rs"]
 mod db;
+#[path = "infrastructure/fic_query.rs"]
+mod fic_query;
+#[path = "infrastructure/health.rs"]
+mod health;
 #[path = "infrastructure/library_location.rs"]
 mod library_location;
+#[path = "infrastructure/merge.rs"]
+mod merge;
+#[path = "infrastructure/saved_search.rs"]
+mod saved_search;
+#[path = "infrastructure/search.rs"]
+mod search;
 #[path = "infrastructure/shelf.rs"]
 mod shelf;
+#[path = "infrastructure/tag.rs"]
+mod tag;
+#[path = "infrastructure/transfer.rs"]
+mod transfer;
+#[path = "infrastructure/trash.rs"]
+mod trash;
 #[path = "infrastructure/url_parser.rs"]
 mod url_parser;
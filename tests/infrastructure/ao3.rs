@@ -70,6 +70,7 @@ mod tests {
             read_count: 0,
             user_rating: None,
             personal_note: None,
+            personal_tags: Vec::new(),
             last_checked_date: Utc::now(),
         };
 
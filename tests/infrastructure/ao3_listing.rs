@@ -0,0 +1,190 @@
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ficflow::{
+        application::search_ao3::search_ao3,
+        domain::discover::{Ao3Search, WorkBlurb, WorkSearcher},
+        infrastructure::external::ao3::{Ao3Fetcher, parser::Ao3Parser},
+        infrastructure::persistence::repository::SqliteRepository,
+    };
+    use httpmock::{Method::GET, MockServer};
+    use scraper::Html;
+    use std::fs;
+    use std::time::Duration;
+
+    fn test_fetcher(base_url: String) -> Ao3Fetcher {
+        Ao3Fetcher::with_min_gap(vec![base_url], 1, Duration::ZERO, Duration::from_millis(1))
+            .unwrap()
+    }
+
+    fn tag_listing() -> Html {
+        Html::parse_document(&fs::read_to_string("tests/fixtures/ao3_tag_works.html").unwrap())
+    }
+
+    #[test]
+    fn parses_the_blurbs_on_a_tag_listing() {
+        let blurbs = Ao3Parser.extract_blurbs(&tag_listing()).unwrap();
+
+        // The mystery work in between has no id and is skipped.
+        assert_eq!(blurbs.len(), 3);
+        assert_eq!(
+            blurbs[0],
+            WorkBlurb {
+                id: 53960491,
+                title: "Featherlight".to_string(),
+                authors: vec!["Gummy_bean".to_string()],
+                fandoms: vec!["Hazbin Hotel (Cartoon)".to_string()],
+                words: 1021,
+                chapters_published: 1,
+                chapters_total: Some(1),
+                kudos: 159,
+                tracked: false,
+            }
+        );
+        assert_eq!(
+            blurbs[1].authors,
+            vec!["quillfeather".to_string(), "Skyward_Ink".to_string()]
+        );
+        assert_eq!(
+            blurbs[1].fandoms,
+            vec![
+                "Good Omens (TV)".to_string(),
+                "Good Omens - Neil Gaiman & Terry Pratchett".to_string(),
+            ]
+        );
+        assert_eq!(
+            (
+                blurbs[1].words,
+                blurbs[1].chapters_published,
+                blurbs[1].chapters_total,
+                blurbs[1].kudos
+            ),
+            (48310, 7, None, 1877)
+        );
+        assert_eq!(blurbs[2].title, "Flightless");
+        assert_eq!(blurbs[2].authors, vec!["Anonymous".to_string()]);
+        assert_eq!(blurbs[2].kudos, 0);
+    }
+
+    #[test]
+    fn reads_the_page_count_off_the_pagination() {
+        assert_eq!(Ao3Parser.extract_page_count(&tag_listing()), 61);
+        let short = Html::parse_document("<h2 class=\"heading\">3 Works in Fluff</h2>");
+        assert_eq!(Ao3Parser.extract_page_count(&short), 1);
+        assert!(Ao3Parser.extract_blurbs(&short).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_a_page_that_is_not_a_listing() {
+        let page = Html::parse_document("<p>The archive is down for maintenance.</p>");
+        assert!(Ao3Parser.extract_blurbs(&page).is_err());
+    }
+
+    #[test]
+    fn searches_a_tag_page_by_page() {
+        let server = MockServer::start();
+        let listing = server.mock(|when, then| {
+            when.method(GET)
+                .path("/tags/Wingfic/works")
+                .query_param("page", "2");
+            then.status(200)
+                .body(fs::read_to_string("tests/fixtures/ao3_tag_works.html").unwrap());
+        });
+
+        let found = test_fetcher(server.base_url())
+            .search_works(&Ao3Search::Tag("Wingfic".to_string()), 2)
+            .unwrap();
+
+        listing.assert();
+        assert_eq!((found.page, found.page_count), (2, 61));
+        assert!(found.has_next());
+        assert_eq!(found.blurbs.len(), 3);
+    }
+
+    #[test]
+    fn escapes_tag_names_the_way_ao3_does() {
+        let server = MockServer::start();
+        let listing = server.mock(|when, then| {
+            when.method(GET)
+                .path("/tags/Good%20Omens%20-%20Neil%20Gaiman%20*a*%20Terry%20Pratchett/works");
+            then.status(200)
+                .body("<h2 class=\"heading\">0 Works in Good Omens</h2>");
+        });
+
+        let found = test_fetcher(server.base_url())
+            .search_works(
+                &Ao3Search::Tag("Good Omens - Neil Gaiman & Terry Pratchett".to_string()),
+                1,
+            )
+            .unwrap();
+
+        listing.assert();
+        assert!(found.blurbs.is_empty());
+        assert_eq!(found.page_count, 1);
+    }
+
+    #[test]
+    fn an_unknown_tag_is_reported_by_name() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET);
+            then.status(404);
+        });
+
+        let err = test_fetcher(server.base_url())
+            .search_works(&Ao3Search::Tag("Wingfc".to_string()), 1)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid input: AO3 has no tag named 'Wingfc'"
+        );
+    }
+
+    #[test]
+    fn free_text_goes_through_the_works_search() {
+        let server = MockServer::start();
+        let search = server.mock(|when, then| {
+            when.method(GET)
+                .path("/works/search")
+                .query_param("work_search[query]", "angel wings")
+                .query_param("page", "1");
+            then.status(200)
+                .body(fs::read_to_string("tests/fixtures/ao3_tag_works.html").unwrap());
+        });
+
+        test_fetcher(server.base_url())
+            .search_works(&Ao3Search::Text("angel wings".to_string()), 1)
+            .unwrap();
+
+        search.assert();
+    }
+
+    #[test]
+    fn flags_or_hides_works_already_in_the_library() {
+        let (conn, _path, _temp_dir) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let tracked = fixtures::given_sample_fanfiction(58812004, "Flightless");
+        fixtures::when_fanfiction_added_to_db(&conn, &tracked).unwrap();
+        let server = fixtures::given_mock_ao3_tag_listing();
+        let fetcher = test_fetcher(server.base_url());
+        let wingfic = Ao3Search::Tag("Wingfic".to_string());
+
+        let flagged = search_ao3(&fetcher, &repo, &wingfic, 1, false).unwrap();
+        let tracked_ids: Vec<u64> = flagged
+            .blurbs
+            .iter()
+            .filter(|b| b.tracked)
+            .map(|b| b.id)
+            .collect();
+        assert_eq!(tracked_ids, vec![58812004]);
+        assert_eq!(flagged.hidden, 0);
+
+        let hidden = search_ao3(&fetcher, &repo, &wingfic, 1, true).unwrap();
+        let ids: Vec<u64> = hidden.blurbs.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![53960491, 61230077]);
+        assert_eq!(hidden.hidden, 1);
+    }
+}
@@ -0,0 +1,153 @@
+use rusqlite::Connection;
+use std::error::Error;
+use std::path::Path;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::application::backup_if_due::backup_if_due;
+    use ficflow::application::create_backup::create_backup;
+    use ficflow::application::prune_backups::prune_backups;
+    use ficflow::domain::backup::{BackupOps, BackupReason};
+    use ficflow::domain::fanfiction::FanfictionOps;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::infrastructure::{SqliteBackups, backups_dir, open_configured_db};
+
+    use super::*;
+
+    /// Database files only: opening a backup leaves SQLite sidecars.
+    fn backup_names(db_path: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(backups_dir(db_path))
+            .map(|entries| {
+                entries
+                    .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+                    .filter(|name| name.ends_with(".db"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_backup_is_a_readable_copy_listed_with_its_reason() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Saved"),
+        )?;
+        let backups = SqliteBackups::new(&conn, &db_path);
+
+        let taken = backups.create_backup(BackupReason::Manual)?;
+
+        assert_eq!(taken.path.parent(), Some(backups_dir(&db_path).as_path()));
+        assert!(taken.size_bytes > 0);
+        assert_eq!(backups.list_backups()?, vec![taken.clone()]);
+        let copy = Connection::open(&taken.path)?;
+        assert_eq!(
+            SqliteRepository::new(&copy).get_fanfiction_by_id(1)?.title,
+            "Saved"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_skips_files_that_are_not_backups() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let backups = SqliteBackups::new(&conn, &db_path);
+        backups.create_backup(BackupReason::Daily)?;
+        std::fs::write(backups_dir(&db_path).join("notes.txt"), "mine")?;
+        std::fs::write(
+            backups_dir(&db_path).join("ficflow-20260101-000000-000-sideways.db"),
+            "",
+        )?;
+
+        let listed = backups.list_backups()?;
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].reason, BackupReason::Daily);
+        assert!(
+            backups
+                .delete_backup(&backups_dir(&db_path).join("notes.txt"))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_prunes_oldest_of_each_reason_beyond_keep() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let backups = SqliteBackups::new(&conn, &db_path);
+        let migration = backups.create_backup(BackupReason::Migration)?;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let first = backups.create_backup(BackupReason::Exit)?;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        backups.create_backup(BackupReason::Exit)?;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let newest = create_backup(&backups, BackupReason::Exit, 2)?;
+
+        let listed = backups.list_backups()?;
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[0], newest);
+        assert!(!first.path.exists());
+        assert!(
+            migration.path.exists(),
+            "exit backups pushed out another kind"
+        );
+
+        let removed = prune_backups(&backups, 0)?;
+        assert_eq!(removed.len(), 1, "at least one of each kind is always kept");
+        assert_eq!(backups.list_backups()?, vec![newest, migration]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_daily_backup_waits_a_day_after_any_backup() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let backups = SqliteBackups::new(&conn, &db_path);
+
+        let taken = backup_if_due(&backups, 10)?;
+        assert_eq!(taken.map(|b| b.reason), Some(BackupReason::Daily));
+        assert!(backup_if_due(&backups, 10)?.is_none());
+
+        // A day-old backup doesn't count.
+        let stale = backups_dir(&db_path).join("ficflow-20200101-000000-000-daily.db");
+        std::fs::remove_file(&backups.list_backups()?[0].path)?;
+        std::fs::write(&stale, "")?;
+        assert!(backup_if_due(&backups, 10)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_opening_an_older_library_backs_it_up_first() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(&conn, &fixtures::given_sample_fanfiction(1, "Old"))?;
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        // The latest migration can run again, so stepping the version
+        // back is enough for reopening to have one to run.
+        conn.execute_batch(&format!("PRAGMA user_version = {};", version - 1))?;
+        drop(conn);
+        assert!(backup_names(&db_path).is_empty());
+
+        let conn = open_configured_db(&db_path)?;
+
+        let names = backup_names(&db_path);
+        assert_eq!(names.len(), 1);
+        assert!(names[0].ends_with("-migration.db"), "{:?}", names);
+        let listed = SqliteBackups::new(&conn, &db_path).list_backups()?;
+        let old = Connection::open(&listed[0].path)?;
+        let old_version: i32 = old.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        assert_eq!(old_version, version - 1);
+
+        drop(conn);
+        open_configured_db(&db_path)?;
+        assert_eq!(
+            backup_names(&db_path).len(),
+            1,
+            "an up-to-date library was backed up"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use std::error::Error;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use ficflow::application::apply_visit_hint::apply_visit_hint;
+    use ficflow::application::plan_link_import::plan_link_import;
+    use ficflow::domain::fanfiction::ReadingStatus;
+    use ficflow::domain::transfer::{BrowserLink, LinkSource};
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::infrastructure::{BookmarksFile, FirefoxPlaces};
+    use ficflow::interfaces::utils::url_parser::extract_ao3_id;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn visited(secs: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(secs, 0)
+    }
+
+    fn work_links(links: Vec<BrowserLink>) -> Vec<(u64, BrowserLink)> {
+        links
+            .into_iter()
+            .filter_map(|link| extract_ao3_id(&link.url).ok().map(|id| (id, link)))
+            .collect()
+    }
+
+    #[test]
+    fn test_bookmarks_keep_innermost_folder_and_last_visit() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let path = fixtures::given_bookmarks_file(dir.path());
+
+        let links = BookmarksFile::open(&path)?.read_links()?;
+
+        let summary: Vec<(&str, Option<&str>)> = links
+            .iter()
+            .map(|l| (l.url.as_str(), l.folder.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("https://archiveofourown.org/works/8001", None),
+                (
+                    "https://archiveofourown.org/works/8002?view_adult=true",
+                    None
+                ),
+                (
+                    "https://archiveofourown.org/works/8003/chapters/12",
+                    Some("Hurt/Comfort")
+                ),
+                ("https://example.com/recipes", Some("Hurt/Comfort")),
+                ("https://archiveofourown.org/works/8001", Some("Fic recs")),
+            ]
+        );
+        assert_eq!(
+            links[0].title.as_deref(),
+            Some("Starlight - Chapter 1 - Ann - Fandom")
+        );
+        assert_eq!(links[0].last_visited, visited(1_700_000_000));
+        assert_eq!(links[1].last_visited, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_firefox_reads_bookmarks_then_history() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let path = fixtures::given_firefox_places(dir.path());
+
+        let links = FirefoxPlaces::open(&path)?.read_links()?;
+
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = links
+            .iter()
+            .map(|l| (l.url.as_str(), l.title.as_deref(), l.folder.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "https://archiveofourown.org/works/8103",
+                    Some("Quiet Hours"),
+                    Some("Slow Burn")
+                ),
+                (
+                    "https://archiveofourown.org/works/8101",
+                    Some("Ember"),
+                    None
+                ),
+                (
+                    "https://archiveofourown.org/works/8101",
+                    Some("Ember - Chapter 3 - Bo - Fandom"),
+                    None
+                ),
+                (
+                    "https://archiveofourown.org/works/8102/chapters/77",
+                    Some("Driftwood - Chapter 2 - Ann - Fandom"),
+                    None
+                ),
+            ],
+            "the tag copy of 8103 and the non-work page are left out"
+        );
+        assert_eq!(links[1].last_visited, visited(1_700_000_000));
+        assert_eq!(links[3].last_visited, visited(1_650_000_000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_rejects_other_files() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "just some notes")?;
+        let calibre = fixtures::given_calibre_library(dir.path());
+
+        assert!(matches!(
+            BookmarksFile::open(&text),
+            Err(FicflowError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            FirefoxPlaces::open(&calibre),
+            Err(FicflowError::InvalidInput(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_merges_links_and_skips_tracked_works() -> Result<(), Box<dyn Error>> {
+        let (conn, _db_path, _td) = fixtures::given_test_database();
+        let tracked = fixtures::given_sample_fanfiction(8002, "Tidewater");
+        fixtures::when_fanfiction_added_to_db(&conn, &tracked)?;
+        let repo = SqliteRepository::new(&conn);
+        let dir = TempDir::new()?;
+        let path = fixtures::given_bookmarks_file(dir.path());
+        let links = BookmarksFile::open(&path)?.read_links()?;
+
+        let plan = plan_link_import(&repo, work_links(links))?;
+
+        assert_eq!(plan.already_tracked, 1);
+        let ids: Vec<u64> = plan.works.iter().map(|w| w.fic_id).collect();
+        assert_eq!(ids, vec![8001, 8003]);
+        let starlight = &plan.works[0];
+        assert_eq!(
+            starlight.title.as_deref(),
+            Some("Starlight - Chapter 1 - Ann - Fandom")
+        );
+        assert_eq!(starlight.folder.as_deref(), Some("Fic recs"));
+        assert_eq!(starlight.last_visited, visited(1_700_000_000));
+        assert_eq!(plan.shelf_names(), vec!["Fic recs", "Hurt/Comfort"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_visit_hint_follows_the_last_update() -> Result<(), Box<dyn Error>> {
+        let (conn, _db_path, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let mut caught_up = fixtures::given_sample_fanfiction(8201, "Caught Up");
+        caught_up.chapters_published = 7;
+        caught_up.complete = false;
+        let mut behind = fixtures::given_sample_fanfiction(8202, "Fell Behind");
+        behind.chapters_published = 7;
+        let mut started = fixtures::given_sample_fanfiction(8203, "Already Started");
+        started.reading_status = ReadingStatus::Paused;
+        let mut finished = fixtures::given_sample_fanfiction(8204, "Finished Work");
+        finished.chapters_published = 2;
+        finished.complete = true;
+        for fic in [&caught_up, &behind, &started, &finished] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+        let after = caught_up.date_updated + chrono::Duration::days(1);
+        let before = behind.date_updated - chrono::Duration::days(1);
+
+        let caught_up = apply_visit_hint(&repo, 8201, after)?;
+        let behind = apply_visit_hint(&repo, 8202, before)?;
+        let started = apply_visit_hint(&repo, 8203, after)?;
+        let finished = apply_visit_hint(&repo, 8204, after)?;
+
+        assert_eq!(caught_up.reading_status, ReadingStatus::InProgress);
+        assert_eq!(caught_up.last_chapter_read, Some(7));
+        assert_eq!(behind.reading_status, ReadingStatus::InProgress);
+        assert_eq!(behind.last_chapter_read, None);
+        assert_eq!(started.reading_status, ReadingStatus::Paused);
+        // A visit doesn't say a finished work was read to the end.
+        assert_eq!(finished.reading_status, ReadingStatus::InProgress);
+        assert_eq!(finished.last_chapter_read, None);
+        assert_eq!(finished.read_count, 0);
+        Ok(())
+    }
+}
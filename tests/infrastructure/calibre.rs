@@ -0,0 +1,163 @@
+use std::error::Error;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::application::apply_fic_import::apply_fic_import;
+    use ficflow::application::plan_fic_import::plan_fic_import;
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus, UserRating};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::domain::transfer::{ConflictChoice, ExternalLibrary, FicImportSummary};
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::infrastructure::{CalibreColumns, CalibreLibrary};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_reads_ao3_works_with_user_fields() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        fixtures::given_calibre_library(dir.path());
+
+        // The library folder works as well as metadata.db itself.
+        let library = CalibreLibrary::open(dir.path(), CalibreColumns::default())?;
+        let fics = library.read_fics()?;
+
+        let ids: Vec<u64> = fics.iter().map(|f| f.fic.id).collect();
+        assert_eq!(ids, vec![7001, 7002, 7003]);
+        let comet = &fics[0];
+        assert_eq!(comet.fic.title, "Comet Tail");
+        assert_eq!(comet.fic.authors, vec!["Ann".to_string(), "Bo".to_string()]);
+        assert_eq!(comet.fic.tags, Some(vec!["Fluff".to_string()]));
+        assert_eq!(comet.fic.summary, "First line.\nSecond.");
+        assert_eq!(
+            comet.fic.date_published.to_rfc3339(),
+            "2020-05-06T07:08:09.123456+00:00"
+        );
+        assert_eq!(comet.reading_status, Some(ReadingStatus::Read));
+        assert_eq!(comet.user_rating, Some(UserRating::Four));
+
+        let harbour = &fics[1];
+        assert_eq!(harbour.reading_status, None);
+        assert_eq!(harbour.user_rating, None);
+        // Calibre's "unknown" date is left as the placeholder's.
+        assert_eq!(harbour.fic.date_published.timestamp(), 0);
+        assert_eq!(fics[2].user_rating, Some(UserRating::Five));
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_named_column_is_rejected() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let path = fixtures::given_calibre_library(dir.path());
+        let columns = CalibreColumns {
+            rating: Some("#stars".into()),
+            ..CalibreColumns::default()
+        };
+
+        let result = CalibreLibrary::open(&path, columns)?.read_fics();
+
+        assert!(matches!(
+            result,
+            Err(FicflowError::InvalidInput(msg)) if msg.contains("#stars")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_then_apply_adds_new_fics_and_resolves_conflicts() -> Result<(), Box<dyn Error>> {
+        let (conn, _db_path, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let dir = TempDir::new()?;
+        let path = fixtures::given_calibre_library(dir.path());
+        // 7001 disagrees with Calibre; 7002 has nothing Calibre would
+        // change.
+        let mut comet = fixtures::given_sample_fanfiction(7001, "Comet Tail");
+        comet.reading_status = ReadingStatus::InProgress;
+        comet.personal_note = Some("halfway".into());
+        fixtures::when_fanfiction_added_to_db(&conn, &comet)?;
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(7002, "Harbour"),
+        )?;
+        let library = CalibreLibrary::open(&path, CalibreColumns::default())?;
+
+        let mut plan = plan_fic_import(&repo, &repo, &library)?;
+
+        assert_eq!(plan.new.len(), 1);
+        assert_eq!(plan.new[0].fic.id, 7003);
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].choice, ConflictChoice::KeepLocal);
+
+        plan.conflicts[0].choice = ConflictChoice::UseIncoming;
+        let summary = apply_fic_import(&repo, &repo, &plan)?;
+
+        assert_eq!(
+            summary,
+            FicImportSummary {
+                added: 1,
+                updated: 1,
+                unchanged: 1,
+                ..FicImportSummary::default()
+            }
+        );
+        let comet = repo.get_fanfiction_by_id(7001)?;
+        assert_eq!(comet.reading_status, ReadingStatus::Read);
+        assert_eq!(comet.user_rating, Some(UserRating::Four));
+        assert_eq!(comet.personal_note.as_deref(), Some("halfway"));
+        let moons = repo.get_fanfiction_by_id(7003)?;
+        assert_eq!(moons.title, "Paper Moons");
+        assert_eq!(moons.reading_status, ReadingStatus::Read);
+        assert_eq!(moons.user_rating, Some(UserRating::Five));
+
+        // Everything now matches, so a second pass has nothing to do.
+        let again = plan_fic_import(&repo, &repo, &library)?;
+        assert!(again.new.is_empty() && again.conflicts.is_empty());
+        assert_eq!(again.unchanged, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trashed_fics_are_conflicts_and_come_back_with_their_shelves()
+    -> Result<(), Box<dyn Error>> {
+        let (conn, _db_path, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let dir = TempDir::new()?;
+        let path = fixtures::given_calibre_library(dir.path());
+        let mut moons = fixtures::given_sample_fanfiction(7003, "Paper Moons");
+        moons.personal_note = Some("reread".into());
+        fixtures::when_fanfiction_added_to_db(&conn, &moons)?;
+        let shelf = repo.create_shelf("Favourites", None)?;
+        repo.add_fic_to_shelf(7003, shelf.id)?;
+        repo.delete_fanfiction(7003)?;
+        let library = CalibreLibrary::open(&path, CalibreColumns::default())?;
+
+        let mut plan = plan_fic_import(&repo, &repo, &library)?;
+
+        assert!(plan.new.iter().all(|entry| entry.fic.id != 7003));
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].local.id, 7003);
+        assert!(plan.conflicts[0].trashed);
+
+        // Keeping it leaves it in the trash.
+        apply_fic_import(&repo, &repo, &plan)?;
+        assert!(matches!(
+            repo.get_fanfiction_by_id(7003),
+            Err(FicflowError::NotFound { .. })
+        ));
+
+        plan.conflicts[0].choice = ConflictChoice::UseIncoming;
+        apply_fic_import(&repo, &repo, &plan)?;
+
+        let moons = repo.get_fanfiction_by_id(7003)?;
+        assert_eq!(moons.reading_status, ReadingStatus::Read);
+        assert_eq!(moons.personal_note.as_deref(), Some("reread"));
+        let shelves = repo.list_shelves_for_fic(7003)?;
+        assert_eq!(shelves.len(), 1);
+        assert_eq!(shelves[0].id, shelf.id);
+        Ok(())
+    }
+}
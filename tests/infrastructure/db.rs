@@ -129,4 +129,29 @@ mod tests {
         assertions::then_fanfiction_was_fetched(&test_fic, &result, None);
         Ok(())
     }
+
+    #[test]
+    fn test_save_fanfictions_round_trips_personal_tags() -> Result<(), Box<dyn Error>> {
+        // Given
+        let (conn, _temp_dir) = setup_test_db();
+        let mut first = fixtures::given_sample_fanfiction(401, "First");
+        let mut second = fixtures::given_sample_fanfiction(402, "Second");
+        first.personal_tags = vec!["comfort".into(), "reread".into()];
+        second.personal_tags = vec!["comfort".into()];
+
+        // When
+        let repo = SqliteRepository::new(&conn);
+        repo.save_fanfictions(&[first, second])?;
+
+        // Then
+        assert_eq!(
+            repo.get_fanfiction_by_id(401)?.personal_tags,
+            vec!["comfort".to_string(), "reread".to_string()]
+        );
+        assert_eq!(
+            repo.get_fanfiction_by_id(402)?.personal_tags,
+            vec!["comfort".to_string()]
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,357 @@
+use rusqlite::Connection;
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use ficflow::domain::fanfiction::{
+        ColumnKey, FanfictionOps, FicFilter, FicQuery, ReadingStatus, SortDirection, SortPref,
+        TagKind,
+    };
+    use ficflow::domain::shelf::{AutoShelfCriteria, ClauseLogic, ShelfOps};
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    fn sort(column: ColumnKey, direction: SortDirection) -> SortPref {
+        SortPref { column, direction }
+    }
+
+    /// Four fics: 1 "Delta" (Read, 300 words), 2 "alpha" (PlanToRead,
+    /// 100), 3 "Charlie" (Read, 300, complete), 4 "Bravo" (PlanToRead, 200).
+    fn given_four_fics(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        let specs = [
+            (1, "Delta", ReadingStatus::Read, 300, false),
+            (2, "alpha", ReadingStatus::PlanToRead, 100, false),
+            (3, "Charlie", ReadingStatus::Read, 300, true),
+            (4, "Bravo", ReadingStatus::PlanToRead, 200, false),
+        ];
+        for (id, title, status, words, complete) in specs {
+            let mut fic = fixtures::given_sample_fanfiction(id, title);
+            fic.reading_status = status;
+            fic.words = words;
+            fic.complete = complete;
+            fixtures::when_fanfiction_added_to_db(conn, &fic)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_sorts_by_keys_in_order_with_title_tiebreak() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        given_four_fics(&conn)?;
+
+        let by_title = FicQuery {
+            sort: vec![sort(ColumnKey::Title, SortDirection::Ascending)],
+            ..FicQuery::default()
+        };
+        assert_eq!(repo.query_fanfiction_ids(&by_title)?, vec![2, 4, 3, 1]);
+
+        // Words descending; the two 300-word fics fall back to title.
+        let by_words = FicQuery {
+            sort: vec![sort(ColumnKey::Words, SortDirection::Descending)],
+            ..FicQuery::default()
+        };
+        assert_eq!(repo.query_fanfiction_ids(&by_words)?, vec![3, 1, 4, 2]);
+
+        let by_status_then_words = FicQuery {
+            sort: vec![
+                sort(ColumnKey::Status, SortDirection::Ascending),
+                sort(ColumnKey::Words, SortDirection::Ascending),
+            ],
+            ..FicQuery::default()
+        };
+        assert_eq!(
+            repo.query_fanfiction_ids(&by_status_then_words)?,
+            vec![3, 1, 2, 4]
+        );
+        Ok(())
+    }
+
+    /// The SQL order and `SortPref::compare_total` agree, accents and
+    /// all, so views sorted in memory match the ones sorted by SQLite.
+    #[test]
+    fn test_query_sort_matches_in_memory_sort_for_non_ascii_text() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let titles = [
+            "zebra",
+            "Émile",
+            "apple",
+            "éclair",
+            "Ångström",
+            "Apple",
+            "Zoë",
+        ];
+        for (id, title) in (1..).zip(titles) {
+            let mut fic = fixtures::given_sample_fanfiction(id, title);
+            fic.authors = vec![title.to_uppercase()];
+            fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        }
+
+        for column in [ColumnKey::Title, ColumnKey::Author] {
+            for direction in [SortDirection::Ascending, SortDirection::Descending] {
+                let pref = sort(column, direction);
+                let query = FicQuery {
+                    sort: vec![pref],
+                    ..FicQuery::default()
+                };
+                let mut fics = repo.list_fanfictions()?;
+                fics.sort_by(|a, b| pref.compare_total(a, b));
+                let in_memory: Vec<u64> = fics.iter().map(|f| f.id).collect();
+                assert_eq!(repo.query_fanfiction_ids(&query)?, in_memory, "{:?}", pref);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_filters_combine_and_skip_deleted() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        given_four_fics(&conn)?;
+        fixtures::when_fanfiction_deleted_from_db(&conn, 1)?;
+
+        let read = FicQuery {
+            filter: FicFilter {
+                status: Some(ReadingStatus::Read),
+                ..FicFilter::default()
+            },
+            ..FicQuery::default()
+        };
+        assert_eq!(repo.query_fanfiction_ids(&read)?, vec![3]);
+
+        let incomplete_with_text = FicQuery {
+            filter: FicFilter {
+                complete: Some(false),
+                text: Some("brav".into()),
+                ..FicFilter::default()
+            },
+            ..FicQuery::default()
+        };
+        assert_eq!(repo.query_fanfiction_ids(&incomplete_with_text)?, vec![4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_filters_by_tags_and_min_words() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        given_four_fics(&conn)?;
+        for (id, fandom, tags) in [
+            (1, "Good Omens", vec!["Fluff"]),
+            (3, "Good Omens", vec!["Angst", "Hurt/Comfort"]),
+            (4, "Discworld", vec!["Angst"]),
+        ] {
+            let mut fic = repo.get_fanfiction_by_id(id)?;
+            fic.fandoms = vec![fandom.to_string()];
+            fic.tags = Some(tags.into_iter().map(String::from).collect());
+            repo.save_fanfiction(&fic)?;
+        }
+        let query = |tags: Vec<(TagKind, &str)>, min_words| FicQuery {
+            filter: FicFilter {
+                tags: tags
+                    .into_iter()
+                    .map(|(kind, name)| (kind, name.to_string()))
+                    .collect(),
+                min_words,
+                ..FicFilter::default()
+            },
+            sort: vec![sort(ColumnKey::Title, SortDirection::Ascending)],
+            ..FicQuery::default()
+        };
+
+        assert_eq!(
+            repo.query_fanfiction_ids(&query(vec![(TagKind::Fandom, "good omens")], None))?,
+            vec![3, 1]
+        );
+        assert_eq!(
+            repo.query_fanfiction_ids(&query(
+                vec![(TagKind::Fandom, "Good Omens"), (TagKind::Tag, "angst")],
+                None
+            ))?,
+            vec![3]
+        );
+        // A fandom name isn't an additional tag.
+        assert!(
+            repo.query_fanfiction_ids(&query(vec![(TagKind::Tag, "Discworld")], None))?
+                .is_empty()
+        );
+        assert_eq!(
+            repo.query_fanfiction_ids(&query(vec![], Some(200)))?,
+            vec![4, 3, 1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_shelf_filter_includes_nested_shelves() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        given_four_fics(&conn)?;
+        let parent = repo.create_shelf("Parent", None)?;
+        let child = repo.create_shelf("Child", Some(parent.id))?;
+        repo.add_fic_to_shelf(1, parent.id)?;
+        repo.add_fic_to_shelf(4, child.id)?;
+
+        let query = FicQuery {
+            filter: FicFilter {
+                shelf_id: Some(parent.id),
+                ..FicFilter::default()
+            },
+            sort: vec![sort(ColumnKey::Title, SortDirection::Ascending)],
+            ..FicQuery::default()
+        };
+        assert_eq!(repo.query_fanfiction_ids(&query)?, vec![4, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_rejects_auto_shelf_filter() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let auto = repo.upsert_auto_shelf(
+            None,
+            "Everything",
+            None,
+            AutoShelfCriteria {
+                logic: ClauseLogic::And,
+                clauses: vec![],
+            },
+        )?;
+
+        let query = FicQuery {
+            filter: FicFilter {
+                shelf_id: Some(auto.id),
+                ..FicFilter::default()
+            },
+            ..FicQuery::default()
+        };
+        assert!(matches!(
+            repo.query_fanfictions(&query).unwrap_err(),
+            FicflowError::InvalidInput(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_pages_with_limit_offset_and_reports_total() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        given_four_fics(&conn)?;
+
+        let page = repo.query_fanfictions(&FicQuery {
+            sort: vec![sort(ColumnKey::Title, SortDirection::Ascending)],
+            limit: Some(2),
+            offset: 1,
+            ..FicQuery::default()
+        })?;
+
+        let ids: Vec<u64> = page.fics.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![4, 3]);
+        assert_eq!(page.total, 4);
+        Ok(())
+    }
+
+    const PERF_LIBRARY_SIZE: u64 = 50_000;
+
+    /// Generates a 50k-fic library and times the reads the GUI and CLI
+    /// issue against it. Ignored by default — building the library takes
+    /// a while in debug builds. Run with
+    /// `cargo test --release --test infrastructure -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "perf: generates a 50k-fic library"]
+    fn perf_query_50k_fic_library() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let statuses = [
+            ReadingStatus::InProgress,
+            ReadingStatus::Read,
+            ReadingStatus::PlanToRead,
+            ReadingStatus::Paused,
+            ReadingStatus::Abandoned,
+        ];
+        let started = Instant::now();
+        conn.execute_batch("BEGIN")?;
+        for id in 1..=PERF_LIBRARY_SIZE {
+            let mut fic = fixtures::given_sample_fanfiction(id, &format!("Fic {:05}", id));
+            fic.reading_status = statuses[(id % 5) as usize];
+            fic.words = ((id * 7919) % 200_000) as u32;
+            fic.summary = format!("Summary number {} about lighthouse {}", id, id % 97);
+            repo.save_fanfiction(&fic)?;
+        }
+        conn.execute_batch("COMMIT")?;
+        println!(
+            "generated {} fics in {:?}",
+            PERF_LIBRARY_SIZE,
+            started.elapsed()
+        );
+
+        let time = |label: &str, run: &dyn Fn() -> Result<usize, FicflowError>| {
+            let started = Instant::now();
+            let n = run().expect(label);
+            let elapsed = started.elapsed();
+            println!("{label}: {n} rows in {elapsed:?}");
+            elapsed
+        };
+
+        let first_page = time("status + title sort, first page", &|| {
+            repo.query_fanfictions(&FicQuery {
+                filter: FicFilter {
+                    status: Some(ReadingStatus::Read),
+                    ..FicFilter::default()
+                },
+                sort: vec![sort(ColumnKey::Title, SortDirection::Ascending)],
+                limit: Some(100),
+                offset: 0,
+            })
+            .map(|p| p.fics.len())
+        });
+        let deep_page = time("words sort, page 400", &|| {
+            repo.query_fanfictions(&FicQuery {
+                sort: vec![sort(ColumnKey::Words, SortDirection::Descending)],
+                limit: Some(100),
+                offset: 40_000,
+                ..FicQuery::default()
+            })
+            .map(|p| p.fics.len())
+        });
+        let all_ids = time("all ids, updated sort", &|| {
+            repo.query_fanfiction_ids(&FicQuery {
+                sort: vec![sort(ColumnKey::Updated, SortDirection::Descending)],
+                ..FicQuery::default()
+            })
+            .map(|ids| ids.len())
+        });
+        let text = time("full-text filter, first page", &|| {
+            repo.query_fanfictions(&FicQuery {
+                filter: FicFilter {
+                    text: Some("lighthouse 42".into()),
+                    ..FicFilter::default()
+                },
+                limit: Some(100),
+                ..FicQuery::default()
+            })
+            .map(|p| p.fics.len())
+        });
+
+        // Loose bounds: these catch a lost index or an accidental full
+        // load, not machine-to-machine noise.
+        assert!(first_page < Duration::from_millis(500));
+        assert!(deep_page < Duration::from_millis(1000));
+        assert!(all_ids < Duration::from_millis(2000));
+        assert!(text < Duration::from_millis(1000));
+        Ok(())
+    }
+}
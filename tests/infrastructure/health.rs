@@ -0,0 +1,259 @@
+use rusqlite::{Connection, params};
+use std::error::Error;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use ficflow::application::check_library::check_library;
+    use ficflow::application::repair_library::repair_library;
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus, SortPref};
+    use ficflow::domain::health::{HealthOps, LibraryIssue};
+    use ficflow::domain::saved_search::{SavedSearchOps, SearchScope};
+    use ficflow::domain::shelf::{AutoShelfCriteria, Clause, ClauseLogic, ShelfKind, ShelfOps};
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    /// Rows that point nowhere can only be written with enforcement off,
+    /// as an older build or another tool might have.
+    fn without_foreign_keys(conn: &Connection, sql: &str) -> Result<(), Box<dyn Error>> {
+        conn.execute_batch(&format!(
+            "PRAGMA foreign_keys = OFF; {} PRAGMA foreign_keys = ON;",
+            sql
+        ))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_healthy_library_has_no_issues() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Fine"),
+        )?;
+        let repo = SqliteRepository::new(&conn);
+        let parent = repo.create_shelf("Parent", None)?;
+        let child = repo.create_shelf("Child", Some(parent.id))?;
+        repo.add_fic_to_shelf(1, child.id)?;
+        repo.delete_fanfiction(1)?;
+
+        assert_eq!(check_library(&repo)?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_fic_list_is_found_and_cleared() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Torn"),
+        )?;
+        conn.execute(
+            "UPDATE fanfiction SET warnings = '[\"Dragons\"]' WHERE id = 1",
+            [],
+        )?;
+        let repo = SqliteRepository::new(&conn);
+        assert!(repo.get_fanfiction_by_id(1).is_err());
+
+        let issues = check_library(&repo)?;
+        assert_eq!(
+            issues,
+            vec![LibraryIssue::BadFicJson {
+                fic_id: 1,
+                column: "warnings"
+            }]
+        );
+
+        let report = repair_library(&repo)?;
+        assert_eq!(report.repaired, issues);
+        assert!(report.remaining.is_empty());
+        let fic = repo.get_fanfiction_by_id(1)?;
+        assert_eq!(fic.title, "Torn");
+        assert!(fic.warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_membership_of_a_missing_fic_is_removed() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let shelf = repo.create_shelf("Shelf", None)?;
+        without_foreign_keys(
+            &conn,
+            &format!(
+                "INSERT INTO fic_shelf (fic_id, shelf_id, added_at) VALUES (999, {}, '{}');",
+                shelf.id,
+                Utc::now().to_rfc3339()
+            ),
+        )?;
+
+        assert_eq!(
+            check_library(&repo)?,
+            vec![LibraryIssue::DanglingMembership {
+                fic_id: 999,
+                shelf_id: shelf.id
+            }]
+        );
+        repair_library(&repo)?;
+        let left: i64 = conn.query_row("SELECT COUNT(*) FROM fic_shelf", [], |r| r.get(0))?;
+        assert_eq!(left, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shelf_loop_is_reported_once_and_broken() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let outer = repo.create_shelf("Outer", None)?;
+        let inner = repo.create_shelf("Inner", Some(outer.id))?;
+        conn.execute(
+            "UPDATE shelf SET parent_shelf_id = ?2 WHERE id = ?1",
+            params![outer.id, inner.id],
+        )?;
+
+        assert_eq!(
+            check_library(&repo)?,
+            vec![LibraryIssue::ShelfCycle {
+                shelf_id: outer.id,
+                name: "Outer".into()
+            }]
+        );
+        let report = repair_library(&repo)?;
+        assert!(report.remaining.is_empty());
+        assert_eq!(repo.get_shelf_by_id(outer.id)?.parent_shelf_id, None);
+        assert_eq!(
+            repo.get_shelf_by_id(inner.id)?.parent_shelf_id,
+            Some(outer.id)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_too_deep_and_orphaned_shelves_move_to_the_top() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let one = repo.create_shelf("One", None)?;
+        let two = repo.create_shelf("Two", Some(one.id))?;
+        let three = repo.create_shelf("Three", Some(two.id))?;
+        let four = repo.create_shelf("Four", None)?;
+        let five = repo.create_shelf("Five", Some(four.id))?;
+        conn.execute(
+            "UPDATE shelf SET parent_shelf_id = ?2 WHERE id = ?1",
+            params![four.id, three.id],
+        )?;
+        let orphan = repo.create_shelf("Orphan", None)?;
+        without_foreign_keys(
+            &conn,
+            &format!(
+                "UPDATE shelf SET parent_shelf_id = 999 WHERE id = {};",
+                orphan.id
+            ),
+        )?;
+
+        assert_eq!(
+            check_library(&repo)?,
+            vec![
+                LibraryIssue::MissingParent {
+                    shelf_id: orphan.id,
+                    name: "Orphan".into()
+                },
+                LibraryIssue::ShelfTooDeep {
+                    shelf_id: four.id,
+                    name: "Four".into(),
+                    depth: 4
+                },
+            ]
+        );
+
+        assert!(repair_library(&repo)?.remaining.is_empty());
+        assert_eq!(repo.get_shelf_by_id(four.id)?.parent_shelf_id, None);
+        assert_eq!(
+            repo.get_shelf_by_id(five.id)?.parent_shelf_id,
+            Some(four.id)
+        );
+        assert_eq!(repo.get_shelf_by_id(orphan.id)?.parent_shelf_id, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_shelf_criteria_are_checked_and_cleaned() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let criteria = AutoShelfCriteria {
+            logic: ClauseLogic::Or,
+            clauses: vec![Clause::Tag("Fluff".into()), Clause::Fandom("  ".into())],
+        };
+        let blank = repo.upsert_auto_shelf(None, "Blank", None, criteria)?;
+        let broken = repo.upsert_auto_shelf(None, "Broken", None, AutoShelfCriteria::default())?;
+        conn.execute(
+            "UPDATE shelf SET auto_criteria = 'not json' WHERE id = ?1",
+            params![broken.id],
+        )?;
+
+        assert_eq!(
+            check_library(&repo)?,
+            vec![
+                LibraryIssue::BadAutoCriteria {
+                    shelf_id: blank.id,
+                    name: "Blank".into()
+                },
+                LibraryIssue::BadAutoCriteria {
+                    shelf_id: broken.id,
+                    name: "Broken".into()
+                },
+            ]
+        );
+        assert!(repair_library(&repo)?.remaining.is_empty());
+        let ShelfKind::Auto(cleaned) = repo.get_shelf_by_id(blank.id)?.kind else {
+            panic!("still an auto-shelf");
+        };
+        assert_eq!(cleaned.clauses, vec![Clause::Tag("Fluff".into())]);
+        assert_eq!(cleaned.logic, ClauseLogic::Or);
+        assert!(matches!(
+            repo.get_shelf_by_id(broken.id)?.kind,
+            ShelfKind::Auto(c) if c.clauses.is_empty()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_saved_search_is_reset() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let search = repo.create_saved_search(
+            "Angst",
+            "angst",
+            SearchScope::ByStatus(ReadingStatus::Read),
+            SortPref::default(),
+        )?;
+        conn.execute(
+            "UPDATE saved_search SET scope = '{\"Shelf\":' WHERE id = ?1",
+            params![search.id],
+        )?;
+
+        assert_eq!(
+            check_library(&repo)?,
+            vec![LibraryIssue::BadSavedSearchJson {
+                saved_search_id: search.id,
+                column: "scope"
+            }]
+        );
+        repair_library(&repo)?;
+        assert_eq!(
+            repo.get_saved_search_by_id(search.id)?.scope,
+            SearchScope::AllFics
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_corruption_has_no_repair() {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let issue = LibraryIssue::Corruption("Page 4 is never used".into());
+
+        assert_eq!(issue.repair(), None);
+        assert!(SqliteRepository::new(&conn).repair_issue(&issue).is_err());
+    }
+}
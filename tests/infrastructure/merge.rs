@@ -0,0 +1,244 @@
+use rusqlite::{Connection, params};
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use ficflow::application::merge_library::merge_library;
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::domain::transfer::{MergePolicy, MergeSide, TransferOps};
+    use ficflow::domain::trash::TrashOps;
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::infrastructure::read_library_file;
+
+    use super::*;
+
+    fn set_user_updated_at(
+        conn: &Connection,
+        fic_id: u64,
+        days_ago: i64,
+    ) -> Result<(), Box<dyn Error>> {
+        let at = Utc::now() - Duration::days(days_ago);
+        conn.execute(
+            "UPDATE fanfiction SET user_updated_at = ?2 WHERE id = ?1",
+            params![fic_id, at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Fic 1 in both: read on the other computer a day ago, still plan
+    /// to read here since a week ago. Fic 2 only there, on a nested
+    /// shelf this library already has the parent of.
+    fn given_two_libraries() -> Result<(Connection, TempDir, Connection, TempDir), Box<dyn Error>> {
+        let (mine, _, mine_td) = fixtures::given_test_database();
+        let (theirs, _, theirs_td) = fixtures::given_test_database();
+
+        let mut local = fixtures::given_sample_fanfiction(1, "Shared");
+        local.last_checked_date = Utc::now() - Duration::days(10);
+        fixtures::when_fanfiction_added_to_db(&mine, &local)?;
+        set_user_updated_at(&mine, 1, 7)?;
+        SqliteRepository::new(&mine).create_shelf("Favorites", None)?;
+
+        let mut remote = fixtures::given_sample_fanfiction(1, "Shared (retitled)");
+        remote.chapters_published = 2;
+        remote.reading_status = ReadingStatus::Read;
+        remote.last_chapter_read = Some(2);
+        fixtures::when_fanfiction_added_to_db(&theirs, &remote)?;
+        set_user_updated_at(&theirs, 1, 1)?;
+        fixtures::when_fanfiction_added_to_db(
+            &theirs,
+            &fixtures::given_sample_fanfiction(2, "Only there"),
+        )?;
+        let theirs_repo = SqliteRepository::new(&theirs);
+        let favorites = theirs_repo.create_shelf("favorites", None)?;
+        let nested = theirs_repo.create_shelf("Angst", Some(favorites.id))?;
+        theirs_repo.add_fic_to_shelf(2, nested.id)?;
+        theirs_repo.add_fic_to_shelf(1, favorites.id)?;
+
+        Ok((mine, mine_td, theirs, theirs_td))
+    }
+
+    #[test]
+    fn test_merge_newest_wins_takes_newer_fields_and_metadata() -> Result<(), Box<dyn Error>> {
+        let (mine, _td, theirs, _td2) = given_two_libraries()?;
+        let snapshot = SqliteRepository::new(&theirs).export_library()?;
+        let repo = SqliteRepository::new(&mine);
+
+        let report = merge_library(&repo, &snapshot, MergePolicy::NewestWins, false)?;
+
+        assert_eq!(report.fics_added, 1);
+        assert_eq!(report.fics_updated, 1);
+        assert_eq!(report.shelves_matched, 1);
+        assert_eq!(report.shelves_added, 1);
+        assert_eq!(report.memberships_added, 2);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kept, MergeSide::Theirs);
+        assert_eq!(
+            report.conflicts[0].mine.reading_status,
+            ReadingStatus::PlanToRead
+        );
+
+        let shared = repo.get_fanfiction_by_id(1)?;
+        assert_eq!(shared.title, "Shared (retitled)");
+        assert_eq!(shared.chapters_published, 2);
+        assert_eq!(shared.reading_status, ReadingStatus::Read);
+        assert_eq!(shared.last_chapter_read, Some(2));
+
+        let shelves = repo.list_shelves()?;
+        assert_eq!(shelves.len(), 2);
+        let angst = shelves.iter().find(|s| s.name == "Angst").unwrap();
+        let favorites = shelves.iter().find(|s| s.name == "Favorites").unwrap();
+        assert_eq!(angst.parent_shelf_id, Some(favorites.id));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_keep_mine_keeps_fields_but_still_refreshes_metadata() -> Result<(), Box<dyn Error>>
+    {
+        let (mine, _td, theirs, _td2) = given_two_libraries()?;
+        let snapshot = SqliteRepository::new(&theirs).export_library()?;
+        let repo = SqliteRepository::new(&mine);
+
+        let report = merge_library(&repo, &snapshot, MergePolicy::KeepMine, false)?;
+
+        assert_eq!(report.conflicts[0].kept, MergeSide::Mine);
+        let shared = repo.get_fanfiction_by_id(1)?;
+        assert_eq!(shared.title, "Shared (retitled)");
+        assert_eq!(shared.reading_status, ReadingStatus::PlanToRead);
+        assert_eq!(shared.last_chapter_read, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_newest_wins_keeps_mine_when_edit_times_unknown() -> Result<(), Box<dyn Error>> {
+        let (mine, _td, theirs, _td2) = given_two_libraries()?;
+        theirs.execute("UPDATE fanfiction SET user_updated_at = NULL", [])?;
+        let snapshot = SqliteRepository::new(&theirs).export_library()?;
+        let repo = SqliteRepository::new(&mine);
+
+        let report = merge_library(&repo, &snapshot, MergePolicy::NewestWins, false)?;
+
+        assert_eq!(report.conflicts[0].kept, MergeSide::Mine);
+        assert_eq!(
+            repo.get_fanfiction_by_id(1)?.reading_status,
+            ReadingStatus::PlanToRead
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_dry_run_changes_nothing() -> Result<(), Box<dyn Error>> {
+        let (mine, _td, theirs, _td2) = given_two_libraries()?;
+        let snapshot = SqliteRepository::new(&theirs).export_library()?;
+        let repo = SqliteRepository::new(&mine);
+
+        let report = merge_library(&repo, &snapshot, MergePolicy::KeepTheirs, true)?;
+
+        assert_eq!(report.fics_added, 1);
+        assert_eq!(report.conflicts[0].kept, MergeSide::Theirs);
+        assert_eq!(repo.list_fanfictions()?.len(), 1);
+        assert_eq!(repo.get_fanfiction_by_id(1)?.title, "Shared");
+        assert_eq!(repo.list_shelves()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_leaves_trashed_fic_in_trash() -> Result<(), Box<dyn Error>> {
+        let (mine, _td, theirs, _td2) = given_two_libraries()?;
+        let repo = SqliteRepository::new(&mine);
+        repo.delete_fanfiction(1)?;
+        let snapshot = SqliteRepository::new(&theirs).export_library()?;
+
+        merge_library(&repo, &snapshot, MergePolicy::KeepTheirs, false)?;
+
+        assert!(matches!(
+            repo.get_fanfiction_by_id(1),
+            Err(FicflowError::NotFound { .. })
+        ));
+        assert_eq!(repo.list_trashed_fics()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_stamps_edit_time_only_when_user_fields_change() -> Result<(), Box<dyn Error>> {
+        let (conn, _, _td) = fixtures::given_test_database();
+        let repo = SqliteRepository::new(&conn);
+        let mut fic = fixtures::given_sample_fanfiction(1, "Stamped");
+        repo.save_fanfiction(&fic)?;
+        set_user_updated_at(&conn, 1, 30)?;
+        let stamped = |repo: &SqliteRepository| -> Result<_, Box<dyn Error>> {
+            let snapshot = repo.export_library()?;
+            Ok(snapshot.fics[0].user_updated_at.unwrap())
+        };
+        let before = stamped(&repo)?;
+
+        fic.kudos = 500;
+        repo.save_fanfiction(&fic)?;
+        assert_eq!(stamped(&repo)?, before);
+
+        fic.read_count = 1;
+        repo.save_fanfiction(&fic)?;
+        assert!(stamped(&repo)? > before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_library_file_leaves_the_original_alone() -> Result<(), Box<dyn Error>> {
+        let (conn, path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(7, "Kept"),
+        )?;
+        drop(conn);
+        let before = std::fs::read(&path)?;
+
+        let snapshot = read_library_file(&path)?;
+
+        assert_eq!(snapshot.fics.len(), 1);
+        assert_eq!(snapshot.fics[0].fic.title, "Kept");
+        assert_eq!(std::fs::read(&path)?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_library_file_takes_no_backup_of_an_older_library() -> Result<(), Box<dyn Error>> {
+        let (conn, path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(&conn, &fixtures::given_sample_fanfiction(7, "Old"))?;
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        // The latest migration can run again, so stepping the version
+        // back is enough for the copy to have one to run.
+        conn.execute_batch(&format!("PRAGMA user_version = {};", version - 1))?;
+        drop(conn);
+        // Where a backup of the temp copy would land.
+        let temp_backups = std::env::temp_dir().join("backups");
+        let listed = || -> Vec<std::path::PathBuf> {
+            std::fs::read_dir(&temp_backups)
+                .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                .unwrap_or_default()
+        };
+        let before = listed();
+
+        let snapshot = read_library_file(&path)?;
+
+        assert_eq!(snapshot.fics[0].fic.title, "Old");
+        assert_eq!(listed(), before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_library_file_rejects_other_databases() -> Result<(), Box<dyn Error>> {
+        let td = TempDir::new()?;
+        let path = td.path().join("other.db");
+        Connection::open(&path)?.execute_batch("CREATE TABLE notes (body TEXT);")?;
+
+        let result = read_library_file(&path);
+
+        assert!(matches!(result, Err(FicflowError::InvalidInput(_))));
+        Ok(())
+    }
+}
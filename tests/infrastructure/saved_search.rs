@@ -0,0 +1,175 @@
+use rusqlite::Connection;
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::application::run_saved_search::run_saved_search;
+    use ficflow::domain::fanfiction::{ColumnKey, ReadingStatus, SortDirection, SortPref};
+    use ficflow::domain::saved_search::{SavedSearchOps, SearchScope};
+    use ficflow::domain::shelf::{AutoShelfCriteria, Clause, ClauseLogic, ShelfOps};
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    fn by_words(direction: SortDirection) -> SortPref {
+        SortPref {
+            column: ColumnKey::Words,
+            direction,
+        }
+    }
+
+    #[test]
+    fn test_create_saved_search_round_trips_scope_and_sort() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+
+        let created = repo.create_saved_search(
+            "  Long reads  ",
+            "slow burn",
+            SearchScope::ByStatus(ReadingStatus::PlanToRead),
+            by_words(SortDirection::Descending),
+        )?;
+
+        assert_eq!(created.name, "Long reads");
+        let fetched = repo.get_saved_search_by_id(created.id)?;
+        assert_eq!(fetched.query, "slow burn");
+        assert_eq!(
+            fetched.scope,
+            SearchScope::ByStatus(ReadingStatus::PlanToRead)
+        );
+        assert_eq!(fetched.sort, by_words(SortDirection::Descending));
+        assert_eq!(fetched.position, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_saved_search_rejects_blank_name() {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+
+        let err = repo
+            .create_saved_search("  ", "x", SearchScope::AllFics, SortPref::default())
+            .unwrap_err();
+        assert!(matches!(err, FicflowError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_create_saved_search_rejects_missing_shelf_scope() {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+
+        let err = repo
+            .create_saved_search("Ghost", "x", SearchScope::Shelf(42), SortPref::default())
+            .unwrap_err();
+        assert!(matches!(err, FicflowError::ShelfNotFound { shelf_id: 42 }));
+    }
+
+    #[test]
+    fn test_move_and_delete_keep_positions_contiguous() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let a = repo.create_saved_search("A", "", SearchScope::AllFics, SortPref::default())?;
+        let b = repo.create_saved_search("B", "", SearchScope::AllFics, SortPref::default())?;
+        let c = repo.create_saved_search("C", "", SearchScope::AllFics, SortPref::default())?;
+
+        repo.move_saved_search(c.id, 0)?;
+        let order: Vec<u64> = repo.list_saved_searches()?.iter().map(|s| s.id).collect();
+        assert_eq!(order, vec![c.id, a.id, b.id]);
+
+        // Out-of-range targets clamp to the end.
+        repo.move_saved_search(c.id, 99)?;
+        repo.delete_saved_search(a.id)?;
+        let listed = repo.list_saved_searches()?;
+        let order: Vec<u64> = listed.iter().map(|s| s.id).collect();
+        let positions: Vec<u32> = listed.iter().map(|s| s.position).collect();
+        assert_eq!(order, vec![b.id, c.id]);
+        assert_eq!(positions, vec![0, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_and_delete_unknown_saved_search_is_not_found() {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+
+        assert!(matches!(
+            repo.rename_saved_search(7, "New").unwrap_err(),
+            FicflowError::SavedSearchNotFound { saved_search_id: 7 }
+        ));
+        assert!(matches!(
+            repo.delete_saved_search(7).unwrap_err(),
+            FicflowError::SavedSearchNotFound { saved_search_id: 7 }
+        ));
+    }
+
+    #[test]
+    fn test_run_saved_search_filters_scope_and_query_in_sort_order() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut short = fixtures::given_sample_fanfiction(1, "Short Storm");
+        short.words = 100;
+        let mut long = fixtures::given_sample_fanfiction(2, "Long Storm");
+        long.words = 5000;
+        let other = fixtures::given_sample_fanfiction(3, "Calm");
+        for fic in [&short, &long, &other] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+        let shelf = repo.create_shelf("Storms", None)?;
+        repo.add_fic_to_shelf(1, shelf.id)?;
+        repo.add_fic_to_shelf(2, shelf.id)?;
+        repo.add_fic_to_shelf(3, shelf.id)?;
+
+        let search = repo.create_saved_search(
+            "Storms by length",
+            "storm",
+            SearchScope::Shelf(shelf.id),
+            by_words(SortDirection::Descending),
+        )?;
+        let (_, fics) = run_saved_search(&repo, &repo, &repo, search.id)?;
+
+        let ids: Vec<u64> = fics.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_saved_search_on_auto_shelf_uses_its_criteria() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut tagged = fixtures::given_sample_fanfiction(1, "Tagged");
+        tagged.tags = Some(vec!["Fluff".to_string()]);
+        let untagged = fixtures::given_sample_fanfiction(2, "Untagged");
+        fixtures::when_fanfiction_added_to_db(&conn, &tagged)?;
+        fixtures::when_fanfiction_added_to_db(&conn, &untagged)?;
+        let auto = repo.upsert_auto_shelf(
+            None,
+            "Fluff",
+            None,
+            AutoShelfCriteria {
+                logic: ClauseLogic::And,
+                clauses: vec![Clause::Tag("fluff".to_string())],
+            },
+        )?;
+
+        let search = repo.create_saved_search(
+            "All fluff",
+            "",
+            SearchScope::Shelf(auto.id),
+            SortPref::default(),
+        )?;
+        let (_, fics) = run_saved_search(&repo, &repo, &repo, search.id)?;
+
+        let ids: Vec<u64> = fics.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![1]);
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use rusqlite::Connection;
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::fanfiction::FanfictionOps;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    fn hit_ids(repo: &SqliteRepository<'_>, query: &str) -> Result<Vec<u64>, Box<dyn Error>> {
+        Ok(repo
+            .search_fanfictions(query)?
+            .into_iter()
+            .map(|h| h.fic.id)
+            .collect())
+    }
+
+    #[test]
+    fn test_search_covers_summary_tags_and_personal_note() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut by_summary = fixtures::given_sample_fanfiction(1, "One");
+        by_summary.summary = "A lighthouse keeper and a storm.".to_string();
+        let mut by_tag = fixtures::given_sample_fanfiction(2, "Two");
+        by_tag.tags = Some(vec!["Slow Burn".to_string()]);
+        let mut by_note = fixtures::given_sample_fanfiction(3, "Three");
+        by_note.personal_note = Some("reread before the sequel".to_string());
+        for fic in [&by_summary, &by_tag, &by_note] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+
+        assert_eq!(hit_ids(&repo, "lighthouse")?, vec![1]);
+        assert_eq!(hit_ids(&repo, "slow burn")?, vec![2]);
+        assert_eq!(hit_ids(&repo, "sequel")?, vec![3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_matches_word_prefixes_and_requires_every_word() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Lighthouse Keeper"),
+        )?;
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(2, "Lighthouse Ghost"),
+        )?;
+
+        assert_eq!(hit_ids(&repo, "light")?, vec![2, 1]);
+        assert_eq!(hit_ids(&repo, "light keep")?, vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_ranks_title_matches_above_summary_matches() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut in_summary = fixtures::given_sample_fanfiction(1, "Aaa");
+        in_summary.summary = "Somewhere there is a comet.".to_string();
+        let in_title = fixtures::given_sample_fanfiction(2, "Zzz Comet");
+        fixtures::when_fanfiction_added_to_db(&conn, &in_summary)?;
+        fixtures::when_fanfiction_added_to_db(&conn, &in_title)?;
+
+        assert_eq!(hit_ids(&repo, "comet")?, vec![2, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_index_follows_resaves_and_soft_deletes() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut fic = fixtures::given_sample_fanfiction(1, "Original Title");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+
+        fic.title = "Renamed Title".to_string();
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        assert!(hit_ids(&repo, "original")?.is_empty());
+        assert_eq!(hit_ids(&repo, "renamed")?, vec![1]);
+
+        fixtures::when_fanfiction_deleted_from_db(&conn, 1)?;
+        assert!(hit_ids(&repo, "renamed")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_snippet_highlights_matched_terms() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut fic = fixtures::given_sample_fanfiction(1, "Plain");
+        fic.summary = "The keeper lights the lamp every night.".to_string();
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+
+        let hits = repo.search_fanfictions("lamp")?;
+        let snippet = &hits[0].snippet;
+        assert!(snippet.text.contains("The keeper lights the lamp"));
+        let highlighted: Vec<&str> = snippet
+            .highlights
+            .iter()
+            .map(|r| &snippet.text[r.clone()])
+            .collect();
+        assert_eq!(highlighted, vec!["lamp"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_treats_fts_syntax_as_plain_text() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Title"),
+        )?;
+
+        assert!(hit_ids(&repo, "\"")?.is_empty());
+        assert!(hit_ids(&repo, "  ")?.is_empty());
+        assert_eq!(hit_ids(&repo, "title* (")?, vec![1]);
+        Ok(())
+    }
+}
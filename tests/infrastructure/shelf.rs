@@ -674,4 +674,21 @@ mod tests {
         assert_eq!(counts.get(&child.id).copied(), Some(1));
         Ok(())
     }
+
+    #[test]
+    fn test_resaving_fic_keeps_its_shelves() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+
+        let shelf = repo.create_shelf("Favourites", None)?;
+        let mut fic = fixtures::given_sample_fanfiction(97, "Kept");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        repo.add_fic_to_shelf(fic.id, shelf.id)?;
+
+        fic.read_count = 1;
+        repo.save_fanfiction(&fic)?;
+
+        assert_eq!(repo.count_fics_in_shelf(shelf.id)?, 1);
+        Ok(())
+    }
 }
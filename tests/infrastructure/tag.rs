@@ -0,0 +1,232 @@
+use rusqlite::Connection;
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::domain::fanfiction::{FanfictionOps, TagCount, TagKind};
+    use ficflow::infrastructure::persistence::database::connection::open_configured_db;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    fn count(name: &str, count: usize) -> TagCount {
+        TagCount {
+            name: name.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_count_tags_counts_live_fics_per_kind() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut first = fixtures::given_sample_fanfiction(1, "First");
+        first.tags = Some(vec!["Slow Burn".into(), "Fluff".into()]);
+        let mut second = fixtures::given_sample_fanfiction(2, "Second");
+        second.tags = Some(vec!["Slow Burn".into()]);
+        let mut deleted = fixtures::given_sample_fanfiction(3, "Deleted");
+        deleted.tags = Some(vec!["Angst".into()]);
+        for fic in [&first, &second, &deleted] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+        fixtures::when_fanfiction_deleted_from_db(&conn, 3)?;
+
+        assert_eq!(
+            repo.count_tags(TagKind::Tag)?,
+            vec![count("Fluff", 1), count("Slow Burn", 2)]
+        );
+        assert_eq!(
+            repo.count_tags(TagKind::Author)?,
+            vec![count("Test Author", 2)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_fic_ids_by_tag_ignores_case_and_other_kinds() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut tagged = fixtures::given_sample_fanfiction(1, "Tagged");
+        tagged.tags = Some(vec!["Hurt/Comfort".into()]);
+        let mut as_character = fixtures::given_sample_fanfiction(2, "Character");
+        as_character.tags = None;
+        as_character.characters = Some(vec!["Hurt/Comfort".into()]);
+        for fic in [&tagged, &as_character] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+
+        assert_eq!(
+            repo.find_fic_ids_by_tag(TagKind::Tag, "hurt/comfort")?,
+            vec![1]
+        );
+        assert_eq!(
+            repo.find_fic_ids_by_tag(TagKind::Character, "Hurt/Comfort")?,
+            vec![2]
+        );
+        assert!(
+            repo.find_fic_ids_by_tag(TagKind::Fandom, "Hurt/Comfort")?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resaving_fic_replaces_its_tags() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut fic = fixtures::given_sample_fanfiction(1, "Retagged");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+
+        fic.tags = Some(vec!["Tag 2".into(), "Tag 3".into()]);
+        repo.save_fanfiction(&fic)?;
+
+        assert_eq!(
+            repo.count_tags(TagKind::Tag)?,
+            vec![count("Tag 2", 1), count("Tag 3", 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_migration_backfills_tags_from_existing_rows() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let fic = fixtures::given_sample_fanfiction(1, "Before");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        // Roll the schema back to just before the tag tables existed (the
+        // 10th migration), undoing the later ones too, so reopening
+        // re-runs them over the row saved above.
+        conn.execute_batch(
+            "DROP TRIGGER fanfiction_tags_ai;
+             DROP TRIGGER fanfiction_tags_au;
+             DROP TABLE fic_tag;
+             DROP TABLE tag;
+             ALTER TABLE fanfiction DROP COLUMN personal_tags;
+             ALTER TABLE fanfiction DROP COLUMN user_updated_at;
+             PRAGMA user_version = 9;",
+        )?;
+        drop(conn);
+
+        let conn = open_configured_db(&db_path)?;
+        let repo = SqliteRepository::new(&conn);
+
+        assert_eq!(
+            repo.count_tags(TagKind::Character)?,
+            vec![count("Character A", 1), count("Character B", 1)]
+        );
+        assert_eq!(
+            repo.find_fic_ids_by_tag(TagKind::Fandom, "test fandom")?,
+            vec![1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_names_differing_in_case_are_one_tag() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut first = fixtures::given_sample_fanfiction(1, "First");
+        first.tags = Some(vec!["Fluff".into()]);
+        let mut second = fixtures::given_sample_fanfiction(2, "Second");
+        second.tags = Some(vec!["fluff".into(), "FLUFF".into()]);
+        for fic in [&first, &second] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+
+        assert_eq!(repo.count_tags(TagKind::Tag)?, vec![count("Fluff", 2)]);
+        Ok(())
+    }
+
+    /// SQLite's NOCASE folds ASCII letters only; other letters keep
+    /// their case, in the key and in lookups alike.
+    #[test]
+    fn test_names_differing_in_non_ascii_case_stay_apart() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut first = fixtures::given_sample_fanfiction(1, "First");
+        first.characters = Some(vec!["Émilie".into()]);
+        let mut second = fixtures::given_sample_fanfiction(2, "Second");
+        second.characters = Some(vec!["émilie".into(), "ÉMILIE".into()]);
+        for fic in [&first, &second] {
+            fixtures::when_fanfiction_added_to_db(&conn, fic)?;
+        }
+
+        // `ÉMILIE` folds into `Émilie`: only its ASCII letters differ.
+        assert_eq!(
+            repo.count_tags(TagKind::Character)?,
+            vec![count("Émilie", 2), count("émilie", 1)]
+        );
+        assert_eq!(
+            repo.find_fic_ids_by_tag(TagKind::Character, "émilie")?,
+            vec![2]
+        );
+        assert_eq!(
+            repo.find_fic_ids_by_tag(TagKind::Character, "ÉMILIE")?,
+            vec![1, 2]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_migration_merges_tags_differing_in_case() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        for id in 1..=3 {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, "Fic"),
+            )?;
+        }
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        // The tables as an older build left them, when names only
+        // matched exactly: fic 3 carries both spellings.
+        conn.execute_batch(&format!(
+            "DROP TABLE fic_tag;
+             DROP TABLE tag;
+             CREATE TABLE tag (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT, kind TEXT NOT NULL,
+                 name TEXT NOT NULL, UNIQUE (kind, name)
+             );
+             CREATE TABLE fic_tag (
+                 fic_id INTEGER NOT NULL, tag_id INTEGER NOT NULL,
+                 position INTEGER NOT NULL, PRIMARY KEY (fic_id, tag_id)
+             );
+             INSERT INTO tag (id, kind, name) VALUES
+                 (1, 'Tag', 'Slow Burn'), (2, 'Tag', 'slow burn'), (3, 'Tag', 'Angst');
+             INSERT INTO fic_tag (fic_id, tag_id, position) VALUES
+                 (1, 1, 0), (2, 2, 0), (2, 3, 1), (3, 2, 1), (3, 1, 2);
+             PRAGMA user_version = {};",
+            version - 1
+        ))?;
+        drop(conn);
+
+        let conn = open_configured_db(&db_path)?;
+        let repo = SqliteRepository::new(&conn);
+
+        assert_eq!(
+            repo.count_tags(TagKind::Tag)?,
+            vec![count("Angst", 1), count("Slow Burn", 3)]
+        );
+        let position: i64 = conn.query_row(
+            "SELECT position FROM fic_tag WHERE fic_id = 3 AND tag_id = 1",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(position, 1);
+
+        let mut later = fixtures::given_sample_fanfiction(4, "Later");
+        later.tags = Some(vec!["SLOW BURN".into()]);
+        repo.save_fanfiction(&later)?;
+        assert_eq!(
+            repo.count_tags(TagKind::Tag)?,
+            vec![count("Angst", 1), count("Slow Burn", 4)]
+        );
+        Ok(())
+    }
+}
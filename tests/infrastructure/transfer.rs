@@ -0,0 +1,248 @@
+use rusqlite::Connection;
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use ficflow::application::export_library::export_library;
+    use ficflow::application::import_library::{import_library, parse_library_snapshot};
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus, SortPref, TagKind};
+    use ficflow::domain::saved_search::{SavedSearchOps, SearchScope};
+    use ficflow::domain::shelf::{AutoShelfCriteria, Clause, ShelfKind, ShelfOps};
+    use ficflow::domain::transfer::{ImportMode, ImportSummary, LibrarySnapshot, TransferOps};
+    use ficflow::domain::trash::TrashOps;
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    /// Two live fics (one with user fields filled in), one trashed fic,
+    /// a nested pinned shelf, an auto-shelf and a trashed shelf.
+    fn given_populated_library(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        let repo = SqliteRepository::new(conn);
+        let mut read = fixtures::given_sample_fanfiction(1, "Finished");
+        read.reading_status = ReadingStatus::Read;
+        read.read_count = 2;
+        read.personal_note = Some("reread in winter".into());
+        read.personal_tags = vec!["comfort".into()];
+        fixtures::when_fanfiction_added_to_db(conn, &read)?;
+        fixtures::when_fanfiction_added_to_db(conn, &fixtures::given_sample_fanfiction(2, "Next"))?;
+        fixtures::when_fanfiction_added_to_db(conn, &fixtures::given_sample_fanfiction(3, "Gone"))?;
+
+        let parent = repo.create_shelf("Favorites", None)?;
+        let child = repo.create_shelf("Best of", Some(parent.id))?;
+        repo.set_shelf_pinned(parent.id, true)?;
+        let criteria = AutoShelfCriteria {
+            clauses: vec![Clause::Status(ReadingStatus::Read)],
+            ..AutoShelfCriteria::default()
+        };
+        repo.upsert_auto_shelf(None, "Done", None, criteria)?;
+        let old = repo.create_shelf("Old", None)?;
+
+        repo.add_fic_to_shelf(1, child.id)?;
+        repo.add_fic_to_shelf(2, parent.id)?;
+        repo.add_fic_to_shelf(3, parent.id)?;
+        repo.delete_fanfiction(3)?;
+        repo.delete_shelf(old.id)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_then_replace_import_round_trips() -> Result<(), Box<dyn Error>> {
+        let (source, _td) = setup_test_db();
+        given_populated_library(&source)?;
+        let json = export_library(&SqliteRepository::new(&source))?;
+
+        let (target, _td2) = setup_test_db();
+        let repo = SqliteRepository::new(&target);
+        let snapshot = parse_library_snapshot(&json)?;
+        let summary = import_library(&repo, &snapshot, ImportMode::Replace, false)?;
+
+        assert_eq!(summary.fics_added, 3);
+        assert_eq!(summary.shelves_added, 4);
+        assert_eq!(summary.memberships_added, 3);
+
+        let finished = repo.get_fanfiction_by_id(1)?;
+        assert_eq!(finished.reading_status, ReadingStatus::Read);
+        assert_eq!(finished.read_count, 2);
+        assert_eq!(finished.personal_note.as_deref(), Some("reread in winter"));
+        assert_eq!(finished.personal_tags, vec!["comfort".to_string()]);
+
+        let shelves = repo.list_shelves()?;
+        let favorites = shelves.iter().find(|s| s.name == "Favorites").unwrap();
+        let best_of = shelves.iter().find(|s| s.name == "Best of").unwrap();
+        let done = shelves.iter().find(|s| s.name == "Done").unwrap();
+        assert!(favorites.pinned);
+        assert_eq!(best_of.parent_shelf_id, Some(favorites.id));
+        assert!(matches!(&done.kind, ShelfKind::Auto(c) if c.clauses.len() == 1));
+        assert_eq!(repo.count_fics_in_shelf(favorites.id)?, 2);
+
+        let trashed_fics = repo.list_trashed_fics()?;
+        assert_eq!(trashed_fics.len(), 1);
+        assert_eq!(trashed_fics[0].fic.title, "Gone");
+        assert_eq!(repo.list_trashed_shelves()?[0].shelf.name, "Old");
+        // Restoring the trashed fic brings back the membership it was
+        // exported with.
+        repo.restore_fic(3)?;
+        assert_eq!(repo.count_fics_in_shelf(favorites.id)?, 3);
+
+        // Tags were indexed on the way in.
+        assert_eq!(repo.count_tags(TagKind::Author)?.len(), 1);
+
+        // Replace keeps the file's ids, so exporting again gives back
+        // the same shelves and memberships.
+        let again = repo.export_library()?;
+        let shelf_ids =
+            |s: &LibrarySnapshot| -> Vec<u64> { s.shelves.iter().map(|e| e.shelf.id).collect() };
+        let pairs = |s: &LibrarySnapshot| -> Vec<(u64, u64)> {
+            s.memberships
+                .iter()
+                .map(|m| (m.fic_id, m.shelf_id))
+                .collect()
+        };
+        assert_eq!(shelf_ids(&again), shelf_ids(&snapshot));
+        assert_eq!(pairs(&again), pairs(&snapshot));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_import_moves_shelf_scoped_searches_to_the_new_shelves()
+    -> Result<(), Box<dyn Error>> {
+        let (source, _td) = setup_test_db();
+        given_populated_library(&source)?;
+        let snapshot = SqliteRepository::new(&source).export_library()?;
+
+        let (target, _td2) = setup_test_db();
+        let repo = SqliteRepository::new(&target);
+        // Created first, so the target's ids don't line up with the file's.
+        let scratch = repo.create_shelf("Scratch", None)?;
+        let favorites = repo.create_shelf("favorites", None)?;
+        let best_of = repo.create_shelf("Best of", Some(favorites.id))?;
+        let sort = SortPref::default();
+        let follows =
+            repo.create_saved_search("Best", "x", SearchScope::Shelf(best_of.id), sort)?;
+        let orphaned =
+            repo.create_saved_search("Scratch", "x", SearchScope::Shelf(scratch.id), sort)?;
+        let everywhere = repo.create_saved_search("All", "x", SearchScope::AllFics, sort)?;
+
+        repo.import_library(&snapshot, ImportMode::Replace, false)?;
+
+        let imported = repo
+            .list_shelves()?
+            .into_iter()
+            .find(|s| s.name == "Best of")
+            .unwrap();
+        let scope = |id: u64| repo.get_saved_search_by_id(id).map(|s| s.scope);
+        assert_eq!(scope(follows.id)?, SearchScope::Shelf(imported.id));
+        assert_eq!(scope(orphaned.id)?, SearchScope::AllFics);
+        assert_eq!(scope(everywhere.id)?, SearchScope::AllFics);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_import_keeps_local_fics_and_reuses_shelves() -> Result<(), Box<dyn Error>> {
+        let (source, _td) = setup_test_db();
+        given_populated_library(&source)?;
+        let snapshot = SqliteRepository::new(&source).export_library()?;
+
+        let (target, _td2) = setup_test_db();
+        let repo = SqliteRepository::new(&target);
+        let mut local = fixtures::given_sample_fanfiction(1, "Finished");
+        local.personal_note = Some("my own note".into());
+        fixtures::when_fanfiction_added_to_db(&target, &local)?;
+        let favorites = repo.create_shelf("favorites", None)?;
+
+        let summary = repo.import_library(&snapshot, ImportMode::Merge, false)?;
+
+        assert_eq!(summary.fics_added, 2);
+        assert_eq!(summary.fics_skipped, 1);
+        assert_eq!(summary.shelves_matched, 1);
+        assert_eq!(summary.shelves_added, 3);
+        assert_eq!(
+            repo.get_fanfiction_by_id(1)?.personal_note.as_deref(),
+            Some("my own note")
+        );
+        let best_of = repo
+            .list_shelves()?
+            .into_iter()
+            .find(|s| s.name == "Best of")
+            .unwrap();
+        assert_eq!(best_of.parent_shelf_id, Some(favorites.id));
+        assert_eq!(repo.list_shelves()?.len(), 3);
+
+        // Importing the same file again changes nothing.
+        let again = repo.import_library(&snapshot, ImportMode::Merge, false)?;
+        assert_eq!(again.fics_added, 0);
+        assert_eq!(again.shelves_added, 0);
+        assert_eq!(again.memberships_added, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_writing() -> Result<(), Box<dyn Error>> {
+        let (source, _td) = setup_test_db();
+        given_populated_library(&source)?;
+        let snapshot = SqliteRepository::new(&source).export_library()?;
+
+        let (target, _td2) = setup_test_db();
+        let repo = SqliteRepository::new(&target);
+        fixtures::when_fanfiction_added_to_db(
+            &target,
+            &fixtures::given_sample_fanfiction(99, "Local only"),
+        )?;
+
+        let summary = repo.import_library(&snapshot, ImportMode::Replace, true)?;
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                fics_added: 3,
+                shelves_added: 4,
+                memberships_added: 3,
+                fics_removed: 1,
+                ..ImportSummary::default()
+            }
+        );
+        let fics = repo.list_fanfictions()?;
+        assert_eq!(fics.len(), 1);
+        assert_eq!(fics[0].id, 99);
+        assert!(repo.list_shelves()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_schema_version_is_rejected() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"schema_version": 999, "exported_at": "2026-01-01T00:00:00Z"}"#;
+
+        let result = parse_library_snapshot(json);
+
+        assert!(matches!(
+            result,
+            Err(FicflowError::InvalidInput(msg)) if msg.contains("999")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dangling_membership_is_rejected() -> Result<(), Box<dyn Error>> {
+        let (source, _td) = setup_test_db();
+        given_populated_library(&source)?;
+        let mut snapshot = SqliteRepository::new(&source).export_library()?;
+        snapshot.fics.retain(|f| f.fic.id != 2);
+        let json = serde_json::to_string(&snapshot)?;
+
+        let result = parse_library_snapshot(&json);
+
+        assert!(
+            matches!(result, Err(FicflowError::InvalidInput(msg)) if msg.contains("fanfiction 2"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use rusqlite::{Connection, params};
+use std::error::Error;
+use tempfile::TempDir;
+
+use crate::common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use ficflow::domain::fanfiction::{FanfictionOps, TagKind};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::domain::trash::{PurgeSummary, TrashOps};
+    use ficflow::error::FicflowError;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+
+    use super::*;
+
+    fn setup_test_db() -> (Connection, TempDir) {
+        let (conn, _db_path, temp_dir) = fixtures::given_test_database();
+        (conn, temp_dir)
+    }
+
+    fn backdate_fic(conn: &Connection, fic_id: u64, days: i64) -> Result<(), Box<dyn Error>> {
+        let deleted_at = (Utc::now() - Duration::days(days)).to_rfc3339();
+        conn.execute(
+            "UPDATE fanfiction SET deleted_at = ?2 WHERE id = ?1",
+            params![fic_id, deleted_at],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_trashed_fics_newest_first_and_skips_live() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        for (id, title) in [(1, "Old"), (2, "Recent"), (3, "Live")] {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )?;
+        }
+        fixtures::when_fanfiction_deleted_from_db(&conn, 1)?;
+        fixtures::when_fanfiction_deleted_from_db(&conn, 2)?;
+        backdate_fic(&conn, 1, 3)?;
+
+        let trashed = repo.list_trashed_fics()?;
+
+        let titles: Vec<&str> = trashed.iter().map(|t| t.fic.title.as_str()).collect();
+        assert_eq!(titles, vec!["Recent", "Old"]);
+        assert!(trashed[0].deleted_at > trashed[1].deleted_at);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_fic_brings_back_shelf_memberships() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Kept"),
+        )?;
+        let shelf = repo.create_shelf("Favorites", None)?;
+        repo.add_fic_to_shelf(1, shelf.id)?;
+        repo.delete_fanfiction(1)?;
+        assert_eq!(repo.count_fics_in_shelf(shelf.id)?, 0);
+
+        let restored = repo.restore_fic(1)?;
+
+        assert_eq!(restored.title, "Kept");
+        assert!(repo.list_trashed_fics()?.is_empty());
+        assert_eq!(repo.count_fics_in_shelf(shelf.id)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_live_fic_is_not_in_trash() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Live"),
+        )?;
+
+        let err = repo.restore_fic(1).unwrap_err();
+
+        assert!(matches!(err, FicflowError::NotInTrash { fic_id: 1 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_shelf_returns_under_live_parent() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let parent = repo.create_shelf("Parent", None)?;
+        let child = repo.create_shelf("Child", Some(parent.id))?;
+        repo.delete_shelf(child.id)?;
+
+        let restored = repo.restore_shelf(child.id)?;
+
+        assert_eq!(restored.parent_shelf_id, Some(parent.id));
+        assert!(repo.list_trashed_shelves()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_shelf_goes_to_top_level_when_parent_is_trashed() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let parent = repo.create_shelf("Parent", None)?;
+        let child = repo.create_shelf("Child", Some(parent.id))?;
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Member"),
+        )?;
+        repo.add_fic_to_shelf(1, child.id)?;
+        repo.delete_shelf(child.id)?;
+        repo.delete_shelf(parent.id)?;
+
+        let restored = repo.restore_shelf(child.id)?;
+
+        assert_eq!(restored.parent_shelf_id, None);
+        assert_eq!(repo.count_fics_in_shelf(child.id)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_fic_removes_row_memberships_and_orphan_tags() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let mut fic = fixtures::given_sample_fanfiction(1, "Gone");
+        fic.tags = Some(vec!["Only Here".into()]);
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        let shelf = repo.create_shelf("Favorites", None)?;
+        repo.add_fic_to_shelf(1, shelf.id)?;
+        repo.delete_fanfiction(1)?;
+
+        repo.purge_fic(1)?;
+
+        let rows: u32 = conn.query_row("SELECT COUNT(*) FROM fanfiction", [], |r| r.get(0))?;
+        let memberships: u32 =
+            conn.query_row("SELECT COUNT(*) FROM fic_shelf", [], |r| r.get(0))?;
+        let tags: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM tag WHERE kind = ?1",
+            params![TagKind::Tag.to_string()],
+            |r| r.get(0),
+        )?;
+        assert_eq!((rows, memberships, tags), (0, 0, 0));
+        assert!(repo.search_fanfictions("Gone")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_rejects_live_items() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Live"),
+        )?;
+        let shelf = repo.create_shelf("Live Shelf", None)?;
+
+        assert!(matches!(
+            repo.purge_fic(1).unwrap_err(),
+            FicflowError::NotInTrash { fic_id: 1 }
+        ));
+        assert!(matches!(
+            repo.purge_shelf(shelf.id).unwrap_err(),
+            FicflowError::ShelfNotInTrash { .. }
+        ));
+        assert_eq!(repo.list_fanfictions()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_shelf_promotes_trashed_children() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        let parent = repo.create_shelf("Parent", None)?;
+        let child = repo.create_shelf("Child", Some(parent.id))?;
+        repo.delete_shelf(child.id)?;
+        repo.delete_shelf(parent.id)?;
+
+        repo.purge_shelf(parent.id)?;
+
+        let trashed = repo.list_trashed_shelves()?;
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].shelf.id, child.id);
+        assert_eq!(trashed[0].shelf.parent_shelf_id, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_trash_honours_cutoff() -> Result<(), Box<dyn Error>> {
+        let (conn, _td) = setup_test_db();
+        let repo = SqliteRepository::new(&conn);
+        for (id, title) in [(1, "Expired"), (2, "Fresh")] {
+            fixtures::when_fanfiction_added_to_db(
+                &conn,
+                &fixtures::given_sample_fanfiction(id, title),
+            )?;
+        }
+        fixtures::when_fanfiction_deleted_from_db(&conn, 1)?;
+        fixtures::when_fanfiction_deleted_from_db(&conn, 2)?;
+        backdate_fic(&conn, 1, 40)?;
+        let shelf = repo.create_shelf("Fresh Shelf", None)?;
+        repo.delete_shelf(shelf.id)?;
+
+        let summary = repo.purge_trash(Some(Utc::now() - Duration::days(30)))?;
+
+        assert_eq!(
+            summary,
+            PurgeSummary {
+                fics: 1,
+                shelves: 0
+            }
+        );
+        let remaining: Vec<u64> = repo.list_trashed_fics()?.iter().map(|t| t.fic.id).collect();
+        assert_eq!(remaining, vec![2]);
+
+        let summary = repo.purge_trash(None)?;
+
+        assert_eq!(
+            summary,
+            PurgeSummary {
+                fics: 1,
+                shelves: 1
+            }
+        );
+        assert!(repo.list_trashed_fics()?.is_empty());
+        assert!(repo.list_trashed_shelves()?.is_empty());
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use ficflow::interfaces::utils::url_parser::extract_ao3_id;
+use ficflow::interfaces::utils::url_parser::{extract_ao3_id, extract_ao3_ids};
 
 #[test]
 fn test_extract_numeric_id() {
@@ -42,3 +42,41 @@ fn test_invalid_input() {
     assert!(extract_ao3_id("not-a-valid-input").is_err());
     assert!(extract_ao3_id("https://example.com").is_err());
 }
+
+#[test]
+fn test_extract_many_from_pasted_text() {
+    let text = "\
+omg you have to read https://archiveofourown.org/works/111/chapters/5 and
+archiveofourown.org/works/222 (chapter 12 is the best), also the first one
+again: https://archiveofourown.org/works/111
+333, 444
+555";
+    assert_eq!(extract_ao3_ids(text), vec![111, 222, 333, 444, 555]);
+}
+
+#[test]
+fn test_extract_many_ignores_numbers_in_prose() {
+    assert!(extract_ao3_ids("read 3 fics, loved chapter 12").is_empty());
+    assert!(extract_ao3_ids("").is_empty());
+}
+
+#[test]
+fn test_extract_from_url_with_query_or_fragment() {
+    assert_eq!(
+        extract_ao3_id("https://archiveofourown.org/works/62072974?view_adult=true").unwrap(),
+        62072974
+    );
+    assert_eq!(
+        extract_ao3_id("https://archiveofourown.org/works/62072974#workskin").unwrap(),
+        62072974
+    );
+}
+
+#[test]
+fn test_digits_running_into_a_word_are_not_a_work_id() {
+    assert!(extract_ao3_id("https://archiveofourown.org/works/123abc").is_err());
+    assert_eq!(
+        extract_ao3_ids("see archiveofourown.org/works/123abc and archiveofourown.org/works/456."),
+        vec![456]
+    );
+}
@@ -0,0 +1,377 @@
+//! Single-instance tests. The test process plays the open window by
+//! claiming a library's socket, then runs the `ficflow` binary against
+//! the same library to check what it hands over instead of doing itself.
+//! Also covers `ficflow://` links and the desktop entry that registers
+//! them.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ficflow::domain::fanfiction::FanfictionOps;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::interfaces::instance::link::{desktop_entry, is_link, parse_link};
+    use ficflow::interfaces::instance::{self, Claim, Instance, Message};
+    use tempfile::TempDir;
+
+    /// Nothing listens here; nothing in these tests should fetch.
+    const NO_AO3: &str = "http://127.0.0.1:1";
+
+    fn binary_path() -> PathBuf {
+        std::env::current_dir()
+            .unwrap()
+            .join("target")
+            .join("debug")
+            .join("ficflow")
+    }
+
+    fn run(args: &[&str], db_path: &Path, config_home: &Path) -> Output {
+        Command::new(binary_path())
+            .args(args)
+            .env("FICFLOW_DB_PATH", db_path)
+            .env("AO3_BASE_URL", NO_AO3)
+            .env("XDG_CONFIG_HOME", config_home)
+            .output()
+            .expect("ficflow runs")
+    }
+
+    fn claim(socket: &Path) -> Instance {
+        match Instance::claim(socket) {
+            Ok(Claim::First(instance)) => instance,
+            Ok(Claim::Running) => panic!("a window already has {}", socket.display()),
+            Err(err) => panic!("couldn't claim {}: {}", socket.display(), err),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_one_window_holds_the_socket_until_it_closes() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let socket = dir.path().join("run").join("ficflow.sock");
+
+        let window = claim(&socket);
+        assert!(matches!(Instance::claim(&socket)?, Claim::Running));
+        assert!(instance::send(&socket, &Message::Focus)?);
+        assert_eq!(window.take_messages(), vec![Message::Focus]);
+        assert!(window.take_messages().is_empty());
+
+        drop(window);
+        assert!(!socket.exists());
+        assert!(!instance::send(&socket, &Message::Focus)?);
+
+        // What a window that crashed leaves behind: the file, with
+        // nobody listening on it.
+        drop(std::os::unix::net::UnixListener::bind(&socket)?);
+        assert!(socket.exists());
+        let _window = claim(&socket);
+        assert!(instance::send(&socket, &Message::Reload)?);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_is_only_bound_in_a_private_folder() -> Result<(), Box<dyn Error>> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new()?;
+        let private = dir.path().join("sockets");
+        let window = claim(&private.join("ficflow.sock"));
+        let mode = std::fs::metadata(&private)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        drop(window);
+
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared)?;
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o777))?;
+        assert!(Instance::claim(&shared.join("ficflow.sock")).is_err());
+        assert!(!shared.join("ficflow.sock").exists());
+
+        // A symlink where the folder should be, even to a private one.
+        let planted = dir.path().join("planted");
+        std::os::unix::fs::symlink(&private, &planted)?;
+        assert!(Instance::claim(&planted.join("ficflow.sock")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_each_library_has_its_own_socket() {
+        let dir = TempDir::new().unwrap();
+        let library = dir.path().join("library.db");
+        let relative = dir.path().join(".").join("library.db");
+        assert_eq!(
+            instance::socket_path(&library),
+            instance::socket_path(&relative)
+        );
+        assert_ne!(
+            instance::socket_path(&library),
+            instance::socket_path(&dir.path().join("other.db"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cli_hands_adds_to_the_window_and_refuses_other_changes() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let config_home = TempDir::new()?;
+        let window = claim(&instance::socket_path(&db_path));
+
+        let added = run(&["add", "53960491"], &db_path, config_home.path());
+        assert!(added.status.success(), "{:?}", added);
+        assert!(String::from_utf8_lossy(&added.stdout).contains("open Ficflow window"));
+        assert_eq!(
+            window.take_messages(),
+            vec![Message::Add {
+                fic: "53960491".into()
+            }]
+        );
+        let repository = SqliteRepository::new(&conn);
+        assert!(repository.list_fanfictions()?.is_empty());
+
+        let json = run(
+            &["--output", "json", "add", "53960491"],
+            &db_path,
+            config_home.path(),
+        );
+        let reply: serde_json::Value = serde_json::from_slice(&json.stdout)?;
+        assert_eq!(reply["forwarded"], true);
+        assert_eq!(reply["fic_id"], 53960491);
+        window.take_messages();
+
+        let created = run(
+            &["--output", "json", "shelf", "create", "Harbors"],
+            &db_path,
+            config_home.path(),
+        );
+        assert_eq!(created.status.code(), Some(4), "{:?}", created);
+        let error: serde_json::Value = serde_json::from_slice(&created.stderr)?;
+        assert_eq!(error["error"]["code"], "library_in_use");
+        let moved_to = config_home.path().join("moved.db");
+        let moved = run(
+            &["config", "set", "library_path", moved_to.to_str().unwrap()],
+            &db_path,
+            config_home.path(),
+        );
+        assert_eq!(moved.status.code(), Some(4), "{:?}", moved);
+        assert!(!moved_to.exists());
+        assert!(window.take_messages().is_empty());
+
+        for args in [&["list"][..], &["shelf", "list"], &["backup", "list"]] {
+            let read = run(args, &db_path, config_home.path());
+            assert!(read.status.success(), "{:?}", read);
+        }
+        assert!(window.take_messages().is_empty());
+        // Nor did any of them take the day's backup behind the window.
+        assert!(!db_path.parent().unwrap().join("backups").exists());
+
+        drop(window);
+        let created = run(
+            &["shelf", "create", "Harbors"],
+            &db_path,
+            config_home.path(),
+        );
+        assert!(created.status.success(), "{:?}", created);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_native_host_asks_the_window_to_reload_after_changes() -> Result<(), Box<dyn Error>> {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let config_home = TempDir::new()?;
+        let fic = fixtures::given_sample_fanfiction(6101, "Tidewater");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        let window = claim(&instance::socket_path(&db_path));
+
+        let frame = |message: serde_json::Value| {
+            let body = message.to_string().into_bytes();
+            let mut framed = (body.len() as u32).to_ne_bytes().to_vec();
+            framed.extend(body);
+            framed
+        };
+        let mut stdin = frame(serde_json::json!({"type": "status", "work": 6101}));
+        stdin.extend(frame(
+            serde_json::json!({"type": "progress", "work": 6101, "chapter": 2}),
+        ));
+        let mut host = Command::new(binary_path())
+            .arg("native-host")
+            .env("FICFLOW_DB_PATH", &db_path)
+            .env("AO3_BASE_URL", NO_AO3)
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        host.stdin.take().unwrap().write_all(&stdin)?;
+        assert!(host.wait()?.success());
+
+        assert_eq!(window.take_messages(), vec![Message::Reload]);
+        let repository = SqliteRepository::new(&conn);
+        assert_eq!(
+            repository.get_fanfiction_by_id(6101)?.last_chapter_read,
+            Some(2)
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_server_asks_the_window_to_reload_after_changes() -> Result<(), Box<dyn Error>> {
+        use ficflow::infrastructure::open_configured_db;
+        use ficflow::interfaces::server::ApiServer;
+
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let fic = fixtures::given_sample_fanfiction(6102, "Breakwater");
+        fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        let window = claim(&instance::socket_path(&db_path));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let served = db_path.clone();
+        std::thread::spawn(move || {
+            let conn = open_configured_db(&served).expect("server opens the library");
+            let repository = SqliteRepository::new(&conn);
+            let server = ApiServer::bind(
+                "127.0.0.1:0",
+                "token".into(),
+                vec![NO_AO3.into()],
+                1,
+                served,
+            )
+            .expect("server binds a free port");
+            tx.send(server.local_addr().expect("bound to an IP address"))
+                .unwrap();
+            server.run(&repository);
+        });
+        let base = format!("http://{}", rx.recv()?);
+        let client = reqwest::blocking::Client::new();
+
+        let read = client
+            .get(format!("{}/fics", base))
+            .bearer_auth("token")
+            .send()?;
+        assert!(read.status().is_success());
+        assert!(window.take_messages().is_empty());
+
+        let patched = client
+            .patch(format!("{}/fics/6102", base))
+            .bearer_auth("token")
+            .json(&serde_json::json!({"chapter": 3}))
+            .send()?;
+        assert!(patched.status().is_success());
+        // The reload goes out once the reply has.
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut messages = window.take_messages();
+        while messages.is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            messages = window.take_messages();
+        }
+        assert_eq!(messages, vec![Message::Reload]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_links_reach_the_open_window() -> Result<(), Box<dyn Error>> {
+        let (_conn, db_path, _td) = fixtures::given_test_database();
+        let config_home = TempDir::new()?;
+        let window = claim(&instance::socket_path(&db_path));
+
+        let link =
+            "ficflow://add?url=https%3A%2F%2Farchiveofourown.org%2Fworks%2F123%2Fchapters%2F4";
+        let opened = run(&[link], &db_path, config_home.path());
+        assert!(opened.status.success(), "{:?}", opened);
+        assert_eq!(
+            window.take_messages(),
+            vec![Message::Add {
+                fic: "https://archiveofourown.org/works/123/chapters/4".into()
+            }]
+        );
+
+        let bad = run(&["ficflow://shelve?url=123"], &db_path, config_home.path());
+        assert_eq!(bad.status.code(), Some(2));
+        assert!(window.take_messages().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_links_are_parsed() {
+        assert!(is_link("ficflow://add?url=1"));
+        assert!(is_link("FICFLOW:add"));
+        assert!(!is_link("ficflow"));
+        assert!(!is_link("https://archiveofourown.org/works/1"));
+
+        assert_eq!(
+            parse_link("ficflow://add?url=53960491").unwrap(),
+            Message::Add {
+                fic: "53960491".into()
+            }
+        );
+        for bad in [
+            "ficflow://add",
+            "ficflow://add?url=https%3A%2F%2Fexample.org%2F",
+            "ficflow://remove?url=53960491",
+            "https://archiveofourown.org/works/53960491",
+            "not a link",
+        ] {
+            let err = parse_link(bad).unwrap_err();
+            assert_eq!(err.code(), "invalid_input", "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_desktop_entry_claims_the_scheme() {
+        let entry = desktop_entry(Path::new("/opt/My $Apps/ficflow"));
+        assert!(entry.starts_with("[Desktop Entry]\n"), "{}", entry);
+        assert!(
+            entry.contains("Exec=\"/opt/My \\$Apps/ficflow\" %u\n"),
+            "{}",
+            entry
+        );
+        assert!(
+            entry.contains("MimeType=x-scheme-handler/ficflow;\n"),
+            "{}",
+            entry
+        );
+        assert!(entry.contains("NoDisplay=true\n"), "{}", entry);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_url_handler_prints_and_installs_the_entry() -> Result<(), Box<dyn Error>> {
+        let home = TempDir::new()?;
+        let data_home = home.path().join("data");
+        let url_handler = |args: &[&str]| {
+            Command::new(binary_path())
+                .arg("url-handler")
+                .args(args)
+                .env("HOME", home.path())
+                .env("XDG_CONFIG_HOME", home.path().join("config"))
+                .env("XDG_DATA_HOME", &data_home)
+                .env("FICFLOW_DB_PATH", home.path().join("lib.db"))
+                .output()
+                .unwrap()
+        };
+
+        let printed = url_handler(&[]);
+        assert!(printed.status.success());
+        let printed = String::from_utf8(printed.stdout)?;
+        assert!(printed.contains("x-scheme-handler/ficflow"), "{}", printed);
+
+        let installed = url_handler(&["--install"]);
+        assert!(installed.status.success(), "{:?}", installed);
+        let written = data_home
+            .join("applications")
+            .join("ficflow-url-handler.desktop");
+        assert_eq!(std::fs::read_to_string(written)?, printed);
+        Ok(())
+    }
+}
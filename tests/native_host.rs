@@ -0,0 +1,217 @@
+//! Native-messaging host tests. The `ficflow native-host` binary is fed
+//! length-prefixed JSON on a piped stdin, the way a browser does, and its
+//! framed replies are read back from stdout; adds fetch from an
+//! `httpmock`'d AO3. Also covers the manifests `native-host manifest`
+//! prints and installs.
+
+use std::error::Error;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ficflow::domain::fanfiction::FanfictionOps;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    /// Nothing listens here; only the add test fetches.
+    const NO_AO3: &str = "http://127.0.0.1:1";
+    const CHROMIUM_ID: &str = "knldjmfmopnpolahpmmgbagdohdnhkik";
+
+    fn binary_path() -> PathBuf {
+        std::env::current_dir()
+            .unwrap()
+            .join("target")
+            .join("debug")
+            .join("ficflow")
+    }
+
+    fn frame(body: &[u8]) -> Vec<u8> {
+        let mut framed = (body.len() as u32).to_ne_bytes().to_vec();
+        framed.extend_from_slice(body);
+        framed
+    }
+
+    /// Starts the host as Chromium would, writes `stdin` and closes it,
+    /// then splits what came back into messages.
+    fn run_host(stdin: &[u8], db_path: &Path, ao3_url: &str) -> (Vec<Value>, i32) {
+        let config_home = TempDir::new().unwrap();
+        let mut child = Command::new(binary_path())
+            .args([
+                "native-host",
+                &format!("chrome-extension://{}/", CHROMIUM_ID),
+            ])
+            .env("FICFLOW_DB_PATH", db_path)
+            .env("AO3_BASE_URL", ao3_url)
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("host starts");
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        let mut replies = Vec::new();
+        let mut rest = output.stdout.as_slice();
+        while !rest.is_empty() {
+            let len = u32::from_ne_bytes(rest[..4].try_into().unwrap()) as usize;
+            replies.push(serde_json::from_slice(&rest[4..4 + len]).unwrap());
+            rest = &rest[4 + len..];
+        }
+        (replies, output.status.code().unwrap_or(-1))
+    }
+
+    fn messages(messages: &[Value]) -> Vec<u8> {
+        messages
+            .iter()
+            .flat_map(|m| frame(m.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_add_progress_and_status_use_the_library() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let (ao3, fic_id) = fixtures::given_mock_ao3_server();
+        let link = format!("https://archiveofourown.org/works/{}/chapters/1", fic_id);
+
+        let (replies, status) = run_host(
+            &messages(&[
+                json!({"id": 1, "type": "status", "work": link}),
+                json!({"id": 2, "type": "add", "work": link}),
+                json!({"id": 3, "type": "progress", "work": fic_id, "chapter": 1}),
+                json!({"id": 4, "type": "status", "work": fic_id.to_string()}),
+                json!({"id": 5, "type": "add", "work": fic_id}),
+            ]),
+            &db_path,
+            &ao3.base_url(),
+        );
+        assert_eq!(status, 0);
+        assert_eq!(replies.len(), 5);
+
+        assert_eq!(replies[0], json!({"id": 1, "ok": true, "tracked": false}));
+        assert_eq!(replies[1]["ok"], true, "{}", replies[1]);
+        assert_eq!(replies[1]["fic"]["id"], fic_id);
+        assert_eq!(replies[2]["fic"]["last_chapter_read"], 1);
+        assert_eq!(replies[3]["tracked"], true);
+        assert_eq!(replies[3]["fic"]["last_chapter_read"], 1);
+        assert_eq!(replies[4]["ok"], false);
+        assert_eq!(replies[4]["error"]["code"], "already_exists");
+
+        let fic = SqliteRepository::new(&conn).get_fanfiction_by_id(fic_id)?;
+        assert_eq!(fic.last_chapter_read, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_messages_get_errors_and_the_host_keeps_going() -> Result<(), Box<dyn Error>> {
+        let (_conn, db_path, _td) = fixtures::given_test_database();
+        let oversized = vec![b' '; 1024 * 1024 + 1];
+
+        let mut stdin = frame(b"{not json");
+        stdin.extend(messages(&[
+            json!({"id": "a", "type": "shelve", "work": 1}),
+            json!({"id": "b", "type": "progress", "work": 404, "chapter": 2}),
+            json!({"id": "c", "type": "status", "work": "somewhere else"}),
+        ]));
+        stdin.extend(frame(&oversized));
+        stdin.extend(messages(&[json!({"id": "d", "type": "ping"})]));
+
+        let (replies, status) = run_host(&stdin, &db_path, NO_AO3);
+        assert_eq!(status, 0);
+        let codes: Vec<&Value> = replies.iter().map(|r| &r["error"]["code"]).collect();
+        assert_eq!(
+            codes[..5],
+            [
+                "invalid_input",
+                "invalid_input",
+                "not_found",
+                "invalid_input",
+                "invalid_input"
+            ]
+        );
+        assert!(replies[0].get("id").is_none());
+        assert_eq!(replies[1]["id"], "a");
+        assert_eq!(replies[2]["id"], "b");
+        assert_eq!(replies[5]["id"], "d");
+        assert_eq!(replies[5]["ok"], true);
+        assert_eq!(replies[5]["version"], env!("CARGO_PKG_VERSION"));
+        Ok(())
+    }
+
+    /// Where browsers look for manifests differs by platform; this checks
+    /// the Linux folders.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_manifests_are_printed_and_installed_per_browser() -> Result<(), Box<dyn Error>> {
+        let home = TempDir::new()?;
+        let config_home = home.path().join("config");
+        let manifest = |args: &[&str]| {
+            Command::new(binary_path())
+                .args(["native-host", "manifest"])
+                .args(args)
+                .env("HOME", home.path())
+                .env("XDG_CONFIG_HOME", &config_home)
+                .env("FICFLOW_DB_PATH", home.path().join("lib.db"))
+                .output()
+                .unwrap()
+        };
+        let launcher = config_home.join("ficflow").join("native-host.sh");
+
+        let firefox = manifest(&[
+            "--browser",
+            "firefox",
+            "--extension-id",
+            "ficflow@example.org",
+        ]);
+        assert!(firefox.status.success());
+        let firefox: Value = serde_json::from_slice(&firefox.stdout)?;
+        assert_eq!(firefox["name"], "ficflow");
+        assert_eq!(firefox["type"], "stdio");
+        assert_eq!(firefox["path"], launcher.display().to_string());
+        assert_eq!(
+            firefox["allowed_extensions"],
+            json!(["ficflow@example.org"])
+        );
+
+        let rejected = manifest(&[
+            "--browser",
+            "chromium",
+            "--extension-id",
+            "ficflow@example.org",
+        ]);
+        assert_eq!(rejected.status.code(), Some(2));
+
+        let installed = manifest(&[
+            "--browser",
+            "chromium",
+            "--extension-id",
+            CHROMIUM_ID,
+            "--install",
+        ]);
+        assert!(installed.status.success());
+        let written = config_home
+            .join("chromium")
+            .join("NativeMessagingHosts")
+            .join("ficflow.json");
+        let chromium: Value = serde_json::from_str(&std::fs::read_to_string(written)?)?;
+        assert_eq!(
+            chromium["allowed_origins"],
+            json!([format!("chrome-extension://{}/", CHROMIUM_ID)])
+        );
+        let script = std::fs::read_to_string(&launcher)?;
+        assert!(script.starts_with("#!/bin/sh"), "{}", script);
+        assert!(script.contains("native-host \"$@\""), "{}", script);
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&launcher)?.permissions().mode();
+        assert_eq!(mode & 0o111, 0o111, "launcher must be executable");
+        Ok(())
+    }
+}
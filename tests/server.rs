@@ -0,0 +1,269 @@
+//! HTTP API tests. An `ApiServer` runs on a thread against a real
+//! temp-file SQLite library, bound to a free local port, and is driven
+//! with a blocking `reqwest` client; adds fetch from an `httpmock`'d AO3.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::infrastructure::open_configured_db;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::interfaces::server::{ApiServer, load_or_create_token};
+    use reqwest::StatusCode;
+    use reqwest::blocking::{Client, RequestBuilder};
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "test-token";
+    /// Nothing listens here; only the add test fetches.
+    const NO_AO3: &str = "http://127.0.0.1:1";
+
+    /// Where a running server is, and a client that sends the token.
+    struct Api {
+        base: String,
+        client: Client,
+    }
+
+    impl Api {
+        fn get(&self, path: &str) -> RequestBuilder {
+            self.authed(self.client.get(format!("{}{}", self.base, path)))
+        }
+
+        fn send(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+            self.authed(
+                self.client
+                    .request(method, format!("{}{}", self.base, path)),
+            )
+        }
+
+        fn authed(&self, request: RequestBuilder) -> RequestBuilder {
+            request.bearer_auth(TOKEN)
+        }
+    }
+
+    /// Serves `db_path` until the test process exits. The server opens its
+    /// own connection, as `ficflow serve` does on its thread.
+    fn given_running_server(db_path: PathBuf, ao3_url: &str) -> Api {
+        let (tx, rx) = mpsc::channel();
+        let ao3_urls = vec![ao3_url.to_string()];
+        std::thread::spawn(move || {
+            let conn = open_configured_db(&db_path).expect("server opens the library");
+            let repository = SqliteRepository::new(&conn);
+            let server = ApiServer::bind("127.0.0.1:0", TOKEN.into(), ao3_urls, 1, db_path)
+                .expect("server binds a free port");
+            tx.send(server.local_addr().expect("bound to an IP address"))
+                .unwrap();
+            server.run(&repository);
+        });
+        let addr = rx.recv().expect("server started");
+        Api {
+            base: format!("http://{}", addr),
+            client: Client::new(),
+        }
+    }
+
+    #[test]
+    fn test_requests_without_the_token_are_refused() -> Result<(), Box<dyn Error>> {
+        let (_conn, db_path, _td) = fixtures::given_test_database();
+        let api = given_running_server(db_path, NO_AO3);
+        let url = format!("{}/fics", api.base);
+
+        let anonymous = api.client.get(&url).send()?;
+        assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+        let body: Value = anonymous.json()?;
+        assert_eq!(body["error"]["code"], "unauthorized");
+
+        let wrong = api.client.get(&url).bearer_auth("test-tokem").send()?;
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+        assert_eq!(api.get("/fics").send()?.status(), StatusCode::OK);
+        assert_eq!(api.get("/nowhere").send()?.status(), StatusCode::NOT_FOUND);
+        let wrong_method = api.send(reqwest::Method::DELETE, "/fics").send()?;
+        assert_eq!(wrong_method.status(), StatusCode::METHOD_NOT_ALLOWED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fics_are_listed_read_and_updated() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let mut reading = fixtures::given_sample_fanfiction(1, "Bright Harbor");
+        reading.reading_status = ReadingStatus::InProgress;
+        fixtures::when_fanfiction_added_to_db(&conn, &reading)?;
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(2, "Cold Harbor"),
+        )?;
+        let api = given_running_server(db_path, NO_AO3);
+
+        let all: Vec<Value> = api.get("/fics").send()?.json()?;
+        assert_eq!(all.len(), 2);
+        let planned: Vec<Value> = api.get("/fics?status=plan-to-read").send()?.json()?;
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0]["title"], "Cold Harbor");
+        let encoded: Vec<Value> = api.get("/fics?status=plan%2Dto%2Dread").send()?.json()?;
+        assert_eq!(encoded, planned);
+        let one: Value = api.get("/fics/1").send()?.json()?;
+        assert_eq!(one["title"], "Bright Harbor");
+
+        let patched = api
+            .send(reqwest::Method::PATCH, "/fics/2")
+            .json(&json!({"status": "reading", "chapter": 1, "rating": 5, "note": "Reread soon"}))
+            .send()?;
+        assert_eq!(patched.status(), StatusCode::OK);
+        let patched: Value = patched.json()?;
+        assert_eq!(patched["reading_status"], "InProgress");
+        assert_eq!(patched["last_chapter_read"], 1);
+        assert_eq!(patched["personal_note"], "Reread soon");
+
+        let cleared: Value = api
+            .send(reqwest::Method::PATCH, "/fics/2")
+            .json(&json!({"rating": null, "note": null}))
+            .send()?
+            .json()?;
+        assert!(cleared["user_rating"].is_null());
+        assert!(cleared["personal_note"].is_null());
+
+        // The status asked for wins over the one reaching the last
+        // chapter would give.
+        let paused: Value = api
+            .send(reqwest::Method::PATCH, "/fics/2")
+            .json(&json!({"status": "paused", "chapter": 2}))
+            .send()?
+            .json()?;
+        assert_eq!(paused["reading_status"], "Paused");
+        assert_eq!(paused["last_chapter_read"], 2);
+        let repo = SqliteRepository::new(&conn);
+        assert_eq!(
+            repo.get_fanfiction_by_id(2)?.reading_status,
+            ReadingStatus::Paused
+        );
+
+        // A bad value is caught before anything is written.
+        let rejected = api
+            .send(reqwest::Method::PATCH, "/fics/2")
+            .json(&json!({"chapter": 1, "status": "devoured"}))
+            .send()?;
+        assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(repo.get_fanfiction_by_id(2)?.last_chapter_read, Some(2));
+
+        let missing = api.get("/fics/999").send()?;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let body: Value = missing.json()?;
+        assert_eq!(body["error"]["code"], "not_found");
+        Ok(())
+    }
+
+    #[test]
+    fn test_shelf_members_are_listed_added_and_removed() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(1, "Bright Harbor"),
+        )?;
+        let shelf = SqliteRepository::new(&conn).create_shelf("Harbors", None)?;
+        let api = given_running_server(db_path, NO_AO3);
+
+        let shelves: Vec<Value> = api.get("/shelves").send()?.json()?;
+        assert_eq!(shelves[0]["name"], "Harbors");
+
+        let members = format!("/shelves/{}/fics", shelf.id);
+        let added = api
+            .send(reqwest::Method::PUT, &format!("{}/1", members))
+            .send()?;
+        assert_eq!(added.status(), StatusCode::NO_CONTENT);
+        let listed: Vec<Value> = api.get(&members).send()?.json()?;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0]["id"], 1);
+
+        let removed = api
+            .send(reqwest::Method::DELETE, &format!("{}/1", members))
+            .send()?;
+        assert_eq!(removed.status(), StatusCode::NO_CONTENT);
+        let listed: Vec<Value> = api.get(&members).send()?.json()?;
+        assert!(listed.is_empty());
+
+        let no_shelf = api
+            .send(reqwest::Method::PUT, "/shelves/99/fics/1")
+            .send()?;
+        assert_eq!(no_shelf.status(), StatusCode::NOT_FOUND);
+        Ok(())
+    }
+
+    #[test]
+    fn test_adds_are_queued_as_tasks_the_client_polls() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let (ao3, fic_id) = fixtures::given_mock_ao3_server();
+        let shelf = SqliteRepository::new(&conn).create_shelf("New", None)?;
+        let api = given_running_server(db_path, &ao3.base_url());
+
+        let queued = api
+            .send(reqwest::Method::POST, "/fics")
+            .json(&json!({
+                "fic": format!("https://archiveofourown.org/works/{}", fic_id),
+                "shelf": shelf.id,
+            }))
+            .send()?;
+        assert_eq!(queued.status(), StatusCode::ACCEPTED);
+        let task: Value = queued.json()?;
+        assert_eq!(task["kind"], "add");
+        let task_path = format!("/tasks/{}", task["id"]);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut task: Value = api.get(&task_path).send()?.json()?;
+        while task["status"] == "running" && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            task = api.get(&task_path).send()?.json()?;
+        }
+        assert_eq!(task["status"], "done", "{}", task);
+        assert_eq!(api.get("/tasks").send()?.json::<Vec<Value>>()?.len(), 1);
+
+        let fic: Value = api.get(&format!("/fics/{}", fic_id)).send()?.json()?;
+        assert_eq!(fic["title"], task["title"]);
+        let shelved: Vec<Value> = api
+            .get(&format!("/shelves/{}/fics", shelf.id))
+            .send()?
+            .json()?;
+        assert_eq!(shelved.len(), 1);
+
+        let bad = api
+            .send(reqwest::Method::POST, "/fics")
+            .json(&json!({"fic": "not a work"}))
+            .send()?;
+        assert_eq!(bad.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(api.get("/tasks/99").send()?.status(), StatusCode::NOT_FOUND);
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_is_generated_once_and_kept_private() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("ficflow").join("api-token");
+
+        let token = load_or_create_token(&path)?;
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(load_or_create_token(&path)?, token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let other = TempDir::new()?;
+        let fresh = load_or_create_token(&other.path().join("api-token"))?;
+        assert_ne!(fresh, token);
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+//! Terminal UI tests. `TuiApp` is driven with synthetic key events and
+//! drawn into ratatui's `TestBackend`, against a real temp-file SQLite
+//! library and, for adds, an `httpmock`'d AO3 server.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::fixtures;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ficflow::domain::fanfiction::{FanfictionOps, ReadingStatus, UserRating};
+    use ficflow::domain::shelf::ShelfOps;
+    use ficflow::infrastructure::persistence::repository::SqliteRepository;
+    use ficflow::interfaces::tui::{Pane, TuiApp};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    /// Nothing listens here; only the add test fetches.
+    const NO_AO3: &str = "http://127.0.0.1:1";
+
+    fn press(app: &mut TuiApp, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut TuiApp, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    fn titles(app: &TuiApp) -> Vec<String> {
+        app.fics().iter().map(|f| f.title.clone()).collect()
+    }
+
+    fn screen(app: &mut TuiApp) -> Result<String, Box<dyn Error>> {
+        let mut terminal = Terminal::new(TestBackend::new(140, 30))?;
+        terminal.draw(|frame| app.draw(frame))?;
+        Ok(terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect())
+    }
+
+    #[test]
+    fn test_table_sorts_searches_and_follows_the_sidebar() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        for (id, title, words, status) in [
+            (1, "Bright Harbor", 5_000, ReadingStatus::InProgress),
+            (2, "Autumn Field", 20_000, ReadingStatus::Read),
+            (3, "Cold Harbor", 900, ReadingStatus::PlanToRead),
+        ] {
+            let mut fic = fixtures::given_sample_fanfiction(id, title);
+            fic.words = words;
+            fic.reading_status = status;
+            fixtures::when_fanfiction_added_to_db(&conn, &fic)?;
+        }
+        let repo = SqliteRepository::new(&conn);
+        let shelf = repo.create_shelf("Harbors", None)?;
+        repo.add_fic_to_shelf(3, shelf.id)?;
+        let mut app = TuiApp::new(&repo, vec![NO_AO3.to_string()], 1, db_path)?;
+
+        assert_eq!(
+            titles(&app),
+            ["Autumn Field", "Bright Harbor", "Cold Harbor"]
+        );
+        let drawn = screen(&mut app)?;
+        assert!(drawn.contains("All fics (3)"), "{}", drawn);
+        assert!(drawn.contains("Title ▲"), "{}", drawn);
+
+        // Title → Author → Status → Last Ch. → Words, then flip it.
+        for _ in 0..4 {
+            press(&mut app, KeyCode::Char('s'));
+        }
+        assert_eq!(
+            titles(&app),
+            ["Cold Harbor", "Bright Harbor", "Autumn Field"]
+        );
+        press(&mut app, KeyCode::Char('S'));
+        assert_eq!(
+            titles(&app),
+            ["Autumn Field", "Bright Harbor", "Cold Harbor"]
+        );
+
+        press(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "harbor");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(titles(&app), ["Bright Harbor", "Cold Harbor"]);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.fics().len(), 3);
+
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.focus(), Pane::Sidebar);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(titles(&app), ["Bright Harbor"]);
+        // All, five statuses, then the shelf.
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Down);
+        }
+        assert_eq!(titles(&app), ["Cold Harbor"]);
+        assert!(screen(&mut app)?.contains("Harbors (1)"));
+
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.should_quit());
+        Ok(())
+    }
+
+    #[test]
+    fn test_details_pane_edits_fields_in_place() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        fixtures::when_fanfiction_added_to_db(
+            &conn,
+            &fixtures::given_sample_fanfiction(7, "Editable"),
+        )?;
+        let repo = SqliteRepository::new(&conn);
+        let mut app = TuiApp::new(&repo, vec![NO_AO3.to_string()], 1, db_path)?;
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.focus(), Pane::Details);
+
+        // Status: the edit line starts with the current value.
+        press(&mut app, KeyCode::Enter);
+        for _ in 0.."plantoread".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_text(&mut app, "reading");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(
+            repo.get_fanfiction_by_id(7)?.reading_status,
+            ReadingStatus::InProgress
+        );
+
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "1");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(repo.get_fanfiction_by_id(7)?.last_chapter_read, Some(1));
+
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "9");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.message().unwrap().starts_with("Invalid rating"));
+        assert_eq!(repo.get_fanfiction_by_id(7)?.user_rating, None);
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "4");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(
+            repo.get_fanfiction_by_id(7)?.user_rating,
+            Some(UserRating::Four)
+        );
+
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "Reread the ending");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(
+            repo.get_fanfiction_by_id(7)?.personal_note.as_deref(),
+            Some("Reread the ending")
+        );
+        let drawn = screen(&mut app)?;
+        assert!(drawn.contains("★★★★"), "{}", drawn);
+        assert!(drawn.contains("Reread the ending"), "{}", drawn);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_runs_in_the_background_and_shows_on_the_task_line() -> Result<(), Box<dyn Error>> {
+        let (conn, db_path, _td) = fixtures::given_test_database();
+        let (server, fic_id) = fixtures::given_mock_ao3_server();
+        let repo = SqliteRepository::new(&conn);
+        let mut app = TuiApp::new(&repo, vec![server.base_url()], 1, db_path)?;
+
+        press(&mut app, KeyCode::Char('a'));
+        type_text(
+            &mut app,
+            &format!("https://archiveofourown.org/works/{}", fic_id),
+        );
+        press(&mut app, KeyCode::Enter);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.fics().is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            app.poll_tasks();
+        }
+        assert_eq!(app.fics().len(), 1);
+        assert_eq!(app.fics()[0].id, fic_id);
+        let drawn = screen(&mut app)?;
+        assert!(drawn.contains("Tasks: 0 running"), "{}", drawn);
+        assert!(drawn.contains("✓ Add"), "{}", drawn);
+        assert!(app.message().unwrap().starts_with("Added"));
+        Ok(())
+    }
+}